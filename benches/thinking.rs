@@ -0,0 +1,170 @@
+//! Benchmarks for the thinking engine, exports, analytics and serialization.
+//!
+//! Run with `cargo bench --features bench`. Sessions come from
+//! [`SessionGenerator`], so results are comparable between runs.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use std::collections::HashMap;
+use tokio::runtime::Runtime;
+
+use ultrafast_mcp_sequential_thinking::analytics::AnalyticsEngine;
+use ultrafast_mcp_sequential_thinking::export::{
+    ExportConfig, ExportEngine, ExportFormat, ExportOptions,
+};
+use ultrafast_mcp_sequential_thinking::testing::{GeneratedSession, SessionGenerator};
+use ultrafast_mcp_sequential_thinking::thinking::checkpoint::EngineCheckpoint;
+use ultrafast_mcp_sequential_thinking::thinking::{SharedThought, ThinkingEngine};
+
+/// Session sizes every group is measured at
+const SIZES: [u32; 3] = [10, 100, 1000];
+
+fn runtime() -> Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build tokio runtime")
+}
+
+/// Deterministic session with exactly `size` thoughts
+fn session(size: u32) -> GeneratedSession {
+    SessionGenerator::new(42)
+        .with_thoughts(size..=size)
+        .with_words(40..=40)
+        .next_session()
+}
+
+fn process_thought(c: &mut Criterion) {
+    let rt = runtime();
+    let mut group = c.benchmark_group("process_thought");
+    for size in SIZES {
+        let thoughts = session(size).thoughts;
+        group.throughput(Throughput::Elements(u64::from(size)));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &thoughts,
+            |b, thoughts| {
+                b.iter_batched(
+                    || (ThinkingEngine::with_logging(true), thoughts.clone()),
+                    |(mut engine, thoughts)| {
+                        rt.block_on(async {
+                            for thought in thoughts {
+                                engine.process_thought(thought).await.unwrap();
+                            }
+                        });
+                        engine
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn export(c: &mut Criterion) {
+    let rt = runtime();
+    let dir = tempfile::tempdir().expect("failed to create export directory");
+    let mut exporter = ExportEngine::with_config(ExportConfig {
+        export_directory: dir.path().to_string_lossy().into_owned(),
+        ..ExportConfig::default()
+    });
+
+    let mut group = c.benchmark_group("export");
+    for size in [100, 1000] {
+        let engine = rt.block_on(session(size).engine()).unwrap();
+        let branches: HashMap<String, Vec<SharedThought>> = engine
+            .get_branches()
+            .iter()
+            .map(|(id, branch)| (id.clone(), branch.thoughts.clone()))
+            .collect();
+        group.throughput(Throughput::Elements(u64::from(size)));
+
+        for format in [
+            ExportFormat::Json,
+            ExportFormat::Markdown,
+            ExportFormat::Html,
+            ExportFormat::Csv,
+        ] {
+            let options = ExportOptions {
+                format: format.clone(),
+                force: true,
+                ..ExportOptions::default()
+            };
+            group.bench_function(BenchmarkId::new(format.extension(), size), |b| {
+                b.iter(|| {
+                    rt.block_on(exporter.export_session(
+                        "bench",
+                        None,
+                        engine.get_thoughts(),
+                        Some(engine.get_stats()),
+                        Some(engine.get_progress()),
+                        Some(&branches),
+                        None,
+                        None,
+                        options.clone(),
+                    ))
+                    .unwrap();
+                    exporter.clear_export_history();
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn analytics(c: &mut Criterion) {
+    let rt = runtime();
+    let mut group = c.benchmark_group("analytics");
+    for size in SIZES {
+        let engine = rt.block_on(session(size).engine()).unwrap();
+        group.throughput(Throughput::Elements(u64::from(size)));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &engine, |b, engine| {
+            b.iter_batched(
+                AnalyticsEngine::new,
+                |mut analytics| {
+                    analytics.analyze_session(
+                        "bench",
+                        "Synthetic session",
+                        engine.get_thoughts(),
+                        engine.get_stats(),
+                        engine.get_progress(),
+                    )
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn serialization(c: &mut Criterion) {
+    let rt = runtime();
+    let mut group = c.benchmark_group("serialization");
+    for size in SIZES {
+        let engine = rt.block_on(session(size).engine()).unwrap();
+        let thoughts = engine.get_thoughts();
+        let checkpoint = engine.checkpoint();
+        let encoded = serde_json::to_string(&checkpoint).unwrap();
+        group.throughput(Throughput::Elements(u64::from(size)));
+
+        group.bench_with_input(
+            BenchmarkId::new("thoughts", size),
+            thoughts,
+            |b, thoughts| b.iter(|| serde_json::to_string(thoughts).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("checkpoint", size),
+            &checkpoint,
+            |b, checkpoint| b.iter(|| serde_json::to_string(checkpoint).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("checkpoint_restore", size),
+            &encoded,
+            |b, encoded| b.iter(|| serde_json::from_str::<EngineCheckpoint>(encoded).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, process_thought, export, analytics, serialization);
+criterion_main!(benches);
@@ -0,0 +1,180 @@
+//! # Node.js Bindings
+//!
+//! napi-rs bindings exposing the sequential thinking engine to Node.js.
+//!
+//! `SequentialThinkingServer` mirrors the class of the official TypeScript
+//! server: `processThought` takes the same `thoughtData` fields and returns
+//! the same `{ content, isError }` tool result, so Node MCP hosts can swap in
+//! the Rust core without running a separate process.
+
+use napi::{Error, Result};
+use napi_derive::napi;
+
+use engine::export::ExportFormat;
+use engine::thinking::response::ThoughtResponse;
+use engine::{BlockingLocalThinking, LocalThinking};
+use ultrafast_mcp_sequential_thinking as engine;
+
+/// Input of the sequential_thinking tool, with the official field names
+#[napi(object)]
+#[derive(Clone)]
+pub struct ThoughtData {
+    pub thought: String,
+    pub thought_number: u32,
+    pub total_thoughts: u32,
+    pub next_thought_needed: bool,
+    pub is_revision: Option<bool>,
+    pub revises_thought: Option<u32>,
+    pub branch_from_thought: Option<u32>,
+    pub branch_id: Option<String>,
+    pub needs_more_thoughts: Option<bool>,
+}
+
+impl From<ThoughtData> for engine::ThoughtData {
+    fn from(data: ThoughtData) -> Self {
+        let mut thought = Self::new(data.thought, data.thought_number, data.total_thoughts);
+        thought.next_thought_needed = data.next_thought_needed;
+        thought.is_revision = data.is_revision;
+        thought.revises_thought = data.revises_thought;
+        thought.branch_from_thought = data.branch_from_thought;
+        thought.branch_id = data.branch_id;
+        thought.needs_more_thoughts = data.needs_more_thoughts;
+        thought
+    }
+}
+
+impl From<&engine::ThoughtData> for ThoughtData {
+    fn from(data: &engine::ThoughtData) -> Self {
+        Self {
+            thought: data.thought.clone(),
+            thought_number: data.thought_number,
+            total_thoughts: data.total_thoughts,
+            next_thought_needed: data.next_thought_needed,
+            is_revision: data.is_revision,
+            revises_thought: data.revises_thought,
+            branch_from_thought: data.branch_from_thought,
+            branch_id: data.branch_id.clone(),
+            needs_more_thoughts: data.needs_more_thoughts,
+        }
+    }
+}
+
+/// Text content item of a tool result
+#[napi(object)]
+pub struct TextContent {
+    #[napi(js_name = "type")]
+    pub kind: String,
+    pub text: String,
+}
+
+/// Tool result in the shape returned by the official server
+#[napi(object)]
+pub struct ToolResult {
+    pub content: Vec<TextContent>,
+    pub is_error: Option<bool>,
+}
+
+impl ToolResult {
+    fn text(text: String, is_error: bool) -> Self {
+        Self {
+            content: vec![TextContent {
+                kind: "text".to_string(),
+                text,
+            }],
+            is_error: is_error.then_some(true),
+        }
+    }
+}
+
+fn to_napi(error: impl std::fmt::Display) -> Error {
+    Error::from_reason(error.to_string())
+}
+
+/// Sequential thinking server holding one thought history
+#[napi]
+pub struct SequentialThinkingServer {
+    thinking: BlockingLocalThinking,
+    session_id: String,
+}
+
+#[napi]
+impl SequentialThinkingServer {
+    #[napi(constructor)]
+    pub fn new() -> Result<Self> {
+        let thinking = BlockingLocalThinking::from_local(LocalThinking::new()).map_err(to_napi)?;
+        let session_id = thinking.start_session("Node.js session").map_err(to_napi)?;
+        Ok(Self {
+            thinking,
+            session_id,
+        })
+    }
+
+    /// Record a thought, returning the sequential_thinking tool result
+    ///
+    /// Thoughts the engine rejects produce an error result rather than
+    /// throwing, as in the official server.
+    #[napi]
+    pub fn process_thought(&self, input: ThoughtData) -> ToolResult {
+        let start = std::time::Instant::now();
+        let processed = match self.thinking.think(&self.session_id, input.into()) {
+            Ok(processed) => processed,
+            Err(error) => {
+                let body = serde_json::json!({
+                    "error": error.to_string(),
+                    "status": "failed"
+                });
+                return ToolResult::text(serde_json::to_string_pretty(&body).unwrap(), true);
+            }
+        };
+        let session = match self.thinking.session(&self.session_id) {
+            Ok(session) => session,
+            Err(error) => return ToolResult::text(error.to_string(), true),
+        };
+        let response = ThoughtResponse::from_engine(
+            &processed,
+            &session.engine,
+            start.elapsed().as_millis() as u64,
+        );
+        ToolResult::text(serde_json::to_string_pretty(&response).unwrap(), false)
+    }
+
+    /// Thoughts recorded so far
+    #[napi(getter)]
+    pub fn thought_history(&self) -> Result<Vec<ThoughtData>> {
+        let thoughts = self.thinking.thoughts(&self.session_id).map_err(to_napi)?;
+        Ok(thoughts.iter().map(|t| ThoughtData::from(&**t)).collect())
+    }
+
+    /// Identifiers of the branches explored so far
+    #[napi(getter)]
+    pub fn branches(&self) -> Result<Vec<String>> {
+        let session = self.thinking.session(&self.session_id).map_err(to_napi)?;
+        let mut branches: Vec<String> = session.engine.get_branches().keys().cloned().collect();
+        branches.sort();
+        Ok(branches)
+    }
+
+    /// Render the thought history, as `json` by default
+    #[napi]
+    pub fn export_session(&self, format: Option<String>) -> Result<String> {
+        let format: ExportFormat = format
+            .as_deref()
+            .unwrap_or("json")
+            .parse()
+            .map_err(to_napi)?;
+        self.thinking
+            .export(&self.session_id, format)
+            .map_err(to_napi)
+    }
+
+    /// Discard the thought history and start over
+    #[napi]
+    pub fn reset(&mut self) -> Result<()> {
+        self.thinking.end_session(&self.session_id);
+        self.session_id = self
+            .thinking
+            .start_session("Node.js session")
+            .map_err(to_napi)?;
+        Ok(())
+    }
+}
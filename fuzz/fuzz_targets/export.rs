@@ -0,0 +1,26 @@
+//! Export documents parsed from JSON and rendered in the format they name
+
+#![no_main]
+
+use std::sync::OnceLock;
+
+use libfuzzer_sys::fuzz_target;
+use ultrafast_mcp_sequential_thinking::export::{ExportData, ExportEngine};
+
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("runtime starts")
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(export) = serde_json::from_slice::<ExportData>(data) else {
+        return;
+    };
+    let engine = ExportEngine::new();
+    let mut rendered = Vec::new();
+    let _ = runtime().block_on(engine.stream_export(&export, &mut rendered));
+});
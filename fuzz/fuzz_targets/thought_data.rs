@@ -0,0 +1,13 @@
+//! Thoughts deserialized from stored sessions and validated by the engine
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ultrafast_mcp_sequential_thinking::ThoughtData;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(thought) = serde_json::from_slice::<ThoughtData>(data) {
+        let _ = thought.validate();
+        let _ = thought.to_tool_arguments();
+    }
+});
@@ -0,0 +1,20 @@
+//! Arguments of `sequential_thinking` tool calls, as sent by untrusted hosts
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ultrafast_mcp_sequential_thinking::ThoughtData;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(args) = serde_json::from_slice::<serde_json::Value>(data) else {
+        return;
+    };
+    if let Ok(thought) = ThoughtData::from_tool_arguments(&args) {
+        let _ = thought.validate();
+        // A thought read from arguments can always be sent again
+        let resent = ThoughtData::from_tool_arguments(&thought.to_tool_arguments())
+            .expect("arguments written by to_tool_arguments are readable");
+        assert_eq!(resent.thought_number, thought.thought_number);
+        assert_eq!(resent.depends_on, thought.depends_on);
+    }
+});
@@ -0,0 +1,276 @@
+//! # Actions Module
+//!
+//! Action items extracted from a session's thoughts.
+//!
+//! Thoughts often end in follow-up work: `TODO:` notes, unchecked Markdown
+//! checkboxes or sentences such as "we need to add a retry". An
+//! [`ActionExtractor`] finds these with configurable regular expressions plus
+//! an optional phrase heuristic, merges duplicates and remembers which
+//! thoughts mentioned each item. Markdown exports list the items as a
+//! checklist.
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::thinking::SharedThought;
+
+/// Patterns recognized when none are configured
+///
+/// A `text` capture group, when present, holds the action's text.
+pub const DEFAULT_PATTERNS: &[&str] = &[
+    r"(?i)\b(?:TODO|FIXME|ACTION(?: ITEM)?|FOLLOW[- ]UP)\b\s*[:\-]?\s*(?P<text>.+)",
+    r"^\s*[-*+]\s+\[ \]\s+(?P<text>.+)",
+];
+
+/// Phrases that start an action sentence for the heuristic
+const ACTION_PHRASES: &[&str] = &[
+    "we need to ",
+    "we should ",
+    "we must ",
+    "need to ",
+    "next step is to ",
+    "next step: ",
+    "remember to ",
+    "make sure to ",
+    "don't forget to ",
+];
+
+/// Action extraction settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ActionConfig {
+    /// Regular expressions matching action lines
+    #[serde(default = "default_patterns")]
+    pub patterns: Vec<String>,
+    /// Whether sentences starting with phrases such as "we need to" count as actions
+    #[serde(default = "default_heuristic")]
+    pub heuristic: bool,
+}
+
+fn default_patterns() -> Vec<String> {
+    DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect()
+}
+
+fn default_heuristic() -> bool {
+    true
+}
+
+impl Default for ActionConfig {
+    fn default() -> Self {
+        Self {
+            patterns: default_patterns(),
+            heuristic: default_heuristic(),
+        }
+    }
+}
+
+/// How an action item was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionSource {
+    /// Matched a configured pattern
+    Marker,
+    /// Matched the phrase heuristic
+    Heuristic,
+}
+
+/// Follow-up work mentioned in a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionItem {
+    /// What needs to be done
+    pub text: String,
+    /// Thoughts mentioning the item, in ascending order
+    pub thought_numbers: Vec<u32>,
+    /// How the item was first found
+    pub source: ActionSource,
+}
+
+/// Finds action items in thoughts
+#[derive(Debug, Clone)]
+pub struct ActionExtractor {
+    patterns: Vec<Regex>,
+    heuristic: bool,
+}
+
+impl Default for ActionExtractor {
+    fn default() -> Self {
+        Self::new(&ActionConfig::default()).expect("default action patterns are valid")
+    }
+}
+
+impl ActionExtractor {
+    /// Create an extractor, compiling the configured patterns
+    pub fn new(config: &ActionConfig) -> Result<Self, String> {
+        let patterns = config
+            .patterns
+            .iter()
+            .enumerate()
+            .map(|(i, pattern)| {
+                Regex::new(pattern).map_err(|e| format!("patterns[{i}]: invalid regex: {e}"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            patterns,
+            heuristic: config.heuristic,
+        })
+    }
+
+    /// Action text on a line, if the line holds one
+    fn match_line(&self, line: &str) -> Option<(String, ActionSource)> {
+        for pattern in &self.patterns {
+            if let Some(captures) = pattern.captures(line) {
+                let text = captures
+                    .name("text")
+                    .or_else(|| captures.get(0))
+                    .map_or("", |m| m.as_str());
+                return Some((clean(text), ActionSource::Marker));
+            }
+        }
+        if !self.heuristic {
+            return None;
+        }
+        let sentence = line.trim_start_matches(|c: char| !c.is_alphanumeric());
+        let lower = sentence.to_lowercase();
+        ACTION_PHRASES
+            .iter()
+            .any(|phrase| lower.starts_with(phrase))
+            .then(|| (clean(sentence), ActionSource::Heuristic))
+    }
+
+    /// Collect the action items of a session's thoughts, merging duplicates
+    pub fn extract(&self, thoughts: &[SharedThought]) -> Vec<ActionItem> {
+        let mut items: Vec<ActionItem> = Vec::new();
+        for thought in thoughts {
+            for line in thought.thought.lines() {
+                let Some((text, source)) = self.match_line(line) else {
+                    continue;
+                };
+                if text.is_empty() {
+                    continue;
+                }
+                let key = dedup_key(&text);
+                match items.iter_mut().find(|item| dedup_key(&item.text) == key) {
+                    Some(item) => {
+                        if !item.thought_numbers.contains(&thought.thought_number) {
+                            item.thought_numbers.push(thought.thought_number);
+                            item.thought_numbers.sort_unstable();
+                        }
+                    }
+                    None => items.push(ActionItem {
+                        text,
+                        thought_numbers: vec![thought.thought_number],
+                        source,
+                    }),
+                }
+            }
+        }
+        items
+    }
+}
+
+/// Trim an action's text and its trailing punctuation
+fn clean(text: &str) -> String {
+    text.trim()
+        .trim_end_matches(['.', ';', ',', '!'])
+        .trim_end()
+        .to_string()
+}
+
+/// Key under which equivalent action texts are merged
+fn dedup_key(text: &str) -> String {
+    text.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Render action items as a Markdown checklist section
+pub fn markdown_checklist(items: &[ActionItem]) -> String {
+    if items.is_empty() {
+        return String::new();
+    }
+    let mut markdown = String::from("## Action Items\n\n");
+    for item in items {
+        let numbers: Vec<String> = item.thought_numbers.iter().map(u32::to_string).collect();
+        let label = if numbers.len() == 1 {
+            "thought"
+        } else {
+            "thoughts"
+        };
+        markdown.push_str(&format!(
+            "- [ ] {} *({label} {})*\n",
+            item.text,
+            numbers.join(", ")
+        ));
+    }
+    markdown.push('\n');
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::ThoughtData;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_extract_actions() {
+        let thoughts: Vec<SharedThought> = vec![
+            ThoughtData::new(
+                "The cache is stale.\nTODO: add an expiry to cache entries.\n- [ ] Write a migration\n- [x] Profile the query".to_string(),
+                1,
+                3,
+            ),
+            ThoughtData::new(
+                "We need to benchmark the new index.\nFIXME: add an expiry to  cache entries".to_string(),
+                2,
+                3,
+            ),
+            ThoughtData::new("todo: Add an expiry to cache entries!".to_string(), 3, 3),
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+
+        let items = ActionExtractor::default().extract(&thoughts);
+        let summary: Vec<(&str, &[u32], ActionSource)> = items
+            .iter()
+            .map(|i| (i.text.as_str(), &i.thought_numbers[..], i.source))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "add an expiry to cache entries",
+                    &[1, 2, 3][..],
+                    ActionSource::Marker
+                ),
+                ("Write a migration", &[1][..], ActionSource::Marker),
+                (
+                    "We need to benchmark the new index",
+                    &[2][..],
+                    ActionSource::Heuristic
+                ),
+            ]
+        );
+
+        let strict = ActionExtractor::new(&ActionConfig {
+            patterns: vec![r"^ACTION (?P<text>.+)".to_string()],
+            heuristic: true,
+        })
+        .unwrap()
+        .extract(&thoughts[1..2]);
+        assert_eq!(strict.len(), 1);
+        assert_eq!(strict[0].text, "We need to benchmark the new index");
+
+        assert!(markdown_checklist(&items)
+            .contains("- [ ] add an expiry to cache entries *(thoughts 1, 2, 3)*"));
+        assert!(ActionExtractor::new(&ActionConfig {
+            patterns: vec!["(".to_string()],
+            heuristic: false,
+        })
+        .unwrap_err()
+        .starts_with("patterns[0]: invalid regex"));
+    }
+}
@@ -0,0 +1,192 @@
+//! # Admin Module
+//!
+//! Inspection of a running server, for operators without an MCP client.
+//!
+//! Over the HTTP transport the gateway answers admin requests under
+//! [`ADMIN_PATH`], authenticated like the usage report: the API key is read
+//! from the `x-api-key` header or a bearer token and must have the admin role.
+//!
+//! - `GET /admin/sessions` lists the live sessions
+//! - `DELETE /admin/sessions/{id}` ends a session
+//! - `GET /admin/sessions/{id}/export?format=json|markdown` exports a session
+//! - `GET /admin/stats` reports the server statistics
+//! - `GET /admin/analytics?days=N` reports daily aggregates of completed sessions
+//!
+//! [`AdminClient`] makes these requests; the `admin` subcommand of the server
+//! binary is built on it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::thinking::server::ServerStats;
+
+/// Path under which the gateway answers admin requests
+pub const ADMIN_PATH: &str = "/admin";
+
+/// Session held in memory by a running server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveSession {
+    /// Session ID
+    pub session_id: String,
+    /// Thoughts processed, branches included
+    pub thought_count: usize,
+    /// Branches explored
+    pub branch_count: usize,
+    /// Whether the final thought has been processed
+    pub complete: bool,
+}
+
+/// Statistics of a running server
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdminStats {
+    /// Request, thought and error counters
+    #[serde(flatten)]
+    pub stats: ServerStats,
+    /// Sessions currently held in memory
+    pub active_sessions: usize,
+}
+
+#[cfg(feature = "http-transport")]
+pub use client::AdminClient;
+
+#[cfg(feature = "http-transport")]
+mod client {
+    use reqwest::{Method, StatusCode, Url};
+
+    use super::{AdminStats, LiveSession, ADMIN_PATH};
+    use crate::analytics::dashboard::AnalyticsDashboard;
+    use crate::thinking::error::{SequentialThinkingError, SequentialThinkingResult};
+
+    /// Client for the admin endpoints of a running server
+    #[derive(Debug, Clone)]
+    pub struct AdminClient {
+        client: reqwest::Client,
+        base_url: String,
+        api_key: Option<String>,
+    }
+
+    impl AdminClient {
+        /// Create a client for the server at `base_url`, e.g. `http://127.0.0.1:8080`
+        pub fn new(base_url: impl Into<String>) -> Self {
+            Self {
+                client: reqwest::Client::new(),
+                base_url: base_url.into(),
+                api_key: None,
+            }
+        }
+
+        /// Authenticate with an API key
+        pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+            self.api_key = Some(api_key.into());
+            self
+        }
+
+        /// List the live sessions
+        pub async fn list_sessions(&self) -> SequentialThinkingResult<Vec<LiveSession>> {
+            self.send(Method::GET, &["sessions"], None)
+                .await?
+                .json()
+                .await
+                .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+        }
+
+        /// End a live session
+        pub async fn kill_session(&self, session_id: &str) -> SequentialThinkingResult<()> {
+            self.send(Method::DELETE, &["sessions", session_id], None)
+                .await
+                .map(|_| ())
+        }
+
+        /// Export a live session as `json` or `markdown`
+        pub async fn export_session(
+            &self,
+            session_id: &str,
+            format: &str,
+        ) -> SequentialThinkingResult<String> {
+            self.send(
+                Method::GET,
+                &["sessions", session_id, "export"],
+                Some(("format", format)),
+            )
+            .await?
+            .text()
+            .await
+            .map_err(|e| SequentialThinkingError::transport_error(e.to_string()))
+        }
+
+        /// Get the server statistics
+        pub async fn stats(&self) -> SequentialThinkingResult<AdminStats> {
+            self.send(Method::GET, &["stats"], None)
+                .await?
+                .json()
+                .await
+                .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+        }
+
+        /// Get daily aggregates of the sessions completed in the last `days` days
+        pub async fn analytics_dashboard(
+            &self,
+            days: u32,
+        ) -> SequentialThinkingResult<AnalyticsDashboard> {
+            self.send(
+                Method::GET,
+                &["analytics"],
+                Some(("days", &days.to_string())),
+            )
+            .await?
+            .json()
+            .await
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+        }
+
+        /// Send a request for the admin path made of `segments`
+        async fn send(
+            &self,
+            method: Method,
+            segments: &[&str],
+            query: Option<(&str, &str)>,
+        ) -> SequentialThinkingResult<reqwest::Response> {
+            let mut url = Url::parse(&self.base_url).map_err(|e| {
+                SequentialThinkingError::config_error(format!(
+                    "Invalid server URL '{}': {e}",
+                    self.base_url
+                ))
+            })?;
+            url.path_segments_mut()
+                .map_err(|_| {
+                    SequentialThinkingError::config_error(format!(
+                        "Invalid server URL '{}'",
+                        self.base_url
+                    ))
+                })?
+                .pop_if_empty()
+                .push(ADMIN_PATH.trim_start_matches('/'))
+                .extend(segments);
+            if let Some((name, value)) = query {
+                url.query_pairs_mut().append_pair(name, value);
+            }
+
+            let mut request = self.client.request(method, url);
+            if let Some(api_key) = &self.api_key {
+                request = request.header("x-api-key", api_key);
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| SequentialThinkingError::transport_error(e.to_string()))?;
+
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response);
+            }
+            let message = response.text().await.unwrap_or_default();
+            Err(match status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    SequentialThinkingError::permission_denied(message)
+                }
+                StatusCode::NOT_FOUND => SequentialThinkingError::not_found(message),
+                StatusCode::BAD_REQUEST => SequentialThinkingError::validation_error(message),
+                _ => SequentialThinkingError::transport_error(format!("{status}: {message}")),
+            })
+        }
+    }
+}
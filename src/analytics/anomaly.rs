@@ -0,0 +1,508 @@
+//! # Anomaly Detection
+//!
+//! Flagging sessions that think unusually compared to the others.
+//!
+//! A session analyzed by an [`AnalyticsEngine`] is compared with every other
+//! session the engine has analyzed: far more revisions than the average
+//! session or a much lower quality score are reported, as are gaps between
+//! thoughts much longer than the session's usual pace. Anomalies are
+//! returned as an [`AnomalyReport`] and added to the session's insights, and
+//! can be posted to a webhook with the `integrations` feature.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::analytics::{AnalyticsEngine, Insight, SessionAnalytics, Severity};
+use crate::redaction::Redactor;
+use crate::thinking::SharedThought;
+
+/// Thresholds for flagging unusual sessions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AnomalyConfig {
+    /// Flag sessions with this many times the average revisions
+    #[serde(default = "default_revision_factor")]
+    pub revision_factor: f64,
+    /// Fewest revisions ever flagged, for baselines with few revisions
+    #[serde(default = "default_min_revisions")]
+    pub min_revisions: u64,
+    /// Flag gaps this many times the session's median gap between thoughts
+    #[serde(default = "default_gap_factor")]
+    pub gap_factor: f64,
+    /// Shortest gap between thoughts ever flagged, in seconds
+    #[serde(default = "default_min_gap_secs")]
+    pub min_gap_secs: u64,
+    /// Flag quality scores this far below the average (0.0-1.0)
+    #[serde(default = "default_quality_drop")]
+    pub quality_drop: f64,
+    /// Other sessions needed before comparing with the average
+    #[serde(default = "default_min_baseline_sessions")]
+    pub min_baseline_sessions: usize,
+    /// URL alerts are posted to
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+}
+
+fn default_revision_factor() -> f64 {
+    10.0
+}
+
+fn default_min_revisions() -> u64 {
+    3
+}
+
+fn default_gap_factor() -> f64 {
+    5.0
+}
+
+fn default_min_gap_secs() -> u64 {
+    300
+}
+
+fn default_quality_drop() -> f64 {
+    0.25
+}
+
+fn default_min_baseline_sessions() -> usize {
+    3
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            revision_factor: default_revision_factor(),
+            min_revisions: default_min_revisions(),
+            gap_factor: default_gap_factor(),
+            min_gap_secs: default_min_gap_secs(),
+            quality_drop: default_quality_drop(),
+            min_baseline_sessions: default_min_baseline_sessions(),
+            webhook_url: None,
+        }
+    }
+}
+
+impl AnomalyConfig {
+    /// Validate the thresholds, returning every problem found
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if !self.revision_factor.is_finite() || self.revision_factor <= 1.0 {
+            errors.push("revision_factor: must be greater than 1".to_string());
+        }
+        if !self.gap_factor.is_finite() || self.gap_factor <= 1.0 {
+            errors.push("gap_factor: must be greater than 1".to_string());
+        }
+        if !(self.quality_drop > 0.0 && self.quality_drop <= 1.0) {
+            errors.push("quality_drop: must be greater than 0 and at most 1".to_string());
+        }
+        if self.min_baseline_sessions == 0 {
+            errors.push("min_baseline_sessions: must be greater than 0".to_string());
+        }
+        if let Some(url) = &self.webhook_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                errors.push(format!(
+                    "webhook_url: must be an http or https URL (got '{url}')"
+                ));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Kind of unusual behavior
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Far more revisions than the average session
+    ExcessiveRevisions,
+    /// A gap between thoughts far longer than the session's usual pace
+    LongGap,
+    /// A quality score far below the average session
+    QualityDrop,
+}
+
+impl AnomalyKind {
+    /// Name used in insight types and alerts
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ExcessiveRevisions => "excessive_revisions",
+            Self::LongGap => "long_gap",
+            Self::QualityDrop => "quality_drop",
+        }
+    }
+}
+
+/// One unusual behavior of a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Anomaly {
+    /// Kind of behavior
+    pub kind: AnomalyKind,
+    /// Human-readable description
+    pub description: String,
+    /// Value observed in the session
+    pub observed: f64,
+    /// Value expected from the baseline
+    pub expected: f64,
+    /// How far the session is from the expected value
+    pub severity: Severity,
+    /// Confidence from 0.0 to 1.0, growing with the data compared against
+    pub confidence: f64,
+    /// Thoughts the anomaly concerns, empty when it is session-wide
+    pub affected_thoughts: Vec<u32>,
+}
+
+/// Anomalies found in one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyReport {
+    /// Session ID
+    pub session_id: String,
+    /// Detection time
+    pub detected_at: DateTime<Utc>,
+    /// Other sessions the session was compared with
+    pub baseline_sessions: usize,
+    /// Anomalies found, empty for a usual session
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl AnomalyReport {
+    /// Copy with the session ID replaced and descriptions scrubbed by `redactor`
+    pub fn redacted(&self, redactor: &Redactor) -> AnomalyReport {
+        let mut redacted = self.clone();
+        redacted.session_id = redactor.redact_value("session", &self.session_id);
+        for anomaly in &mut redacted.anomalies {
+            anomaly.description = redactor.redact_text(&anomaly.description);
+        }
+        redacted
+    }
+
+    /// Whether no anomalies were found
+    pub fn is_empty(&self) -> bool {
+        self.anomalies.is_empty()
+    }
+
+    /// One-line summary of the report
+    pub fn summary(&self) -> String {
+        if self.anomalies.is_empty() {
+            return format!("No anomalies in session {}", self.session_id);
+        }
+        let kinds: Vec<&str> = self.anomalies.iter().map(|a| a.kind.as_str()).collect();
+        format!(
+            "{} anomal{} in session {}: {}",
+            self.anomalies.len(),
+            if self.anomalies.len() == 1 {
+                "y"
+            } else {
+                "ies"
+            },
+            self.session_id,
+            kinds.join(", ")
+        )
+    }
+
+    /// The anomalies as session insights
+    pub fn insights(&self) -> Vec<Insight> {
+        self.anomalies
+            .iter()
+            .map(|anomaly| Insight {
+                insight_type: format!("{ANOMALY_INSIGHT_PREFIX}{}", anomaly.kind.as_str()),
+                description: anomaly.description.clone(),
+                confidence: anomaly.confidence,
+                supporting_data: HashMap::from([
+                    ("observed".to_string(), serde_json::json!(anomaly.observed)),
+                    ("expected".to_string(), serde_json::json!(anomaly.expected)),
+                    (
+                        "baseline_sessions".to_string(),
+                        serde_json::json!(self.baseline_sessions),
+                    ),
+                    (
+                        "affected_thoughts".to_string(),
+                        serde_json::json!(anomaly.affected_thoughts),
+                    ),
+                ]),
+            })
+            .collect()
+    }
+}
+
+/// Prefix of the insight types of anomalies
+const ANOMALY_INSIGHT_PREFIX: &str = "anomaly_";
+
+impl AnalyticsEngine {
+    /// Set the thresholds for flagging unusual sessions
+    pub fn with_anomaly(mut self, anomaly: AnomalyConfig) -> Self {
+        self.config.anomaly = anomaly;
+        self
+    }
+
+    /// Find what is unusual about an analyzed session
+    ///
+    /// The session is compared with every other session this engine has
+    /// analyzed; `thoughts` are the session's thoughts, used for the gaps
+    /// between them. The anomalies replace any earlier ones in the session's
+    /// insights.
+    pub fn detect_anomalies(
+        &mut self,
+        session_id: &str,
+        thoughts: &[SharedThought],
+    ) -> Result<AnomalyReport, String> {
+        let analytics = self
+            .get_session_analytics(session_id)
+            .ok_or_else(|| format!("Session '{session_id}' has not been analyzed"))?;
+        let baseline: Vec<&SessionAnalytics> = self
+            .analytics_data
+            .values()
+            .filter(|other| other.session_id != session_id)
+            .collect();
+
+        let config = &self.config.anomaly;
+        let mut anomalies = Vec::new();
+        if baseline.len() >= config.min_baseline_sessions {
+            anomalies.extend(revision_anomaly(analytics, &baseline, config));
+            anomalies.extend(quality_anomaly(analytics, &baseline, config));
+        }
+        anomalies.extend(gap_anomalies(thoughts, config));
+
+        let report = AnomalyReport {
+            session_id: session_id.to_string(),
+            detected_at: self.clock.now(),
+            baseline_sessions: baseline.len(),
+            anomalies,
+        };
+        if let Some(analytics) = self.analytics_data.get_mut(session_id) {
+            analytics
+                .insights
+                .retain(|insight| !insight.insight_type.starts_with(ANOMALY_INSIGHT_PREFIX));
+            analytics.insights.extend(report.insights());
+        }
+        Ok(report)
+    }
+}
+
+/// Confidence of a comparison with `samples` data points
+fn confidence(samples: usize) -> f64 {
+    samples as f64 / (samples as f64 + 1.0)
+}
+
+/// Severity of exceeding a threshold `ratio` times
+fn severity(ratio: f64) -> Severity {
+    if ratio >= 2.0 {
+        Severity::Major
+    } else {
+        Severity::Moderate
+    }
+}
+
+/// Flag far more revisions than the average of the baseline
+fn revision_anomaly(
+    analytics: &SessionAnalytics,
+    baseline: &[&SessionAnalytics],
+    config: &AnomalyConfig,
+) -> Option<Anomaly> {
+    let revisions = analytics.basic_metrics.total_revisions;
+    let average = baseline
+        .iter()
+        .map(|other| other.basic_metrics.total_revisions as f64)
+        .sum::<f64>()
+        / baseline.len() as f64;
+    let threshold = (average * config.revision_factor).max(config.min_revisions as f64);
+    (revisions as f64 >= threshold).then(|| Anomaly {
+        kind: AnomalyKind::ExcessiveRevisions,
+        description: format!(
+            "Session has {revisions} revisions, against an average of {average:.1}"
+        ),
+        observed: revisions as f64,
+        expected: average,
+        severity: severity(revisions as f64 / threshold),
+        confidence: confidence(baseline.len()),
+        affected_thoughts: Vec::new(),
+    })
+}
+
+/// Flag a quality score far below the average of the baseline
+fn quality_anomaly(
+    analytics: &SessionAnalytics,
+    baseline: &[&SessionAnalytics],
+    config: &AnomalyConfig,
+) -> Option<Anomaly> {
+    let quality = analytics.quality_metrics.overall_quality_score;
+    let average = baseline
+        .iter()
+        .map(|other| other.quality_metrics.overall_quality_score)
+        .sum::<f64>()
+        / baseline.len() as f64;
+    let drop = average - quality;
+    (drop >= config.quality_drop).then(|| Anomaly {
+        kind: AnomalyKind::QualityDrop,
+        description: format!("Session quality is {quality:.2}, against an average of {average:.2}"),
+        observed: quality,
+        expected: average,
+        severity: severity(drop / config.quality_drop),
+        confidence: confidence(baseline.len()),
+        affected_thoughts: Vec::new(),
+    })
+}
+
+/// Flag gaps between thoughts far longer than the session's median gap
+fn gap_anomalies(thoughts: &[SharedThought], config: &AnomalyConfig) -> Vec<Anomaly> {
+    let mut timed: Vec<(u32, DateTime<Utc>)> = thoughts
+        .iter()
+        .filter_map(|thought| Some((thought.thought_number, thought.timestamp?)))
+        .collect();
+    timed.sort_by_key(|(_, at)| *at);
+    let gaps: Vec<(u32, f64)> = timed
+        .windows(2)
+        .map(|pair| {
+            let secs = (pair[1].1 - pair[0].1).num_milliseconds() as f64 / 1000.0;
+            (pair[1].0, secs)
+        })
+        .collect();
+    if gaps.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut sorted: Vec<f64> = gaps.iter().map(|(_, secs)| *secs).collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median = sorted[sorted.len() / 2];
+    let threshold = (median * config.gap_factor).max(config.min_gap_secs as f64);
+    gaps.iter()
+        .filter(|(_, secs)| *secs >= threshold)
+        .map(|(thought_number, secs)| Anomaly {
+            kind: AnomalyKind::LongGap,
+            description: format!(
+                "Thought {thought_number} came {secs:.0}s after the previous one, against a median gap of {median:.0}s"
+            ),
+            observed: *secs,
+            expected: median,
+            severity: severity(secs / threshold),
+            confidence: confidence(gaps.len()),
+            affected_thoughts: vec![*thought_number],
+        })
+        .collect()
+}
+
+/// Post an anomaly report to a webhook
+///
+/// The body carries the report's summary as `text`, which chat webhooks
+/// display, and the full report as `report`.
+#[cfg(feature = "integrations")]
+pub async fn send_anomaly_alert(
+    url: &str,
+    report: &AnomalyReport,
+) -> Result<(), crate::integrations::IntegrationError> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .json(&serde_json::json!({
+            "text": report.summary(),
+            "report": report
+        }))
+        .send()
+        .await?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Anomaly alert failed with {status}: {body}").into());
+    }
+    Ok(())
+}
+
+/// Post an anomaly report to a webhook
+#[cfg(not(feature = "integrations"))]
+pub async fn send_anomaly_alert(
+    _url: &str,
+    _report: &AnomalyReport,
+) -> Result<(), crate::integrations::IntegrationError> {
+    Err("Sending anomaly alerts requires the `integrations` feature".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::{ThinkingProgress, ThinkingStats, ThoughtData};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_detect_anomalies() {
+        let start = Utc::now();
+        let session = |revisions: u32, gaps: &[i64]| -> Vec<SharedThought> {
+            let mut at = start;
+            let mut thoughts = Vec::new();
+            for (i, gap) in gaps.iter().enumerate() {
+                at += chrono::Duration::seconds(*gap);
+                let number = i as u32 + 1;
+                let mut thought = if number <= revisions {
+                    ThoughtData::revision("Reconsider the earlier plan".to_string(), number, 1)
+                } else {
+                    ThoughtData::new("Work through the next step".to_string(), number, 8)
+                };
+                thought.timestamp = Some(at);
+                thoughts.push(Arc::new(thought));
+            }
+            thoughts
+        };
+        let mut engine = AnalyticsEngine::new();
+        let mut analyze = |id: &str, thoughts: &[SharedThought]| {
+            let stats = ThinkingStats {
+                total_thoughts: thoughts.len() as u64,
+                total_revisions: thoughts.iter().filter(|t| t.is_revision()).count() as u64,
+                ..ThinkingStats::default()
+            };
+            engine.analyze_session(id, id, thoughts, &stats, &ThinkingProgress::new(1, 8));
+        };
+        let usual = session(0, &[0, 30, 30, 30, 30]);
+        for id in ["a", "b", "c"] {
+            analyze(id, &usual);
+        }
+        let unusual = session(6, &[0, 30, 30, 30, 900, 30, 30, 30]);
+        analyze("d", &unusual);
+
+        let report = engine.detect_anomalies("d", &unusual).unwrap();
+        assert_eq!(report.baseline_sessions, 3);
+        let kinds: Vec<AnomalyKind> = report.anomalies.iter().map(|a| a.kind).collect();
+        assert!(kinds.contains(&AnomalyKind::ExcessiveRevisions));
+        assert!(kinds.contains(&AnomalyKind::LongGap));
+        let gap = report
+            .anomalies
+            .iter()
+            .find(|a| a.kind == AnomalyKind::LongGap)
+            .unwrap();
+        assert_eq!(gap.affected_thoughts, vec![5]);
+        assert_eq!(gap.observed, 900.0);
+
+        // Anomalies are added to the session's insights once
+        engine.detect_anomalies("d", &unusual).unwrap();
+        let insights = &engine.get_session_analytics("d").unwrap().insights;
+        assert_eq!(
+            insights
+                .iter()
+                .filter(|i| i.insight_type == "anomaly_long_gap")
+                .count(),
+            1
+        );
+
+        let report = engine.detect_anomalies("a", &usual).unwrap();
+        assert!(report.is_empty(), "{}", report.summary());
+        assert!(engine.detect_anomalies("missing", &[]).is_err());
+
+        // Too small a baseline only allows the gap check
+        let mut small = AnalyticsEngine::new();
+        let stats = ThinkingStats::default();
+        small.analyze_session("d", "d", &unusual, &stats, &ThinkingProgress::new(1, 8));
+        let report = small.detect_anomalies("d", &unusual).unwrap();
+        assert!(report
+            .anomalies
+            .iter()
+            .all(|a| a.kind == AnomalyKind::LongGap));
+
+        assert!(AnomalyConfig {
+            revision_factor: 1.0,
+            webhook_url: Some("ftp://alerts".to_string()),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,180 @@
+//! # Branch Analytics
+//!
+//! Per-branch breakdown of a session's exploration paths.
+//!
+//! Session metrics lump every thought together; the breakdown reports the
+//! length, quality and duration of each branch and whether it was merged
+//! back, concluded, abandoned or is still open, so productive exploration
+//! paths can be told apart from dead ends.
+
+use serde::{Deserialize, Serialize};
+
+use crate::analytics::{session_duration, AnalyticsEngine};
+use crate::thinking::SharedThought;
+
+/// What became of a branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BranchStatus {
+    /// The branch is still the latest line of thought
+    Open,
+    /// The branch's last thought needs no further thoughts
+    Concluded,
+    /// A later thought outside the branch depends on one of its thoughts
+    Merged,
+    /// The session moved on without concluding or merging the branch
+    Abandoned,
+}
+
+/// Metrics of one branch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BranchMetrics {
+    /// Branch identifier
+    pub branch_id: String,
+    /// Thought the branch forked from
+    pub parent_thought: u32,
+    /// Number of thoughts in the branch
+    pub thought_count: usize,
+    /// Total length of the branch's thoughts in characters
+    pub total_length: usize,
+    /// Average length of the branch's thoughts in characters
+    pub avg_thought_length: f64,
+    /// Overall quality score of the branch's reasoning path
+    pub quality_score: f64,
+    /// Time from the branch's first to its last thought in seconds
+    pub duration: u64,
+    /// What became of the branch
+    pub status: BranchStatus,
+}
+
+impl AnalyticsEngine {
+    /// Break a session's metrics down by branch, in the order branches were started
+    ///
+    /// A branch's quality is scored on its reasoning path: the main-line
+    /// thoughts up to its fork followed by its own thoughts.
+    pub fn branch_breakdown(&self, thoughts: &[SharedThought]) -> Vec<BranchMetrics> {
+        let mut branch_ids: Vec<&str> = Vec::new();
+        for thought in thoughts {
+            if let Some(branch_id) = thought.branch_id.as_deref() {
+                if !branch_ids.contains(&branch_id) {
+                    branch_ids.push(branch_id);
+                }
+            }
+        }
+
+        branch_ids
+            .into_iter()
+            .map(|branch_id| self.branch_metrics(thoughts, branch_id))
+            .collect()
+    }
+
+    /// Metrics of the branch with the given ID
+    fn branch_metrics(&self, thoughts: &[SharedThought], branch_id: &str) -> BranchMetrics {
+        let in_branch = |thought: &SharedThought| thought.branch_id.as_deref() == Some(branch_id);
+        let members: Vec<SharedThought> =
+            thoughts.iter().filter(|t| in_branch(t)).cloned().collect();
+        let parent_thought = members
+            .iter()
+            .find_map(|thought| thought.branch_from_thought)
+            .unwrap_or(0);
+        let path: Vec<SharedThought> = thoughts
+            .iter()
+            .filter(|t| !t.is_branch() && t.thought_number <= parent_thought)
+            .chain(members.iter())
+            .cloned()
+            .collect();
+
+        let first = thoughts.iter().position(in_branch).unwrap_or(0);
+        let last = thoughts.iter().rposition(in_branch).unwrap_or(0);
+        let numbers: Vec<u32> = members.iter().map(|t| t.thought_number).collect();
+        let later_outside = || thoughts[first..].iter().filter(|t| !in_branch(t));
+        let status = if later_outside().any(|t| t.depends_on.iter().any(|n| numbers.contains(n))) {
+            BranchStatus::Merged
+        } else if members.last().is_some_and(|t| !t.next_thought_needed) {
+            BranchStatus::Concluded
+        } else if thoughts[last..].iter().any(|t| !in_branch(t)) {
+            BranchStatus::Abandoned
+        } else {
+            BranchStatus::Open
+        };
+
+        let total_length: usize = members.iter().map(|t| t.thought.chars().count()).sum();
+        BranchMetrics {
+            branch_id: branch_id.to_string(),
+            parent_thought,
+            thought_count: members.len(),
+            total_length,
+            avg_thought_length: total_length as f64 / members.len().max(1) as f64,
+            quality_score: self.calculate_quality_metrics(&path).overall_quality_score,
+            duration: session_duration(&members),
+            status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::ThoughtData;
+    use chrono::Utc;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_branch_breakdown() {
+        let start = Utc::now();
+        let at = |secs| Some(start + chrono::Duration::seconds(secs));
+        let mut merged = ThoughtData::branch("Try caching".to_string(), 3, 2, "cache".to_string());
+        merged.timestamp = at(10);
+        let mut merged_more =
+            ThoughtData::branch("Cache hit rates".to_string(), 4, 2, "cache".to_string());
+        merged_more.timestamp = at(70);
+        let abandoned = ThoughtData::branch("Try sharding".to_string(), 5, 2, "shard".to_string());
+        let mut main = ThoughtData::new("Adopt the cache".to_string(), 6, 8);
+        main.depends_on = vec![4];
+        let concluded = {
+            let mut thought =
+                ThoughtData::branch("Rewrite it".to_string(), 7, 6, "rewrite".to_string());
+            thought.next_thought_needed = false;
+            thought
+        };
+        let open = ThoughtData::branch("Tune it".to_string(), 8, 6, "tune".to_string());
+        let thoughts: Vec<SharedThought> = vec![
+            ThoughtData::new("Profile the service".to_string(), 1, 8),
+            ThoughtData::new("It is slow on reads".to_string(), 2, 8),
+            merged,
+            merged_more,
+            abandoned,
+            main,
+            concluded,
+            open,
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+
+        let breakdown = AnalyticsEngine::new().branch_breakdown(&thoughts);
+        let ids: Vec<&str> = breakdown.iter().map(|b| b.branch_id.as_str()).collect();
+        assert_eq!(ids, ["cache", "shard", "rewrite", "tune"]);
+        let statuses: Vec<BranchStatus> = breakdown.iter().map(|b| b.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                BranchStatus::Merged,
+                BranchStatus::Abandoned,
+                BranchStatus::Concluded,
+                BranchStatus::Open
+            ]
+        );
+        let cache = &breakdown[0];
+        assert_eq!((cache.parent_thought, cache.thought_count), (2, 2));
+        assert_eq!(cache.duration, 60);
+        assert_eq!(
+            cache.total_length,
+            "Try caching".len() + "Cache hit rates".len()
+        );
+        assert!(cache.quality_score > 0.0);
+        assert!(AnalyticsEngine::new()
+            .branch_breakdown(&thoughts[..2])
+            .is_empty());
+    }
+}
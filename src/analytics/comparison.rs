@@ -0,0 +1,308 @@
+//! # Session Comparison
+//!
+//! Side-by-side comparison of two analyzed sessions.
+//!
+//! Comparing the metrics of two sessions (length, efficiency, quality, style
+//! and time) is how prompting strategies are A/B tested: run the same problem
+//! with each strategy, analyze both sessions and compare the results.
+
+use serde::{Deserialize, Serialize};
+
+use crate::analytics::{AnalyticsEngine, SessionAnalytics, ThinkingStyle};
+
+/// Which value of a metric counts as better
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preference {
+    /// Higher values are better
+    Higher,
+    /// Lower values are better
+    Lower,
+    /// Neither direction is better; the difference is informational
+    Neutral,
+}
+
+/// Difference in one metric between two sessions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricDelta {
+    /// Metric name
+    pub metric: String,
+    /// Value in the first session
+    pub a: f64,
+    /// Value in the second session
+    pub b: f64,
+    /// `b - a`
+    pub difference: f64,
+    /// Relative change from `a` to `b` in percent, if `a` is non-zero
+    pub percent_change: Option<f64>,
+    /// Which value counts as better
+    pub preference: Preference,
+    /// Session that did better on this metric, if either did
+    pub better: Option<String>,
+}
+
+/// Structured diff of two sessions' analytics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionComparison {
+    /// First session ID
+    pub session_a: String,
+    /// Second session ID
+    pub session_b: String,
+    /// Per-metric differences
+    pub metrics: Vec<MetricDelta>,
+    /// Thinking style of the first session
+    pub style_a: ThinkingStyle,
+    /// Thinking style of the second session
+    pub style_b: ThinkingStyle,
+    /// Ranked metrics on which each session did better, as `(a, b)`
+    pub wins: (usize, usize),
+    /// Human-readable summary of the comparison
+    pub summary: String,
+}
+
+impl MetricDelta {
+    fn new(metric: &str, a: f64, b: f64, preference: Preference, ids: (&str, &str)) -> Self {
+        let better = match preference {
+            _ if (a - b).abs() < f64::EPSILON => None,
+            Preference::Neutral => None,
+            Preference::Higher if b > a => Some(ids.1),
+            Preference::Lower if b < a => Some(ids.1),
+            _ => Some(ids.0),
+        };
+        Self {
+            metric: metric.to_string(),
+            a,
+            b,
+            difference: b - a,
+            percent_change: (a != 0.0).then(|| (b - a) / a.abs() * 100.0),
+            preference,
+            better: better.map(str::to_string),
+        }
+    }
+
+    /// One-line description of the difference
+    fn describe(&self, ids: (&str, &str)) -> String {
+        let change = self
+            .percent_change
+            .map(|percent| format!(", {percent:+.1}%"))
+            .unwrap_or_default();
+        format!(
+            "{}: {} {:.2} vs {} {:.2}{change}",
+            self.metric, ids.0, self.a, ids.1, self.b
+        )
+    }
+}
+
+impl AnalyticsEngine {
+    /// Compare two analyzed sessions metric by metric
+    pub fn compare(a: &SessionAnalytics, b: &SessionAnalytics) -> SessionComparison {
+        let ids = (a.session_id.as_str(), b.session_id.as_str());
+        let delta = |metric, a, b, preference| MetricDelta::new(metric, a, b, preference, ids);
+        let metrics = vec![
+            delta(
+                "total_thoughts",
+                a.basic_metrics.total_thoughts as f64,
+                b.basic_metrics.total_thoughts as f64,
+                Preference::Neutral,
+            ),
+            delta(
+                "avg_thought_length",
+                a.basic_metrics.avg_thought_length,
+                b.basic_metrics.avg_thought_length,
+                Preference::Neutral,
+            ),
+            delta(
+                "total_revisions",
+                a.basic_metrics.total_revisions as f64,
+                b.basic_metrics.total_revisions as f64,
+                Preference::Neutral,
+            ),
+            delta(
+                "total_branches",
+                a.basic_metrics.total_branches as f64,
+                b.basic_metrics.total_branches as f64,
+                Preference::Neutral,
+            ),
+            delta(
+                "completion_rate",
+                a.basic_metrics.completion_rate,
+                b.basic_metrics.completion_rate,
+                Preference::Higher,
+            ),
+            delta(
+                "efficiency_score",
+                a.basic_metrics.efficiency_score,
+                b.basic_metrics.efficiency_score,
+                Preference::Higher,
+            ),
+            delta(
+                "overall_quality_score",
+                a.quality_metrics.overall_quality_score,
+                b.quality_metrics.overall_quality_score,
+                Preference::Higher,
+            ),
+            delta(
+                "coherence_score",
+                a.quality_metrics.coherence_score,
+                b.quality_metrics.coherence_score,
+                Preference::Higher,
+            ),
+            delta(
+                "clarity_score",
+                a.quality_metrics.clarity_score,
+                b.quality_metrics.clarity_score,
+                Preference::Higher,
+            ),
+            delta(
+                "session_duration",
+                a.basic_metrics.session_duration as f64,
+                b.basic_metrics.session_duration as f64,
+                Preference::Lower,
+            ),
+            delta(
+                "avg_processing_time_ms",
+                a.performance_metrics.avg_processing_time_ms,
+                b.performance_metrics.avg_processing_time_ms,
+                Preference::Lower,
+            ),
+            delta(
+                "estimated_cost",
+                a.cost.cost,
+                b.cost.cost,
+                Preference::Lower,
+            ),
+        ];
+
+        let wins_for = |id: &str| {
+            metrics
+                .iter()
+                .filter(|m| m.better.as_deref() == Some(id))
+                .count()
+        };
+        let wins = if ids.0 == ids.1 {
+            (0, 0)
+        } else {
+            (wins_for(ids.0), wins_for(ids.1))
+        };
+
+        let mut summary = vec![match wins.0.cmp(&wins.1) {
+            std::cmp::Ordering::Greater => format!(
+                "{} did better on {} of {} differing ranked metrics",
+                ids.0,
+                wins.0,
+                wins.0 + wins.1
+            ),
+            std::cmp::Ordering::Less => format!(
+                "{} did better on {} of {} differing ranked metrics",
+                ids.1,
+                wins.1,
+                wins.0 + wins.1
+            ),
+            std::cmp::Ordering::Equal => format!(
+                "{} and {} are evenly matched ({} ranked metrics each)",
+                ids.0, ids.1, wins.0
+            ),
+        }];
+        for metric in &metrics {
+            if matches!(
+                metric.metric.as_str(),
+                "overall_quality_score" | "total_thoughts"
+            ) {
+                summary.push(metric.describe(ids));
+            }
+        }
+        summary.push(format!(
+            "style: {} {:?} vs {} {:?}",
+            ids.0, a.thinking_patterns.thinking_style, ids.1, b.thinking_patterns.thinking_style
+        ));
+
+        SessionComparison {
+            session_a: a.session_id.clone(),
+            session_b: b.session_id.clone(),
+            metrics,
+            style_a: a.thinking_patterns.thinking_style.clone(),
+            style_b: b.thinking_patterns.thinking_style.clone(),
+            wins,
+            summary: summary.join("; "),
+        }
+    }
+
+    /// Compare two sessions analyzed by this engine
+    pub fn compare_sessions(
+        &self,
+        session_a: &str,
+        session_b: &str,
+    ) -> Result<SessionComparison, String> {
+        let find = |id: &str| {
+            self.get_session_analytics(id)
+                .ok_or_else(|| format!("Session '{id}' has not been analyzed"))
+        };
+        Ok(Self::compare(find(session_a)?, find(session_b)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::{SharedThought, ThinkingProgress, ThinkingStats, ThoughtData};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_compare_sessions() {
+        let mut engine = AnalyticsEngine::new();
+        let short = [Arc::new(ThoughtData::new(
+            "Guess the answer".to_string(),
+            1,
+            1,
+        ))];
+        let thorough: Vec<SharedThought> = (1..=4)
+            .map(|n| {
+                Arc::new(ThoughtData::new(
+                    format!("Step {n}: examine the problem carefully and therefore refine it"),
+                    n,
+                    4,
+                ))
+            })
+            .collect();
+        let stats = |total| ThinkingStats {
+            total_thoughts: total,
+            ..ThinkingStats::default()
+        };
+        engine.analyze_session(
+            "a",
+            "Terse",
+            &short,
+            &stats(1),
+            &ThinkingProgress::new(1, 4),
+        );
+        engine.analyze_session(
+            "b",
+            "Thorough",
+            &thorough,
+            &stats(4),
+            &ThinkingProgress::new(4, 4),
+        );
+
+        let comparison = engine.compare_sessions("a", "b").unwrap();
+        assert_eq!(comparison.session_b, "b");
+        let thoughts = &comparison.metrics[0];
+        assert_eq!(
+            (thoughts.a, thoughts.b, thoughts.difference),
+            (1.0, 4.0, 3.0)
+        );
+        assert_eq!(thoughts.percent_change, Some(300.0));
+        assert_eq!(thoughts.better, None);
+        let completion = comparison
+            .metrics
+            .iter()
+            .find(|m| m.metric == "completion_rate")
+            .unwrap();
+        assert_eq!(completion.better.as_deref(), Some("b"));
+        assert!(comparison.wins.1 >= 1);
+        assert!(comparison
+            .summary
+            .contains("total_thoughts: a 1.00 vs b 4.00"));
+
+        assert!(engine.compare_sessions("a", "missing").is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! # Cost Tracking
+//!
+//! Estimated spend of LLM-driven thinking sessions.
+//!
+//! Token usage is read from each thought's metadata (`input_tokens`,
+//! `output_tokens` and `model`). Thoughts without recorded usage are estimated
+//! from their text at [`CHARS_PER_TOKEN`] characters per output token. Prices
+//! come from a [`PricingConfig`] keyed by model name, and reports aggregate
+//! the estimate by model, tag, author and day for charging back agent usage.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::thinking::{SharedThought, ThoughtData};
+
+/// Characters per token assumed when a thought has no recorded usage
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Price of one model, per million tokens
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ModelPricing {
+    /// Price per million input (prompt) tokens
+    pub input_per_million: f64,
+    /// Price per million output (completion) tokens
+    pub output_per_million: f64,
+}
+
+/// Per-model pricing used for cost estimates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PricingConfig {
+    /// Currency the prices are given in
+    #[serde(default = "default_currency")]
+    pub currency: String,
+    /// Model assumed for thoughts that do not name one
+    #[serde(default)]
+    pub default_model: Option<String>,
+    /// Prices by model name
+    #[serde(default)]
+    pub models: HashMap<String, ModelPricing>,
+}
+
+fn default_currency() -> String {
+    "USD".to_string()
+}
+
+impl Default for PricingConfig {
+    fn default() -> Self {
+        Self {
+            currency: default_currency(),
+            default_model: None,
+            models: HashMap::new(),
+        }
+    }
+}
+
+impl PricingConfig {
+    /// Validate the prices, returning every problem found
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.currency.trim().is_empty() {
+            errors.push("currency: must not be empty".to_string());
+        }
+        for (model, pricing) in &self.models {
+            for (field, price) in [
+                ("input_per_million", pricing.input_per_million),
+                ("output_per_million", pricing.output_per_million),
+            ] {
+                if !price.is_finite() || price < 0.0 {
+                    errors.push(format!(
+                        "models.{model}.{field}: must be a non-negative number"
+                    ));
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Model a thought was produced with, falling back to the default model
+    pub fn model_for<'a>(&'a self, thought: &'a ThoughtData) -> Option<&'a str> {
+        thought
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("model"))
+            .and_then(|model| model.as_str())
+            .or(self.default_model.as_deref())
+    }
+
+    /// Cost of the given usage, or `None` if the model has no price
+    pub fn cost(&self, model: Option<&str>, usage: TokenUsage) -> Option<f64> {
+        let pricing = self.models.get(model?)?;
+        Some(
+            (usage.input_tokens as f64 * pricing.input_per_million
+                + usage.output_tokens as f64 * pricing.output_per_million)
+                / 1_000_000.0,
+        )
+    }
+}
+
+/// Tokens consumed by a thought
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    /// Input (prompt) tokens
+    pub input_tokens: u64,
+    /// Output (completion) tokens
+    pub output_tokens: u64,
+    /// Whether the usage was estimated from the thought text
+    pub estimated: bool,
+}
+
+impl TokenUsage {
+    /// Usage recorded on a thought, estimated from its text when missing
+    pub fn of(thought: &ThoughtData) -> Self {
+        let recorded = |key: &str| {
+            thought
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get(key))
+                .and_then(|value| value.as_u64())
+        };
+        match (recorded("input_tokens"), recorded("output_tokens")) {
+            (None, None) => Self {
+                input_tokens: 0,
+                output_tokens: thought.thought.chars().count().div_ceil(CHARS_PER_TOKEN) as u64,
+                estimated: true,
+            },
+            (input, output) => Self {
+                input_tokens: input.unwrap_or(0),
+                output_tokens: output.unwrap_or(0),
+                estimated: false,
+            },
+        }
+    }
+}
+
+/// Token usage and cost of a group of thoughts
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CostBucket {
+    /// What the bucket groups by (a model, tag, author, day or session)
+    pub key: String,
+    /// Thoughts in the bucket
+    pub thought_count: u32,
+    /// Thoughts whose usage was estimated from their text
+    pub estimated_thoughts: u32,
+    /// Input tokens
+    pub input_tokens: u64,
+    /// Output tokens
+    pub output_tokens: u64,
+    /// Estimated cost
+    pub cost: f64,
+}
+
+impl CostBucket {
+    pub(crate) fn add(&mut self, usage: TokenUsage, cost: f64) {
+        self.thought_count += 1;
+        if usage.estimated {
+            self.estimated_thoughts += 1;
+        }
+        self.input_tokens += usage.input_tokens;
+        self.output_tokens += usage.output_tokens;
+        self.cost += cost;
+    }
+}
+
+/// Estimated cost of one or more sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostReport {
+    /// Currency of all costs in the report
+    pub currency: String,
+    /// Totals across every thought
+    pub total: CostBucket,
+    /// Totals by model (`unknown` when no model is named or configured)
+    pub by_model: Vec<CostBucket>,
+    /// Totals by session tag (`untagged` for sessions without tags)
+    pub by_tag: Vec<CostBucket>,
+    /// Totals by author (`unattributed` for thoughts without one)
+    pub by_author: Vec<CostBucket>,
+    /// Totals by UTC day the thought was created (`unknown` without a timestamp)
+    pub by_day: Vec<CostBucket>,
+    /// Models that have no configured price and were counted at zero cost
+    pub unpriced_models: Vec<String>,
+    /// When the report was generated
+    pub generated_at: DateTime<Utc>,
+}
+
+impl CostReport {
+    /// Build a report from sessions given as their tags and thoughts
+    ///
+    /// A thought counts towards every tag of its session, so tag totals can
+    /// add up to more than the overall total.
+    pub fn build<'a>(
+        pricing: &PricingConfig,
+        sessions: impl IntoIterator<Item = (&'a [String], &'a [SharedThought])>,
+    ) -> Self {
+        let mut total = CostBucket {
+            key: "total".to_string(),
+            ..Default::default()
+        };
+        let mut by_model = BTreeMap::new();
+        let mut by_tag = BTreeMap::new();
+        let mut by_author = BTreeMap::new();
+        let mut by_day = BTreeMap::new();
+        let mut unpriced_models = BTreeSet::new();
+
+        let add = |buckets: &mut BTreeMap<String, CostBucket>, key: &str, usage, cost| {
+            buckets
+                .entry(key.to_string())
+                .or_insert_with(|| CostBucket {
+                    key: key.to_string(),
+                    ..Default::default()
+                })
+                .add(usage, cost);
+        };
+
+        for (tags, thoughts) in sessions {
+            for thought in thoughts {
+                let usage = TokenUsage::of(thought);
+                let model = pricing.model_for(thought);
+                let cost = pricing.cost(model, usage).unwrap_or_else(|| {
+                    unpriced_models.insert(model.unwrap_or("unknown").to_string());
+                    0.0
+                });
+
+                total.add(usage, cost);
+                add(&mut by_model, model.unwrap_or("unknown"), usage, cost);
+                if tags.is_empty() {
+                    add(&mut by_tag, "untagged", usage, cost);
+                }
+                for tag in tags {
+                    add(&mut by_tag, tag, usage, cost);
+                }
+                add(
+                    &mut by_author,
+                    thought.author.as_deref().unwrap_or("unattributed"),
+                    usage,
+                    cost,
+                );
+                let day = thought.timestamp.map_or_else(
+                    || "unknown".to_string(),
+                    |at| at.format("%Y-%m-%d").to_string(),
+                );
+                add(&mut by_day, &day, usage, cost);
+            }
+        }
+
+        Self {
+            currency: pricing.currency.clone(),
+            total,
+            by_model: by_model.into_values().collect(),
+            by_tag: by_tag.into_values().collect(),
+            by_author: by_author.into_values().collect(),
+            by_day: by_day.into_values().collect(),
+            unpriced_models: unpriced_models.into_iter().collect(),
+            generated_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_cost_report() {
+        let pricing = PricingConfig {
+            default_model: Some("small".to_string()),
+            models: HashMap::from([(
+                "large".to_string(),
+                ModelPricing {
+                    input_per_million: 3.0,
+                    output_per_million: 15.0,
+                },
+            )]),
+            ..Default::default()
+        };
+
+        let recorded = Arc::new(
+            ThoughtData::new("Plan the work".to_string(), 1, 2)
+                .with_author("planner".to_string())
+                .with_metadata("model".to_string(), serde_json::json!("large"))
+                .with_metadata("input_tokens".to_string(), serde_json::json!(1_000_000))
+                .with_metadata("output_tokens".to_string(), serde_json::json!(200_000)),
+        );
+        let estimated = Arc::new(ThoughtData::new("12345678".to_string(), 2, 2));
+        let tags = ["team-a".to_string(), "billing".to_string()];
+
+        let report = CostReport::build(
+            &pricing,
+            [
+                (&tags[..], &[recorded.clone()][..]),
+                (&[][..], &[estimated][..]),
+            ],
+        );
+        assert_eq!(report.currency, "USD");
+        assert_eq!(report.total.thought_count, 2);
+        assert_eq!(report.total.estimated_thoughts, 1);
+        assert_eq!(report.total.output_tokens, 200_002);
+        assert!((report.total.cost - 6.0).abs() < 1e-9);
+        assert_eq!(report.unpriced_models, vec!["small".to_string()]);
+
+        let keys =
+            |buckets: &[CostBucket]| buckets.iter().map(|b| b.key.clone()).collect::<Vec<_>>();
+        assert_eq!(keys(&report.by_model), vec!["large", "small"]);
+        assert_eq!(keys(&report.by_tag), vec!["billing", "team-a", "untagged"]);
+        assert_eq!(keys(&report.by_author), vec!["planner", "unattributed"]);
+        assert_eq!(report.by_day.len(), 1);
+        assert!((report.by_tag[0].cost - 6.0).abs() < 1e-9);
+
+        let mut invalid = pricing.clone();
+        invalid.models.get_mut("large").unwrap().output_per_million = -1.0;
+        assert_eq!(
+            invalid.validate().unwrap_err(),
+            vec!["models.large.output_per_million: must be a non-negative number"]
+        );
+    }
+}
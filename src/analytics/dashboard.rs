@@ -0,0 +1,294 @@
+//! # Analytics Dashboard
+//!
+//! Daily aggregates of analyzed sessions, kept across restarts.
+//!
+//! The [`MetricsAggregator`] of an [`AnalyticsEngine`] buckets every
+//! analyzed session by the day it was analyzed. With a [`MetricsStore`] set,
+//! the aggregates are saved after each session and can be loaded back when
+//! a server restarts. [`AnalyticsEngine::dashboard`] turns the buckets into
+//! a time series ready for charting: sessions, thoughts, average quality
+//! and processing times per day.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::analytics::{AnalyticsEngine, MetricsAggregator, SessionAnalytics};
+
+/// Largest number of days a dashboard covers
+pub const MAX_DASHBOARD_DAYS: u32 = 366;
+
+/// Aggregates of the sessions analyzed on one day
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailyMetrics {
+    /// Sessions analyzed
+    pub sessions: u64,
+    /// Thoughts in those sessions
+    pub thoughts: u64,
+    /// Sum of the sessions' overall quality scores
+    pub quality_total: f64,
+    /// Average processing time per thought of each session, in milliseconds
+    pub processing_times_ms: Vec<f64>,
+}
+
+impl DailyMetrics {
+    /// Add an analyzed session
+    pub(crate) fn record(&mut self, analytics: &SessionAnalytics) {
+        self.sessions += 1;
+        self.thoughts += analytics.basic_metrics.total_thoughts as u64;
+        self.quality_total += analytics.quality_metrics.overall_quality_score;
+        self.processing_times_ms
+            .push(analytics.performance_metrics.avg_processing_time_ms);
+    }
+}
+
+/// One day of a dashboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DashboardDay {
+    /// Day, in UTC
+    pub date: NaiveDate,
+    /// Sessions analyzed
+    pub sessions: u64,
+    /// Thoughts in those sessions
+    pub thoughts: u64,
+    /// Average overall quality score, 0.0 without sessions
+    pub avg_quality: f64,
+    /// Average of the sessions' processing times per thought, in milliseconds
+    pub avg_processing_time_ms: f64,
+    /// 95th percentile of the sessions' processing times per thought, in milliseconds
+    pub p95_processing_time_ms: f64,
+}
+
+/// Time-bucketed aggregates of analyzed sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsDashboard {
+    /// When the dashboard was generated
+    pub generated_at: DateTime<Utc>,
+    /// Sessions analyzed since aggregation began
+    pub total_sessions: u64,
+    /// One entry per day, oldest first, including days without sessions
+    pub days: Vec<DashboardDay>,
+}
+
+impl MetricsAggregator {
+    /// Daily aggregates of the `days` days up to and including `today`
+    pub fn daily_series(&self, today: NaiveDate, days: u32) -> Vec<DashboardDay> {
+        let days = days.clamp(1, MAX_DASHBOARD_DAYS);
+        (0..days)
+            .rev()
+            .filter_map(|back| today.checked_sub_days(chrono::Days::new(back.into())))
+            .map(|date| {
+                let metrics = self.daily.get(&date).cloned().unwrap_or_default();
+                let sessions = metrics.sessions.max(1) as f64;
+                DashboardDay {
+                    date,
+                    sessions: metrics.sessions,
+                    thoughts: metrics.thoughts,
+                    avg_quality: metrics.quality_total / sessions,
+                    avg_processing_time_ms: metrics.processing_times_ms.iter().sum::<f64>()
+                        / sessions,
+                    p95_processing_time_ms: percentile(&metrics.processing_times_ms, 0.95),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Nearest-rank percentile of `values`, 0.0 when empty
+fn percentile(values: &[f64], quantile: f64) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let rank = (quantile * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Storage for aggregated metrics
+pub trait MetricsStore: Send + std::fmt::Debug {
+    /// Load the saved metrics, if any were saved
+    fn load(&self) -> Result<Option<MetricsAggregator>, String>;
+
+    /// Save the metrics, replacing any saved before
+    fn save(&mut self, metrics: &MetricsAggregator) -> Result<(), String>;
+}
+
+/// Metrics store shared between components
+pub type SharedMetricsStore = Arc<Mutex<dyn MetricsStore>>;
+
+/// Metrics store writing a JSON file
+#[derive(Debug, Clone)]
+pub struct FileMetricsStore {
+    path: PathBuf,
+}
+
+impl FileMetricsStore {
+    /// Create a store writing to the given file
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Create a shareable store writing to the given file
+    pub fn shared(path: impl Into<PathBuf>) -> SharedMetricsStore {
+        Arc::new(Mutex::new(Self::new(path)))
+    }
+}
+
+impl MetricsStore for FileMetricsStore {
+    fn load(&self) -> Result<Option<MetricsAggregator>, String> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read {}: {e}", self.path.display()))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Invalid metrics in {}: {e}", self.path.display()))
+    }
+
+    fn save(&mut self, metrics: &MetricsAggregator) -> Result<(), String> {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        let content = serde_json::to_string_pretty(metrics).map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, content)
+            .map_err(|e| format!("Failed to write {}: {e}", self.path.display()))
+    }
+}
+
+/// Metrics store keeping the metrics in memory, for tests
+#[derive(Debug, Default)]
+pub struct MemoryMetricsStore {
+    saved: Option<MetricsAggregator>,
+}
+
+impl MemoryMetricsStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty shareable store
+    pub fn shared() -> SharedMetricsStore {
+        Arc::new(Mutex::new(Self::new()))
+    }
+}
+
+impl MetricsStore for MemoryMetricsStore {
+    fn load(&self) -> Result<Option<MetricsAggregator>, String> {
+        Ok(self.saved.clone())
+    }
+
+    fn save(&mut self, metrics: &MetricsAggregator) -> Result<(), String> {
+        self.saved = Some(metrics.clone());
+        Ok(())
+    }
+}
+
+impl AnalyticsEngine {
+    /// Save the aggregated metrics to `store` after every analyzed session
+    pub fn with_metrics_store(mut self, store: SharedMetricsStore) -> Self {
+        self.metrics_store = Some(store);
+        self
+    }
+
+    /// Replace the aggregated metrics with those saved in the metrics store
+    ///
+    /// Does nothing without a store or when nothing was saved yet.
+    pub fn load_aggregated_metrics(&mut self) -> Result<(), String> {
+        let Some(store) = &self.metrics_store else {
+            return Ok(());
+        };
+        let saved = store
+            .lock()
+            .map_err(|_| "Metrics store lock poisoned".to_string())?
+            .load()?;
+        if let Some(metrics) = saved {
+            self.metrics_aggregator = metrics;
+        }
+        Ok(())
+    }
+
+    /// Save the aggregated metrics to the metrics store, if one is set
+    pub fn persist_aggregated_metrics(&self) -> Result<(), String> {
+        match &self.metrics_store {
+            Some(store) => store
+                .lock()
+                .map_err(|_| "Metrics store lock poisoned".to_string())?
+                .save(&self.metrics_aggregator),
+            None => Ok(()),
+        }
+    }
+
+    /// Daily aggregates of the last `days` days, today included
+    pub fn dashboard(&self, days: u32) -> AnalyticsDashboard {
+        let generated_at = self.clock.now();
+        AnalyticsDashboard {
+            generated_at,
+            total_sessions: self.metrics_aggregator.total_sessions,
+            days: self
+                .metrics_aggregator
+                .daily_series(generated_at.date_naive(), days),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::clock::{Clock, TestClock};
+    use crate::thinking::{SharedThought, ThinkingProgress, ThinkingStats, ThoughtData};
+
+    #[test]
+    fn test_dashboard_and_metrics_store() {
+        let clock = TestClock::new(Utc::now());
+        let store = MemoryMetricsStore::shared();
+        let mut engine = AnalyticsEngine::new()
+            .with_clock(clock.shared())
+            .with_metrics_store(store.clone());
+        let thoughts: Vec<SharedThought> = vec![Arc::new(ThoughtData::new(
+            "Measure the latency first".to_string(),
+            1,
+            1,
+        ))];
+        let analyze = |engine: &mut AnalyticsEngine, id: &str, processing_ms: f64| {
+            let stats = ThinkingStats {
+                total_thoughts: 1,
+                avg_processing_time_ms: processing_ms,
+                ..ThinkingStats::default()
+            };
+            engine.analyze_session(id, id, &thoughts, &stats, &ThinkingProgress::new(1, 1));
+        };
+
+        analyze(&mut engine, "a", 10.0);
+        clock.advance(chrono::Duration::days(2));
+        for (id, processing_ms) in [("b", 20.0), ("c", 40.0), ("d", 100.0)] {
+            analyze(&mut engine, id, processing_ms);
+        }
+
+        let dashboard = engine.dashboard(3);
+        assert_eq!(dashboard.total_sessions, 4);
+        let sessions: Vec<u64> = dashboard.days.iter().map(|d| d.sessions).collect();
+        assert_eq!(sessions, [1, 0, 3]);
+        let today = &dashboard.days[2];
+        assert_eq!(today.date, clock.now().date_naive());
+        assert_eq!(today.thoughts, 3);
+        assert!((today.avg_processing_time_ms - 160.0 / 3.0).abs() < 1e-9);
+        assert_eq!(today.p95_processing_time_ms, 100.0);
+        assert_eq!(dashboard.days[1].avg_quality, 0.0);
+
+        // A restarted engine picks the aggregates up from the store
+        let mut restarted = AnalyticsEngine::new()
+            .with_clock(clock.shared())
+            .with_metrics_store(store);
+        assert_eq!(restarted.dashboard(3).total_sessions, 0);
+        restarted.load_aggregated_metrics().unwrap();
+        assert_eq!(restarted.dashboard(3).days, dashboard.days);
+
+        assert_eq!(percentile(&[], 0.95), 0.0);
+        assert_eq!(percentile(&[3.0, 1.0, 2.0], 0.5), 2.0);
+    }
+}
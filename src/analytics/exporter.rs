@@ -0,0 +1,348 @@
+//! # Analytics Exporters
+//!
+//! Pushing session analytics to external systems.
+//!
+//! An [`AnalyticsExporter`] sends a batch of session analytics somewhere;
+//! [`HttpAnalyticsExporter`] posts them to the configured analytics endpoint.
+//! An [`AnalyticsPusher`] queues analytics as sessions complete and flushes
+//! the queue periodically in batches, retrying failed batches with backoff
+//! and keeping them queued when every attempt fails. With a [`Redactor`]
+//! set, session IDs, titles, author names, branch IDs and the text of
+//! insights and recommendations are redacted before anything is queued.
+
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+use crate::analytics::SessionAnalytics;
+use crate::redaction::Redactor;
+
+/// Error type returned by analytics exporters
+pub type ExporterError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Most analytics a pusher queues; the oldest are dropped beyond this
+pub const MAX_QUEUED_ANALYTICS: usize = 10_000;
+
+/// Target that session analytics are pushed to
+#[async_trait]
+pub trait AnalyticsExporter: Send + Sync {
+    /// Send a batch of session analytics
+    async fn export(&self, batch: &[SessionAnalytics]) -> Result<(), ExporterError>;
+
+    /// Human-readable description of the target
+    fn describe(&self) -> String;
+}
+
+/// Exporter posting batches as JSON to an HTTP endpoint
+///
+/// The body is `{"sessions": [...]}`; the API key, if any, is sent as a
+/// bearer token. Posting needs the `integrations` feature.
+#[derive(Debug, Clone)]
+pub struct HttpAnalyticsExporter {
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+impl HttpAnalyticsExporter {
+    /// Create an exporter posting to `endpoint`
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            api_key: None,
+        }
+    }
+
+    /// Authenticate with an API key
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
+    }
+}
+
+#[async_trait]
+impl AnalyticsExporter for HttpAnalyticsExporter {
+    #[cfg(feature = "integrations")]
+    async fn export(&self, batch: &[SessionAnalytics]) -> Result<(), ExporterError> {
+        let mut request = reqwest::Client::new()
+            .post(&self.endpoint)
+            .json(&serde_json::json!({ "sessions": batch }));
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("Analytics export failed with {status}: {body}").into());
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "integrations"))]
+    async fn export(&self, _batch: &[SessionAnalytics]) -> Result<(), ExporterError> {
+        Err("Pushing analytics requires the `integrations` feature".into())
+    }
+
+    fn describe(&self) -> String {
+        self.endpoint.clone()
+    }
+}
+
+/// Queue of session analytics flushed to an exporter in batches
+pub struct AnalyticsPusher {
+    exporter: Arc<dyn AnalyticsExporter>,
+    queue: Mutex<VecDeque<SessionAnalytics>>,
+    batch_size: usize,
+    max_retries: u32,
+    retry_delay: Duration,
+    redactor: Option<Arc<Redactor>>,
+}
+
+impl std::fmt::Debug for AnalyticsPusher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnalyticsPusher")
+            .field("exporter", &self.exporter.describe())
+            .field("batch_size", &self.batch_size)
+            .field("max_retries", &self.max_retries)
+            .field("redactor", &self.redactor)
+            .finish()
+    }
+}
+
+impl AnalyticsPusher {
+    /// Create a pusher sending batches of 100, retried 3 times
+    pub fn new(exporter: Arc<dyn AnalyticsExporter>) -> Self {
+        Self {
+            exporter,
+            queue: Mutex::new(VecDeque::new()),
+            batch_size: 100,
+            max_retries: 3,
+            retry_delay: Duration::from_secs(1),
+            redactor: None,
+        }
+    }
+
+    /// Send at most `batch_size` sessions per request
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Retry a failed batch up to `max_retries` times, doubling `delay` each time
+    pub fn with_retries(mut self, max_retries: u32, delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Redact analytics with `redactor` before they are queued
+    pub fn with_redactor(mut self, redactor: Arc<Redactor>) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    /// Description of the exporter's target
+    pub fn describe(&self) -> String {
+        self.exporter.describe()
+    }
+
+    /// Queue a session's analytics for the next flush
+    pub async fn enqueue(&self, analytics: &SessionAnalytics) {
+        let analytics = match &self.redactor {
+            Some(redactor) => analytics.redacted(redactor),
+            None => analytics.clone(),
+        };
+        let mut queue = self.queue.lock().await;
+        queue.push_back(analytics);
+        if queue.len() > MAX_QUEUED_ANALYTICS {
+            queue.pop_front();
+            tracing::warn!("Analytics queue full, dropped the oldest session");
+        }
+    }
+
+    /// Number of sessions waiting to be sent
+    pub async fn queued(&self) -> usize {
+        self.queue.lock().await.len()
+    }
+
+    /// Send every queued session, returning how many were sent
+    ///
+    /// Stops at the first batch that fails every attempt; that batch and
+    /// the ones after it stay queued for the next flush.
+    pub async fn flush(&self) -> Result<usize, ExporterError> {
+        let mut sent = 0;
+        loop {
+            let batch: Vec<SessionAnalytics> = {
+                let mut queue = self.queue.lock().await;
+                let size = queue.len().min(self.batch_size);
+                queue.drain(..size).collect()
+            };
+            if batch.is_empty() {
+                return Ok(sent);
+            }
+            if let Err(e) = self.send(&batch).await {
+                let mut queue = self.queue.lock().await;
+                for analytics in batch.into_iter().rev() {
+                    queue.push_front(analytics);
+                }
+                return Err(e);
+            }
+            sent += batch.len();
+        }
+    }
+
+    /// Send a batch, retrying with exponential backoff
+    async fn send(&self, batch: &[SessionAnalytics]) -> Result<(), ExporterError> {
+        let mut delay = self.retry_delay;
+        let mut attempt = 0;
+        loop {
+            match self.exporter.export(batch).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt >= self.max_retries => return Err(e),
+                Err(e) => {
+                    tracing::debug!("Analytics export attempt {} failed: {}", attempt + 1, e);
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Flush the queue every `interval` until the task is aborted
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.flush().await {
+                    tracing::warn!("Failed to push analytics to {}: {}", self.describe(), e);
+                }
+            }
+        })
+    }
+}
+
+impl SessionAnalytics {
+    /// Copy with identifying values replaced and free text scrubbed by `redactor`
+    pub fn redacted(&self, redactor: &Redactor) -> SessionAnalytics {
+        let mut redacted = self.clone();
+        redacted.session_id = redactor.redact_value("session", &self.session_id);
+        redacted.session_title = redactor.redact_thought(&self.session_title);
+        for contribution in &mut redacted.author_breakdown {
+            if let Some(author) = &contribution.author {
+                contribution.author = Some(redactor.redact_value("author", author));
+            }
+        }
+        for branch in &mut redacted.branch_breakdown {
+            branch.branch_id = redactor.redact_value("branch", &branch.branch_id);
+        }
+        for insight in &mut redacted.insights {
+            insight.description = redactor.redact_text(&insight.description);
+            for value in insight.supporting_data.values_mut() {
+                *value = redactor.redact_json(value);
+            }
+        }
+        for recommendation in &mut redacted.recommendations {
+            recommendation.description = redactor.redact_text(&recommendation.description);
+        }
+        redacted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::AnalyticsEngine;
+    use crate::thinking::{SharedThought, ThinkingProgress, ThinkingStats, ThoughtData};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Exporter failing its first calls, recording the batches it accepts
+    #[derive(Default)]
+    struct FlakyExporter {
+        failures: AtomicUsize,
+        batches: std::sync::Mutex<Vec<Vec<SessionAnalytics>>>,
+    }
+
+    #[async_trait]
+    impl AnalyticsExporter for FlakyExporter {
+        async fn export(&self, batch: &[SessionAnalytics]) -> Result<(), ExporterError> {
+            if self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok()
+            {
+                return Err("unavailable".into());
+            }
+            self.batches.lock().unwrap().push(batch.to_vec());
+            Ok(())
+        }
+
+        fn describe(&self) -> String {
+            "flaky".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pusher_batches_retries_and_redacts() {
+        let mut engine = AnalyticsEngine::new();
+        let mut thought = ThoughtData::branch("Try caching".to_string(), 2, 1, "cache".to_string());
+        thought.author = Some("alice".to_string());
+        let thoughts: Vec<SharedThought> = vec![Arc::new(thought)];
+        let sessions: Vec<SessionAnalytics> = (0..5)
+            .map(|i| {
+                engine.analyze_session(
+                    &format!("session-{i}"),
+                    "Secret project",
+                    &thoughts,
+                    &ThinkingStats::default(),
+                    &ThinkingProgress::new(1, 2),
+                )
+            })
+            .collect();
+
+        let exporter = Arc::new(FlakyExporter::default());
+        let pusher = AnalyticsPusher::new(exporter.clone())
+            .with_batch_size(2)
+            .with_retries(1, Duration::ZERO)
+            .with_redactor(Arc::new(
+                Redactor::new(&crate::redaction::RedactionConfig {
+                    mode: crate::redaction::RedactionMode::Hash,
+                    ..Default::default()
+                })
+                .unwrap(),
+            ));
+        for analytics in &sessions {
+            pusher.enqueue(analytics).await;
+        }
+
+        // One failure is retried away
+        exporter.failures.store(1, Ordering::SeqCst);
+        assert_eq!(pusher.flush().await.unwrap(), 5);
+        let sizes: Vec<usize> = exporter
+            .batches
+            .lock()
+            .unwrap()
+            .iter()
+            .map(Vec::len)
+            .collect();
+        assert_eq!(sizes, [2, 2, 1]);
+        let sent = exporter.batches.lock().unwrap()[0][0].clone();
+        assert!(sent.session_id.starts_with("session-") && sent.session_id != "session-0");
+        assert!(sent.session_title.starts_with("thought-"));
+        let author = sent.author_breakdown[0].author.as_deref().unwrap();
+        assert!(author.starts_with("author-") && author != "alice");
+        assert_ne!(sent.branch_breakdown[0].branch_id, "cache");
+
+        // A batch failing every attempt stays queued
+        pusher.enqueue(&sessions[0]).await;
+        exporter.failures.store(2, Ordering::SeqCst);
+        assert!(pusher.flush().await.is_err());
+        assert_eq!(pusher.queued().await, 1);
+        assert_eq!(pusher.flush().await.unwrap(), 1);
+        assert_eq!(pusher.queued().await, 0);
+    }
+}
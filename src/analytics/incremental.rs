@@ -0,0 +1,493 @@
+//! # Incremental Analytics
+//!
+//! Session analytics kept up to date as each thought arrives.
+//!
+//! [`AnalyticsEngine::analyze_session`] walks every thought on each call,
+//! which adds up when a live session is analyzed after every thought. An
+//! [`IncrementalAnalytics`] keeps running totals instead, so recording a
+//! thought is constant work and reading the analytics does not revisit
+//! earlier thoughts. Both paths share the same scoring rules and produce the
+//! same results; the full recompute remains the fallback and the reference
+//! that [`AnalyticsEngine::verify_incremental`] checks against.
+
+use std::collections::HashMap;
+
+use super::{
+    add_author_totals, add_quality_issues, apply_coherence_penalty, apply_flow_penalty,
+    author_contributions, common_patterns, completeness_score, complexity_trend, thinking_style,
+    AnalyticsEngine, AuthorContribution, AuthorTotals, ComplexityTrend, QualityIssue,
+    QualityMetrics, SessionAnalytics, ThinkingPatterns,
+};
+use crate::analytics::cost::{CostBucket, CostReport, PricingConfig, TokenUsage};
+use crate::analytics::readability::{
+    thought_readability, ReadabilityConfig, TextCounts, ThoughtReadability,
+};
+use crate::thinking::graph::ThoughtGraph;
+use crate::thinking::{SharedThought, ThinkingProgress, ThinkingStats, ThoughtData};
+
+/// Running analytics of one session
+#[derive(Debug, Clone)]
+pub struct IncrementalAnalytics {
+    /// Revisions recorded
+    revisions: usize,
+    /// Branch thoughts recorded
+    branches: usize,
+    /// Prefix sums of thought lengths, starting at zero
+    length_prefix: Vec<usize>,
+    /// Most recent thought, for the coherence check
+    previous: Option<SharedThought>,
+    coherence: f32,
+    flow: f32,
+    consecutive_revisions: usize,
+    /// Sum of the clarity of each thought
+    clarity: f64,
+    /// Readability counts of all thoughts, and the scores of each
+    text_counts: TextCounts,
+    thought_readability: Vec<ThoughtReadability>,
+    quality_issues: Vec<QualityIssue>,
+    authors: AuthorTotals,
+    cost: CostBucket,
+    /// Dependency graph, built as `ThoughtGraph::from_thoughts` would
+    graph: ThoughtGraph,
+    /// Thoughts recorded per thought number
+    occurrences: HashMap<u32, usize>,
+    /// Thoughts whose number depends on more than one earlier thought
+    convergent: usize,
+    /// Length of the longest dependency chain ending at each thought number
+    depths: HashMap<u32, usize>,
+    max_depth: usize,
+    /// Whether a repeated thought number added edges, invalidating `depths`
+    depths_stale: bool,
+}
+
+impl Default for IncrementalAnalytics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IncrementalAnalytics {
+    /// Create running analytics for an empty session
+    pub fn new() -> Self {
+        Self {
+            revisions: 0,
+            branches: 0,
+            length_prefix: vec![0],
+            previous: None,
+            coherence: 1.0,
+            flow: 1.0,
+            consecutive_revisions: 0,
+            clarity: 0.0,
+            text_counts: TextCounts::default(),
+            thought_readability: Vec::new(),
+            quality_issues: Vec::new(),
+            authors: HashMap::new(),
+            cost: CostBucket::default(),
+            graph: ThoughtGraph::new(),
+            occurrences: HashMap::new(),
+            convergent: 0,
+            depths: HashMap::new(),
+            max_depth: 0,
+            depths_stale: false,
+        }
+    }
+
+    /// Build running analytics from the thoughts recorded so far
+    pub fn from_thoughts(
+        thoughts: &[SharedThought],
+        pricing: &PricingConfig,
+        readability: &ReadabilityConfig,
+    ) -> Self {
+        let mut analytics = Self::new();
+        for thought in thoughts {
+            analytics.record(thought, pricing, readability);
+        }
+        analytics
+    }
+
+    /// Number of thoughts recorded
+    pub fn thought_count(&self) -> usize {
+        self.length_prefix.len() - 1
+    }
+
+    /// Update the running totals with the next thought
+    pub fn record(
+        &mut self,
+        thought: &SharedThought,
+        pricing: &PricingConfig,
+        readability: &ReadabilityConfig,
+    ) {
+        let index = self.thought_count();
+        if thought.is_revision() {
+            self.revisions += 1;
+        }
+        if thought.is_branch() {
+            self.branches += 1;
+        }
+        let total_length = self.length_prefix[index] + thought.thought.len();
+        self.length_prefix.push(total_length);
+
+        if let Some(previous) = &self.previous {
+            apply_coherence_penalty(&mut self.coherence, previous, thought);
+        }
+        apply_flow_penalty(&mut self.flow, &mut self.consecutive_revisions, thought);
+        let counts = TextCounts::of(&thought.thought);
+        self.clarity += counts.clarity(readability);
+        self.text_counts.add(counts);
+        self.thought_readability
+            .push(thought_readability(thought, &counts));
+        add_quality_issues(
+            &mut self.quality_issues,
+            index,
+            thought,
+            &counts,
+            readability,
+        );
+        add_author_totals(&mut self.authors, thought);
+
+        let usage = TokenUsage::of(thought);
+        let cost = pricing
+            .cost(pricing.model_for(thought), usage)
+            .unwrap_or(0.0);
+        self.cost.add(usage, cost);
+
+        self.record_dependencies(thought);
+        self.previous = Some(thought.clone());
+    }
+
+    /// Add a thought to the dependency graph and convergence counts
+    fn record_dependencies(&mut self, thought: &ThoughtData) {
+        let number = thought.thought_number;
+        let existed = self.graph.contains(number);
+        let before = self.graph.dependencies(number).len();
+        let valid: Vec<u32> = thought
+            .depends_on
+            .iter()
+            .copied()
+            .filter(|dep| self.graph.contains(*dep))
+            .collect();
+        if self.graph.add_thought(number, &valid).is_err() {
+            let _ = self.graph.add_thought(number, &[]);
+        }
+        let dependencies = self.graph.dependencies(number);
+
+        let occurrences = self.occurrences.entry(number).or_insert(0);
+        *occurrences += 1;
+        if dependencies.len() > 1 {
+            // Earlier thoughts with this number become convergent too
+            self.convergent += if before > 1 { 1 } else { *occurrences };
+        }
+
+        if !existed {
+            let depth = dependencies
+                .iter()
+                .filter_map(|dep| self.depths.get(dep))
+                .max()
+                .map_or(1, |d| d + 1);
+            self.depths.insert(number, depth);
+            self.max_depth = self.max_depth.max(depth);
+        } else if dependencies.len() > before {
+            self.depths_stale = true;
+        }
+    }
+
+    /// Thinking patterns of the recorded thoughts
+    pub fn thinking_patterns(&self) -> ThinkingPatterns {
+        let total = self.thought_count();
+        let frequency = |count: usize| {
+            if total > 1 {
+                count as f64 / (total - 1) as f64
+            } else {
+                0.0
+            }
+        };
+
+        ThinkingPatterns {
+            revision_frequency: frequency(self.revisions),
+            branching_frequency: frequency(self.branches),
+            complexity_trend: self.complexity_trend(),
+            thinking_style: thinking_style(self.revisions, self.branches, total),
+            common_patterns: common_patterns(
+                total,
+                self.revisions,
+                self.branches,
+                self.convergent,
+                || {
+                    if self.depths_stale {
+                        self.graph.depth()
+                    } else {
+                        self.max_depth
+                    }
+                },
+            ),
+        }
+    }
+
+    /// Complexity trend from the average length of the first and last thirds
+    fn complexity_trend(&self) -> ComplexityTrend {
+        let total = self.thought_count();
+        if total < 3 {
+            return ComplexityTrend::Stable;
+        }
+        let first_third = total / 3;
+        let last_third = total - first_third;
+        let avg_first = self.length_prefix[first_third] as f64 / first_third as f64;
+        let avg_last = (self.length_prefix[total] - self.length_prefix[last_third]) as f64
+            / (total - last_third) as f64;
+        complexity_trend(avg_first, avg_last)
+    }
+
+    /// Quality metrics of the recorded thoughts
+    pub fn quality_metrics(&self) -> QualityMetrics {
+        let total = self.thought_count();
+        let coherence_score = if total < 2 {
+            1.0
+        } else {
+            self.coherence.max(0.0) as f64
+        };
+        let (logical_flow_score, completeness_score, clarity_score) = if total == 0 {
+            (0.0, 0.0, 0.0)
+        } else {
+            (
+                self.flow.max(0.0) as f64,
+                completeness_score(self.length_prefix[total] as f64 / total as f64),
+                self.clarity / total as f64,
+            )
+        };
+
+        QualityMetrics {
+            coherence_score,
+            logical_flow_score,
+            completeness_score,
+            clarity_score,
+            overall_quality_score: (coherence_score
+                + logical_flow_score
+                + completeness_score
+                + clarity_score)
+                / 4.0,
+            quality_issues: self.quality_issues.clone(),
+            readability: self.text_counts.scores(),
+            thought_readability: self.thought_readability.clone(),
+        }
+    }
+
+    /// Contributions of each author, most prolific first
+    pub fn author_breakdown(&self) -> Vec<AuthorContribution> {
+        author_contributions(self.authors.clone(), self.thought_count())
+    }
+
+    /// Estimated token usage and cost
+    pub fn cost(&self) -> &CostBucket {
+        &self.cost
+    }
+}
+
+impl AnalyticsEngine {
+    /// Record a thought in a session's running analytics
+    pub fn record_thought(&mut self, session_id: &str, thought: &SharedThought) {
+        self.incremental
+            .entry(session_id.to_string())
+            .or_default()
+            .record(thought, &self.pricing, &self.config.readability);
+    }
+
+    /// Drop a session's running analytics, e.g. after a thought was edited
+    pub fn reset_incremental(&mut self, session_id: &str) {
+        self.incremental.remove(session_id);
+    }
+
+    /// Analyze a session from its running analytics
+    ///
+    /// Falls back to rebuilding the running analytics from `thoughts` when
+    /// they have not been recorded thought by thought, so the result always
+    /// matches [`analyze_session`](Self::analyze_session).
+    pub fn analyze_session_incremental(
+        &mut self,
+        session_id: &str,
+        session_title: &str,
+        thoughts: &[SharedThought],
+        stats: &ThinkingStats,
+        progress: &ThinkingProgress,
+    ) -> SessionAnalytics {
+        let up_to_date = self
+            .incremental
+            .get(session_id)
+            .is_some_and(|running| running.thought_count() == thoughts.len());
+        if !up_to_date {
+            self.incremental.insert(
+                session_id.to_string(),
+                IncrementalAnalytics::from_thoughts(
+                    thoughts,
+                    &self.pricing,
+                    &self.config.readability,
+                ),
+            );
+        }
+        let running = &self.incremental[session_id];
+
+        let basic_metrics = self.calculate_basic_metrics(thoughts, stats, progress);
+        let thinking_patterns = running.thinking_patterns();
+        let performance_metrics = self.calculate_performance_metrics(stats);
+        let quality_metrics = running.quality_metrics();
+        let insights = self.generate_insights(&[], &basic_metrics, &thinking_patterns);
+        let recommendations = self.generate_recommendations(&basic_metrics, &quality_metrics);
+        let author_breakdown = running.author_breakdown();
+        let cost = CostBucket {
+            key: session_id.to_string(),
+            ..running.cost().clone()
+        };
+
+        self.store(SessionAnalytics {
+            session_id: session_id.to_string(),
+            session_title: session_title.to_string(),
+            analyzed_at: self.clock.now(),
+            basic_metrics,
+            thinking_patterns,
+            performance_metrics,
+            quality_metrics,
+            insights,
+            recommendations,
+            author_breakdown,
+            branch_breakdown: self.branch_breakdown(thoughts),
+            cost,
+        })
+    }
+
+    /// Check a session's running analytics against a full recompute
+    ///
+    /// Returns the names of the sections that differ.
+    pub fn verify_incremental(
+        &self,
+        session_id: &str,
+        thoughts: &[SharedThought],
+    ) -> Result<(), Vec<String>> {
+        let running = self
+            .incremental
+            .get(session_id)
+            .ok_or_else(|| vec![format!("session '{session_id}' has no running analytics")])?;
+
+        let full_cost = CostReport::build(&self.pricing, [(&[][..], thoughts)]).total;
+        let sections = [
+            (
+                "thinking_patterns",
+                serde_json::to_value(running.thinking_patterns()),
+                serde_json::to_value(self.analyze_thinking_patterns(thoughts)),
+            ),
+            (
+                "quality_metrics",
+                serde_json::to_value(running.quality_metrics()),
+                serde_json::to_value(self.calculate_quality_metrics(thoughts)),
+            ),
+            (
+                "author_breakdown",
+                serde_json::to_value(running.author_breakdown()),
+                serde_json::to_value(Self::author_breakdown(thoughts)),
+            ),
+            (
+                "cost",
+                serde_json::to_value(CostBucket {
+                    key: String::new(),
+                    ..running.cost().clone()
+                }),
+                serde_json::to_value(CostBucket {
+                    key: String::new(),
+                    ..full_cost
+                }),
+            ),
+        ];
+
+        let mismatched: Vec<String> = sections
+            .into_iter()
+            .filter(|(_, running, full)| running.as_ref().ok() != full.as_ref().ok())
+            .map(|(name, _, _)| name.to_string())
+            .collect();
+        if mismatched.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatched)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incremental_matches_full_recompute() {
+        let mut thoughts: Vec<SharedThought> = Vec::new();
+        let mut push = |thought: ThoughtData| thoughts.push(SharedThought::new(thought));
+        push(ThoughtData::new(
+            "Frame the problem and its constraints".to_string(),
+            1,
+            8,
+        ));
+        push(
+            ThoughtData::new("Frame it again".to_string(), 2, 8).with_author("critic".to_string()),
+        );
+        push(ThoughtData::revision("Short".to_string(), 3, 1));
+        push(ThoughtData::revision(
+            "Revise the framing once more".to_string(),
+            4,
+            1,
+        ));
+        push(ThoughtData::revision(
+            "And once more, with feeling".to_string(),
+            5,
+            2,
+        ));
+        push(
+            ThoughtData::branch(
+                "Explore an alternative".to_string(),
+                6,
+                2,
+                "alt".to_string(),
+            )
+            .with_dependencies(vec![1, 2]),
+        );
+        push(
+            ThoughtData::new("Combine the results into a conclusion".repeat(20), 7, 8)
+                .with_dependencies(vec![6, 4]),
+        );
+        // A repeated number adding edges invalidates the cached depths
+        push(
+            ThoughtData::new("Naïve 日本語 restatement".to_string(), 3, 8)
+                .with_dependencies(vec![7, 2]),
+        );
+
+        let stats = ThinkingStats {
+            total_thoughts: thoughts.len() as u64,
+            ..ThinkingStats::default()
+        };
+        let progress = ThinkingProgress::new(8, 8);
+        let mut engine = AnalyticsEngine::new();
+        for (n, thought) in thoughts.iter().enumerate() {
+            engine.record_thought("live", thought);
+            assert_eq!(engine.verify_incremental("live", &thoughts[..=n]), Ok(()));
+        }
+
+        let full = engine.analyze_session("full", "", &thoughts, &stats, &progress);
+        let incremental =
+            engine.analyze_session_incremental("live", "", &thoughts, &stats, &progress);
+        let comparable = |analytics: &SessionAnalytics| {
+            let mut value = serde_json::to_value(analytics).unwrap();
+            for field in ["session_id", "analyzed_at", "cost"] {
+                value.as_object_mut().unwrap().remove(field);
+            }
+            value
+        };
+        assert_eq!(comparable(&incremental), comparable(&full));
+        assert_eq!(incremental.cost.cost, full.cost.cost);
+        assert!(incremental
+            .thinking_patterns
+            .common_patterns
+            .iter()
+            .any(|p| p.pattern_type == "convergent_reasoning"));
+
+        // Sessions that were never recorded fall back to a rebuild
+        let rebuilt = engine.analyze_session_incremental("cold", "", &thoughts, &stats, &progress);
+        assert_eq!(comparable(&rebuilt), comparable(&full));
+        engine.reset_incremental("cold");
+        assert!(engine.verify_incremental("cold", &thoughts).is_err());
+    }
+}
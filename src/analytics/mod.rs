@@ -5,11 +5,31 @@
 //! This module provides comprehensive analytics capabilities including
 //! session analysis, performance metrics, and insights generation.
 
+pub mod anomaly;
+pub mod branches;
+pub mod comparison;
+pub mod cost;
+pub mod dashboard;
+pub mod exporter;
+pub mod incremental;
+pub mod readability;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-
-use crate::thinking::{ThinkingProgress, ThinkingStats, ThoughtData};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::analytics::anomaly::AnomalyConfig;
+use crate::analytics::branches::BranchMetrics;
+use crate::analytics::cost::{CostBucket, CostReport, PricingConfig};
+use crate::analytics::dashboard::{DailyMetrics, SharedMetricsStore};
+use crate::analytics::incremental::IncrementalAnalytics;
+use crate::analytics::readability::{
+    add_readability_issue, thought_readability, ReadabilityConfig, ReadabilityScores, TextCounts,
+    ThoughtReadability,
+};
+use crate::thinking::clock::{SharedClock, SystemClock};
+use crate::thinking::graph::ThoughtGraph;
+use crate::thinking::{SharedThought, ThinkingProgress, ThinkingStats, ThoughtData};
 
 /// Analytics configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +50,12 @@ pub struct AnalyticsConfig {
     pub anonymize_data: bool,
     /// Export analytics data
     pub export_analytics: bool,
+    /// Thresholds marking a thought as hard to read
+    #[serde(default)]
+    pub readability: ReadabilityConfig,
+    /// Thresholds for flagging unusual sessions
+    #[serde(default)]
+    pub anomaly: AnomalyConfig,
 }
 
 impl Default for AnalyticsConfig {
@@ -43,6 +69,8 @@ impl Default for AnalyticsConfig {
             retention_days: 30,
             anonymize_data: false,
             export_analytics: false,
+            readability: ReadabilityConfig::default(),
+            anomaly: AnomalyConfig::default(),
         }
     }
 }
@@ -68,6 +96,32 @@ pub struct SessionAnalytics {
     pub insights: Vec<Insight>,
     /// Recommendations
     pub recommendations: Vec<Recommendation>,
+    /// Per-author contribution breakdown
+    #[serde(default)]
+    pub author_breakdown: Vec<AuthorContribution>,
+    /// Per-branch breakdown, in the order branches were started
+    #[serde(default)]
+    pub branch_breakdown: Vec<BranchMetrics>,
+    /// Estimated token usage and cost
+    #[serde(default)]
+    pub cost: CostBucket,
+}
+
+/// Contribution of one author to a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorContribution {
+    /// Author name, or `None` for unattributed thoughts
+    pub author: Option<String>,
+    /// Thoughts contributed
+    pub thought_count: u32,
+    /// Revisions contributed
+    pub revision_count: u32,
+    /// Branch thoughts contributed
+    pub branch_count: u32,
+    /// Average thought length in characters
+    pub avg_thought_length: f64,
+    /// Share of the session's thoughts (0.0 to 1.0)
+    pub share: f64,
 }
 
 /// Basic session metrics
@@ -81,6 +135,12 @@ pub struct BasicMetrics {
     pub total_branches: u32,
     /// Session duration in seconds
     pub session_duration: u64,
+    /// Seconds of the session spent thinking rather than paused
+    #[serde(default)]
+    pub active_duration: u64,
+    /// Seconds the session spent paused
+    #[serde(default)]
+    pub paused_duration: u64,
     /// Average thought length
     pub avg_thought_length: f64,
     /// Completion rate
@@ -183,12 +243,18 @@ pub struct QualityMetrics {
     pub logical_flow_score: f64,
     /// Completeness score
     pub completeness_score: f64,
-    /// Clarity score
+    /// Clarity score, from the readability of each thought
     pub clarity_score: f64,
     /// Overall quality score
     pub overall_quality_score: f64,
     /// Quality issues
     pub quality_issues: Vec<QualityIssue>,
+    /// Readability of the session's text as a whole
+    #[serde(default)]
+    pub readability: ReadabilityScores,
+    /// Readability of each thought, in session order
+    #[serde(default)]
+    pub thought_readability: Vec<ThoughtReadability>,
 }
 
 /// Quality issue
@@ -259,18 +325,176 @@ pub enum Difficulty {
     VeryHard,
 }
 
+/// Flat per-session metrics row for tabular exports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMetricsRow {
+    pub session_id: String,
+    pub session_title: String,
+    pub analyzed_at: DateTime<Utc>,
+    pub total_thoughts: u32,
+    pub total_revisions: u32,
+    pub total_branches: u32,
+    pub session_duration: u64,
+    #[serde(default)]
+    pub active_duration: u64,
+    #[serde(default)]
+    pub paused_duration: u64,
+    pub avg_thought_length: f64,
+    pub completion_rate: f64,
+    pub efficiency_score: f64,
+    pub revision_frequency: f64,
+    pub branching_frequency: f64,
+    pub complexity_trend: String,
+    pub thinking_style: String,
+    pub avg_processing_time_ms: f64,
+    pub total_processing_time_ms: u64,
+    pub throughput: f64,
+    pub coherence_score: f64,
+    pub logical_flow_score: f64,
+    pub completeness_score: f64,
+    pub clarity_score: f64,
+    pub overall_quality_score: f64,
+    pub insight_count: usize,
+    pub recommendation_count: usize,
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub estimated_cost: f64,
+}
+
+impl SessionMetricsRow {
+    /// Column names, in the order of `values`
+    pub const COLUMNS: [&'static str; 29] = [
+        "session_id",
+        "session_title",
+        "analyzed_at",
+        "total_thoughts",
+        "total_revisions",
+        "total_branches",
+        "session_duration",
+        "active_duration",
+        "paused_duration",
+        "avg_thought_length",
+        "completion_rate",
+        "efficiency_score",
+        "revision_frequency",
+        "branching_frequency",
+        "complexity_trend",
+        "thinking_style",
+        "avg_processing_time_ms",
+        "total_processing_time_ms",
+        "throughput",
+        "coherence_score",
+        "logical_flow_score",
+        "completeness_score",
+        "clarity_score",
+        "overall_quality_score",
+        "insight_count",
+        "recommendation_count",
+        "input_tokens",
+        "output_tokens",
+        "estimated_cost",
+    ];
+
+    /// Row values rendered as strings, in the order of `COLUMNS`
+    pub fn values(&self) -> Vec<String> {
+        vec![
+            self.session_id.clone(),
+            self.session_title.clone(),
+            self.analyzed_at.to_rfc3339(),
+            self.total_thoughts.to_string(),
+            self.total_revisions.to_string(),
+            self.total_branches.to_string(),
+            self.session_duration.to_string(),
+            self.active_duration.to_string(),
+            self.paused_duration.to_string(),
+            format!("{:.4}", self.avg_thought_length),
+            format!("{:.4}", self.completion_rate),
+            format!("{:.4}", self.efficiency_score),
+            format!("{:.4}", self.revision_frequency),
+            format!("{:.4}", self.branching_frequency),
+            self.complexity_trend.clone(),
+            self.thinking_style.clone(),
+            format!("{:.4}", self.avg_processing_time_ms),
+            self.total_processing_time_ms.to_string(),
+            format!("{:.4}", self.throughput),
+            format!("{:.4}", self.coherence_score),
+            format!("{:.4}", self.logical_flow_score),
+            format!("{:.4}", self.completeness_score),
+            format!("{:.4}", self.clarity_score),
+            format!("{:.4}", self.overall_quality_score),
+            self.insight_count.to_string(),
+            self.recommendation_count.to_string(),
+            self.input_tokens.to_string(),
+            self.output_tokens.to_string(),
+            format!("{:.6}", self.estimated_cost),
+        ]
+    }
+}
+
+impl From<&SessionAnalytics> for SessionMetricsRow {
+    fn from(analytics: &SessionAnalytics) -> Self {
+        let basic = &analytics.basic_metrics;
+        let patterns = &analytics.thinking_patterns;
+        let performance = &analytics.performance_metrics;
+        let quality = &analytics.quality_metrics;
+
+        Self {
+            session_id: analytics.session_id.clone(),
+            session_title: analytics.session_title.clone(),
+            analyzed_at: analytics.analyzed_at,
+            total_thoughts: basic.total_thoughts,
+            total_revisions: basic.total_revisions,
+            total_branches: basic.total_branches,
+            session_duration: basic.session_duration,
+            active_duration: basic.active_duration,
+            paused_duration: basic.paused_duration,
+            avg_thought_length: basic.avg_thought_length,
+            completion_rate: basic.completion_rate,
+            efficiency_score: basic.efficiency_score,
+            revision_frequency: patterns.revision_frequency,
+            branching_frequency: patterns.branching_frequency,
+            complexity_trend: format!("{:?}", patterns.complexity_trend),
+            thinking_style: format!("{:?}", patterns.thinking_style),
+            avg_processing_time_ms: performance.avg_processing_time_ms,
+            total_processing_time_ms: performance.total_processing_time_ms,
+            throughput: performance.throughput,
+            coherence_score: quality.coherence_score,
+            logical_flow_score: quality.logical_flow_score,
+            completeness_score: quality.completeness_score,
+            clarity_score: quality.clarity_score,
+            overall_quality_score: quality.overall_quality_score,
+            insight_count: analytics.insights.len(),
+            recommendation_count: analytics.recommendations.len(),
+            input_tokens: analytics.cost.input_tokens,
+            output_tokens: analytics.cost.output_tokens,
+            estimated_cost: analytics.cost.cost,
+        }
+    }
+}
+
 /// Analytics engine for processing session data
+#[derive(Debug)]
 pub struct AnalyticsEngine {
-    #[allow(dead_code)]
     config: AnalyticsConfig,
+    /// Model prices used for cost estimates
+    pricing: PricingConfig,
     /// Analytics data storage
     analytics_data: HashMap<String, SessionAnalytics>,
     /// Metrics aggregator
     metrics_aggregator: MetricsAggregator,
+    /// Running analytics of sessions analyzed incrementally
+    incremental: HashMap<String, IncrementalAnalytics>,
+    /// Source of analysis and export times
+    clock: SharedClock,
+    /// Where aggregated metrics are saved after each analyzed session
+    metrics_store: Option<SharedMetricsStore>,
 }
 
 /// Metrics aggregator for collecting and processing metrics
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsAggregator {
     /// Total sessions analyzed
     pub total_sessions: u64,
@@ -284,6 +508,9 @@ pub struct MetricsAggregator {
     pub avg_branches_per_session: f64,
     /// Performance trends
     pub performance_trends: HashMap<String, Vec<f64>>,
+    /// Sessions aggregated by the day they were analyzed
+    #[serde(default)]
+    pub daily: BTreeMap<chrono::NaiveDate, DailyMetrics>,
 }
 
 impl Default for MetricsAggregator {
@@ -295,6 +522,7 @@ impl Default for MetricsAggregator {
             avg_revisions_per_session: 0.0,
             avg_branches_per_session: 0.0,
             performance_trends: HashMap::new(),
+            daily: BTreeMap::new(),
         }
     }
 }
@@ -304,8 +532,12 @@ impl AnalyticsEngine {
     pub fn new() -> Self {
         Self {
             config: AnalyticsConfig::default(),
+            pricing: PricingConfig::default(),
             analytics_data: HashMap::new(),
             metrics_aggregator: MetricsAggregator::default(),
+            incremental: HashMap::new(),
+            clock: SystemClock::shared(),
+            metrics_store: None,
         }
     }
 
@@ -313,24 +545,51 @@ impl AnalyticsEngine {
     pub fn with_config(config: AnalyticsConfig) -> Self {
         Self {
             config,
+            pricing: PricingConfig::default(),
             analytics_data: HashMap::new(),
             metrics_aggregator: MetricsAggregator::default(),
+            incremental: HashMap::new(),
+            clock: SystemClock::shared(),
+            metrics_store: None,
         }
     }
 
+    /// Set the model prices used for cost estimates
+    pub fn with_pricing(mut self, pricing: PricingConfig) -> Self {
+        self.pricing = pricing;
+        self
+    }
+
+    /// Read analysis and export times from `clock`
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Set the thresholds marking a thought as hard to read
+    pub fn with_readability(mut self, readability: ReadabilityConfig) -> Self {
+        self.config.readability = readability;
+        self
+    }
+
+    /// Get the model prices used for cost estimates
+    pub fn pricing(&self) -> &PricingConfig {
+        &self.pricing
+    }
+
     /// Analyze a thinking session
     pub fn analyze_session(
         &mut self,
         session_id: &str,
         session_title: &str,
-        _thoughts: &[ThoughtData],
+        _thoughts: &[SharedThought],
         stats: &ThinkingStats,
         progress: &ThinkingProgress,
     ) -> SessionAnalytics {
-        let analyzed_at = Utc::now();
+        let analyzed_at = self.clock.now();
 
         // Calculate basic metrics
-        let basic_metrics = self.calculate_basic_metrics(stats, progress);
+        let basic_metrics = self.calculate_basic_metrics(_thoughts, stats, progress);
 
         // Analyze thinking patterns
         let thinking_patterns = self.analyze_thinking_patterns(_thoughts);
@@ -347,7 +606,19 @@ impl AnalyticsEngine {
         // Generate recommendations
         let recommendations = self.generate_recommendations(&basic_metrics, &quality_metrics);
 
-        let analytics = SessionAnalytics {
+        // Break contributions down by author
+        let author_breakdown = Self::author_breakdown(_thoughts);
+
+        // Break metrics down by branch
+        let branch_breakdown = self.branch_breakdown(_thoughts);
+
+        // Estimate what the session cost
+        let cost = CostBucket {
+            key: session_id.to_string(),
+            ..CostReport::build(&self.pricing, [(&[][..], _thoughts)]).total
+        };
+
+        self.store(SessionAnalytics {
             session_id: session_id.to_string(),
             session_title: session_title.to_string(),
             analyzed_at,
@@ -357,21 +628,36 @@ impl AnalyticsEngine {
             quality_metrics,
             insights,
             recommendations,
-        };
+            author_breakdown,
+            branch_breakdown,
+            cost,
+        })
+    }
 
-        // Store analytics data
+    /// Store a session's analytics and fold it into the aggregated metrics
+    fn store(&mut self, analytics: SessionAnalytics) -> SessionAnalytics {
         self.analytics_data
-            .insert(session_id.to_string(), analytics.clone());
-
-        // Update aggregator
+            .insert(analytics.session_id.clone(), analytics.clone());
         self.update_aggregator(&analytics);
-
+        if let Err(e) = self.persist_aggregated_metrics() {
+            tracing::warn!("Failed to save aggregated metrics: {}", e);
+        }
         analytics
     }
 
+    /// Group thoughts by author, most prolific first
+    pub fn author_breakdown(thoughts: &[SharedThought]) -> Vec<AuthorContribution> {
+        let mut by_author = HashMap::new();
+        for thought in thoughts {
+            add_author_totals(&mut by_author, thought);
+        }
+        author_contributions(by_author, thoughts.len())
+    }
+
     /// Calculate basic metrics
     fn calculate_basic_metrics(
         &self,
+        thoughts: &[SharedThought],
         stats: &ThinkingStats,
         progress: &ThinkingProgress,
     ) -> BasicMetrics {
@@ -379,7 +665,8 @@ impl AnalyticsEngine {
         let total_revisions = stats.total_revisions as u32;
         let total_branches = stats.total_branches as u32;
 
-        let session_duration = 0u64;
+        let session_duration = session_duration(thoughts);
+        let paused_duration = stats.total_paused_time_ms / 1000;
         let avg_thought_length = if stats.total_thought_length > 0 {
             stats.total_thought_length as f64 / total_thoughts as f64
         } else {
@@ -398,6 +685,8 @@ impl AnalyticsEngine {
             total_revisions,
             total_branches,
             session_duration,
+            active_duration: session_duration.saturating_sub(paused_duration),
+            paused_duration,
             avg_thought_length,
             completion_rate,
             efficiency_score,
@@ -431,7 +720,7 @@ impl AnalyticsEngine {
     }
 
     /// Analyze thinking patterns
-    fn analyze_thinking_patterns(&self, thoughts: &[ThoughtData]) -> ThinkingPatterns {
+    fn analyze_thinking_patterns(&self, thoughts: &[SharedThought]) -> ThinkingPatterns {
         let revision_frequency = if thoughts.len() > 1 {
             thoughts.iter().filter(|t| t.is_revision()).count() as f64 / (thoughts.len() - 1) as f64
         } else {
@@ -458,7 +747,7 @@ impl AnalyticsEngine {
     }
 
     /// Analyze complexity trend
-    fn analyze_complexity_trend(&self, thoughts: &[ThoughtData]) -> ComplexityTrend {
+    fn analyze_complexity_trend(&self, thoughts: &[SharedThought]) -> ComplexityTrend {
         if thoughts.len() < 3 {
             return ComplexityTrend::Stable;
         }
@@ -472,75 +761,32 @@ impl AnalyticsEngine {
         let avg_last = complexities[last_third..].iter().sum::<usize>() as f64
             / (complexities.len() - last_third) as f64;
 
-        let change_ratio = (avg_last - avg_first) / avg_first.max(1.0);
-
-        if change_ratio > 0.2 {
-            ComplexityTrend::Increasing
-        } else if change_ratio < -0.2 {
-            ComplexityTrend::Decreasing
-        } else if change_ratio.abs() < 0.1 {
-            ComplexityTrend::Stable
-        } else {
-            ComplexityTrend::Variable
-        }
+        complexity_trend(avg_first, avg_last)
     }
 
     /// Classify thinking style
-    fn classify_thinking_style(&self, thoughts: &[ThoughtData]) -> ThinkingStyle {
+    fn classify_thinking_style(&self, thoughts: &[SharedThought]) -> ThinkingStyle {
         let revisions = thoughts.iter().filter(|t| t.is_revision()).count();
         let branches = thoughts.iter().filter(|t| t.is_branch()).count();
-        let total = thoughts.len();
-
-        if revisions > total / 3 {
-            ThinkingStyle::Iterative
-        } else if branches > total / 4 {
-            ThinkingStyle::Exploratory
-        } else if revisions == 0 && branches == 0 {
-            ThinkingStyle::Linear
-        } else if total > 10 {
-            ThinkingStyle::Analytical
-        } else {
-            ThinkingStyle::Mixed
-        }
+        thinking_style(revisions, branches, thoughts.len())
     }
 
     /// Identify common patterns
-    fn identify_patterns(&self, thoughts: &[ThoughtData]) -> Vec<Pattern> {
-        let mut patterns = Vec::new();
-
-        // Pattern: Frequent revisions
+    fn identify_patterns(&self, thoughts: &[SharedThought]) -> Vec<Pattern> {
         let revision_count = thoughts.iter().filter(|t| t.is_revision()).count();
-        if revision_count > thoughts.len() / 4 {
-            patterns.push(Pattern {
-                pattern_type: "frequent_revisions".to_string(),
-                description: "High frequency of thought revisions".to_string(),
-                frequency: revision_count as u32,
-                confidence: 0.8,
-            });
-        }
-
-        // Pattern: Branching exploration
         let branch_count = thoughts.iter().filter(|t| t.is_branch()).count();
-        if branch_count > thoughts.len() / 5 {
-            patterns.push(Pattern {
-                pattern_type: "branching_exploration".to_string(),
-                description: "Exploratory thinking with multiple branches".to_string(),
-                frequency: branch_count as u32,
-                confidence: 0.7,
-            });
-        }
-
-        // Pattern: Linear progression
-        if revision_count == 0 && branch_count == 0 && thoughts.len() > 3 {
-            patterns.push(Pattern {
-                pattern_type: "linear_progression".to_string(),
-                description: "Straightforward linear thinking process".to_string(),
-                frequency: thoughts.len() as u32,
-                confidence: 0.9,
-            });
-        }
-
-        patterns
+        let graph = ThoughtGraph::from_thoughts(thoughts);
+        let convergent_count = thoughts
+            .iter()
+            .filter(|t| graph.dependencies(t.thought_number).len() > 1)
+            .count();
+        common_patterns(
+            thoughts.len(),
+            revision_count,
+            branch_count,
+            convergent_count,
+            || graph.depth(),
+        )
     }
 
     /// Calculate performance metrics
@@ -568,16 +814,23 @@ impl AnalyticsEngine {
     }
 
     /// Calculate quality metrics
-    fn calculate_quality_metrics(&self, thoughts: &[ThoughtData]) -> QualityMetrics {
+    pub fn calculate_quality_metrics(&self, thoughts: &[SharedThought]) -> QualityMetrics {
         let coherence_score = self.calculate_coherence_score(thoughts);
         let logical_flow_score = self.calculate_logical_flow_score(thoughts);
         let completeness_score = self.calculate_completeness_score(thoughts);
-        let clarity_score = self.calculate_clarity_score(thoughts);
+        let counts: Vec<TextCounts> = thoughts
+            .iter()
+            .map(|thought| TextCounts::of(&thought.thought))
+            .collect();
+        let clarity_score = self.calculate_clarity_score(&counts);
 
         let overall_quality_score =
             (coherence_score + logical_flow_score + completeness_score + clarity_score) / 4.0;
 
-        let quality_issues = self.identify_quality_issues(thoughts);
+        let quality_issues = self.identify_quality_issues(thoughts, &counts);
+
+        let mut session_counts = TextCounts::default();
+        counts.iter().for_each(|c| session_counts.add(*c));
 
         QualityMetrics {
             coherence_score,
@@ -586,36 +839,32 @@ impl AnalyticsEngine {
             clarity_score,
             overall_quality_score,
             quality_issues,
+            readability: session_counts.scores(),
+            thought_readability: thoughts
+                .iter()
+                .zip(&counts)
+                .map(|(thought, counts)| thought_readability(thought, counts))
+                .collect(),
         }
     }
 
     /// Calculate coherence score
-    fn calculate_coherence_score(&self, thoughts: &[ThoughtData]) -> f64 {
+    fn calculate_coherence_score(&self, thoughts: &[SharedThought]) -> f64 {
         if thoughts.len() < 2 {
             return 1.0;
         }
 
         let mut coherence_score: f32 = 1.0;
 
-        for i in 1..thoughts.len() {
-            let prev_thought = &thoughts[i - 1];
-            let curr_thought = &thoughts[i];
-
-            // Check for logical connections
-            let has_connection = curr_thought.thought.to_lowercase().contains(
-                &prev_thought.thought.to_lowercase()[..prev_thought.thought.len().min(10)],
-            );
-
-            if !has_connection && !curr_thought.is_revision() && !curr_thought.is_branch() {
-                coherence_score -= 0.1;
-            }
+        for pair in thoughts.windows(2) {
+            apply_coherence_penalty(&mut coherence_score, &pair[0], &pair[1]);
         }
 
         coherence_score.max(0.0) as f64
     }
 
     /// Calculate logical flow score
-    fn calculate_logical_flow_score(&self, thoughts: &[ThoughtData]) -> f64 {
+    fn calculate_logical_flow_score(&self, thoughts: &[SharedThought]) -> f64 {
         if thoughts.is_empty() {
             return 0.0;
         }
@@ -624,81 +873,46 @@ impl AnalyticsEngine {
         let mut consecutive_revisions = 0;
 
         for thought in thoughts {
-            if thought.is_revision() {
-                consecutive_revisions += 1;
-                if consecutive_revisions > 2 {
-                    flow_score -= 0.1;
-                }
-            } else {
-                consecutive_revisions = 0;
-            }
+            apply_flow_penalty(&mut flow_score, &mut consecutive_revisions, thought);
         }
 
         flow_score.max(0.0) as f64
     }
 
     /// Calculate completeness score
-    fn calculate_completeness_score(&self, thoughts: &[ThoughtData]) -> f64 {
+    fn calculate_completeness_score(&self, thoughts: &[SharedThought]) -> f64 {
         if thoughts.is_empty() {
             return 0.0;
         }
 
         let avg_length =
             thoughts.iter().map(|t| t.thought.len()).sum::<usize>() as f64 / thoughts.len() as f64;
-        let min_acceptable_length = 20.0;
-
-        if avg_length < min_acceptable_length {
-            0.5
-        } else if avg_length > 100.0 {
-            1.0
-        } else {
-            0.5 + (avg_length - min_acceptable_length) / (100.0 - min_acceptable_length) * 0.5
-        }
+        completeness_score(avg_length)
     }
 
-    /// Calculate clarity score
-    fn calculate_clarity_score(&self, thoughts: &[ThoughtData]) -> f64 {
-        if thoughts.is_empty() {
+    /// Calculate clarity score as the average readability-based clarity of the thoughts
+    fn calculate_clarity_score(&self, counts: &[TextCounts]) -> f64 {
+        if counts.is_empty() {
             return 0.0;
         }
 
-        let mut clarity_score: f32 = 1.0;
-
-        for thought in thoughts {
-            let words = thought.thought.split_whitespace().count();
-            if words < 5 {
-                clarity_score -= 0.1;
-            }
-            if thought.thought.len() > 500 {
-                clarity_score -= 0.05;
-            }
-        }
-
-        clarity_score.max(0.0) as f64
+        let total: f64 = counts
+            .iter()
+            .map(|c| c.clarity(&self.config.readability))
+            .sum();
+        total / counts.len() as f64
     }
 
     /// Identify quality issues
-    fn identify_quality_issues(&self, thoughts: &[ThoughtData]) -> Vec<QualityIssue> {
+    fn identify_quality_issues(
+        &self,
+        thoughts: &[SharedThought],
+        counts: &[TextCounts],
+    ) -> Vec<QualityIssue> {
         let mut issues = Vec::new();
 
-        for (i, thought) in thoughts.iter().enumerate() {
-            if thought.thought.len() < 10 {
-                issues.push(QualityIssue {
-                    issue_type: "short_thought".to_string(),
-                    description: "Thought is too short".to_string(),
-                    severity: Severity::Minor,
-                    affected_thoughts: vec![i as u32 + 1],
-                });
-            }
-
-            if thought.thought.len() > 1000 {
-                issues.push(QualityIssue {
-                    issue_type: "long_thought".to_string(),
-                    description: "Thought is too long".to_string(),
-                    severity: Severity::Moderate,
-                    affected_thoughts: vec![i as u32 + 1],
-                });
-            }
+        for (i, (thought, counts)) in thoughts.iter().zip(counts).enumerate() {
+            add_quality_issues(&mut issues, i, thought, counts, &self.config.readability);
         }
 
         issues
@@ -707,7 +921,7 @@ impl AnalyticsEngine {
     /// Generate insights
     fn generate_insights(
         &self,
-        _thoughts: &[ThoughtData],
+        _thoughts: &[SharedThought],
         basic_metrics: &BasicMetrics,
         thinking_patterns: &ThinkingPatterns,
     ) -> Vec<Insight> {
@@ -809,6 +1023,12 @@ impl AnalyticsEngine {
             (self.metrics_aggregator.avg_branches_per_session * (total_sessions - 1.0)
                 + analytics.basic_metrics.total_branches as f64)
                 / total_sessions;
+
+        self.metrics_aggregator
+            .daily
+            .entry(analytics.analyzed_at.date_naive())
+            .or_default()
+            .record(analytics);
     }
 
     /// Get analytics for a session
@@ -821,12 +1041,27 @@ impl AnalyticsEngine {
         &self.metrics_aggregator
     }
 
+    /// Get one metrics row per analyzed session, ordered by analysis time
+    pub fn metrics_rows(&self) -> Vec<SessionMetricsRow> {
+        let mut rows: Vec<SessionMetricsRow> = self
+            .analytics_data
+            .values()
+            .map(SessionMetricsRow::from)
+            .collect();
+        rows.sort_by(|a, b| {
+            a.analyzed_at
+                .cmp(&b.analyzed_at)
+                .then_with(|| a.session_id.cmp(&b.session_id))
+        });
+        rows
+    }
+
     /// Export analytics data
     pub fn export_analytics(&self) -> serde_json::Value {
         serde_json::json!({
             "analytics_data": self.analytics_data,
             "aggregated_metrics": self.metrics_aggregator,
-            "exported_at": Utc::now()
+            "exported_at": self.clock.now()
         })
     }
 }
@@ -837,10 +1072,254 @@ impl Default for AnalyticsEngine {
     }
 }
 
+/// Seconds from the first to the last timestamped thought
+fn session_duration(thoughts: &[SharedThought]) -> u64 {
+    let mut timestamps = thoughts.iter().filter_map(|thought| thought.timestamp);
+    let Some(first) = timestamps.next() else {
+        return 0;
+    };
+    let (start, end) = timestamps.fold((first, first), |(start, end), at| {
+        (start.min(at), end.max(at))
+    });
+    (end - start).num_seconds().max(0) as u64
+}
+
+/// Classify the complexity trend from the average length of the first and last thirds
+fn complexity_trend(avg_first: f64, avg_last: f64) -> ComplexityTrend {
+    let change_ratio = (avg_last - avg_first) / avg_first.max(1.0);
+
+    if change_ratio > 0.2 {
+        ComplexityTrend::Increasing
+    } else if change_ratio < -0.2 {
+        ComplexityTrend::Decreasing
+    } else if change_ratio.abs() < 0.1 {
+        ComplexityTrend::Stable
+    } else {
+        ComplexityTrend::Variable
+    }
+}
+
+/// Classify the thinking style from revision and branch counts
+fn thinking_style(revisions: usize, branches: usize, total: usize) -> ThinkingStyle {
+    if revisions > total / 3 {
+        ThinkingStyle::Iterative
+    } else if branches > total / 4 {
+        ThinkingStyle::Exploratory
+    } else if revisions == 0 && branches == 0 {
+        ThinkingStyle::Linear
+    } else if total > 10 {
+        ThinkingStyle::Analytical
+    } else {
+        ThinkingStyle::Mixed
+    }
+}
+
+/// Identify common patterns from thought counts
+///
+/// `depth` gives the length of the longest dependency chain and is only
+/// called when some thoughts combine several earlier ones.
+fn common_patterns(
+    total: usize,
+    revision_count: usize,
+    branch_count: usize,
+    convergent_count: usize,
+    depth: impl FnOnce() -> usize,
+) -> Vec<Pattern> {
+    let mut patterns = Vec::new();
+
+    // Pattern: Frequent revisions
+    if revision_count > total / 4 {
+        patterns.push(Pattern {
+            pattern_type: "frequent_revisions".to_string(),
+            description: "High frequency of thought revisions".to_string(),
+            frequency: revision_count as u32,
+            confidence: 0.8,
+        });
+    }
+
+    // Pattern: Branching exploration
+    if branch_count > total / 5 {
+        patterns.push(Pattern {
+            pattern_type: "branching_exploration".to_string(),
+            description: "Exploratory thinking with multiple branches".to_string(),
+            frequency: branch_count as u32,
+            confidence: 0.7,
+        });
+    }
+
+    // Pattern: Convergent reasoning (thoughts combining several earlier ones)
+    if convergent_count > 0 {
+        patterns.push(Pattern {
+            pattern_type: "convergent_reasoning".to_string(),
+            description: format!(
+                "Thoughts combine earlier conclusions (dependency depth {})",
+                depth()
+            ),
+            frequency: convergent_count as u32,
+            confidence: 0.8,
+        });
+    }
+
+    // Pattern: Linear progression
+    if revision_count == 0 && branch_count == 0 && total > 3 {
+        patterns.push(Pattern {
+            pattern_type: "linear_progression".to_string(),
+            description: "Straightforward linear thinking process".to_string(),
+            frequency: total as u32,
+            confidence: 0.9,
+        });
+    }
+
+    patterns
+}
+
+/// Penalize a thought that neither follows on from the previous one nor
+/// revises or branches
+fn apply_coherence_penalty(score: &mut f32, previous: &ThoughtData, thought: &ThoughtData) {
+    // Check for logical connections
+    let opening: String = previous.thought.to_lowercase().chars().take(10).collect();
+    let has_connection = thought.thought.to_lowercase().contains(&opening);
+
+    if !has_connection && !thought.is_revision() && !thought.is_branch() {
+        *score -= 0.1;
+    }
+}
+
+/// Penalize long runs of consecutive revisions
+fn apply_flow_penalty(score: &mut f32, consecutive_revisions: &mut usize, thought: &ThoughtData) {
+    if thought.is_revision() {
+        *consecutive_revisions += 1;
+        if *consecutive_revisions > 2 {
+            *score -= 0.1;
+        }
+    } else {
+        *consecutive_revisions = 0;
+    }
+}
+
+/// Score completeness from the average thought length
+fn completeness_score(avg_length: f64) -> f64 {
+    let min_acceptable_length = 20.0;
+
+    if avg_length < min_acceptable_length {
+        0.5
+    } else if avg_length > 100.0 {
+        1.0
+    } else {
+        0.5 + (avg_length - min_acceptable_length) / (100.0 - min_acceptable_length) * 0.5
+    }
+}
+
+/// Record the quality issues of the thought at an index
+fn add_quality_issues(
+    issues: &mut Vec<QualityIssue>,
+    index: usize,
+    thought: &ThoughtData,
+    counts: &TextCounts,
+    readability: &ReadabilityConfig,
+) {
+    if thought.thought.len() < 10 {
+        issues.push(QualityIssue {
+            issue_type: "short_thought".to_string(),
+            description: "Thought is too short".to_string(),
+            severity: Severity::Minor,
+            affected_thoughts: vec![index as u32 + 1],
+        });
+    }
+
+    if thought.thought.len() > 1000 {
+        issues.push(QualityIssue {
+            issue_type: "long_thought".to_string(),
+            description: "Thought is too long".to_string(),
+            severity: Severity::Moderate,
+            affected_thoughts: vec![index as u32 + 1],
+        });
+    }
+
+    add_readability_issue(issues, index, counts, readability);
+}
+
+/// Thought, revision and branch counts and total length of one author's thoughts
+type AuthorTotals = HashMap<Option<String>, (u32, u32, u32, usize)>;
+
+/// Add a thought to its author's totals
+fn add_author_totals(by_author: &mut AuthorTotals, thought: &ThoughtData) {
+    let entry = by_author.entry(thought.author.clone()).or_default();
+    entry.0 += 1;
+    if thought.is_revision() {
+        entry.1 += 1;
+    }
+    if thought.is_branch() {
+        entry.2 += 1;
+    }
+    entry.3 += thought.thought.len();
+}
+
+/// Turn per-author totals into contributions, most prolific first
+fn author_contributions(by_author: AuthorTotals, total: usize) -> Vec<AuthorContribution> {
+    let mut breakdown: Vec<AuthorContribution> = by_author
+        .into_iter()
+        .map(
+            |(author, (thought_count, revision_count, branch_count, length))| AuthorContribution {
+                author,
+                thought_count,
+                revision_count,
+                branch_count,
+                avg_thought_length: length as f64 / thought_count as f64,
+                share: thought_count as f64 / total.max(1) as f64,
+            },
+        )
+        .collect();
+    breakdown.sort_by(|a, b| {
+        b.thought_count
+            .cmp(&a.thought_count)
+            .then_with(|| a.author.cmp(&b.author))
+    });
+    breakdown
+}
+
+/// Analytics presentation utilities
+pub mod utils {
+    use super::*;
+
+    /// Characters used to render sparklines, from lowest to highest
+    const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    /// Render a sequence of values as a unicode sparkline
+    pub fn sparkline(values: &[usize]) -> String {
+        let min = values.iter().copied().min().unwrap_or(0);
+        let max = values.iter().copied().max().unwrap_or(0);
+        let range = (max - min).max(1) as f64;
+
+        values
+            .iter()
+            .map(|value| {
+                let level = ((value - min) as f64 / range * (SPARK_CHARS.len() - 1) as f64).round();
+                SPARK_CHARS[level as usize]
+            })
+            .collect()
+    }
+
+    /// Find the most revised thoughts, ordered by revision count
+    pub fn revision_hotspots(thoughts: &[SharedThought]) -> Vec<(u32, usize)> {
+        let mut counts: HashMap<u32, usize> = HashMap::new();
+        for thought in thoughts.iter().filter(|t| t.is_revision()) {
+            if let Some(revised) = thought.revises_thought {
+                *counts.entry(revised).or_insert(0) += 1;
+            }
+        }
+
+        let mut hotspots: Vec<(u32, usize)> = counts.into_iter().collect();
+        hotspots.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        hotspots
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::thinking::ThoughtData;
+    use std::sync::Arc;
 
     #[test]
     fn test_analytics_engine_creation() {
@@ -864,7 +1343,8 @@ mod tests {
             ..ThinkingStats::default()
         };
         let progress = ThinkingProgress::new(3, 3);
-        let metrics = engine.calculate_basic_metrics(&stats, &progress);
+        let thoughts: Vec<SharedThought> = thoughts.into_iter().map(Arc::new).collect();
+        let metrics = engine.calculate_basic_metrics(&thoughts, &stats, &progress);
 
         assert_eq!(metrics.total_thoughts, 3);
         assert_eq!(metrics.total_revisions, 0);
@@ -875,11 +1355,14 @@ mod tests {
     #[test]
     fn test_thinking_patterns_analysis() {
         let engine = AnalyticsEngine::new();
-        let _thoughts = vec![
+        let _thoughts: Vec<SharedThought> = vec![
             ThoughtData::new("First thought".to_string(), 1, 3),
             ThoughtData::revision("Revised thought".to_string(), 2, 1),
             ThoughtData::new("Third thought".to_string(), 3, 3),
-        ];
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
 
         let patterns = engine.analyze_thinking_patterns(&_thoughts);
 
@@ -890,14 +1373,17 @@ mod tests {
     #[test]
     fn test_quality_metrics_calculation() {
         let engine = AnalyticsEngine::new();
-        let _thoughts = vec![
+        let _thoughts: Vec<SharedThought> = vec![
             ThoughtData::new(
                 "This is a well-formed thought with sufficient detail".to_string(),
                 1,
                 3,
             ),
             ThoughtData::new("Another comprehensive thought".to_string(), 2, 3),
-        ];
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
 
         let metrics = engine.calculate_quality_metrics(&_thoughts);
 
@@ -907,4 +1393,138 @@ mod tests {
         assert!(metrics.clarity_score > 0.0);
         assert!(metrics.overall_quality_score > 0.0);
     }
+
+    #[test]
+    fn test_readability_in_quality_metrics() {
+        let thoughts: Vec<SharedThought> = vec![
+            ThoughtData::new("We read the logs and found the bug.".to_string(), 1, 2),
+            ThoughtData::new(
+                "Comprehensive institutional reevaluation necessitates organizational \
+                 interdependency considerations"
+                    .to_string(),
+                2,
+                2,
+            ),
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+
+        let metrics = AnalyticsEngine::new().calculate_quality_metrics(&thoughts);
+        assert_eq!(metrics.thought_readability.len(), 2);
+        assert!(
+            metrics.thought_readability[0].scores.flesch_reading_ease
+                > metrics.thought_readability[1].scores.flesch_reading_ease
+        );
+        assert_eq!(metrics.clarity_score, 0.5);
+        let hard: Vec<_> = metrics
+            .quality_issues
+            .iter()
+            .filter(|issue| issue.issue_type == "hard_to_read")
+            .collect();
+        assert_eq!(hard.len(), 1);
+        assert_eq!(hard[0].affected_thoughts, vec![2]);
+
+        // Thoughts shorter than min_words are not scored
+        let lenient = AnalyticsEngine::new()
+            .with_readability(ReadabilityConfig {
+                min_words: 20,
+                ..Default::default()
+            })
+            .calculate_quality_metrics(&thoughts);
+        assert!(lenient
+            .quality_issues
+            .iter()
+            .all(|issue| issue.issue_type != "hard_to_read"));
+    }
+
+    #[test]
+    fn test_author_breakdown() {
+        let thoughts: Vec<SharedThought> = vec![
+            ThoughtData::new("Plan the approach".to_string(), 1, 4)
+                .with_author("planner".to_string()),
+            ThoughtData::new("Gather data".to_string(), 2, 4).with_author("researcher".to_string()),
+            ThoughtData::revision("Better plan".to_string(), 3, 1)
+                .with_author("planner".to_string()),
+            ThoughtData::new("Unattributed".to_string(), 4, 4),
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+
+        let breakdown = AnalyticsEngine::author_breakdown(&thoughts);
+        assert_eq!(breakdown.len(), 3);
+        assert_eq!(breakdown[0].author.as_deref(), Some("planner"));
+        assert_eq!(breakdown[0].thought_count, 2);
+        assert_eq!(breakdown[0].revision_count, 1);
+        assert_eq!(breakdown[0].share, 0.5);
+        assert!(breakdown.iter().any(|c| c.author.is_none()));
+
+        // agent_id is accepted as an alias
+        let thought: ThoughtData = serde_json::from_value(serde_json::json!({
+            "thought": "From an agent",
+            "thought_number": 1,
+            "total_thoughts": 1,
+            "next_thought_needed": false,
+            "agent_id": "agent-7"
+        }))
+        .unwrap();
+        assert_eq!(thought.author.as_deref(), Some("agent-7"));
+    }
+
+    #[test]
+    fn test_sparkline_and_hotspots() {
+        assert_eq!(utils::sparkline(&[1, 5, 9]), "▁▅█");
+        assert_eq!(utils::sparkline(&[4, 4]), "▁▁");
+        assert_eq!(utils::sparkline(&[]), "");
+
+        let thoughts: Vec<SharedThought> = vec![
+            ThoughtData::new("First thought".to_string(), 1, 4),
+            ThoughtData::revision("Revise first".to_string(), 2, 1),
+            ThoughtData::revision("Revise first again".to_string(), 3, 1),
+            ThoughtData::revision("Revise second".to_string(), 4, 2),
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+        assert_eq!(utils::revision_hotspots(&thoughts), vec![(1, 2), (2, 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_clock_durations_and_throughput() {
+        use crate::thinking::clock::TestClock;
+        use crate::thinking::ThinkingEngine;
+
+        // Every clock reading takes a second, so each thought takes one
+        let start = Utc::now();
+        let mut thinking = ThinkingEngine::with_logging(true);
+        thinking.set_clock(
+            TestClock::new(start)
+                .with_step(chrono::Duration::seconds(1))
+                .shared(),
+        );
+        for n in 1..=3 {
+            let mut thought = ThoughtData::new(format!("Step {n}"), n, 3);
+            thought.timestamp = None;
+            thinking.process_thought(thought).await.unwrap();
+        }
+        assert_eq!(
+            thinking.get_thoughts()[2].timestamp,
+            Some(start + chrono::Duration::seconds(4))
+        );
+
+        let analyzed_at = start + chrono::Duration::hours(1);
+        let mut engine = AnalyticsEngine::new().with_clock(TestClock::new(analyzed_at).shared());
+        let analytics = engine.analyze_session(
+            "clocked",
+            "Clocked",
+            &thinking.all_thoughts(),
+            thinking.get_stats(),
+            thinking.get_progress(),
+        );
+        assert_eq!(analytics.analyzed_at, analyzed_at);
+        assert_eq!(analytics.basic_metrics.session_duration, 4);
+        assert_eq!(analytics.performance_metrics.total_processing_time_ms, 3000);
+        assert_eq!(analytics.performance_metrics.throughput, 60.0);
+    }
 }
@@ -0,0 +1,278 @@
+//! # Readability
+//!
+//! Readability scores of thoughts and sessions.
+//!
+//! Text is scored with the Flesch reading ease, the Flesch-Kincaid grade
+//! level and the Automated Readability Index. Scores are computed from word,
+//! sentence, syllable and character counts, which add up across thoughts,
+//! so a session is scored as one text and running analytics can keep the
+//! counts instead of the text. Syllables are estimated from vowel groups,
+//! which is close enough for English prose.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::analytics::{QualityIssue, Severity};
+use crate::thinking::ThoughtData;
+
+/// Thresholds marking a thought as hard to read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ReadabilityConfig {
+    /// Lowest acceptable Flesch reading ease (0-100, higher is easier)
+    #[serde(default = "default_min_reading_ease")]
+    pub min_reading_ease: f64,
+    /// Highest acceptable grade level, by Flesch-Kincaid or ARI
+    #[serde(default = "default_max_grade_level")]
+    pub max_grade_level: f64,
+    /// Words a thought needs before it is scored
+    #[serde(default = "default_min_words")]
+    pub min_words: usize,
+}
+
+fn default_min_reading_ease() -> f64 {
+    30.0
+}
+
+fn default_max_grade_level() -> f64 {
+    16.0
+}
+
+fn default_min_words() -> usize {
+    5
+}
+
+impl Default for ReadabilityConfig {
+    fn default() -> Self {
+        Self {
+            min_reading_ease: default_min_reading_ease(),
+            max_grade_level: default_max_grade_level(),
+            min_words: default_min_words(),
+        }
+    }
+}
+
+impl ReadabilityConfig {
+    /// Validate the thresholds, returning every problem found
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if !(0.0..=100.0).contains(&self.min_reading_ease) {
+            errors.push("min_reading_ease: must be between 0 and 100".to_string());
+        }
+        if !self.max_grade_level.is_finite() || self.max_grade_level <= 0.0 {
+            errors.push("max_grade_level: must be greater than 0".to_string());
+        }
+        if self.min_words == 0 {
+            errors.push("min_words: must be greater than 0".to_string());
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Readability scores of a text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReadabilityScores {
+    /// Flesch reading ease, roughly 0-100 where higher is easier
+    pub flesch_reading_ease: f64,
+    /// Flesch-Kincaid US grade level
+    pub flesch_kincaid_grade: f64,
+    /// Automated Readability Index, a US grade level
+    pub automated_readability_index: f64,
+}
+
+/// Readability scores of one thought
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThoughtReadability {
+    /// Number of the scored thought
+    pub thought_number: u32,
+    /// Scores of the thought's text
+    pub scores: ReadabilityScores,
+}
+
+/// Counts the readability formulas are computed from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextCounts {
+    /// Words containing a letter or digit
+    pub words: usize,
+    /// Sentences, at least one for text with words
+    pub sentences: usize,
+    /// Estimated syllables
+    pub syllables: usize,
+    /// Letters and digits in words
+    pub characters: usize,
+}
+
+impl TextCounts {
+    /// Count the words, sentences, syllables and characters of a text
+    pub fn of(text: &str) -> Self {
+        let mut counts = Self::default();
+        let mut open_sentence = false;
+        for token in text.split_whitespace() {
+            let word: String = token
+                .chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'')
+                .collect();
+            if word.chars().any(char::is_alphanumeric) {
+                counts.words += 1;
+                counts.characters += word.chars().filter(|c| c.is_alphanumeric()).count();
+                counts.syllables += syllables(&word);
+                open_sentence = true;
+            }
+            if open_sentence && token.ends_with(['.', '!', '?']) {
+                counts.sentences += 1;
+                open_sentence = false;
+            }
+        }
+        if open_sentence {
+            counts.sentences += 1;
+        }
+        counts
+    }
+
+    /// Add the counts of another text
+    pub fn add(&mut self, other: TextCounts) {
+        self.words += other.words;
+        self.sentences += other.sentences;
+        self.syllables += other.syllables;
+        self.characters += other.characters;
+    }
+
+    /// Scores of the counted text, all zero when it has no words
+    pub fn scores(&self) -> ReadabilityScores {
+        if self.words == 0 {
+            return ReadabilityScores::default();
+        }
+        let words = self.words as f64;
+        let words_per_sentence = words / self.sentences as f64;
+        let syllables_per_word = self.syllables as f64 / words;
+        ReadabilityScores {
+            flesch_reading_ease: 206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word,
+            flesch_kincaid_grade: 0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59,
+            automated_readability_index: 4.71 * (self.characters as f64 / words)
+                + 0.5 * words_per_sentence
+                - 21.43,
+        }
+    }
+
+    /// Clarity of the counted text from 0.0 to 1.0
+    ///
+    /// Plain English (reading ease 60 or more) scores 1.0, falling to 0.0 at
+    /// a reading ease of 0. Text too short to score gets 0.5.
+    pub fn clarity(&self, config: &ReadabilityConfig) -> f64 {
+        if self.words < config.min_words {
+            return 0.5;
+        }
+        (self.scores().flesch_reading_ease / 60.0).clamp(0.0, 1.0)
+    }
+}
+
+/// Estimate the syllables of a word from its vowel groups
+fn syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for c in word.chars() {
+        let vowel = matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+    // A trailing silent "e", as in "make", adds no syllable
+    if count > 1 && word.ends_with('e') && !word.ends_with("le") && !word.ends_with("ee") {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+/// Record a quality issue if a thought is harder to read than allowed
+pub(crate) fn add_readability_issue(
+    issues: &mut Vec<QualityIssue>,
+    index: usize,
+    counts: &TextCounts,
+    config: &ReadabilityConfig,
+) {
+    if counts.words < config.min_words {
+        return;
+    }
+    let scores = counts.scores();
+    let grade = scores
+        .flesch_kincaid_grade
+        .max(scores.automated_readability_index);
+    if scores.flesch_reading_ease < config.min_reading_ease || grade > config.max_grade_level {
+        issues.push(QualityIssue {
+            issue_type: "hard_to_read".to_string(),
+            description: format!(
+                "Thought is hard to read (reading ease {:.1}, grade level {:.1})",
+                scores.flesch_reading_ease, grade
+            ),
+            severity: Severity::Moderate,
+            affected_thoughts: vec![index as u32 + 1],
+        });
+    }
+}
+
+/// Readability of one thought
+pub(crate) fn thought_readability(
+    thought: &ThoughtData,
+    counts: &TextCounts,
+) -> ThoughtReadability {
+    ThoughtReadability {
+        thought_number: thought.thought_number,
+        scores: counts.scores(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readability_scores() {
+        let simple = TextCounts::of("The cat sat on the mat. It was a good day.");
+        assert_eq!(simple.words, 11);
+        assert_eq!(simple.sentences, 2);
+        let scores = simple.scores();
+        assert!(scores.flesch_reading_ease > 90.0);
+        assert!(scores.flesch_kincaid_grade < 3.0);
+
+        let dense = TextCounts::of(
+            "Institutional considerations regarding organizational interdependencies \
+             necessitate comprehensive reevaluation of administrative responsibilities",
+        );
+        assert_eq!(dense.sentences, 1);
+        let scores = dense.scores();
+        assert!(scores.flesch_reading_ease < 0.0);
+        assert!(scores.automated_readability_index > 20.0);
+
+        let config = ReadabilityConfig::default();
+        assert_eq!(simple.clarity(&config), 1.0);
+        assert_eq!(dense.clarity(&config), 0.0);
+        assert_eq!(TextCounts::of("Too short").clarity(&config), 0.5);
+        assert_eq!(TextCounts::of("").scores(), ReadabilityScores::default());
+
+        // Counts add up, so a session scores as one text
+        let mut session = simple;
+        session.add(dense);
+        assert_eq!(session.words, simple.words + dense.words);
+        assert_eq!(session.sentences, 3);
+
+        let mut issues = Vec::new();
+        add_readability_issue(&mut issues, 0, &simple, &config);
+        add_readability_issue(&mut issues, 1, &dense, &config);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, "hard_to_read");
+        assert_eq!(issues[0].affected_thoughts, vec![2]);
+
+        assert!(ReadabilityConfig {
+            min_reading_ease: 120.0,
+            min_words: 0,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,188 @@
+//! # Annotations Module
+//!
+//! Reviewer comments attached to individual thoughts.
+//!
+//! Annotations let humans review agent reasoning: each comment targets a
+//! thought (optionally on a branch), records its author and time, and can be
+//! marked resolved once addressed. Annotations are stored with the session
+//! and included in exports.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Reviewer comment on a thought
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Annotation {
+    /// Unique annotation identifier
+    pub id: String,
+    /// Number of the annotated thought
+    pub thought_number: u32,
+    /// Branch of the annotated thought, if any
+    #[serde(default)]
+    pub branch_id: Option<String>,
+    /// Comment author
+    pub author: String,
+    /// Comment body
+    pub body: String,
+    /// When the comment was made
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Whether the comment has been resolved
+    #[serde(default)]
+    pub resolved: bool,
+    /// When the comment was resolved
+    #[serde(default)]
+    pub resolved_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl Annotation {
+    /// Create a new unresolved annotation
+    pub fn new(thought_number: u32, author: String, body: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            thought_number,
+            branch_id: None,
+            author,
+            body,
+            created_at: chrono::Utc::now(),
+            resolved: false,
+            resolved_at: None,
+        }
+    }
+
+    /// Target a thought on a branch
+    pub fn on_branch(mut self, branch_id: String) -> Self {
+        self.branch_id = Some(branch_id);
+        self
+    }
+
+    /// Check whether the annotation targets the given thought
+    pub fn targets(&self, thought_number: u32, branch_id: Option<&str>) -> bool {
+        self.thought_number == thought_number && self.branch_id.as_deref() == branch_id
+    }
+
+    /// Validate the annotation
+    pub fn validate(&self) -> Result<(), String> {
+        if self.author.trim().is_empty() {
+            return Err("Annotation author cannot be empty".to_string());
+        }
+        if self.body.trim().is_empty() {
+            return Err("Annotation body cannot be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Annotations of a session, in creation order
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AnnotationStore {
+    annotations: Vec<Annotation>,
+}
+
+impl AnnotationStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an annotation
+    pub fn add(&mut self, annotation: Annotation) -> Result<&Annotation, String> {
+        annotation.validate()?;
+        self.annotations.push(annotation);
+        Ok(self.annotations.last().expect("annotation was just added"))
+    }
+
+    /// Mark an annotation as resolved
+    pub fn resolve(&mut self, id: &str) -> Result<&Annotation, String> {
+        let annotation = self
+            .annotations
+            .iter_mut()
+            .find(|a| a.id == id)
+            .ok_or_else(|| format!("Annotation {id} not found"))?;
+
+        if !annotation.resolved {
+            annotation.resolved = true;
+            annotation.resolved_at = Some(chrono::Utc::now());
+        }
+        Ok(annotation)
+    }
+
+    /// Get an annotation by ID
+    pub fn get(&self, id: &str) -> Option<&Annotation> {
+        self.annotations.iter().find(|a| a.id == id)
+    }
+
+    /// Get annotations on a thought
+    pub fn for_thought(&self, thought_number: u32, branch_id: Option<&str>) -> Vec<&Annotation> {
+        self.annotations
+            .iter()
+            .filter(|a| a.targets(thought_number, branch_id))
+            .collect()
+    }
+
+    /// Get annotations that have not been resolved
+    pub fn unresolved(&self) -> Vec<&Annotation> {
+        self.annotations.iter().filter(|a| !a.resolved).collect()
+    }
+
+    /// Get all annotations
+    pub fn all(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// Number of annotations
+    pub fn len(&self) -> usize {
+        self.annotations.len()
+    }
+
+    /// Check if there are no annotations
+    pub fn is_empty(&self) -> bool {
+        self.annotations.is_empty()
+    }
+
+    /// Remove all annotations
+    pub fn clear(&mut self) {
+        self.annotations.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotation_store() {
+        let mut store = AnnotationStore::new();
+        let id = store
+            .add(Annotation::new(
+                1,
+                "reviewer".to_string(),
+                "Unsupported assumption".to_string(),
+            ))
+            .unwrap()
+            .id
+            .clone();
+        store
+            .add(
+                Annotation::new(1, "reviewer".to_string(), "Good fork".to_string())
+                    .on_branch("alt".to_string()),
+            )
+            .unwrap();
+        assert!(store
+            .add(Annotation::new(2, "reviewer".to_string(), " ".to_string()))
+            .is_err());
+
+        assert_eq!(store.for_thought(1, None).len(), 1);
+        assert_eq!(store.for_thought(1, Some("alt")).len(), 1);
+        assert_eq!(store.unresolved().len(), 2);
+
+        let resolved = store.resolve(&id).unwrap();
+        assert!(resolved.resolved && resolved.resolved_at.is_some());
+        assert_eq!(store.unresolved().len(), 1);
+        assert!(store.resolve("missing").is_err());
+
+        let json = serde_json::to_string(&store).unwrap();
+        let restored: AnnotationStore = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.all(), store.all());
+    }
+}
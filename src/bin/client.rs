@@ -6,11 +6,58 @@
 //! sequential thinking servers and managing thinking sessions.
 
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
-use ultrafast_mcp_sequential_thinking::SequentialThinkingClient;
+use ultrafast_mcp_sequential_thinking::analytics::utils;
+use ultrafast_mcp_sequential_thinking::config::{
+    apply_env_overrides, read_config_document, CLIENT_ENV_PREFIX,
+};
+use ultrafast_mcp_sequential_thinking::export::dedup::export_fingerprint;
+use ultrafast_mcp_sequential_thinking::session::{
+    template::DEFAULT_TEMPLATES_DIR, SessionTemplate,
+};
+use ultrafast_mcp_sequential_thinking::thinking::approval::{ApproveAll, Approver};
+use ultrafast_mcp_sequential_thinking::thinking::client::{
+    AutoSaveRecord, SessionHandle, SyncMode,
+};
+use ultrafast_mcp_sequential_thinking::thinking::loadtest::{self, LoadTestConfig};
+use ultrafast_mcp_sequential_thinking::thinking::replay::{replay_delays, ReplayEventKind};
+use ultrafast_mcp_sequential_thinking::thinking::response::ThoughtResponse;
+use ultrafast_mcp_sequential_thinking::thinking::transcript::Transcript;
+use ultrafast_mcp_sequential_thinking::thinking::{SharedThought, ThinkingProgress};
+use ultrafast_mcp_sequential_thinking::{
+    AnalyticsEngine, Progress, SequentialThinkingClient, SequentialThinkingError,
+    SequentialThinkingResult, ThinkingStats, ThoughtRenderer, VisualizationStyle,
+};
+
+/// Approver asking for confirmation on the terminal
+///
+/// Without a terminal to ask on, calls are refused; `--yes` approves them.
+struct TerminalApprover;
+
+impl Approver for TerminalApprover {
+    fn approve(&self, tool: &str, arguments: &serde_json::Value) -> bool {
+        if !atty::is(atty::Stream::Stdin) {
+            eprintln!("⚠️  {tool} needs confirmation; pass --yes to run it without a terminal");
+            return false;
+        }
+        let mut shown = arguments.clone();
+        if let Some(checkpoint) = shown.get_mut("checkpoint") {
+            *checkpoint = serde_json::json!("…");
+        }
+        eprint!("⚠️  {tool} {shown} can discard reasoning history. Continue? [y/N] ");
+        let _ = std::io::Write::flush(&mut std::io::stderr());
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).is_ok()
+            && matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+}
+
+/// Saved sessions listed when an interactive session starts
+const RECENT_SESSIONS_SHOWN: usize = 5;
 
 /// Command-line arguments for the sequential thinking client
 #[derive(Parser)]
@@ -21,26 +68,68 @@ use ultrafast_mcp_sequential_thinking::SequentialThinkingClient;
     long_about = "High-performance Rust-based MCP client for sequential thinking"
 )]
 struct Args {
-    /// Server URL
-    #[arg(short, long, default_value = "stdio://")]
-    server: String,
+    /// Server URL (defaults to the configured server_url, stdio://)
+    #[arg(short, long, env = "SEQUENTIAL_THINKING_CLIENT__SERVER_URL")]
+    server: Option<String>,
+
+    /// Named server from the configuration's `servers` table
+    #[arg(
+        long,
+        value_name = "NAME",
+        env = "SEQUENTIAL_THINKING_SERVER_NAME",
+        conflicts_with = "server"
+    )]
+    server_name: Option<String>,
 
     /// Configuration file path
     #[arg(short, long, value_name = "FILE")]
     config: Option<PathBuf>,
 
+    /// Configuration profile to merge over the base configuration
+    #[arg(long, value_name = "NAME", env = "SEQUENTIAL_THINKING_PROFILE")]
+    profile: Option<String>,
+
     /// Timeout in seconds
-    #[arg(short, long, default_value = "30")]
+    #[arg(
+        short,
+        long,
+        default_value = "30",
+        env = "SEQUENTIAL_THINKING_CLIENT__TIMEOUT_SECONDS"
+    )]
     timeout: u64,
 
     /// Retry attempts
-    #[arg(long, default_value = "3")]
+    #[arg(
+        long,
+        default_value = "3",
+        env = "SEQUENTIAL_THINKING_CLIENT__RETRY_ATTEMPTS"
+    )]
     retries: u32,
 
     /// Session title
     #[arg(long)]
     session_title: Option<String>,
 
+    /// Directory containing session templates
+    #[arg(long, value_name = "DIR")]
+    templates_dir: Option<PathBuf>,
+
+    /// Directory for auto-saved sessions
+    #[arg(long, value_name = "DIR")]
+    auto_save_dir: Option<PathBuf>,
+
+    /// Author attributed to thoughts added by this client
+    #[arg(long, value_name = "NAME")]
+    author: Option<String>,
+
+    /// API key for servers that enforce access roles
+    #[arg(long, value_name = "KEY")]
+    api_key: Option<String>,
+
+    /// Run destructive tool calls without asking for confirmation
+    #[arg(short, long)]
+    yes: bool,
+
     /// Disable progress tracking
     #[arg(long)]
     disable_progress: bool,
@@ -49,8 +138,16 @@ struct Args {
     #[arg(long)]
     disable_visualization: bool,
 
+    /// Thought log style (boxed, compact, json, none)
+    #[arg(long, value_name = "STYLE")]
+    thought_style: Option<VisualizationStyle>,
+
     /// Auto-save interval in seconds
-    #[arg(long, default_value = "60")]
+    #[arg(
+        long,
+        default_value = "60",
+        env = "SEQUENTIAL_THINKING_CLIENT__THINKING__AUTO_SAVE_INTERVAL"
+    )]
     auto_save: u64,
 
     /// Log level
@@ -73,11 +170,28 @@ enum Commands {
     Interactive {
         /// Session title
         title: Option<String>,
+        /// Session template name or file
+        #[arg(long)]
+        template: Option<String>,
+        /// Recover an auto-saved session by ID
+        #[arg(long, value_name = "SESSION_ID", conflicts_with = "template")]
+        recover: Option<String>,
+        /// Record every command and response to a transcript file
+        #[arg(long, value_name = "FILE")]
+        record: Option<PathBuf>,
     },
-    /// Process a single thought
+    /// Process a single thought, or a stream of thoughts from stdin
     Think {
         /// Thought content
-        thought: String,
+        #[arg(required_unless_present = "stdin")]
+        thought: Option<String>,
+        /// Read thoughts from stdin and write results to stdout as JSON Lines
+        ///
+        /// Thoughts are separated by blank lines. A line holding a JSON object
+        /// is one thought given as sequential_thinking arguments, in which
+        /// `thoughtNumber` and `totalThoughts` may be left out.
+        #[arg(long, conflicts_with = "thought")]
+        stdin: bool,
         /// Thought number
         #[arg(long, default_value = "1")]
         number: u32,
@@ -87,6 +201,9 @@ enum Commands {
         /// Whether more thoughts are needed
         #[arg(long)]
         more_needed: bool,
+        /// Session template name or file
+        #[arg(long)]
+        template: Option<String>,
     },
     /// Export a session
     Export {
@@ -104,6 +221,74 @@ enum Commands {
         /// Session ID
         session_id: String,
     },
+    /// Merge the thoughts of several sessions on the server
+    ///
+    /// Asks for confirmation unless --yes is given.
+    Merge {
+        /// IDs of the sessions to merge
+        #[arg(required = true, num_args = 2.., value_name = "SESSION_ID")]
+        session_ids: Vec<String>,
+    },
+    /// Show a compact one-screen summary of a session
+    Summary {
+        /// Session ID
+        session_id: String,
+    },
+    /// Watch a session and print new thoughts as they arrive
+    Watch {
+        /// Session ID
+        session_id: String,
+        /// Output format (json, pretty), or the export format with --output
+        #[arg(long, default_value = "pretty")]
+        format: String,
+        /// Keep this file updated with the session's export
+        ///
+        /// The export format follows --format, or the file extension when
+        /// --format is left at pretty.
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// Poll interval in milliseconds
+        #[arg(long, default_value = "1000")]
+        interval: u64,
+    },
+    /// Replay how a session unfolded, paced like the original
+    ///
+    /// Given a transcript file recorded with `interactive --record`, its
+    /// commands are run again in a new session instead.
+    Replay {
+        /// Session ID or transcript file
+        session_id: String,
+        /// Playback speed (1 = original pacing, 0 = no delays)
+        #[arg(long, default_value = "1.0")]
+        speed: f64,
+        /// Output format (json, pretty)
+        #[arg(long, default_value = "pretty")]
+        format: String,
+        /// Fail if a replayed transcript's responses differ from the recording
+        #[arg(long)]
+        check: bool,
+    },
+    /// List, inspect and continue sessions saved by this client
+    Sessions {
+        /// Operation to perform
+        #[command(subcommand)]
+        action: SessionsCommand,
+    },
+    /// Drive the server with concurrent sessions and report throughput and latency
+    Loadtest {
+        /// Number of sessions to run
+        #[arg(long, default_value = "10")]
+        sessions: usize,
+        /// Thoughts added per session
+        #[arg(long, default_value = "10")]
+        thoughts: u32,
+        /// Sessions running at the same time
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// List available tools
     Tools,
     /// Show client information
@@ -117,64 +302,151 @@ enum Commands {
     },
 }
 
+/// Operations on sessions saved in the auto-save directory
+#[derive(Subcommand)]
+enum SessionsCommand {
+    /// List saved sessions, most recently saved first
+    List {
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show the thoughts of a saved session
+    Show {
+        /// Session ID
+        session_id: String,
+        /// Print the saved record as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Continue a saved session interactively
+    Resume {
+        /// Session ID
+        session_id: String,
+        /// Record every command and response to a transcript file
+        #[arg(long, value_name = "FILE")]
+        record: Option<PathBuf>,
+    },
+}
+
+/// Thought read by `think --stdin`
+enum StdinThought {
+    /// Paragraph of thought text
+    Text(String),
+    /// Line of sequential_thinking arguments
+    Json(String),
+}
+
+/// Thought numbering of an interactive session
+struct InteractiveState {
+    /// Number the next thought gets
+    thought_number: u32,
+    /// Total thoughts the next thought estimates
+    total_thoughts: u32,
+}
+
+impl InteractiveState {
+    /// Continue after the thoughts a session already has
+    fn new(existing_thoughts: &[SharedThought], template: Option<&SessionTemplate>) -> Self {
+        Self {
+            thought_number: existing_thoughts.len() as u32 + 1,
+            total_thoughts: match (template, existing_thoughts.last()) {
+                (Some(template), _) => template.total_thoughts,
+                (None, Some(last)) => last.total_thoughts,
+                (None, None) => 5,
+            },
+        }
+    }
+}
+
 /// Main client application
 struct ClientApp {
     /// Client configuration
     config: ultrafast_mcp_sequential_thinking::ClientConfig,
     /// Client instance
     client: SequentialThinkingClient,
+    /// Directory containing session templates
+    templates_dir: PathBuf,
 }
 
 impl ClientApp {
     /// Create a new client application
     async fn new(args: &Args) -> Result<Self, Box<dyn std::error::Error>> {
         // Load configuration
-        let mut config = if let Some(config_path) = &args.config {
-            Self::load_config_from_file(config_path)?
-        } else {
-            ultrafast_mcp_sequential_thinking::default_client_config()
+        let mut config = match (&args.config, &args.profile) {
+            (Some(config_path), profile) => {
+                Self::load_config_from_file(config_path, profile.as_deref())?
+            }
+            (None, Some(profile)) => {
+                return Err(format!("Profile '{profile}' requires a configuration file").into())
+            }
+            (None, None) => ultrafast_mcp_sequential_thinking::default_client_config(),
         };
 
-        // Override configuration with command-line arguments
+        // Override configuration with environment variables, then command-line arguments
+        config = apply_env_overrides(&config, CLIENT_ENV_PREFIX, std::env::vars())?;
+        if args.server.is_none() {
+            let server_name = args
+                .server_name
+                .clone()
+                .or_else(|| config.default_server.clone());
+            if let Some(name) = server_name {
+                config = config.for_server(&name)?;
+            }
+        }
         Self::override_config(&mut config, args);
 
         // Create client (connection and initialization handled internally)
-        let client = SequentialThinkingClient::with_config(&args.server, config.thinking.clone())
-            .await
-            .map_err(|e| format!("Failed to create client: {e}"))?;
+        let client =
+            SequentialThinkingClient::with_config(&config.server_url, config.thinking.clone())
+                .await
+                .map_err(|e| format!("Failed to create client: {e}"))?
+                .with_api_key(config.api_key.clone())
+                .with_request_timeout(std::time::Duration::from_secs(config.timeout_seconds))
+                .with_renderer(if config.thinking.show_thought_visualization {
+                    ThoughtRenderer::from_ui_config(&config.ui)
+                } else {
+                    ThoughtRenderer::new(VisualizationStyle::None)
+                })
+                .with_approver(if args.yes {
+                    Arc::new(ApproveAll)
+                } else {
+                    Arc::new(TerminalApprover)
+                });
+
+        let templates_dir = args
+            .templates_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_TEMPLATES_DIR));
+
+        Ok(Self {
+            config,
+            client,
+            templates_dir,
+        })
+    }
 
-        Ok(Self { config, client })
+    /// Progress bars and spinners following the UI configuration
+    fn progress(&self) -> Progress {
+        Progress::from_ui_config(&self.config.ui)
     }
 
-    /// Load configuration from file
+    /// Load configuration from file, applying the selected profile
     fn load_config_from_file(
         path: &PathBuf,
+        profile: Option<&str>,
     ) -> Result<ultrafast_mcp_sequential_thinking::ClientConfig, Box<dyn std::error::Error>> {
-        let content = std::fs::read_to_string(path)?;
-
-        if path.extension().and_then(|s| s.to_str()) == Some("toml") {
-            let config: toml::Value = toml::from_str(&content)?;
-            if let Some(client) = config.get("client") {
-                Ok(client.clone().try_into()?)
-            } else {
-                Ok(ultrafast_mcp_sequential_thinking::ClientConfig::default())
-            }
-        } else if path.extension().and_then(|s| s.to_str()) == Some("json") {
-            let config: serde_json::Value = serde_json::from_str(&content)?;
-            if let Some(client) = config.get("client") {
-                Ok(serde_json::from_value(client.clone())?)
-            } else {
-                Ok(ultrafast_mcp_sequential_thinking::ClientConfig::default())
-            }
-        } else {
-            Err("Unsupported configuration file format".into())
+        let document = read_config_document(path, profile)?;
+        match document.get("client") {
+            Some(client) => Ok(serde_json::from_value(client.clone())?),
+            None => Ok(ultrafast_mcp_sequential_thinking::ClientConfig::default()),
         }
     }
 
     /// Override configuration with command-line arguments
     fn override_config(config: &mut ultrafast_mcp_sequential_thinking::ClientConfig, args: &Args) {
-        if !args.server.is_empty() {
-            config.server_url = args.server.clone();
+        if let Some(server) = &args.server {
+            config.server_url = server.clone();
         }
 
         if args.timeout != 0 {
@@ -193,9 +465,25 @@ impl ClientApp {
             config.thinking.show_thought_visualization = false;
         }
 
+        if let Some(style) = args.thought_style {
+            config.ui.thought_style = style;
+        }
+
         if args.auto_save != 0 {
             config.thinking.auto_save_interval = args.auto_save;
         }
+
+        if let Some(ref dir) = args.auto_save_dir {
+            config.thinking.auto_save_dir = dir.to_string_lossy().to_string();
+        }
+
+        if let Some(ref author) = args.author {
+            config.thinking.author = Some(author.clone());
+        }
+
+        if let Some(ref api_key) = args.api_key {
+            config.api_key = Some(api_key.clone());
+        }
     }
 
     /// Initialize logging
@@ -204,8 +492,10 @@ impl ClientApp {
         let env_filter =
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&args.log_level));
 
+        // Logs go to stderr, leaving stdout to command output such as JSON Lines
         let builder = tracing_subscriber::fmt()
             .with_env_filter(env_filter)
+            .with_writer(std::io::stderr)
             .with_ansi(atty::is(atty::Stream::Stderr));
 
         // TODO: Add file logging support later
@@ -221,36 +511,106 @@ impl ClientApp {
         Ok(())
     }
 
-    /// Start interactive session
+    /// Start a session, optionally from a named template
+    async fn start_session(
+        &self,
+        title: Option<String>,
+        default_title: &str,
+        template: Option<&str>,
+    ) -> Result<(SessionHandle, Option<SessionTemplate>), Box<dyn std::error::Error>> {
+        match template {
+            Some(name) => {
+                let template = SessionTemplate::resolve(name, &self.templates_dir)?;
+                let session = self
+                    .client
+                    .start_session_from_template(&template, title, None)
+                    .await
+                    .map_err(|e| format!("Failed to start session: {e}"))?;
+                Ok((session, Some(template)))
+            }
+            None => {
+                let session = self
+                    .client
+                    .start_session(title.unwrap_or_else(|| default_title.to_string()))
+                    .await
+                    .map_err(|e| format!("Failed to start session: {e}"))?;
+                Ok((session, None))
+            }
+        }
+    }
+
+    /// Start interactive session, recording it to `record` when given
     async fn interactive_session(
         &self,
         title: Option<String>,
+        template: Option<&str>,
+        recover: Option<&str>,
+        record: Option<&Path>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let session_title = title.unwrap_or_else(|| "Interactive Session".to_string());
-        info!("Starting interactive thinking session: {}", session_title);
-
-        let session = self
-            .client
-            .start_session(session_title)
-            .await
-            .map_err(|e| format!("Failed to start session: {e}"))?;
+        info!("Starting interactive thinking session");
+
+        let template_name = template;
+        let (mut session, template) = match recover {
+            Some(session_id) => {
+                let record = self
+                    .client
+                    .load_auto_save(session_id)
+                    .map_err(|e| format!("Failed to recover session: {e}"))?;
+                let session = self
+                    .client
+                    .recover_session(session_id)
+                    .await
+                    .map_err(|e| format!("Failed to recover session: {e}"))?;
+                self.restore_on_server(&record).await;
+                (session, None)
+            }
+            None => {
+                let recoverable = self.client.list_recoverable_sessions();
+                if !recoverable.is_empty() {
+                    println!("💾 Saved sessions (continue one with `sessions resume <ID>`):");
+                    for record in recoverable.iter().take(RECENT_SESSIONS_SHOWN) {
+                        println!(
+                            "  {} - {} ({} thoughts, saved {})",
+                            record.session_id,
+                            record.title,
+                            record.checkpoint.thoughts.len(),
+                            record.saved_at.format("%Y-%m-%d %H:%M:%S")
+                        );
+                    }
+                    if recoverable.len() > RECENT_SESSIONS_SHOWN {
+                        println!(
+                            "  ... and {} more (see `sessions list`)",
+                            recoverable.len() - RECENT_SESSIONS_SHOWN
+                        );
+                    }
+                    println!();
+                }
+                self.start_session(title, "Interactive Session", template)
+                    .await?
+            }
+        };
+        let existing_thoughts = session.get_thoughts().await;
 
         println!("🎯 Interactive Thinking Session Started");
         println!("Session ID: {}", session.session_id);
         println!("Title: {}", session.title);
+        if let Some(template) = &template {
+            println!("Template: {}", template.name);
+        }
+        for thought in &existing_thoughts {
+            println!("  {}. {}", thought.thought_number, thought.thought);
+        }
         println!();
-        println!("Commands:");
-        println!("  think <content> - Add a thought");
-        println!("  revise <number> <content> - Revise a thought");
-        println!("  branch <from> <id> <content> - Create a branch");
-        println!("  progress - Show progress");
-        println!("  stats - Show statistics");
-        println!("  export [format] - Export session");
-        println!("  quit - End session");
+        println!("{}", Self::interactive_help());
         println!();
 
-        let mut thought_number = 1;
-        let mut total_thoughts = 5;
+        let mut transcript = record.map(|path| {
+            println!("⏺️  Recording to {}", path.display());
+            let mut transcript = Transcript::new(session.title.clone(), session.session_id.clone());
+            transcript.template = template_name.map(str::to_string);
+            (transcript, path)
+        });
+        let mut state = InteractiveState::new(&existing_thoughts, template.as_ref());
 
         loop {
             print!("💭 > ");
@@ -264,157 +624,519 @@ impl ClientApp {
                 continue;
             }
 
-            let parts: Vec<&str> = input.splitn(2, ' ').collect();
-            let command = parts[0].to_lowercase();
+            let (response, keep_going) = self.run_command(&mut session, &mut state, input).await;
+            println!("{response}");
+            if let Some((transcript, path)) = &mut transcript {
+                transcript.record(input, response);
+                if let Err(e) = transcript.save(path) {
+                    println!("⚠️  {e}");
+                }
+            }
+            if !keep_going {
+                break;
+            }
+        }
 
-            match command.as_str() {
-                "think" => {
-                    if parts.len() < 2 {
-                        println!("❌ Usage: think <content>");
-                        continue;
-                    }
-                    let content = parts[1];
+        Ok(())
+    }
 
-                    let thought = ultrafast_mcp_sequential_thinking::ThoughtData::new(
-                        content.to_string(),
-                        thought_number,
-                        total_thoughts,
+    /// Re-run the commands of a recorded transcript in a new session
+    ///
+    /// With `check`, fails if any response differs from the recorded one.
+    /// The new session's ID counts as the recorded one when comparing.
+    async fn replay_transcript(
+        &self,
+        path: &Path,
+        speed: f64,
+        format: &str,
+        check: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if format != "json" && format != "pretty" {
+            return Err(format!("Unsupported replay format: {format}").into());
+        }
+
+        let transcript = Transcript::load(path)?;
+        let (mut session, template) = self
+            .start_session(
+                Some(transcript.title.clone()),
+                "Interactive Session",
+                transcript.template.as_deref(),
+            )
+            .await?;
+        let existing_thoughts = session.get_thoughts().await;
+        let mut state = InteractiveState::new(&existing_thoughts, template.as_ref());
+
+        let delays = transcript.delays(speed);
+        let mut replayed = 0;
+        let mut differing = 0;
+        for (entry, delay_ms) in transcript.entries.iter().zip(delays) {
+            if delay_ms > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+            }
+
+            let (response, keep_going) = self
+                .run_command(&mut session, &mut state, &entry.command)
+                .await;
+            let matches =
+                response.replace(&session.session_id, &transcript.session_id) == entry.response;
+            replayed += 1;
+            if !matches {
+                differing += 1;
+            }
+
+            if format == "json" {
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "command": entry.command,
+                        "response": response,
+                        "recorded": entry.response,
+                        "matches": matches,
+                    })
+                );
+            } else {
+                println!("💭 > {}", entry.command);
+                println!("{response}");
+                if check && !matches {
+                    println!("⚠️  Recorded response was:");
+                    println!("{}", entry.response);
+                }
+            }
+            if !keep_going {
+                break;
+            }
+        }
+
+        // The replayed session is a throwaway copy
+        session.discard_auto_save();
+        if format == "pretty" {
+            println!("🏁 Replayed {replayed} command(s), {differing} response(s) differed");
+        }
+        if check && differing > 0 {
+            return Err(format!("{differing} response(s) differed from {}", path.display()).into());
+        }
+        Ok(())
+    }
+
+    /// Run an operation on the sessions saved in the auto-save directory
+    async fn sessions_command(
+        &self,
+        action: &SessionsCommand,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match action {
+            SessionsCommand::List { json } => {
+                let records = self.client.list_recoverable_sessions();
+                if *json {
+                    let summaries: Vec<_> = records
+                        .iter()
+                        .map(|record| {
+                            serde_json::json!({
+                                "sessionId": record.session_id,
+                                "title": record.title,
+                                "thoughtCount": record.checkpoint.thoughts.len(),
+                                "pendingSync": record.pending_sync.len(),
+                                "createdAt": record.created_at,
+                                "savedAt": record.saved_at,
+                            })
+                        })
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&summaries)?);
+                } else if records.is_empty() {
+                    println!(
+                        "No saved sessions in {}",
+                        self.config.thinking.auto_save_dir
                     );
-                    match self.client.add_thought(&session.session_id, thought).await {
-                        Ok(processed) => {
-                            println!("✅ Thought {} processed", processed.thought_number);
-                            thought_number += 1;
-                            if processed.total_thoughts > total_thoughts {
-                                total_thoughts = processed.total_thoughts;
-                            }
-                        }
-                        Err(e) => {
-                            println!("❌ Failed to process thought: {e}");
-                        }
+                } else {
+                    for record in &records {
+                        println!(
+                            "{}  {:>4} thoughts  {}  {}",
+                            record.session_id,
+                            record.checkpoint.thoughts.len(),
+                            record.saved_at.format("%Y-%m-%d %H:%M"),
+                            record.title
+                        );
                     }
                 }
-                "revise" => {
-                    if parts.len() < 3 {
-                        println!("❌ Usage: revise <number> <content>");
-                        continue;
-                    }
-                    let number = parts[1].parse::<u32>().unwrap_or(0);
-                    let content = parts[2];
+            }
+            SessionsCommand::Show { session_id, json } => {
+                let record = self.client.load_auto_save(session_id)?;
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&record)?);
+                    return Ok(());
+                }
+                println!("📋 {}", record.title);
+                println!("Session ID: {}", record.session_id);
+                println!(
+                    "Created: {}, saved: {}",
+                    record.created_at.format("%Y-%m-%d %H:%M:%S"),
+                    record.saved_at.format("%Y-%m-%d %H:%M:%S")
+                );
+                println!("Thoughts: {}", record.checkpoint.thoughts.len());
+                if !record.pending_sync.is_empty() {
+                    println!(
+                        "⏳ {} thought(s) not yet acknowledged by the server",
+                        record.pending_sync.len()
+                    );
+                }
+                for thought in &record.checkpoint.thoughts {
+                    println!("  {}. {}", thought.thought_number, thought.thought);
+                }
+            }
+            SessionsCommand::Resume { session_id, record } => {
+                self.interactive_session(None, None, Some(session_id), record.as_deref())
+                    .await?;
+            }
+        }
+        Ok(())
+    }
 
-                    if number == 0 || number >= thought_number {
-                        println!("❌ Invalid thought number");
-                        continue;
-                    }
+    /// Give the server the saved state of a recovered session
+    ///
+    /// A server started since the session was saved has none of its
+    /// thoughts and would reject the next one as out of sequence.
+    async fn restore_on_server(&self, record: &AutoSaveRecord) {
+        // The checkpoint holds thoughts the server never acknowledged, which
+        // `resync` sends instead
+        if !record.pending_sync.is_empty() {
+            println!(
+                "⏳ {} thought(s) were not acknowledged by the server; send them with `resync`",
+                record.pending_sync.len()
+            );
+            return;
+        }
+        if let Err(e) = self.client.resume_session(&record.checkpoint, None).await {
+            println!("⚠️  Could not restore the session on the server: {e}");
+        }
+    }
 
-                    let thought = ultrafast_mcp_sequential_thinking::ThoughtData::revision(
-                        content.to_string(),
-                        thought_number,
-                        number,
-                    );
-                    match self.client.add_thought(&session.session_id, thought).await {
-                        Ok(processed) => {
-                            println!("✅ Revision {} processed", processed.thought_number);
-                            thought_number += 1;
-                        }
-                        Err(e) => {
-                            println!("❌ Failed to process revision: {e}");
+    /// Commands of the interactive mode
+    fn interactive_help() -> String {
+        [
+            "Commands:",
+            "  think <content> - Add a thought",
+            "  revise <number> <content> - Revise a thought",
+            "  branch <from> <id> <content> - Create a branch",
+            "  progress - Show progress",
+            "  stats - Show statistics",
+            "  export [format] - Export session",
+            "  resync - Send thoughts the server has not acknowledged",
+            "  quit - End session, keeping it saved to resume later",
+            "  discard - End session and delete its saved copy",
+        ]
+        .join("\n")
+    }
+
+    /// Run one interactive command, returning its response and whether to go on
+    async fn run_command(
+        &self,
+        session: &mut SessionHandle,
+        state: &mut InteractiveState,
+        input: &str,
+    ) -> (String, bool) {
+        let parts: Vec<&str> = input.splitn(2, ' ').collect();
+        let command = parts[0].to_lowercase();
+        let mut out = Vec::new();
+
+        match command.as_str() {
+            "think" => {
+                if parts.len() < 2 {
+                    return ("❌ Usage: think <content>".to_string(), true);
+                }
+                let content = parts[1];
+
+                let thought = ultrafast_mcp_sequential_thinking::ThoughtData::new(
+                    content.to_string(),
+                    state.thought_number,
+                    state.total_thoughts,
+                );
+                match session.add_thought(thought, None).await {
+                    Ok(processed) => {
+                        out.push(format!("✅ Thought {} processed", processed.thought_number));
+                        state.thought_number += 1;
+                        if processed.total_thoughts > state.total_thoughts {
+                            state.total_thoughts = processed.total_thoughts;
                         }
                     }
-                }
-                "branch" => {
-                    if parts.len() < 4 {
-                        println!("❌ Usage: branch <from> <id> <content>");
-                        continue;
+                    Err(e) => {
+                        out.push(format!("❌ Failed to process thought: {e}"));
+                        Self::kept_for_resync(session, state, &mut out).await;
                     }
-                    let from = parts[1].parse::<u32>().unwrap_or(0);
-                    let branch_id = parts[2];
-                    let content = parts[3];
+                }
+            }
+            "revise" => {
+                if parts.len() < 3 {
+                    return ("❌ Usage: revise <number> <content>".to_string(), true);
+                }
+                let number = parts[1].parse::<u32>().unwrap_or(0);
+                let content = parts[2];
 
-                    if from == 0 || from >= thought_number {
-                        println!("❌ Invalid branch from number");
-                        continue;
-                    }
+                if number == 0 || number >= state.thought_number {
+                    return ("❌ Invalid thought number".to_string(), true);
+                }
 
-                    let thought = ultrafast_mcp_sequential_thinking::ThoughtData::branch(
-                        content.to_string(),
-                        thought_number,
-                        from,
-                        branch_id.to_string(),
-                    );
-                    match self.client.add_thought(&session.session_id, thought).await {
-                        Ok(processed) => {
-                            println!("✅ Branch {} processed", processed.thought_number);
-                            thought_number += 1;
-                        }
-                        Err(e) => {
-                            println!("❌ Failed to process branch: {e}");
-                        }
+                let thought = ultrafast_mcp_sequential_thinking::ThoughtData::revision(
+                    content.to_string(),
+                    state.thought_number,
+                    number,
+                );
+                match session.add_thought(thought, None).await {
+                    Ok(processed) => {
+                        out.push(format!(
+                            "✅ Revision {} processed",
+                            processed.thought_number
+                        ));
+                        state.thought_number += 1;
+                    }
+                    Err(e) => {
+                        out.push(format!("❌ Failed to process revision: {e}"));
+                        Self::kept_for_resync(session, state, &mut out).await;
                     }
                 }
-                "progress" => {
-                    if let Some(progress) = self.client.get_progress().await {
-                        println!(
-                            "📊 Progress: {}/{} ({:.1}%)",
-                            progress.completed_thoughts,
-                            progress.total_thoughts,
-                            progress.progress_percentage * 100.0
-                        );
-                    } else {
-                        println!("📊 No progress information available");
+            }
+            "branch" => {
+                if parts.len() < 4 {
+                    return ("❌ Usage: branch <from> <id> <content>".to_string(), true);
+                }
+                let from = parts[1].parse::<u32>().unwrap_or(0);
+                let branch_id = parts[2];
+                let content = parts[3];
+
+                if from == 0 || from >= state.thought_number {
+                    return ("❌ Invalid branch from number".to_string(), true);
+                }
+
+                let thought = ultrafast_mcp_sequential_thinking::ThoughtData::branch(
+                    content.to_string(),
+                    state.thought_number,
+                    from,
+                    branch_id.to_string(),
+                );
+                match session.add_thought(thought, None).await {
+                    Ok(processed) => {
+                        out.push(format!("✅ Branch {} processed", processed.thought_number));
+                        state.thought_number += 1;
+                    }
+                    Err(e) => {
+                        out.push(format!("❌ Failed to process branch: {e}"));
+                        Self::kept_for_resync(session, state, &mut out).await;
                     }
                 }
-                "stats" => {
-                    let stats = self.client.get_stats().await;
-                    println!("📈 Client Statistics:");
-                    println!("  Total requests: {}", stats.total_requests);
-                    println!("  Total thoughts: {}", stats.total_thoughts);
-                    println!("  Total sessions: {}", stats.total_sessions);
-                    println!(
-                        "  Average response time: {:.2}ms",
-                        stats.avg_response_time_ms
-                    );
-                    println!("  Error count: {}", stats.error_count);
-                    println!("  Retry count: {}", stats.retry_count);
-                }
-                "export" => {
-                    let format = if parts.len() > 1 { parts[1] } else { "json" };
-                    match self
-                        .client
-                        .export_session(&session.session_id, format)
-                        .await
-                    {
-                        Ok(content) => {
-                            println!("📄 Session exported in {format} format:");
-                            println!("{content}");
-                        }
-                        Err(e) => {
-                            println!("❌ Failed to export session: {e}");
-                        }
+            }
+            "progress" => {
+                if let Some(progress) = session.get_progress().await {
+                    out.push(format!(
+                        "📊 Progress: {}/{} ({:.1}%)",
+                        progress.completed_thoughts,
+                        progress.total_thoughts,
+                        progress.progress_percentage * 100.0
+                    ));
+                } else {
+                    out.push("📊 No progress information available".to_string());
+                }
+            }
+            "stats" => {
+                let stats = session.get_stats().await;
+                out.push("📈 Session Statistics:".to_string());
+                out.push(format!("  Total requests: {}", stats.total_requests));
+                out.push(format!("  Total thoughts: {}", stats.total_thoughts));
+                out.push(format!(
+                    "  Average response time: {:.2}ms",
+                    stats.avg_response_time_ms
+                ));
+                out.push(format!("  Error count: {}", stats.error_count));
+                out.push(format!("  Retry count: {}", stats.retry_count));
+            }
+            "export" => {
+                let format = if parts.len() > 1 { parts[1] } else { "json" };
+                match self
+                    .client
+                    .export_session(&session.session_id, format, None)
+                    .await
+                {
+                    Ok(content) => {
+                        out.push(format!("📄 Session exported in {format} format:"));
+                        out.push(content);
+                    }
+                    Err(e) => {
+                        out.push(format!("❌ Failed to export session: {e}"));
                     }
                 }
-                "quit" | "exit" => {
-                    println!("👋 Ending session...");
-                    break;
+            }
+            "resync" | "sync" => match session.resync(None).await {
+                Ok(synced) => out.push(format!("🔄 Resynced {synced} thought(s)")),
+                Err(e) => out.push(format!(
+                    "❌ Resync stopped with {} thought(s) pending: {e}",
+                    session.pending_sync().await.len()
+                )),
+            },
+            "quit" | "exit" => {
+                // Auto-saved sessions are kept so they can be continued later
+                if session.is_auto_saving() {
+                    session.stop_auto_save();
+                    match session.save_now().await {
+                        Ok(_) => out.push(format!(
+                            "💾 Session saved; continue it with `sessions resume {}`",
+                            session.session_id
+                        )),
+                        Err(e) => out.push(format!("❌ Failed to save session: {e}")),
+                    }
                 }
-                "help" => {
-                    println!("Commands:");
-                    println!("  think <content> - Add a thought");
-                    println!("  revise <number> <content> - Revise a thought");
-                    println!("  branch <from> <id> <content> - Create a branch");
-                    println!("  progress - Show progress");
-                    println!("  stats - Show statistics");
-                    println!("  export [format] - Export session");
-                    println!("  quit - End session");
+                out.push("👋 Ending session...".to_string());
+                return (out.join("\n"), false);
+            }
+            "discard" => {
+                session.discard_auto_save();
+                return ("🗑️  Session discarded".to_string(), false);
+            }
+            "help" => out.push(Self::interactive_help()),
+            _ => {
+                out.push(format!(
+                    "❌ Unknown command: {command}. Type 'help' for available commands."
+                ));
+            }
+        }
+
+        (out.join("\n"), true)
+    }
+
+    /// Count a thought the server rejected if it was kept locally for a resync
+    async fn kept_for_resync(
+        session: &SessionHandle,
+        state: &mut InteractiveState,
+        out: &mut Vec<String>,
+    ) {
+        let queued = session
+            .pending_sync()
+            .await
+            .last()
+            .is_some_and(|t| t.thought_number == state.thought_number);
+        if queued {
+            out.push("⏳ Kept locally; run 'resync' once the server is reachable".to_string());
+            state.thought_number += 1;
+        }
+    }
+
+    /// Process thoughts read from stdin, writing one JSON line per thought
+    ///
+    /// Thoughts without a number follow the ones the session already has.
+    /// Text thoughts need another thought while `total` is not reached or
+    /// `more_needed` is set. Failed thoughts are reported and skipped.
+    async fn think_from_stdin(
+        &self,
+        total: u32,
+        more_needed: bool,
+        template: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        use tokio::io::AsyncBufReadExt;
+
+        let (session, _) = self.start_session(None, "Stdin Session", template).await?;
+        let spinner = self.progress().spinner("Submitting thoughts");
+        let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+        let mut paragraph: Vec<String> = Vec::new();
+        let (mut line_number, mut start_line, mut failed) = (0, 0, 0);
+
+        loop {
+            let line = lines.next_line().await?;
+            line_number += 1;
+            let input = match &line {
+                None => None,
+                Some(text) if text.trim().is_empty() => None,
+                Some(text) if paragraph.is_empty() && text.trim_start().starts_with('{') => {
+                    Some((line_number, StdinThought::Json(text.clone())))
                 }
-                _ => {
-                    println!("❌ Unknown command: {command}. Type 'help' for available commands.");
+                Some(text) => {
+                    if paragraph.is_empty() {
+                        start_line = line_number;
+                    }
+                    paragraph.push(text.clone());
+                    continue;
                 }
+            };
+            // A blank line or the end of input closes the paragraph being read
+            let text = (!paragraph.is_empty())
+                .then(|| (start_line, StdinThought::Text(paragraph.join("\n"))));
+            paragraph.clear();
+
+            for (start, thought) in text.into_iter().chain(input) {
+                let result = self
+                    .stdin_thought(&session, thought, total, more_needed)
+                    .await;
+                let output = match result {
+                    Ok(response) => serde_json::json!({
+                        "line": start,
+                        "ok": true,
+                        "sessionId": session.session_id,
+                        "response": response,
+                    }),
+                    Err(e) => {
+                        failed += 1;
+                        serde_json::json!({
+                            "line": start,
+                            "ok": false,
+                            "sessionId": session.session_id,
+                            "error": e.to_string(),
+                            "code": e.error_code(),
+                            "advice": e.recovery_advice().to_string(),
+                        })
+                    }
+                };
+                spinner.suspend(|| println!("{output}"));
+                spinner.inc(1);
+                spinner.set_message(format!(
+                    "Submitted {} thoughts ({failed} failed)",
+                    spinner.position()
+                ));
+            }
+            if line.is_none() {
+                break;
             }
         }
+        spinner.finish_and_clear();
 
+        if failed > 0 {
+            return Err(format!("{failed} thought(s) failed").into());
+        }
         Ok(())
     }
 
+    /// Add one thought read from stdin to the session
+    async fn stdin_thought(
+        &self,
+        session: &SessionHandle,
+        input: StdinThought,
+        total: u32,
+        more_needed: bool,
+    ) -> SequentialThinkingResult<ThoughtResponse> {
+        let next = session.get_thoughts().await.len() as u32 + 1;
+        let thought = match input {
+            StdinThought::Text(text) => ultrafast_mcp_sequential_thinking::ThoughtData {
+                thought: text,
+                thought_number: next,
+                total_thoughts: total.max(next),
+                next_thought_needed: more_needed || next < total,
+                ..Default::default()
+            },
+            StdinThought::Json(line) => {
+                let mut args: serde_json::Value = serde_json::from_str(&line).map_err(|e| {
+                    SequentialThinkingError::invalid_thought_data(format!("Invalid JSON: {e}"))
+                })?;
+                if let Some(fields) = args.as_object_mut() {
+                    let number = fields
+                        .entry("thoughtNumber")
+                        .or_insert_with(|| next.into())
+                        .as_u64()
+                        .unwrap_or(0);
+                    fields
+                        .entry("totalThoughts")
+                        .or_insert_with(|| u64::from(total).max(number).into());
+                }
+                ultrafast_mcp_sequential_thinking::ThoughtData::from_tool_arguments(&args)
+                    .map_err(SequentialThinkingError::invalid_thought_data)?
+            }
+        };
+        session.add_thought(thought, None).await
+    }
+
     /// Process a single thought
     async fn process_thought(
         &self,
@@ -422,35 +1144,38 @@ impl ClientApp {
         number: u32,
         total: u32,
         more_needed: bool,
+        template: Option<&str>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let session_title = "Single Thought Session".to_string();
-        let session = self
-            .client
-            .start_session(session_title)
-            .await
-            .map_err(|e| format!("Failed to start session: {e}"))?;
+        let (session, template) = self
+            .start_session(None, "Single Thought Session", template)
+            .await?;
+
+        // Seeded thoughts come first, so number this one after them
+        let seeded = session.get_thoughts().await.len() as u32;
+        let total = template.map_or(total, |t| total.max(t.total_thoughts));
 
         let thought_data = ultrafast_mcp_sequential_thinking::ThoughtData {
-            thought,
-            thought_number: number,
+            thought: thought.clone(),
+            thought_number: number.max(seeded + 1),
             total_thoughts: total,
             next_thought_needed: more_needed,
             ..Default::default()
         };
 
-        match self
-            .client
-            .add_thought(&session.session_id, thought_data)
-            .await
-        {
+        match session.add_thought(thought_data, None).await {
             Ok(processed) => {
                 println!("✅ Thought processed successfully");
                 println!(
                     "Thought Number: {}/{}",
                     processed.thought_number, processed.total_thoughts
                 );
-                println!("Content: {}", processed.thought);
+                println!("Content: {thought}");
                 println!("More thoughts needed: {}", processed.next_thought_needed);
+                println!(
+                    "Server progress: {:.1}% ({} thoughts in history)",
+                    processed.progress.progress_percentage * 100.0,
+                    processed.thought_history_length
+                );
             }
             Err(e) => {
                 println!("❌ Failed to process thought: {e}");
@@ -467,7 +1192,18 @@ impl ClientApp {
         format: &str,
         output: Option<PathBuf>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        match self.client.export_session(session_id, format).await {
+        // Older servers lack get_server_info, so only stop on a definite "no"
+        if let Ok(false) = self.client.supports_export_format(format, None).await {
+            println!("❌ Server does not support {format} export");
+            return Ok(());
+        }
+
+        let spinner = self
+            .progress()
+            .spinner(format!("Exporting session {session_id} as {format}"));
+        let exported = self.client.export_session(session_id, format, None).await;
+        spinner.finish_and_clear();
+        match exported {
             Ok(content) => {
                 if let Some(output_path) = output {
                     std::fs::write(&output_path, content)?;
@@ -487,7 +1223,7 @@ impl ClientApp {
 
     /// Analyze a session
     async fn analyze_session(&self, session_id: &str) -> Result<(), Box<dyn std::error::Error>> {
-        match self.client.analyze_session(session_id).await {
+        match self.client.analyze_session(session_id, None).await {
             Ok(analysis) => {
                 println!("📊 Session Analysis:");
                 println!("{}", serde_json::to_string_pretty(&analysis)?);
@@ -500,9 +1236,342 @@ impl ClientApp {
         Ok(())
     }
 
+    /// Merge sessions on the server
+    async fn merge_sessions(
+        &self,
+        session_ids: &[String],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match self.client.merge_sessions(session_ids, None).await {
+            Ok(result) => {
+                println!("🔀 Sessions merged:");
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+            Err(e) => {
+                println!("❌ Failed to merge sessions: {e}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Print a compact summary of a session
+    async fn session_summary(&self, session_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let update = self
+            .client
+            .poll_session_updates(session_id, 0, None)
+            .await
+            .map_err(|e| format!("Failed to fetch session: {e}"))?;
+        let thoughts = update.new_thoughts;
+
+        if thoughts.is_empty() {
+            println!("📋 Session {session_id} has no thoughts yet");
+            return Ok(());
+        }
+
+        let stats = ThinkingStats {
+            total_thoughts: thoughts.len() as u64,
+            total_revisions: thoughts.iter().filter(|t| t.is_revision()).count() as u64,
+            total_branches: thoughts.iter().filter(|t| t.is_branch()).count() as u64,
+            total_thought_length: thoughts.iter().map(|t| t.thought.len() as u64).sum(),
+            ..ThinkingStats::default()
+        };
+        let progress = update
+            .progress
+            .unwrap_or_else(|| ThinkingProgress::new(1, 1));
+        let analytics =
+            AnalyticsEngine::new().analyze_session(session_id, "", &thoughts, &stats, &progress);
+
+        let lengths: Vec<usize> = thoughts.iter().map(|t| t.thought.chars().count()).collect();
+        let branch_ids: std::collections::HashSet<&str> =
+            thoughts.iter().filter_map(|t| t.get_branch_id()).collect();
+        let hotspots = utils::revision_hotspots(&thoughts);
+        let quality = &analytics.quality_metrics;
+
+        println!("📋 Session Summary: {session_id}");
+        println!(
+            "Thoughts:   {} ({} revisions, {} branch thoughts, {} branches)",
+            stats.total_thoughts,
+            stats.total_revisions,
+            stats.total_branches,
+            branch_ids.len()
+        );
+        println!(
+            "Lengths:    {} ({}-{} chars)",
+            utils::sparkline(&lengths),
+            lengths.iter().min().unwrap_or(&0),
+            lengths.iter().max().unwrap_or(&0)
+        );
+        if hotspots.is_empty() {
+            println!("Hotspots:   none");
+        } else {
+            let hotspots: Vec<String> = hotspots
+                .iter()
+                .take(3)
+                .map(|(thought, count)| format!("thought {thought} ({count}x)"))
+                .collect();
+            println!("Hotspots:   {}", hotspots.join(", "));
+        }
+        println!(
+            "Quality:    overall {:.2} | coherence {:.2} | flow {:.2} | completeness {:.2} | clarity {:.2}",
+            quality.overall_quality_score,
+            quality.coherence_score,
+            quality.logical_flow_score,
+            quality.completeness_score,
+            quality.clarity_score
+        );
+        println!(
+            "Progress:   {}/{} ({:.1}%)",
+            progress.completed_thoughts,
+            progress.total_thoughts,
+            progress.progress_percentage * 100.0
+        );
+        match thoughts.iter().rev().find(|t| !t.next_thought_needed) {
+            Some(conclusion) => println!("Conclusion: {}", conclusion.thought),
+            None => println!("Conclusion: (in progress)"),
+        }
+
+        Ok(())
+    }
+
+    /// Watch a session until interrupted
+    async fn watch_session(
+        &self,
+        session_id: &str,
+        format: &str,
+        interval: u64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if format != "json" && format != "pretty" {
+            return Err(format!("Unsupported watch format: {format}").into());
+        }
+
+        if format == "pretty" {
+            println!("👀 Watching session {session_id} (Ctrl+C to stop)");
+        }
+
+        let mut seen = 0;
+        let mut ticker = tokio::time::interval(std::time::Duration::from_millis(interval.max(100)));
+        // One listener for the whole watch, so a Ctrl+C during a poll is not lost
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
+        loop {
+            tokio::select! {
+                _ = &mut ctrl_c => break,
+                _ = ticker.tick() => {}
+            }
+
+            let update = match self
+                .client
+                .poll_session_updates(session_id, seen, None)
+                .await
+            {
+                Ok(update) => update,
+                Err(e) => {
+                    if format == "json" {
+                        println!(
+                            "{}",
+                            serde_json::json!({ "event": "error", "message": e.to_string() })
+                        );
+                    } else {
+                        println!("❌ Failed to poll session: {e}");
+                    }
+                    continue;
+                }
+            };
+
+            if update.new_thoughts.is_empty() {
+                continue;
+            }
+            seen = update.thought_count;
+
+            for thought in &update.new_thoughts {
+                Self::print_watched_thought(thought, format);
+            }
+
+            if let Some(progress) = update.progress {
+                if format == "json" {
+                    println!(
+                        "{}",
+                        serde_json::json!({ "event": "progress", "progress": progress })
+                    );
+                } else {
+                    println!(
+                        "📊 Progress: {}/{} ({:.1}%)",
+                        progress.completed_thoughts,
+                        progress.total_thoughts,
+                        progress.progress_percentage * 100.0
+                    );
+                }
+            }
+        }
+
+        if format == "pretty" {
+            println!("👋 Stopped watching session {session_id}");
+        }
+
+        Ok(())
+    }
+
+    /// Keep a file updated with the export of a session until Ctrl+C
+    ///
+    /// The file is only rewritten when the export changed, replacing it in
+    /// one rename so editors never see a partial file.
+    async fn watch_to_file(
+        &self,
+        session_id: &str,
+        format: &str,
+        output: &Path,
+        interval: u64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let format = match format {
+            "pretty" if output.extension().is_some_and(|ext| ext == "json") => "json",
+            "pretty" => "markdown",
+            format => format,
+        };
+        // Older servers lack get_server_info, so only stop on a definite "no"
+        if let Ok(false) = self.client.supports_export_format(format, None).await {
+            return Err(format!("Server does not support {format} export").into());
+        }
+
+        println!(
+            "👀 Writing session {session_id} to {} as {format} (Ctrl+C to stop)",
+            output.display()
+        );
+
+        let mut written: Option<String> = None;
+        let mut ticker = tokio::time::interval(std::time::Duration::from_millis(interval.max(100)));
+        // One listener for the whole watch, so a Ctrl+C during an export is not lost
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
+        loop {
+            tokio::select! {
+                _ = &mut ctrl_c => break,
+                _ = ticker.tick() => {}
+            }
+
+            let export = match self.client.export_session(session_id, format, None).await {
+                Ok(export) => export,
+                Err(e) => {
+                    println!("❌ Failed to export session: {e}");
+                    continue;
+                }
+            };
+            let fingerprint = export_fingerprint(format, &export);
+            if written.as_ref() == Some(&fingerprint) {
+                continue;
+            }
+
+            let tmp_path = output.with_extension("tmp");
+            let replaced = std::fs::write(&tmp_path, &export)
+                .and_then(|()| std::fs::rename(&tmp_path, output));
+            match replaced {
+                Ok(()) => {
+                    written = Some(fingerprint);
+                    println!(
+                        "📝 Updated {} at {}",
+                        output.display(),
+                        chrono::Local::now().format("%H:%M:%S")
+                    );
+                }
+                Err(e) => println!("❌ Failed to write {}: {e}", output.display()),
+            }
+        }
+
+        println!("👋 Stopped watching session {session_id}");
+        Ok(())
+    }
+
+    /// Print a single watched thought
+    /// Replay a session's timeline, sleeping between events
+    async fn replay_session(
+        &self,
+        session_id: &str,
+        speed: f64,
+        format: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if format != "json" && format != "pretty" {
+            return Err(format!("Unsupported replay format: {format}").into());
+        }
+
+        let events = self.client.replay_session(session_id, None).await?;
+        let delays = replay_delays(&events, speed);
+
+        for (event, delay_ms) in events.iter().zip(delays) {
+            if delay_ms > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+            }
+
+            if format == "json" {
+                println!("{}", serde_json::to_string(event)?);
+                continue;
+            }
+
+            match &event.kind {
+                ReplayEventKind::ThoughtAdded { thought }
+                | ReplayEventKind::ThoughtRevised { thought, .. }
+                | ReplayEventKind::Branched { thought, .. } => {
+                    Self::print_watched_thought(thought, format)
+                }
+                ReplayEventKind::ThoughtEdited { edit } => {
+                    println!("✏️  Edited thought {}", edit.thought_number);
+                    println!("   {}", edit.new_text);
+                }
+            }
+        }
+
+        if format == "pretty" {
+            println!("🏁 Replayed {} event(s)", events.len());
+        }
+
+        Ok(())
+    }
+
+    fn print_watched_thought(
+        thought: &ultrafast_mcp_sequential_thinking::ThoughtData,
+        format: &str,
+    ) {
+        let event = if thought.is_revision() {
+            "revision"
+        } else if thought.is_branch() {
+            "branch"
+        } else {
+            "thought"
+        };
+
+        if format == "json" {
+            println!(
+                "{}",
+                serde_json::json!({ "event": event, "thought": thought })
+            );
+            return;
+        }
+
+        match event {
+            "revision" => println!(
+                "🔄 Revision {}/{} (revising thought {})",
+                thought.thought_number,
+                thought.total_thoughts,
+                thought.revises_thought.unwrap_or(0)
+            ),
+            "branch" => println!(
+                "🌿 Branch {}/{} (from thought {}, ID: {})",
+                thought.thought_number,
+                thought.total_thoughts,
+                thought.branch_from_thought.unwrap_or(0),
+                thought.branch_id.as_deref().unwrap_or("unknown")
+            ),
+            _ => println!(
+                "💭 Thought {}/{}",
+                thought.thought_number, thought.total_thoughts
+            ),
+        }
+        println!("   {}", thought.thought);
+    }
+
     /// List available tools
     async fn list_tools(&self) -> Result<(), Box<dyn std::error::Error>> {
-        match self.client.list_tools().await {
+        match self.client.list_tools(None).await {
             Ok(tools) => {
                 println!("🔧 Available Tools:");
                 for tool in tools {
@@ -517,6 +1586,74 @@ impl ClientApp {
         Ok(())
     }
 
+    /// Run a load test against the server
+    async fn load_test(
+        &self,
+        config: LoadTestConfig,
+        json: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        config.validate().map_err(|errors| errors.join("; "))?;
+
+        // A dedicated client, so sessions are neither auto-saved nor logged and
+        // a rejected thought does not hold back the rest of its session
+        let mut thinking = self.config.thinking.clone();
+        thinking.auto_save_interval = 0;
+        thinking.sync_mode = SyncMode::Rollback;
+        let client = SequentialThinkingClient::with_config(&self.config.server_url, thinking)
+            .await
+            .map_err(|e| format!("Failed to create client: {e}"))?
+            .with_api_key(self.config.api_key.clone())
+            .with_request_timeout(std::time::Duration::from_secs(self.config.timeout_seconds))
+            .with_renderer(ThoughtRenderer::new(VisualizationStyle::None));
+
+        if !json {
+            println!(
+                "🚀 Load testing {} with {} sessions x {} thoughts ({} concurrent)...",
+                self.config.server_url, config.sessions, config.thoughts, config.concurrency
+            );
+        }
+        let bar = self.progress().bar(
+            config.sessions as u64 * u64::from(config.thoughts),
+            "Sending thoughts",
+        );
+        let report = loadtest::run_with_progress(&client, config, {
+            let bar = bar.clone();
+            move |_| bar.inc(1)
+        })
+        .await;
+        bar.finish_and_clear();
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+        println!();
+        println!("Duration:    {:.0}ms", report.duration_ms);
+        println!("Requests:    {}", report.requests);
+        println!(
+            "Succeeded:   {} ({:.1}% errors)",
+            report.succeeded,
+            report.error_rate * 100.0
+        );
+        println!("Throughput:  {:.1} thoughts/s", report.throughput);
+        let latency = &report.latency;
+        println!(
+            "Latency:     min {:.1}ms, mean {:.1}ms, p50 {:.1}ms, p90 {:.1}ms, p99 {:.1}ms, max {:.1}ms",
+            latency.min, latency.mean, latency.p50, latency.p90, latency.p99, latency.max
+        );
+        if report.failed_sessions > 0 {
+            println!(
+                "⚠️  {} sessions could not be started",
+                report.failed_sessions
+            );
+        }
+        for (code, count) in &report.errors {
+            println!("  {code}: {count}");
+        }
+
+        Ok(())
+    }
+
     /// Show client information
     fn show_info(&self) {
         println!("UltraFast MCP Sequential Thinking Client");
@@ -538,6 +1675,14 @@ impl ClientApp {
             "  Auto-save interval: {} seconds",
             self.config.thinking.auto_save_interval
         );
+        println!(
+            "  Auto-save directory: {}",
+            self.config.thinking.auto_save_dir
+        );
+        println!(
+            "  Author: {}",
+            self.config.thinking.author.as_deref().unwrap_or("(none)")
+        );
         println!(
             "  Max retry attempts: {}",
             self.config.thinking.max_retry_attempts
@@ -552,7 +1697,7 @@ impl ClientApp {
     async fn test_connection(&self) -> Result<(), Box<dyn std::error::Error>> {
         println!("🔍 Testing connection to server...");
 
-        match self.client.list_tools().await {
+        match self.client.list_tools(None).await {
             Ok(tools) => {
                 println!("✅ Connection successful!");
                 println!("Found {} tools:", tools.len());
@@ -595,27 +1740,54 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Handle subcommands first
     if let Some(ref command) = args.command {
         match command {
-            Commands::Interactive { title } => {
+            Commands::Interactive {
+                title,
+                template,
+                recover,
+                record,
+            } => {
                 // Initialize logging
                 ClientApp::init_logging(&args)?;
 
                 // Create client and start interactive session
                 let app = ClientApp::new(&args).await?;
-                app.interactive_session(title.clone()).await
+                app.interactive_session(
+                    title.clone(),
+                    template.as_deref(),
+                    recover.as_deref(),
+                    record.as_deref(),
+                )
+                .await
             }
             Commands::Think {
                 thought,
+                stdin,
                 number,
                 total,
                 more_needed,
+                template,
             } => {
                 // Initialize logging
                 ClientApp::init_logging(&args)?;
 
-                // Create client and process thought
+                // Create client and process the thought or the stream on stdin
                 let app = ClientApp::new(&args).await?;
-                app.process_thought(thought.to_string(), *number, *total, *more_needed)
-                    .await
+                match thought {
+                    Some(thought) if !stdin => {
+                        app.process_thought(
+                            thought.to_string(),
+                            *number,
+                            *total,
+                            *more_needed,
+                            template.as_deref(),
+                        )
+                        .await
+                    }
+                    _ => {
+                        app.think_from_stdin(*total, *more_needed, template.as_deref())
+                            .await
+                    }
+                }
             }
             Commands::Export {
                 session_id,
@@ -637,6 +1809,86 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 let app = ClientApp::new(&args).await?;
                 app.analyze_session(session_id).await
             }
+            Commands::Merge { session_ids } => {
+                // Initialize logging
+                ClientApp::init_logging(&args)?;
+
+                // Create client and merge sessions
+                let app = ClientApp::new(&args).await?;
+                app.merge_sessions(session_ids).await
+            }
+            Commands::Summary { session_id } => {
+                // Initialize logging
+                ClientApp::init_logging(&args)?;
+
+                // Create client and summarize session
+                let app = ClientApp::new(&args).await?;
+                app.session_summary(session_id).await
+            }
+            Commands::Watch {
+                session_id,
+                format,
+                output,
+                interval,
+            } => {
+                // Initialize logging
+                ClientApp::init_logging(&args)?;
+
+                // Create client and watch session
+                let app = ClientApp::new(&args).await?;
+                match output {
+                    Some(output) => {
+                        app.watch_to_file(session_id, format, output, *interval)
+                            .await
+                    }
+                    None => app.watch_session(session_id, format, *interval).await,
+                }
+            }
+            Commands::Replay {
+                session_id,
+                speed,
+                format,
+                check,
+            } => {
+                // Initialize logging
+                ClientApp::init_logging(&args)?;
+
+                // Create client and replay the transcript or session
+                let app = ClientApp::new(&args).await?;
+                let transcript = Path::new(session_id);
+                if transcript.is_file() {
+                    app.replay_transcript(transcript, *speed, format, *check)
+                        .await
+                } else {
+                    app.replay_session(session_id, *speed, format).await
+                }
+            }
+            Commands::Sessions { action } => {
+                // Initialize logging
+                ClientApp::init_logging(&args)?;
+
+                // Create client and run the session operation
+                let app = ClientApp::new(&args).await?;
+                app.sessions_command(action).await
+            }
+            Commands::Loadtest {
+                sessions,
+                thoughts,
+                concurrency,
+                json,
+            } => {
+                // Initialize logging
+                ClientApp::init_logging(&args)?;
+
+                // Create client and run the load test
+                let app = ClientApp::new(&args).await?;
+                let config = LoadTestConfig {
+                    sessions: *sessions,
+                    thoughts: *thoughts,
+                    concurrency: *concurrency,
+                };
+                app.load_test(config, *json).await
+            }
             Commands::Tools => {
                 // Initialize logging
                 ClientApp::init_logging(&args)?;
@@ -10,9 +10,27 @@ use std::path::PathBuf;
 use tracing::{error, info};
 use tracing_subscriber::EnvFilter;
 
-use ultrafast_mcp::{ServerCapabilities, ServerInfo, ToolsCapability};
+use ultrafast_mcp::{ServerCapabilities, ServerInfo, ToolsCapability, UltraFastServer};
 use ultrafast_mcp_sequential_thinking::{
-    default_server_config, SequentialThinkingServer, ServerConfig,
+    analytics::{
+        anomaly,
+        cost::CostReport,
+        dashboard::FileMetricsStore,
+        exporter::{AnalyticsPusher, HttpAnalyticsExporter},
+    },
+    config::{self, apply_env_overrides, ClientConfig, ConfigFile, SERVER_ENV_PREFIX},
+    default_server_config,
+    export::{dedup::INDEX_FILENAME, ExportConfig},
+    redaction::Redactor,
+    session::{utils as session_utils, SessionFilter, SessionManagerConfig, SessionStatus},
+    AnalyticsEngine, ExportEngine, ExportFormat, ExportOptions, SequentialThinkingServer,
+    ServerConfig, SessionManager, TransportConfig, VisualizationStyle,
+};
+
+#[cfg(feature = "http-transport")]
+use ultrafast_mcp_sequential_thinking::{
+    thinking::cors::{gateway, CorsPolicy},
+    AdminClient,
 };
 
 /// Command-line arguments for the sequential thinking server
@@ -28,13 +46,21 @@ struct Args {
     #[arg(short, long, value_name = "FILE")]
     config: Option<PathBuf>,
 
-    /// Transport type (stdio, http)
-    #[arg(short, long, default_value = "stdio")]
-    transport: String,
+    /// Configuration profile to merge over the base configuration
+    #[arg(long, value_name = "NAME", env = "SEQUENTIAL_THINKING_PROFILE")]
+    profile: Option<String>,
+
+    /// Transport (stdio, http, or a URL such as http://127.0.0.1:8080)
+    #[arg(short, long)]
+    transport: Option<TransportConfig>,
+
+    /// Address to bind for HTTP transport
+    #[arg(long)]
+    host: Option<String>,
 
     /// Port for HTTP transport
-    #[arg(short, long, default_value = "8080")]
-    port: u16,
+    #[arg(short, long)]
+    port: Option<u16>,
 
     /// Server name
     #[arg(long)]
@@ -44,6 +70,10 @@ struct Args {
     #[arg(long)]
     disable_logging: bool,
 
+    /// Thought log style (boxed, compact, json, none)
+    #[arg(long, value_name = "STYLE")]
+    thought_style: Option<VisualizationStyle>,
+
     /// Log level
     #[arg(long, default_value = "info")]
     log_level: String,
@@ -72,6 +102,10 @@ struct Args {
     #[arg(long)]
     session_timeout: Option<u64>,
 
+    /// Maximum time to process a single thought, in seconds
+    #[arg(long)]
+    thought_timeout: Option<u64>,
+
     /// Rate limiting enabled
     #[arg(long)]
     rate_limiting: bool,
@@ -88,11 +122,17 @@ struct Args {
 /// Available subcommands
 #[derive(Subcommand)]
 enum Commands {
-    /// Validate configuration
+    /// Validate configuration, rejecting unknown keys and out-of-range values
     Validate {
         /// Configuration file to validate
         config: PathBuf,
     },
+    /// Inspect the configuration format
+    Config {
+        /// Operation to perform
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
     /// Generate default configuration
     Generate {
         /// Output file path
@@ -102,6 +142,188 @@ enum Commands {
     Info,
     /// Run health check
     Health,
+    /// Administer persisted sessions without starting the server
+    Sessions {
+        /// Session persistence directory
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+        /// Operation to perform
+        #[command(subcommand)]
+        action: SessionsCommand,
+    },
+    /// Inspect a running server through its HTTP admin endpoints
+    #[cfg(feature = "http-transport")]
+    Admin {
+        /// Server URL (defaults to the configured HTTP address)
+        #[arg(long, value_name = "URL")]
+        url: Option<String>,
+        /// API key with the admin role
+        #[arg(long, env = "SEQUENTIAL_THINKING_ADMIN_KEY", hide_env_values = true)]
+        api_key: Option<String>,
+        /// Operation to perform
+        #[command(subcommand)]
+        action: AdminCommand,
+    },
+}
+
+/// Configuration format operations
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print the JSON Schema of the configuration
+    Schema {
+        /// Section to describe (file, server, client)
+        #[arg(long, default_value = "file")]
+        section: String,
+    },
+}
+
+/// Operations on a running server
+#[cfg(feature = "http-transport")]
+#[derive(Subcommand)]
+enum AdminCommand {
+    /// List the sessions held by the server
+    ListSessions {
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// End a session, discarding its thoughts
+    KillSession {
+        /// Session ID
+        session_id: String,
+    },
+    /// Export a session as JSON or Markdown
+    ExportSession {
+        /// Session ID
+        session_id: String,
+        /// Export format (json, markdown)
+        #[arg(short, long, default_value = "json")]
+        format: String,
+        /// Output file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Show the server statistics
+    Stats {
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show daily aggregates of completed sessions
+    Analytics {
+        /// Number of days to show, today included
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Bulk session maintenance operations
+#[derive(Subcommand)]
+enum SessionsCommand {
+    /// List stored sessions, oldest first
+    List {
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+        /// Only sessions with this status
+        #[arg(long)]
+        status: Option<String>,
+        /// Only sessions carrying this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only sessions with this priority (low, normal, high, critical)
+        #[arg(long)]
+        priority: Option<String>,
+        /// Only sessions whose title contains this text
+        #[arg(long)]
+        title: Option<String>,
+        /// Only sessions created at or after this RFC 3339 time
+        #[arg(long, value_name = "TIME")]
+        created_after: Option<String>,
+        /// Only sessions created before this RFC 3339 time
+        #[arg(long, value_name = "TIME")]
+        created_before: Option<String>,
+        /// Matching sessions to skip
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+        /// Maximum number of sessions to list
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Export stored sessions
+    Export {
+        /// Session ID to export
+        session_id: Option<String>,
+        /// Export every stored session
+        #[arg(long)]
+        all: bool,
+        /// Export format
+        #[arg(short, long, default_value = "json")]
+        format: String,
+        /// Output directory
+        #[arg(short, long, default_value = "./exports")]
+        output: PathBuf,
+        /// Upload to object storage instead (s3://bucket/prefix); credentials,
+        /// region and endpoint are read from the AWS_* environment variables
+        #[arg(long, value_name = "URI")]
+        destination: Option<String>,
+        /// Write exports even when the session is unchanged since its last export
+        #[arg(long)]
+        force: bool,
+    },
+    /// Export per-session analytics metrics (csv, jsonl or json)
+    Analytics {
+        /// Export format
+        #[arg(short, long, default_value = "csv")]
+        format: String,
+        /// Output directory
+        #[arg(short, long, default_value = "./exports")]
+        output: PathBuf,
+        /// Upload to object storage instead (s3://bucket/prefix); credentials,
+        /// region and endpoint are read from the AWS_* environment variables
+        #[arg(long, value_name = "URI")]
+        destination: Option<String>,
+    },
+    /// Compare the metrics of two stored sessions, e.g. to A/B test prompts
+    Compare {
+        /// First session ID
+        session_a: String,
+        /// Second session ID
+        session_b: String,
+        /// Print the full comparison as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Estimate the cost of stored sessions by model, tag, author and day
+    Cost {
+        /// Print the full report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Flag stored sessions that are unusual compared to the others
+    Anomalies {
+        /// Print the full reports as JSON
+        #[arg(long)]
+        json: bool,
+        /// Post reports with anomalies to the configured webhook
+        #[arg(long)]
+        alert: bool,
+    },
+    /// Archive sessions not modified within the given age (e.g. 30d, 12h)
+    Archive {
+        /// Minimum age of sessions to archive
+        #[arg(long, value_name = "AGE")]
+        older_than: String,
+    },
+    /// Delete sessions with the given status
+    Delete {
+        /// Session status (active, paused, completed, cancelled, expired)
+        #[arg(long)]
+        status: String,
+    },
 }
 
 /// Main server configuration
@@ -116,14 +338,19 @@ impl ServerApp {
     /// Create a new server application
     fn new(args: &Args) -> Result<Self, Box<dyn std::error::Error>> {
         // Load configuration
-        let mut config = if let Some(config_path) = &args.config {
-            Self::load_config_from_file(config_path)?
-        } else {
-            default_server_config()
+        let mut config = match (&args.config, &args.profile) {
+            (Some(config_path), profile) => {
+                Self::load_config_from_file(config_path, profile.as_deref())?
+            }
+            (None, Some(profile)) => {
+                return Err(format!("Profile '{profile}' requires a configuration file").into())
+            }
+            (None, None) => default_server_config(),
         };
 
-        // Override configuration with command-line arguments
-        Self::override_config(&mut config, args);
+        // Override configuration with environment variables, then command-line arguments
+        config = apply_env_overrides(&config, SERVER_ENV_PREFIX, std::env::vars())?;
+        Self::override_config(&mut config, args)?;
 
         // Create server
         let server = SequentialThinkingServer::with_config(
@@ -149,42 +376,66 @@ impl ServerApp {
                 ..Default::default()
             },
             args.disable_logging,
-        );
+        )
+        .with_thinking_config(config.thinking.clone())
+        .with_security_config(config.security.clone())
+        .with_pricing_config(config.analytics.pricing.clone())
+        .with_readability_config(config.analytics.readability.clone())
+        .with_integrations_config(config.integrations.clone())
+        .with_usage_config(config.usage.clone())
+        .with_export_config(&config.export)
+        .with_ui_config(&config.ui);
+        let server = match &config.analytics.metrics_path {
+            Some(path) => server.with_metrics_store(FileMetricsStore::shared(path)),
+            None => server,
+        };
+        let server = if config.analytics.enabled {
+            let exporter = HttpAnalyticsExporter::new(&config.analytics.endpoint)
+                .with_api_key(config.analytics.api_key.clone());
+            let pusher = AnalyticsPusher::new(std::sync::Arc::new(exporter))
+                .with_batch_size(config.analytics.export_batch_size)
+                .with_retries(
+                    config.analytics.export_retries,
+                    std::time::Duration::from_secs(1),
+                );
+            let pusher = match analytics_redactor(&config.analytics)? {
+                Some(redactor) => pusher.with_redactor(redactor),
+                None => pusher,
+            };
+            server.with_analytics_pusher(std::sync::Arc::new(pusher))
+        } else {
+            server
+        };
 
         Ok(Self { config, server })
     }
 
-    /// Load configuration from file
-    fn load_config_from_file(path: &PathBuf) -> Result<ServerConfig, Box<dyn std::error::Error>> {
-        let content = std::fs::read_to_string(path)?;
-
-        if path.extension().and_then(|s| s.to_str()) == Some("toml") {
-            let config: toml::Value = toml::from_str(&content)?;
-            if let Some(server) = config.get("server") {
-                Ok(server.clone().try_into()?)
-            } else {
-                Ok(ServerConfig::default())
-            }
-        } else if path.extension().and_then(|s| s.to_str()) == Some("json") {
-            let config: serde_json::Value = serde_json::from_str(&content)?;
-            if let Some(server) = config.get("server") {
-                Ok(serde_json::from_value(server.clone())?)
-            } else {
-                Ok(ServerConfig::default())
-            }
-        } else {
-            Err("Unsupported configuration file format".into())
+    /// Load configuration from file, applying the selected profile
+    fn load_config_from_file(
+        path: &PathBuf,
+        profile: Option<&str>,
+    ) -> Result<ServerConfig, Box<dyn std::error::Error>> {
+        let document = config::read_config_document(path, profile)?;
+        match document.get("server") {
+            Some(server) => Ok(serde_json::from_value(server.clone())?),
+            None => Ok(ServerConfig::default()),
         }
     }
 
     /// Override configuration with command-line arguments
-    fn override_config(config: &mut ServerConfig, args: &Args) {
-        if !args.transport.is_empty() {
-            config.transport = args.transport.clone();
+    fn override_config(
+        config: &mut ServerConfig,
+        args: &Args,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(ref transport) = args.transport {
+            config.transport = transport.clone();
         }
 
-        if args.port != 0 {
-            config.port = args.port;
+        if args.host.is_some() || args.port.is_some() {
+            config
+                .transport
+                .set_address(args.host.clone(), args.port)
+                .map_err(|e| format!("--host/--port: {e}"))?;
         }
 
         if let Some(ref name) = args.name {
@@ -211,6 +462,14 @@ impl ServerApp {
             config.thinking.session_timeout_seconds = timeout;
         }
 
+        if let Some(style) = args.thought_style {
+            config.ui.thought_style = style;
+        }
+
+        if let Some(timeout) = args.thought_timeout {
+            config.thinking.thought_timeout_seconds = Some(timeout);
+        }
+
         if args.rate_limiting {
             config.security.rate_limiting_enabled = true;
         }
@@ -218,6 +477,8 @@ impl ServerApp {
         if let Some(requests_per_minute) = args.requests_per_minute {
             config.thinking.rate_limiting.requests_per_minute = requests_per_minute;
         }
+
+        Ok(())
     }
 
     /// Initialize logging
@@ -249,10 +510,6 @@ impl ServerApp {
         info!("Server: {} v{}", self.config.name, self.config.version);
         info!("Transport: {}", self.config.transport);
 
-        if self.config.transport == "http" {
-            info!("Port: {}", self.config.port);
-        }
-
         info!(
             "Max thoughts per session: {}",
             self.config.thinking.max_thoughts_per_session
@@ -266,6 +523,12 @@ impl ServerApp {
             self.config.thinking.session_timeout_seconds
         );
         info!("Analytics enabled: {}", self.config.analytics.enabled);
+        if let Some(pusher) = self.server.analytics_pusher() {
+            info!("Pushing analytics to {}", pusher.describe());
+            pusher.clone().spawn(std::time::Duration::from_secs(
+                self.config.analytics.collection_interval,
+            ));
+        }
         info!(
             "Rate limiting enabled: {}",
             self.config.security.rate_limiting_enabled
@@ -275,57 +538,64 @@ impl ServerApp {
         let mcp_server = self.server.clone().create_mcp_server();
 
         // Run server based on transport
-        match self.config.transport.as_str() {
-            "stdio" => {
+        match &self.config.transport {
+            TransportConfig::Stdio => {
                 info!("Running server with STDIO transport");
                 mcp_server.run_stdio().await?;
             }
-            "http" => {
-                info!(
-                    "Running server with HTTP transport on port {}",
-                    self.config.port
-                );
-                mcp_server
-                    .run_streamable_http("0.0.0.0", self.config.port)
-                    .await?;
+            TransportConfig::Http {
+                host,
+                port,
+                tls: None,
+            } => {
+                info!("Running server with HTTP transport on {host}:{port}");
+                Self::run_http(mcp_server, &self.server, host, *port).await?;
             }
-            _ => {
-                return Err(format!("Unsupported transport: {}", self.config.transport).into());
+            transport => {
+                return Err(format!("Unsupported transport: {transport}").into());
             }
         }
 
         Ok(())
     }
 
-    /// Validate configuration
-    fn validate_config(&self) -> Result<(), Vec<String>> {
-        let mut errors = Vec::new();
-
-        if self.config.name.is_empty() {
-            errors.push("Server name cannot be empty".to_string());
-        }
-
-        if self.config.port == 0 {
-            errors.push("Server port must be greater than 0".to_string());
-        }
-
-        if self.config.thinking.max_thoughts_per_session == 0 {
-            errors.push("Max thoughts per session must be greater than 0".to_string());
-        }
-
-        if self.config.thinking.max_branches_per_session == 0 {
-            errors.push("Max branches per session must be greater than 0".to_string());
+    /// Serve the MCP listener on loopback behind the CORS gateway
+    #[cfg(feature = "http-transport")]
+    async fn run_http(
+        mcp_server: UltraFastServer,
+        server: &SequentialThinkingServer,
+        host: &str,
+        port: u16,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let security = server.security_config();
+        info!("Allowed origins: {}", security.allowed_origins.join(", "));
+        let policy = CorsPolicy::from_config(security);
+        let listener = tokio::net::TcpListener::bind((host, port)).await?;
+        let upstream = gateway::UpstreamPort::default();
+        tokio::select! {
+            result = gateway::run_upstream(&upstream, |upstream_port| {
+                mcp_server.run_streamable_http("127.0.0.1", upstream_port)
+            }) => result.map_err(|e| e as Box<dyn std::error::Error>)?,
+            result = gateway::serve(policy, server.clone(), listener, upstream.clone()) => result?,
         }
+        Ok(())
+    }
 
-        if self.config.thinking.session_timeout_seconds == 0 {
-            errors.push("Session timeout must be greater than 0".to_string());
-        }
+    /// Serve the MCP listener directly
+    #[cfg(not(feature = "http-transport"))]
+    async fn run_http(
+        mcp_server: UltraFastServer,
+        _server: &SequentialThinkingServer,
+        host: &str,
+        port: u16,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        mcp_server.run_streamable_http(host, port).await?;
+        Ok(())
+    }
 
-        if errors.is_empty() {
-            Ok(())
-        } else {
-            Err(errors)
-        }
+    /// Validate configuration
+    fn validate_config(&self) -> Result<(), Vec<String>> {
+        self.config.validate()
     }
 
     /// Generate default configuration
@@ -354,7 +624,6 @@ impl ServerApp {
         println!("Name: {}", self.config.name);
         println!("Version: {}", self.config.version);
         println!("Transport: {}", self.config.transport);
-        println!("Port: {}", self.config.port);
         println!();
         println!("Configuration:");
         println!(
@@ -391,10 +660,470 @@ impl ServerApp {
         println!("Total thoughts: {}", stats.total_thoughts);
         println!("Total sessions: {}", stats.total_sessions);
         println!("Error count: {}", stats.error_count);
+        println!("Duplicate requests: {}", stats.duplicate_requests);
         println!("Average response time: {:.2}ms", stats.avg_response_time_ms);
 
         Ok(())
     }
+
+    /// Run a bulk session maintenance operation against the persistence layer
+    async fn run_sessions_command(
+        dir: Option<&PathBuf>,
+        action: &SessionsCommand,
+        analytics_config: &config::AnalyticsConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = SessionManagerConfig {
+            persist_sessions: true,
+            ..Default::default()
+        };
+        if let Some(dir) = dir {
+            config.persistence_dir = dir.to_string_lossy().to_string();
+        }
+        let manager = SessionManager::with_config(config);
+        manager.load_sessions().await?;
+
+        match action {
+            SessionsCommand::List {
+                json,
+                status,
+                tag,
+                priority,
+                title,
+                created_after,
+                created_before,
+                offset,
+                limit,
+            } => {
+                let filter = SessionFilter {
+                    status: status.as_deref().map(str::parse).transpose()?,
+                    tag: tag.clone(),
+                    priority: priority.as_deref().map(str::parse).transpose()?,
+                    created_after: created_after.as_deref().map(str::parse).transpose()?,
+                    created_before: created_before.as_deref().map(str::parse).transpose()?,
+                    title_contains: title.clone(),
+                    offset: *offset,
+                    limit: *limit,
+                };
+                let page = manager.list_sessions(&filter).await;
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&page)?);
+                } else if page.items.is_empty() {
+                    println!("No matching sessions");
+                } else {
+                    for overview in &page.items {
+                        println!(
+                            "{}  {:<10} {:>4} thoughts  {}  {}",
+                            overview.session_id,
+                            format!("{:?}", overview.status),
+                            overview.thought_count,
+                            overview.last_modified.format("%Y-%m-%d %H:%M"),
+                            overview.title
+                        );
+                    }
+                    if page.has_more() {
+                        println!(
+                            "Showing {}-{} of {} sessions",
+                            page.offset + 1,
+                            page.offset + page.items.len(),
+                            page.total
+                        );
+                    }
+                }
+            }
+            SessionsCommand::Export {
+                session_id,
+                all,
+                format,
+                output,
+                destination,
+                force,
+            } => {
+                let format: ExportFormat = format.parse()?;
+                let session_ids = match (session_id, all) {
+                    (_, true) => manager
+                        .list_overviews()
+                        .await
+                        .into_iter()
+                        .map(|o| o.session_id)
+                        .collect(),
+                    (Some(id), false) => vec![id.clone()],
+                    (None, false) => {
+                        return Err("Specify a session ID or --all".into());
+                    }
+                };
+
+                let mut exporter = ExportEngine::with_config(ExportConfig {
+                    export_directory: output.to_string_lossy().to_string(),
+                    destination: destination.as_deref().unwrap_or("local").parse()?,
+                    ..Default::default()
+                })
+                .with_export_index(output.join(INDEX_FILENAME))?;
+                for id in session_ids {
+                    let session = manager
+                        .get_session(&id)
+                        .await
+                        .ok_or_else(|| format!("Session not found: {id}"))?;
+                    let branches = session
+                        .engine
+                        .get_branches()
+                        .iter()
+                        .map(|(branch_id, branch)| (branch_id.clone(), branch.thoughts.clone()))
+                        .collect();
+                    let location = exporter
+                        .export_session(
+                            &id,
+                            Some(&session.metadata),
+                            &session.get_thoughts(),
+                            Some(&session.get_stats()),
+                            Some(&session.get_progress()),
+                            Some(&branches),
+                            None,
+                            Some(session.engine.get_annotations()),
+                            ExportOptions {
+                                format: format.clone(),
+                                force: *force,
+                                ..Default::default()
+                            },
+                        )
+                        .await?;
+                    let skipped = exporter
+                        .get_export_history()
+                        .last()
+                        .is_some_and(|record| record.skipped_duplicate);
+                    if skipped {
+                        println!("Skipped {id}, unchanged since {location}");
+                    } else {
+                        println!("Exported {id} to {location}");
+                    }
+                }
+            }
+            SessionsCommand::Analytics {
+                format,
+                output,
+                destination,
+            } => {
+                let format: ExportFormat = format.parse()?;
+                let mut analytics = AnalyticsEngine::new()
+                    .with_pricing(analytics_config.pricing.clone())
+                    .with_readability(analytics_config.readability.clone());
+                for overview in manager.list_overviews().await {
+                    if let Some(session) = manager.get_session(&overview.session_id).await {
+                        analytics.analyze_session(
+                            &overview.session_id,
+                            &overview.title,
+                            &session.get_thoughts(),
+                            &session.get_stats(),
+                            &session.get_progress(),
+                        );
+                    }
+                }
+
+                let mut exporter = ExportEngine::with_config(ExportConfig {
+                    export_directory: output.to_string_lossy().to_string(),
+                    destination: destination.as_deref().unwrap_or("local").parse()?,
+                    ..Default::default()
+                });
+                let location = exporter.export_analytics(&analytics, format).await?;
+                println!("Exported analytics to {location}");
+            }
+            SessionsCommand::Compare {
+                session_a,
+                session_b,
+                json,
+            } => {
+                let mut analytics = AnalyticsEngine::new()
+                    .with_pricing(analytics_config.pricing.clone())
+                    .with_readability(analytics_config.readability.clone());
+                for session_id in [session_a, session_b] {
+                    let session = manager
+                        .get_session(session_id)
+                        .await
+                        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+                    analytics.analyze_session(
+                        session_id,
+                        &session.metadata.title,
+                        &session.get_thoughts(),
+                        &session.get_stats(),
+                        &session.get_progress(),
+                    );
+                }
+                let comparison = analytics.compare_sessions(session_a, session_b)?;
+
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&comparison)?);
+                } else {
+                    println!("{}", comparison.summary);
+                    println!(
+                        "{:<24} {:>12} {:>12} {:>9}",
+                        "metric", session_a, session_b, "change"
+                    );
+                    for metric in &comparison.metrics {
+                        let change = metric
+                            .percent_change
+                            .map(|percent| format!("{percent:+.1}%"))
+                            .unwrap_or_else(|| "-".to_string());
+                        let marker = metric
+                            .better
+                            .as_ref()
+                            .map(|id| format!("  better: {id}"))
+                            .unwrap_or_default();
+                        println!(
+                            "{:<24} {:>12.3} {:>12.3} {:>9}{marker}",
+                            metric.metric, metric.a, metric.b, change
+                        );
+                    }
+                }
+            }
+            SessionsCommand::Cost { json } => {
+                let mut sessions = Vec::new();
+                for overview in manager.list_overviews().await {
+                    if let Some(session) = manager.get_session(&overview.session_id).await {
+                        sessions.push((session.metadata.tags.clone(), session.get_thoughts()));
+                    }
+                }
+                let report = CostReport::build(
+                    &analytics_config.pricing,
+                    sessions
+                        .iter()
+                        .map(|(tags, thoughts)| (&tags[..], &thoughts[..])),
+                );
+
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    let total = &report.total;
+                    println!(
+                        "Total: {:.4} {} ({} thoughts, {} input / {} output tokens, {} estimated)",
+                        total.cost,
+                        report.currency,
+                        total.thought_count,
+                        total.input_tokens,
+                        total.output_tokens,
+                        total.estimated_thoughts
+                    );
+                    for (title, buckets) in [
+                        ("By model", &report.by_model),
+                        ("By tag", &report.by_tag),
+                        ("By author", &report.by_author),
+                        ("By day", &report.by_day),
+                    ] {
+                        println!("{title}:");
+                        for bucket in buckets {
+                            println!(
+                                "  {:<24} {:>12.4} {}  {:>5} thoughts",
+                                bucket.key, bucket.cost, report.currency, bucket.thought_count
+                            );
+                        }
+                    }
+                    if !report.unpriced_models.is_empty() {
+                        println!(
+                            "No price configured for: {}",
+                            report.unpriced_models.join(", ")
+                        );
+                    }
+                }
+            }
+            SessionsCommand::Anomalies { json, alert } => {
+                let webhook_url = analytics_config.anomaly.webhook_url.as_deref();
+                if *alert && webhook_url.is_none() {
+                    return Err("--alert needs analytics.anomaly.webhook_url to be set".into());
+                }
+                let mut analytics = AnalyticsEngine::new()
+                    .with_pricing(analytics_config.pricing.clone())
+                    .with_readability(analytics_config.readability.clone())
+                    .with_anomaly(analytics_config.anomaly.clone());
+                let mut sessions = Vec::new();
+                for overview in manager.list_overviews().await {
+                    if let Some(session) = manager.get_session(&overview.session_id).await {
+                        let thoughts = session.get_thoughts();
+                        analytics.analyze_session(
+                            &overview.session_id,
+                            &overview.title,
+                            &thoughts,
+                            &session.get_stats(),
+                            &session.get_progress(),
+                        );
+                        sessions.push((overview.session_id, thoughts));
+                    }
+                }
+
+                let mut reports = Vec::new();
+                for (session_id, thoughts) in &sessions {
+                    let report = analytics.detect_anomalies(session_id, thoughts)?;
+                    if !report.is_empty() {
+                        reports.push(report);
+                    }
+                }
+                if let (true, Some(url)) = (*alert, webhook_url) {
+                    let redactor = analytics_redactor(analytics_config)?;
+                    for report in &reports {
+                        let report = match &redactor {
+                            Some(redactor) => report.redacted(redactor),
+                            None => report.clone(),
+                        };
+                        anomaly::send_anomaly_alert(url, &report)
+                            .await
+                            .map_err(|e| e.to_string())?;
+                    }
+                }
+
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&reports)?);
+                } else if reports.is_empty() {
+                    println!("No anomalies in {} session(s)", sessions.len());
+                } else {
+                    for report in &reports {
+                        println!("{}", report.summary());
+                        for anomaly in &report.anomalies {
+                            println!("  [{:?}] {}", anomaly.severity, anomaly.description);
+                        }
+                    }
+                }
+            }
+            SessionsCommand::Archive { older_than } => {
+                let max_age = session_utils::parse_age(older_than)?;
+                let archived = manager.archive_sessions_older_than(max_age).await?;
+                manager.persist_sessions().await?;
+                println!("Archived {} session(s)", archived.len());
+                for id in archived {
+                    println!("  - {id}");
+                }
+            }
+            SessionsCommand::Delete { status } => {
+                let status: SessionStatus = status.parse()?;
+                let deleted = manager.delete_sessions_with_status(&status).await;
+                manager.persist_sessions().await?;
+                println!("Deleted {} session(s)", deleted.len());
+                for id in deleted {
+                    println!("  - {id}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run an admin operation against a running server
+    #[cfg(feature = "http-transport")]
+    async fn run_admin_command(
+        &self,
+        url: Option<&str>,
+        api_key: Option<&str>,
+        action: &AdminCommand,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let url = match (url, &self.config.transport) {
+            (Some(url), _) => url.to_string(),
+            (None, TransportConfig::Http { host, port, .. }) => {
+                let host = match host.as_str() {
+                    "0.0.0.0" | "::" | "[::]" => "127.0.0.1",
+                    host => host,
+                };
+                format!("http://{host}:{port}")
+            }
+            (None, transport) => {
+                return Err(
+                    format!("Specify --url; the configured transport is {transport}").into(),
+                )
+            }
+        };
+        let mut client = AdminClient::new(url);
+        if let Some(api_key) = api_key {
+            client = client.with_api_key(api_key);
+        }
+
+        match action {
+            AdminCommand::ListSessions { json } => {
+                let sessions = client.list_sessions().await?;
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&sessions)?);
+                } else if sessions.is_empty() {
+                    println!("No active sessions");
+                } else {
+                    for session in &sessions {
+                        println!(
+                            "{}  {:>4} thoughts  {:>3} branches  {}",
+                            session.session_id,
+                            session.thought_count,
+                            session.branch_count,
+                            if session.complete {
+                                "complete"
+                            } else {
+                                "in progress"
+                            }
+                        );
+                    }
+                }
+            }
+            AdminCommand::KillSession { session_id } => {
+                client.kill_session(session_id).await?;
+                println!("Killed session {session_id}");
+            }
+            AdminCommand::ExportSession {
+                session_id,
+                format,
+                output,
+            } => {
+                let content = client.export_session(session_id, format).await?;
+                match output {
+                    Some(output) => {
+                        std::fs::write(output, content)?;
+                        println!("Exported {session_id} to {}", output.display());
+                    }
+                    None => println!("{content}"),
+                }
+            }
+            AdminCommand::Stats { json } => {
+                let stats = client.stats().await?;
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&stats)?);
+                } else {
+                    println!("Active sessions: {}", stats.active_sessions);
+                    println!("Total requests: {}", stats.stats.total_requests);
+                    println!("Total thoughts: {}", stats.stats.total_thoughts);
+                    println!("Total sessions: {}", stats.stats.total_sessions);
+                    println!("Error count: {}", stats.stats.error_count);
+                    println!("Duplicate requests: {}", stats.stats.duplicate_requests);
+                    println!(
+                        "Average response time: {:.2}ms",
+                        stats.stats.avg_response_time_ms
+                    );
+                }
+            }
+            AdminCommand::Analytics { days, json } => {
+                let dashboard = client.analytics_dashboard(*days).await?;
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&dashboard)?);
+                } else {
+                    println!("Total sessions: {}", dashboard.total_sessions);
+                    for day in &dashboard.days {
+                        println!(
+                            "{}  {:>4} sessions  {:>5} thoughts  quality {:.2}  avg {:.2}ms  p95 {:.2}ms",
+                            day.date,
+                            day.sessions,
+                            day.thoughts,
+                            day.avg_quality,
+                            day.avg_processing_time_ms,
+                            day.p95_processing_time_ms
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Redactor for data leaving the server, when `analytics.anonymize_data` is set
+fn analytics_redactor(
+    analytics: &config::AnalyticsConfig,
+) -> Result<Option<std::sync::Arc<Redactor>>, String> {
+    if !analytics.anonymize_data {
+        return Ok(None);
+    }
+    Redactor::new(&analytics.redaction)
+        .map(|redactor| Some(std::sync::Arc::new(redactor)))
+        .map_err(|e| format!("analytics.redaction: {e}"))
 }
 
 #[tokio::main]
@@ -404,31 +1133,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Handle subcommands first
     if let Some(ref command) = args.command {
         match command {
-            Commands::Validate { config } => match ServerApp::load_config_from_file(config) {
-                Ok(server_config) => {
-                    let server = SequentialThinkingServer::new();
-                    let app = ServerApp {
-                        config: server_config,
-                        server,
-                    };
-
-                    match app.validate_config() {
-                        Ok(()) => {
-                            println!("Configuration is valid");
-                            Ok(())
-                        }
-                        Err(errors) => {
-                            println!("Configuration validation failed:");
-                            for error in errors {
-                                println!("  - {error}");
-                            }
-                            Err("Configuration validation failed".into())
-                        }
+            Commands::Validate { config } => match config::validate_file(config) {
+                Ok(_) => {
+                    println!("Configuration is valid");
+                    Ok(())
+                }
+                Err(errors) => {
+                    println!("Configuration validation failed:");
+                    for error in errors {
+                        println!("  - {error}");
                     }
+                    Err("Configuration validation failed".into())
                 }
-                Err(e) => {
-                    error!("Failed to load configuration: {}", e);
-                    Err(e)
+            },
+            Commands::Config { action } => match action {
+                ConfigCommand::Schema { section } => {
+                    let schema = match section.as_str() {
+                        "file" => config::config_schema::<ConfigFile>(),
+                        "server" => config::config_schema::<ServerConfig>(),
+                        "client" => config::config_schema::<ClientConfig>(),
+                        other => {
+                            return Err(format!("Unknown configuration section: {other}").into())
+                        }
+                    };
+                    println!("{}", serde_json::to_string_pretty(&schema)?);
+                    Ok(())
                 }
             },
             Commands::Generate { output } => ServerApp::generate_config(output),
@@ -441,6 +1170,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 let app = ServerApp::new(&args)?;
                 app.health_check().await
             }
+            Commands::Sessions { dir, action } => {
+                let app = ServerApp::new(&args)?;
+                ServerApp::run_sessions_command(dir.as_ref(), action, &app.config.analytics).await
+            }
+            #[cfg(feature = "http-transport")]
+            Commands::Admin {
+                url,
+                api_key,
+                action,
+            } => {
+                let app = ServerApp::new(&args)?;
+                app.run_admin_command(url.as_deref(), api_key.as_deref(), action)
+                    .await
+            }
         }
     } else {
         // Initialize logging
@@ -4,23 +4,87 @@
 //!
 //! This module provides configuration structures and loading functionality
 //! for both server and client components.
-
+//!
+//! ## Environment Overrides
+//!
+//! Every configuration field can be set from the environment. Server fields
+//! use the `SEQUENTIAL_THINKING__` prefix and client fields the
+//! `SEQUENTIAL_THINKING_CLIENT__` prefix, followed by the field path with
+//! sections separated by a double underscore:
+//!
+//! ```text
+//! SEQUENTIAL_THINKING__TRANSPORT=http://0.0.0.0:9090
+//! SEQUENTIAL_THINKING__THINKING__MAX_THOUGHTS_PER_SESSION=250
+//! SEQUENTIAL_THINKING__EXPORT__FORMATS=json,markdown
+//! SEQUENTIAL_THINKING__SECURITY__API_KEYS='{"ci-key": "contributor"}'
+//! SEQUENTIAL_THINKING_CLIENT__UI__COLOR_OUTPUT=false
+//! ```
+//!
+//! Paths are case-insensitive. Values are parsed according to the field they
+//! replace: strings are taken verbatim, lists accept JSON or comma-separated
+//! items, and other fields accept JSON.
+//!
+//! ## Profiles
+//!
+//! A configuration file can hold named profiles under `[profiles.<name>]`,
+//! each shaped like the file itself. Selecting a profile merges it over the
+//! base configuration, so one file can describe several deployments:
+//!
+//! ```toml
+//! [server]
+//! transport = "stdio"
+//!
+//! [profiles.prod.server]
+//! transport = "http"
+//!
+//! [profiles.prod.server.thinking]
+//! max_thoughts_per_session = 500
+//! ```
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{Schema, SchemaObject};
+use schemars::visit::{self, Visitor};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
+use crate::actions::{ActionConfig, ActionExtractor};
+use crate::analytics::anomaly::AnomalyConfig;
+use crate::analytics::cost::PricingConfig;
+use crate::analytics::readability::ReadabilityConfig;
+use crate::export::DestinationConfig;
+use crate::integrations::IntegrationsConfig;
+use crate::redaction::RedactionConfig;
+use crate::thinking::actor::ActorConfig;
+use crate::thinking::attachment::AttachmentLimits;
+use crate::thinking::backpressure::BackpressureConfig;
 use crate::thinking::client::ClientThinkingConfig;
+use crate::thinking::elicitation::{ElicitationConfig, ELICITABLE_FIELDS};
+use crate::thinking::metadata::check_schema;
+use crate::thinking::sampling::SamplingConfig;
+use crate::thinking::sanitize::SanitizeConfig;
+use crate::thinking::sequence::NumberingPolicy;
+use crate::thinking::spill::BoundedMemoryConfig;
+use crate::thinking::strategy::ThinkingStrategy;
+use crate::usage::UsageConfig;
+use crate::visualization::VisualizationStyle;
 
 /// Server configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerConfig {
     /// Server name
     pub name: String,
     /// Server version
     pub version: String,
-    /// Transport type (stdio, http)
-    pub transport: String,
-    /// Port for HTTP transport
-    pub port: u16,
+    /// Transport the server listens on
+    ///
+    /// Accepts a table (`{ type = "http", host = "127.0.0.1", port = 8080 }`)
+    /// or a string such as `"stdio"`, `"http"` or `"http://127.0.0.1:8080"`.
+    #[serde(deserialize_with = "deserialize_transport")]
+    #[schemars(schema_with = "transport_schema")]
+    pub transport: TransportConfig,
     /// Thinking configuration
     pub thinking: ThinkingConfig,
     /// Export configuration
@@ -31,6 +95,15 @@ pub struct ServerConfig {
     pub logging: LoggingConfig,
     /// Security configuration
     pub security: SecurityConfig,
+    /// Thought log display configuration
+    #[serde(default)]
+    pub ui: UIConfig,
+    /// Issue tracker integrations
+    #[serde(default)]
+    pub integrations: IntegrationsConfig,
+    /// Per-API-key monthly quotas
+    #[serde(default)]
+    pub usage: UsageConfig,
 }
 
 impl Default for ServerConfig {
@@ -38,19 +111,477 @@ impl Default for ServerConfig {
         Self {
             name: "ultrafast-sequential-thinking".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
-            transport: "stdio".to_string(),
-            port: 8080,
+            transport: TransportConfig::Stdio,
             thinking: ThinkingConfig::default(),
             export: ExportConfig::default(),
             analytics: AnalyticsConfig::default(),
             logging: LoggingConfig::default(),
             security: SecurityConfig::default(),
+            ui: UIConfig::default(),
+            integrations: IntegrationsConfig::default(),
+            usage: UsageConfig::default(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Check that every field is within its allowed range
+    ///
+    /// Each error is prefixed with the path of the offending field.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.name.is_empty() {
+            errors.push("name: must not be empty".to_string());
+        }
+        match &self.transport {
+            TransportConfig::Stdio => {}
+            TransportConfig::Http { host, port, tls }
+            | TransportConfig::Ws {
+                host, port, tls, ..
+            } => {
+                if host.is_empty() {
+                    errors.push("transport.host: must not be empty".to_string());
+                }
+                if *port == 0 {
+                    errors.push("transport.port: must be greater than 0".to_string());
+                }
+                if tls.is_some() {
+                    errors.push(
+                        "transport.tls: TLS is not supported by the MCP framework; terminate TLS at a reverse proxy"
+                            .to_string(),
+                    );
+                }
+                if matches!(self.transport, TransportConfig::Ws { .. }) {
+                    errors.push(
+                        "transport: WebSocket transport is not supported by the MCP framework yet"
+                            .to_string(),
+                    );
+                }
+            }
+        }
+
+        let thinking = &self.thinking;
+        if thinking.max_thoughts_per_session == 0 {
+            errors.push("thinking.max_thoughts_per_session: must be greater than 0".to_string());
+        }
+        if thinking.max_branches_per_session == 0 {
+            errors.push("thinking.max_branches_per_session: must be greater than 0".to_string());
+        }
+        if thinking.session_timeout_seconds == 0 {
+            errors.push("thinking.session_timeout_seconds: must be greater than 0".to_string());
+        }
+        if thinking.thought_timeout_seconds == Some(0) {
+            errors.push(
+                "thinking.thought_timeout_seconds: must be greater than 0 when set".to_string(),
+            );
+        }
+        if thinking.max_thought_length == 0 {
+            errors.push("thinking.max_thought_length: must be greater than 0".to_string());
+        }
+        if thinking.min_thought_length > thinking.max_thought_length {
+            errors.push(format!(
+                "thinking.min_thought_length: must not exceed thinking.max_thought_length ({} > {})",
+                thinking.min_thought_length, thinking.max_thought_length
+            ));
+        }
+        if thinking.max_request_bytes == 0 {
+            errors.push("thinking.max_request_bytes: must be greater than 0".to_string());
+        }
+        if thinking.attachments.max_attachment_bytes == 0 {
+            errors.push(
+                "thinking.attachments.max_attachment_bytes: must be greater than 0".to_string(),
+            );
+        }
+        if let Err(e) = ActionExtractor::new(&thinking.actions) {
+            errors.push(format!("thinking.actions.{e}"));
+        }
+        if let Some(bounded) = &thinking.bounded_memory {
+            if bounded.max_thoughts_in_memory == 0 {
+                errors.push(
+                    "thinking.bounded_memory.max_thoughts_in_memory: must be greater than 0"
+                        .to_string(),
+                );
+            }
+            if bounded.spill_dir.is_empty() {
+                errors.push("thinking.bounded_memory.spill_dir: must not be empty".to_string());
+            }
+        }
+        let mut schema_names: Vec<&String> = thinking.metadata_schemas.keys().collect();
+        schema_names.sort();
+        for name in schema_names {
+            errors.extend(check_schema(
+                &thinking.metadata_schemas[name],
+                &format!("thinking.metadata_schemas.{name}"),
+            ));
+        }
+        if let Some(sampling) = &thinking.sampling {
+            if sampling.continue_marker.trim().is_empty() {
+                errors.push("thinking.sampling.continue_marker: must not be empty".to_string());
+            }
+            if sampling.max_tokens == 0 {
+                errors.push("thinking.sampling.max_tokens: must be greater than 0".to_string());
+            }
+        }
+        if let Some(elicitation) = &thinking.elicitation {
+            if elicitation.fields.is_empty() {
+                errors.push("thinking.elicitation.fields: must not be empty".to_string());
+            }
+            for field in &elicitation.fields {
+                if !ELICITABLE_FIELDS.iter().any(|(name, _, _)| name == field) {
+                    errors.push(format!(
+                        "thinking.elicitation.fields: unsupported field '{field}'"
+                    ));
+                }
+            }
+        }
+        if let Some(backpressure) = &thinking.backpressure {
+            for (field, value) in [
+                ("max_in_flight_calls", backpressure.max_in_flight_calls),
+                (
+                    "max_queued_per_session",
+                    backpressure.max_queued_per_session,
+                ),
+            ] {
+                if value == 0 {
+                    errors.push(format!(
+                        "thinking.backpressure.{field}: must be greater than 0"
+                    ));
+                }
+            }
+        }
+        if thinking
+            .actors
+            .as_ref()
+            .is_some_and(|actors| actors.mailbox_capacity == 0)
+        {
+            errors.push("thinking.actors.mailbox_capacity: must be greater than 0".to_string());
+        }
+        let rate_limiting = &thinking.rate_limiting;
+        if rate_limiting.enabled {
+            for (field, value) in [
+                ("requests_per_minute", rate_limiting.requests_per_minute),
+                ("thoughts_per_minute", rate_limiting.thoughts_per_minute),
+                ("burst_size", rate_limiting.burst_size),
+            ] {
+                if value == 0 {
+                    errors.push(format!(
+                        "thinking.rate_limiting.{field}: must be greater than 0 when rate limiting is enabled"
+                    ));
+                }
+            }
+        }
+
+        for (index, format) in self.export.formats.iter().enumerate() {
+            if let Err(e) = format.parse::<crate::export::ExportFormat>() {
+                errors.push(format!("export.formats[{index}]: {e}"));
+            }
+        }
+        if self.export.filename_template.is_empty() {
+            errors.push("export.filename_template: must not be empty".to_string());
+        }
+        if self
+            .export
+            .session_log_directory
+            .as_ref()
+            .is_some_and(|dir| dir.is_empty())
+        {
+            errors.push("export.session_log_directory: must not be empty".to_string());
+        }
+        if self.export.session_log_snapshot_interval == Some(0) {
+            errors.push("export.session_log_snapshot_interval: must be greater than 0".to_string());
+        }
+
+        if self.analytics.collection_interval == 0 {
+            errors.push("analytics.collection_interval: must be greater than 0".to_string());
+        }
+        if self.analytics.retention_days == 0 {
+            errors.push("analytics.retention_days: must be greater than 0".to_string());
+        }
+        if let Err(pricing_errors) = self.analytics.pricing.validate() {
+            errors.extend(
+                pricing_errors
+                    .into_iter()
+                    .map(|e| format!("analytics.pricing.{e}")),
+            );
+        }
+        if let Err(readability_errors) = self.analytics.readability.validate() {
+            errors.extend(
+                readability_errors
+                    .into_iter()
+                    .map(|e| format!("analytics.readability.{e}")),
+            );
+        }
+        if let Err(anomaly_errors) = self.analytics.anomaly.validate() {
+            errors.extend(
+                anomaly_errors
+                    .into_iter()
+                    .map(|e| format!("analytics.anomaly.{e}")),
+            );
+        }
+        if self
+            .analytics
+            .metrics_path
+            .as_deref()
+            .is_some_and(|path| path.trim().is_empty())
+        {
+            errors.push("analytics.metrics_path: must not be empty when set".to_string());
+        }
+        if let Err(redaction_errors) = self.analytics.redaction.validate() {
+            errors.extend(
+                redaction_errors
+                    .into_iter()
+                    .map(|e| format!("analytics.redaction.{e}")),
+            );
+        }
+        if self.analytics.export_batch_size == 0 {
+            errors.push("analytics.export_batch_size: must be greater than 0".to_string());
+        }
+
+        let levels = ["trace", "debug", "info", "warn", "error"];
+        if !levels.contains(&self.logging.level.to_lowercase().as_str()) {
+            errors.push(format!(
+                "logging.level: must be one of {} (got '{}')",
+                levels.join(", "),
+                self.logging.level
+            ));
+        }
+        if !["text", "json"].contains(&self.logging.format.as_str()) {
+            errors.push(format!(
+                "logging.format: must be one of text, json (got '{}')",
+                self.logging.format
+            ));
+        }
+        if self.logging.file && self.logging.file_path.is_none() {
+            errors.push("logging.file_path: must be set when logging.file is enabled".to_string());
+        }
+
+        if self.security.api_key_validation && self.security.api_keys.is_empty() {
+            errors.push(
+                "security.api_keys: API key validation requires at least one API key".to_string(),
+            );
+        }
+
+        if let Err(integration_errors) = self.integrations.validate() {
+            errors.extend(
+                integration_errors
+                    .into_iter()
+                    .map(|e| format!("integrations.{e}")),
+            );
+        }
+        if let Err(usage_errors) = self.usage.validate() {
+            errors.extend(usage_errors.into_iter().map(|e| format!("usage.{e}")));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
         }
     }
 }
 
+/// Default address network transports bind to
+pub const DEFAULT_HOST: &str = "0.0.0.0";
+
+/// Default port for network transports
+pub const DEFAULT_PORT: u16 = 8080;
+
+fn default_host() -> String {
+    DEFAULT_HOST.to_string()
+}
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+fn default_ws_path() -> String {
+    "/".to_string()
+}
+
+/// Transport the server listens on
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TransportConfig {
+    /// Standard input and output
+    #[default]
+    Stdio,
+    /// Streamable HTTP
+    Http {
+        /// Address to bind
+        #[serde(default = "default_host")]
+        host: String,
+        /// Port to bind
+        #[serde(default = "default_port")]
+        port: u16,
+        /// Certificate and key for HTTPS
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tls: Option<TlsConfig>,
+    },
+    /// WebSocket
+    Ws {
+        /// Address to bind
+        #[serde(default = "default_host")]
+        host: String,
+        /// Port to bind
+        #[serde(default = "default_port")]
+        port: u16,
+        /// Path the WebSocket endpoint is served on
+        #[serde(default = "default_ws_path")]
+        path: String,
+        /// Certificate and key for secure WebSockets
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tls: Option<TlsConfig>,
+    },
+}
+
+/// TLS certificate and private key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TlsConfig {
+    /// Path to the PEM certificate chain
+    pub cert_path: String,
+    /// Path to the PEM private key
+    pub key_path: String,
+}
+
+impl TransportConfig {
+    /// HTTP transport bound to an address
+    pub fn http(host: impl Into<String>, port: u16) -> Self {
+        TransportConfig::Http {
+            host: host.into(),
+            port,
+            tls: None,
+        }
+    }
+
+    /// Short name of the transport (stdio, http, ws)
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransportConfig::Stdio => "stdio",
+            TransportConfig::Http { .. } => "http",
+            TransportConfig::Ws { .. } => "ws",
+        }
+    }
+
+    /// Address network transports bind to
+    pub fn address(&self) -> Option<(&str, u16)> {
+        match self {
+            TransportConfig::Stdio => None,
+            TransportConfig::Http { host, port, .. } | TransportConfig::Ws { host, port, .. } => {
+                Some((host, *port))
+            }
+        }
+    }
+
+    /// Override the host and/or port of a network transport
+    pub fn set_address(
+        &mut self,
+        new_host: Option<String>,
+        new_port: Option<u16>,
+    ) -> Result<(), String> {
+        match self {
+            TransportConfig::Stdio => Err("The stdio transport has no host or port".to_string()),
+            TransportConfig::Http { host, port, .. } | TransportConfig::Ws { host, port, .. } => {
+                if let Some(new_host) = new_host {
+                    *host = new_host;
+                }
+                if let Some(new_port) = new_port {
+                    *port = new_port;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for TransportConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransportConfig::Stdio => write!(f, "stdio"),
+            TransportConfig::Http { host, port, tls } => {
+                let scheme = if tls.is_some() { "https" } else { "http" };
+                write!(f, "{scheme}://{host}:{port}")
+            }
+            TransportConfig::Ws {
+                host,
+                port,
+                path,
+                tls,
+            } => {
+                let scheme = if tls.is_some() { "wss" } else { "ws" };
+                write!(f, "{scheme}://{host}:{port}{path}")
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for TransportConfig {
+    type Err = String;
+
+    /// Parse `stdio`, `http`, `ws`, or a URL such as `http://127.0.0.1:8080`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (scheme, rest) = s.split_once("://").unwrap_or((s, ""));
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if !port.contains(']') => {
+                let port = port
+                    .parse::<u16>()
+                    .map_err(|_| format!("Invalid port in transport: {s}"))?;
+                (host.to_string(), port)
+            }
+            _ if authority.is_empty() => (default_host(), DEFAULT_PORT),
+            _ => (authority.to_string(), DEFAULT_PORT),
+        };
+
+        match scheme.to_lowercase().as_str() {
+            "stdio" if rest.is_empty() => Ok(TransportConfig::Stdio),
+            "http" if path.is_empty() || path == "/" => Ok(TransportConfig::http(host, port)),
+            "ws" => Ok(TransportConfig::Ws {
+                host,
+                port,
+                path: if path.is_empty() {
+                    default_ws_path()
+                } else {
+                    path.to_string()
+                },
+                tls: None,
+            }),
+            "https" | "wss" => Err(format!(
+                "Transport {s} needs a certificate; configure transport.tls instead"
+            )),
+            _ => Err(format!("Unknown transport: {s}")),
+        }
+    }
+}
+
+/// Deserialize a transport from its table form or a string
+fn deserialize_transport<'de, D>(deserializer: D) -> Result<TransportConfig, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(s) => s.parse().map_err(D::Error::custom),
+        value => serde_json::from_value(value).map_err(D::Error::custom),
+    }
+}
+
+/// Schema accepting either form of [`TransportConfig`]
+fn transport_schema(generator: &mut SchemaGenerator) -> Schema {
+    let mut schema = SchemaObject::default();
+    schema.subschemas().any_of = Some(vec![
+        generator.subschema_for::<String>(),
+        generator.subschema_for::<TransportConfig>(),
+    ]);
+    schema.into()
+}
+
 /// Client configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ClientConfig {
     /// Server URL
     pub server_url: String,
@@ -64,6 +595,15 @@ pub struct ClientConfig {
     pub connection: ConnectionConfig,
     /// UI configuration
     pub ui: UIConfig,
+    /// API key presented to servers that enforce access roles
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Named servers the client can be pointed at with `--server-name`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub servers: HashMap<String, ServerTarget>,
+    /// Server used when none is named on the command line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_server: Option<String>,
 }
 
 impl Default for ClientConfig {
@@ -75,12 +615,159 @@ impl Default for ClientConfig {
             thinking: ClientThinkingConfig::default(),
             connection: ConnectionConfig::default(),
             ui: UIConfig::default(),
+            api_key: None,
+            servers: HashMap::new(),
+            default_server: None,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Configuration for one of the named servers
+    ///
+    /// The server's URL, credentials and connection settings replace the
+    /// top-level ones; settings the server leaves out are kept.
+    pub fn for_server(&self, name: &str) -> Result<Self, String> {
+        let target = self.servers.get(name).ok_or_else(|| {
+            let mut available: Vec<&str> = self.servers.keys().map(String::as_str).collect();
+            available.sort_unstable();
+            if available.is_empty() {
+                format!("Unknown server '{name}': no servers are defined")
+            } else {
+                format!(
+                    "Unknown server '{name}' (available: {})",
+                    available.join(", ")
+                )
+            }
+        })?;
+
+        let mut config = self.clone();
+        config.server_url = target.server_url.clone();
+        if let Some(api_key) = target.api_key()? {
+            config.api_key = Some(api_key);
+        }
+        if let Some(timeout_seconds) = target.timeout_seconds {
+            config.timeout_seconds = timeout_seconds;
+        }
+        if let Some(retry_attempts) = target.retry_attempts {
+            config.retry_attempts = retry_attempts;
+        }
+        if let Some(connection) = &target.connection {
+            config.connection = connection.clone();
+        }
+        Ok(config)
+    }
+
+    /// Check that every field is within its allowed range
+    ///
+    /// Each error is prefixed with the path of the offending field.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.server_url.is_empty() {
+            errors.push("server_url: must not be empty".to_string());
+        }
+        if self.timeout_seconds == 0 {
+            errors.push("timeout_seconds: must be greater than 0".to_string());
+        }
+        if self.thinking.operation_timeout == 0 {
+            errors.push("thinking.operation_timeout: must be greater than 0".to_string());
+        }
+        if self.connection.timeout_seconds == 0 {
+            errors.push("connection.timeout_seconds: must be greater than 0".to_string());
+        }
+        if self.connection.connection_pooling && self.connection.pool_size == 0 {
+            errors.push(
+                "connection.pool_size: must be greater than 0 when connection pooling is enabled"
+                    .to_string(),
+            );
+        }
+        if let Some(cache) = &self.thinking.response_cache {
+            if let Err(cache_errors) = cache.validate() {
+                errors.extend(
+                    cache_errors
+                        .into_iter()
+                        .map(|e| format!("thinking.response_cache.{e}")),
+                );
+            }
+        }
+        let mut names: Vec<&String> = self.servers.keys().collect();
+        names.sort();
+        for name in names {
+            let target = &self.servers[name];
+            if target.server_url.is_empty() {
+                errors.push(format!("servers.{name}.server_url: must not be empty"));
+            }
+            if target.timeout_seconds == Some(0) {
+                errors.push(format!(
+                    "servers.{name}.timeout_seconds: must be greater than 0"
+                ));
+            }
+            if target.api_key.is_some() && target.api_key_env.is_some() {
+                errors.push(format!(
+                    "servers.{name}.api_key_env: cannot be combined with api_key"
+                ));
+            }
+            if let Some(connection) = &target.connection {
+                if connection.timeout_seconds == 0 {
+                    errors.push(format!(
+                        "servers.{name}.connection.timeout_seconds: must be greater than 0"
+                    ));
+                }
+            }
+        }
+        if let Some(name) = &self.default_server {
+            if !self.servers.contains_key(name) {
+                errors.push(format!(
+                    "default_server: '{name}' is not one of the configured servers"
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Named server a client configuration can target
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ServerTarget {
+    /// Server URL
+    pub server_url: String,
+    /// API key presented to this server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Environment variable holding the API key, to keep it out of the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    /// Request timeout in seconds, if different from the client's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>,
+    /// Number of retry attempts, if different from the client's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_attempts: Option<u32>,
+    /// Connection settings, if different from the client's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection: Option<ConnectionConfig>,
+}
+
+impl ServerTarget {
+    /// API key of the server, read from `api_key_env` if set
+    pub fn api_key(&self) -> Result<Option<String>, String> {
+        match &self.api_key_env {
+            Some(variable) => std::env::var(variable)
+                .map(Some)
+                .map_err(|_| format!("API key variable {variable} is not set")),
+            None => Ok(self.api_key.clone()),
         }
     }
 }
 
 /// Thinking configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ThinkingConfig {
     /// Maximum thoughts per session
     pub max_thoughts_per_session: u32,
@@ -88,6 +775,9 @@ pub struct ThinkingConfig {
     pub max_branches_per_session: u32,
     /// Session timeout in seconds
     pub session_timeout_seconds: u64,
+    /// Maximum time to process a single thought, in seconds (no limit if unset)
+    #[serde(default)]
+    pub thought_timeout_seconds: Option<u64>,
     /// Whether to enable analytics
     pub enable_analytics: bool,
     /// Whether to enable thought logging
@@ -96,8 +786,44 @@ pub struct ThinkingConfig {
     pub max_thought_length: usize,
     /// Minimum thought length
     pub min_thought_length: usize,
+    /// Largest tool call arguments accepted, in bytes of JSON
+    #[serde(default = "default_max_request_bytes")]
+    pub max_request_bytes: usize,
     /// Rate limiting configuration
     pub rate_limiting: RateLimitingConfig,
+    /// How thought numbers that do not follow the previous thought are handled
+    #[serde(default)]
+    pub numbering_policy: NumberingPolicy,
+    /// Strategy of sessions that do not select one
+    #[serde(default)]
+    pub strategy: ThinkingStrategy,
+    /// Limits on the attachments of each thought
+    #[serde(default)]
+    pub attachments: AttachmentLimits,
+    /// How thought text is cleaned before it is stored and logged
+    #[serde(default)]
+    pub sanitize: SanitizeConfig,
+    /// How action items are recognized in thoughts
+    #[serde(default)]
+    pub actions: ActionConfig,
+    /// Named JSON Schemas sessions can require thought metadata to match
+    #[serde(default)]
+    pub metadata_schemas: HashMap<String, serde_json::Value>,
+    /// Keep only recent thoughts in memory, spilling older ones to disk
+    #[serde(default)]
+    pub bounded_memory: Option<BoundedMemoryConfig>,
+    /// Generate thoughts through MCP sampling when a continue marker is sent
+    #[serde(default)]
+    pub sampling: Option<SamplingConfig>,
+    /// Ask the user for missing thought fields through MCP elicitation
+    #[serde(default)]
+    pub elicitation: Option<ElicitationConfig>,
+    /// Reject tool calls beyond these concurrency limits (no limit if unset)
+    #[serde(default)]
+    pub backpressure: Option<BackpressureConfig>,
+    /// Handle each session's tool calls in order on an engine actor (shared locks if unset)
+    #[serde(default)]
+    pub actors: Option<ActorConfig>,
 }
 
 impl Default for ThinkingConfig {
@@ -106,17 +832,34 @@ impl Default for ThinkingConfig {
             max_thoughts_per_session: 100,
             max_branches_per_session: 10,
             session_timeout_seconds: 3600,
+            thought_timeout_seconds: None,
             enable_analytics: true,
             enable_thought_logging: true,
             max_thought_length: 10000,
             min_thought_length: 10,
+            max_request_bytes: default_max_request_bytes(),
             rate_limiting: RateLimitingConfig::default(),
+            numbering_policy: NumberingPolicy::default(),
+            strategy: ThinkingStrategy::default(),
+            attachments: AttachmentLimits::default(),
+            sanitize: SanitizeConfig::default(),
+            actions: ActionConfig::default(),
+            metadata_schemas: HashMap::new(),
+            bounded_memory: None,
+            sampling: None,
+            elicitation: None,
+            backpressure: None,
+            actors: None,
         }
     }
 }
 
+fn default_max_request_bytes() -> usize {
+    1024 * 1024
+}
+
 /// Rate limiting configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RateLimitingConfig {
     /// Maximum requests per minute
     pub requests_per_minute: u32,
@@ -140,7 +883,7 @@ impl Default for RateLimitingConfig {
 }
 
 /// Export configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ExportConfig {
     /// Supported export formats
     pub formats: Vec<String>,
@@ -154,6 +897,15 @@ pub struct ExportConfig {
     pub include_metadata: bool,
     /// Whether to include statistics
     pub include_statistics: bool,
+    /// Where exports are written (local directory or S3-compatible bucket)
+    #[serde(default)]
+    pub destination: DestinationConfig,
+    /// Directory each processed thought is appended to, one JSON Lines file per session
+    #[serde(default)]
+    pub session_log_directory: Option<String>,
+    /// Thoughts appended to a session log between snapshots; logs are never compacted if unset
+    #[serde(default)]
+    pub session_log_snapshot_interval: Option<usize>,
 }
 
 impl Default for ExportConfig {
@@ -169,12 +921,15 @@ impl Default for ExportConfig {
             filename_template: "session_{session_id}_{timestamp}".to_string(),
             include_metadata: true,
             include_statistics: true,
+            destination: DestinationConfig::Local,
+            session_log_directory: None,
+            session_log_snapshot_interval: None,
         }
     }
 }
 
 /// Analytics configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AnalyticsConfig {
     /// Whether analytics is enabled
     pub enabled: bool,
@@ -188,6 +943,38 @@ pub struct AnalyticsConfig {
     pub detailed_metrics: bool,
     /// Retention period for metrics in days
     pub retention_days: u32,
+    /// Model prices used to estimate session costs
+    #[serde(default)]
+    pub pricing: PricingConfig,
+    /// Thresholds marking a thought as hard to read
+    #[serde(default)]
+    pub readability: ReadabilityConfig,
+    /// Thresholds for flagging unusual sessions, and where to send alerts
+    #[serde(default)]
+    pub anomaly: AnomalyConfig,
+    /// File keeping the aggregated metrics of completed sessions across restarts
+    #[serde(default)]
+    pub metrics_path: Option<String>,
+    /// Whether to redact pushed analytics and anomaly alerts
+    #[serde(default)]
+    pub anonymize_data: bool,
+    /// How analytics and alerts are redacted when `anonymize_data` is set
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    /// Most sessions pushed to the endpoint per request
+    #[serde(default = "default_export_batch_size")]
+    pub export_batch_size: usize,
+    /// Retries of a failed push before it is left for the next interval
+    #[serde(default = "default_export_retries")]
+    pub export_retries: u32,
+}
+
+fn default_export_batch_size() -> usize {
+    100
+}
+
+fn default_export_retries() -> u32 {
+    3
 }
 
 impl Default for AnalyticsConfig {
@@ -199,12 +986,20 @@ impl Default for AnalyticsConfig {
             collection_interval: 60,
             detailed_metrics: true,
             retention_days: 30,
+            pricing: PricingConfig::default(),
+            readability: ReadabilityConfig::default(),
+            anomaly: AnomalyConfig::default(),
+            metrics_path: None,
+            anonymize_data: false,
+            redaction: RedactionConfig::default(),
+            export_batch_size: default_export_batch_size(),
+            export_retries: default_export_retries(),
         }
     }
 }
 
 /// Logging configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LoggingConfig {
     /// Log level
     pub level: String,
@@ -237,7 +1032,7 @@ impl Default for LoggingConfig {
 }
 
 /// Security configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SecurityConfig {
     /// Whether authentication is required
     pub require_auth: bool,
@@ -251,6 +1046,22 @@ pub struct SecurityConfig {
     pub session_encryption: bool,
     /// Audit logging
     pub audit_logging: bool,
+    /// Roles granted to API keys
+    #[serde(default)]
+    pub api_keys: HashMap<String, Role>,
+}
+
+impl SecurityConfig {
+    /// Resolve the role of a caller from its API key
+    ///
+    /// Without API key validation every caller is an admin. With validation,
+    /// unknown or missing keys have no role.
+    pub fn role_for_key(&self, api_key: Option<&str>) -> Option<Role> {
+        if !self.api_key_validation {
+            return Some(Role::Admin);
+        }
+        api_key.and_then(|key| self.api_keys.get(key).copied())
+    }
 }
 
 impl Default for SecurityConfig {
@@ -262,12 +1073,44 @@ impl Default for SecurityConfig {
             rate_limiting_enabled: true,
             session_encryption: false,
             audit_logging: true,
+            api_keys: HashMap::new(),
+        }
+    }
+}
+
+/// Access role granted to an API key, from least to most privileged
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Can read, export and analyze sessions
+    Reader,
+    /// Can also add, edit and annotate thoughts
+    Contributor,
+    /// Can also merge and restore sessions
+    Admin,
+}
+
+impl Role {
+    /// Check whether this role includes the permissions of `required`
+    pub fn allows(&self, required: Role) -> bool {
+        *self >= required
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Reader => write!(f, "reader"),
+            Role::Contributor => write!(f, "contributor"),
+            Role::Admin => write!(f, "admin"),
         }
     }
 }
 
 /// Connection configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ConnectionConfig {
     /// Connection timeout in seconds
     pub timeout_seconds: u64,
@@ -297,20 +1140,23 @@ impl Default for ConnectionConfig {
 }
 
 /// UI configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct UIConfig {
-    /// Whether to show progress bars
+    /// Whether to show progress bars and spinners in the client
     pub show_progress_bars: bool,
     /// Whether to show thought visualization
     pub show_thought_visualization: bool,
     /// Whether to show session statistics
     pub show_session_stats: bool,
-    /// UI theme
+    /// Look of progress bars: `default`, `minimal` or `ascii`
     pub theme: String,
     /// Whether to enable color output
     pub color_output: bool,
     /// Whether to show timestamps
     pub show_timestamps: bool,
+    /// How thoughts are rendered in the stderr thought log
+    #[serde(default)]
+    pub thought_style: VisualizationStyle,
 }
 
 impl Default for UIConfig {
@@ -322,10 +1168,364 @@ impl Default for UIConfig {
             theme: "default".to_string(),
             color_output: true,
             show_timestamps: true,
+            thought_style: VisualizationStyle::Boxed,
+        }
+    }
+}
+
+/// Environment variable prefix for server configuration fields
+pub const SERVER_ENV_PREFIX: &str = "SEQUENTIAL_THINKING__";
+
+/// Environment variable prefix for client configuration fields
+pub const CLIENT_ENV_PREFIX: &str = "SEQUENTIAL_THINKING_CLIENT__";
+
+/// Configuration whose secrets are never serialized
+///
+/// Secrets such as S3 credentials are left out when a configuration is saved
+/// or printed, so they are also lost when it is round-tripped through JSON.
+pub trait SecretFields {
+    /// Copy the secrets of `source` into the fields this configuration lacks
+    fn keep_secrets_from(&mut self, _source: &Self) {}
+}
+
+impl SecretFields for ServerConfig {
+    fn keep_secrets_from(&mut self, source: &Self) {
+        if let (DestinationConfig::S3(ours), DestinationConfig::S3(theirs)) =
+            (&mut self.export.destination, &source.export.destination)
+        {
+            if ours.secret_access_key.is_none() {
+                ours.secret_access_key = theirs.secret_access_key.clone();
+            }
+            if ours.session_token.is_none() {
+                ours.session_token = theirs.session_token.clone();
+            }
+        }
+    }
+}
+
+impl SecretFields for ClientConfig {}
+
+/// Apply environment variable overrides to a configuration
+///
+/// Variables named `<prefix><SECTION>__<FIELD>` replace the field at that
+/// path; variables without the prefix are ignored. See the module
+/// documentation for the naming and value conventions.
+pub fn apply_env_overrides<T, I>(
+    config: &T,
+    prefix: &str,
+    vars: I,
+) -> Result<T, Box<dyn std::error::Error>>
+where
+    T: Serialize + DeserializeOwned + Clone + SecretFields,
+    I: IntoIterator<Item = (String, String)>,
+{
+    let mut overrides: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(prefix) && name.len() > prefix.len())
+        .collect();
+    if overrides.is_empty() {
+        return Ok(config.clone());
+    }
+    // Apply whole sections before the fields inside them
+    overrides.sort();
+
+    let mut root = serde_json::to_value(config)?;
+    for (name, raw) in &overrides {
+        let path: Vec<String> = name[prefix.len()..]
+            .split("__")
+            .map(|segment| segment.to_lowercase())
+            .collect();
+        if path.iter().any(|segment| segment.is_empty()) {
+            return Err(format!("Invalid configuration variable name: {name}").into());
+        }
+
+        let (field, sections) = path.split_last().expect("path is not empty");
+        let mut current = &mut root;
+        for section in sections {
+            if current.is_null() {
+                *current = serde_json::json!({});
+            }
+            current = current
+                .as_object_mut()
+                .ok_or_else(|| format!("{name}: '{section}' is not a configuration section"))?
+                .entry(section.clone())
+                .or_insert(serde_json::Value::Null);
+        }
+        if current.is_null() {
+            *current = serde_json::json!({});
+        }
+        let parent = current
+            .as_object_mut()
+            .ok_or_else(|| format!("{name}: parent of '{field}' is not a configuration section"))?;
+        let value = env_value(parent.get(field), raw).map_err(|e| format!("{name}: {e}"))?;
+        parent.insert(field.clone(), value);
+    }
+
+    let mut overridden: T = serde_json::from_value(root)
+        .map_err(|e| format!("Invalid configuration from environment: {e}"))?;
+    overridden.keep_secrets_from(config);
+    Ok(overridden)
+}
+
+/// Parse an environment value according to the field it replaces
+fn env_value(existing: Option<&serde_json::Value>, raw: &str) -> Result<serde_json::Value, String> {
+    use serde_json::Value;
+
+    match existing {
+        Some(Value::String(_)) => Ok(Value::String(raw.to_string())),
+        Some(Value::Bool(_)) => match raw.to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Ok(Value::Bool(true)),
+            "false" | "0" | "no" | "off" => Ok(Value::Bool(false)),
+            _ => Err(format!("expected a boolean, got '{raw}'")),
+        },
+        Some(Value::Number(_)) => match serde_json::from_str(raw.trim()) {
+            Ok(Value::Number(n)) => Ok(Value::Number(n)),
+            _ => Err(format!("expected a number, got '{raw}'")),
+        },
+        Some(Value::Array(_)) if !raw.trim_start().starts_with('[') => Ok(Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        )),
+        // Sections with a string form, such as the transport
+        Some(Value::Object(_)) if !raw.trim_start().starts_with('{') => {
+            Ok(Value::String(raw.to_string()))
+        }
+        Some(Value::Array(_)) | Some(Value::Object(_)) => {
+            serde_json::from_str(raw).map_err(|e| format!("expected JSON: {e}"))
+        }
+        // Unset optional fields and new map entries: JSON if it parses,
+        // otherwise a string
+        Some(Value::Null) | None => {
+            Ok(serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())))
+        }
+    }
+}
+
+/// Layout of a configuration file, with optional server and client sections
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigFile {
+    /// Server configuration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<ServerConfig>,
+    /// Client configuration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<ClientConfig>,
+    /// Named overlays merged over the base configuration when selected
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, serde_json::Value>,
+}
+
+/// Read a configuration file into a JSON document, applying a profile
+///
+/// TOML and JSON files are supported. See [`apply_profile`] for how the
+/// profile is selected.
+pub fn read_config_document<P: AsRef<Path>>(
+    path: P,
+    profile: Option<&str>,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let mut document = parse_config_document(path.as_ref())?;
+    apply_profile(&mut document, profile)?;
+    Ok(document)
+}
+
+/// Parse a TOML or JSON configuration file
+fn parse_config_document(path: &Path) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(path)?;
+    let mut document = match path.extension().and_then(|s| s.to_str()) {
+        Some("toml") => toml::from_str(&content)?,
+        Some("json") => serde_json::from_str(&content)?,
+        _ => return Err("Unsupported configuration file format".into()),
+    };
+    upgrade_legacy_port(&mut document);
+    Ok(document)
+}
+
+/// Move the legacy top-level `server.port` into the transport
+fn upgrade_legacy_port(document: &mut serde_json::Value) {
+    let Some(server) = document
+        .get_mut("server")
+        .and_then(serde_json::Value::as_object_mut)
+    else {
+        return;
+    };
+    let Some(port) = server.remove("port") else {
+        return;
+    };
+    match server.get_mut("transport") {
+        Some(serde_json::Value::String(name)) if name == "http" || name == "ws" => {
+            let name = name.clone();
+            server.insert(
+                "transport".to_string(),
+                serde_json::json!({ "type": name, "port": port }),
+            );
+        }
+        Some(serde_json::Value::Object(transport)) if transport.contains_key("type") => {
+            transport.entry("port").or_insert(port);
+        }
+        _ => {}
+    }
+}
+
+/// Remove the `profiles` table from a document, merging the selected one
+///
+/// Tables in the profile are merged key by key; any other value replaces
+/// the base value. Selecting a profile the document does not define is an
+/// error.
+pub fn apply_profile(
+    document: &mut serde_json::Value,
+    profile: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut profiles = document
+        .as_object_mut()
+        .and_then(|root| root.remove("profiles"))
+        .unwrap_or_else(|| serde_json::json!({}));
+
+    let Some(name) = profile else {
+        return Ok(());
+    };
+    let overlay = profiles
+        .as_object_mut()
+        .and_then(|profiles| profiles.remove(name))
+        .ok_or_else(|| {
+            let mut available: Vec<&String> = profiles
+                .as_object()
+                .map(|profiles| profiles.keys().collect())
+                .unwrap_or_default();
+            available.sort();
+            if available.is_empty() {
+                format!("Unknown configuration profile '{name}': no profiles are defined")
+            } else {
+                let available: Vec<&str> = available.iter().map(|s| s.as_str()).collect();
+                format!(
+                    "Unknown configuration profile '{name}' (available: {})",
+                    available.join(", ")
+                )
+            }
+        })?;
+    merge_values(document, overlay);
+    upgrade_legacy_port(document);
+    Ok(())
+}
+
+/// Recursively merge `overlay` into `base`
+fn merge_values(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Generate the JSON Schema of a configuration type
+///
+/// Objects are closed (`additionalProperties: false`) so editors flag the
+/// same unknown keys that [`validate_file`] rejects.
+pub fn config_schema<T: JsonSchema>() -> serde_json::Value {
+    let generator = SchemaSettings::draft07()
+        .with_visitor(ClosedObjects)
+        .into_generator();
+    serde_json::to_value(generator.into_root_schema_for::<T>()).expect("schemas serialize to JSON")
+}
+
+/// Schema visitor that forbids properties not declared by a struct
+#[derive(Debug, Clone)]
+struct ClosedObjects;
+
+impl Visitor for ClosedObjects {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        visit::visit_schema_object(self, schema);
+        if let Some(object) = schema.object.as_mut() {
+            if !object.properties.is_empty() && object.additional_properties.is_none() {
+                object.additional_properties = Some(Box::new(Schema::Bool(false)));
+            }
         }
     }
 }
 
+/// Strictly validate a configuration file
+///
+/// Unlike [`ConfigManager::load_from_file`], unknown keys, mistyped values
+/// and out-of-range values are all reported, each prefixed with the path of
+/// the offending field (e.g. `server.thinking.min_thought_length`). Every
+/// profile is checked as merged over the base configuration.
+pub fn validate_file<P: AsRef<Path>>(path: P) -> Result<ConfigFile, Vec<String>> {
+    let value = parse_config_document(path.as_ref()).map_err(|e| vec![e.to_string()])?;
+    validate_value(value)
+}
+
+/// Strictly validate a parsed configuration file and its profiles
+fn validate_value(value: serde_json::Value) -> Result<ConfigFile, Vec<String>> {
+    let (file, mut errors) = check_document(value.clone());
+    let Some(file) = file else {
+        return Err(errors);
+    };
+
+    let mut names: Vec<&String> = file.profiles.keys().collect();
+    names.sort();
+    for name in names {
+        let mut merged = value.clone();
+        if let Err(e) = apply_profile(&mut merged, Some(name)) {
+            errors.push(format!("profiles.{name}: {e}"));
+            continue;
+        }
+        let (_, profile_errors) = check_document(merged);
+        for error in profile_errors {
+            // Problems inherited from the base are already reported
+            if !errors.contains(&error) {
+                errors.push(format!("profiles.{name}.{error}"));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(file)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Deserialize a document strictly, collecting every problem found
+fn check_document(value: serde_json::Value) -> (Option<ConfigFile>, Vec<String>) {
+    let mut unknown = Vec::new();
+    let mut record_unknown = |path: serde_ignored::Path| {
+        // Optional sections show up as `?` segments
+        let path = path.to_string();
+        let segments: Vec<&str> = path.split('.').filter(|s| *s != "?").collect();
+        unknown.push(format!("{}: unknown field", segments.join(".")));
+    };
+    let parsed: Result<ConfigFile, _> = serde_path_to_error::deserialize(
+        serde_ignored::Deserializer::new(value, &mut record_unknown),
+    );
+
+    let mut errors = unknown;
+    let file = match parsed {
+        Ok(file) => file,
+        Err(e) => {
+            errors.push(format!("{}: {}", e.path(), e.inner()));
+            return (None, errors);
+        }
+    };
+
+    let mut manager = ConfigManager::new();
+    manager.server_config = file.server.clone();
+    manager.client_config = file.client.clone();
+    if let Err(range_errors) = manager.validate() {
+        errors.extend(range_errors);
+    }
+    (Some(file), errors)
+}
+
 /// Configuration manager
 pub struct ConfigManager {
     /// Server configuration
@@ -334,6 +1534,8 @@ pub struct ConfigManager {
     client_config: Option<ClientConfig>,
     /// Configuration file path
     config_path: Option<String>,
+    /// Profile merged over the base configuration when loading
+    profile: Option<String>,
 }
 
 impl ConfigManager {
@@ -343,6 +1545,7 @@ impl ConfigManager {
             server_config: None,
             client_config: None,
             config_path: None,
+            profile: None,
         }
     }
 
@@ -366,24 +1569,28 @@ impl ConfigManager {
         Ok(())
     }
 
+    /// Select the profile merged over the base configuration when loading
+    pub fn set_profile(&mut self, profile: Option<String>) {
+        self.profile = profile;
+    }
+
     /// Load configuration from TOML string
     pub fn load_from_toml(&mut self, content: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let config: toml::Value = toml::from_str(content)?;
-
-        if let Some(server) = config.get("server") {
-            self.server_config = Some(server.clone().try_into()?);
-        }
-
-        if let Some(client) = config.get("client") {
-            self.client_config = Some(client.clone().try_into()?);
-        }
-
-        Ok(())
+        self.load_document(toml::from_str(content)?)
     }
 
     /// Load configuration from JSON string
     pub fn load_from_json(&mut self, content: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let config: serde_json::Value = serde_json::from_str(content)?;
+        self.load_document(serde_json::from_str(content)?)
+    }
+
+    /// Load the server and client sections of a parsed configuration file
+    fn load_document(
+        &mut self,
+        mut config: serde_json::Value,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        upgrade_legacy_port(&mut config);
+        apply_profile(&mut config, self.profile.as_deref())?;
 
         if let Some(server) = config.get("server") {
             self.server_config = Some(serde_json::from_value(server.clone())?);
@@ -397,7 +1604,13 @@ impl ConfigManager {
     }
 
     /// Load configuration from environment variables
-    pub fn load_from_env(&mut self) {
+    ///
+    /// Applies the prefixed overrides described in the module documentation,
+    /// as well as the legacy `SEQUENTIAL_THINKING_SERVER_NAME`,
+    /// `SEQUENTIAL_THINKING_TRANSPORT`, `SEQUENTIAL_THINKING_PORT`,
+    /// `SEQUENTIAL_THINKING_SERVER_URL` and `SEQUENTIAL_THINKING_TIMEOUT`
+    /// variables.
+    pub fn load_from_env(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         // Server configuration from environment
         if let Ok(name) = std::env::var("SEQUENTIAL_THINKING_SERVER_NAME") {
             self.server_config
@@ -408,14 +1621,17 @@ impl ConfigManager {
         if let Ok(transport) = std::env::var("SEQUENTIAL_THINKING_TRANSPORT") {
             self.server_config
                 .get_or_insert_with(ServerConfig::default)
-                .transport = transport;
+                .transport = transport.parse()?;
         }
 
         if let Ok(port) = std::env::var("SEQUENTIAL_THINKING_PORT") {
             if let Ok(port_num) = port.parse::<u16>() {
-                self.server_config
+                // Ignored for stdio, as before
+                let _ = self
+                    .server_config
                     .get_or_insert_with(ServerConfig::default)
-                    .port = port_num;
+                    .transport
+                    .set_address(None, Some(port_num));
             }
         }
 
@@ -433,6 +1649,24 @@ impl ConfigManager {
                     .timeout_seconds = timeout_num;
             }
         }
+
+        let vars: Vec<(String, String)> = std::env::vars().collect();
+        if vars
+            .iter()
+            .any(|(name, _)| name.starts_with(SERVER_ENV_PREFIX))
+        {
+            let server = self.server_config.get_or_insert_with(ServerConfig::default);
+            *server = apply_env_overrides(server, SERVER_ENV_PREFIX, vars.clone())?;
+        }
+        if vars
+            .iter()
+            .any(|(name, _)| name.starts_with(CLIENT_ENV_PREFIX))
+        {
+            let client = self.client_config.get_or_insert_with(ClientConfig::default);
+            *client = apply_env_overrides(client, CLIENT_ENV_PREFIX, vars)?;
+        }
+
+        Ok(())
     }
 
     /// Get server configuration
@@ -470,33 +1704,17 @@ impl ConfigManager {
     }
 
     /// Validate configuration
+    ///
+    /// Errors are prefixed with the path of the offending field, starting
+    /// with the `server` or `client` section.
     pub fn validate(&self) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
 
-        // Validate server configuration
-        if let Some(ref server_config) = self.server_config {
-            if server_config.name.is_empty() {
-                errors.push("Server name cannot be empty".to_string());
-            }
-
-            if server_config.port == 0 {
-                errors.push("Server port must be greater than 0".to_string());
-            }
-
-            if server_config.thinking.max_thoughts_per_session == 0 {
-                errors.push("Max thoughts per session must be greater than 0".to_string());
-            }
+        if let Some(Err(server_errors)) = self.server_config.as_ref().map(ServerConfig::validate) {
+            errors.extend(server_errors.into_iter().map(|e| format!("server.{e}")));
         }
-
-        // Validate client configuration
-        if let Some(ref client_config) = self.client_config {
-            if client_config.server_url.is_empty() {
-                errors.push("Server URL cannot be empty".to_string());
-            }
-
-            if client_config.timeout_seconds == 0 {
-                errors.push("Timeout must be greater than 0".to_string());
-            }
+        if let Some(Err(client_errors)) = self.client_config.as_ref().map(ClientConfig::validate) {
+            errors.extend(client_errors.into_iter().map(|e| format!("client.{e}")));
         }
 
         if errors.is_empty() {
@@ -520,6 +1738,7 @@ pub mod utils {
     /// Load configuration from default locations
     pub fn load_default_config() -> Result<ConfigManager, Box<dyn std::error::Error>> {
         let mut manager = ConfigManager::new();
+        manager.set_profile(std::env::var("SEQUENTIAL_THINKING_PROFILE").ok());
 
         // Try to load from default config file
         let default_paths = [
@@ -538,7 +1757,7 @@ pub mod utils {
         }
 
         // Load from environment variables
-        manager.load_from_env();
+        manager.load_from_env()?;
 
         // Validate configuration
         let _ = manager.validate();
@@ -565,12 +1784,9 @@ pub mod utils {
         if !override_config.version.is_empty() {
             base.version = override_config.version.clone();
         }
-        if !override_config.transport.is_empty() {
+        if override_config.transport != TransportConfig::default() {
             base.transport = override_config.transport.clone();
         }
-        if override_config.port != 0 {
-            base.port = override_config.port;
-        }
         // Merge other fields as needed
     }
 }
@@ -583,8 +1799,8 @@ mod tests {
     fn test_server_config_default() {
         let config = ServerConfig::default();
         assert_eq!(config.name, "ultrafast-sequential-thinking");
-        assert_eq!(config.transport, "stdio");
-        assert_eq!(config.port, 8080);
+        assert_eq!(config.transport, TransportConfig::Stdio);
+        assert_eq!(config.transport.address(), None);
     }
 
     #[test]
@@ -595,6 +1811,57 @@ mod tests {
         assert_eq!(config.retry_attempts, 3);
     }
 
+    #[test]
+    fn test_named_servers() {
+        let config: ClientConfig = serde_json::from_value(serde_json::json!({
+            "server_url": "stdio://",
+            "timeout_seconds": 30,
+            "retry_attempts": 3,
+            "thinking": ClientThinkingConfig::default(),
+            "connection": ConnectionConfig::default(),
+            "ui": UIConfig::default(),
+            "api_key": "shared-key",
+            "default_server": "local",
+            "servers": {
+                "local": { "server_url": "http://localhost:8080" },
+                "prod": {
+                    "server_url": "https://thinking.example.com",
+                    "api_key_env": "TEST_NAMED_SERVERS_PROD_KEY",
+                    "timeout_seconds": 90,
+                    "connection": { "timeout_seconds": 10, "keep_alive_interval": 30, "max_retries": 5, "retry_delay": 2, "connection_pooling": false, "pool_size": 1 }
+                }
+            }
+        }))
+        .unwrap();
+        assert!(config.validate().is_ok());
+
+        let local = config.for_server("local").unwrap();
+        assert_eq!(local.server_url, "http://localhost:8080");
+        assert_eq!(local.api_key.as_deref(), Some("shared-key"));
+        assert_eq!(local.timeout_seconds, 30);
+
+        assert!(config.for_server("prod").unwrap_err().contains("not set"));
+        std::env::set_var("TEST_NAMED_SERVERS_PROD_KEY", "prod-key");
+        let prod = config.for_server("prod").unwrap();
+        assert_eq!(prod.api_key.as_deref(), Some("prod-key"));
+        assert_eq!(prod.timeout_seconds, 90);
+        assert!(!prod.connection.connection_pooling);
+
+        let error = config.for_server("staging").unwrap_err();
+        assert!(error.contains("available: local, prod"), "{error}");
+
+        let mut broken = config.clone();
+        broken.default_server = Some("staging".to_string());
+        broken.servers.get_mut("local").unwrap().server_url.clear();
+        assert_eq!(
+            broken.validate().unwrap_err(),
+            [
+                "servers.local.server_url: must not be empty",
+                "default_server: 'staging' is not one of the configured servers"
+            ]
+        );
+    }
+
     #[test]
     fn test_thinking_config_default() {
         let config = ThinkingConfig::default();
@@ -613,6 +1880,255 @@ mod tests {
         assert_eq!(loaded_config.name, "ultrafast-sequential-thinking");
     }
 
+    #[test]
+    fn test_transport_config() {
+        assert_eq!("stdio".parse(), Ok(TransportConfig::Stdio));
+        assert_eq!(
+            "http".parse(),
+            Ok(TransportConfig::http(DEFAULT_HOST, DEFAULT_PORT))
+        );
+        assert_eq!(
+            "http://[::1]:9000".parse(),
+            Ok(TransportConfig::http("[::1]", 9000))
+        );
+        let ws: TransportConfig = "ws://localhost:9001/mcp".parse().unwrap();
+        assert_eq!(ws.to_string(), "ws://localhost:9001/mcp");
+        assert!("https://localhost".parse::<TransportConfig>().is_err());
+        assert!("http://localhost:http".parse::<TransportConfig>().is_err());
+        assert!("smtp".parse::<TransportConfig>().is_err());
+
+        // String and table forms both load; unknown transports fail at load
+        let mut manager = ConfigManager::new();
+        let mut server = serde_json::to_value(ServerConfig::default()).unwrap();
+        server["transport"] = serde_json::json!({ "type": "http", "host": "127.0.0.1" });
+        manager
+            .load_from_json(&serde_json::json!({ "server": server }).to_string())
+            .unwrap();
+        assert_eq!(
+            manager.get_server_config().transport,
+            TransportConfig::http("127.0.0.1", DEFAULT_PORT)
+        );
+        server["transport"] = "carrier-pigeon".into();
+        assert!(manager
+            .load_from_json(&serde_json::json!({ "server": server }).to_string())
+            .is_err());
+
+        // The legacy top-level port moves into the transport
+        server["transport"] = "http".into();
+        server["port"] = 9090.into();
+        manager
+            .load_from_json(&serde_json::json!({ "server": server }).to_string())
+            .unwrap();
+        assert_eq!(
+            manager.get_server_config().transport.address(),
+            Some((DEFAULT_HOST, 9090))
+        );
+
+        let mut config = ServerConfig::default();
+        assert!(config.transport.set_address(None, Some(1)).is_err());
+        config.transport = TransportConfig::Ws {
+            host: DEFAULT_HOST.to_string(),
+            port: DEFAULT_PORT,
+            path: "/".to_string(),
+            tls: None,
+        };
+        assert!(config.validate().unwrap_err()[0].starts_with("transport:"));
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let vars = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        let server = apply_env_overrides(
+            &ServerConfig::default(),
+            SERVER_ENV_PREFIX,
+            vars(&[
+                ("SEQUENTIAL_THINKING__TRANSPORT", "http://127.0.0.1:9090"),
+                ("SEQUENTIAL_THINKING__NAME", "12345"),
+                (
+                    "SEQUENTIAL_THINKING__THINKING__MAX_THOUGHTS_PER_SESSION",
+                    "250",
+                ),
+                (
+                    "SEQUENTIAL_THINKING__THINKING__THOUGHT_TIMEOUT_SECONDS",
+                    "30",
+                ),
+                (
+                    "SEQUENTIAL_THINKING__THINKING__RATE_LIMITING__ENABLED",
+                    "no",
+                ),
+                ("SEQUENTIAL_THINKING__EXPORT__FORMATS", "json, markdown"),
+                (
+                    "SEQUENTIAL_THINKING__SECURITY__API_KEYS",
+                    r#"{"ci-key": "contributor"}"#,
+                ),
+                ("SEQUENTIAL_THINKING__UI__THOUGHT_STYLE", "compact"),
+                ("SEQUENTIAL_THINKING_CLIENT__TIMEOUT_SECONDS", "5"),
+                ("UNRELATED", "x"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(server.transport, TransportConfig::http("127.0.0.1", 9090));
+        assert_eq!(server.name, "12345");
+        assert_eq!(server.thinking.max_thoughts_per_session, 250);
+        assert_eq!(server.thinking.thought_timeout_seconds, Some(30));
+        assert!(!server.thinking.rate_limiting.enabled);
+        assert_eq!(server.export.formats, vec!["json", "markdown"]);
+        assert_eq!(
+            server.security.api_keys.get("ci-key"),
+            Some(&Role::Contributor)
+        );
+        assert_eq!(server.ui.thought_style, VisualizationStyle::Compact);
+
+        // Overrides round-trip the configuration without losing secrets
+        let mut with_secret = ServerConfig::default();
+        with_secret.export.destination = DestinationConfig::S3(
+            crate::export::S3Config::new("bucket".to_string())
+                .with_credentials("id".to_string(), "secret".to_string()),
+        );
+        let overridden = apply_env_overrides(
+            &with_secret,
+            SERVER_ENV_PREFIX,
+            vars(&[("SEQUENTIAL_THINKING__NAME", "renamed")]),
+        )
+        .unwrap();
+        assert_eq!(
+            overridden.export.destination,
+            with_secret.export.destination
+        );
+
+        // Saved configurations leave the secrets out
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let mut manager = ConfigManager::new();
+        manager.set_server_config(overridden);
+        manager.save_to_file(&path).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("\"bucket\""), "{saved}");
+        assert!(!saved.contains("secret"), "{saved}");
+
+        let client = apply_env_overrides(
+            &ClientConfig::default(),
+            CLIENT_ENV_PREFIX,
+            vars(&[
+                ("SEQUENTIAL_THINKING_CLIENT__TIMEOUT_SECONDS", "5"),
+                ("SEQUENTIAL_THINKING_CLIENT__UI__COLOR_OUTPUT", "false"),
+                ("SEQUENTIAL_THINKING_CLIENT__API_KEY", "secret"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(client.timeout_seconds, 5);
+        assert!(!client.ui.color_output);
+        assert_eq!(client.api_key.as_deref(), Some("secret"));
+
+        for bad in [
+            ("SEQUENTIAL_THINKING__TRANSPORT", "carrier-pigeon"),
+            (
+                "SEQUENTIAL_THINKING__THINKING__MAX_BRANCHES_PER_SESSION",
+                "eighty",
+            ),
+            ("SEQUENTIAL_THINKING__NAME__FIRST", "1"),
+            ("SEQUENTIAL_THINKING__THINKING____X", "1"),
+        ] {
+            assert!(
+                apply_env_overrides(&ServerConfig::default(), SERVER_ENV_PREFIX, vars(&[bad]))
+                    .is_err(),
+                "{bad:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_strict_validation() {
+        assert!(validate_value(serde_json::json!({
+            "server": ServerConfig::default(),
+            "client": ClientConfig::default(),
+        }))
+        .is_ok());
+
+        let mut server = serde_json::to_value(ServerConfig::default()).unwrap();
+        server["thinking"]["min_thought_length"] = 500.into();
+        server["thinking"]["max_thought_length"] = 100.into();
+        server["thinking"]["max_thoghts"] = 5.into();
+        server["export"]["formats"] = serde_json::json!(["json", "docx"]);
+        let errors =
+            validate_value(serde_json::json!({ "server": server, "extra": true })).unwrap_err();
+        for expected in [
+            "server.thinking.max_thoghts: unknown field",
+            "extra: unknown field",
+            "server.thinking.min_thought_length: must not exceed thinking.max_thought_length (500 > 100)",
+            "server.export.formats[1]: Unknown export format: docx",
+        ] {
+            assert!(errors.iter().any(|e| e == expected), "missing {expected:?} in {errors:?}");
+        }
+
+        let errors = validate_value(serde_json::json!({ "client": { "timeout_seconds": "soon" } }))
+            .unwrap_err();
+        assert!(errors[0].starts_with("client.timeout_seconds: invalid type"));
+
+        let schema = config_schema::<ConfigFile>();
+        assert_eq!(schema["additionalProperties"], false);
+        assert_eq!(
+            schema["definitions"]["ThinkingConfig"]["additionalProperties"],
+            false
+        );
+        assert!(
+            schema["definitions"]["SecurityConfig"]["properties"]["api_keys"]
+                ["additionalProperties"]
+                .is_object()
+        );
+    }
+
+    #[test]
+    fn test_profiles() {
+        let document = serde_json::json!({
+            "server": ServerConfig::default(),
+            "profiles": {
+                "prod": {
+                    "server": {
+                        "transport": "http",
+                        "thinking": { "max_thoughts_per_session": 500 }
+                    }
+                },
+                "broken": {
+                    "server": { "thinking": { "min_thought_length": 20000 } }
+                }
+            }
+        });
+        let content = document.to_string();
+
+        let mut manager = ConfigManager::new();
+        manager.load_from_json(&content).unwrap();
+        let base = manager.get_server_config();
+        assert_eq!(base.transport, TransportConfig::Stdio);
+        assert!(base.validate().is_ok());
+        assert_eq!(base.thinking.max_thoughts_per_session, 100);
+
+        manager.set_profile(Some("prod".to_string()));
+        manager.load_from_json(&content).unwrap();
+        let prod = manager.get_server_config();
+        assert_eq!(prod.name, "ultrafast-sequential-thinking");
+        assert_eq!(
+            prod.transport,
+            TransportConfig::http(DEFAULT_HOST, DEFAULT_PORT)
+        );
+        assert_eq!(prod.thinking.max_thoughts_per_session, 500);
+        assert_eq!(prod.thinking.max_branches_per_session, 10);
+
+        manager.set_profile(Some("staging".to_string()));
+        let error = manager.load_from_json(&content).unwrap_err().to_string();
+        assert!(error.contains("available: broken, prod"), "{error}");
+
+        let errors = validate_value(document).unwrap_err();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(errors[0].starts_with("profiles.broken.server.thinking.min_thought_length"));
+    }
+
     #[test]
     fn test_config_validation() {
         let mut manager = ConfigManager::new();
@@ -624,5 +2140,15 @@ mod tests {
 
         let result = manager.validate();
         assert!(result.is_err());
+
+        let mut server_config = ServerConfig::default();
+        server_config.thinking.backpressure = Some(BackpressureConfig {
+            max_in_flight_calls: 0,
+            ..Default::default()
+        });
+        assert_eq!(
+            server_config.validate().unwrap_err(),
+            ["thinking.backpressure.max_in_flight_calls: must be greater than 0"]
+        );
     }
 }
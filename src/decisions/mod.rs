@@ -0,0 +1,298 @@
+//! # Decisions Module
+//!
+//! Decision log extracted from a session's thoughts.
+//!
+//! A thought records a decision when its metadata has `decision` set to
+//! `true` (or to a status name), or when its text starts with `decision:`.
+//! Each decision is tracked through its lifecycle: it is proposed when made,
+//! accepted once the session concludes or when marked accepted, and reversed
+//! when a later thought revises it. The log renders as Architecture Decision
+//! Records in Markdown.
+
+use serde::{Deserialize, Serialize};
+
+use crate::thinking::{SharedThought, ThoughtData};
+
+/// Metadata key marking a thought as a decision
+pub const DECISION_KEY: &str = "decision";
+
+/// Text prefix marking a thought as a decision
+pub const DECISION_PREFIX: &str = "decision:";
+
+/// Where a decision stands
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecisionStatus {
+    /// Made but not yet settled
+    #[default]
+    Proposed,
+    /// Settled by the end of the session or marked accepted
+    Accepted,
+    /// Revised by a later thought
+    Reversed,
+}
+
+impl std::str::FromStr for DecisionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "proposed" => Ok(Self::Proposed),
+            "accepted" => Ok(Self::Accepted),
+            "reversed" => Ok(Self::Reversed),
+            _ => Err(format!("Unknown decision status: {s}")),
+        }
+    }
+}
+
+impl std::fmt::Display for DecisionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Proposed => "Proposed",
+            Self::Accepted => "Accepted",
+            Self::Reversed => "Reversed",
+        };
+        f.write_str(name)
+    }
+}
+
+/// How a thought is marked as a decision, if it is one
+///
+/// Returns the status the thought sets explicitly, if any.
+fn marking(thought: &ThoughtData) -> Option<Option<DecisionStatus>> {
+    match thought
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(DECISION_KEY))
+    {
+        Some(serde_json::Value::Bool(marked)) => marked.then_some(None),
+        Some(serde_json::Value::String(status)) => Some(status.parse().ok()),
+        _ => has_prefix(&thought.thought).then_some(None),
+    }
+}
+
+/// Check whether text starts with the decision prefix, ignoring case
+fn has_prefix(text: &str) -> bool {
+    text.trim_start()
+        .get(..DECISION_PREFIX.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(DECISION_PREFIX))
+}
+
+/// Check whether a thought records a decision
+pub fn is_decision(thought: &ThoughtData) -> bool {
+    marking(thought).is_some()
+}
+
+/// Text of a decision without the decision prefix
+pub fn decision_text(thought: &ThoughtData) -> &str {
+    let text = thought.thought.trim();
+    if has_prefix(text) {
+        text[DECISION_PREFIX.len()..].trim_start()
+    } else {
+        text
+    }
+}
+
+/// Decision recorded in a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    /// Record number, starting at 1
+    pub id: usize,
+    /// Number of the thought making the decision
+    pub thought_number: u32,
+    /// Branch of the thought, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_id: Option<String>,
+    /// First line of the decision
+    pub title: String,
+    /// Full decision text
+    pub decision: String,
+    /// Thought the decision follows from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// Current status
+    pub status: DecisionStatus,
+    /// Who made the decision
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// When the decision was made
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Record this decision replaces
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<usize>,
+    /// Thought that reversed the decision, and its text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reversed_by: Option<(u32, String)>,
+}
+
+/// Decisions of a session, in the order they were made
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DecisionLog {
+    /// Recorded decisions
+    pub decisions: Vec<Decision>,
+}
+
+impl DecisionLog {
+    /// Build the log of a session's thoughts
+    pub fn from_thoughts(thoughts: &[SharedThought]) -> Self {
+        let concluded = thoughts.last().is_some_and(|t| !t.next_thought_needed);
+        let mut decisions: Vec<Decision> = Vec::new();
+
+        for (position, thought) in thoughts.iter().enumerate() {
+            // A revision reverses every decision made by the thought it revises
+            let mut supersedes = None;
+            if let Some(revised) = thought.revises_thought.filter(|_| thought.is_revision()) {
+                for decision in decisions.iter_mut().filter(|d| {
+                    d.thought_number == revised
+                        && d.branch_id == thought.branch_id
+                        && d.reversed_by.is_none()
+                }) {
+                    decision.status = DecisionStatus::Reversed;
+                    decision.reversed_by =
+                        Some((thought.thought_number, decision_text(thought).to_string()));
+                    supersedes = Some(decision.id);
+                }
+            }
+
+            let Some(explicit) = marking(thought) else {
+                continue;
+            };
+            let text = decision_text(thought).to_string();
+            let context = thoughts[..position]
+                .iter()
+                .rev()
+                .find(|t| t.branch_id == thought.branch_id)
+                .map(|t| t.thought.clone());
+            decisions.push(Decision {
+                id: decisions.len() + 1,
+                thought_number: thought.thought_number,
+                branch_id: thought.branch_id.clone(),
+                title: text.lines().next().unwrap_or_default().to_string(),
+                decision: text,
+                context,
+                status: explicit.unwrap_or_default(),
+                author: thought.author.clone(),
+                decided_at: thought.timestamp,
+                supersedes,
+                reversed_by: None,
+            });
+        }
+
+        if concluded {
+            for decision in &mut decisions {
+                if decision.status == DecisionStatus::Proposed {
+                    decision.status = DecisionStatus::Accepted;
+                }
+            }
+        }
+        Self { decisions }
+    }
+
+    /// Decisions with a given status
+    pub fn with_status(&self, status: DecisionStatus) -> impl Iterator<Item = &Decision> {
+        self.decisions.iter().filter(move |d| d.status == status)
+    }
+
+    /// Render the log as Architecture Decision Records
+    pub fn to_markdown(&self, session_id: Option<&str>) -> String {
+        let mut markdown = String::from("# Decision Log\n\n");
+        if let Some(session_id) = session_id {
+            markdown.push_str(&format!("**Session ID:** {session_id}\n\n"));
+        }
+        if self.decisions.is_empty() {
+            markdown.push_str("No decisions were recorded.\n");
+            return markdown;
+        }
+
+        for decision in &self.decisions {
+            markdown.push_str(&format!(
+                "## ADR-{:03}: {}\n\n",
+                decision.id, decision.title
+            ));
+            markdown.push_str(&format!("- **Status:** {}\n", decision.status));
+            match &decision.branch_id {
+                Some(branch_id) => markdown.push_str(&format!(
+                    "- **Thought:** {} (branch {branch_id})\n",
+                    decision.thought_number
+                )),
+                None => markdown.push_str(&format!("- **Thought:** {}\n", decision.thought_number)),
+            }
+            if let Some(decided_at) = decision.decided_at {
+                markdown.push_str(&format!(
+                    "- **Date:** {}\n",
+                    decided_at.format("%Y-%m-%d %H:%M:%S UTC")
+                ));
+            }
+            if let Some(author) = &decision.author {
+                markdown.push_str(&format!("- **Author:** {author}\n"));
+            }
+            if let Some(supersedes) = decision.supersedes {
+                markdown.push_str(&format!("- **Supersedes:** ADR-{supersedes:03}\n"));
+            }
+            markdown.push('\n');
+
+            if let Some(context) = &decision.context {
+                markdown.push_str(&format!("### Context\n\n{context}\n\n"));
+            }
+            markdown.push_str(&format!("### Decision\n\n{}\n\n", decision.decision));
+            if let Some((thought_number, text)) = &decision.reversed_by {
+                markdown.push_str(&format!(
+                    "### Consequences\n\nReversed by thought {thought_number}: {text}\n\n"
+                ));
+            }
+        }
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_decision_log() {
+        let mut reversal = ThoughtData::revision(
+            "Decision: use Postgres after all, SQLite cannot handle the write load".to_string(),
+            4,
+            2,
+        );
+        reversal.next_thought_needed = false;
+        let thoughts: Vec<SharedThought> = vec![
+            ThoughtData::new("We need a datastore".to_string(), 1, 4),
+            ThoughtData::new(
+                "DECISION: use SQLite\nIt is simple to deploy".to_string(),
+                2,
+                4,
+            ),
+            ThoughtData::new("Cache responses for a minute".to_string(), 3, 4)
+                .with_metadata(DECISION_KEY.to_string(), serde_json::json!(true)),
+            reversal,
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+
+        let log = DecisionLog::from_thoughts(&thoughts);
+        assert_eq!(log.decisions.len(), 3);
+        let sqlite = &log.decisions[0];
+        assert_eq!(sqlite.title, "use SQLite");
+        assert_eq!(sqlite.context.as_deref(), Some("We need a datastore"));
+        assert_eq!(sqlite.status, DecisionStatus::Reversed);
+        assert_eq!(sqlite.reversed_by.as_ref().unwrap().0, 4);
+        assert_eq!(log.decisions[1].status, DecisionStatus::Accepted);
+        assert_eq!(log.decisions[2].supersedes, Some(1));
+        assert_eq!(log.with_status(DecisionStatus::Accepted).count(), 2);
+
+        let markdown = log.to_markdown(Some("db"));
+        assert!(markdown.contains("## ADR-001: use SQLite\n\n- **Status:** Reversed"));
+        assert!(markdown.contains("Reversed by thought 4: use Postgres after all"));
+        assert!(markdown.contains("- **Supersedes:** ADR-001"));
+
+        // Without a conclusion decisions stay proposed
+        let open = DecisionLog::from_thoughts(&thoughts[..3]);
+        assert_eq!(open.decisions[1].status, DecisionStatus::Proposed);
+        assert!(!is_decision(&thoughts[0]));
+    }
+}
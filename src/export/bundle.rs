@@ -0,0 +1,350 @@
+//! # Bundle Export
+//!
+//! A whole session record in one ZIP archive, for attaching to a ticket.
+//!
+//! A bundle holds the session as JSON, Markdown and HTML, its analytics,
+//! the content of its code and image attachments under `attachments/`, and
+//! a `manifest.json` listing every file with its size and SHA-256 digest.
+//! File and link attachments have no content to include; the manifest lists
+//! them as references instead.
+
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::{ExportData, ExportEngine, ExportFormat, ExportOptions};
+use crate::analytics::AnalyticsEngine;
+use crate::thinking::attachment::Attachment;
+use crate::thinking::{ThinkingProgress, ThinkingStats};
+
+/// Name of the manifest inside a bundle
+pub const MANIFEST_FILENAME: &str = "manifest.json";
+
+/// Contents of a bundle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Session ID
+    pub session_id: String,
+    /// When the bundle was exported
+    pub exported_at: DateTime<Utc>,
+    /// Version of the exporting tool
+    pub version: String,
+    /// Every file in the bundle except the manifest
+    pub files: Vec<BundleFile>,
+    /// Attachments referring to files or pages outside the bundle
+    #[serde(default)]
+    pub references: Vec<BundleReference>,
+}
+
+/// File in a bundle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleFile {
+    /// Path inside the archive
+    pub path: String,
+    /// Media type of the content
+    pub media_type: String,
+    /// Size in bytes
+    pub size: u64,
+    /// Hex SHA-256 digest of the content
+    pub sha256: String,
+    /// Thought the file was attached to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thought_number: Option<u32>,
+}
+
+/// Attachment referring to something outside the bundle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleReference {
+    /// Thought the reference was attached to
+    pub thought_number: u32,
+    /// Path of a file attachment or address of a link
+    pub target: String,
+}
+
+/// Archive being written, with the manifest entries of its files
+struct BundleWriter {
+    zip: ZipWriter<std::io::Cursor<Vec<u8>>>,
+    files: Vec<BundleFile>,
+}
+
+impl BundleWriter {
+    /// Add a file to the archive and the manifest
+    fn add(
+        &mut self,
+        path: String,
+        media_type: &str,
+        content: &[u8],
+        thought_number: Option<u32>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        self.zip.start_file(path.as_str(), options)?;
+        self.zip.write_all(content)?;
+        self.files.push(BundleFile {
+            path,
+            media_type: media_type.to_string(),
+            size: content.len() as u64,
+            sha256: hex::encode(Sha256::digest(content)),
+            thought_number,
+        });
+        Ok(())
+    }
+}
+
+impl ExportEngine {
+    /// Render a session as a ZIP bundle
+    pub(super) fn export_to_bundle(
+        &self,
+        data: &ExportData,
+        options: &ExportOptions,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut bundle = BundleWriter {
+            zip: ZipWriter::new(std::io::Cursor::new(Vec::new())),
+            files: Vec::new(),
+        };
+
+        for format in [
+            ExportFormat::Json,
+            ExportFormat::Markdown,
+            ExportFormat::Html,
+        ] {
+            let options = ExportOptions {
+                format: format.clone(),
+                ..options.clone()
+            };
+            let content = self.render(data, &options)?;
+            bundle.add(
+                format!("session.{}", format.extension()),
+                format.mime_type(),
+                content.as_bytes(),
+                None,
+            )?;
+        }
+
+        let analytics = analyze(data);
+        bundle.add(
+            "analytics.json".to_string(),
+            ExportFormat::Json.mime_type(),
+            serde_json::to_string_pretty(&analytics)?.as_bytes(),
+            None,
+        )?;
+
+        let mut references = Vec::new();
+        for thought in &data.session.thoughts {
+            let number = thought.thought_number;
+            let prefix = match &thought.branch_id {
+                Some(branch_id) => format!(
+                    "attachments/thought-{number}-{}",
+                    branch_id.replace(['/', '\\'], "_")
+                ),
+                None => format!("attachments/thought-{number}"),
+            };
+            for (index, attachment) in thought.attachments.iter().enumerate() {
+                match attachment {
+                    Attachment::Code {
+                        content,
+                        language,
+                        filename,
+                    } => {
+                        let name = match filename {
+                            Some(filename) => file_name(filename).to_string(),
+                            None => format!("code.{}", code_extension(language.as_deref())),
+                        };
+                        bundle.add(
+                            format!("{prefix}-{}-{name}", index + 1),
+                            "text/plain",
+                            content.as_bytes(),
+                            Some(number),
+                        )?;
+                    }
+                    Attachment::Image {
+                        mime_type, data, ..
+                    } => {
+                        let image = base64::engine::general_purpose::STANDARD.decode(data)?;
+                        let extension = image_extension(mime_type);
+                        bundle.add(
+                            format!("{prefix}-{}-image.{extension}", index + 1),
+                            mime_type,
+                            &image,
+                            Some(number),
+                        )?;
+                    }
+                    Attachment::File { path, .. } => references.push(BundleReference {
+                        thought_number: number,
+                        target: path.clone(),
+                    }),
+                    Attachment::Url { url, .. } => references.push(BundleReference {
+                        thought_number: number,
+                        target: url.clone(),
+                    }),
+                }
+            }
+        }
+
+        let manifest = BundleManifest {
+            session_id: data.session.session_id.clone(),
+            exported_at: data.export_metadata.exported_at,
+            version: data.export_metadata.version.clone(),
+            files: std::mem::take(&mut bundle.files),
+            references,
+        };
+        bundle.add(
+            MANIFEST_FILENAME.to_string(),
+            ExportFormat::Json.mime_type(),
+            serde_json::to_string_pretty(&manifest)?.as_bytes(),
+            None,
+        )?;
+        Ok(bundle.zip.finish()?.into_inner())
+    }
+}
+
+/// Analytics of the exported session, computed when the export has none
+fn analyze(data: &ExportData) -> serde_json::Value {
+    if let Some(analytics) = &data.session.analytics {
+        return analytics.clone();
+    }
+    let session = &data.session;
+    let total = session.thoughts.len() as u32;
+    let stats = session.statistics.clone().unwrap_or_else(|| ThinkingStats {
+        total_thoughts: total as u64,
+        ..ThinkingStats::default()
+    });
+    let progress = session
+        .progress
+        .clone()
+        .unwrap_or_else(|| ThinkingProgress::new(total, total));
+    let title = session
+        .metadata
+        .as_ref()
+        .map(|m| m.title.as_str())
+        .unwrap_or_default();
+    let analytics = AnalyticsEngine::new().analyze_session(
+        &session.session_id,
+        title,
+        &session.thoughts,
+        &stats,
+        &progress,
+    );
+    serde_json::to_value(analytics).unwrap_or_default()
+}
+
+/// Last component of a path, so attachments cannot escape their directory
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\'])
+        .find(|part| !part.is_empty() && *part != "..")
+        .unwrap_or("code.txt")
+}
+
+/// File extension of code in a language
+fn code_extension(language: Option<&str>) -> &'static str {
+    match language.map(str::to_lowercase).as_deref() {
+        Some("rust" | "rs") => "rs",
+        Some("python" | "py") => "py",
+        Some("javascript" | "js") => "js",
+        Some("typescript" | "ts") => "ts",
+        Some("go") => "go",
+        Some("java") => "java",
+        Some("c") => "c",
+        Some("cpp" | "c++") => "cpp",
+        Some("shell" | "bash" | "sh") => "sh",
+        Some("sql") => "sql",
+        Some("json") => "json",
+        Some("yaml" | "yml") => "yml",
+        Some("toml") => "toml",
+        _ => "txt",
+    }
+}
+
+/// File extension of an image media type
+pub(super) fn image_extension(mime_type: &str) -> &str {
+    match mime_type {
+        "image/jpeg" => "jpg",
+        "image/svg+xml" => "svg",
+        other => other
+            .strip_prefix("image/")
+            .filter(|subtype| subtype.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or("bin"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::{SharedThought, ThoughtData};
+    use std::io::Read;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_bundle_export() {
+        let mut first = ThoughtData::new("Reproduce the crash".to_string(), 1, 2);
+        first.attachments = vec![
+            Attachment::Code {
+                content: "fn main() {}".to_string(),
+                language: Some("rust".to_string()),
+                filename: Some("../src/main.rs".to_string()),
+            },
+            Attachment::Image {
+                mime_type: "image/png".to_string(),
+                data: "iVBORw0K".to_string(),
+                alt: None,
+            },
+            Attachment::url("https://example.com/issue/1", None),
+        ];
+        let thoughts: Vec<SharedThought> = vec![
+            Arc::new(first),
+            Arc::new(ThoughtData::new("Fix it".to_string(), 2, 2)),
+        ];
+        let engine = ExportEngine::new();
+        let options = ExportOptions {
+            format: ExportFormat::Bundle,
+            ..Default::default()
+        };
+        let data = engine
+            .prepare_export_data(
+                "s1", None, &thoughts, None, None, None, None, None, &options,
+            )
+            .unwrap();
+        let bytes = engine.render_bytes(&data, &options).unwrap();
+        assert!(engine.render(&data, &options).is_err());
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        let mut read = |name: &str| {
+            let mut content = Vec::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_end(&mut content)
+                .unwrap();
+            content
+        };
+        let manifest: BundleManifest = serde_json::from_slice(&read(MANIFEST_FILENAME)).unwrap();
+        let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "session.json",
+                "session.md",
+                "session.html",
+                "analytics.json",
+                "attachments/thought-1-1-main.rs",
+                "attachments/thought-1-2-image.png",
+            ]
+        );
+        assert_eq!(read("attachments/thought-1-1-main.rs"), b"fn main() {}");
+        let image = read("attachments/thought-1-2-image.png");
+        assert!(image.starts_with(b"\x89PNG"));
+        assert_eq!(
+            manifest.files[5].sha256,
+            hex::encode(Sha256::digest(&image))
+        );
+        assert_eq!(manifest.references[0].target, "https://example.com/issue/1");
+
+        let session: ExportData = serde_json::from_slice(&read("session.json")).unwrap();
+        assert_eq!(session.session.thoughts.len(), 2);
+        let analytics: serde_json::Value = serde_json::from_slice(&read("analytics.json")).unwrap();
+        assert_eq!(analytics["session_id"], "s1");
+    }
+}
@@ -0,0 +1,275 @@
+//! # Export Deduplication
+//!
+//! Skipping exports whose content has not changed since the last one.
+//!
+//! An export payload is hashed with SHA-256 over the session data and the
+//! export options, leaving out the time of the export. The [`ExportIndex`]
+//! keeps the hash and location of the last export of each session, format
+//! and destination. When a new export hashes the same, nothing is written
+//! and a `skipped_duplicate` [`ExportRecord`](super::ExportRecord) points at
+//! the earlier export instead. Saving the index next to the exports makes
+//! repeated runs skip unchanged sessions too.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::{ExportData, ExportFormat, ExportLocation};
+
+/// File name of the index kept in an export directory
+pub const INDEX_FILENAME: &str = ".export-index.json";
+
+/// Last export of a session in one format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedExport {
+    /// Hash of the exported payload
+    pub content_hash: String,
+    /// Where the export was written
+    pub location: ExportLocation,
+    /// When the export was written
+    pub exported_at: DateTime<Utc>,
+}
+
+/// Hashes of the last export of each session, format and destination
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExportIndex {
+    /// Last export, by destination, session ID and format
+    pub entries: BTreeMap<String, IndexedExport>,
+}
+
+impl ExportIndex {
+    /// Load an index saved with [`ExportIndex::save`], or an empty one if the file is missing
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid export index {}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read {}: {e}", path.display())),
+        }
+    }
+
+    /// Save the index as JSON
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, content)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+
+    /// Key of a session's exports in a format to a destination
+    pub fn key(destination: &str, session_id: &str, format: &ExportFormat) -> String {
+        format!("{destination}/{session_id}.{}", format.extension())
+    }
+
+    /// The last export under `key`, if it had the same hash and is still there
+    ///
+    /// Local files that were deleted since do not count; objects in a bucket
+    /// are assumed to still exist.
+    pub fn unchanged(&self, key: &str, content_hash: &str) -> Option<&IndexedExport> {
+        self.entries.get(key).filter(|previous| {
+            let still_there = match previous.location.path() {
+                Some(path) => path.exists(),
+                None => true,
+            };
+            previous.content_hash == content_hash && still_there
+        })
+    }
+
+    /// Remember the last export under `key`
+    pub fn record(&mut self, key: String, export: IndexedExport) {
+        self.entries.insert(key, export);
+    }
+}
+
+/// Hex SHA-256 digest of an export payload, ignoring when it was exported
+pub fn content_hash(data: &ExportData) -> Result<String, serde_json::Error> {
+    let mut options = data.export_metadata.options.clone();
+    options.force = false;
+    let payload = serde_json::to_value((
+        &data.session,
+        &data.export_metadata.format,
+        &data.export_metadata.version,
+        &options,
+        &data.custom_data,
+    ))?;
+    let mut canonical = String::new();
+    write_canonical(&payload, &mut canonical);
+    Ok(hex::encode(Sha256::digest(canonical.as_bytes())))
+}
+
+/// Hex SHA-256 digest of what a session export says about its reasoning
+///
+/// Of a JSON `export_session` result only the thoughts, branches and
+/// annotations count, so the time of the export, the correlation ID of the
+/// call and figures derived from the thoughts do not make it look changed.
+/// Other formats are hashed whole.
+pub fn export_fingerprint(format: &str, export: &str) -> String {
+    let content = match serde_json::from_str::<Value>(export) {
+        Ok(value) if format == "json" => {
+            let session = &value["session"];
+            let mut canonical = String::new();
+            write_canonical(
+                &serde_json::json!([
+                    session["thoughts"],
+                    session["branches"],
+                    session["annotations"]
+                ]),
+                &mut canonical,
+            );
+            canonical
+        }
+        _ => export.to_string(),
+    };
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Write JSON with object keys sorted, so maps hash the same in any order
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(fields) => {
+            let mut fields: Vec<(&String, &Value)> = fields.iter().collect();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (index, (key, field)) in fields.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(field, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{ExportConfig, ExportEngine, ExportOptions};
+    use crate::thinking::{SharedThought, ThoughtData};
+    use std::sync::Arc;
+
+    async fn export(
+        engine: &mut ExportEngine,
+        thoughts: &[SharedThought],
+        force: bool,
+    ) -> (ExportLocation, bool) {
+        let location = engine
+            .export_session(
+                "s1",
+                None,
+                thoughts,
+                None,
+                None,
+                None,
+                None,
+                None,
+                ExportOptions {
+                    force,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let skipped = engine
+            .get_export_history()
+            .last()
+            .unwrap()
+            .skipped_duplicate;
+        (location, skipped)
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_exports_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let index_path = dir.path().join(INDEX_FILENAME);
+        let engine = || {
+            ExportEngine::with_config(ExportConfig {
+                export_directory: dir.path().to_string_lossy().to_string(),
+                ..ExportConfig::default()
+            })
+            .with_export_index(&index_path)
+            .unwrap()
+        };
+        let mut thoughts: Vec<SharedThought> = vec![Arc::new(ThoughtData::new(
+            "Profile first".to_string(),
+            1,
+            2,
+        ))];
+
+        let mut exporter = engine();
+        let (first, skipped) = export(&mut exporter, &thoughts, false).await;
+        assert!(!skipped);
+        let (second, skipped) = export(&mut exporter, &thoughts, false).await;
+        assert!(skipped);
+        assert_eq!(second, first);
+        let history = exporter.get_export_history();
+        assert_eq!(history[1].file_size, None);
+        assert_eq!(history[1].content_hash, history[0].content_hash);
+        assert_eq!(exporter.export_index().entries.len(), 1);
+
+        // The saved index skips unchanged sessions after a restart, unless forced
+        let mut restarted = engine();
+        assert!(export(&mut restarted, &thoughts, false).await.1);
+        assert!(!export(&mut restarted, &thoughts, true).await.1);
+
+        // Changed content and deleted files are written again
+        thoughts.push(Arc::new(ThoughtData::new("Then cache".to_string(), 2, 2)));
+        let (changed, skipped) = export(&mut restarted, &thoughts, false).await;
+        assert!(!skipped);
+        std::fs::remove_file(changed.path().unwrap()).unwrap();
+        let (rewritten, skipped) = export(&mut restarted, &thoughts, false).await;
+        assert!(!skipped);
+        assert!(rewritten.path().unwrap().exists());
+    }
+
+    #[test]
+    fn test_export_fingerprint_follows_thoughts() {
+        let export = |thought: &str, exported_at: &str, correlation_id: &str| {
+            serde_json::json!({
+                "session": {
+                    "sessionId": "s1",
+                    "thoughts": [{ "thought": thought, "thoughtNumber": 1 }],
+                    "branches": {},
+                    "stats": { "avgProcessingTimeMs": exported_at.len() },
+                    "annotations": [],
+                    "exportedAt": exported_at
+                },
+                "format": "json",
+                "correlationId": correlation_id
+            })
+            .to_string()
+        };
+        let first = export_fingerprint("json", &export("Plan", "2026-01-01", "a"));
+        assert_eq!(
+            first,
+            export_fingerprint("json", &export("Plan", "2026-01-02T10:00", "b"))
+        );
+        assert_ne!(
+            first,
+            export_fingerprint("json", &export("Replan", "2026-01-01", "a"))
+        );
+        assert_ne!(
+            export_fingerprint("markdown", "# One"),
+            export_fingerprint("markdown", "# Two")
+        );
+    }
+}
@@ -0,0 +1,493 @@
+//! # EPUB Export
+//!
+//! EPUB 3 books of long sessions, for reading on e-readers.
+//!
+//! Each phase of the main line becomes a chapter, and so does each branch.
+//! A phase is a run of consecutive thoughts whose metadata sets the same
+//! `phase`; a session without phases has its main line in one chapter. The
+//! book opens with an overview of the session, has a generated table of
+//! contents linking every chapter and thought, and carries the title,
+//! author, description, tags and dates in its package metadata. Images
+//! attached to thoughts are stored in the book.
+
+use base64::Engine as _;
+use std::io::Write;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::bundle::image_extension;
+use super::html::{escape_html, render_content};
+use super::ExportData;
+use crate::thinking::attachment::Attachment;
+use crate::thinking::{SharedThought, ThoughtData};
+
+/// Stylesheet shared by every page
+const STYLE: &str = r#"body { font-family: serif; line-height: 1.5; }
+h1, h2, h3 { font-family: sans-serif; }
+.meta { color: #666; font-size: 0.85em; }
+pre { white-space: pre-wrap; font-size: 0.85em; }
+aside.comment { border-left: 3px solid #999; padding-left: 0.8em; font-style: italic; }
+figure { margin: 1em 0; }
+img { max-width: 100%; }
+"#;
+
+/// Part of the session read as one chapter
+struct Chapter<'a> {
+    title: String,
+    thoughts: Vec<&'a SharedThought>,
+}
+
+impl Chapter<'_> {
+    /// File name of the chapter inside the book
+    fn href(index: usize) -> String {
+        format!("chapter-{}.xhtml", index + 1)
+    }
+}
+
+/// Image stored in the book
+struct Image {
+    href: String,
+    media_type: String,
+    data: Vec<u8>,
+}
+
+/// Render a session as an EPUB 3 book
+pub(super) fn render(data: &ExportData) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let session = &data.session;
+    let metadata = session.metadata.as_ref();
+    let title = metadata
+        .map(|m| m.title.clone())
+        .unwrap_or_else(|| "Sequential Thinking Session".to_string());
+    let chapters = chapters(&session.thoughts);
+
+    let mut images = Vec::new();
+    let mut pages = Vec::with_capacity(chapters.len());
+    for (index, chapter) in chapters.iter().enumerate() {
+        let mut body = format!("<h1>{}</h1>\n", escape_html(&chapter.title));
+        for thought in &chapter.thoughts {
+            body.push_str(&render_thought(data, thought, &mut images));
+        }
+        pages.push((Chapter::href(index), page(&chapter.title, &body)));
+    }
+
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    // The media type must come first and uncompressed
+    zip.start_file(
+        "mimetype",
+        FileOptions::default().compression_method(CompressionMethod::Stored),
+    )?;
+    zip.write_all(b"application/epub+zip")?;
+
+    let mut add = |path: &str, content: &[u8]| -> Result<(), Box<dyn std::error::Error>> {
+        zip.start_file(
+            path,
+            FileOptions::default().compression_method(CompressionMethod::Deflated),
+        )?;
+        zip.write_all(content)?;
+        Ok(())
+    };
+    add("META-INF/container.xml", CONTAINER.as_bytes())?;
+    add("OEBPS/style.css", STYLE.as_bytes())?;
+    add(
+        "OEBPS/overview.xhtml",
+        page(&title, &overview(data, &title)).as_bytes(),
+    )?;
+    add("OEBPS/nav.xhtml", navigation(&title, &chapters).as_bytes())?;
+    for (href, content) in &pages {
+        add(&format!("OEBPS/{href}"), content.as_bytes())?;
+    }
+    for image in &images {
+        add(&format!("OEBPS/{}", image.href), &image.data)?;
+    }
+    add(
+        "OEBPS/content.opf",
+        package(data, &title, &pages, &images).as_bytes(),
+    )?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Split a session into phases of its main line followed by its branches
+fn chapters(thoughts: &[SharedThought]) -> Vec<Chapter<'_>> {
+    let mut chapters: Vec<Chapter> = Vec::new();
+    let mut current_phase: Option<Option<&str>> = None;
+    for thought in thoughts.iter().filter(|t| t.branch_id.is_none()) {
+        let phase = thought
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("phase"))
+            .and_then(|phase| phase.as_str());
+        match chapters.last_mut() {
+            Some(chapter) if current_phase == Some(phase) => chapter.thoughts.push(thought),
+            _ => chapters.push(Chapter {
+                title: phase.unwrap_or("Thoughts").to_string(),
+                thoughts: vec![thought],
+            }),
+        }
+        current_phase = Some(phase);
+    }
+
+    let mut branches: Vec<Chapter> = Vec::new();
+    for thought in thoughts {
+        let Some(branch_id) = &thought.branch_id else {
+            continue;
+        };
+        let title = format!("Branch {branch_id}");
+        match branches.iter_mut().find(|chapter| chapter.title == title) {
+            Some(chapter) => chapter.thoughts.push(thought),
+            None => branches.push(Chapter {
+                title,
+                thoughts: vec![thought],
+            }),
+        }
+    }
+    chapters.extend(branches);
+    chapters
+}
+
+/// Anchor of a thought inside its chapter
+fn anchor(thought: &ThoughtData) -> String {
+    match &thought.branch_id {
+        Some(branch_id) => format!(
+            "thought-{}-{}",
+            thought.thought_number,
+            branch_id
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect::<String>()
+        ),
+        None => format!("thought-{}", thought.thought_number),
+    }
+}
+
+/// Heading of a thought, as shown in the chapter and the table of contents
+fn heading(thought: &ThoughtData) -> String {
+    let keyword = if thought.is_revision() {
+        "Revision"
+    } else if thought.is_branch() {
+        "Branch"
+    } else {
+        "Thought"
+    };
+    format!(
+        "{keyword} {}/{}",
+        thought.thought_number, thought.total_thoughts
+    )
+}
+
+/// XHTML of one thought, collecting its images
+fn render_thought(data: &ExportData, thought: &ThoughtData, images: &mut Vec<Image>) -> String {
+    let mut html = format!(
+        "<section id=\"{}\">\n<h2>{}</h2>\n",
+        anchor(thought),
+        heading(thought)
+    );
+
+    let mut meta = Vec::new();
+    if let Some(author) = &thought.author {
+        meta.push(escape_html(author));
+    }
+    if let Some(timestamp) = thought.timestamp {
+        meta.push(timestamp.format("%Y-%m-%d %H:%M UTC").to_string());
+    }
+    if let Some(revises) = thought.revises_thought {
+        meta.push(format!("revises thought {revises}"));
+    }
+    if let Some(branch_from) = thought.branch_from_thought {
+        meta.push(format!("branches from thought {branch_from}"));
+    }
+    if !meta.is_empty() {
+        html.push_str(&format!("<p class=\"meta\">{}</p>\n", meta.join(" · ")));
+    }
+
+    render_content(&thought.thought, &mut html);
+
+    for (index, attachment) in thought.attachments.iter().enumerate() {
+        match attachment {
+            Attachment::Code {
+                content, filename, ..
+            } => {
+                html.push_str("<figure>\n");
+                if let Some(filename) = filename {
+                    html.push_str(&format!(
+                        "<figcaption>{}</figcaption>\n",
+                        escape_html(filename)
+                    ));
+                }
+                html.push_str(&format!(
+                    "<pre><code>{}</code></pre>\n</figure>\n",
+                    escape_html(content)
+                ));
+            }
+            Attachment::Image {
+                mime_type,
+                data,
+                alt,
+            } => {
+                let alt = escape_html(alt.as_deref().unwrap_or(""));
+                match base64::engine::general_purpose::STANDARD.decode(data) {
+                    Ok(bytes) => {
+                        let href = format!(
+                            "images/{}-{}.{}",
+                            anchor(thought),
+                            index + 1,
+                            image_extension(mime_type)
+                        );
+                        html.push_str(&format!(
+                            "<figure><img src=\"{href}\" alt=\"{alt}\"/></figure>\n"
+                        ));
+                        images.push(Image {
+                            href,
+                            media_type: mime_type.clone(),
+                            data: bytes,
+                        });
+                    }
+                    Err(_) => html.push_str(&format!("<p class=\"meta\">[image: {alt}]</p>\n")),
+                }
+            }
+            Attachment::File { path, description } => html.push_str(&format!(
+                "<p class=\"meta\">File: <code>{}</code>{}</p>\n",
+                escape_html(path),
+                description
+                    .as_ref()
+                    .map(|d| format!(" – {}", escape_html(d)))
+                    .unwrap_or_default()
+            )),
+            Attachment::Url { url, title } => html.push_str(&format!(
+                "<p class=\"meta\"><a href=\"{}\">{}</a></p>\n",
+                escape_html(url),
+                escape_html(title.as_deref().unwrap_or(url))
+            )),
+        }
+    }
+
+    for annotation in data.session.annotations_for(thought) {
+        html.push_str(&format!(
+            "<aside class=\"comment\"><p>{}: {}</p></aside>\n",
+            escape_html(&annotation.author),
+            escape_html(&annotation.body)
+        ));
+    }
+
+    html.push_str("</section>\n");
+    html
+}
+
+/// Opening page summarizing the session
+fn overview(data: &ExportData, title: &str) -> String {
+    let mut html = format!("<h1>{}</h1>\n", escape_html(title));
+    if let Some(metadata) = &data.session.metadata {
+        if let Some(description) = &metadata.description {
+            html.push_str(&format!("<p>{}</p>\n", escape_html(description)));
+        }
+        if let Some(author) = &metadata.author {
+            html.push_str(&format!(
+                "<p class=\"meta\">By {}</p>\n",
+                escape_html(author)
+            ));
+        }
+    }
+    html.push_str(&format!(
+        "<p class=\"meta\">Session {} · {} thoughts · exported {}</p>\n",
+        escape_html(&data.session.session_id),
+        data.session.thoughts.len(),
+        data.export_metadata.exported_at.format("%Y-%m-%d")
+    ));
+    html
+}
+
+/// XHTML page with a title and body
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\">\n\
+         <head>\n<title>{}</title>\n<link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/>\n</head>\n\
+         <body>\n{body}</body>\n</html>\n",
+        escape_html(title)
+    )
+}
+
+/// Table of contents linking every chapter and thought
+fn navigation(title: &str, chapters: &[Chapter]) -> String {
+    let mut nav = String::from(
+        "<nav epub:type=\"toc\" id=\"toc\">\n<h1>Contents</h1>\n<ol>\n\
+         <li><a href=\"overview.xhtml\">Overview</a></li>\n",
+    );
+    for (index, chapter) in chapters.iter().enumerate() {
+        let href = Chapter::href(index);
+        nav.push_str(&format!(
+            "<li><a href=\"{href}\">{}</a>\n<ol>\n",
+            escape_html(&chapter.title)
+        ));
+        for thought in &chapter.thoughts {
+            nav.push_str(&format!(
+                "<li><a href=\"{href}#{}\">{}</a></li>\n",
+                anchor(thought),
+                heading(thought)
+            ));
+        }
+        nav.push_str("</ol>\n</li>\n");
+    }
+    nav.push_str("</ol>\n</nav>\n");
+    page(title, &nav)
+}
+
+/// Package document with the book's metadata, files and reading order
+fn package(data: &ExportData, title: &str, pages: &[(String, String)], images: &[Image]) -> String {
+    let session = &data.session;
+    let mut metadata = format!(
+        "<dc:identifier id=\"book-id\">urn:session:{}</dc:identifier>\n\
+         <dc:title>{}</dc:title>\n<dc:language>en</dc:language>\n\
+         <meta property=\"dcterms:modified\">{}</meta>\n",
+        escape_html(&session.session_id),
+        escape_html(title),
+        data.export_metadata
+            .exported_at
+            .format("%Y-%m-%dT%H:%M:%SZ")
+    );
+    if let Some(session_metadata) = &session.metadata {
+        if let Some(author) = &session_metadata.author {
+            metadata.push_str(&format!(
+                "<dc:creator>{}</dc:creator>\n",
+                escape_html(author)
+            ));
+        }
+        if let Some(description) = &session_metadata.description {
+            metadata.push_str(&format!(
+                "<dc:description>{}</dc:description>\n",
+                escape_html(description)
+            ));
+        }
+        for tag in &session_metadata.tags {
+            metadata.push_str(&format!("<dc:subject>{}</dc:subject>\n", escape_html(tag)));
+        }
+        metadata.push_str(&format!(
+            "<dc:date>{}</dc:date>\n",
+            session_metadata.created_at.format("%Y-%m-%d")
+        ));
+    }
+    metadata.push_str(&format!(
+        "<dc:publisher>{}</dc:publisher>\n",
+        escape_html(&data.export_metadata.tool)
+    ));
+
+    let mut manifest = String::from(
+        "<item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n\
+         <item id=\"style\" href=\"style.css\" media-type=\"text/css\"/>\n\
+         <item id=\"overview\" href=\"overview.xhtml\" media-type=\"application/xhtml+xml\"/>\n",
+    );
+    let mut spine = String::from("<itemref idref=\"overview\"/>\n<itemref idref=\"nav\"/>\n");
+    for (index, (href, _)) in pages.iter().enumerate() {
+        manifest.push_str(&format!(
+            "<item id=\"chapter-{}\" href=\"{href}\" media-type=\"application/xhtml+xml\"/>\n",
+            index + 1
+        ));
+        spine.push_str(&format!("<itemref idref=\"chapter-{}\"/>\n", index + 1));
+    }
+    for (index, image) in images.iter().enumerate() {
+        manifest.push_str(&format!(
+            "<item id=\"image-{}\" href=\"{}\" media-type=\"{}\"/>\n",
+            index + 1,
+            image.href,
+            escape_html(&image.media_type)
+        ));
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\">\n\
+         <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n{metadata}</metadata>\n\
+         <manifest>\n{manifest}</manifest>\n<spine>\n{spine}</spine>\n</package>\n"
+    )
+}
+
+/// Container pointing readers at the package document
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+<rootfiles>
+<rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+</rootfiles>
+</container>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{ExportEngine, ExportFormat, ExportOptions};
+    use std::io::Read;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_epub_export() {
+        let phase = |thought: &mut ThoughtData, name: &str| {
+            thought.metadata = Some([("phase".to_string(), serde_json::json!(name))].into());
+        };
+        let mut thoughts = Vec::new();
+        for (number, name) in [(1, "Research"), (2, "Research"), (3, "Design")] {
+            let mut thought = ThoughtData::new(format!("Step {number} <a & b>"), number, 3);
+            phase(&mut thought, name);
+            thoughts.push(thought);
+        }
+        thoughts[2].attachments = vec![Attachment::Image {
+            mime_type: "image/png".to_string(),
+            data: "iVBORw0K".to_string(),
+            alt: Some("Sketch".to_string()),
+        }];
+        thoughts.push(ThoughtData::branch(
+            "Try a cache".to_string(),
+            4,
+            2,
+            "cache/v2".to_string(),
+        ));
+        let thoughts: Vec<SharedThought> = thoughts.into_iter().map(Arc::new).collect();
+
+        let engine = ExportEngine::new();
+        let options = ExportOptions {
+            format: ExportFormat::Epub,
+            ..Default::default()
+        };
+        let data = engine
+            .prepare_export_data(
+                "s1", None, &thoughts, None, None, None, None, None, &options,
+            )
+            .unwrap();
+        let bytes = engine.render_bytes(&data, &options).unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.by_index(0).unwrap().name(), "mimetype");
+        let mut read = |name: &str| {
+            let mut content = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            content
+        };
+        let nav = read("OEBPS/nav.xhtml");
+        let chapter_titles: Vec<&str> = nav
+            .lines()
+            .filter(|line| line.starts_with("<li><a href=\"chapter-") && !line.contains('#'))
+            .collect();
+        assert_eq!(
+            chapter_titles,
+            [
+                "<li><a href=\"chapter-1.xhtml\">Research</a>",
+                "<li><a href=\"chapter-2.xhtml\">Design</a>",
+                "<li><a href=\"chapter-3.xhtml\">Branch cache/v2</a>",
+            ]
+        );
+        assert!(nav.contains("<a href=\"chapter-3.xhtml#thought-4-cache-v2\">Branch 4/4</a>"));
+
+        let research = read("OEBPS/chapter-1.xhtml");
+        assert_eq!(research.matches("<section").count(), 2);
+        assert!(research.contains("Step 1 &lt;a &amp; b&gt;"));
+        assert!(read("OEBPS/chapter-2.xhtml")
+            .contains("<img src=\"images/thought-3-1.png\" alt=\"Sketch\"/>"));
+
+        let opf = read("OEBPS/content.opf");
+        assert!(opf.contains("<dc:identifier id=\"book-id\">urn:session:s1</dc:identifier>"));
+        assert!(opf.contains("href=\"images/thought-3-1.png\" media-type=\"image/png\""));
+        assert_eq!(opf.matches("<itemref").count(), 5);
+    }
+}
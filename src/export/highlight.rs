@@ -0,0 +1,304 @@
+//! # Syntax Highlighting
+//!
+//! Lightweight highlighting of code blocks in HTML exports.
+//!
+//! Code is split into keywords, strings, comments and numbers, each wrapped
+//! in a `tok-*` span the export stylesheet colors. Languages without rules
+//! are escaped and shown as plain text.
+
+use super::html::escape_html;
+
+/// Highlighting rules of a language
+struct Syntax {
+    keywords: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    case_insensitive: bool,
+}
+
+const RUST: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+const PYTHON: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+const JAVASCRIPT: &[&str] = &[
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "return",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "type",
+    "typeof",
+    "undefined",
+    "var",
+    "void",
+    "while",
+    "yield",
+];
+
+const GO: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "false",
+    "for",
+    "func",
+    "go",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "nil",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "true",
+    "type",
+    "var",
+];
+
+const C_FAMILY: &[&str] = &[
+    "abstract",
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "float",
+    "for",
+    "if",
+    "implements",
+    "import",
+    "int",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "nullptr",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "unsigned",
+    "void",
+    "while",
+];
+
+const SHELL: &[&str] = &[
+    "case", "do", "done", "echo", "elif", "else", "esac", "exit", "export", "fi", "for",
+    "function", "if", "in", "local", "return", "then", "while",
+];
+
+const SQL: &[&str] = &[
+    "and", "as", "by", "create", "delete", "desc", "from", "group", "having", "in", "index",
+    "insert", "into", "join", "left", "limit", "not", "null", "on", "or", "order", "select", "set",
+    "table", "update", "values", "where",
+];
+
+const LITERALS: &[&str] = &["false", "null", "true"];
+
+/// Rules for a language name or alias
+fn syntax(language: &str) -> Option<Syntax> {
+    let c_like = |keywords, quotes| Syntax {
+        keywords,
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes,
+        case_insensitive: false,
+    };
+    let hash_commented = |keywords, quotes| Syntax {
+        keywords,
+        line_comments: &["#"],
+        block_comment: None,
+        quotes,
+        case_insensitive: false,
+    };
+    let syntax = match language.to_lowercase().as_str() {
+        // Single quotes are left alone in Rust, where they also start lifetimes
+        "rust" | "rs" => c_like(RUST, &['"']),
+        "javascript" | "js" | "jsx" | "typescript" | "ts" | "tsx" => {
+            c_like(JAVASCRIPT, &['"', '\'', '`'])
+        }
+        "go" | "golang" => c_like(GO, &['"', '`']),
+        "c" | "h" | "cpp" | "c++" | "java" | "csharp" | "cs" => c_like(C_FAMILY, &['"', '\'']),
+        "json" => c_like(LITERALS, &['"']),
+        "python" | "py" => hash_commented(PYTHON, &['"', '\'']),
+        "sh" | "bash" | "shell" | "zsh" => hash_commented(SHELL, &['"', '\'']),
+        "toml" | "yaml" | "yml" => hash_commented(LITERALS, &['"', '\'']),
+        "sql" => Syntax {
+            keywords: SQL,
+            line_comments: &["--"],
+            block_comment: Some(("/*", "*/")),
+            quotes: &['\''],
+            case_insensitive: true,
+        },
+        _ => return None,
+    };
+    Some(syntax)
+}
+
+impl Syntax {
+    /// Length of a comment at the start of `code`
+    fn comment(&self, code: &str) -> Option<usize> {
+        if self.line_comments.iter().any(|c| code.starts_with(c)) {
+            return Some(code.find('\n').unwrap_or(code.len()));
+        }
+        let (open, close) = self.block_comment?;
+        let body = code.strip_prefix(open)?;
+        Some(
+            body.find(close)
+                .map_or(code.len(), |end| open.len() + end + close.len()),
+        )
+    }
+
+    fn is_keyword(&self, word: &str) -> bool {
+        if self.case_insensitive {
+            self.keywords.contains(&word.to_lowercase().as_str())
+        } else {
+            self.keywords.contains(&word)
+        }
+    }
+}
+
+/// Length of a string literal opened by `quote` at the start of `code`
+///
+/// Strings other than backtick strings end at a line break if unterminated.
+fn string_len(code: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in code.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '\n' if quote != '`' => return i,
+            c if c == quote => return i + c.len_utf8(),
+            _ => {}
+        }
+    }
+    code.len()
+}
+
+/// Highlight code as HTML, escaping everything that is not markup
+pub fn highlight(code: &str, language: Option<&str>) -> String {
+    let Some(syntax) = language.and_then(syntax) else {
+        return escape_html(code);
+    };
+    let mut html = String::with_capacity(code.len() * 2);
+    let mut rest = code;
+    while let Some(c) = rest.chars().next() {
+        let (class, len) = if let Some(len) = syntax.comment(rest) {
+            (Some("comment"), len)
+        } else if syntax.quotes.contains(&c) {
+            (Some("string"), string_len(rest, c))
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '_'))
+                .unwrap_or(rest.len());
+            (Some("number"), len)
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            (syntax.is_keyword(&rest[..len]).then_some("keyword"), len)
+        } else {
+            (None, c.len_utf8())
+        };
+
+        let token = escape_html(&rest[..len]);
+        match class {
+            Some(class) => html.push_str(&format!("<span class=\"tok-{class}\">{token}</span>")),
+            None => html.push_str(&token),
+        }
+        rest = &rest[len..];
+    }
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight() {
+        assert_eq!(
+            highlight("let s = \"<a>\"; // done", Some("rust")),
+            "<span class=\"tok-keyword\">let</span> s = <span class=\"tok-string\">&quot;&lt;a&gt;&quot;</span>; <span class=\"tok-comment\">// done</span>"
+        );
+        assert_eq!(
+            highlight("select 1 -- one", Some("SQL")),
+            "<span class=\"tok-keyword\">select</span> <span class=\"tok-number\">1</span> <span class=\"tok-comment\">-- one</span>"
+        );
+        assert_eq!(
+            highlight("'unterminated\nx", Some("py")),
+            "<span class=\"tok-string\">&#39;unterminated</span>\nx"
+        );
+        assert_eq!(highlight("if a < b", Some("cobol")), "if a &lt; b");
+        assert_eq!(highlight("fn ünïcode", None), "fn ünïcode");
+    }
+}
@@ -0,0 +1,719 @@
+//! # HTML Export
+//!
+//! Self-contained single-file HTML rendering of thinking sessions.
+//!
+//! Thoughts are laid out as a collapsible tree: revisions nest under the
+//! thought they revise and branches nest under the thought they fork from.
+//! Styles and scripts are inlined so the file can be shared without assets.
+
+use std::collections::HashMap;
+
+use super::highlight::highlight;
+use super::{join_numbers, ExportData, ExportOptions};
+use crate::annotations::Annotation;
+use crate::thinking::attachment::Attachment;
+use crate::thinking::code::{self, Segment};
+use crate::thinking::SharedThought;
+
+/// Inline stylesheet with light and dark themes
+const STYLE: &str = r#"
+:root {
+    --bg: #f8f9fa;
+    --fg: #2c3e50;
+    --card: #ffffff;
+    --border: #e1e8ed;
+    --muted: #7f8c8d;
+    --accent: #3498db;
+    --revision: #e74c3c;
+    --branch: #27ae60;
+    --dependency: #8e44ad;
+}
+
+:root[data-theme="dark"] {
+    --bg: #1a1a1a;
+    --fg: #e1e1e1;
+    --card: #2d2d2d;
+    --border: #4d4d4d;
+    --muted: #a0a0a0;
+}
+
+@media (prefers-color-scheme: dark) {
+    :root:not([data-theme="light"]) {
+        --bg: #1a1a1a;
+        --fg: #e1e1e1;
+        --card: #2d2d2d;
+        --border: #4d4d4d;
+        --muted: #a0a0a0;
+    }
+}
+
+* { margin: 0; padding: 0; box-sizing: border-box; }
+
+body {
+    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Oxygen, Ubuntu, Cantarell, sans-serif;
+    line-height: 1.6;
+    color: var(--fg);
+    background-color: var(--bg);
+}
+
+.container { max-width: 1200px; margin: 0 auto; padding: 2rem; }
+
+h1 {
+    font-size: 2.2rem;
+    margin-bottom: 1rem;
+    border-bottom: 3px solid var(--accent);
+    padding-bottom: 0.5rem;
+}
+
+h2 {
+    font-size: 1.5rem;
+    margin: 2rem 0 1rem 0;
+    border-left: 4px solid var(--accent);
+    padding-left: 1rem;
+}
+
+.toolbar { display: flex; gap: 0.5rem; margin-bottom: 1rem; }
+
+.toolbar button {
+    background: var(--card);
+    color: var(--fg);
+    border: 1px solid var(--border);
+    border-radius: 4px;
+    padding: 0.3rem 0.8rem;
+    cursor: pointer;
+}
+
+.session-info, .statistics {
+    background-color: var(--card);
+    border: 1px solid var(--border);
+    border-left: 4px solid var(--accent);
+    border-radius: 8px;
+    padding: 1rem 1.5rem;
+}
+
+.statistics ul { list-style: none; }
+
+.progress-bar {
+    background-color: var(--border);
+    border-radius: 10px;
+    height: 20px;
+    margin: 1rem 0;
+    overflow: hidden;
+}
+
+.progress-fill {
+    background: linear-gradient(90deg, #3498db, #2980b9);
+    height: 100%;
+    color: white;
+    font-size: 0.8rem;
+    font-weight: bold;
+    text-align: center;
+}
+
+.tree, .tree ul { list-style: none; }
+.tree ul { margin-left: 1.5rem; border-left: 2px dashed var(--border); padding-left: 1rem; }
+
+.thought {
+    background-color: var(--card);
+    border: 1px solid var(--border);
+    border-radius: 8px;
+    margin: 0.75rem 0;
+    padding: 0.75rem 1rem;
+}
+
+.thought.revision { border-left: 4px solid var(--revision); }
+.thought.branch { border-left: 4px solid var(--branch); }
+
+.thought > summary { cursor: pointer; font-weight: 600; }
+.thought.revision > summary::before { content: "🔄 "; }
+.thought.branch > summary::before { content: "🌿 "; }
+.thought:not(.revision):not(.branch) > summary::before { content: "💭 "; }
+
+.branch-group > summary {
+    cursor: pointer;
+    color: var(--branch);
+    font-weight: 600;
+    margin-top: 0.5rem;
+}
+
+.timestamp { color: var(--muted); font-size: 0.85rem; font-style: italic; }
+.author { color: var(--accent); font-size: 0.85rem; }
+.content { margin: 0.5rem 0; white-space: pre-wrap; }
+
+.attachment { margin: 0.5rem 0; }
+pre.code, .attachment pre {
+    padding: 0.5rem 0.75rem;
+    overflow-x: auto;
+    background-color: rgba(127, 127, 127, 0.1);
+    border-radius: 4px;
+}
+pre.code { margin: 0.5rem 0; }
+.attachment pre { margin: 0; }
+.attachment figcaption { color: var(--muted); font-size: 0.85rem; }
+.attachment img { max-width: 100%; }
+.tok-keyword { color: #8e44ad; font-weight: 600; }
+.tok-string { color: #27ae60; }
+.tok-comment { color: var(--muted); font-style: italic; }
+.tok-number { color: #d35400; }
+
+.revision-note, .branch-note, .dependency-note {
+    font-size: 0.9rem;
+    font-style: italic;
+    padding-left: 0.5rem;
+}
+
+.revision-note { color: var(--revision); border-left: 3px solid var(--revision); }
+.branch-note { color: var(--branch); border-left: 3px solid var(--branch); }
+.dependency-note { color: var(--dependency); border-left: 3px solid var(--dependency); }
+
+.annotation {
+    margin: 0.5rem 0;
+    padding: 0.5rem 0.75rem;
+    border-left: 3px solid #f39c12;
+    background-color: rgba(243, 156, 18, 0.08);
+    border-radius: 4px;
+}
+
+.annotation.resolved { opacity: 0.6; border-left-color: var(--muted); }
+.annotation-meta { color: var(--muted); font-size: 0.8rem; }
+
+.analytics pre {
+    background-color: #2c3e50;
+    color: #ecf0f1;
+    padding: 1rem;
+    border-radius: 4px;
+    overflow-x: auto;
+}
+
+footer {
+    margin-top: 3rem;
+    padding-top: 1rem;
+    border-top: 1px solid var(--border);
+    text-align: center;
+    color: var(--muted);
+    font-size: 0.9rem;
+}
+
+@media print {
+    .toolbar { display: none; }
+    .thought { break-inside: avoid; }
+}
+"#;
+
+/// Inline script for expanding, collapsing and theme switching
+const SCRIPT: &str = r#"
+(function () {
+    function setAll(open) {
+        document.querySelectorAll('.tree details').forEach(function (d) { d.open = open; });
+    }
+    document.getElementById('expand-all').addEventListener('click', function () { setAll(true); });
+    document.getElementById('collapse-all').addEventListener('click', function () { setAll(false); });
+    document.getElementById('toggle-theme').addEventListener('click', function () {
+        var root = document.documentElement;
+        var dark = root.dataset.theme
+            ? root.dataset.theme === 'dark'
+            : window.matchMedia('(prefers-color-scheme: dark)').matches;
+        root.dataset.theme = dark ? 'light' : 'dark';
+    });
+})();
+"#;
+
+/// Render thought text, highlighting its fenced code blocks
+pub(super) fn render_content(text: &str, html: &mut String) {
+    for segment in code::split_code(text) {
+        match segment {
+            Segment::Text(prose) if prose.trim().is_empty() => {}
+            Segment::Text(prose) => html.push_str(&format!(
+                "<p class=\"content\">{}</p>\n",
+                escape_html(prose.trim_matches('\n'))
+            )),
+            Segment::Code { language, content } => {
+                let class = language
+                    .map(|l| format!(" class=\"language-{}\"", escape_html(l)))
+                    .unwrap_or_default();
+                html.push_str(&format!(
+                    "<pre class=\"code\"><code{class}>{}</code></pre>\n",
+                    highlight(content.trim_end_matches('\n'), language)
+                ));
+            }
+        }
+    }
+}
+
+/// Render one attachment of a thought
+fn render_attachment(attachment: &Attachment, html: &mut String) {
+    match attachment {
+        Attachment::Code {
+            content,
+            language,
+            filename,
+        } => {
+            html.push_str("<figure class=\"attachment code\">\n");
+            if let Some(filename) = filename {
+                html.push_str(&format!(
+                    "<figcaption>{}</figcaption>\n",
+                    escape_html(filename)
+                ));
+            }
+            let class = language
+                .as_ref()
+                .map(|l| format!(" class=\"language-{}\"", escape_html(l)))
+                .unwrap_or_default();
+            html.push_str(&format!(
+                "<pre><code{class}>{}</code></pre>\n</figure>\n",
+                highlight(content, language.as_deref())
+            ));
+        }
+        Attachment::File { path, description } => {
+            html.push_str(&format!(
+                "<p class=\"attachment file\">📎 <code>{}</code>{}</p>\n",
+                escape_html(path),
+                description
+                    .as_ref()
+                    .map(|d| format!(" &mdash; {}", escape_html(d)))
+                    .unwrap_or_default()
+            ));
+        }
+        Attachment::Image {
+            mime_type,
+            data,
+            alt,
+        } => {
+            html.push_str(&format!(
+                "<figure class=\"attachment image\"><img src=\"data:{};base64,{}\" alt=\"{}\"></figure>\n",
+                escape_html(mime_type),
+                escape_html(data),
+                escape_html(alt.as_deref().unwrap_or(""))
+            ));
+        }
+        Attachment::Url { url, title } => {
+            html.push_str(&format!(
+                "<p class=\"attachment url\">🔗 <a href=\"{}\" rel=\"noopener noreferrer\">{}</a></p>\n",
+                escape_html(url),
+                escape_html(title.as_deref().unwrap_or(url))
+            ));
+        }
+    }
+}
+
+/// Escape text for inclusion in HTML
+pub(super) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Parent/child layout of a session's thoughts
+struct ThoughtTree<'a> {
+    thoughts: &'a [SharedThought],
+    /// Reviewer annotations on the thoughts
+    annotations: &'a [Annotation],
+    /// Revisions keyed by the number of the revised thought
+    revisions: HashMap<u32, Vec<usize>>,
+    /// Branch identifiers keyed by the number of the thought they fork from
+    branches: HashMap<u32, Vec<String>>,
+    /// Thoughts of each branch, in session order
+    branch_members: HashMap<String, Vec<usize>>,
+    /// Thoughts rendered so far
+    rendered: Vec<bool>,
+}
+
+impl<'a> ThoughtTree<'a> {
+    fn new(thoughts: &'a [SharedThought], annotations: &'a [Annotation]) -> Self {
+        let mut revisions: HashMap<u32, Vec<usize>> = HashMap::new();
+        let mut branches: HashMap<u32, Vec<String>> = HashMap::new();
+        let mut branch_members: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for (i, thought) in thoughts.iter().enumerate() {
+            if thought.is_revision() {
+                if let Some(revised) = thought.revises_thought {
+                    revisions.entry(revised).or_default().push(i);
+                    continue;
+                }
+            }
+            if let Some(ref branch_id) = thought.branch_id {
+                let members = branch_members.entry(branch_id.clone()).or_default();
+                if members.is_empty() {
+                    let anchor = thought.branch_from_thought.unwrap_or(0);
+                    branches.entry(anchor).or_default().push(branch_id.clone());
+                }
+                members.push(i);
+            }
+        }
+
+        Self {
+            thoughts,
+            annotations,
+            revisions,
+            branches,
+            branch_members,
+            rendered: vec![false; thoughts.len()],
+        }
+    }
+
+    /// Render the whole tree, starting from main-line thoughts
+    fn render(mut self, html: &mut String) {
+        html.push_str("<ul class=\"tree\">\n");
+        for i in 0..self.thoughts.len() {
+            let thought = &self.thoughts[i];
+            let nested = thought.branch_id.is_some()
+                || (thought.is_revision() && thought.revises_thought.is_some());
+            if !nested {
+                self.render_node(i, html);
+            }
+        }
+        // Anything still unrendered references a thought that is not in the export
+        let mut orphaned: Vec<String> = self.branches.drain().flat_map(|(_, ids)| ids).collect();
+        orphaned.sort();
+        for branch_id in orphaned {
+            self.render_branch(&branch_id, html);
+        }
+        for i in 0..self.thoughts.len() {
+            if !self.rendered[i] {
+                self.render_node(i, html);
+            }
+        }
+        html.push_str("</ul>\n");
+    }
+
+    fn render_node(&mut self, index: usize, html: &mut String) {
+        if self.rendered[index] {
+            return;
+        }
+        self.rendered[index] = true;
+
+        let thought = &self.thoughts[index];
+        let (kind, css_class) = if thought.is_revision() {
+            ("Revision", "thought revision")
+        } else if thought.is_branch() {
+            ("Branch", "thought branch")
+        } else {
+            ("Thought", "thought")
+        };
+
+        html.push_str("<li>\n");
+        html.push_str(&format!(
+            "<details class=\"{css_class}\" id=\"thought-{index}\" open>\n"
+        ));
+        html.push_str(&format!(
+            "<summary>{kind} {}/{}</summary>\n",
+            thought.thought_number, thought.total_thoughts
+        ));
+
+        if let Some(timestamp) = thought.timestamp {
+            html.push_str(&format!(
+                "<p class=\"timestamp\">{}</p>\n",
+                timestamp.format("%Y-%m-%d %H:%M:%S UTC")
+            ));
+        }
+        if let Some(ref author) = thought.author {
+            html.push_str(&format!(
+                "<p class=\"author\">By {}</p>\n",
+                escape_html(author)
+            ));
+        }
+
+        render_content(&thought.thought, html);
+        for attachment in &thought.attachments {
+            render_attachment(attachment, html);
+        }
+
+        if let Some(revises_thought) = thought.revises_thought {
+            html.push_str(&format!(
+                "<p class=\"revision-note\">Revises thought {revises_thought}</p>\n"
+            ));
+        }
+        if let Some(ref branch_id) = thought.branch_id {
+            html.push_str(&format!(
+                "<p class=\"branch-note\">Branch ID: {}</p>\n",
+                escape_html(branch_id)
+            ));
+        }
+        if !thought.depends_on.is_empty() {
+            html.push_str(&format!(
+                "<p class=\"dependency-note\">Builds on thoughts {}</p>\n",
+                join_numbers(&thought.depends_on)
+            ));
+        }
+
+        for annotation in self
+            .annotations
+            .iter()
+            .filter(|a| a.targets(thought.thought_number, thought.branch_id.as_deref()))
+        {
+            let class = if annotation.resolved {
+                "annotation resolved"
+            } else {
+                "annotation"
+            };
+            html.push_str(&format!(
+                "<blockquote class=\"{class}\"><p class=\"annotation-meta\">{} &middot; {}</p><p>{}</p></blockquote>\n",
+                escape_html(&annotation.author),
+                annotation.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                escape_html(&annotation.body)
+            ));
+        }
+
+        // Revisions nest under the thought they revise within the same
+        // branch; branches fork from main-line thoughts only
+        let mut children = String::new();
+        if !thought.is_revision() {
+            let branch_id = thought.branch_id.clone();
+            for revision in self
+                .revisions
+                .get(&thought.thought_number)
+                .cloned()
+                .unwrap_or_default()
+            {
+                if self.thoughts[revision].branch_id == branch_id {
+                    self.render_node(revision, &mut children);
+                }
+            }
+            if branch_id.is_none() {
+                for branch_id in self
+                    .branches
+                    .remove(&thought.thought_number)
+                    .unwrap_or_default()
+                {
+                    self.render_branch(&branch_id, &mut children);
+                }
+            }
+        }
+        if !children.is_empty() {
+            html.push_str("<ul>\n");
+            html.push_str(&children);
+            html.push_str("</ul>\n");
+        }
+
+        html.push_str("</details>\n</li>\n");
+    }
+
+    fn render_branch(&mut self, branch_id: &str, html: &mut String) {
+        let members = self
+            .branch_members
+            .get(branch_id)
+            .cloned()
+            .unwrap_or_default();
+        html.push_str("<li>\n<details class=\"branch-group\" open>\n");
+        html.push_str(&format!(
+            "<summary>Branch {} ({} thoughts)</summary>\n<ul>\n",
+            escape_html(branch_id),
+            members.len()
+        ));
+        for index in members {
+            self.render_node(index, html);
+        }
+        html.push_str("</ul>\n</details>\n</li>\n");
+    }
+}
+
+/// Render a session as a self-contained HTML document
+pub(crate) fn render(
+    data: &ExportData,
+    options: &ExportOptions,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut html = String::new();
+
+    let title = data
+        .session
+        .metadata
+        .as_ref()
+        .map(|m| m.title.clone())
+        .unwrap_or_else(|| "Sequential Thinking Session".to_string());
+
+    // Header
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+    html.push_str("<meta charset=\"UTF-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n");
+    html.push_str("<meta name=\"color-scheme\" content=\"light dark\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape_html(&title)));
+    html.push_str("<style>\n");
+    html.push_str(STYLE);
+    html.push_str("</style>\n");
+    if let Some(ref styling) = options.custom_styling {
+        html.push_str("<style>\n");
+        html.push_str(styling);
+        html.push_str("\n</style>\n");
+    }
+    html.push_str("</head>\n<body>\n");
+
+    html.push_str("<div class=\"container\">\n");
+    html.push_str(&format!("<h1>{}</h1>\n", escape_html(&title)));
+
+    // Session information
+    html.push_str("<div class=\"session-info\">\n");
+    html.push_str(&format!(
+        "<p><strong>Session ID:</strong> {}</p>\n",
+        escape_html(&data.session.session_id)
+    ));
+    if let Some(ref metadata) = data.session.metadata {
+        if let Some(ref author) = metadata.author {
+            html.push_str(&format!(
+                "<p><strong>Author:</strong> {}</p>\n",
+                escape_html(author)
+            ));
+        }
+        if let Some(ref description) = metadata.description {
+            html.push_str(&format!(
+                "<p><strong>Description:</strong> {}</p>\n",
+                escape_html(description)
+            ));
+        }
+        html.push_str(&format!(
+            "<p><strong>Status:</strong> {:?}</p>\n",
+            metadata.status
+        ));
+        html.push_str(&format!(
+            "<p><strong>Priority:</strong> {:?}</p>\n",
+            metadata.priority
+        ));
+    }
+    html.push_str("</div>\n");
+
+    // Progress
+    if let Some(ref progress) = data.session.progress {
+        let percent = progress.progress_percentage * 100.0;
+        html.push_str(&format!(
+            "<div class=\"progress-bar\"><div class=\"progress-fill\" style=\"width: {percent:.1}%\">{percent:.0}%</div></div>\n"
+        ));
+    }
+
+    // Statistics
+    if let Some(ref stats) = data.session.statistics {
+        html.push_str("<div class=\"statistics\">\n<ul>\n");
+        html.push_str(&format!(
+            "<li><strong>Total Thoughts:</strong> {}</li>\n",
+            stats.total_thoughts
+        ));
+        html.push_str(&format!(
+            "<li><strong>Total Revisions:</strong> {}</li>\n",
+            stats.total_revisions
+        ));
+        html.push_str(&format!(
+            "<li><strong>Total Branches:</strong> {}</li>\n",
+            stats.total_branches
+        ));
+        html.push_str("</ul>\n</div>\n");
+    }
+
+    // Thoughts
+    html.push_str("<h2>Thoughts</h2>\n");
+    html.push_str("<div class=\"toolbar\">\n");
+    html.push_str("<button id=\"expand-all\" type=\"button\">Expand all</button>\n");
+    html.push_str("<button id=\"collapse-all\" type=\"button\">Collapse all</button>\n");
+    html.push_str("<button id=\"toggle-theme\" type=\"button\">Toggle theme</button>\n");
+    html.push_str("</div>\n");
+    ThoughtTree::new(&data.session.thoughts, &data.session.annotations).render(&mut html);
+
+    // Analytics
+    if let Some(ref analytics) = data.session.analytics {
+        html.push_str("<div class=\"analytics\">\n<h2>Analytics</h2>\n<pre>");
+        html.push_str(&escape_html(&serde_json::to_string_pretty(analytics)?));
+        html.push_str("</pre>\n</div>\n");
+    }
+
+    html.push_str("</div>\n");
+
+    // Footer
+    html.push_str("<footer>\n");
+    html.push_str(&format!(
+        "<p>Exported on {} using UltraFast MCP Sequential Thinking</p>\n",
+        data.export_metadata
+            .exported_at
+            .format("%Y-%m-%d %H:%M:%S UTC")
+    ));
+    html.push_str("</footer>\n");
+
+    html.push_str("<script>\n");
+    html.push_str(SCRIPT);
+    html.push_str("</script>\n");
+    html.push_str("</body>\n</html>");
+
+    Ok(html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{ExportMetadata, SessionExportData};
+    use crate::thinking::ThoughtData;
+    use chrono::Utc;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_html_tree() {
+        let mut revision = ThoughtData::new("Refined <start>".to_string(), 3, 3);
+        revision.is_revision = Some(true);
+        revision.revises_thought = Some(1);
+
+        let thoughts: Vec<SharedThought> = vec![
+            ThoughtData::new("Start".to_string(), 1, 3)
+                .with_attachment(Attachment::code("a < b", Some("rust"))),
+            ThoughtData::new("Continue\n```rust\nlet x = 1;\n```".to_string(), 2, 3),
+            revision,
+            ThoughtData::branch("Fork".to_string(), 2, 1, "alt".to_string()),
+            ThoughtData::branch("Fork more".to_string(), 3, 1, "alt".to_string()),
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+
+        let data = ExportData {
+            session: SessionExportData {
+                session_id: "tree".to_string(),
+                metadata: None,
+                thoughts,
+                statistics: None,
+                progress: None,
+                branches: HashMap::new(),
+                analytics: None,
+                annotations: Vec::new(),
+                action_items: Vec::new(),
+                branch_report: Vec::new(),
+            },
+            export_metadata: ExportMetadata {
+                exported_at: Utc::now(),
+                format: "html".to_string(),
+                version: "1.0.0".to_string(),
+                tool: "test".to_string(),
+                options: ExportOptions::default(),
+            },
+            custom_data: HashMap::new(),
+        };
+
+        let options = ExportOptions {
+            custom_styling: Some(".thought { color: red; }".to_string()),
+            ..ExportOptions::default()
+        };
+        let html = render(&data, &options).unwrap();
+
+        // Each thought appears exactly once
+        assert_eq!(html.matches("<details class=\"thought").count(), 5);
+        assert!(html.contains("Refined &lt;start&gt;"));
+        assert!(html.contains(
+            "<pre class=\"code\"><code class=\"language-rust\"><span class=\"tok-keyword\">let</span> x"
+        ));
+        assert!(html.contains("<pre><code class=\"language-rust\">a &lt; b</code></pre>"));
+        assert!(html.contains("Branch alt (2 thoughts)"));
+        assert!(html.contains(".thought { color: red; }"));
+        assert!(html.contains("prefers-color-scheme: dark"));
+        assert!(html.contains("<script>"));
+
+        // The revision and the branch both nest under thought 1
+        let first = html.find("<summary>Thought 1/3</summary>").unwrap();
+        let second = html.find("<summary>Thought 2/3</summary>").unwrap();
+        let revision = html.find("<summary>Revision 3/3</summary>").unwrap();
+        let branch = html.find("Branch alt (2 thoughts)").unwrap();
+        assert!(first < revision && revision < second);
+        assert!(first < branch && branch < second);
+    }
+}
@@ -10,8 +10,25 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+pub mod bundle;
+pub mod dedup;
+pub mod destination;
+mod epub;
+mod highlight;
+mod html;
+pub mod session_log;
+pub mod stream;
+
+use crate::actions::{self, ActionExtractor, ActionItem};
+use crate::analytics::branches::BranchMetrics;
+use crate::analytics::{AnalyticsEngine, SessionMetricsRow};
+use crate::annotations::{Annotation, AnnotationStore};
 use crate::session::SessionMetadata;
-use crate::thinking::{ThinkingProgress, ThinkingStats, ThoughtData};
+use crate::thinking::attachment::Attachment;
+use crate::thinking::code;
+use crate::thinking::{SharedThought, ThinkingProgress, ThinkingStats, ThoughtData};
+pub use dedup::{ExportIndex, IndexedExport};
+pub use destination::{DestinationConfig, ExportDestination, ExportLocation, S3Config};
 
 /// Export configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +51,9 @@ pub struct ExportConfig {
     pub compression: bool,
     /// Export encryption
     pub encryption: bool,
+    /// Where exports are written
+    #[serde(default)]
+    pub destination: DestinationConfig,
 }
 
 impl Default for ExportConfig {
@@ -52,6 +72,7 @@ impl Default for ExportConfig {
             include_analytics: false,
             compression: false,
             encryption: false,
+            destination: DestinationConfig::Local,
         }
     }
 }
@@ -66,6 +87,11 @@ pub enum ExportFormat {
     Csv,
     Yaml,
     Toml,
+    Jsonl,
+    OrgMode,
+    Asciidoc,
+    Bundle,
+    Epub,
 }
 
 impl ExportFormat {
@@ -79,6 +105,11 @@ impl ExportFormat {
             ExportFormat::Csv => "csv",
             ExportFormat::Yaml => "yml",
             ExportFormat::Toml => "toml",
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::OrgMode => "org",
+            ExportFormat::Asciidoc => "adoc",
+            ExportFormat::Bundle => "zip",
+            ExportFormat::Epub => "epub",
         }
     }
 
@@ -92,6 +123,11 @@ impl ExportFormat {
             ExportFormat::Csv => "text/csv",
             ExportFormat::Yaml => "application/x-yaml",
             ExportFormat::Toml => "application/toml",
+            ExportFormat::Jsonl => "application/x-ndjson",
+            ExportFormat::OrgMode => "text/org",
+            ExportFormat::Asciidoc => "text/asciidoc",
+            ExportFormat::Bundle => "application/zip",
+            ExportFormat::Epub => "application/epub+zip",
         }
     }
 }
@@ -119,6 +155,9 @@ pub struct ExportOptions {
     pub custom_styling: Option<String>,
     /// Export template
     pub template: Option<String>,
+    /// Whether to write the export even when its content is unchanged
+    #[serde(default)]
+    pub force: bool,
 }
 
 impl Default for ExportOptions {
@@ -134,6 +173,7 @@ impl Default for ExportOptions {
             pretty_print: true,
             custom_styling: None,
             template: None,
+            force: false,
         }
     }
 }
@@ -157,15 +197,34 @@ pub struct SessionExportData {
     /// Session metadata
     pub metadata: Option<SessionMetadata>,
     /// Thoughts
-    pub thoughts: Vec<ThoughtData>,
+    pub thoughts: Vec<SharedThought>,
     /// Statistics
     pub statistics: Option<ThinkingStats>,
     /// Progress
     pub progress: Option<ThinkingProgress>,
     /// Branches
-    pub branches: HashMap<String, Vec<ThoughtData>>,
+    pub branches: HashMap<String, Vec<SharedThought>>,
     /// Analytics
     pub analytics: Option<serde_json::Value>,
+    /// Reviewer annotations
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+    /// Action items mentioned in the thoughts
+    #[serde(default)]
+    pub action_items: Vec<ActionItem>,
+    /// Metrics and outcome of each branch
+    #[serde(default)]
+    pub branch_report: Vec<BranchMetrics>,
+}
+
+impl SessionExportData {
+    /// Get the annotations attached to a thought
+    pub fn annotations_for(&self, thought: &ThoughtData) -> Vec<&Annotation> {
+        self.annotations
+            .iter()
+            .filter(|a| a.targets(thought.thought_number, thought.branch_id.as_deref()))
+            .collect()
+    }
 }
 
 /// Export metadata
@@ -183,6 +242,16 @@ pub struct ExportMetadata {
     pub options: ExportOptions,
 }
 
+/// Rendering of one format in [`ExportEngine::export_all`]
+enum Rendering {
+    /// Content unchanged since the export at this location
+    Unchanged(ExportLocation),
+    /// Content rendered on the current task
+    Rendered(Result<Vec<u8>, String>),
+    /// Content being rendered on the blocking thread pool
+    Blocking(tokio::task::JoinHandle<Result<Vec<u8>, String>>),
+}
+
 /// Export engine for handling session exports
 pub struct ExportEngine {
     /// Configuration
@@ -191,6 +260,14 @@ pub struct ExportEngine {
     templates: HashMap<String, String>,
     /// Export history
     export_history: Vec<ExportRecord>,
+    /// Destination overriding the configured one
+    destination: Option<Box<dyn ExportDestination>>,
+    /// Finds the action items listed in exports
+    actions: ActionExtractor,
+    /// Hashes of the last export of each session
+    index: ExportIndex,
+    /// File the index is saved to after every export
+    index_path: Option<PathBuf>,
 }
 
 /// Export record for tracking export history
@@ -204,12 +281,32 @@ pub struct ExportRecord {
     pub exported_at: DateTime<Utc>,
     /// Export file path
     pub file_path: Option<PathBuf>,
+    /// Where the export was written
+    #[serde(default)]
+    pub location: Option<ExportLocation>,
     /// Export size in bytes
     pub file_size: Option<u64>,
     /// Export success
     pub success: bool,
     /// Error message if failed
     pub error_message: Option<String>,
+    /// Hash of the exported payload
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// Whether nothing was written because the content was unchanged
+    #[serde(default)]
+    pub skipped_duplicate: bool,
+}
+
+impl std::fmt::Debug for ExportEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExportEngine")
+            .field("config", &self.config)
+            .field("destination", &self.destination_label())
+            .field("exports", &self.export_history.len())
+            .field("index_path", &self.index_path)
+            .finish_non_exhaustive()
+    }
 }
 
 impl ExportEngine {
@@ -219,6 +316,10 @@ impl ExportEngine {
             config: ExportConfig::default(),
             templates: HashMap::new(),
             export_history: Vec::new(),
+            destination: None,
+            actions: ActionExtractor::default(),
+            index: ExportIndex::default(),
+            index_path: None,
         }
     }
 
@@ -228,22 +329,88 @@ impl ExportEngine {
             config,
             templates: HashMap::new(),
             export_history: Vec::new(),
+            destination: None,
+            actions: ActionExtractor::default(),
+            index: ExportIndex::default(),
+            index_path: None,
         }
     }
 
+    /// Recognize action items with a custom extractor
+    pub fn with_action_extractor(mut self, actions: ActionExtractor) -> Self {
+        self.actions = actions;
+        self
+    }
+
+    /// Write exports to a custom destination instead of the configured one
+    pub fn with_destination(mut self, destination: Box<dyn ExportDestination>) -> Self {
+        self.destination = Some(destination);
+        self
+    }
+
+    /// Keep the export index in a file, loading the hashes saved there
+    pub fn with_export_index(mut self, path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        self.index = ExportIndex::load(&path)?;
+        self.index_path = Some(path);
+        Ok(self)
+    }
+
+    /// Hashes of the last export of each session
+    pub fn export_index(&self) -> &ExportIndex {
+        &self.index
+    }
+
+    /// Description of where exports are written, distinguishing index entries
+    fn destination_label(&self) -> String {
+        match (&self.destination, &self.config.destination) {
+            (Some(destination), _) => destination.describe(),
+            (None, DestinationConfig::Local) => self.config.export_directory.clone(),
+            (None, DestinationConfig::S3(s3)) => format!("s3://{}/{}", s3.bucket, s3.prefix),
+        }
+    }
+
+    /// Write rendered export content to the destination
+    async fn write_export(
+        &self,
+        filename: &str,
+        content: &[u8],
+        format: &ExportFormat,
+    ) -> Result<ExportLocation, Box<dyn std::error::Error>> {
+        let written = match &self.destination {
+            Some(destination) => {
+                destination
+                    .write(filename, content, format.mime_type())
+                    .await
+            }
+            None => {
+                let destination = destination::from_config(
+                    &self.config.destination,
+                    &self.config.export_directory,
+                )
+                .map_err(|e| e as Box<dyn std::error::Error>)?;
+                destination
+                    .write(filename, content, format.mime_type())
+                    .await
+            }
+        };
+        written.map_err(|e| e as Box<dyn std::error::Error>)
+    }
+
     /// Export a session
     #[allow(clippy::too_many_arguments)]
     pub async fn export_session(
         &mut self,
         session_id: &str,
         session_metadata: Option<&SessionMetadata>,
-        thoughts: &[ThoughtData],
+        thoughts: &[SharedThought],
         stats: Option<&ThinkingStats>,
         progress: Option<&ThinkingProgress>,
-        branches: Option<&HashMap<String, Vec<ThoughtData>>>,
+        branches: Option<&HashMap<String, Vec<SharedThought>>>,
         analytics: Option<&serde_json::Value>,
+        annotations: Option<&AnnotationStore>,
         _options: ExportOptions,
-    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    ) -> Result<ExportLocation, Box<dyn std::error::Error>> {
         let start_time = std::time::Instant::now();
 
         // Prepare export data
@@ -255,42 +422,79 @@ impl ExportEngine {
             progress,
             branches,
             analytics,
+            annotations,
             &_options,
         )?;
 
+        // Unchanged content is not written again unless forced
+        let content_hash = dedup::content_hash(&export_data)?;
+        let index_key = ExportIndex::key(&self.destination_label(), session_id, &_options.format);
+        if !_options.force {
+            if let Some(previous) = self.index.unchanged(&index_key, &content_hash) {
+                let location = previous.location.clone();
+                self.export_history.push(ExportRecord {
+                    session_id: session_id.to_string(),
+                    format: _options.format,
+                    exported_at: Utc::now(),
+                    file_path: location.path().map(PathBuf::from),
+                    location: Some(location.clone()),
+                    file_size: None,
+                    success: true,
+                    error_message: None,
+                    content_hash: Some(content_hash),
+                    skipped_duplicate: true,
+                });
+                tracing::info!(
+                    "Skipped export of session {}, unchanged since {}",
+                    session_id,
+                    location
+                );
+                return Ok(location);
+            }
+        }
+
         // Generate filename
         let filename = self.generate_filename(session_id, &_options.format)?;
-        let file_path = PathBuf::from(&self.config.export_directory).join(&filename);
-
-        // Ensure export directory exists
-        if let Some(parent) = file_path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-
-        // Export based on format
-        let content = match _options.format {
-            ExportFormat::Json => self.export_to_json(&export_data, &_options)?,
-            ExportFormat::Markdown => self.export_to_markdown(&export_data, &_options)?,
-            ExportFormat::Html => self.export_to_html(&export_data, &_options)?,
-            ExportFormat::Csv => self.export_to_csv(&export_data, &_options)?,
-            ExportFormat::Yaml => self.export_to_yaml(&export_data, &_options)?,
-            ExportFormat::Toml => self.export_to_toml(&export_data, &_options)?,
-            ExportFormat::Pdf => self.export_to_pdf(&export_data, &_options)?,
-        };
 
-        // Write to file
-        std::fs::write(&file_path, content)?;
+        // Local exports are streamed to the file; other destinations take
+        // the rendered content
+        let (location, file_size) = match (&self.destination, &self.config.destination) {
+            (None, DestinationConfig::Local) => {
+                let path = PathBuf::from(&self.config.export_directory).join(&filename);
+                let written = self.stream_to_file(&export_data, &path).await?;
+                (ExportLocation::File { path }, written)
+            }
+            _ => {
+                let content = self.render_bytes(&export_data, &_options)?;
+                let location = self
+                    .write_export(&filename, &content, &_options.format)
+                    .await?;
+                (location, content.len() as u64)
+            }
+        };
 
         // Record export
-        let file_size = std::fs::metadata(&file_path).ok().map(|m| m.len());
+        let exported_at = Utc::now();
+        self.index.record(
+            index_key,
+            IndexedExport {
+                content_hash: content_hash.clone(),
+                location: location.clone(),
+                exported_at,
+            },
+        );
+        self.save_index();
         let export_record = ExportRecord {
             session_id: session_id.to_string(),
             format: _options.format,
-            exported_at: Utc::now(),
-            file_path: Some(file_path.clone()),
-            file_size,
+            exported_at,
+            file_path: location.path().map(PathBuf::from),
+            location: Some(location.clone()),
+            file_size: Some(file_size),
             success: true,
             error_message: None,
+            content_hash: Some(content_hash),
+            skipped_duplicate: false,
         };
         self.export_history.push(export_record);
 
@@ -298,24 +502,174 @@ impl ExportEngine {
         tracing::info!(
             "Exported session {} to {} in {:?}",
             session_id,
-            file_path.display(),
+            location,
             duration
         );
 
-        Ok(file_path)
+        Ok(location)
+    }
+
+    /// Export prepared session data in several formats at once
+    ///
+    /// The formats are rendered concurrently, HTML, PDF and EPUB on the blocking
+    /// thread pool, and then written to the destination. As with
+    /// [`ExportEngine::export_session`], unchanged content is skipped unless
+    /// the options in `data` force it. Returns one record per format, in the
+    /// order given; the records are added to the export history as well.
+    pub async fn export_all(
+        &mut self,
+        data: &ExportData,
+        formats: &[ExportFormat],
+    ) -> Vec<ExportRecord> {
+        let session_id = data.session.session_id.clone();
+        let destination = self.destination_label();
+
+        let mut pending = Vec::with_capacity(formats.len());
+        for format in formats {
+            let mut data = data.clone();
+            data.export_metadata.format = format.to_string();
+            data.export_metadata.options.format = format.clone();
+            let options = data.export_metadata.options.clone();
+            let key = ExportIndex::key(&destination, &session_id, format);
+            let content_hash = match dedup::content_hash(&data) {
+                Ok(hash) => hash,
+                Err(e) => {
+                    pending.push((key, None, Rendering::Rendered(Err(e.to_string()))));
+                    continue;
+                }
+            };
+            let unchanged = self
+                .index
+                .unchanged(&key, &content_hash)
+                .filter(|_| !options.force)
+                .map(|previous| previous.location.clone());
+            let rendering = match (unchanged, format) {
+                (Some(location), _) => Rendering::Unchanged(location),
+                // PDF is rendered as HTML
+                (None, ExportFormat::Html | ExportFormat::Pdf) => {
+                    Rendering::Blocking(tokio::task::spawn_blocking(move || {
+                        html::render(&data, &options)
+                            .map(String::into_bytes)
+                            .map_err(|e| e.to_string())
+                    }))
+                }
+                (None, ExportFormat::Epub) => {
+                    Rendering::Blocking(tokio::task::spawn_blocking(move || {
+                        epub::render(&data).map_err(|e| e.to_string())
+                    }))
+                }
+                (None, _) => {
+                    let content = self
+                        .render_bytes(&data, &options)
+                        .map_err(|e| e.to_string());
+                    Rendering::Rendered(content)
+                }
+            };
+            pending.push((key, Some(content_hash), rendering));
+        }
+
+        let mut records = Vec::with_capacity(pending.len());
+        for (format, (key, content_hash, rendering)) in formats.iter().zip(pending) {
+            let mut record = ExportRecord {
+                session_id: session_id.clone(),
+                format: format.clone(),
+                exported_at: Utc::now(),
+                file_path: None,
+                location: None,
+                file_size: None,
+                success: true,
+                error_message: None,
+                content_hash: content_hash.clone(),
+                skipped_duplicate: false,
+            };
+            let content = match rendering {
+                Rendering::Unchanged(location) => {
+                    record.file_path = location.path().map(PathBuf::from);
+                    record.location = Some(location);
+                    record.skipped_duplicate = true;
+                    records.push(record);
+                    continue;
+                }
+                Rendering::Rendered(content) => content,
+                Rendering::Blocking(handle) => handle.await.unwrap_or_else(|e| Err(e.to_string())),
+            };
+            let written = match content {
+                Ok(content) => self.write_rendered(&session_id, format, &content).await,
+                Err(e) => Err(e),
+            };
+            match written {
+                Ok((location, file_size)) => {
+                    if let Some(content_hash) = content_hash {
+                        self.index.record(
+                            key,
+                            IndexedExport {
+                                content_hash,
+                                location: location.clone(),
+                                exported_at: record.exported_at,
+                            },
+                        );
+                    }
+                    record.file_path = location.path().map(PathBuf::from);
+                    record.location = Some(location);
+                    record.file_size = Some(file_size);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to export session {} as {}: {}",
+                        session_id,
+                        format,
+                        e
+                    );
+                    record.success = false;
+                    record.error_message = Some(e);
+                }
+            }
+            records.push(record);
+        }
+
+        self.save_index();
+        self.export_history.extend(records.iter().cloned());
+        records
+    }
+
+    /// Write rendered content under a generated file name
+    async fn write_rendered(
+        &self,
+        session_id: &str,
+        format: &ExportFormat,
+        content: &[u8],
+    ) -> Result<(ExportLocation, u64), String> {
+        let filename = self
+            .generate_filename(session_id, format)
+            .map_err(|e| e.to_string())?;
+        let written: Result<ExportLocation, String> = self
+            .write_export(&filename, content, format)
+            .await
+            .map_err(|e| e.to_string());
+        written.map(|location| (location, content.len() as u64))
+    }
+
+    /// Save the export index, if it is kept in a file
+    fn save_index(&self) {
+        if let Some(path) = &self.index_path {
+            if let Err(e) = self.index.save(path) {
+                tracing::warn!("Failed to save export index: {}", e);
+            }
+        }
     }
 
     /// Prepare export data
     #[allow(clippy::too_many_arguments)]
-    fn prepare_export_data(
+    pub fn prepare_export_data(
         &self,
         session_id: &str,
         session_metadata: Option<&SessionMetadata>,
-        thoughts: &[ThoughtData],
+        thoughts: &[SharedThought],
         stats: Option<&ThinkingStats>,
         progress: Option<&ThinkingProgress>,
-        branches: Option<&HashMap<String, Vec<ThoughtData>>>,
+        branches: Option<&HashMap<String, Vec<SharedThought>>>,
         analytics: Option<&serde_json::Value>,
+        annotations: Option<&AnnotationStore>,
         _options: &ExportOptions,
     ) -> Result<ExportData, Box<dyn std::error::Error>> {
         let session_data = SessionExportData {
@@ -346,6 +700,15 @@ impl ExportEngine {
             } else {
                 None
             },
+            annotations: annotations
+                .map(|store| store.all().to_vec())
+                .unwrap_or_default(),
+            action_items: self.actions.extract(thoughts),
+            branch_report: if _options.include_branches {
+                AnalyticsEngine::new().branch_breakdown(thoughts)
+            } else {
+                Vec::new()
+            },
         };
 
         let export_metadata = ExportMetadata {
@@ -363,6 +726,42 @@ impl ExportEngine {
         })
     }
 
+    /// Render export data in its format, including binary formats
+    fn render_bytes(
+        &self,
+        data: &ExportData,
+        options: &ExportOptions,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        match options.format {
+            ExportFormat::Bundle => self.export_to_bundle(data, options),
+            ExportFormat::Epub => epub::render(data),
+            _ => Ok(self.render(data, options)?.into_bytes()),
+        }
+    }
+
+    /// Render export data in a text format
+    fn render(
+        &self,
+        data: &ExportData,
+        options: &ExportOptions,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        match options.format {
+            ExportFormat::Json => self.export_to_json(data, options),
+            ExportFormat::Markdown => self.export_to_markdown(data, options),
+            ExportFormat::Html => self.export_to_html(data, options),
+            ExportFormat::Csv => self.export_to_csv(data, options),
+            ExportFormat::Yaml => self.export_to_yaml(data, options),
+            ExportFormat::Toml => self.export_to_toml(data, options),
+            ExportFormat::Pdf => self.export_to_pdf(data, options),
+            ExportFormat::Jsonl => self.export_to_jsonl(data),
+            ExportFormat::OrgMode => self.export_to_org(data),
+            ExportFormat::Asciidoc => self.export_to_asciidoc(data),
+            ExportFormat::Bundle | ExportFormat::Epub => {
+                Err(format!("{} exports are binary", options.format).into())
+            }
+        }
+    }
+
     /// Generate filename
     fn generate_filename(
         &self,
@@ -402,267 +801,228 @@ impl ExportEngine {
         data: &ExportData,
         _options: &ExportOptions,
     ) -> Result<String, Box<dyn std::error::Error>> {
-        let mut markdown = String::new();
-
-        // Header
-        markdown.push_str("# Sequential Thinking Session\n\n");
-
-        // Session information
-        markdown.push_str(&format!("**Session ID:** {}\n\n", data.session.session_id));
+        let mut markdown = markdown_header(data);
+        for thought in &data.session.thoughts {
+            markdown.push_str(&markdown_thought(data, thought));
+        }
+        markdown.push_str(&markdown_footer(data)?);
+        Ok(markdown)
+    }
 
+    /// Export to Org-mode format (one headline per thought)
+    fn export_to_org(&self, data: &ExportData) -> Result<String, Box<dyn std::error::Error>> {
+        let mut org = String::new();
+
+        let title = data
+            .session
+            .metadata
+            .as_ref()
+            .map(|m| m.title.clone())
+            .unwrap_or_else(|| "Sequential Thinking Session".to_string());
+        org.push_str(&format!("#+TITLE: {title}\n"));
+        org.push_str(&format!(
+            "#+DATE: {}\n",
+            org_timestamp(&data.export_metadata.exported_at)
+        ));
+        org.push_str(&format!(
+            "#+PROPERTY: SESSION_ID {}\n",
+            data.session.session_id
+        ));
         if let Some(ref metadata) = data.session.metadata {
-            markdown.push_str(&format!("**Title:** {}\n", metadata.title));
             if let Some(ref description) = metadata.description {
-                markdown.push_str(&format!("**Description:** {description}\n"));
+                org.push_str(&format!("#+DESCRIPTION: {description}\n"));
+            }
+            if !metadata.tags.is_empty() {
+                org.push_str(&format!("#+FILETAGS: :{}:\n", metadata.tags.join(":")));
             }
-            markdown.push_str(&format!("**Status:** {:?}\n", metadata.status));
-            markdown.push_str(&format!("**Priority:** {:?}\n", metadata.priority));
-            markdown.push_str(&format!(
-                "**Created:** {}\n",
-                metadata.created_at.format("%Y-%m-%d %H:%M:%S UTC")
-            ));
-            markdown.push_str(&format!(
-                "**Modified:** {}\n",
-                metadata.last_modified.format("%Y-%m-%d %H:%M:%S UTC")
-            ));
-            markdown.push('\n');
-        }
-
-        // Statistics
-        if let Some(ref stats) = data.session.statistics {
-            markdown.push_str("## Statistics\n\n");
-            markdown.push_str(&format!("- **Total Thoughts:** {}\n", stats.total_thoughts));
-            markdown.push_str(&format!(
-                "- **Total Revisions:** {}\n",
-                stats.total_revisions
-            ));
-            markdown.push_str(&format!("- **Total Branches:** {}\n", stats.total_branches));
-            markdown.push_str(&format!(
-                "- **Average Processing Time:** {:.2}ms\n",
-                stats.avg_processing_time_ms
-            ));
-            markdown.push_str(&format!(
-                "- **Total Processing Time:** {}ms\n",
-                stats.total_processing_time_ms
-            ));
-            markdown.push('\n');
-        }
-
-        // Progress
-        if let Some(ref progress) = data.session.progress {
-            markdown.push_str("## Progress\n\n");
-            markdown.push_str(&format!(
-                "- **Current Thought:** {}/{}\n",
-                progress.current_thought, progress.total_thoughts
-            ));
-            markdown.push_str(&format!(
-                "- **Completed Thoughts:** {}\n",
-                progress.completed_thoughts
-            ));
-            markdown.push_str(&format!(
-                "- **Progress:** {:.1}%\n",
-                progress.progress_percentage * 100.0
-            ));
-            markdown.push_str(&format!(
-                "- **Status:** {}\n",
-                if progress.is_complete() {
-                    "Complete"
-                } else {
-                    "In Progress"
-                }
-            ));
-            markdown.push('\n');
         }
+        org.push('\n');
 
-        // Thoughts
-        markdown.push_str("## Thoughts\n\n");
-        for (i, thought) in data.session.thoughts.iter().enumerate() {
-            let _thought_number = i + 1;
-            let prefix = if thought.is_revision() {
-                "🔄 Revision"
+        for thought in &data.session.thoughts {
+            let keyword = if thought.is_revision() {
+                "Revision"
             } else if thought.is_branch() {
-                "🌿 Branch"
+                "Branch"
             } else {
-                "💭 Thought"
+                "Thought"
             };
-
-            markdown.push_str(&format!(
-                "### {} {}/{}\n\n",
-                prefix, thought.thought_number, thought.total_thoughts
+            org.push_str(&format!(
+                "* {keyword} {}/{}\n",
+                thought.thought_number, thought.total_thoughts
             ));
 
+            org.push_str(":PROPERTIES:\n");
+            org.push_str(&format!(":THOUGHT_NUMBER: {}\n", thought.thought_number));
+            if let Some(ref author) = thought.author {
+                org.push_str(&format!(":AUTHOR: {author}\n"));
+            }
             if let Some(timestamp) = thought.timestamp {
-                markdown.push_str(&format!(
-                    "*{}*\n\n",
-                    timestamp.format("%Y-%m-%d %H:%M:%S UTC")
+                org.push_str(&format!(":CREATED: {}\n", org_timestamp(&timestamp)));
+            }
+            if let Some(revises_thought) = thought.revises_thought {
+                org.push_str(&format!(":REVISES: {revises_thought}\n"));
+            }
+            if let Some(ref branch_id) = thought.branch_id {
+                org.push_str(&format!(":BRANCH_ID: {branch_id}\n"));
+            }
+            if let Some(branch_from) = thought.branch_from_thought {
+                org.push_str(&format!(":BRANCH_FROM: {branch_from}\n"));
+            }
+            if !thought.depends_on.is_empty() {
+                org.push_str(&format!(
+                    ":DEPENDS_ON: {}\n",
+                    join_numbers(&thought.depends_on)
                 ));
             }
-
-            markdown.push_str(&format!("{}\n\n", thought.thought));
-
-            if thought.is_revision() {
-                if let Some(revises_thought) = thought.revises_thought {
-                    markdown.push_str(&format!("*Revises thought {revises_thought}*\n\n"));
+            if let Some(ref metadata) = thought.metadata {
+                let mut keys: Vec<&String> = metadata.keys().collect();
+                keys.sort();
+                for key in keys {
+                    org.push_str(&format!(
+                        ":META_{}: {}\n",
+                        key.to_uppercase(),
+                        metadata_value(&metadata[key])
+                    ));
                 }
             }
+            org.push_str(":END:\n\n");
 
-            if thought.is_branch() {
-                if let Some(branch_id) = &thought.branch_id {
-                    markdown.push_str(&format!("*Branch ID: {branch_id}*\n\n"));
+            // Lines starting with "*" would be parsed as headlines
+            for line in thought.thought.lines() {
+                if line.starts_with('*') {
+                    org.push(',');
                 }
+                org.push_str(line);
+                org.push('\n');
             }
-        }
-
-        // Branches
-        if !data.session.branches.is_empty() {
-            markdown.push_str("## Branches\n\n");
-            for (branch_id, branch_thoughts) in &data.session.branches {
-                markdown.push_str(&format!("### Branch: {branch_id}\n\n"));
-                for thought in branch_thoughts {
-                    markdown.push_str(&format!("- {}\n", thought.thought));
-                }
-                markdown.push('\n');
+            org.push('\n');
+
+            for annotation in data.session.annotations_for(thought) {
+                let state = if annotation.resolved { "DONE" } else { "TODO" };
+                org.push_str(&format!("** {state} Comment by {}\n", annotation.author));
+                org.push_str(":PROPERTIES:\n");
+                org.push_str(&format!(":ANNOTATION_ID: {}\n", annotation.id));
+                org.push_str(&format!(
+                    ":CREATED: {}\n",
+                    org_timestamp(&annotation.created_at)
+                ));
+                org.push_str(":END:\n");
+                org.push_str(&format!("{}\n\n", annotation.body));
             }
         }
 
-        // Analytics
-        if let Some(ref analytics) = data.session.analytics {
-            markdown.push_str("## Analytics\n\n");
-            markdown.push_str("```json\n");
-            markdown.push_str(&serde_json::to_string_pretty(analytics)?);
-            markdown.push_str("\n```\n\n");
-        }
-
-        // Footer
-        markdown.push_str("---\n\n");
-        markdown.push_str(&format!(
-            "*Exported on {} using UltraFast MCP Sequential Thinking*\n",
-            data.export_metadata
-                .exported_at
-                .format("%Y-%m-%d %H:%M:%S UTC")
-        ));
-
-        Ok(markdown)
+        Ok(org)
     }
 
-    /// Export to HTML format
-    fn export_to_html(
-        &self,
-        data: &ExportData,
-        _options: &ExportOptions,
-    ) -> Result<String, Box<dyn std::error::Error>> {
-        let mut html = String::new();
-
-        // HTML header
-        html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
-        html.push_str("<meta charset=\"UTF-8\">\n");
-        html.push_str(
-            "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n",
-        );
-        html.push_str("<title>Sequential Thinking Session</title>\n");
-
-        // CSS styling
-        html.push_str("<style>\n");
-        html.push_str(include_str!("../templates/export.css"));
-        html.push_str("</style>\n");
-        html.push_str("</head>\n<body>\n");
-
-        // Content
-        html.push_str("<div class=\"container\">\n");
-        html.push_str("<h1>Sequential Thinking Session</h1>\n");
-
-        // Session information
-        html.push_str("<div class=\"session-info\">\n");
-        html.push_str(&format!(
-            "<p><strong>Session ID:</strong> {}</p>\n",
-            data.session.session_id
+    /// Export to AsciiDoc format (one section per thought)
+    fn export_to_asciidoc(&self, data: &ExportData) -> Result<String, Box<dyn std::error::Error>> {
+        let mut adoc = String::new();
+
+        let title = data
+            .session
+            .metadata
+            .as_ref()
+            .map(|m| m.title.clone())
+            .unwrap_or_else(|| "Sequential Thinking Session".to_string());
+        adoc.push_str(&format!("= {title}\n"));
+        adoc.push_str(&format!(":session-id: {}\n", data.session.session_id));
+        adoc.push_str(&format!(
+            ":revdate: {}\n",
+            data.export_metadata.exported_at.to_rfc3339()
         ));
-
         if let Some(ref metadata) = data.session.metadata {
-            html.push_str(&format!(
-                "<p><strong>Title:</strong> {}</p>\n",
-                metadata.title
-            ));
             if let Some(ref description) = metadata.description {
-                html.push_str(&format!(
-                    "<p><strong>Description:</strong> {description}</p>\n"
-                ));
+                adoc.push_str(&format!(":description: {description}\n"));
+            }
+            if !metadata.tags.is_empty() {
+                adoc.push_str(&format!(":keywords: {}\n", metadata.tags.join(", ")));
             }
-            html.push_str(&format!(
-                "<p><strong>Status:</strong> {:?}</p>\n",
-                metadata.status
-            ));
-            html.push_str(&format!(
-                "<p><strong>Priority:</strong> {:?}</p>\n",
-                metadata.priority
-            ));
         }
-        html.push_str("</div>\n");
+        adoc.push('\n');
 
-        // Thoughts
-        html.push_str("<h2>Thoughts</h2>\n");
-        html.push_str("<div class=\"thoughts\">\n");
-
-        for (i, thought) in data.session.thoughts.iter().enumerate() {
-            let _thought_number = i + 1;
-            let css_class = if thought.is_revision() {
-                "thought revision"
+        for thought in &data.session.thoughts {
+            let keyword = if thought.is_revision() {
+                "Revision"
             } else if thought.is_branch() {
-                "thought branch"
+                "Branch"
             } else {
-                "thought"
+                "Thought"
             };
 
-            html.push_str(&format!("<div class=\"{css_class}\">\n"));
-            html.push_str(&format!(
-                "<h3>Thought {}/{}</h3>\n",
+            let mut roles = vec![keyword.to_lowercase()];
+            if let Some(ref branch_id) = thought.branch_id {
+                roles.push(format!("branch-{branch_id}"));
+            }
+            adoc.push_str(&format!(
+                "[#thought-{}.{}]\n",
+                thought.thought_number,
+                roles.join(".")
+            ));
+            adoc.push_str(&format!(
+                "== {keyword} {}/{}\n\n",
                 thought.thought_number, thought.total_thoughts
             ));
 
+            let mut attributes = Vec::new();
+            if let Some(ref author) = thought.author {
+                attributes.push(("Author", author.clone()));
+            }
             if let Some(timestamp) = thought.timestamp {
-                html.push_str(&format!(
-                    "<p class=\"timestamp\">{}</p>\n",
-                    timestamp.format("%Y-%m-%d %H:%M:%S UTC")
-                ));
+                attributes.push(("Created", timestamp.to_rfc3339()));
             }
-
-            html.push_str(&format!("<p class=\"content\">{}</p>\n", thought.thought));
-
-            if thought.is_revision() {
-                if let Some(revises_thought) = thought.revises_thought {
-                    html.push_str(&format!(
-                        "<p class=\"revision-note\">Revises thought {revises_thought}</p>\n"
-                    ));
+            if let Some(revises_thought) = thought.revises_thought {
+                attributes.push(("Revises", format!("<<thought-{revises_thought}>>")));
+            }
+            if let Some(ref branch_id) = thought.branch_id {
+                attributes.push(("Branch", branch_id.clone()));
+            }
+            if let Some(branch_from) = thought.branch_from_thought {
+                attributes.push(("Branches from", format!("<<thought-{branch_from}>>")));
+            }
+            if !thought.depends_on.is_empty() {
+                attributes.push(("Depends on", join_numbers(&thought.depends_on)));
+            }
+            if let Some(ref metadata) = thought.metadata {
+                let mut keys: Vec<&String> = metadata.keys().collect();
+                keys.sort();
+                for key in keys {
+                    attributes.push((key.as_str(), metadata_value(&metadata[key])));
                 }
             }
-
-            if thought.is_branch() {
-                if let Some(branch_id) = &thought.branch_id {
-                    html.push_str(&format!(
-                        "<p class=\"branch-note\">Branch ID: {branch_id}</p>\n"
-                    ));
+            if !attributes.is_empty() {
+                for (name, value) in attributes {
+                    adoc.push_str(&format!("{name}:: {value}\n"));
                 }
+                adoc.push('\n');
             }
 
-            html.push_str("</div>\n");
-        }
+            adoc.push_str(&format!("{}\n\n", thought.thought));
 
-        html.push_str("</div>\n");
-        html.push_str("</div>\n");
-
-        // Footer
-        html.push_str("<footer>\n");
-        html.push_str(&format!(
-            "<p>Exported on {} using UltraFast MCP Sequential Thinking</p>\n",
-            data.export_metadata
-                .exported_at
-                .format("%Y-%m-%d %H:%M:%S UTC")
-        ));
-        html.push_str("</footer>\n");
+            for annotation in data.session.annotations_for(thought) {
+                let label = if annotation.resolved {
+                    "NOTE"
+                } else {
+                    "IMPORTANT"
+                };
+                adoc.push_str(&format!(
+                    "[{label}]\n.Comment by {} ({})\n====\n{}\n====\n\n",
+                    annotation.author,
+                    annotation.created_at.to_rfc3339(),
+                    annotation.body
+                ));
+            }
+        }
 
-        html.push_str("</body>\n</html>");
+        Ok(adoc)
+    }
 
-        Ok(html)
+    /// Export to HTML format
+    fn export_to_html(
+        &self,
+        data: &ExportData,
+        options: &ExportOptions,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        html::render(data, options)
     }
 
     /// Export to CSV format
@@ -674,7 +1034,7 @@ impl ExportEngine {
         let mut csv = String::new();
 
         // Header
-        csv.push_str("Thought Number,Total Thoughts,Content,Is Revision,Revises Thought,Is Branch,Branch ID,Timestamp\n");
+        csv.push_str("Thought Number,Total Thoughts,Content,Is Revision,Revises Thought,Is Branch,Branch ID,Depends On,Timestamp,Author\n");
 
         // Data rows
         for thought in &data.session.thoughts {
@@ -692,19 +1052,102 @@ impl ExportEngine {
                 .unwrap_or_default();
             let is_branch = if thought.is_branch() { "true" } else { "false" };
             let branch_id = thought.branch_id.as_deref().unwrap_or("");
+            let depends_on = join_numbers(&thought.depends_on);
             let timestamp = thought
                 .timestamp
                 .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
                 .unwrap_or_default();
+            let author = thought
+                .author
+                .as_deref()
+                .unwrap_or_default()
+                .replace("\"", "\"\"");
 
             csv.push_str(&format!(
-                "\"{thought_number}\",\"{total_thoughts}\",\"{content}\",\"{is_revision}\",\"{revises_thought}\",\"{is_branch}\",\"{branch_id}\",\"{timestamp}\"\n"
+                "\"{thought_number}\",\"{total_thoughts}\",\"{content}\",\"{is_revision}\",\"{revises_thought}\",\"{is_branch}\",\"{branch_id}\",\"{depends_on}\",\"{timestamp}\",\"{author}\"\n"
             ));
         }
 
         Ok(csv)
     }
 
+    /// Export to JSON Lines format (one thought per line)
+    fn export_to_jsonl(&self, data: &ExportData) -> Result<String, Box<dyn std::error::Error>> {
+        let mut jsonl = String::new();
+        for thought in &data.session.thoughts {
+            jsonl.push_str(&serde_json::to_string(thought)?);
+            jsonl.push('\n');
+        }
+        Ok(jsonl)
+    }
+
+    /// Export per-session analytics metrics to a file
+    ///
+    /// Writes one row per analyzed session in CSV, JSONL or JSON format, for
+    /// loading into spreadsheets or dataframes.
+    pub async fn export_analytics(
+        &mut self,
+        analytics: &AnalyticsEngine,
+        format: ExportFormat,
+    ) -> Result<ExportLocation, Box<dyn std::error::Error>> {
+        let rows = analytics.metrics_rows();
+        let content = match format {
+            ExportFormat::Csv => Self::metrics_to_csv(&rows),
+            ExportFormat::Jsonl => {
+                let mut jsonl = String::new();
+                for row in &rows {
+                    jsonl.push_str(&serde_json::to_string(row)?);
+                    jsonl.push('\n');
+                }
+                jsonl
+            }
+            ExportFormat::Json => serde_json::to_string_pretty(&rows)?,
+            other => {
+                return Err(format!("Unsupported analytics export format: {other}").into());
+            }
+        };
+
+        let filename = format!(
+            "analytics_{}.{}",
+            Utc::now().format("%Y%m%d_%H%M%S"),
+            format.extension()
+        );
+        let location = self
+            .write_export(&filename, content.as_bytes(), &format)
+            .await?;
+
+        self.export_history.push(ExportRecord {
+            session_id: "analytics".to_string(),
+            format,
+            exported_at: Utc::now(),
+            file_path: location.path().map(PathBuf::from),
+            location: Some(location.clone()),
+            file_size: Some(content.len() as u64),
+            success: true,
+            error_message: None,
+            content_hash: None,
+            skipped_duplicate: false,
+        });
+
+        Ok(location)
+    }
+
+    /// Render metrics rows as CSV with a header row
+    fn metrics_to_csv(rows: &[SessionMetricsRow]) -> String {
+        let mut csv = SessionMetricsRow::COLUMNS.join(",");
+        csv.push('\n');
+        for row in rows {
+            let fields: Vec<String> = row
+                .values()
+                .iter()
+                .map(|value| format!("\"{}\"", value.replace('"', "\"\"")))
+                .collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
     /// Export to YAML format
     fn export_to_yaml(
         &self,
@@ -761,6 +1204,270 @@ impl Default for ExportEngine {
     }
 }
 
+/// Markdown up to and including the thoughts heading
+fn markdown_header(data: &ExportData) -> String {
+    let mut markdown = String::new();
+
+    // Header
+    markdown.push_str("# Sequential Thinking Session\n\n");
+
+    // Session information
+    markdown.push_str(&format!("**Session ID:** {}\n\n", data.session.session_id));
+
+    if let Some(ref metadata) = data.session.metadata {
+        markdown.push_str(&format!("**Title:** {}\n", metadata.title));
+        if let Some(ref description) = metadata.description {
+            markdown.push_str(&format!("**Description:** {description}\n"));
+        }
+        if let Some(ref author) = metadata.author {
+            markdown.push_str(&format!("**Author:** {author}\n"));
+        }
+        markdown.push_str(&format!("**Status:** {:?}\n", metadata.status));
+        markdown.push_str(&format!("**Priority:** {:?}\n", metadata.priority));
+        markdown.push_str(&format!(
+            "**Created:** {}\n",
+            metadata.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+        ));
+        markdown.push_str(&format!(
+            "**Modified:** {}\n",
+            metadata.last_modified.format("%Y-%m-%d %H:%M:%S UTC")
+        ));
+        markdown.push('\n');
+    }
+
+    // Statistics
+    if let Some(ref stats) = data.session.statistics {
+        markdown.push_str("## Statistics\n\n");
+        markdown.push_str(&format!("- **Total Thoughts:** {}\n", stats.total_thoughts));
+        markdown.push_str(&format!(
+            "- **Total Revisions:** {}\n",
+            stats.total_revisions
+        ));
+        markdown.push_str(&format!("- **Total Branches:** {}\n", stats.total_branches));
+        markdown.push_str(&format!(
+            "- **Average Processing Time:** {:.2}ms\n",
+            stats.avg_processing_time_ms
+        ));
+        markdown.push_str(&format!(
+            "- **Total Processing Time:** {}ms\n",
+            stats.total_processing_time_ms
+        ));
+        markdown.push('\n');
+    }
+
+    // Progress
+    if let Some(ref progress) = data.session.progress {
+        markdown.push_str("## Progress\n\n");
+        markdown.push_str(&format!(
+            "- **Current Thought:** {}/{}\n",
+            progress.current_thought, progress.total_thoughts
+        ));
+        markdown.push_str(&format!(
+            "- **Completed Thoughts:** {}\n",
+            progress.completed_thoughts
+        ));
+        markdown.push_str(&format!(
+            "- **Progress:** {:.1}%\n",
+            progress.progress_percentage * 100.0
+        ));
+        markdown.push_str(&format!(
+            "- **Status:** {}\n",
+            if progress.is_complete() {
+                "Complete"
+            } else {
+                "In Progress"
+            }
+        ));
+        markdown.push('\n');
+    }
+
+    // Thoughts
+    markdown.push_str("## Thoughts\n\n");
+    markdown
+}
+
+/// Markdown section for one thought
+fn markdown_thought(data: &ExportData, thought: &ThoughtData) -> String {
+    let mut markdown = String::new();
+    let prefix = if thought.is_revision() {
+        "🔄 Revision"
+    } else if thought.is_branch() {
+        "🌿 Branch"
+    } else {
+        "💭 Thought"
+    };
+
+    markdown.push_str(&format!(
+        "### {} {}/{}\n\n",
+        prefix, thought.thought_number, thought.total_thoughts
+    ));
+
+    if let Some(timestamp) = thought.timestamp {
+        markdown.push_str(&format!(
+            "*{}*\n\n",
+            timestamp.format("%Y-%m-%d %H:%M:%S UTC")
+        ));
+    }
+
+    if let Some(ref author) = thought.author {
+        markdown.push_str(&format!("*By {author}*\n\n"));
+    }
+
+    markdown.push_str(&format!("{}\n\n", code::normalize_fences(&thought.thought)));
+
+    for attachment in &thought.attachments {
+        markdown.push_str(&markdown_attachment(attachment));
+    }
+
+    if thought.is_revision() {
+        if let Some(revises_thought) = thought.revises_thought {
+            markdown.push_str(&format!("*Revises thought {revises_thought}*\n\n"));
+        }
+    }
+
+    if thought.is_branch() {
+        if let Some(branch_id) = &thought.branch_id {
+            markdown.push_str(&format!("*Branch ID: {branch_id}*\n\n"));
+        }
+    }
+
+    if !thought.depends_on.is_empty() {
+        markdown.push_str(&format!(
+            "*Builds on thoughts {}*\n\n",
+            join_numbers(&thought.depends_on)
+        ));
+    }
+
+    for annotation in data.session.annotations_for(thought) {
+        markdown.push_str(&format!(
+            "> 💬 **{}** ({}){}: {}\n\n",
+            annotation.author,
+            annotation.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            if annotation.resolved {
+                " ✅ resolved"
+            } else {
+                ""
+            },
+            annotation.body
+        ));
+    }
+
+    markdown
+}
+
+/// Markdown for one attachment of a thought
+fn markdown_attachment(attachment: &Attachment) -> String {
+    match attachment {
+        Attachment::Code {
+            content,
+            language,
+            filename,
+        } => {
+            let caption = filename
+                .as_ref()
+                .map(|f| format!("*{f}*\n\n"))
+                .unwrap_or_default();
+            format!("{caption}{}\n", code::fenced(content, language.as_deref()))
+        }
+        Attachment::File { path, description } => match description {
+            Some(description) => format!("📎 `{path}` — {description}\n\n"),
+            None => format!("📎 `{path}`\n\n"),
+        },
+        Attachment::Image {
+            mime_type,
+            data,
+            alt,
+        } => format!(
+            "![{}](data:{mime_type};base64,{data})\n\n",
+            alt.as_deref().unwrap_or("image")
+        ),
+        Attachment::Url { url, title } => {
+            format!("🔗 [{}]({url})\n\n", title.as_deref().unwrap_or(url))
+        }
+    }
+}
+
+/// Markdown after the thoughts: branches, analytics and footer
+fn markdown_footer(data: &ExportData) -> Result<String, serde_json::Error> {
+    let mut markdown = String::new();
+
+    // Branches
+    if !data.session.branches.is_empty() {
+        markdown.push_str("## Branches\n\n");
+        for (branch_id, branch_thoughts) in &data.session.branches {
+            markdown.push_str(&format!("### Branch: {branch_id}\n\n"));
+            for thought in branch_thoughts {
+                markdown.push_str(&format!("- {}\n", thought.thought));
+            }
+            markdown.push('\n');
+        }
+    }
+
+    // Branch report
+    if !data.session.branch_report.is_empty() {
+        markdown.push_str("## Branch Report\n\n");
+        markdown
+            .push_str("| Branch | From | Thoughts | Avg Length | Quality | Duration | Outcome |\n");
+        markdown.push_str("|---|---|---|---|---|---|---|\n");
+        for branch in &data.session.branch_report {
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {:.1} | {:.2} | {}s | {:?} |\n",
+                branch.branch_id,
+                branch.parent_thought,
+                branch.thought_count,
+                branch.avg_thought_length,
+                branch.quality_score,
+                branch.duration,
+                branch.status
+            ));
+        }
+        markdown.push('\n');
+    }
+
+    markdown.push_str(&actions::markdown_checklist(&data.session.action_items));
+
+    // Analytics
+    if let Some(ref analytics) = data.session.analytics {
+        markdown.push_str("## Analytics\n\n");
+        markdown.push_str("```json\n");
+        markdown.push_str(&serde_json::to_string_pretty(analytics)?);
+        markdown.push_str("\n```\n\n");
+    }
+
+    // Footer
+    markdown.push_str("---\n\n");
+    markdown.push_str(&format!(
+        "*Exported on {} using UltraFast MCP Sequential Thinking*\n",
+        data.export_metadata
+            .exported_at
+            .format("%Y-%m-%d %H:%M:%S UTC")
+    ));
+
+    Ok(markdown)
+}
+
+/// Join thought numbers into a comma-separated list
+/// Render a metadata value, leaving strings unquoted
+fn metadata_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Format a timestamp as an inactive Org-mode timestamp
+fn org_timestamp(timestamp: &DateTime<Utc>) -> String {
+    timestamp.format("[%Y-%m-%d %a %H:%M]").to_string()
+}
+
+fn join_numbers(numbers: &[u32]) -> String {
+    numbers
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 // Implement Display for ExportFormat
 impl std::fmt::Display for ExportFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -772,6 +1479,11 @@ impl std::fmt::Display for ExportFormat {
             ExportFormat::Csv => write!(f, "CSV"),
             ExportFormat::Yaml => write!(f, "YAML"),
             ExportFormat::Toml => write!(f, "TOML"),
+            ExportFormat::Jsonl => write!(f, "JSONL"),
+            ExportFormat::OrgMode => write!(f, "Org"),
+            ExportFormat::Asciidoc => write!(f, "AsciiDoc"),
+            ExportFormat::Bundle => write!(f, "Bundle"),
+            ExportFormat::Epub => write!(f, "EPUB"),
         }
     }
 }
@@ -789,6 +1501,11 @@ impl std::str::FromStr for ExportFormat {
             "csv" => Ok(ExportFormat::Csv),
             "yaml" | "yml" => Ok(ExportFormat::Yaml),
             "toml" => Ok(ExportFormat::Toml),
+            "jsonl" | "ndjson" => Ok(ExportFormat::Jsonl),
+            "org" | "orgmode" => Ok(ExportFormat::OrgMode),
+            "adoc" | "asciidoc" => Ok(ExportFormat::Asciidoc),
+            "bundle" | "zip" => Ok(ExportFormat::Bundle),
+            "epub" => Ok(ExportFormat::Epub),
             _ => Err(format!("Unknown export format: {s}")),
         }
     }
@@ -797,7 +1514,9 @@ impl std::str::FromStr for ExportFormat {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::thinking::ThoughtData;
+    use crate::thinking::{SharedThought, ThinkingProgress, ThinkingStats, ThoughtData};
+    use proptest::prelude::*;
+    use std::sync::Arc;
 
     #[test]
     fn test_export_engine_creation() {
@@ -840,10 +1559,15 @@ mod tests {
     #[test]
     fn test_markdown_export() {
         let engine = ExportEngine::new();
-        let thoughts = vec![
+        let thoughts: Vec<SharedThought> = vec![
             ThoughtData::new("First thought".to_string(), 1, 3),
-            ThoughtData::new("Second thought".to_string(), 2, 3),
-        ];
+            ThoughtData::new("Second thought".to_string(), 2, 3)
+                .with_attachment(Attachment::code("let fence = \"```\";", Some("rust")))
+                .with_attachment(Attachment::url("https://example.com", Some("Spec"))),
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
 
         let export_data = ExportData {
             session: SessionExportData {
@@ -854,6 +1578,9 @@ mod tests {
                 progress: None,
                 branches: HashMap::new(),
                 analytics: None,
+                annotations: Vec::new(),
+                action_items: Vec::new(),
+                branch_report: Vec::new(),
             },
             export_metadata: ExportMetadata {
                 exported_at: Utc::now(),
@@ -872,5 +1599,195 @@ mod tests {
         assert!(markdown.contains("test-session"));
         assert!(markdown.contains("First thought"));
         assert!(markdown.contains("Second thought"));
+        assert!(markdown.contains("````rust\nlet fence = \"```\";\n````"));
+        assert!(markdown.contains("🔗 [Spec](https://example.com)"));
+
+        // Branches get a report of their metrics and outcome
+        let mut thoughts = export_data.session.thoughts;
+        thoughts.push(Arc::new(ThoughtData::branch(
+            "Alternative".to_string(),
+            3,
+            1,
+            "alt".to_string(),
+        )));
+        let data = engine
+            .prepare_export_data("s", None, &thoughts, None, None, None, None, None, &options)
+            .unwrap();
+        assert_eq!(data.session.branch_report.len(), 1);
+        let markdown = engine.export_to_markdown(&data, &options).unwrap();
+        assert!(markdown.contains("## Branch Report"));
+        assert!(markdown.contains("| alt | 1 | 1 |"));
+        assert!(markdown.contains("| Open |"));
+    }
+
+    #[tokio::test]
+    async fn test_analytics_tabular_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut engine = ExportEngine::with_config(ExportConfig {
+            export_directory: dir.path().to_string_lossy().to_string(),
+            ..ExportConfig::default()
+        });
+
+        let mut analytics = AnalyticsEngine::new();
+        let thoughts: Vec<SharedThought> = vec![
+            ThoughtData::new("First, \"quoted\" thought".to_string(), 1, 2),
+            ThoughtData::new("Second thought".to_string(), 2, 2),
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+        let stats = ThinkingStats {
+            total_thoughts: 2,
+            ..ThinkingStats::default()
+        };
+        let progress = ThinkingProgress::new(2, 2);
+        analytics.analyze_session("s1", "Title, with comma", &thoughts, &stats, &progress);
+
+        let csv_location = engine
+            .export_analytics(&analytics, ExportFormat::Csv)
+            .await
+            .unwrap();
+        let csv = std::fs::read_to_string(csv_location.path().unwrap()).unwrap();
+        let mut lines = csv.lines();
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("session_id,session_title,"));
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("\"s1\",\"Title, with comma\","));
+        assert!(lines.next().is_none());
+
+        let jsonl_location = engine
+            .export_analytics(&analytics, ExportFormat::Jsonl)
+            .await
+            .unwrap();
+        let jsonl = std::fs::read_to_string(jsonl_location.path().unwrap()).unwrap();
+        let row: SessionMetricsRow = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+        assert_eq!(row.session_id, "s1");
+        assert_eq!(row.total_thoughts, 2);
+
+        assert!(engine
+            .export_analytics(&analytics, ExportFormat::Pdf)
+            .await
+            .is_err());
+        assert_eq!(engine.get_export_history().len(), 2);
+    }
+
+    #[test]
+    fn test_org_and_asciidoc_export() {
+        let engine = ExportEngine::new();
+        let mut first = ThoughtData::new("* Frame the problem".to_string(), 1, 3);
+        first.timestamp = Some(Utc::now());
+        let branch = ThoughtData::branch("Alternative".to_string(), 2, 1, "alt".to_string())
+            .with_metadata("confidence".to_string(), serde_json::json!(0.8));
+
+        let export_data = ExportData {
+            session: SessionExportData {
+                session_id: "doc-session".to_string(),
+                metadata: None,
+                thoughts: vec![Arc::new(first), Arc::new(branch)],
+                statistics: None,
+                progress: None,
+                branches: HashMap::new(),
+                analytics: None,
+                annotations: Vec::new(),
+                action_items: Vec::new(),
+                branch_report: Vec::new(),
+            },
+            export_metadata: ExportMetadata {
+                exported_at: Utc::now(),
+                format: "org".to_string(),
+                version: "1.0.0".to_string(),
+                tool: "test".to_string(),
+                options: ExportOptions::default(),
+            },
+            custom_data: HashMap::new(),
+        };
+
+        let org = engine.export_to_org(&export_data).unwrap();
+        assert!(org.contains("#+PROPERTY: SESSION_ID doc-session"));
+        assert!(org.contains("* Thought 1/3\n:PROPERTIES:"));
+        assert!(org.contains(":CREATED: ["));
+        assert!(org.contains("* Branch 2/2"));
+        assert!(org.contains(":BRANCH_ID: alt"));
+        assert!(org.contains(":META_CONFIDENCE: 0.8"));
+        assert!(org.contains(",* Frame the problem"));
+
+        let adoc = engine.export_to_asciidoc(&export_data).unwrap();
+        assert!(adoc.contains(":session-id: doc-session"));
+        assert!(adoc.contains("[#thought-2.branch.branch-alt]\n== Branch 2/2"));
+        assert!(adoc.contains("Branches from:: <<thought-1>>"));
+        assert!(adoc.contains("confidence:: 0.8"));
+
+        assert_eq!(
+            "org".parse::<ExportFormat>().unwrap(),
+            ExportFormat::OrgMode
+        );
+        assert_eq!(ExportFormat::Asciidoc.extension(), "adoc");
+    }
+
+    /// Thoughts with text, authors, branch IDs and metadata of any content
+    fn thought() -> impl Strategy<Value = ThoughtData> {
+        (
+            ".*",
+            1u32..50,
+            1u32..50,
+            proptest::option::of(".*"),
+            proptest::option::of((1u32..50, ".*")),
+            proptest::option::of(prop::collection::btree_map(".*", ".*", 0..3)),
+        )
+            .prop_map(|(text, number, total, author, branch, metadata)| {
+                let mut thought = ThoughtData::new(text, number, total);
+                thought.author = author;
+                if let Some((from, id)) = branch {
+                    thought.branch_from_thought = Some(from);
+                    thought.branch_id = Some(id);
+                }
+                thought.metadata = metadata.map(|fields| {
+                    fields
+                        .into_iter()
+                        .map(|(key, value)| (key, serde_json::Value::from(value)))
+                        .collect()
+                });
+                thought
+            })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn test_exports_never_fail(thoughts in prop::collection::vec(thought(), 0..8)) {
+            let engine = ExportEngine::new();
+            let thoughts: Vec<SharedThought> = thoughts.into_iter().map(Arc::new).collect();
+            for format in [
+                ExportFormat::Json,
+                ExportFormat::Markdown,
+                ExportFormat::Pdf,
+                ExportFormat::Html,
+                ExportFormat::Csv,
+                ExportFormat::Yaml,
+                ExportFormat::Toml,
+                ExportFormat::Jsonl,
+                ExportFormat::OrgMode,
+                ExportFormat::Asciidoc,
+            ] {
+                let options = ExportOptions {
+                    format: format.clone(),
+                    ..Default::default()
+                };
+                let data = engine
+                    .prepare_export_data("fuzz", None, &thoughts, None, None, None, None, None, &options)
+                    .unwrap();
+                let rendered = engine.render(&data, &options);
+                prop_assert!(rendered.is_ok(), "{:?} export failed: {:?}", format, rendered.err().map(|e| e.to_string()));
+                if format == ExportFormat::Json {
+                    let parsed: ExportData = serde_json::from_str(&rendered.unwrap()).unwrap();
+                    prop_assert_eq!(parsed.session.thoughts.len(), thoughts.len());
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,384 @@
+//! # Session Log
+//!
+//! Raw logs of sessions, appended to as each thought is processed.
+//!
+//! A [`SessionLog`] keeps one JSON Lines file per session, in the same
+//! format as a [`Jsonl`](super::ExportFormat::Jsonl) export: one thought per
+//! line. Each line is written whole and synced to disk before the next
+//! thought is accepted, so after a crash the log holds every thought
+//! processed until then. A line cut short by the crash is ignored when the
+//! log is read back, and dropped before the next thought is appended.
+//!
+//! With a snapshot interval, the log is compacted every N thoughts so it
+//! stays short on long-running servers. Compaction seals the log as a
+//! numbered segment, writes a [`SessionSnapshot`] holding every thought up
+//! to that segment, and only then deletes the segments it covers. Reading a
+//! session loads its snapshot and replays the segments and log written
+//! after it, so a crash at any step loses or repeats no thoughts.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::thinking::ThoughtData;
+
+/// Thoughts of a session up to a compacted log segment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// Session ID
+    pub session_id: String,
+    /// When the snapshot was taken
+    pub taken_at: DateTime<Utc>,
+    /// Last log segment included in the snapshot
+    pub segment: u64,
+    /// Every thought up to and including that segment, oldest first
+    pub thoughts: Vec<ThoughtData>,
+}
+
+/// Append-only JSON Lines log of each session's thoughts
+#[derive(Debug, Clone)]
+pub struct SessionLog {
+    dir: PathBuf,
+    /// Thoughts appended between snapshots, if the log is compacted
+    snapshot_interval: Option<usize>,
+    /// Lines in each session's current log, counted on first append
+    pending: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl SessionLog {
+    /// Create a log writing to the given directory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            snapshot_interval: None,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Compact a session's log every `interval` appended thoughts
+    pub fn with_snapshot_interval(mut self, interval: usize) -> Self {
+        self.snapshot_interval = Some(interval.max(1));
+        self
+    }
+
+    /// Path of a session's log file
+    pub fn path(&self, session_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", file_stem(session_id)))
+    }
+
+    /// Path of a session's snapshot
+    pub fn snapshot_path(&self, session_id: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.snapshot.json", file_stem(session_id)))
+    }
+
+    /// Path of a sealed segment of a session's log
+    fn segment_path(&self, session_id: &str, segment: u64) -> PathBuf {
+        self.dir
+            .join(format!("{}.jsonl.{segment}", file_stem(session_id)))
+    }
+
+    /// Numbers of a session's sealed log segments, in order
+    fn segments(&self, session_id: &str) -> Result<Vec<u64>, String> {
+        let prefix = format!("{}.jsonl.", file_stem(session_id));
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to list {}: {e}", self.dir.display())),
+        };
+        let mut segments: Vec<u64> = entries
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name();
+                name.to_str()?.strip_prefix(&prefix)?.parse().ok()
+            })
+            .collect();
+        segments.sort_unstable();
+        Ok(segments)
+    }
+
+    /// Load a session's snapshot, if it has one
+    pub fn load_snapshot(&self, session_id: &str) -> Result<Option<SessionSnapshot>, String> {
+        let path = self.snapshot_path(session_id);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map(Some)
+                .map_err(|e| format!("Corrupt session snapshot {}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read {}: {e}", path.display())),
+        }
+    }
+
+    /// Fold a session's log into its snapshot and delete the compacted segments
+    pub fn compact(&self, session_id: &str) -> Result<(), String> {
+        let snapshot = self.load_snapshot(session_id)?;
+        let mut segment = snapshot.as_ref().map_or(0, |s| s.segment);
+        let mut thoughts = snapshot.map(|s| s.thoughts).unwrap_or_default();
+
+        // Segments sealed by a compaction that crashed before its snapshot
+        for sealed in self.segments(session_id)? {
+            if sealed > segment {
+                thoughts.extend(read_lines(&self.segment_path(session_id, sealed))?);
+                segment = sealed;
+            }
+        }
+        let path = self.path(session_id);
+        if path.exists() {
+            segment += 1;
+            let sealed = self.segment_path(session_id, segment);
+            std::fs::rename(&path, &sealed)
+                .map_err(|e| format!("Failed to seal {}: {e}", path.display()))?;
+            thoughts.extend(read_lines(&sealed)?);
+        }
+        self.pending_lines().insert(session_id.to_string(), 0);
+
+        let snapshot = SessionSnapshot {
+            session_id: session_id.to_string(),
+            taken_at: Utc::now(),
+            segment,
+            thoughts,
+        };
+        write_atomically(
+            &self.snapshot_path(session_id),
+            &serde_json::to_vec(&snapshot).map_err(|e| e.to_string())?,
+        )?;
+
+        for sealed in self.segments(session_id)? {
+            if sealed <= segment {
+                let path = self.segment_path(session_id, sealed);
+                std::fs::remove_file(&path)
+                    .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Lines in each session's current log
+    fn pending_lines(&self) -> std::sync::MutexGuard<'_, HashMap<String, usize>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Append a thought to a session's log and sync it to disk
+    pub fn append(&self, session_id: &str, thought: &ThoughtData) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create session log directory: {e}"))?;
+        let path = self.path(session_id);
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+        drop_torn_line(&mut file)
+            .map_err(|e| format!("Failed to repair {}: {e}", path.display()))?;
+
+        let mut line = serde_json::to_string(thought).map_err(|e| e.to_string())?;
+        line.push('\n');
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.sync_data())
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+
+        let Some(interval) = self.snapshot_interval else {
+            return Ok(());
+        };
+        let mut pending = self.pending_lines();
+        let lines = match pending.get_mut(session_id) {
+            Some(lines) => {
+                *lines += 1;
+                *lines
+            }
+            None => {
+                let lines = read_lines(&path)?.len();
+                pending.insert(session_id.to_string(), lines);
+                lines
+            }
+        };
+        drop(pending);
+        if lines >= interval {
+            self.compact(session_id)
+                .map_err(|e| format!("Failed to compact session log: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Read back a session's logged thoughts, oldest first
+    ///
+    /// A missing log reads as empty, and an unterminated last line, left by
+    /// a write interrupted by a crash, is skipped. Thoughts compacted into
+    /// the snapshot come first.
+    pub fn read(&self, session_id: &str) -> Result<Vec<ThoughtData>, String> {
+        let snapshot = self.load_snapshot(session_id)?;
+        let segment = snapshot.as_ref().map_or(0, |s| s.segment);
+        let mut thoughts = snapshot.map(|s| s.thoughts).unwrap_or_default();
+        for sealed in self.segments(session_id)? {
+            if sealed > segment {
+                thoughts.extend(read_lines(&self.segment_path(session_id, sealed))?);
+            }
+        }
+        thoughts.extend(read_lines(&self.path(session_id))?);
+        Ok(thoughts)
+    }
+}
+
+/// File name of a session's log, without characters unsafe in paths
+fn file_stem(session_id: &str) -> String {
+    session_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Thoughts in a JSON Lines file, skipping an unterminated last line
+fn read_lines(path: &Path) -> Result<Vec<ThoughtData>, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {e}", path.display())),
+    };
+    let complete = match content.rfind('\n') {
+        Some(end) => &content[..end],
+        None => "",
+    };
+    complete
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                format!(
+                    "Corrupt session log {} line {}: {e}",
+                    path.display(),
+                    index + 1
+                )
+            })
+        })
+        .collect()
+}
+
+/// Write a file through a synced temporary file, so it is replaced whole or not at all
+fn write_atomically(path: &Path, content: &[u8]) -> Result<(), String> {
+    let temporary = path.with_extension("tmp");
+    let mut file = std::fs::File::create(&temporary)
+        .map_err(|e| format!("Failed to create {}: {e}", temporary.display()))?;
+    file.write_all(content)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Failed to write {}: {e}", temporary.display()))?;
+    std::fs::rename(&temporary, path)
+        .map_err(|e| format!("Failed to replace {}: {e}", path.display()))
+}
+
+/// Truncate a log after its last complete line
+fn drop_torn_line(file: &mut std::fs::File) -> std::io::Result<()> {
+    if file.metadata()?.len() == 0 {
+        return Ok(());
+    }
+    let mut last = [0u8];
+    file.seek(SeekFrom::End(-1))?;
+    file.read_exact(&mut last)?;
+    if last[0] == b'\n' {
+        return Ok(());
+    }
+    let mut content = Vec::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_end(&mut content)?;
+    let complete = content
+        .iter()
+        .rposition(|&byte| byte == b'\n')
+        .map_or(0, |end| end + 1);
+    file.set_len(complete as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_log_survives_torn_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = SessionLog::new(dir.path().join("logs"));
+        assert!(log.read("s/1").unwrap().is_empty());
+
+        log.append("s/1", &ThoughtData::new("Measure".to_string(), 1, 2))
+            .unwrap();
+        log.append("s/1", &ThoughtData::new("Optimize".to_string(), 2, 2))
+            .unwrap();
+        assert_eq!(log.path("s/1"), dir.path().join("logs").join("s_1.jsonl"));
+
+        // A crash in the middle of a write leaves a partial last line
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(log.path("s/1"))
+            .unwrap();
+        file.write_all(br#"{"thought":"Ver"#).unwrap();
+
+        let thoughts = log.read("s/1").unwrap();
+        let texts: Vec<&str> = thoughts.iter().map(|t| t.thought.as_str()).collect();
+        assert_eq!(texts, ["Measure", "Optimize"]);
+
+        // The next append replaces the partial line
+        log.append("s/1", &ThoughtData::new("Verify".to_string(), 3, 3))
+            .unwrap();
+        assert_eq!(log.read("s/1").unwrap()[2].thought, "Verify");
+        let content = std::fs::read_to_string(log.path("s/1")).unwrap();
+        assert_eq!(content.lines().count(), 3);
+    }
+
+    #[test]
+    fn test_snapshots_compact_the_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = SessionLog::new(dir.path()).with_snapshot_interval(2);
+        let step = |number: u32| ThoughtData::new(format!("Step {number}"), number, 9);
+        for number in 1..=5 {
+            log.append("s1", &step(number)).unwrap();
+        }
+        let snapshot = log.load_snapshot("s1").unwrap().unwrap();
+        assert_eq!(snapshot.segment, 2);
+        assert_eq!(snapshot.thoughts.len(), 4);
+        assert_eq!(
+            std::fs::read_to_string(log.path("s1"))
+                .unwrap()
+                .lines()
+                .count(),
+            1
+        );
+        assert!(log.segments("s1").unwrap().is_empty());
+        assert_eq!(log.read("s1").unwrap().len(), 5);
+
+        // A crash after sealing the log but before the snapshot loses nothing,
+        // and neither does one after the snapshot but before cleaning up
+        std::fs::rename(log.path("s1"), log.segment_path("s1", 3)).unwrap();
+        std::fs::write(log.segment_path("s1", 2), "").unwrap();
+        let restarted = SessionLog::new(dir.path()).with_snapshot_interval(2);
+        restarted.append("s1", &step(6)).unwrap();
+        let texts: Vec<String> = restarted
+            .read("s1")
+            .unwrap()
+            .into_iter()
+            .map(|t| t.thought)
+            .collect();
+        assert_eq!(
+            texts,
+            (1..=6).map(|n| format!("Step {n}")).collect::<Vec<_>>()
+        );
+        restarted.append("s1", &step(7)).unwrap();
+        assert_eq!(
+            restarted
+                .load_snapshot("s1")
+                .unwrap()
+                .unwrap()
+                .thoughts
+                .len(),
+            7
+        );
+        assert!(restarted.segments("s1").unwrap().is_empty());
+        assert!(!restarted.path("s1").exists());
+    }
+}
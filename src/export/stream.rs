@@ -0,0 +1,317 @@
+//! # Streaming Export
+//!
+//! Writes exports incrementally instead of rendering them into one string.
+//!
+//! JSON, Markdown and JSON Lines exports are written thought by thought, so
+//! memory use stays bounded by the largest thought rather than the whole
+//! session. The output is byte-for-byte the same as the in-memory renderers.
+//! Other formats are rendered in memory and then written.
+
+use serde::Serialize;
+use std::path::Path;
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufWriter};
+
+use super::{
+    markdown_footer, markdown_header, markdown_thought, ExportData, ExportEngine, ExportFormat,
+    ExportLocation, ExportRecord,
+};
+use chrono::Utc;
+
+/// Writer counting the bytes it has written
+struct CountingWriter<'a, W> {
+    writer: &'a mut W,
+    written: u64,
+}
+
+impl<W: AsyncWrite + Unpin> CountingWriter<'_, W> {
+    async fn write(&mut self, text: &str) -> std::io::Result<()> {
+        self.writer.write_all(text.as_bytes()).await?;
+        self.written += text.len() as u64;
+        Ok(())
+    }
+}
+
+/// Incremental JSON writer matching `serde_json`'s compact and pretty output
+struct JsonStream<'a, W> {
+    out: CountingWriter<'a, W>,
+    pretty: bool,
+}
+
+impl<W: AsyncWrite + Unpin> JsonStream<'_, W> {
+    /// Separator before an object field or array element at a depth
+    async fn separator(&mut self, first: bool, depth: usize) -> std::io::Result<()> {
+        if !first {
+            self.out.write(",").await?;
+        }
+        if self.pretty {
+            self.out.write(&format!("\n{}", "  ".repeat(depth))).await?;
+        }
+        Ok(())
+    }
+
+    /// Closing bracket of a non-empty object or array at a depth
+    async fn close(&mut self, bracket: &str, depth: usize) -> std::io::Result<()> {
+        if self.pretty {
+            self.out.write(&format!("\n{}", "  ".repeat(depth))).await?;
+        }
+        self.out.write(bracket).await
+    }
+
+    /// Object key at a depth, followed by the colon
+    async fn key(
+        &mut self,
+        first: bool,
+        depth: usize,
+        key: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.separator(first, depth).await?;
+        let colon = if self.pretty { ": " } else { ":" };
+        self.out
+            .write(&format!("{}{colon}", serde_json::to_string(key)?))
+            .await?;
+        Ok(())
+    }
+
+    /// Value nested at a depth
+    async fn value(
+        &mut self,
+        depth: usize,
+        value: &impl Serialize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let json = if self.pretty {
+            serde_json::to_string_pretty(value)?.replace('\n', &format!("\n{}", "  ".repeat(depth)))
+        } else {
+            serde_json::to_string(value)?
+        };
+        self.out.write(&json).await?;
+        Ok(())
+    }
+
+    /// Object field with a value at a depth
+    async fn field(
+        &mut self,
+        first: bool,
+        depth: usize,
+        key: &str,
+        value: &impl Serialize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.key(first, depth, key).await?;
+        self.value(depth, value).await
+    }
+}
+
+/// Write export data as JSON, one thought at a time
+///
+/// Fields are written in the declaration order of [`ExportData`] and
+/// [`SessionExportData`](super::SessionExportData), as `serde_json` does.
+async fn write_json<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    data: &ExportData,
+    pretty: bool,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let session = &data.session;
+    let mut json = JsonStream {
+        out: CountingWriter { writer, written: 0 },
+        pretty,
+    };
+
+    json.out.write("{").await?;
+    json.key(true, 1, "session").await?;
+    json.out.write("{").await?;
+    json.field(true, 2, "session_id", &session.session_id)
+        .await?;
+    json.field(false, 2, "metadata", &session.metadata).await?;
+    json.key(false, 2, "thoughts").await?;
+    json.out.write("[").await?;
+    for (i, thought) in session.thoughts.iter().enumerate() {
+        json.separator(i == 0, 3).await?;
+        json.value(3, thought).await?;
+    }
+    if session.thoughts.is_empty() {
+        json.out.write("]").await?;
+    } else {
+        json.close("]", 2).await?;
+    }
+    json.field(false, 2, "statistics", &session.statistics)
+        .await?;
+    json.field(false, 2, "progress", &session.progress).await?;
+    json.field(false, 2, "branches", &session.branches).await?;
+    json.field(false, 2, "analytics", &session.analytics)
+        .await?;
+    json.field(false, 2, "annotations", &session.annotations)
+        .await?;
+    json.field(false, 2, "action_items", &session.action_items)
+        .await?;
+    json.field(false, 2, "branch_report", &session.branch_report)
+        .await?;
+    json.close("}", 1).await?;
+    json.field(false, 1, "export_metadata", &data.export_metadata)
+        .await?;
+    json.field(false, 1, "custom_data", &data.custom_data)
+        .await?;
+    json.close("}", 0).await?;
+
+    Ok(json.out.written)
+}
+
+/// Write export data as Markdown, one thought at a time
+async fn write_markdown<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    data: &ExportData,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let mut out = CountingWriter { writer, written: 0 };
+    out.write(&markdown_header(data)).await?;
+    for thought in &data.session.thoughts {
+        out.write(&markdown_thought(data, thought)).await?;
+    }
+    out.write(&markdown_footer(data)?).await?;
+    Ok(out.written)
+}
+
+/// Write export data as JSON Lines, one thought per line
+async fn write_jsonl<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    data: &ExportData,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let mut out = CountingWriter { writer, written: 0 };
+    for thought in &data.session.thoughts {
+        out.write(&serde_json::to_string(thought)?).await?;
+        out.write("\n").await?;
+    }
+    Ok(out.written)
+}
+
+impl ExportEngine {
+    /// Stream an export to a writer, returning the number of bytes written
+    ///
+    /// The format and options are taken from the export metadata. The writer
+    /// is flushed but not shut down.
+    pub async fn stream_export<W: AsyncWrite + Unpin>(
+        &self,
+        data: &ExportData,
+        writer: &mut W,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let options = &data.export_metadata.options;
+        let written = match options.format {
+            ExportFormat::Json => write_json(writer, data, options.pretty_print).await?,
+            ExportFormat::Markdown => write_markdown(writer, data).await?,
+            ExportFormat::Jsonl => write_jsonl(writer, data).await?,
+            _ => {
+                let content = self.render_bytes(data, options)?;
+                writer.write_all(&content).await?;
+                content.len() as u64
+            }
+        };
+        writer.flush().await?;
+        Ok(written)
+    }
+
+    /// Stream an export to a file, recording it in the export history
+    pub async fn stream_export_to_file(
+        &mut self,
+        data: &ExportData,
+        path: impl AsRef<Path>,
+    ) -> Result<ExportLocation, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let written = self.stream_to_file(data, path).await?;
+        let location = ExportLocation::File {
+            path: path.to_path_buf(),
+        };
+        self.export_history.push(ExportRecord {
+            session_id: data.session.session_id.clone(),
+            format: data.export_metadata.options.format.clone(),
+            exported_at: Utc::now(),
+            file_path: Some(path.to_path_buf()),
+            location: Some(location.clone()),
+            file_size: Some(written),
+            success: true,
+            error_message: None,
+            content_hash: None,
+            skipped_duplicate: false,
+        });
+        Ok(location)
+    }
+
+    /// Stream an export to a file through a buffer
+    pub(super) async fn stream_to_file(
+        &self,
+        data: &ExportData,
+        path: &Path,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = BufWriter::new(tokio::fs::File::create(path).await?);
+        let written = self.stream_export(data, &mut file).await?;
+        file.shutdown().await?;
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::ExportOptions;
+    use crate::thinking::ThoughtData;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_streaming_export() {
+        let thoughts: Vec<_> = (1..=3)
+            .map(|n| {
+                Arc::new(ThoughtData::new(
+                    format!("Thought {n} with \"quotes\"\nand a line break"),
+                    n,
+                    3,
+                ))
+            })
+            .collect();
+        let mut engine = ExportEngine::new();
+        let mut options = ExportOptions::default();
+
+        for (format, pretty) in [
+            (ExportFormat::Json, true),
+            (ExportFormat::Json, false),
+            (ExportFormat::Markdown, true),
+            (ExportFormat::Jsonl, true),
+            (ExportFormat::Csv, true),
+        ] {
+            options.format = format.clone();
+            options.pretty_print = pretty;
+            for thoughts in [&thoughts[..], &[]] {
+                let data = engine
+                    .prepare_export_data(
+                        "stream", None, thoughts, None, None, None, None, None, &options,
+                    )
+                    .unwrap();
+                let expected = match format {
+                    ExportFormat::Json => engine.export_to_json(&data, &options).unwrap(),
+                    ExportFormat::Markdown => engine.export_to_markdown(&data, &options).unwrap(),
+                    ExportFormat::Jsonl => engine.export_to_jsonl(&data).unwrap(),
+                    _ => engine.export_to_csv(&data, &options).unwrap(),
+                };
+
+                let mut streamed = Vec::new();
+                let written = engine.stream_export(&data, &mut streamed).await.unwrap();
+                assert_eq!(String::from_utf8(streamed).unwrap(), expected);
+                assert_eq!(written, expected.len() as u64);
+            }
+        }
+
+        options.format = ExportFormat::Markdown;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("session.md");
+        let data = engine
+            .prepare_export_data(
+                "stream", None, &thoughts, None, None, None, None, None, &options,
+            )
+            .unwrap();
+        let location = engine.stream_export_to_file(&data, &path).await.unwrap();
+        assert_eq!(location.path(), Some(path.as_path()));
+        let record = &engine.get_export_history()[0];
+        assert_eq!(
+            record.file_size,
+            Some(std::fs::metadata(&path).unwrap().len())
+        );
+    }
+}
@@ -0,0 +1,543 @@
+//! # Integrations Module
+//!
+//! Pushing reasoning outcomes into issue trackers.
+//!
+//! Extracted action items, or a summary of a completed session, are turned
+//! into [`IssueDraft`]s and created as GitHub Issues or Jira issues, so the
+//! work a session identifies ends up tracked. A dry run returns the requests
+//! that would be sent without contacting the tracker.
+//!
+//! Sending requests needs the `integrations` feature; dry runs work without it.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::{self, ActionItem};
+use crate::thinking::SharedThought;
+
+/// Error type returned by issue trackers
+pub type IntegrationError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Issue tracker settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct IntegrationsConfig {
+    /// GitHub Issues settings
+    #[serde(default)]
+    pub github: Option<GitHubConfig>,
+    /// Jira settings
+    #[serde(default)]
+    pub jira: Option<JiraConfig>,
+    /// Whether `create_issues` defaults to a dry run
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// GitHub Issues settings
+///
+/// An unset token falls back to the `GITHUB_TOKEN` environment variable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct GitHubConfig {
+    /// Repository as `owner/name`
+    pub repository: String,
+    /// Personal access token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Labels added to every issue
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// API base URL, for GitHub Enterprise
+    #[serde(default = "default_github_api_url")]
+    pub api_url: String,
+}
+
+fn default_github_api_url() -> String {
+    "https://api.github.com".to_string()
+}
+
+/// Jira settings
+///
+/// Unset credentials fall back to the `JIRA_EMAIL` and `JIRA_API_TOKEN`
+/// environment variables.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct JiraConfig {
+    /// Site URL, such as `https://example.atlassian.net`
+    pub base_url: String,
+    /// Key of the project issues are created in
+    pub project_key: String,
+    /// Issue type name
+    #[serde(default = "default_issue_type")]
+    pub issue_type: String,
+    /// Labels added to every issue
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Account email
+    #[serde(default)]
+    pub email: Option<String>,
+    /// API token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_token: Option<String>,
+}
+
+fn default_issue_type() -> String {
+    "Task".to_string()
+}
+
+impl IntegrationsConfig {
+    /// Check the tracker settings, returning errors prefixed with field paths
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if let Some(github) = &self.github {
+            let parts: Vec<&str> = github.repository.split('/').collect();
+            if parts.len() != 2 || parts.iter().any(|part| part.is_empty()) {
+                errors.push("github.repository: must be owner/name".to_string());
+            }
+            if !is_http_url(&github.api_url) {
+                errors.push("github.api_url: must be an http(s) URL".to_string());
+            }
+        }
+        if let Some(jira) = &self.jira {
+            if !is_http_url(&jira.base_url) {
+                errors.push("jira.base_url: must be an http(s) URL".to_string());
+            }
+            if jira.project_key.is_empty() {
+                errors.push("jira.project_key: must not be empty".to_string());
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Build the tracker with the given name, or the only configured one
+    pub fn tracker(&self, name: Option<&str>) -> Result<Box<dyn IssueTracker>, String> {
+        match (name, &self.github, &self.jira) {
+            (Some("github") | None, Some(github), None) | (Some("github"), Some(github), _) => {
+                Ok(Box::new(GitHubTracker::new(github.clone())))
+            }
+            (Some("jira") | None, None, Some(jira)) | (Some("jira"), _, Some(jira)) => {
+                Ok(Box::new(JiraTracker::new(jira.clone())))
+            }
+            (None, Some(_), Some(_)) => {
+                Err("Both github and jira are configured; choose a tracker".to_string())
+            }
+            (Some(name @ ("github" | "jira")), _, _) => {
+                Err(format!("Issue tracker '{name}' is not configured"))
+            }
+            (Some(name), _, _) => Err(format!("Unknown issue tracker: {name}")),
+            (None, None, None) => Err("No issue tracker is configured".to_string()),
+        }
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+/// Read a non-empty environment variable
+#[cfg(feature = "integrations")]
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Issue to be created
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IssueDraft {
+    /// Issue title
+    pub title: String,
+    /// Issue body in Markdown
+    pub body: String,
+}
+
+/// Longest issue title, in characters
+const MAX_TITLE_CHARS: usize = 120;
+
+/// Shorten text to a single-line issue title
+fn title(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.chars().count() <= MAX_TITLE_CHARS {
+        return line.to_string();
+    }
+    let mut title: String = line.chars().take(MAX_TITLE_CHARS - 1).collect();
+    title.push('…');
+    title
+}
+
+impl IssueDraft {
+    /// Draft an issue for an action item, quoting the thoughts mentioning it
+    pub fn from_action(
+        item: &ActionItem,
+        thoughts: &[SharedThought],
+        session_id: Option<&str>,
+    ) -> Self {
+        let mut body = match session_id {
+            Some(session_id) => format!("Action item from thinking session `{session_id}`.\n\n"),
+            None => "Action item from a thinking session.\n\n".to_string(),
+        };
+        for thought in thoughts
+            .iter()
+            .filter(|t| item.thought_numbers.contains(&t.thought_number))
+        {
+            body.push_str(&format!("**Thought {}:**\n\n", thought.thought_number));
+            for line in thought.thought.lines() {
+                body.push_str(&format!("> {line}\n"));
+            }
+            body.push('\n');
+        }
+        Self {
+            title: title(&item.text),
+            body: body.trim_end().to_string(),
+        }
+    }
+
+    /// Draft an issue summarizing a completed session
+    pub fn from_session(
+        thoughts: &[SharedThought],
+        action_items: &[ActionItem],
+        session_id: Option<&str>,
+    ) -> Result<Self, String> {
+        let (Some(first), Some(last)) = (thoughts.first(), thoughts.last()) else {
+            return Err("The session has no thoughts".to_string());
+        };
+        if last.next_thought_needed {
+            return Err("The session is not complete".to_string());
+        }
+
+        let mut body = String::new();
+        if let Some(session_id) = session_id {
+            body.push_str(&format!("Outcome of thinking session `{session_id}`.\n\n"));
+        }
+        body.push_str(&format!("## Conclusion\n\n{}\n\n", last.thought));
+        body.push_str("## Reasoning\n\n");
+        for thought in thoughts {
+            body.push_str(&format!(
+                "{}. {}\n",
+                thought.thought_number,
+                title(&thought.thought)
+            ));
+        }
+        body.push('\n');
+        body.push_str(&actions::markdown_checklist(action_items));
+        Ok(Self {
+            title: title(&first.thought),
+            body: body.trim_end().to_string(),
+        })
+    }
+}
+
+/// Issue created, or that would be created in a dry run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedIssue {
+    /// Tracker the issue belongs to
+    pub tracker: String,
+    /// Issue title
+    pub title: String,
+    /// Issue identifier, such as `#12` or `PROJ-3`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Link to the issue
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Request that would be sent, for dry runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<IssueRequest>,
+}
+
+/// HTTP request creating an issue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IssueRequest {
+    /// Endpoint the request is posted to
+    pub url: String,
+    /// JSON body
+    pub body: serde_json::Value,
+}
+
+/// Issue tracker that drafts can be created in
+#[async_trait]
+pub trait IssueTracker: Send + Sync {
+    /// Tracker name, such as `github`
+    fn name(&self) -> &'static str;
+
+    /// Request that creates an issue for a draft
+    fn request(&self, draft: &IssueDraft) -> IssueRequest;
+
+    /// Create an issue
+    async fn create(&self, draft: &IssueDraft) -> Result<CreatedIssue, IntegrationError>;
+}
+
+/// Create issues for drafts, or describe them without sending in a dry run
+///
+/// Stops at the first failure; issues created before it are not rolled back.
+pub async fn create_issues(
+    tracker: &dyn IssueTracker,
+    drafts: &[IssueDraft],
+    dry_run: bool,
+) -> Result<Vec<CreatedIssue>, IntegrationError> {
+    let mut created = Vec::with_capacity(drafts.len());
+    for draft in drafts {
+        if dry_run {
+            created.push(CreatedIssue {
+                tracker: tracker.name().to_string(),
+                title: draft.title.clone(),
+                key: None,
+                url: None,
+                request: Some(tracker.request(draft)),
+            });
+        } else {
+            created.push(tracker.create(draft).await?);
+        }
+    }
+    Ok(created)
+}
+
+/// Creates GitHub Issues through the REST API
+#[derive(Debug, Clone)]
+pub struct GitHubTracker {
+    config: GitHubConfig,
+}
+
+impl GitHubTracker {
+    /// Create a tracker for a repository
+    pub fn new(config: GitHubConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl IssueTracker for GitHubTracker {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn request(&self, draft: &IssueDraft) -> IssueRequest {
+        IssueRequest {
+            url: format!(
+                "{}/repos/{}/issues",
+                self.config.api_url.trim_end_matches('/'),
+                self.config.repository
+            ),
+            body: serde_json::json!({
+                "title": draft.title,
+                "body": draft.body,
+                "labels": self.config.labels
+            }),
+        }
+    }
+
+    #[cfg(feature = "integrations")]
+    async fn create(&self, draft: &IssueDraft) -> Result<CreatedIssue, IntegrationError> {
+        let token = self
+            .config
+            .token
+            .clone()
+            .or_else(|| env_var("GITHUB_TOKEN"))
+            .ok_or("GitHub token not configured (set GITHUB_TOKEN)")?;
+        let request = self.request(draft);
+        let response: serde_json::Value = send(
+            reqwest::Client::new()
+                .post(&request.url)
+                .bearer_auth(token)
+                .header("accept", "application/vnd.github+json")
+                .header("user-agent", "ultrafast-mcp-sequential-thinking")
+                .json(&request.body),
+        )
+        .await?;
+        Ok(CreatedIssue {
+            tracker: self.name().to_string(),
+            title: draft.title.clone(),
+            key: response["number"].as_u64().map(|n| format!("#{n}")),
+            url: response["html_url"].as_str().map(str::to_string),
+            request: None,
+        })
+    }
+
+    #[cfg(not(feature = "integrations"))]
+    async fn create(&self, _draft: &IssueDraft) -> Result<CreatedIssue, IntegrationError> {
+        Err(FEATURE_REQUIRED.into())
+    }
+}
+
+/// Creates Jira issues through the REST API
+#[derive(Debug, Clone)]
+pub struct JiraTracker {
+    config: JiraConfig,
+}
+
+impl JiraTracker {
+    /// Create a tracker for a project
+    pub fn new(config: JiraConfig) -> Self {
+        Self { config }
+    }
+
+    fn base_url(&self) -> &str {
+        self.config.base_url.trim_end_matches('/')
+    }
+}
+
+#[async_trait]
+impl IssueTracker for JiraTracker {
+    fn name(&self) -> &'static str {
+        "jira"
+    }
+
+    fn request(&self, draft: &IssueDraft) -> IssueRequest {
+        IssueRequest {
+            url: format!("{}/rest/api/2/issue", self.base_url()),
+            body: serde_json::json!({
+                "fields": {
+                    "project": { "key": self.config.project_key },
+                    "summary": draft.title,
+                    "description": draft.body,
+                    "issuetype": { "name": self.config.issue_type },
+                    "labels": self.config.labels
+                }
+            }),
+        }
+    }
+
+    #[cfg(feature = "integrations")]
+    async fn create(&self, draft: &IssueDraft) -> Result<CreatedIssue, IntegrationError> {
+        let email = self
+            .config
+            .email
+            .clone()
+            .or_else(|| env_var("JIRA_EMAIL"))
+            .ok_or("Jira email not configured (set JIRA_EMAIL)")?;
+        let api_token = self
+            .config
+            .api_token
+            .clone()
+            .or_else(|| env_var("JIRA_API_TOKEN"))
+            .ok_or("Jira API token not configured (set JIRA_API_TOKEN)")?;
+        let request = self.request(draft);
+        let response: serde_json::Value = send(
+            reqwest::Client::new()
+                .post(&request.url)
+                .basic_auth(email, Some(api_token))
+                .json(&request.body),
+        )
+        .await?;
+        let key = response["key"].as_str().map(str::to_string);
+        Ok(CreatedIssue {
+            tracker: self.name().to_string(),
+            title: draft.title.clone(),
+            url: key
+                .as_ref()
+                .map(|key| format!("{}/browse/{key}", self.base_url())),
+            key,
+            request: None,
+        })
+    }
+
+    #[cfg(not(feature = "integrations"))]
+    async fn create(&self, _draft: &IssueDraft) -> Result<CreatedIssue, IntegrationError> {
+        Err(FEATURE_REQUIRED.into())
+    }
+}
+
+#[cfg(not(feature = "integrations"))]
+const FEATURE_REQUIRED: &str = "Creating issues requires the `integrations` feature";
+
+/// Send a request and parse its JSON response, failing on error statuses
+#[cfg(feature = "integrations")]
+async fn send(request: reqwest::RequestBuilder) -> Result<serde_json::Value, IntegrationError> {
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Issue creation failed with {status}: {body}").into());
+    }
+    Ok(response.json().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::ActionExtractor;
+    use crate::thinking::ThoughtData;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_create_issues_dry_run() {
+        let mut conclusion = ThoughtData::new(
+            "Ship it behind a flag.\nTODO: add a rollout flag".to_string(),
+            2,
+            2,
+        );
+        conclusion.next_thought_needed = false;
+        let thoughts: Vec<SharedThought> = vec![
+            ThoughtData::new("Should we ship the new parser?".to_string(), 1, 2),
+            conclusion,
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+        let items = ActionExtractor::default().extract(&thoughts);
+
+        let config = IntegrationsConfig {
+            github: Some(GitHubConfig {
+                repository: "acme/parser".to_string(),
+                token: None,
+                labels: vec!["thinking".to_string()],
+                api_url: default_github_api_url(),
+            }),
+            jira: Some(JiraConfig {
+                base_url: "https://acme.atlassian.net/".to_string(),
+                project_key: "PAR".to_string(),
+                issue_type: default_issue_type(),
+                labels: Vec::new(),
+                email: None,
+                api_token: None,
+            }),
+            dry_run: true,
+        };
+        assert!(config.validate().is_ok());
+        assert!(config.tracker(None).is_err());
+
+        let drafts = vec![
+            IssueDraft::from_action(&items[0], &thoughts, Some("s1")),
+            IssueDraft::from_session(&thoughts, &items, Some("s1")).unwrap(),
+        ];
+        assert_eq!(drafts[0].title, "add a rollout flag");
+        assert!(drafts[0].body.contains("> TODO: add a rollout flag"));
+        assert_eq!(drafts[1].title, "Should we ship the new parser?");
+        assert!(drafts[1]
+            .body
+            .contains("## Conclusion\n\nShip it behind a flag."));
+
+        let github = config.tracker(Some("github")).unwrap();
+        let created = create_issues(github.as_ref(), &drafts, true).await.unwrap();
+        let request = created[0].request.as_ref().unwrap();
+        assert_eq!(
+            request.url,
+            "https://api.github.com/repos/acme/parser/issues"
+        );
+        assert_eq!(request.body["labels"][0], "thinking");
+        assert_eq!(created[0].key, None);
+
+        let jira = config.tracker(Some("jira")).unwrap();
+        let created = create_issues(jira.as_ref(), &drafts[..1], true)
+            .await
+            .unwrap();
+        let request = created[0].request.as_ref().unwrap();
+        assert_eq!(request.url, "https://acme.atlassian.net/rest/api/2/issue");
+        assert_eq!(request.body["fields"]["project"]["key"], "PAR");
+
+        assert!(IssueDraft::from_session(&thoughts[..1], &items, None).is_err());
+        assert_eq!(
+            IntegrationsConfig {
+                github: Some(GitHubConfig {
+                    repository: "acme".to_string(),
+                    ..config.github.clone().unwrap()
+                }),
+                ..IntegrationsConfig::default()
+            }
+            .validate()
+            .unwrap_err(),
+            vec!["github.repository: must be owner/name"]
+        );
+    }
+}
@@ -42,7 +42,7 @@
 //!         total_thoughts: 5,
 //!         next_thought_needed: true,
 //!         ..Default::default()
-//!     }).await?;
+//!     }, None).await?;
 //!
 //!     // Mark the session as complete
 //!     client.complete_session(&session.session_id).await?;
@@ -53,33 +53,75 @@
 //!
 //! See the crate documentation and module docs for more details and advanced usage.
 
+pub mod actions;
+pub mod admin;
 pub mod analytics;
+pub mod annotations;
 pub mod config;
+pub mod decisions;
 pub mod export;
+pub mod integrations;
+pub mod local;
+pub mod redaction;
 pub mod session;
+#[cfg(feature = "test_utils")]
+pub mod test_utils;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod thinking;
+pub mod usage;
+pub mod visualization;
 
 // Re-export main types for convenience
 pub use session::{SessionManager, SessionMetadata, ThinkingSession};
 pub use thinking::{ThinkingEngine, ThoughtData, ThoughtProcessor};
 
 // Re-export client and server types
-pub use crate::thinking::client::SequentialThinkingClient;
+pub use crate::thinking::client::{SequentialThinkingClient, SessionHandle};
+pub use crate::thinking::clock::{Clock, SharedClock, SystemClock, TestClock};
 pub use crate::thinking::server::SequentialThinkingServer;
+pub use crate::thinking::transport::{
+    InMemoryTransport, LoopbackTransport, McpTransport, Transport,
+};
 
 // Re-export error types
-pub use crate::thinking::error::{SequentialThinkingError, SequentialThinkingResult};
+pub use crate::thinking::error::{
+    ErrorContext, RecoveryAdvice, SequentialThinkingError, SequentialThinkingResult,
+};
 
 // Re-export configuration types
-pub use crate::config::{ClientConfig, ServerConfig, ThinkingConfig};
+pub use crate::config::{
+    ClientConfig, ServerConfig, ServerTarget, ThinkingConfig, TransportConfig,
+};
+
+// Re-export admin types
+#[cfg(feature = "http-transport")]
+pub use crate::admin::AdminClient;
+pub use crate::admin::{AdminStats, LiveSession};
 
 // Re-export analytics types
 pub use crate::analytics::{AnalyticsEngine, SessionAnalytics};
 pub use crate::thinking::ThinkingStats;
 
+// Re-export annotation types
+pub use crate::annotations::{Annotation, AnnotationStore};
+
+// Re-export decision types
+pub use crate::decisions::{Decision, DecisionLog, DecisionStatus};
+
 // Re-export export types
 pub use crate::export::{ExportEngine, ExportFormat, ExportOptions};
 
+// Re-export embedding types
+pub use crate::local::{BlockingLocalThinking, LocalThinking};
+
+// Re-export usage types
+pub use crate::usage::{UsageReport, UsageTracker};
+
+// Re-export visualization types
+pub use crate::visualization::progress::{Progress, ProgressTheme};
+pub use crate::visualization::{ThoughtRenderer, VisualizationStyle};
+
 /// Result type for sequential thinking operations
 pub type Result<T> = std::result::Result<T, SequentialThinkingError>;
 
@@ -88,13 +130,15 @@ pub fn default_server_config() -> ServerConfig {
     ServerConfig {
         name: "ultrafast-sequential-thinking".to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
-        transport: "stdio".to_string(),
-        port: 8080,
+        transport: config::TransportConfig::Stdio,
         thinking: ThinkingConfig::default(),
         export: config::ExportConfig::default(),
         analytics: config::AnalyticsConfig::default(),
         logging: config::LoggingConfig::default(),
         security: config::SecurityConfig::default(),
+        ui: config::UIConfig::default(),
+        integrations: integrations::IntegrationsConfig::default(),
+        usage: usage::UsageConfig::default(),
     }
 }
 
@@ -107,6 +151,9 @@ pub fn default_client_config() -> ClientConfig {
         thinking: thinking::client::ClientThinkingConfig::default(),
         connection: config::ConnectionConfig::default(),
         ui: config::UIConfig::default(),
+        api_key: None,
+        servers: std::collections::HashMap::new(),
+        default_server: None,
     }
 }
 
@@ -118,7 +165,7 @@ mod tests {
     fn test_default_configs() {
         let server_config = default_server_config();
         assert_eq!(server_config.name, "ultrafast-sequential-thinking");
-        assert_eq!(server_config.transport, "stdio");
+        assert_eq!(server_config.transport, config::TransportConfig::Stdio);
 
         let client_config = default_client_config();
         assert_eq!(client_config.server_url, "stdio://");
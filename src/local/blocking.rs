@@ -0,0 +1,108 @@
+//! # Blocking API
+//!
+//! Synchronous wrapper around [`LocalThinking`].
+//!
+//! Each call runs on a runtime owned by the wrapper, so it must not be made
+//! from within an async context; use [`LocalThinking`] there instead.
+
+use tokio::runtime::{Builder, Runtime};
+
+use super::LocalThinking;
+use crate::export::{ExportFormat, ExportLocation, ExportOptions};
+use crate::session::ThinkingSession;
+use crate::thinking::error::{SequentialThinkingError, SequentialThinkingResult};
+use crate::thinking::{SharedThought, ThoughtData};
+
+/// Synchronous embedded sequential thinking
+pub struct BlockingLocalThinking {
+    inner: LocalThinking,
+    runtime: Runtime,
+}
+
+impl BlockingLocalThinking {
+    /// Create an instance with default settings
+    pub fn new() -> SequentialThinkingResult<Self> {
+        Self::from_local(LocalThinking::new())
+    }
+
+    /// Wrap a configured [`LocalThinking`]
+    pub fn from_local(inner: LocalThinking) -> SequentialThinkingResult<Self> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| SequentialThinkingError::internal_error(e.to_string()))?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Get the wrapped asynchronous API
+    pub fn local(&self) -> &LocalThinking {
+        &self.inner
+    }
+
+    /// Start a session and return its ID
+    pub fn start_session(&self, title: impl Into<String>) -> SequentialThinkingResult<String> {
+        self.runtime.block_on(self.inner.start_session(title))
+    }
+
+    /// Record a thought in a session, returning it as processed
+    pub fn think(
+        &self,
+        session_id: &str,
+        thought: ThoughtData,
+    ) -> SequentialThinkingResult<ThoughtData> {
+        self.runtime.block_on(self.inner.think(session_id, thought))
+    }
+
+    /// Get a snapshot of a session
+    pub fn session(&self, session_id: &str) -> SequentialThinkingResult<ThinkingSession> {
+        self.runtime.block_on(self.inner.session(session_id))
+    }
+
+    /// Get the thoughts of a session, including spilled ones
+    pub fn thoughts(&self, session_id: &str) -> SequentialThinkingResult<Vec<SharedThought>> {
+        self.runtime.block_on(self.inner.thoughts(session_id))
+    }
+
+    /// Render a session in an export format
+    pub fn export(
+        &self,
+        session_id: &str,
+        format: ExportFormat,
+    ) -> SequentialThinkingResult<String> {
+        self.runtime.block_on(self.inner.export(session_id, format))
+    }
+
+    /// Export a session to the export engine's destination
+    pub fn save_export(
+        &self,
+        session_id: &str,
+        options: ExportOptions,
+    ) -> SequentialThinkingResult<ExportLocation> {
+        self.runtime
+            .block_on(self.inner.save_export(session_id, options))
+    }
+
+    /// Remove a session, returning whether it existed
+    pub fn end_session(&self, session_id: &str) -> bool {
+        self.runtime.block_on(self.inner.end_session(session_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocking_local_thinking() {
+        let local = BlockingLocalThinking::new().unwrap();
+        let session_id = local.start_session("Sync embedding").unwrap();
+        let mut thought = ThoughtData::new("One step is enough".to_string(), 1, 1);
+        thought.next_thought_needed = false;
+        local.think(&session_id, thought).unwrap();
+
+        let json = local.export(&session_id, ExportFormat::Json).unwrap();
+        assert!(json.contains("One step is enough"));
+        assert!(local.end_session(&session_id));
+        assert!(local.session(&session_id).is_err());
+    }
+}
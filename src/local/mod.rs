@@ -0,0 +1,252 @@
+//! # Local Module
+//!
+//! In-process sequential thinking without a server.
+//!
+//! [`LocalThinking`] ties a [`SessionManager`], the [`ThinkingEngine`] of each
+//! session and an [`ExportEngine`] together behind a small API, so
+//! applications can record and export thinking sessions directly instead of
+//! talking MCP to a spawned binary. [`BlockingLocalThinking`] offers the same
+//! API for synchronous code.
+
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+pub mod blocking;
+
+pub use blocking::BlockingLocalThinking;
+
+use crate::config::ThinkingConfig;
+use crate::export::{ExportEngine, ExportFormat, ExportLocation, ExportOptions};
+use crate::session::{SessionManager, ThinkingSession};
+use crate::thinking::error::{SequentialThinkingError, SequentialThinkingResult};
+use crate::thinking::spill::FileSpillStore;
+use crate::thinking::{SharedThought, ThinkingEngine, ThoughtData};
+
+/// Embedded sequential thinking: sessions, thought processing and exports
+pub struct LocalThinking {
+    thinking_config: ThinkingConfig,
+    sessions: SessionManager,
+    exporter: Mutex<ExportEngine>,
+}
+
+impl Default for LocalThinking {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalThinking {
+    /// Create an instance with default settings
+    pub fn new() -> Self {
+        Self {
+            thinking_config: ThinkingConfig::default(),
+            sessions: SessionManager::new(),
+            exporter: Mutex::new(ExportEngine::new()),
+        }
+    }
+
+    /// Apply the numbering policy, strategy, attachment limits and memory
+    /// bounds of a thinking configuration to new sessions
+    pub fn with_thinking_config(mut self, thinking_config: ThinkingConfig) -> Self {
+        self.thinking_config = thinking_config;
+        self
+    }
+
+    /// Keep sessions in a custom session manager
+    pub fn with_session_manager(mut self, sessions: SessionManager) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
+    /// Export sessions with a custom export engine
+    pub fn with_export_engine(mut self, exporter: ExportEngine) -> Self {
+        self.exporter = Mutex::new(exporter);
+        self
+    }
+
+    /// Get the session manager, for persistence, leases and listing
+    pub fn sessions(&self) -> &SessionManager {
+        &self.sessions
+    }
+
+    /// Start a session and return its ID
+    pub async fn start_session(
+        &self,
+        title: impl Into<String>,
+    ) -> SequentialThinkingResult<String> {
+        let session_id = self
+            .sessions
+            .create_session(title.into())
+            .await
+            .map_err(|e| SequentialThinkingError::session_error(e.to_string()))?;
+        self.sessions
+            .modify_session(&session_id, |session| {
+                session.engine = self.engine(&session_id);
+            })
+            .await?;
+        Ok(session_id)
+    }
+
+    /// Engine for a new session, configured without stderr logging
+    fn engine(&self, session_id: &str) -> ThinkingEngine {
+        let config = &self.thinking_config;
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.set_numbering_policy(config.numbering_policy);
+        engine.set_strategy(config.strategy);
+        engine.set_attachment_limits(config.attachments);
+        engine.set_sanitize_config(config.sanitize);
+        if let Some(bounded) = &config.bounded_memory {
+            engine.set_bounded_memory(
+                bounded.max_thoughts_in_memory,
+                FileSpillStore::shared(&bounded.spill_dir),
+            );
+        }
+        engine.start_session(session_id.to_string());
+        engine
+    }
+
+    /// Record a thought in a session, returning it as processed
+    pub async fn think(
+        &self,
+        session_id: &str,
+        thought: ThoughtData,
+    ) -> SequentialThinkingResult<ThoughtData> {
+        self.sessions.process_thought(session_id, thought).await
+    }
+
+    /// Get a snapshot of a session
+    pub async fn session(&self, session_id: &str) -> SequentialThinkingResult<ThinkingSession> {
+        self.sessions.get_session(session_id).await.ok_or_else(|| {
+            SequentialThinkingError::not_found(format!("Session not found: {session_id}"))
+        })
+    }
+
+    /// Get the thoughts of a session, including spilled ones
+    pub async fn thoughts(&self, session_id: &str) -> SequentialThinkingResult<Vec<SharedThought>> {
+        Ok(self.session(session_id).await?.get_thoughts())
+    }
+
+    /// Render a session in an export format
+    pub async fn export(
+        &self,
+        session_id: &str,
+        format: ExportFormat,
+    ) -> SequentialThinkingResult<String> {
+        let options = ExportOptions {
+            format,
+            ..Default::default()
+        };
+        let session = self.session(session_id).await?;
+        let exporter = self.exporter.lock().await;
+        let data = exporter
+            .prepare_export_data(
+                session_id,
+                Some(&session.metadata),
+                &session.get_thoughts(),
+                Some(&session.get_stats()),
+                Some(&session.get_progress()),
+                Some(&branches(&session)),
+                None,
+                Some(session.engine.get_annotations()),
+                &options,
+            )
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))?;
+        let mut content = Vec::new();
+        exporter
+            .stream_export(&data, &mut content)
+            .await
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))?;
+        String::from_utf8(content)
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+    }
+
+    /// Export a session to the export engine's destination
+    pub async fn save_export(
+        &self,
+        session_id: &str,
+        options: ExportOptions,
+    ) -> SequentialThinkingResult<ExportLocation> {
+        let session = self.session(session_id).await?;
+        self.exporter
+            .lock()
+            .await
+            .export_session(
+                session_id,
+                Some(&session.metadata),
+                &session.get_thoughts(),
+                Some(&session.get_stats()),
+                Some(&session.get_progress()),
+                Some(&branches(&session)),
+                None,
+                Some(session.engine.get_annotations()),
+                options,
+            )
+            .await
+            .map_err(|e| SequentialThinkingError::internal_error(e.to_string()))
+    }
+
+    /// Remove a session, returning whether it existed
+    pub async fn end_session(&self, session_id: &str) -> bool {
+        self.sessions.remove_session(session_id).await
+    }
+}
+
+/// Thoughts of each branch of a session
+fn branches(session: &ThinkingSession) -> HashMap<String, Vec<SharedThought>> {
+    session
+        .engine
+        .get_branches()
+        .iter()
+        .map(|(branch_id, branch)| (branch_id.clone(), branch.thoughts.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionStatus;
+    use crate::thinking::sequence::NumberingPolicy;
+
+    #[tokio::test]
+    async fn test_local_thinking() {
+        let local = LocalThinking::new().with_thinking_config(ThinkingConfig {
+            numbering_policy: NumberingPolicy::Strict,
+            ..Default::default()
+        });
+        let session_id = local.start_session("Pick a cache").await.unwrap();
+
+        local
+            .think(
+                &session_id,
+                ThoughtData::new("LRU or LFU?".to_string(), 1, 2),
+            )
+            .await
+            .unwrap();
+        let skipped = local
+            .think(&session_id, ThoughtData::new("Skip".to_string(), 3, 3))
+            .await;
+        assert!(skipped.is_err());
+
+        let mut last = ThoughtData::new("LRU: access is recency-heavy".to_string(), 2, 2);
+        last.next_thought_needed = false;
+        local.think(&session_id, last).await.unwrap();
+
+        let session = local.session(&session_id).await.unwrap();
+        assert_eq!(session.status(), &SessionStatus::Completed);
+        assert_eq!(local.thoughts(&session_id).await.unwrap().len(), 2);
+
+        let markdown = local
+            .export(&session_id, ExportFormat::Markdown)
+            .await
+            .unwrap();
+        assert!(markdown.contains("LRU: access is recency-heavy"));
+
+        assert!(local.end_session(&session_id).await);
+        assert!(matches!(
+            local
+                .think(&session_id, ThoughtData::new("Late".to_string(), 3, 3))
+                .await,
+            Err(SequentialThinkingError::NotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,349 @@
+//! # Redaction Module
+//!
+//! Scrubbing personal data from analytics and alerts before they leave the server.
+//!
+//! A [`Redactor`] replaces whole values, such as thought text, session IDs
+//! and author names, and scrubs emails, URLs and configured patterns out of
+//! free text. Each [`RedactionMode`] picks the replacement:
+//!
+//! - `strip` drops the value for a placeholder such as `[email]`
+//! - `hash` replaces it with a digest such as `email-1f0c9a2b3d4e`, keyed by
+//!   the secret when one is set, so equal values stay linkable
+//! - `pseudonymize` replaces it with a token keyed by the secret and records
+//!   the token in a [`PseudonymTable`], which maps tokens back to the
+//!   original values for whoever holds it
+//!
+//! The [`pii`] submodule finds personal data and secrets in thoughts and
+//! redacts a copy of a session for sharing.
+
+pub mod pii;
+
+use hmac::{Hmac, Mac};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Pattern matching email addresses
+pub(crate) const EMAIL_PATTERN: &str =
+    r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}";
+
+/// Pattern matching web and FTP URLs
+const URL_PATTERN: &str = r#"\b(?:https?|ftp)://[^\s<>"')\]]+"#;
+
+/// How redacted values are replaced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionMode {
+    /// Replace values with a placeholder naming their kind
+    #[default]
+    Strip,
+    /// Replace values with a digest, keyed by the secret when one is set
+    Hash,
+    /// Replace values with tokens keyed by the secret, recorded for reversal
+    Pseudonymize,
+}
+
+/// Redaction settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RedactionConfig {
+    /// How redacted values are replaced
+    #[serde(default)]
+    pub mode: RedactionMode,
+    /// Key of hashes and pseudonyms; required to pseudonymize
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Whether whole thought text and session titles are replaced
+    #[serde(default = "default_true")]
+    pub thought_text: bool,
+    /// Whether email addresses are redacted from text
+    #[serde(default = "default_true")]
+    pub emails: bool,
+    /// Whether URLs are redacted from text
+    #[serde(default = "default_true")]
+    pub urls: bool,
+    /// Further regular expressions redacted from text
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// File keeping the pseudonym table across restarts
+    #[serde(default)]
+    pub pseudonym_table: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            mode: RedactionMode::default(),
+            secret: None,
+            thought_text: true,
+            emails: true,
+            urls: true,
+            patterns: Vec::new(),
+            pseudonym_table: None,
+        }
+    }
+}
+
+impl RedactionConfig {
+    /// Validate the settings, collecting every problem found
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let missing_secret = self.secret.as_deref().unwrap_or_default().is_empty();
+        if self.mode == RedactionMode::Pseudonymize && missing_secret {
+            errors.push("secret: must be set to pseudonymize".to_string());
+        }
+        for (index, pattern) in self.patterns.iter().enumerate() {
+            if let Err(e) = Regex::new(pattern) {
+                errors.push(format!("patterns[{index}]: {e}"));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Original values of pseudonyms, by token
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PseudonymTable {
+    /// Original value of each token
+    pub entries: BTreeMap<String, String>,
+}
+
+impl PseudonymTable {
+    /// Load a table saved with [`PseudonymTable::save`], or an empty one if the file is missing
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid pseudonym table {}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read {}: {e}", path.display())),
+        }
+    }
+
+    /// Save the table as JSON
+    pub fn save(&self, path: &std::path::Path) -> Result<(), String> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, content)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+
+    /// Replace every token in `text` with its original value
+    pub fn reidentify(&self, text: &str) -> String {
+        self.entries
+            .iter()
+            .fold(text.to_string(), |text, (token, original)| {
+                text.replace(token, original)
+            })
+    }
+}
+
+/// Redacts values and text according to a [`RedactionConfig`]
+pub struct Redactor {
+    mode: RedactionMode,
+    secret: Option<Vec<u8>>,
+    thought_text: bool,
+    rules: Vec<(&'static str, Regex)>,
+    table: Mutex<PseudonymTable>,
+    table_path: Option<PathBuf>,
+}
+
+impl std::fmt::Debug for Redactor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Redactor")
+            .field("mode", &self.mode)
+            .field("thought_text", &self.thought_text)
+            .field("rules", &self.rules)
+            .field("table_path", &self.table_path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Redactor {
+    /// Create a redactor, loading the pseudonym table if one was saved
+    pub fn new(config: &RedactionConfig) -> Result<Self, String> {
+        config.validate().map_err(|errors| errors.join("; "))?;
+        let mut rules = Vec::new();
+        if config.urls {
+            rules.push(("url", Regex::new(URL_PATTERN).expect("valid URL pattern")));
+        }
+        if config.emails {
+            rules.push((
+                "email",
+                Regex::new(EMAIL_PATTERN).expect("valid email pattern"),
+            ));
+        }
+        for pattern in &config.patterns {
+            rules.push(("redacted", Regex::new(pattern).map_err(|e| e.to_string())?));
+        }
+        let table_path = config.pseudonym_table.as_ref().map(PathBuf::from);
+        let table = match &table_path {
+            Some(path) => PseudonymTable::load(path)?,
+            None => PseudonymTable::default(),
+        };
+
+        Ok(Self {
+            mode: config.mode,
+            secret: config.secret.as_ref().map(|s| s.as_bytes().to_vec()),
+            thought_text: config.thought_text,
+            rules,
+            table: Mutex::new(table),
+            table_path,
+        })
+    }
+
+    /// Replace a whole value, such as a session ID or author name
+    pub fn redact_value(&self, kind: &str, value: &str) -> String {
+        match self.mode {
+            RedactionMode::Strip => format!("[{kind}]"),
+            RedactionMode::Hash => format!("{kind}-{}", &self.digest(value)[..12]),
+            RedactionMode::Pseudonymize => {
+                let token = format!("{kind}_{}", &self.digest(value)[..16]);
+                self.record(&token, value);
+                token
+            }
+        }
+    }
+
+    /// Redact emails, URLs and configured patterns from free text
+    pub fn redact_text(&self, text: &str) -> String {
+        self.rules
+            .iter()
+            .fold(text.to_string(), |text, (kind, rule)| {
+                rule.replace_all(&text, |caps: &regex::Captures| {
+                    self.redact_value(kind, &caps[0])
+                })
+                .into_owned()
+            })
+    }
+
+    /// Redact thought text, replacing it whole unless `thought_text` is off
+    pub fn redact_thought(&self, text: &str) -> String {
+        if self.thought_text && !text.is_empty() {
+            self.redact_value("thought", text)
+        } else {
+            self.redact_text(text)
+        }
+    }
+
+    /// Redact every string in a JSON value as free text
+    pub fn redact_json(&self, value: &serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::String(text) => serde_json::Value::String(self.redact_text(text)),
+            serde_json::Value::Array(items) => {
+                serde_json::Value::Array(items.iter().map(|item| self.redact_json(item)).collect())
+            }
+            serde_json::Value::Object(fields) => serde_json::Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, field)| (key.clone(), self.redact_json(field)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// Pseudonyms recorded so far
+    pub fn pseudonyms(&self) -> PseudonymTable {
+        self.table.lock().map(|t| t.clone()).unwrap_or_default()
+    }
+
+    /// Hex digest of a value, keyed by the secret when one is set
+    fn digest(&self, value: &str) -> String {
+        match &self.secret {
+            Some(secret) => {
+                let mut mac =
+                    Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+                mac.update(value.as_bytes());
+                hex::encode(mac.finalize().into_bytes())
+            }
+            None => hex::encode(Sha256::digest(value.as_bytes())),
+        }
+    }
+
+    /// Record a pseudonym, saving the table when it changes
+    fn record(&self, token: &str, value: &str) {
+        let Ok(mut table) = self.table.lock() else {
+            return;
+        };
+        if table.entries.contains_key(token) {
+            return;
+        }
+        table.entries.insert(token.to_string(), value.to_string());
+        if let Some(path) = &self.table_path {
+            if let Err(e) = table.save(path) {
+                tracing::warn!("Failed to save pseudonym table: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redaction_modes() {
+        let text = "Mail ana@example.com about https://internal.example.com/doc?id=7 and TICKET-42";
+        let config = |mode| RedactionConfig {
+            mode,
+            secret: Some("s3cret".to_string()),
+            patterns: vec![r"TICKET-\d+".to_string()],
+            ..Default::default()
+        };
+
+        let strip = Redactor::new(&config(RedactionMode::Strip)).unwrap();
+        assert_eq!(
+            strip.redact_text(text),
+            "Mail [email] about [url] and [redacted]"
+        );
+        assert_eq!(strip.redact_thought("Call Ana"), "[thought]");
+
+        let hash = Redactor::new(&config(RedactionMode::Hash)).unwrap();
+        let hashed = hash.redact_value("author", "ana");
+        assert_eq!(hashed, hash.redact_value("author", "ana"));
+        assert!(hashed.starts_with("author-") && hashed.len() == "author-".len() + 12);
+        assert!(hash.pseudonyms().entries.is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let table_path = dir.path().join("pseudonyms.json");
+        let pseudonymize = |secret: &str| {
+            Redactor::new(&RedactionConfig {
+                secret: Some(secret.to_string()),
+                pseudonym_table: Some(table_path.to_string_lossy().into_owned()),
+                ..config(RedactionMode::Pseudonymize)
+            })
+            .unwrap()
+        };
+        let redactor = pseudonymize("s3cret");
+        let redacted = redactor.redact_text(text);
+        assert!(!redacted.contains("ana@example.com"));
+        assert!(!redacted.contains("internal.example.com"));
+        assert_eq!(redactor.pseudonyms().reidentify(&redacted), text);
+        // Tokens are stable for a secret and the table survives restarts
+        let restarted = pseudonymize("s3cret");
+        assert_eq!(restarted.redact_text(text), redacted);
+        assert_eq!(PseudonymTable::load(&table_path).unwrap().entries.len(), 3);
+        assert_ne!(pseudonymize("other").redact_text(text), redacted);
+
+        let invalid = RedactionConfig {
+            mode: RedactionMode::Pseudonymize,
+            patterns: vec!["(".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(invalid.validate().unwrap_err().len(), 2);
+    }
+}
@@ -0,0 +1,323 @@
+//! # Session Leases
+//!
+//! Time-limited locks that keep two replicas from working on one session.
+//!
+//! Replicas sharing a session store take a [`SessionLease`] before processing
+//! a session's thoughts and renew it while they work. A lease that is not
+//! renewed expires and can be stolen by another replica, so a crashed replica
+//! never blocks a session for longer than the lease duration. Every grant
+//! carries a fencing token that increases per session, letting a store reject
+//! writes from a holder whose lease was stolen.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::thinking::error::{
+    utils::storage_io_error, SequentialThinkingError, SequentialThinkingResult,
+};
+
+/// Lease on a session held by one replica
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionLease {
+    /// Leased session
+    pub session_id: String,
+    /// Replica holding the lease
+    pub holder: String,
+    /// Fencing token, increasing with every grant for the session
+    pub token: u64,
+    /// When the lease was granted or last renewed
+    pub acquired_at: chrono::DateTime<chrono::Utc>,
+    /// When the lease expires unless renewed
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SessionLease {
+    /// Check whether the lease has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= chrono::Utc::now()
+    }
+}
+
+/// Storage for session leases shared between replicas
+///
+/// Implementations must make each operation atomic across every replica
+/// using the store. A lease held by another replica, or lost to one, is a
+/// [`StorageConflict`](SequentialThinkingError::StorageConflict); failures
+/// of the store itself are reported as unavailable or corrupted storage.
+pub trait LeaseStore: Send + std::fmt::Debug {
+    /// Take a session's lease if it is free, expired or already held by `holder`
+    fn acquire(
+        &mut self,
+        session_id: &str,
+        holder: &str,
+        ttl: chrono::Duration,
+    ) -> SequentialThinkingResult<SessionLease>;
+
+    /// Extend a lease that has not been taken over by another replica
+    fn renew(
+        &mut self,
+        lease: &SessionLease,
+        ttl: chrono::Duration,
+    ) -> SequentialThinkingResult<SessionLease>;
+
+    /// Give up a lease; releasing a lease taken over since is a no-op
+    fn release(&mut self, lease: &SessionLease) -> SequentialThinkingResult<()>;
+}
+
+/// Lease store shared between session managers
+pub type SharedLeaseStore = Arc<Mutex<dyn LeaseStore>>;
+
+/// Grant a lease given the session's current lease
+fn grant(
+    current: Option<&SessionLease>,
+    session_id: &str,
+    holder: &str,
+    ttl: chrono::Duration,
+) -> SequentialThinkingResult<SessionLease> {
+    if let Some(current) = current {
+        if current.holder != holder && !current.is_expired() {
+            return Err(SequentialThinkingError::storage_conflict(format!(
+                "Session {session_id} is locked by {} until {}",
+                current.holder, current.expires_at
+            )));
+        }
+    }
+    let now = chrono::Utc::now();
+    Ok(SessionLease {
+        session_id: session_id.to_string(),
+        holder: holder.to_string(),
+        token: current.map_or(1, |lease| lease.token + 1),
+        acquired_at: now,
+        expires_at: now + ttl,
+    })
+}
+
+/// Extend a lease given the session's current lease
+fn extend(
+    current: Option<&SessionLease>,
+    lease: &SessionLease,
+    ttl: chrono::Duration,
+) -> SequentialThinkingResult<SessionLease> {
+    match current {
+        Some(current) if current.token == lease.token && current.holder == lease.holder => {
+            let now = chrono::Utc::now();
+            Ok(SessionLease {
+                acquired_at: now,
+                expires_at: now + ttl,
+                ..current.clone()
+            })
+        }
+        _ => Err(SequentialThinkingError::storage_conflict(format!(
+            "Lease on session {} was lost",
+            lease.session_id
+        ))),
+    }
+}
+
+/// Lease store for replicas within one process
+#[derive(Debug, Default)]
+pub struct MemoryLeaseStore {
+    leases: HashMap<String, SessionLease>,
+}
+
+impl MemoryLeaseStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty shareable store
+    pub fn shared() -> SharedLeaseStore {
+        Arc::new(Mutex::new(Self::new()))
+    }
+}
+
+impl LeaseStore for MemoryLeaseStore {
+    fn acquire(
+        &mut self,
+        session_id: &str,
+        holder: &str,
+        ttl: chrono::Duration,
+    ) -> SequentialThinkingResult<SessionLease> {
+        let lease = grant(self.leases.get(session_id), session_id, holder, ttl)?;
+        self.leases.insert(session_id.to_string(), lease.clone());
+        Ok(lease)
+    }
+
+    fn renew(
+        &mut self,
+        lease: &SessionLease,
+        ttl: chrono::Duration,
+    ) -> SequentialThinkingResult<SessionLease> {
+        let renewed = extend(self.leases.get(&lease.session_id), lease, ttl)?;
+        self.leases
+            .insert(lease.session_id.clone(), renewed.clone());
+        Ok(renewed)
+    }
+
+    fn release(&mut self, lease: &SessionLease) -> SequentialThinkingResult<()> {
+        if let Some(current) = self.leases.get_mut(&lease.session_id) {
+            if current == lease {
+                // Keep the entry so fencing tokens keep increasing
+                current.expires_at = current.acquired_at;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lease store keeping one JSON file per session in a shared directory
+///
+/// Operations hold an exclusive lock on `<dir>/.lock`, so replicas on hosts
+/// sharing the directory see each other's leases. Released leases leave their
+/// file behind with an expired lease, keeping fencing tokens increasing.
+#[derive(Debug, Clone)]
+pub struct FileLeaseStore {
+    dir: PathBuf,
+}
+
+impl FileLeaseStore {
+    /// Create a store in the given directory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Create a shareable store in the given directory
+    pub fn shared(dir: impl Into<PathBuf>) -> SharedLeaseStore {
+        Arc::new(Mutex::new(Self::new(dir)))
+    }
+
+    /// Path of a session's lease file
+    pub fn path(&self, session_id: &str) -> PathBuf {
+        let name: String = session_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{name}.lease.json"))
+    }
+
+    /// Run an update of a session's lease under the directory lock
+    ///
+    /// The update returns the lease to write, if any, and its result.
+    fn update<T>(
+        &self,
+        session_id: &str,
+        update: impl FnOnce(Option<SessionLease>) -> SequentialThinkingResult<(Option<SessionLease>, T)>,
+    ) -> SequentialThinkingResult<T> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| storage_io_error("Failed to create lease directory", e))?;
+        let lock = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join(".lock"))
+            .map_err(|e| storage_io_error("Failed to open lease lock", e))?;
+        lock.lock()
+            .map_err(|e| storage_io_error("Failed to lock lease directory", e))?;
+
+        let path = self.path(session_id);
+        let current = match std::fs::read_to_string(&path) {
+            Ok(content) => Some(serde_json::from_str(&content).map_err(|e| {
+                SequentialThinkingError::storage_corrupted(format!("Corrupt lease: {e}"))
+            })?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(storage_io_error("Failed to read lease", e)),
+        };
+        let (lease, result) = update(current)?;
+        if let Some(lease) = lease {
+            let content = serde_json::to_string(&lease)?;
+            std::fs::write(&path, content)
+                .map_err(|e| storage_io_error("Failed to write lease", e))?;
+        }
+        Ok(result)
+    }
+}
+
+impl LeaseStore for FileLeaseStore {
+    fn acquire(
+        &mut self,
+        session_id: &str,
+        holder: &str,
+        ttl: chrono::Duration,
+    ) -> SequentialThinkingResult<SessionLease> {
+        self.update(session_id, |current| {
+            let lease = grant(current.as_ref(), session_id, holder, ttl)?;
+            Ok((Some(lease.clone()), lease))
+        })
+    }
+
+    fn renew(
+        &mut self,
+        lease: &SessionLease,
+        ttl: chrono::Duration,
+    ) -> SequentialThinkingResult<SessionLease> {
+        self.update(&lease.session_id, |current| {
+            let renewed = extend(current.as_ref(), lease, ttl)?;
+            Ok((Some(renewed.clone()), renewed))
+        })
+    }
+
+    fn release(&mut self, lease: &SessionLease) -> SequentialThinkingResult<()> {
+        self.update(&lease.session_id, |current| match current {
+            Some(current) if current == *lease => {
+                let expires_at = current.acquired_at;
+                Ok((
+                    Some(SessionLease {
+                        expires_at,
+                        ..current
+                    }),
+                    (),
+                ))
+            }
+            _ => Ok((None, ())),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_leases() {
+        let dir = tempfile::tempdir().unwrap();
+        let minute = chrono::Duration::minutes(1);
+        let stores: [SharedLeaseStore; 2] = [
+            MemoryLeaseStore::shared(),
+            FileLeaseStore::shared(dir.path()),
+        ];
+
+        for store in stores {
+            let mut store = store.lock().unwrap();
+            let lease = store.acquire("s1", "replica-a", minute).unwrap();
+            assert_eq!(lease.token, 1);
+            let locked = store.acquire("s1", "replica-b", minute).unwrap_err();
+            assert!(locked.to_string().contains("locked by replica-a"));
+            assert!(matches!(
+                locked,
+                SequentialThinkingError::StorageConflict { .. }
+            ));
+            let renewed = store.renew(&lease, minute).unwrap();
+            assert_eq!(renewed.token, 1);
+
+            // An expired lease is stolen and the old holder loses it
+            let expiring = store.renew(&renewed, chrono::Duration::zero()).unwrap();
+            let stolen = store.acquire("s1", "replica-b", minute).unwrap();
+            assert_eq!(stolen.token, 2);
+            assert!(store.renew(&expiring, minute).is_err());
+            store.release(&expiring).unwrap();
+            assert!(store.acquire("s1", "replica-a", minute).is_err());
+
+            store.release(&stolen).unwrap();
+            assert_eq!(store.acquire("s1", "replica-a", minute).unwrap().token, 3);
+        }
+    }
+}
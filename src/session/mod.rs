@@ -11,7 +11,22 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::thinking::{ThinkingEngine, ThinkingProgress, ThinkingStats, ThoughtData};
+pub mod lease;
+pub mod retry;
+pub mod template;
+
+pub use lease::{FileLeaseStore, LeaseStore, MemoryLeaseStore, SessionLease, SharedLeaseStore};
+pub use retry::RetryPolicy;
+pub use template::SessionTemplate;
+
+use crate::thinking::checkpoint::EngineCheckpoint;
+use crate::thinking::clock::{SharedClock, SystemClock};
+use crate::thinking::error::{
+    utils::storage_io_error, SequentialThinkingError, SequentialThinkingResult,
+};
+use crate::thinking::{
+    SharedThought, ThinkingEngine, ThinkingProgress, ThinkingStats, ThoughtData, ThoughtEdit,
+};
 
 /// Session metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,10 +49,13 @@ pub struct SessionMetadata {
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
     /// Custom metadata
     pub custom_data: HashMap<String, serde_json::Value>,
+    /// Agent or human that owns the session
+    #[serde(default, alias = "agent_id")]
+    pub author: Option<String>,
 }
 
-/// Session priority levels
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// Session priority levels, ordered from lowest to highest
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SessionPriority {
     Low,
     Normal,
@@ -45,6 +63,20 @@ pub enum SessionPriority {
     Critical,
 }
 
+impl std::str::FromStr for SessionPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(SessionPriority::Low),
+            "normal" => Ok(SessionPriority::Normal),
+            "high" => Ok(SessionPriority::High),
+            "critical" => Ok(SessionPriority::Critical),
+            _ => Err(format!("Unknown session priority: {s}")),
+        }
+    }
+}
+
 /// Session status
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum SessionStatus {
@@ -55,6 +87,21 @@ pub enum SessionStatus {
     Expired,
 }
 
+impl std::str::FromStr for SessionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "active" => Ok(SessionStatus::Active),
+            "paused" => Ok(SessionStatus::Paused),
+            "completed" => Ok(SessionStatus::Completed),
+            "cancelled" | "canceled" => Ok(SessionStatus::Cancelled),
+            "expired" => Ok(SessionStatus::Expired),
+            _ => Err(format!("Unknown session status: {s}")),
+        }
+    }
+}
+
 impl Default for SessionMetadata {
     fn default() -> Self {
         Self {
@@ -67,6 +114,7 @@ impl Default for SessionMetadata {
             last_modified: chrono::Utc::now(),
             expires_at: None,
             custom_data: HashMap::new(),
+            author: None,
         }
     }
 }
@@ -80,6 +128,8 @@ pub struct ThinkingSession {
     pub metadata: SessionMetadata,
     /// Thinking engine
     pub engine: ThinkingEngine,
+    /// Version of the stored session this copy was taken from
+    version: u64,
     #[allow(dead_code)]
     lock: Arc<RwLock<()>>,
 }
@@ -96,6 +146,7 @@ impl ThinkingSession {
             session_id,
             metadata,
             engine: ThinkingEngine::new(),
+            version: 1,
             lock: Arc::new(RwLock::new(())),
         }
     }
@@ -106,15 +157,38 @@ impl ThinkingSession {
             session_id,
             metadata,
             engine: ThinkingEngine::new(),
+            version: 1,
             lock: Arc::new(RwLock::new(())),
         }
     }
 
+    /// Read time from `clock`, restamping the session as created now
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        let now = clock.now();
+        self.metadata.created_at = now;
+        self.metadata.last_modified = now;
+        self.engine.set_clock(clock);
+        self
+    }
+
+    /// Current time on the session's clock
+    fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        self.engine.clock().now()
+    }
+
     /// Get session ID
     pub fn id(&self) -> &str {
         &self.session_id
     }
 
+    /// Get the version of the stored session this copy was taken from
+    ///
+    /// The [`SessionManager`] increments it on every change, and rejects
+    /// updates of copies taken from an older version.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
     /// Get session title
     pub fn title(&self) -> &str {
         &self.metadata.title
@@ -128,7 +202,7 @@ impl ThinkingSession {
     /// Set session status
     pub fn set_status(&mut self, status: SessionStatus) {
         self.metadata.status = status;
-        self.metadata.last_modified = chrono::Utc::now();
+        self.metadata.last_modified = self.now();
     }
 
     /// Get session priority
@@ -139,27 +213,27 @@ impl ThinkingSession {
     /// Set session priority
     pub fn set_priority(&mut self, priority: SessionPriority) {
         self.metadata.priority = priority;
-        self.metadata.last_modified = chrono::Utc::now();
+        self.metadata.last_modified = self.now();
     }
 
     /// Add a tag to the session
     pub fn add_tag(&mut self, tag: String) {
         if !self.metadata.tags.contains(&tag) {
             self.metadata.tags.push(tag);
-            self.metadata.last_modified = chrono::Utc::now();
+            self.metadata.last_modified = self.now();
         }
     }
 
     /// Remove a tag from the session
     pub fn remove_tag(&mut self, tag: &str) {
         self.metadata.tags.retain(|t| t != tag);
-        self.metadata.last_modified = chrono::Utc::now();
+        self.metadata.last_modified = self.now();
     }
 
     /// Set custom metadata
     pub fn set_custom_data(&mut self, key: String, value: serde_json::Value) {
         self.metadata.custom_data.insert(key, value);
-        self.metadata.last_modified = chrono::Utc::now();
+        self.metadata.last_modified = self.now();
     }
 
     /// Get custom metadata
@@ -167,13 +241,48 @@ impl ThinkingSession {
         self.metadata.custom_data.get(key)
     }
 
+    /// Pause the session, rejecting new thoughts until it is resumed
+    pub fn pause(&mut self) -> Result<(), String> {
+        self.engine.pause()?;
+        self.set_status(SessionStatus::Paused);
+        Ok(())
+    }
+
+    /// Resume a paused session, returning how long it was paused
+    pub fn resume(&mut self) -> Result<std::time::Duration, String> {
+        let paused_for = self.engine.resume()?;
+        self.set_status(SessionStatus::Active);
+        Ok(paused_for)
+    }
+
+    /// Replace the engine, e.g. with a server's copy, taking over its paused
+    /// or completed state
+    pub fn set_engine(&mut self, engine: ThinkingEngine) {
+        let completed = engine
+            .get_thoughts()
+            .last()
+            .is_some_and(|thought| !thought.next_thought_needed);
+        let status = if engine.is_paused() {
+            SessionStatus::Paused
+        } else if completed {
+            SessionStatus::Completed
+        } else {
+            SessionStatus::Active
+        };
+        self.engine = engine;
+        self.set_status(status);
+    }
+
     /// Check if session is expired
     pub fn is_expired(&self) -> bool {
-        if let Some(expires_at) = self.metadata.expires_at {
-            chrono::Utc::now() > expires_at
-        } else {
-            false
-        }
+        self.is_expired_at(self.now())
+    }
+
+    /// Check if session is expired at `now`
+    pub fn is_expired_at(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.metadata
+            .expires_at
+            .is_some_and(|expires_at| now > expires_at)
     }
 
     /// Check if session is active
@@ -192,13 +301,45 @@ impl ThinkingSession {
     }
 
     /// Get all thoughts in the session
-    pub fn get_thoughts(&self) -> Vec<ThoughtData> {
-        self.engine.get_thoughts().to_vec()
+    pub fn get_thoughts(&self) -> Vec<SharedThought> {
+        self.engine.all_thoughts().into_owned()
+    }
+
+    /// Correct a thought in place and append the change to the edit log
+    ///
+    /// The edit log is the engine's, persisted with its checkpoint.
+    pub fn edit_thought(
+        &mut self,
+        thought_number: u32,
+        new_text: String,
+        editor: Option<String>,
+    ) -> Result<ThoughtEdit, String> {
+        let edit = self
+            .engine
+            .edit_thought_as(thought_number, new_text, editor)?;
+        self.metadata.last_modified = self.now();
+        Ok(edit)
+    }
+
+    /// Get the log of in-place thought edits
+    pub fn edit_log(&self) -> Vec<ThoughtEdit> {
+        self.engine.get_edit_log().to_vec()
+    }
+
+    /// Time of the last change to the session or its thoughts
+    pub fn last_activity(&self) -> chrono::DateTime<chrono::Utc> {
+        self.engine
+            .get_thoughts()
+            .last()
+            .and_then(|thought| thought.timestamp)
+            .map_or(self.metadata.last_modified, |at| {
+                at.max(self.metadata.last_modified)
+            })
     }
 
     /// Get session age
     pub fn age(&self) -> chrono::Duration {
-        chrono::Utc::now() - self.metadata.created_at
+        self.now() - self.metadata.created_at
     }
 
     /// Get session duration
@@ -207,7 +348,163 @@ impl ThinkingSession {
     }
 }
 
+/// Lightweight listing entry for a stored session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionOverview {
+    /// Session ID
+    pub session_id: String,
+    /// Session title
+    pub title: String,
+    /// Session status
+    pub status: SessionStatus,
+    /// Session priority
+    pub priority: SessionPriority,
+    /// Session tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Agent or human that owns the session
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Number of thoughts in the session
+    pub thought_count: usize,
+    /// Created timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Last modified timestamp
+    pub last_modified: chrono::DateTime<chrono::Utc>,
+}
+
+/// Summary returned by paginated session listings
+pub type SessionSummary = SessionOverview;
+
+impl From<&ThinkingSession> for SessionOverview {
+    fn from(session: &ThinkingSession) -> Self {
+        Self {
+            session_id: session.session_id.clone(),
+            title: session.metadata.title.clone(),
+            status: session.metadata.status.clone(),
+            priority: session.metadata.priority.clone(),
+            tags: session.metadata.tags.clone(),
+            author: session.metadata.author.clone(),
+            thought_count: session.engine.thought_count(),
+            created_at: session.metadata.created_at,
+            last_modified: session.metadata.last_modified,
+        }
+    }
+}
+
+/// Filter and page selecting sessions in a listing
+///
+/// Every criterion that is set must match. Sessions are listed oldest first,
+/// and `offset` and `limit` select a page of the matching sessions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionFilter {
+    /// Only sessions with this status
+    pub status: Option<SessionStatus>,
+    /// Only sessions carrying this tag
+    pub tag: Option<String>,
+    /// Only sessions with this priority
+    pub priority: Option<SessionPriority>,
+    /// Only sessions created at or after this time
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only sessions created before this time
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only sessions whose title contains this text, ignoring case
+    pub title_contains: Option<String>,
+    /// Matching sessions to skip
+    #[serde(default)]
+    pub offset: usize,
+    /// Maximum number of sessions to return
+    pub limit: Option<usize>,
+}
+
+impl SessionFilter {
+    /// Create a filter matching every session
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match sessions with a status
+    pub fn with_status(mut self, status: SessionStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Only match sessions carrying a tag
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Only match sessions with a priority
+    pub fn with_priority(mut self, priority: SessionPriority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Only match sessions created in `[after, before)`
+    pub fn with_created_range(
+        mut self,
+        after: Option<chrono::DateTime<chrono::Utc>>,
+        before: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Self {
+        self.created_after = after;
+        self.created_before = before;
+        self
+    }
+
+    /// Only match sessions whose title contains some text, ignoring case
+    pub fn with_title(mut self, text: impl Into<String>) -> Self {
+        self.title_contains = Some(text.into());
+        self
+    }
+
+    /// Select a page of the matching sessions
+    pub fn with_page(mut self, offset: usize, limit: Option<usize>) -> Self {
+        self.offset = offset;
+        self.limit = limit;
+        self
+    }
+
+    /// Check whether a session matches the filter criteria
+    pub fn matches(&self, session: &ThinkingSession) -> bool {
+        let metadata = &session.metadata;
+        self.status.as_ref().is_none_or(|s| &metadata.status == s)
+            && self.tag.as_ref().is_none_or(|t| metadata.tags.contains(t))
+            && self
+                .priority
+                .as_ref()
+                .is_none_or(|p| &metadata.priority == p)
+            && self.created_after.is_none_or(|t| metadata.created_at >= t)
+            && self.created_before.is_none_or(|t| metadata.created_at < t)
+            && self
+                .title_contains
+                .as_ref()
+                .is_none_or(|text| metadata.title.to_lowercase().contains(&text.to_lowercase()))
+    }
+}
+
+/// One page of a session listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionPage {
+    /// Sessions on this page
+    pub items: Vec<SessionSummary>,
+    /// Number of sessions matching the filter
+    pub total: usize,
+    /// Matching sessions skipped before this page
+    pub offset: usize,
+    /// Maximum page size requested
+    pub limit: Option<usize>,
+}
+
+impl SessionPage {
+    /// Check whether more matching sessions follow this page
+    pub fn has_more(&self) -> bool {
+        self.offset + self.items.len() < self.total
+    }
+}
+
 /// Session manager for handling multiple sessions
+#[derive(Debug)]
 pub struct SessionManager {
     /// Active sessions
     sessions: Arc<RwLock<HashMap<String, ThinkingSession>>>,
@@ -215,6 +512,10 @@ pub struct SessionManager {
     config: SessionManagerConfig,
     /// Statistics
     stats: Arc<RwLock<SessionManagerStats>>,
+    /// Leases coordinating replicas that share sessions
+    leases: SharedLeaseStore,
+    /// Source of session timestamps and expiry checks
+    clock: SharedClock,
 }
 
 /// Session manager configuration
@@ -232,6 +533,78 @@ pub struct SessionManagerConfig {
     pub persist_sessions: bool,
     /// Persistence directory
     pub persistence_dir: String,
+    /// What happens when a session is created at `max_sessions`
+    pub eviction_policy: EvictionPolicy,
+    /// How lease and persistence operations are retried after transient failures
+    pub store_retry: RetryPolicy,
+}
+
+/// Policy applied when a new session would exceed `max_sessions`
+///
+/// Evicted sessions are written to `<persistence_dir>/evicted/<id>.json`
+/// first when persistence is enabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    /// Refuse to create the session
+    #[default]
+    Reject,
+    /// Evict the least recently active completed session
+    EvictOldestCompleted,
+    /// Evict the lowest-priority session, least recently active first,
+    /// never one ranked above the new session
+    EvictLowestPriority,
+}
+
+impl std::str::FromStr for EvictionPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "reject" => Ok(EvictionPolicy::Reject),
+            "evict_oldest_completed" => Ok(EvictionPolicy::EvictOldestCompleted),
+            "evict_lowest_priority" => Ok(EvictionPolicy::EvictLowestPriority),
+            _ => Err(format!("Unknown eviction policy: {s}")),
+        }
+    }
+}
+
+impl EvictionPolicy {
+    /// Choose the session to evict for a new session of the given priority
+    fn select<'a>(
+        &self,
+        sessions: &'a HashMap<String, ThinkingSession>,
+        incoming: &SessionPriority,
+    ) -> Option<&'a str> {
+        let oldest = |candidates: Vec<&'a ThinkingSession>| {
+            candidates
+                .into_iter()
+                .min_by_key(|s| (s.last_activity(), s.session_id.as_str()))
+                .map(|s| s.session_id.as_str())
+        };
+        match self {
+            EvictionPolicy::Reject => None,
+            EvictionPolicy::EvictOldestCompleted => oldest(
+                sessions
+                    .values()
+                    .filter(|s| s.metadata.status == SessionStatus::Completed)
+                    .collect(),
+            ),
+            EvictionPolicy::EvictLowestPriority => {
+                let lowest = sessions
+                    .values()
+                    .map(|s| &s.metadata.priority)
+                    .filter(|p| *p <= incoming)
+                    .min()?;
+                oldest(
+                    sessions
+                        .values()
+                        .filter(|s| &s.metadata.priority == lowest)
+                        .collect(),
+                )
+            }
+        }
+    }
 }
 
 impl Default for SessionManagerConfig {
@@ -243,6 +616,8 @@ impl Default for SessionManagerConfig {
             cleanup_interval: 300,
             persist_sessions: false,
             persistence_dir: "./sessions".to_string(),
+            eviction_policy: EvictionPolicy::Reject,
+            store_retry: RetryPolicy::default(),
         }
     }
 }
@@ -258,12 +633,16 @@ pub struct SessionManagerStats {
     pub total_sessions_cancelled: u64,
     /// Total sessions expired
     pub total_sessions_expired: u64,
+    /// Total sessions evicted to make room for new ones
+    pub total_sessions_evicted: u64,
     /// Current active sessions
     pub active_sessions: usize,
     /// Average session duration in seconds
     pub avg_session_duration: f64,
     /// Total session time in seconds
     pub total_session_time: u64,
+    /// Lease and persistence operations retried after a transient failure
+    pub store_retries: u64,
 }
 
 impl SessionManager {
@@ -273,6 +652,8 @@ impl SessionManager {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             config: SessionManagerConfig::default(),
             stats: Arc::new(RwLock::new(SessionManagerStats::default())),
+            leases: MemoryLeaseStore::shared(),
+            clock: SystemClock::shared(),
         }
     }
 
@@ -282,29 +663,140 @@ impl SessionManager {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             config,
             stats: Arc::new(RwLock::new(SessionManagerStats::default())),
+            leases: MemoryLeaseStore::shared(),
+            clock: SystemClock::shared(),
         }
     }
 
+    /// Coordinate session leases through a store shared with other replicas
+    pub fn with_lease_store(mut self, leases: SharedLeaseStore) -> Self {
+        self.leases = leases;
+        self
+    }
+
+    /// Read time from `clock` for new and loaded sessions, expiry and archiving
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Run a lease or persistence operation under the store retry policy
+    async fn with_store_retry<T, F, Fut>(
+        &self,
+        description: &str,
+        operation: F,
+    ) -> SequentialThinkingResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = SequentialThinkingResult<T>>,
+    {
+        let (result, retries) = self.config.store_retry.run(description, operation).await;
+        if retries > 0 {
+            self.stats.write().await.store_retries += u64::from(retries);
+        }
+        result
+    }
+
+    /// Run an operation on the lease store
+    fn with_leases<T>(
+        &self,
+        operation: impl FnOnce(&mut dyn LeaseStore) -> SequentialThinkingResult<T>,
+    ) -> SequentialThinkingResult<T> {
+        let mut leases = self
+            .leases
+            .lock()
+            .map_err(|e| SequentialThinkingError::internal_error(e.to_string()))?;
+        operation(&mut *leases)
+    }
+
+    /// Lock a session for a replica for `ttl`, stealing an expired lease
+    ///
+    /// Renew the lease before it expires while processing the session's
+    /// thoughts, and release it when done. A session locked by another
+    /// replica is a [`StorageConflict`](SequentialThinkingError::StorageConflict).
+    pub async fn acquire_lease(
+        &self,
+        session_id: &str,
+        holder: &str,
+        ttl: chrono::Duration,
+    ) -> SequentialThinkingResult<SessionLease> {
+        self.with_store_retry("Acquiring a session lease", || async {
+            self.with_leases(|leases| leases.acquire(session_id, holder, ttl))
+        })
+        .await
+    }
+
+    /// Extend a session lease, failing if another replica has taken it over
+    pub async fn renew_lease(
+        &self,
+        lease: &SessionLease,
+        ttl: chrono::Duration,
+    ) -> SequentialThinkingResult<SessionLease> {
+        self.with_store_retry("Renewing a session lease", || async {
+            self.with_leases(|leases| leases.renew(lease, ttl))
+        })
+        .await
+    }
+
+    /// Release a session lease
+    pub async fn release_lease(&self, lease: &SessionLease) -> SequentialThinkingResult<()> {
+        self.with_store_retry("Releasing a session lease", || async {
+            self.with_leases(|leases| leases.release(lease))
+        })
+        .await
+    }
+
+    /// Write a file under the persistence directory, creating its parent
+    async fn write_persisted(
+        &self,
+        path: &std::path::Path,
+        content: &str,
+    ) -> SequentialThinkingResult<()> {
+        self.with_store_retry("Writing persisted sessions", || async {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| {
+                    storage_io_error(&format!("Failed to create {}", dir.display()), e)
+                })?;
+            }
+            std::fs::write(path, content)
+                .map_err(|e| storage_io_error(&format!("Failed to write {}", path.display()), e))
+        })
+        .await
+    }
+
     /// Create a new session
     pub async fn create_session(
         &self,
         title: String,
     ) -> Result<String, Box<dyn std::error::Error>> {
         let session_id = Uuid::new_v4().to_string();
+        self.create_named_session(session_id.clone(), title).await?;
+        Ok(session_id)
+    }
 
-        // Check if we've reached the maximum number of sessions
-        {
-            let sessions = self.sessions.read().await;
-            if sessions.len() >= self.config.max_sessions {
-                return Err("Maximum number of sessions reached".into());
-            }
-        }
-
-        let session = ThinkingSession::new(session_id.clone(), title);
+    /// Create a new session under a given ID, failing if it exists
+    pub async fn create_named_session(
+        &self,
+        session_id: String,
+        title: String,
+    ) -> SequentialThinkingResult<()> {
+        let session =
+            ThinkingSession::new(session_id.clone(), title).with_clock(self.clock.clone());
 
         {
             let mut sessions = self.sessions.write().await;
-            sessions.insert(session_id.clone(), session);
+            if sessions.contains_key(&session_id) {
+                return Err(SequentialThinkingError::validation_error(format!(
+                    "Session already exists: {session_id}"
+                )));
+            }
+            // Make room under the eviction policy once the limit is reached
+            while sessions.len() >= self.config.max_sessions {
+                self.evict_one(&mut sessions, session.priority())
+                    .await
+                    .map_err(|e| SequentialThinkingError::session_error(e.to_string()))?;
+            }
+            sessions.insert(session_id, session);
         }
 
         // Update statistics
@@ -314,19 +806,197 @@ impl SessionManager {
             stats.active_sessions += 1;
         }
 
+        Ok(())
+    }
+
+    /// Create a new session from a template
+    ///
+    /// The session receives the template's tags and metadata, and its seeded
+    /// thoughts are processed before the session ID is returned.
+    pub async fn create_from_template(
+        &self,
+        template: &SessionTemplate,
+        title: Option<String>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        template.validate()?;
+
+        let session_id = self
+            .create_session(title.unwrap_or_else(|| template.name.clone()))
+            .await?;
+
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(&session_id)
+            .ok_or("Session disappeared during template setup")?;
+
+        session.engine.start_session(session_id.clone());
+        for thought in template.seed_thought_data() {
+            session.engine.process_thought(thought).await?;
+        }
+
+        session.metadata.description = template.description.clone();
+        for tag in &template.required_tags {
+            session.add_tag(tag.clone());
+        }
+        for (key, value) in &template.metadata {
+            session.set_custom_data(key.clone(), value.clone());
+        }
+        session.set_custom_data(
+            "template".to_string(),
+            serde_json::Value::String(template.name.clone()),
+        );
+
         Ok(session_id)
     }
 
+    /// Evict one session under the eviction policy, persisting it first
+    async fn evict_one(
+        &self,
+        sessions: &mut HashMap<String, ThinkingSession>,
+        incoming: &SessionPriority,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let evicted = self
+            .config
+            .eviction_policy
+            .select(sessions, incoming)
+            .ok_or("Maximum number of sessions reached")?
+            .to_string();
+
+        if self.config.persist_sessions {
+            let path = std::path::Path::new(&self.config.persistence_dir)
+                .join("evicted")
+                .join(format!("{evicted}.json"));
+            let content =
+                serde_json::to_string_pretty(&Self::session_to_json(&sessions[&evicted]))?;
+            self.write_persisted(&path, &content).await?;
+        }
+        sessions.remove(&evicted);
+        tracing::info!("Evicted session {} to stay within max_sessions", evicted);
+
+        let mut stats = self.stats.write().await;
+        stats.total_sessions_evicted += 1;
+        stats.active_sessions = stats.active_sessions.saturating_sub(1);
+        Ok(())
+    }
+
     /// Get a session by ID
     pub async fn get_session(&self, session_id: &str) -> Option<ThinkingSession> {
         let sessions = self.sessions.read().await;
         sessions.get(session_id).cloned()
     }
 
-    /// Update a session
-    pub async fn update_session(&self, session_id: &str, session: ThinkingSession) -> bool {
+    /// Process a thought in a session while holding the session lock
+    ///
+    /// The session is marked completed once its last thought needs no successor.
+    pub async fn process_thought(
+        &self,
+        session_id: &str,
+        thought: ThoughtData,
+    ) -> SequentialThinkingResult<ThoughtData> {
         let mut sessions = self.sessions.write().await;
-        sessions.insert(session_id.to_string(), session).is_some()
+        let session = sessions.get_mut(session_id).ok_or_else(|| {
+            SequentialThinkingError::not_found(format!("Session not found: {session_id}"))
+        })?;
+        session.engine.check_sequence(&thought)?;
+        let processed = session
+            .engine
+            .process_thought(thought)
+            .await
+            .map_err(SequentialThinkingError::processing_error)?;
+        if !processed.next_thought_needed {
+            session.set_status(SessionStatus::Completed);
+        } else {
+            session.metadata.last_modified = self.clock.now();
+        }
+        session.version += 1;
+        Ok(processed)
+    }
+
+    /// Pause a session, rejecting its new thoughts until it is resumed
+    pub async fn pause_session(&self, session_id: &str) -> SequentialThinkingResult<()> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.get_mut(session_id).ok_or_else(|| {
+            SequentialThinkingError::not_found(format!("Session not found: {session_id}"))
+        })?;
+        session
+            .pause()
+            .map_err(SequentialThinkingError::session_error)?;
+        session.version += 1;
+        Ok(())
+    }
+
+    /// Resume a paused session, returning how long it was paused
+    pub async fn resume_session(
+        &self,
+        session_id: &str,
+    ) -> SequentialThinkingResult<std::time::Duration> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.get_mut(session_id).ok_or_else(|| {
+            SequentialThinkingError::not_found(format!("Session not found: {session_id}"))
+        })?;
+        let paused = session
+            .resume()
+            .map_err(SequentialThinkingError::session_error)?;
+        session.version += 1;
+        Ok(paused)
+    }
+
+    /// Replace a session with an updated copy, returning its new version
+    ///
+    /// The copy must have been taken from the current version of the
+    /// session; if the session changed since, the update is rejected with a
+    /// [`StorageConflict`](SequentialThinkingError::StorageConflict) instead
+    /// of overwriting the other change.
+    pub async fn update_session(
+        &self,
+        session_id: &str,
+        mut session: ThinkingSession,
+    ) -> SequentialThinkingResult<u64> {
+        let mut sessions = self.sessions.write().await;
+        let current = sessions.get_mut(session_id).ok_or_else(|| {
+            SequentialThinkingError::not_found(format!("Session not found: {session_id}"))
+        })?;
+        if current.version != session.version {
+            return Err(SequentialThinkingError::storage_conflict(format!(
+                "Session {session_id} is at version {}, the update was based on version {}",
+                current.version, session.version
+            )));
+        }
+        session.version += 1;
+        let version = session.version;
+        *current = session;
+        Ok(version)
+    }
+
+    /// Apply `change` to the current session and store it
+    ///
+    /// When another writer updates the session first, the change is applied
+    /// again to the fresh session, up to `store_retry.max_retries` times.
+    pub async fn modify_session(
+        &self,
+        session_id: &str,
+        mut change: impl FnMut(&mut ThinkingSession),
+    ) -> SequentialThinkingResult<ThinkingSession> {
+        let mut conflicts = 0;
+        loop {
+            let mut session = self.get_session(session_id).await.ok_or_else(|| {
+                SequentialThinkingError::not_found(format!("Session not found: {session_id}"))
+            })?;
+            change(&mut session);
+            match self.update_session(session_id, session.clone()).await {
+                Ok(version) => {
+                    session.version = version;
+                    return Ok(session);
+                }
+                Err(SequentialThinkingError::StorageConflict { .. })
+                    if conflicts < self.config.store_retry.max_retries =>
+                {
+                    tracing::debug!("Session {} changed during an update, retrying", session_id);
+                    conflicts += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 
     /// Remove a session
@@ -358,6 +1028,96 @@ impl SessionManager {
             .collect()
     }
 
+    /// List an overview of every session, oldest first
+    pub async fn list_overviews(&self) -> Vec<SessionOverview> {
+        let sessions = self.sessions.read().await;
+        let mut overviews: Vec<SessionOverview> =
+            sessions.values().map(SessionOverview::from).collect();
+        overviews.sort_by_key(|o| o.created_at);
+        overviews
+    }
+
+    /// List a filtered page of session summaries, oldest first
+    ///
+    /// Only the sessions on the page are summarized; nothing is cloned
+    /// beyond their metadata.
+    pub async fn list_sessions(&self, filter: &SessionFilter) -> SessionPage {
+        let sessions = self.sessions.read().await;
+        let mut matching: Vec<&ThinkingSession> = sessions
+            .values()
+            .filter(|session| filter.matches(session))
+            .collect();
+        matching.sort_by(|a, b| {
+            (a.metadata.created_at, &a.session_id).cmp(&(b.metadata.created_at, &b.session_id))
+        });
+
+        SessionPage {
+            total: matching.len(),
+            items: matching
+                .into_iter()
+                .skip(filter.offset)
+                .take(filter.limit.unwrap_or(usize::MAX))
+                .map(SessionSummary::from)
+                .collect(),
+            offset: filter.offset,
+            limit: filter.limit,
+        }
+    }
+
+    /// Remove all sessions with the given status, returning their IDs
+    pub async fn delete_sessions_with_status(&self, status: &SessionStatus) -> Vec<String> {
+        let mut sessions = self.sessions.write().await;
+        let matching: Vec<String> = sessions
+            .iter()
+            .filter(|(_, session)| session.status() == status)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for session_id in &matching {
+            sessions.remove(session_id);
+        }
+
+        let mut stats = self.stats.write().await;
+        stats.active_sessions = stats.active_sessions.saturating_sub(matching.len());
+        matching
+    }
+
+    /// Move sessions not modified within `max_age` into the archive directory
+    ///
+    /// Each archived session is written to `<persistence_dir>/archive/<id>.json`
+    /// and removed from the manager. Returns the archived session IDs.
+    pub async fn archive_sessions_older_than(
+        &self,
+        max_age: chrono::Duration,
+    ) -> SequentialThinkingResult<Vec<String>> {
+        let cutoff = self.clock.now() - max_age;
+        let archive_dir = std::path::Path::new(&self.config.persistence_dir).join("archive");
+
+        let mut sessions = self.sessions.write().await;
+        let stale: Vec<String> = sessions
+            .iter()
+            .filter(|(_, session)| session.metadata.last_modified < cutoff)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        if stale.is_empty() {
+            return Ok(stale);
+        }
+
+        for session_id in &stale {
+            if let Some(session) = sessions.get(session_id) {
+                let content = serde_json::to_string_pretty(&Self::session_to_json(session))?;
+                self.write_persisted(&archive_dir.join(format!("{session_id}.json")), &content)
+                    .await?;
+            }
+            sessions.remove(session_id);
+        }
+
+        let mut stats = self.stats.write().await;
+        stats.active_sessions = stats.active_sessions.saturating_sub(stale.len());
+        Ok(stale)
+    }
+
     /// Get session statistics
     pub async fn get_stats(&self) -> SessionManagerStats {
         self.stats.read().await.clone()
@@ -368,9 +1128,10 @@ impl SessionManager {
         let mut sessions = self.sessions.write().await;
         let mut expired_count = 0;
 
+        let now = self.clock.now();
         let expired_sessions: Vec<String> = sessions
             .iter()
-            .filter(|(_, session)| session.is_expired())
+            .filter(|(_, session)| session.is_expired_at(now))
             .map(|(id, _)| id.clone())
             .collect();
 
@@ -396,6 +1157,7 @@ impl SessionManager {
         let sessions = Arc::clone(&self.sessions);
         let config = self.config.clone();
         let stats = Arc::clone(&self.stats);
+        let clock = Arc::clone(&self.clock);
 
         tokio::spawn(async move {
             let mut interval =
@@ -407,9 +1169,10 @@ impl SessionManager {
                 let mut sessions_guard = sessions.write().await;
                 let mut expired_count = 0;
 
+                let now = clock.now();
                 let expired_sessions: Vec<String> = sessions_guard
                     .iter()
-                    .filter(|(_, session)| session.is_expired())
+                    .filter(|(_, session)| session.is_expired_at(now))
                     .map(|(id, _)| id.clone())
                     .collect();
 
@@ -435,7 +1198,9 @@ impl SessionManager {
     }
 
     /// Persist sessions to disk
-    pub async fn persist_sessions(&self) -> Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// Failed writes are retried under the store retry policy.
+    pub async fn persist_sessions(&self) -> SequentialThinkingResult<()> {
         if !self.config.persist_sessions {
             return Ok(());
         }
@@ -443,58 +1208,130 @@ impl SessionManager {
         let sessions = self.sessions.read().await;
         let sessions_data: HashMap<String, serde_json::Value> = sessions
             .iter()
-            .map(|(id, session)| {
-                let session_data = serde_json::json!({
-                    "metadata": session.metadata,
-                    "thoughts": session.get_thoughts(),
-                    "stats": session.get_stats()
-                });
-                (id.clone(), session_data)
-            })
+            .map(|(id, session)| (id.clone(), Self::session_to_json(session)))
             .collect();
 
         let content = serde_json::to_string_pretty(&sessions_data)?;
-
-        // Ensure directory exists
-        std::fs::create_dir_all(&self.config.persistence_dir)?;
-
-        let file_path = format!("{}/sessions.json", self.config.persistence_dir);
-        std::fs::write(file_path, content)?;
-
-        Ok(())
+        let file_path = std::path::Path::new(&self.config.persistence_dir).join("sessions.json");
+        self.write_persisted(&file_path, &content).await
     }
 
     /// Load sessions from disk
-    pub async fn load_sessions(&self) -> Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// Failed reads are retried under the store retry policy; a file that
+    /// cannot be parsed is reported as corrupted storage. A session that
+    /// cannot be restored is skipped with a warning and copied to
+    /// `corrupted/<id>.json`, so the others still load and the next save
+    /// does not lose it.
+    pub async fn load_sessions(&self) -> SequentialThinkingResult<()> {
         if !self.config.persist_sessions {
             return Ok(());
         }
 
-        let file_path = format!("{}/sessions.json", self.config.persistence_dir);
-        if !std::path::Path::new(&file_path).exists() {
+        let file_path = std::path::Path::new(&self.config.persistence_dir).join("sessions.json");
+        let content = self
+            .with_store_retry("Reading persisted sessions", || async {
+                match std::fs::read_to_string(&file_path) {
+                    Ok(content) => Ok(Some(content)),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                    Err(e) => Err(storage_io_error(
+                        &format!("Failed to read {}", file_path.display()),
+                        e,
+                    )),
+                }
+            })
+            .await?;
+        let Some(content) = content else {
             return Ok(());
-        }
-
-        let content = std::fs::read_to_string(file_path)?;
-        let sessions_data: HashMap<String, serde_json::Value> = serde_json::from_str(&content)?;
+        };
+        let corrupted = |e: String| {
+            SequentialThinkingError::storage_corrupted(format!("{}: {e}", file_path.display()))
+        };
+        let sessions_data: HashMap<String, serde_json::Value> =
+            serde_json::from_str(&content).map_err(|e| corrupted(e.to_string()))?;
 
-        let mut sessions = self.sessions.write().await;
+        let mut restored = HashMap::new();
         for (id, session_data) in sessions_data {
-            // Reconstruct session from persisted data
-            // This is a simplified implementation
-            let metadata: SessionMetadata = serde_json::from_value(
-                session_data
-                    .get("metadata")
-                    .unwrap_or(&serde_json::Value::Null)
-                    .clone(),
-            )?;
-
-            let session = ThinkingSession::with_metadata(id.clone(), metadata);
-            sessions.insert(id, session);
+            match self.restore_session(&id, &session_data).await {
+                Ok(session) => {
+                    restored.insert(id, session);
+                }
+                Err(e) => {
+                    tracing::warn!("Skipping persisted session {}: {}", id, e);
+                    let path = std::path::Path::new(&self.config.persistence_dir)
+                        .join("corrupted")
+                        .join(format!("{id}.json"));
+                    let content = serde_json::to_string_pretty(&session_data)?;
+                    if let Err(e) = self.write_persisted(&path, &content).await {
+                        tracing::warn!("Failed to keep a copy of session {}: {}", id, e);
+                    }
+                }
+            }
         }
+        self.sessions.write().await.extend(restored);
 
         Ok(())
     }
+
+    /// Rebuild a session from its persisted layout
+    ///
+    /// The engine state is restored from the saved checkpoint as it was.
+    /// Files saved before checkpoints were persisted only hold thoughts,
+    /// which are processed again.
+    async fn restore_session(
+        &self,
+        id: &str,
+        session_data: &serde_json::Value,
+    ) -> Result<ThinkingSession, String> {
+        let metadata: SessionMetadata = serde_json::from_value(
+            session_data
+                .get("metadata")
+                .cloned()
+                .unwrap_or(serde_json::Value::Null),
+        )
+        .map_err(|e| e.to_string())?;
+
+        let mut session = ThinkingSession::with_metadata(id.to_string(), metadata);
+        session.engine = ThinkingEngine::with_logging(true);
+        session.engine.set_clock(self.clock.clone());
+        session.engine.start_session(id.to_string());
+
+        if let Some(checkpoint) = session_data.get("checkpoint") {
+            let checkpoint: EngineCheckpoint =
+                serde_json::from_value(checkpoint.clone()).map_err(|e| e.to_string())?;
+            session.engine.resume_from(checkpoint)?;
+        } else {
+            if let Some(thoughts) = session_data.get("thoughts") {
+                let thoughts: Vec<ThoughtData> =
+                    serde_json::from_value(thoughts.clone()).map_err(|e| e.to_string())?;
+                for thought in thoughts {
+                    session
+                        .engine
+                        .process_thought(thought)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+            if let Some(annotations) = session_data.get("annotations") {
+                session.engine.restore_annotations(
+                    serde_json::from_value(annotations.clone()).map_err(|e| e.to_string())?,
+                );
+            }
+        }
+        if let Some(version) = session_data.get("version").and_then(|v| v.as_u64()) {
+            session.version = version;
+        }
+        Ok(session)
+    }
+
+    /// Serialize a session in the persisted layout
+    fn session_to_json(session: &ThinkingSession) -> serde_json::Value {
+        serde_json::json!({
+            "metadata": session.metadata,
+            "checkpoint": session.engine.checkpoint(),
+            "version": session.version
+        })
+    }
 }
 
 impl Default for SessionManager {
@@ -503,6 +1340,30 @@ impl Default for SessionManager {
     }
 }
 
+/// Utility functions for session management
+pub mod utils {
+    /// Parse a human-friendly age such as `30d`, `12h`, `45m`, `2w` or `90s`
+    pub fn parse_age(input: &str) -> Result<chrono::Duration, String> {
+        let input = input.trim();
+        let split = input
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(input.len());
+        let (amount, unit) = input.split_at(split);
+        let amount: i64 = amount
+            .parse()
+            .map_err(|_| format!("Invalid age '{input}': expected a number and unit"))?;
+
+        match unit {
+            "s" => Ok(chrono::Duration::seconds(amount)),
+            "m" => Ok(chrono::Duration::minutes(amount)),
+            "h" => Ok(chrono::Duration::hours(amount)),
+            "d" | "" => Ok(chrono::Duration::days(amount)),
+            "w" => Ok(chrono::Duration::weeks(amount)),
+            _ => Err(format!("Invalid age unit '{unit}': use s, m, h, d or w")),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -534,6 +1395,46 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_edit_thought_audit_log() {
+        let mut session = ThinkingSession::new("edits".to_string(), "Edits".to_string());
+        session
+            .engine
+            .process_thought(ThoughtData::new("Teh first step".to_string(), 1, 2))
+            .await
+            .unwrap();
+
+        let edit = session
+            .edit_thought(1, "The first step".to_string(), Some("alice".to_string()))
+            .unwrap();
+        assert_eq!(edit.old_text, "Teh first step");
+        assert_eq!(session.get_thoughts()[0].thought, "The first step");
+
+        // Edits are not revisions
+        assert_eq!(session.get_stats().total_revisions, 0);
+        assert_eq!(session.get_thoughts().len(), 1);
+
+        let log = session.edit_log();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].editor.as_deref(), Some("alice"));
+        assert_eq!(log[0].new_text, "The first step");
+
+        assert!(session
+            .edit_thought(5, "Missing".to_string(), None)
+            .is_err());
+        assert!(session.edit_thought(1, "  ".to_string(), None).is_err());
+        assert_eq!(session.edit_log().len(), 1);
+
+        // Stats follow the edited text
+        let longer = "The first step, spelled out";
+        session.edit_thought(1, longer.to_string(), None).unwrap();
+        let stats = session.get_stats();
+        assert_eq!(stats.total_thought_length, longer.len() as u64);
+        assert_eq!(stats.total_thoughts, 1);
+        assert!(session.engine.verify_invariants().is_ok());
+        assert_eq!(session.edit_log().len(), 2);
+    }
+
     #[tokio::test]
     async fn test_session_manager() {
         let manager = SessionManager::new();
@@ -565,7 +1466,7 @@ mod tests {
         // Mark session as expired
         if let Some(mut session) = manager.get_session(&session_id).await {
             session.metadata.expires_at = Some(chrono::Utc::now() - chrono::Duration::hours(1));
-            manager.update_session(&session_id, session).await;
+            manager.update_session(&session_id, session).await.unwrap();
         }
 
         // Cleanup expired sessions
@@ -576,4 +1477,475 @@ mod tests {
         let session = manager.get_session(&session_id).await;
         assert!(session.is_none());
     }
+
+    #[tokio::test]
+    async fn test_session_expiry_with_clock() {
+        use crate::thinking::clock::{Clock, TestClock};
+
+        let clock = TestClock::new(chrono::Utc::now());
+        let manager = SessionManager::new().with_clock(clock.shared());
+        let session_id = manager.create_session("Timed".to_string()).await.unwrap();
+
+        let mut session = manager.get_session(&session_id).await.unwrap();
+        session.metadata.expires_at = Some(clock.now() + chrono::Duration::hours(1));
+        manager.update_session(&session_id, session).await.unwrap();
+
+        clock.advance(chrono::Duration::minutes(59));
+        assert_eq!(manager.cleanup_expired_sessions().await, 0);
+        let session = manager.get_session(&session_id).await.unwrap();
+        assert_eq!(session.age(), chrono::Duration::minutes(59));
+        assert!(session.is_active());
+
+        clock.advance(chrono::Duration::minutes(2));
+        assert!(session.is_expired());
+        assert_eq!(manager.cleanup_expired_sessions().await, 1);
+        assert_eq!(manager.get_stats().await.total_sessions_expired, 1);
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume() {
+        use crate::thinking::clock::TestClock;
+
+        let clock = TestClock::new(chrono::Utc::now());
+        let manager = SessionManager::new().with_clock(clock.shared());
+        let id = manager.create_session("Paused".to_string()).await.unwrap();
+        let step = |number: u32| {
+            let mut thought = ThoughtData::new(format!("Step {number}"), number, 2);
+            thought.timestamp = None;
+            thought
+        };
+        manager.process_thought(&id, step(1)).await.unwrap();
+
+        manager.pause_session(&id).await.unwrap();
+        assert!(manager.pause_session(&id).await.is_err());
+        let session = manager.get_session(&id).await.unwrap();
+        assert_eq!(session.status(), &SessionStatus::Paused);
+        assert!(!session.is_active());
+        let rejected = manager.process_thought(&id, step(2)).await.unwrap_err();
+        assert!(rejected.to_string().contains("Session is paused"));
+
+        clock.advance(chrono::Duration::minutes(10));
+        assert_eq!(
+            manager.resume_session(&id).await.unwrap(),
+            std::time::Duration::from_secs(600)
+        );
+        assert!(manager.resume_session(&id).await.is_err());
+        clock.advance(chrono::Duration::seconds(45));
+        manager.process_thought(&id, step(2)).await.unwrap();
+
+        // Analytics split the session into thinking and paused time
+        let session = manager.get_session(&id).await.unwrap();
+        let analytics = crate::analytics::AnalyticsEngine::new().analyze_session(
+            &id,
+            session.title(),
+            &session.get_thoughts(),
+            &session.get_stats(),
+            &session.get_progress(),
+        );
+        assert_eq!(analytics.basic_metrics.session_duration, 645);
+        assert_eq!(analytics.basic_metrics.paused_duration, 600);
+        assert_eq!(analytics.basic_metrics.active_duration, 45);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = SessionManager::with_config(SessionManagerConfig {
+            persist_sessions: true,
+            persistence_dir: dir.path().to_string_lossy().to_string(),
+            ..Default::default()
+        });
+
+        let old_id = manager.create_session("Old".to_string()).await.unwrap();
+        let cancelled_id = manager
+            .create_session("Cancelled".to_string())
+            .await
+            .unwrap();
+        manager.create_session("Current".to_string()).await.unwrap();
+
+        if let Some(mut session) = manager.get_session(&old_id).await {
+            session.metadata.last_modified = chrono::Utc::now() - chrono::Duration::days(45);
+            manager.update_session(&old_id, session).await.unwrap();
+        }
+        if let Some(mut session) = manager.get_session(&cancelled_id).await {
+            session.set_status(SessionStatus::Cancelled);
+            manager
+                .update_session(&cancelled_id, session)
+                .await
+                .unwrap();
+        }
+
+        let archived = manager
+            .archive_sessions_older_than(utils::parse_age("30d").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(archived, vec![old_id.clone()]);
+        assert!(dir
+            .path()
+            .join("archive")
+            .join(format!("{old_id}.json"))
+            .exists());
+
+        let deleted = manager
+            .delete_sessions_with_status(&SessionStatus::Cancelled)
+            .await;
+        assert_eq!(deleted, vec![cancelled_id]);
+        assert_eq!(manager.list_overviews().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_sessions_filtered() {
+        let manager = SessionManager::new();
+        let start = chrono::Utc::now() - chrono::Duration::days(10);
+        for day in 0..10 {
+            let id = manager
+                .create_session(format!("Session {day}"))
+                .await
+                .unwrap();
+            let mut session = manager.get_session(&id).await.unwrap();
+            session.metadata.created_at = start + chrono::Duration::days(day);
+            if day % 2 == 0 {
+                session.add_tag("even".to_string());
+            }
+            if day == 7 {
+                session.set_status(SessionStatus::Completed);
+                session.set_priority(SessionPriority::High);
+            }
+            manager.update_session(&id, session).await.unwrap();
+        }
+
+        let page = manager
+            .list_sessions(&SessionFilter::new().with_page(2, Some(3)))
+            .await;
+        let titles: Vec<&str> = page.items.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Session 2", "Session 3", "Session 4"]);
+        assert_eq!(page.total, 10);
+        assert!(page.has_more());
+
+        let even = manager
+            .list_sessions(
+                &SessionFilter::new()
+                    .with_tag("even")
+                    .with_created_range(Some(start + chrono::Duration::days(3)), None),
+            )
+            .await;
+        assert_eq!(even.total, 3);
+        assert_eq!(even.items[0].tags, vec!["even".to_string()]);
+        assert!(!even.has_more());
+
+        let filter = SessionFilter::new()
+            .with_status(SessionStatus::Completed)
+            .with_priority(SessionPriority::High)
+            .with_title("SESSION 7");
+        assert_eq!(
+            manager.list_sessions(&filter).await.items[0].title,
+            "Session 7"
+        );
+        assert_eq!(
+            manager
+                .list_sessions(&filter.with_title("Session 8"))
+                .await
+                .total,
+            0
+        );
+        assert_eq!("critical".parse(), Ok(SessionPriority::Critical));
+    }
+
+    #[tokio::test]
+    async fn test_eviction_policies() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SessionManagerConfig {
+            max_sessions: 3,
+            persist_sessions: true,
+            persistence_dir: dir.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let manager = SessionManager::with_config(config.clone());
+        let hour = chrono::Duration::hours(1);
+        let mut ids = Vec::new();
+        for (n, (status, priority)) in [
+            (SessionStatus::Completed, SessionPriority::Normal),
+            (SessionStatus::Active, SessionPriority::Low),
+            (SessionStatus::Completed, SessionPriority::High),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let id = manager.create_session(format!("S{n}")).await.unwrap();
+            let mut session = manager.get_session(&id).await.unwrap();
+            session.metadata.status = status;
+            session.metadata.priority = priority;
+            session.metadata.last_modified = chrono::Utc::now() - hour * (10 - n as i32);
+            manager.update_session(&id, session).await.unwrap();
+            ids.push(id);
+        }
+        assert!(manager
+            .create_session("Rejected".to_string())
+            .await
+            .is_err());
+
+        let manager = SessionManager {
+            config: SessionManagerConfig {
+                eviction_policy: EvictionPolicy::EvictOldestCompleted,
+                ..config.clone()
+            },
+            ..manager
+        };
+        manager.create_session("New".to_string()).await.unwrap();
+        assert!(manager.get_session(&ids[0]).await.is_none());
+        assert!(dir
+            .path()
+            .join("evicted")
+            .join(format!("{}.json", ids[0]))
+            .exists());
+
+        let manager = SessionManager {
+            config: SessionManagerConfig {
+                eviction_policy: "evict-lowest-priority".parse().unwrap(),
+                ..config
+            },
+            ..manager
+        };
+        manager.create_session("Newer".to_string()).await.unwrap();
+        assert!(manager.get_session(&ids[1]).await.is_none());
+        assert_eq!(manager.get_stats().await.total_sessions_evicted, 2);
+
+        // Sessions ranked above a new one are never evicted
+        for mut session in manager.list_active_sessions().await {
+            session.metadata.priority = SessionPriority::Critical;
+            manager
+                .update_session(&session.session_id.clone(), session)
+                .await
+                .unwrap();
+        }
+        assert!(manager.create_session("Blocked".to_string()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_from_template() {
+        let manager = SessionManager::new();
+        let template = SessionTemplate::new("review".to_string(), 4)
+            .with_seed_thought("Summarize the change under review".to_string())
+            .with_tag("code-review".to_string());
+
+        let session_id = manager.create_from_template(&template, None).await.unwrap();
+        let session = manager.get_session(&session_id).await.unwrap();
+
+        assert_eq!(session.title(), "review");
+        assert_eq!(session.get_thoughts().len(), 1);
+        assert_eq!(session.get_progress().total_thoughts, 4);
+        assert!(session.metadata.tags.contains(&"code-review".to_string()));
+        assert_eq!(
+            session.get_custom_data("template"),
+            Some(&serde_json::json!("review"))
+        );
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(utils::parse_age("30d").unwrap(), chrono::Duration::days(30));
+        assert_eq!(
+            utils::parse_age("12h").unwrap(),
+            chrono::Duration::hours(12)
+        );
+        assert!(utils::parse_age("soon").is_err());
+        assert!(utils::parse_age("3y").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stale_updates_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SessionManagerConfig {
+            persist_sessions: true,
+            persistence_dir: dir.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let manager = SessionManager::with_config(config.clone());
+        let id = manager.create_session("Shared".to_string()).await.unwrap();
+
+        let mut first = manager.get_session(&id).await.unwrap();
+        let mut second = first.clone();
+        assert_eq!(first.version(), 1);
+        first.add_tag("first".to_string());
+        assert_eq!(manager.update_session(&id, first).await.unwrap(), 2);
+        second.add_tag("second".to_string());
+        let stale = manager.update_session(&id, second).await.unwrap_err();
+        assert!(matches!(
+            stale,
+            SequentialThinkingError::StorageConflict { .. }
+        ));
+
+        // Processing a thought changes the session too
+        let before = manager.get_session(&id).await.unwrap();
+        manager
+            .process_thought(&id, ThoughtData::new("Start".to_string(), 1, 2))
+            .await
+            .unwrap();
+        assert!(manager.update_session(&id, before).await.is_err());
+
+        // Modifications are applied to the current session
+        let modified = manager
+            .modify_session(&id, |session| session.add_tag("second".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(modified.version(), 4);
+        assert_eq!(modified.metadata.tags, ["first", "second"]);
+        assert!(matches!(
+            manager.update_session("missing", modified).await,
+            Err(SequentialThinkingError::NotFound { .. })
+        ));
+
+        manager.persist_sessions().await.unwrap();
+        let restarted = SessionManager::with_config(config);
+        restarted.load_sessions().await.unwrap();
+        assert_eq!(restarted.get_session(&id).await.unwrap().version(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_bad_persisted_sessions_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SessionManagerConfig {
+            persist_sessions: true,
+            persistence_dir: dir.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let manager = SessionManager::with_config(config.clone());
+        let id = manager.create_session("Good".to_string()).await.unwrap();
+        manager
+            .process_thought(&id, ThoughtData::new("Keep me".to_string(), 1, 2))
+            .await
+            .unwrap();
+        manager
+            .modify_session(&id, |session| {
+                session
+                    .edit_thought(1, "Keep me, edited".to_string(), None)
+                    .unwrap();
+            })
+            .await
+            .unwrap();
+        manager.persist_sessions().await.unwrap();
+        let saved = manager.get_session(&id).await.unwrap();
+
+        // Add a session whose thought cannot be processed, and one saved
+        // before checkpoints were persisted
+        let path = dir.path().join("sessions.json");
+        let mut file: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let mut bad = ThoughtData::new("Broken".to_string(), 1, 2);
+        bad.thought_number = 0;
+        file["bad"] = serde_json::json!({ "metadata": saved.metadata, "thoughts": [bad] });
+        file["legacy"] = serde_json::json!({
+            "metadata": saved.metadata,
+            "thoughts": [ThoughtData::new("Old layout".to_string(), 1, 1)]
+        });
+        std::fs::write(&path, file.to_string()).unwrap();
+
+        let restarted = SessionManager::with_config(config);
+        restarted.load_sessions().await.unwrap();
+        let loaded = restarted.get_session(&id).await.unwrap();
+        assert_eq!(
+            loaded.get_thoughts()[0].timestamp,
+            saved.get_thoughts()[0].timestamp
+        );
+        assert_eq!(
+            serde_json::to_value(loaded.get_stats()).unwrap(),
+            serde_json::to_value(saved.get_stats()).unwrap()
+        );
+        // The edit log travels with the checkpoint
+        assert_eq!(loaded.edit_log().len(), 1);
+        assert_eq!(
+            restarted
+                .get_session("legacy")
+                .await
+                .unwrap()
+                .get_thoughts()
+                .len(),
+            1
+        );
+        assert!(restarted.get_session("bad").await.is_none());
+        assert!(dir.path().join("corrupted").join("bad.json").exists());
+    }
+
+    /// Lease store whose first operations fail as if it were unreachable
+    #[derive(Debug)]
+    struct FlakyLeaseStore {
+        failures: u32,
+        inner: MemoryLeaseStore,
+    }
+
+    impl FlakyLeaseStore {
+        fn check(&mut self) -> SequentialThinkingResult<()> {
+            if self.failures == 0 {
+                return Ok(());
+            }
+            self.failures -= 1;
+            Err(SequentialThinkingError::storage_unavailable(
+                "lease store down",
+            ))
+        }
+    }
+
+    impl LeaseStore for FlakyLeaseStore {
+        fn acquire(
+            &mut self,
+            session_id: &str,
+            holder: &str,
+            ttl: chrono::Duration,
+        ) -> SequentialThinkingResult<SessionLease> {
+            self.check()?;
+            self.inner.acquire(session_id, holder, ttl)
+        }
+
+        fn renew(
+            &mut self,
+            lease: &SessionLease,
+            ttl: chrono::Duration,
+        ) -> SequentialThinkingResult<SessionLease> {
+            self.check()?;
+            self.inner.renew(lease, ttl)
+        }
+
+        fn release(&mut self, lease: &SessionLease) -> SequentialThinkingResult<()> {
+            self.check()?;
+            self.inner.release(lease)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_failures_are_retried() {
+        let store = Arc::new(std::sync::Mutex::new(FlakyLeaseStore {
+            failures: 2,
+            inner: MemoryLeaseStore::new(),
+        }));
+        let manager = SessionManager::with_config(SessionManagerConfig {
+            store_retry: RetryPolicy {
+                max_retries: 2,
+                initial_backoff: std::time::Duration::from_millis(1),
+                max_backoff: std::time::Duration::from_millis(1),
+            },
+            ..Default::default()
+        })
+        .with_lease_store(store.clone());
+        let minute = chrono::Duration::minutes(1);
+
+        let lease = manager
+            .acquire_lease("s1", "replica-a", minute)
+            .await
+            .unwrap();
+        assert_eq!(manager.get_stats().await.store_retries, 2);
+
+        // Conflicts are not retried
+        let locked = manager
+            .acquire_lease("s1", "replica-b", minute)
+            .await
+            .unwrap_err();
+        assert_eq!(locked.error_code(), "STORAGE_CONFLICT");
+        assert_eq!(manager.get_stats().await.store_retries, 2);
+
+        // Failures outlasting the retries are returned
+        store.lock().unwrap().failures = 3;
+        let down = manager.release_lease(&lease).await.unwrap_err();
+        assert!(down.is_retryable());
+        assert_eq!(manager.get_stats().await.store_retries, 4);
+    }
 }
@@ -0,0 +1,128 @@
+//! # Store Retries
+//!
+//! Retrying session store operations that fail transiently.
+//!
+//! A [`RetryPolicy`] runs a store operation again while it fails with a
+//! [retryable](crate::thinking::error::SequentialThinkingError::is_retryable)
+//! error, such as an unavailable store, doubling the wait between attempts
+//! up to a limit. Conflicts and corrupted data are returned at once, since
+//! running the same operation again cannot fix them.
+
+use std::future::Future;
+use std::time::Duration;
+
+use crate::thinking::error::SequentialThinkingResult;
+
+/// How store operations are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for each retry after it
+    pub initial_backoff: Duration,
+    /// Longest wait between attempts
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy running every operation once
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Wait before the given retry, counting from zero
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+
+    /// Run `operation` until it succeeds, fails for good or runs out of retries
+    ///
+    /// Returns the last result and the number of retries made.
+    pub async fn run<T, F, Fut>(
+        &self,
+        description: &str,
+        mut operation: F,
+    ) -> (SequentialThinkingResult<T>, u32)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = SequentialThinkingResult<T>>,
+    {
+        let mut retries = 0;
+        loop {
+            match operation().await {
+                Err(e) if e.is_retryable() && retries < self.max_retries => {
+                    tracing::debug!("{} failed, retrying: {}", description, e);
+                    tokio::time::sleep(self.backoff(retries)).await;
+                    retries += 1;
+                }
+                result => return (result, retries),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::error::SequentialThinkingError;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_retry_policy() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(3),
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(1));
+        assert_eq!(policy.backoff(1), Duration::from_millis(2));
+        assert_eq!(policy.backoff(5), Duration::from_millis(3));
+
+        // Transient failures are retried until the operation succeeds
+        let attempts = AtomicU32::new(0);
+        let (result, retries) = policy
+            .run("write", || async {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err(SequentialThinkingError::storage_unavailable("busy")),
+                    _ => Ok("written"),
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), "written");
+        assert_eq!(retries, 2);
+
+        // Conflicts are returned at once, and retries run out
+        let (result, retries) = policy
+            .run("write", || async {
+                Err::<(), _>(SequentialThinkingError::storage_conflict("stale"))
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(SequentialThinkingError::StorageConflict { .. })
+        ));
+        assert_eq!(retries, 0);
+        let (result, retries) = policy
+            .run("write", || async {
+                Err::<(), _>(SequentialThinkingError::storage_unavailable("down"))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(retries, 3);
+    }
+}
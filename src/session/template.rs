@@ -0,0 +1,177 @@
+//! # Session Templates
+//!
+//! Reusable blueprints for starting thinking sessions.
+//!
+//! A template fixes the expected number of thoughts, seeds the first
+//! thoughts of the session, and attaches required tags and metadata. Templates
+//! are stored as TOML or JSON files in a templates directory.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::thinking::ThoughtData;
+
+/// Default directory searched for session templates
+pub const DEFAULT_TEMPLATES_DIR: &str = "./templates/sessions";
+
+/// Blueprint for a new thinking session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTemplate {
+    /// Template name
+    pub name: String,
+    /// Template description
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Default total thoughts for sessions created from this template
+    pub total_thoughts: u32,
+    /// Thoughts seeded at the start of the session
+    #[serde(default)]
+    pub seed_thoughts: Vec<String>,
+    /// Tags applied to every session created from this template
+    #[serde(default)]
+    pub required_tags: Vec<String>,
+    /// Custom metadata applied to the session
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+impl SessionTemplate {
+    /// Create a new template
+    pub fn new(name: String, total_thoughts: u32) -> Self {
+        Self {
+            name,
+            description: None,
+            total_thoughts,
+            seed_thoughts: Vec::new(),
+            required_tags: Vec::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Add a seeded thought
+    pub fn with_seed_thought(mut self, thought: String) -> Self {
+        self.seed_thoughts.push(thought);
+        self
+    }
+
+    /// Add a required tag
+    pub fn with_tag(mut self, tag: String) -> Self {
+        self.required_tags.push(tag);
+        self
+    }
+
+    /// Load a template from a TOML or JSON file
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+
+        let template: Self = match path.extension().and_then(|s| s.to_str()) {
+            Some("toml") => toml::from_str(&content)?,
+            Some("json") => serde_json::from_str(&content)?,
+            _ => return Err("Unsupported template file format".into()),
+        };
+
+        template.validate()?;
+        Ok(template)
+    }
+
+    /// Load every template in a directory, keyed by template name
+    pub fn load_dir<P: AsRef<Path>>(
+        dir: P,
+    ) -> Result<HashMap<String, Self>, Box<dyn std::error::Error>> {
+        let mut templates = HashMap::new();
+
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let supported = matches!(
+                path.extension().and_then(|s| s.to_str()),
+                Some("toml") | Some("json")
+            );
+            if path.is_file() && supported {
+                let template = Self::load_from_file(&path)?;
+                templates.insert(template.name.clone(), template);
+            }
+        }
+
+        Ok(templates)
+    }
+
+    /// Resolve a template by file path or by name within a templates directory
+    pub fn resolve<P: AsRef<Path>>(
+        name_or_path: &str,
+        dir: P,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = Path::new(name_or_path);
+        if path.is_file() {
+            return Self::load_from_file(path);
+        }
+
+        Self::load_dir(dir.as_ref())?
+            .remove(name_or_path)
+            .ok_or_else(|| format!("Session template not found: {name_or_path}").into())
+    }
+
+    /// Validate the template
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Template name cannot be empty".to_string());
+        }
+        if self.total_thoughts == 0 {
+            return Err("Template total thoughts must be greater than 0".to_string());
+        }
+        if self.seed_thoughts.iter().any(|t| t.trim().is_empty()) {
+            return Err("Seed thoughts cannot be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// Build the seeded thoughts for a new session
+    pub fn seed_thought_data(&self) -> Vec<ThoughtData> {
+        let total = self.total_thoughts.max(self.seed_thoughts.len() as u32);
+        self.seed_thoughts
+            .iter()
+            .enumerate()
+            .map(|(i, thought)| {
+                ThoughtData::new(thought.clone(), i as u32 + 1, total).with_metadata(
+                    "template".to_string(),
+                    serde_json::Value::String(self.name.clone()),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_loading() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("root-cause.toml"),
+            r#"
+name = "root-cause"
+total_thoughts = 6
+seed_thoughts = ["Restate the incident", "List the symptoms"]
+required_tags = ["incident"]
+
+[metadata]
+team = "sre"
+"#,
+        )
+        .unwrap();
+
+        let template = SessionTemplate::resolve("root-cause", dir.path()).unwrap();
+        assert_eq!(template.total_thoughts, 6);
+        assert_eq!(template.required_tags, vec!["incident".to_string()]);
+
+        let seeds = template.seed_thought_data();
+        assert_eq!(seeds.len(), 2);
+        assert_eq!(seeds[1].thought_number, 2);
+        assert_eq!(seeds[1].total_thoughts, 6);
+
+        assert!(SessionTemplate::resolve("missing", dir.path()).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+//! # Test Utilities
+//!
+//! A scriptable server for testing code built on the client.
+//!
+//! [`MockSequentialThinkingServer`] is a [`Transport`] wrapping a real
+//! [`SequentialThinkingServer`]. Calls to a tool can be answered with
+//! scripted results, failed with forced errors or delayed by a fixed
+//! latency; anything not scripted reaches the real server. Scripts are
+//! consumed in order, so agents can be run through failure scenarios
+//! deterministically. Enabled with the `test_utils` feature.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ultrafast_mcp::{Tool, ToolCall, ToolContent, ToolResult};
+
+use crate::thinking::client::{ClientThinkingConfig, SequentialThinkingClient};
+use crate::thinking::error::{SequentialThinkingError, SequentialThinkingResult};
+use crate::thinking::server::SequentialThinkingServer;
+use crate::thinking::transport::{InMemoryTransport, Transport};
+
+/// How the mock answers one tool call
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+    /// Answer with this result
+    Result(ToolResult),
+    /// Fail with this message, as a server error would
+    Error(String),
+    /// Let the real server answer
+    Forward,
+}
+
+impl MockResponse {
+    /// Answer with a JSON document, the way most tools do
+    pub fn json(value: &serde_json::Value) -> Self {
+        Self::text(value.to_string())
+    }
+
+    /// Answer with plain text
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Result(ToolResult {
+            content: vec![ToolContent::text(text.into())],
+            is_error: Some(false),
+        })
+    }
+
+    /// Fail with an error
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::Error(message.into())
+    }
+}
+
+/// Scripted responses of every tool
+#[derive(Debug, Default)]
+struct MockScript {
+    /// Responses to the next calls, by tool
+    queued: HashMap<String, VecDeque<MockResponse>>,
+    /// Responses once a tool's queue is empty
+    fallback: HashMap<String, MockResponse>,
+    /// Delay before answering, by tool
+    latency: HashMap<String, Duration>,
+}
+
+/// Sequential thinking server with scripted responses, latencies and errors
+///
+/// Clones share their script and call log, so a test can keep one clone
+/// while a client uses another.
+#[derive(Clone)]
+pub struct MockSequentialThinkingServer {
+    server: Arc<InMemoryTransport>,
+    script: Arc<Mutex<MockScript>>,
+    calls: Arc<Mutex<Vec<ToolCall>>>,
+}
+
+impl Default for MockSequentialThinkingServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockSequentialThinkingServer {
+    /// Create a mock forwarding unscripted calls to a default server
+    pub fn new() -> Self {
+        Self::with_server(SequentialThinkingServer::new())
+    }
+
+    /// Create a mock forwarding unscripted calls to `server`
+    pub fn with_server(server: SequentialThinkingServer) -> Self {
+        Self {
+            server: Arc::new(InMemoryTransport::new(server)),
+            script: Arc::new(Mutex::new(MockScript::default())),
+            calls: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Answer the next unanswered call to `tool` with `response`
+    pub fn with_response(self, tool: &str, response: MockResponse) -> Self {
+        self.script()
+            .queued
+            .entry(tool.to_string())
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// Fail the next `count` calls to `tool`
+    pub fn with_failures(self, tool: &str, count: usize, message: &str) -> Self {
+        (0..count).fold(self, |mock, _| {
+            mock.with_response(tool, MockResponse::error(message))
+        })
+    }
+
+    /// Answer calls to `tool` with `response` once its queue is empty
+    pub fn with_fallback(self, tool: &str, response: MockResponse) -> Self {
+        self.script().fallback.insert(tool.to_string(), response);
+        self
+    }
+
+    /// Delay every answer to `tool` by `latency`
+    pub fn with_latency(self, tool: &str, latency: Duration) -> Self {
+        self.script().latency.insert(tool.to_string(), latency);
+        self
+    }
+
+    /// Create a client connected to this mock
+    pub async fn client(
+        &self,
+        config: ClientThinkingConfig,
+    ) -> SequentialThinkingResult<SequentialThinkingClient> {
+        SequentialThinkingClient::with_transport(Arc::new(self.clone()), config).await
+    }
+
+    /// Get every tool call received, oldest first
+    pub fn calls(&self) -> Vec<ToolCall> {
+        self.calls.lock().expect("call log poisoned").clone()
+    }
+
+    /// Count the calls received for `tool`
+    pub fn call_count(&self, tool: &str) -> usize {
+        self.calls
+            .lock()
+            .expect("call log poisoned")
+            .iter()
+            .filter(|call| call.name == tool)
+            .count()
+    }
+
+    fn script(&self) -> std::sync::MutexGuard<'_, MockScript> {
+        self.script.lock().expect("mock script poisoned")
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for MockSequentialThinkingServer {
+    async fn connect(&self) -> SequentialThinkingResult<()> {
+        self.server.connect().await
+    }
+
+    async fn call_tool(&self, call: ToolCall) -> SequentialThinkingResult<ToolResult> {
+        self.calls
+            .lock()
+            .expect("call log poisoned")
+            .push(call.clone());
+        let (latency, response) = {
+            let mut script = self.script();
+            let response = script
+                .queued
+                .get_mut(&call.name)
+                .and_then(VecDeque::pop_front)
+                .or_else(|| script.fallback.get(&call.name).cloned())
+                .unwrap_or(MockResponse::Forward);
+            (script.latency.get(&call.name).copied(), response)
+        };
+
+        if let Some(latency) = latency {
+            tokio::time::sleep(latency).await;
+        }
+        match response {
+            MockResponse::Result(result) => Ok(result),
+            MockResponse::Error(message) => Err(SequentialThinkingError::transport_error(message)),
+            MockResponse::Forward => self.server.call_tool(call).await,
+        }
+    }
+
+    async fn list_tools(&self) -> SequentialThinkingResult<Vec<Tool>> {
+        self.server.list_tools().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::ThoughtData;
+
+    #[tokio::test]
+    async fn test_mock_server() {
+        let mock = MockSequentialThinkingServer::new()
+            .with_failures("sequential_thinking", 1, "server overloaded")
+            .with_response(
+                "analyze_session",
+                MockResponse::json(&serde_json::json!({ "quality": 0.9 })),
+            )
+            .with_fallback("analyze_session", MockResponse::error("analysis disabled"))
+            .with_latency("export_session", Duration::from_millis(200));
+        let client = mock
+            .client(ClientThinkingConfig {
+                max_retry_attempts: 2,
+                auto_save_interval: 0,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        // The forced error is retried, then the real server answers
+        let session = client.start_session("Mocked".to_string()).await.unwrap();
+        session
+            .add_thought(ThoughtData::new("Plan the work".to_string(), 1, 2), None)
+            .await
+            .unwrap();
+        assert_eq!(session.get_stats().await.retry_count, 1);
+        assert_eq!(mock.call_count("sequential_thinking"), 2);
+
+        let analysis = client.analyze_session(session.id(), None).await.unwrap();
+        assert_eq!(analysis["quality"], 0.9);
+        assert!(client.analyze_session(session.id(), None).await.is_err());
+
+        let slow = client
+            .export_session(session.id(), "json", Some(Duration::from_millis(50)))
+            .await;
+        let slow = slow.unwrap_err();
+        assert!(matches!(
+            slow.root_cause(),
+            SequentialThinkingError::Timeout { .. }
+        ));
+        assert_eq!(slow.tool_call().unwrap().0, "export_session");
+        // The session was created on the server before its first thought
+        assert_eq!(mock.call_count("create_session"), 1);
+        assert_eq!(mock.calls().len(), 6);
+    }
+}
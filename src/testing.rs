@@ -0,0 +1,359 @@
+//! # Testing Support
+//!
+//! Random session generators for benchmarks, export fuzzing and load tests.
+//!
+//! A [`SessionGenerator`] produces realistic thinking sessions: a varying
+//! number of thoughts with revisions, branches, dependencies, uneven lengths
+//! and spaced-out timestamps. Output is deterministic for a given seed, so a
+//! failing fuzz case or a benchmark run can be reproduced exactly. Enabled
+//! with the `testing` feature.
+
+use chrono::{DateTime, Duration, Utc};
+use std::ops::RangeInclusive;
+use ultrafast_mcp::ToolCall;
+
+use crate::thinking::{ThinkingEngine, ThoughtData};
+
+/// Words thought text is drawn from
+const VOCABULARY: &[&str] = &[
+    "analyze",
+    "assume",
+    "because",
+    "branch",
+    "check",
+    "compare",
+    "constraint",
+    "data",
+    "derive",
+    "estimate",
+    "evidence",
+    "explore",
+    "however",
+    "hypothesis",
+    "input",
+    "measure",
+    "model",
+    "option",
+    "output",
+    "problem",
+    "refine",
+    "result",
+    "revise",
+    "risk",
+    "step",
+    "test",
+    "therefore",
+    "tradeoff",
+    "verify",
+    "why",
+];
+
+/// Words that need escaping in at least one export format
+const SPECIAL_WORDS: &[&str] = &[
+    "\"quoted\"",
+    "comma,separated",
+    "<tag>",
+    "a&b",
+    "pipe|cell",
+    "# heading",
+    "*emphasis*",
+    "`code`",
+    "line\nbreak",
+    "tab\there",
+    "naïve",
+    "日本語",
+    "🚀",
+    "back\\slash",
+];
+
+/// Authors generated thoughts are attributed to
+const AUTHORS: &[&str] = &["planner", "researcher", "critic"];
+
+/// Generator of random thinking sessions
+#[derive(Debug, Clone)]
+pub struct SessionGenerator {
+    /// Number of thoughts per session
+    pub thought_count: RangeInclusive<u32>,
+    /// Probability that a thought revises an earlier one
+    pub revision_rate: f64,
+    /// Probability that a thought continues a branch
+    pub branch_rate: f64,
+    /// Probability that a thought depends on earlier thoughts
+    pub dependency_rate: f64,
+    /// Number of distinct branches per session
+    pub max_branches: u32,
+    /// Words per thought
+    pub words_per_thought: RangeInclusive<usize>,
+    /// Probability that a word needs escaping in exports
+    pub special_word_rate: f64,
+    /// Probability that a thought is attributed to an author
+    pub author_rate: f64,
+    /// Seconds between consecutive thoughts
+    pub interval_secs: RangeInclusive<i64>,
+    /// Timestamp of the first thought of the first session
+    pub start: DateTime<Utc>,
+    /// Seed of the random sequence
+    pub seed: u64,
+    rng: Lcg,
+    generated: u64,
+}
+
+/// One generated session
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedSession {
+    /// Session ID
+    pub session_id: String,
+    /// Session title
+    pub title: String,
+    /// Thoughts in submission order
+    pub thoughts: Vec<ThoughtData>,
+}
+
+impl Default for SessionGenerator {
+    fn default() -> Self {
+        Self::new(42)
+    }
+}
+
+impl SessionGenerator {
+    /// Create a generator with the given seed and default rates
+    pub fn new(seed: u64) -> Self {
+        Self {
+            thought_count: 5..=30,
+            revision_rate: 0.1,
+            branch_rate: 0.15,
+            dependency_rate: 0.3,
+            max_branches: 3,
+            words_per_thought: 10..=80,
+            special_word_rate: 0.0,
+            author_rate: 0.0,
+            interval_secs: 1..=30,
+            start: DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap_or_default(),
+            seed,
+            rng: Lcg(seed),
+            generated: 0,
+        }
+    }
+
+    /// Set the number of thoughts per session
+    pub fn with_thoughts(mut self, thought_count: RangeInclusive<u32>) -> Self {
+        self.thought_count = thought_count;
+        self
+    }
+
+    /// Set the revision and branch rates
+    pub fn with_rates(mut self, revision_rate: f64, branch_rate: f64) -> Self {
+        self.revision_rate = revision_rate;
+        self.branch_rate = branch_rate;
+        self
+    }
+
+    /// Set the dependency rate
+    pub fn with_dependency_rate(mut self, dependency_rate: f64) -> Self {
+        self.dependency_rate = dependency_rate;
+        self
+    }
+
+    /// Set the number of words per thought
+    pub fn with_words(mut self, words_per_thought: RangeInclusive<usize>) -> Self {
+        self.words_per_thought = words_per_thought;
+        self
+    }
+
+    /// Mix in words that need escaping, for fuzzing exports
+    pub fn with_special_words(mut self, special_word_rate: f64) -> Self {
+        self.special_word_rate = special_word_rate;
+        self
+    }
+
+    /// Attribute thoughts to authors at the given rate
+    pub fn with_authors(mut self, author_rate: f64) -> Self {
+        self.author_rate = author_rate;
+        self
+    }
+
+    /// Set the time between thoughts and the first timestamp
+    pub fn with_timing(mut self, start: DateTime<Utc>, interval_secs: RangeInclusive<i64>) -> Self {
+        self.start = start;
+        self.interval_secs = interval_secs;
+        self
+    }
+
+    /// Generate the next session
+    pub fn next_session(&mut self) -> GeneratedSession {
+        self.generated += 1;
+        let count = self.range(&self.thought_count.clone()).max(1);
+        let mut at = self.start;
+        let mut thoughts = Vec::with_capacity(count as usize);
+
+        for n in 1..=count {
+            let text = self.text();
+            let earlier = 1 + self.rng.below(u64::from(n - 1).max(1)) as u32;
+            let mut thought = if n > 1 && self.chance(self.revision_rate) {
+                ThoughtData::revision(text, n, earlier)
+            } else if n > 1 && self.chance(self.branch_rate) {
+                let branch = self.rng.below(u64::from(self.max_branches.max(1)));
+                ThoughtData::branch(text, n, earlier, format!("branch-{branch}"))
+            } else {
+                ThoughtData::new(text, n, count)
+            };
+            if n > 2 && self.chance(self.dependency_rate) {
+                let other = 1 + self.rng.below(u64::from(n - 1)) as u32;
+                let mut depends_on = vec![n - 1, other];
+                depends_on.sort_unstable();
+                depends_on.dedup();
+                thought = thought.with_dependencies(depends_on);
+            }
+            if self.chance(self.author_rate) {
+                let author = AUTHORS[self.rng.below(AUTHORS.len() as u64) as usize];
+                thought = thought.with_author(author.to_string());
+            }
+
+            thought.total_thoughts = count;
+            thought.next_thought_needed = n < count;
+            thought.timestamp = Some(at);
+            at += Duration::seconds(self.range(&self.interval_secs.clone()).max(0));
+            thoughts.push(thought);
+        }
+        // Later sessions start where this one ended
+        self.start = at;
+
+        GeneratedSession {
+            session_id: format!("generated-{}-{}", self.seed, self.generated),
+            title: format!("Generated session {}", self.generated),
+            thoughts,
+        }
+    }
+
+    /// Generate a number of sessions
+    pub fn sessions(&mut self, count: usize) -> Vec<GeneratedSession> {
+        (0..count).map(|_| self.next_session()).collect()
+    }
+
+    /// Random thought text
+    fn text(&mut self) -> String {
+        let words = self.range(&self.words_per_thought.clone()).max(1);
+        (0..words)
+            .map(|_| {
+                let pool = if self.chance(self.special_word_rate) {
+                    SPECIAL_WORDS
+                } else {
+                    VOCABULARY
+                };
+                pool[self.rng.below(pool.len() as u64) as usize]
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Whether an event with the given probability happens
+    fn chance(&mut self, probability: f64) -> bool {
+        probability > 0.0 && (self.rng.below(1_000_000) as f64) < probability * 1_000_000.0
+    }
+
+    /// Random value in a range
+    fn range<T>(&mut self, range: &RangeInclusive<T>) -> T
+    where
+        T: Copy + TryFrom<u64> + TryInto<u64> + Default,
+    {
+        let (start, end) = (
+            (*range.start()).try_into().unwrap_or(0),
+            (*range.end()).try_into().unwrap_or(0),
+        );
+        if end <= start {
+            return *range.start();
+        }
+        T::try_from(start + self.rng.below(end - start + 1)).unwrap_or_default()
+    }
+}
+
+impl GeneratedSession {
+    /// Process the session into a fresh engine with logging disabled
+    pub async fn engine(&self) -> Result<ThinkingEngine, String> {
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.start_session(self.session_id.clone());
+        for thought in &self.thoughts {
+            engine.process_thought(thought.clone()).await?;
+        }
+        Ok(engine)
+    }
+
+    /// `sequential_thinking` tool calls submitting the session, for load tests
+    pub fn tool_calls(&self) -> Vec<ToolCall> {
+        self.thoughts
+            .iter()
+            .map(|thought| ToolCall {
+                name: "sequential_thinking".to_string(),
+                arguments: Some(thought.to_tool_arguments()),
+            })
+            .collect()
+    }
+}
+
+/// Minimal linear congruential generator, so the crate needs no `rand`
+#[derive(Debug, Clone)]
+struct Lcg(u64);
+
+impl Lcg {
+    /// Next value in `0..bound`
+    fn below(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 33) % bound.max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_session_generator() {
+        let mut generator = SessionGenerator::new(7)
+            .with_thoughts(50..=50)
+            .with_rates(0.2, 0.2)
+            .with_words(8..=8)
+            .with_authors(0.5);
+        let session = generator.next_session();
+        let thoughts = &session.thoughts;
+        assert_eq!(thoughts.len(), 50);
+        assert!(thoughts.iter().any(|t| t.is_revision()));
+        assert!(thoughts.iter().any(|t| t.is_branch()));
+        assert!(thoughts.iter().any(|t| !t.depends_on.is_empty()));
+        assert!(thoughts.iter().any(|t| t.author.is_some()));
+        assert_eq!(thoughts[0].thought.split(' ').count(), 8);
+        assert!(thoughts.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+        assert!(thoughts.iter().all(|t| t.validate().is_ok()));
+
+        // Same seed, same sessions; successive sessions differ
+        let mut again = SessionGenerator::new(7)
+            .with_thoughts(50..=50)
+            .with_rates(0.2, 0.2)
+            .with_words(8..=8)
+            .with_authors(0.5);
+        assert_eq!(again.next_session(), session);
+        let next = generator.next_session();
+        assert_ne!(next.thoughts, session.thoughts);
+        assert!(next.thoughts[0].timestamp > thoughts[49].timestamp);
+
+        let engine = session.engine().await.unwrap();
+        assert_eq!(engine.get_thoughts().len(), 50);
+        assert!(!engine.get_branches().is_empty());
+        assert!(!engine.get_progress().needs_more_thoughts);
+
+        let calls = session.tool_calls();
+        assert_eq!(calls.len(), 50);
+        assert_eq!(calls[0].arguments.as_ref().unwrap()["thoughtNumber"], 1);
+
+        let fuzz = SessionGenerator::new(1)
+            .with_words(40..=40)
+            .with_special_words(1.0)
+            .next_session();
+        assert!(fuzz.thoughts[0]
+            .thought
+            .split(' ')
+            .all(|word| !VOCABULARY.contains(&word)));
+    }
+}
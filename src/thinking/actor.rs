@@ -0,0 +1,420 @@
+//! # Engine Actors
+//!
+//! An alternative to sharing session engines behind locks, for servers
+//! handling many sessions at once.
+//!
+//! An [`EngineActor`] runs one [`ThinkingEngine`] in a task of its own and
+//! takes commands from a bounded mailbox. Commands run one at a time in the
+//! order they were sent, so the thoughts of a session are never reordered,
+//! and a slow session only holds up callers waiting on that session. A full
+//! mailbox makes [`process_thought`](EngineActor::process_thought) wait and
+//! [`try_process_thought`](EngineActor::try_process_thought) fail, pushing
+//! back on callers instead of queueing without bound.
+//!
+//! [`SessionActors`] keeps one actor per session ID and starts actors as
+//! sessions are first used. With `thinking.actors` set, the server hands
+//! every tool call of a session to its actor with
+//! [`run`](EngineActor::run), so the calls of a session are handled in the
+//! order they arrived.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tracing::Instrument;
+
+use crate::thinking::error::{ErrorContext, SequentialThinkingError, SequentialThinkingResult};
+use crate::thinking::{ThinkingEngine, ThoughtData};
+
+/// Default number of commands waiting in a session's mailbox
+pub const DEFAULT_MAILBOX_CAPACITY: usize = 64;
+
+/// Engine run by an actor, shared with readers outside it
+pub type SharedEngine = Arc<RwLock<ThinkingEngine>>;
+
+/// Settings of the engine actors the server runs sessions on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ActorConfig {
+    /// Commands waiting in each session's mailbox
+    pub mailbox_capacity: usize,
+}
+
+impl Default for ActorConfig {
+    fn default() -> Self {
+        Self {
+            mailbox_capacity: DEFAULT_MAILBOX_CAPACITY,
+        }
+    }
+}
+
+/// Read of an engine's state, run inside its actor
+type Inspection = Box<dyn FnOnce(&ThinkingEngine) + Send>;
+
+/// Work run inside an actor, after the commands sent before it
+type Job = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Command sent to an engine actor
+enum Command {
+    Process {
+        thought: Box<ThoughtData>,
+        reply: oneshot::Sender<SequentialThinkingResult<ThoughtData>>,
+    },
+    Inspect(Inspection),
+    Run(Job),
+    Stop(oneshot::Sender<SharedEngine>),
+}
+
+/// Reply to a command accepted by an actor's mailbox
+#[derive(Debug)]
+pub struct PendingReply<T> {
+    session_id: String,
+    receiver: oneshot::Receiver<SequentialThinkingResult<T>>,
+}
+
+impl<T> PendingReply<T> {
+    /// Wait for the actor to handle the command
+    pub async fn wait(self) -> SequentialThinkingResult<T> {
+        self.receiver
+            .await
+            .unwrap_or_else(|_| Err(stopped(&self.session_id)))
+    }
+}
+
+/// Handle to a session engine running in its own task
+///
+/// Clones address the same actor. The actor stops once every handle has
+/// been dropped or [`stop`](Self::stop) is called.
+#[derive(Debug, Clone)]
+pub struct EngineActor {
+    session_id: String,
+    engine: SharedEngine,
+    mailbox: mpsc::Sender<Command>,
+}
+
+impl EngineActor {
+    /// Start an actor owning `engine`, with room for `capacity` waiting commands
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn spawn(session_id: impl Into<String>, engine: ThinkingEngine, capacity: usize) -> Self {
+        Self::attach(session_id, Arc::new(RwLock::new(engine)), capacity)
+    }
+
+    /// Start an actor running an engine that others may read through its lock
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn attach(session_id: impl Into<String>, engine: SharedEngine, capacity: usize) -> Self {
+        let (mailbox, commands) = mpsc::channel(capacity.max(1));
+        tokio::spawn(run(engine.clone(), commands));
+        Self {
+            session_id: session_id.into(),
+            engine,
+            mailbox,
+        }
+    }
+
+    /// ID of the session the actor runs
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Whether the actor runs the given engine
+    pub fn runs(&self, engine: &SharedEngine) -> bool {
+        Arc::ptr_eq(&self.engine, engine)
+    }
+
+    /// Process a thought, waiting for room in the mailbox if it is full
+    pub async fn process_thought(
+        &self,
+        thought: ThoughtData,
+    ) -> SequentialThinkingResult<ThoughtData> {
+        let (reply, receiver) = oneshot::channel();
+        self.mailbox
+            .send(Command::Process {
+                thought: Box::new(thought),
+                reply,
+            })
+            .await
+            .map_err(|_| stopped(&self.session_id))?;
+        self.pending(receiver).wait().await
+    }
+
+    /// Queue a thought without waiting, failing if the mailbox is full
+    pub fn try_process_thought(
+        &self,
+        thought: ThoughtData,
+    ) -> SequentialThinkingResult<PendingReply<ThoughtData>> {
+        let (reply, receiver) = oneshot::channel();
+        self.mailbox
+            .try_send(Command::Process {
+                thought: Box::new(thought),
+                reply,
+            })
+            .map_err(|e| match e {
+                mpsc::error::TrySendError::Full(_) => SequentialThinkingError::rate_limit_exceeded(
+                    format!("mailbox of session '{}' is full", self.session_id),
+                ),
+                mpsc::error::TrySendError::Closed(_) => stopped(&self.session_id),
+            })?;
+        Ok(self.pending(receiver))
+    }
+
+    /// Read the engine's state once the commands sent before have been handled
+    pub async fn inspect<R, F>(&self, read: F) -> SequentialThinkingResult<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&ThinkingEngine) -> R + Send + 'static,
+    {
+        let (reply, receiver) = oneshot::channel();
+        let inspection: Inspection = Box::new(move |engine| {
+            let _ = reply.send(Ok(read(engine)));
+        });
+        self.mailbox
+            .send(Command::Inspect(inspection))
+            .await
+            .map_err(|_| stopped(&self.session_id))?;
+        self.pending(receiver).wait().await
+    }
+
+    /// Run `job` once the commands sent before have been handled
+    ///
+    /// Commands sent later wait until the job has finished.
+    pub async fn run<R, F>(&self, job: F) -> SequentialThinkingResult<R>
+    where
+        R: Send + 'static,
+        F: Future<Output = R> + Send + 'static,
+    {
+        let (reply, receiver) = oneshot::channel();
+        let job: Job = Box::pin(async move {
+            let _ = reply.send(Ok(job.await));
+        });
+        self.mailbox
+            .send(Command::Run(job))
+            .await
+            .map_err(|_| stopped(&self.session_id))?;
+        self.pending(receiver).wait().await
+    }
+
+    /// Stop the actor once the commands sent before have been handled
+    ///
+    /// Returns the engine, so its session can be exported or checkpointed.
+    pub async fn stop(&self) -> SequentialThinkingResult<SharedEngine> {
+        let (reply, receiver) = oneshot::channel();
+        self.mailbox
+            .send(Command::Stop(reply))
+            .await
+            .map_err(|_| stopped(&self.session_id))?;
+        receiver.await.map_err(|_| stopped(&self.session_id))
+    }
+
+    fn pending<T>(
+        &self,
+        receiver: oneshot::Receiver<SequentialThinkingResult<T>>,
+    ) -> PendingReply<T> {
+        PendingReply {
+            session_id: self.session_id.clone(),
+            receiver,
+        }
+    }
+}
+
+/// Handle commands until every handle is dropped or the actor is stopped
+async fn run(engine: SharedEngine, mut commands: mpsc::Receiver<Command>) {
+    while let Some(command) = commands.recv().await {
+        match command {
+            Command::Process { thought, reply } => {
+                let result = process(&mut *engine.write().await, *thought).await;
+                // The caller may have given up waiting
+                let _ = reply.send(result);
+            }
+            Command::Inspect(inspection) => inspection(&*engine.read().await),
+            Command::Run(job) => job.await,
+            Command::Stop(reply) => {
+                let _ = reply.send(engine);
+                return;
+            }
+        }
+    }
+}
+
+async fn process(
+    engine: &mut ThinkingEngine,
+    thought: ThoughtData,
+) -> SequentialThinkingResult<ThoughtData> {
+    let mut context = ErrorContext::new("process_thought").with_thought(thought.thought_number);
+    if let Some(session_id) = engine.session_id() {
+        context = context.with_session(session_id);
+    }
+    let span = tracing::info_span!(
+        "process_thought",
+        session_id = engine.session_id(),
+        thought_number = thought.thought_number,
+    );
+    let result = match engine.check_sequence(&thought) {
+        Ok(_) => engine
+            .process_thought(thought)
+            .instrument(span)
+            .await
+            .map_err(SequentialThinkingError::processing_error),
+        Err(e) => Err(e.into()),
+    };
+    result.map_err(|e| e.with_context(context))
+}
+
+fn stopped(session_id: &str) -> SequentialThinkingError {
+    SequentialThinkingError::session_error(format!("session '{session_id}' has stopped"))
+}
+
+/// One engine actor per session, started on first use
+#[derive(Debug)]
+pub struct SessionActors {
+    capacity: usize,
+    actors: RwLock<HashMap<String, EngineActor>>,
+}
+
+impl Default for SessionActors {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAILBOX_CAPACITY)
+    }
+}
+
+impl SessionActors {
+    /// Create an empty set of actors with `capacity` waiting commands each
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            actors: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Actor of a session, starting it with a fresh engine if needed
+    pub async fn actor(&self, session_id: &str) -> EngineActor {
+        if let Some(actor) = self.actors.read().await.get(session_id) {
+            return actor.clone();
+        }
+        self.actors
+            .write()
+            .await
+            .entry(session_id.to_string())
+            .or_insert_with(|| {
+                let mut engine = ThinkingEngine::new();
+                engine.start_session(session_id.to_string());
+                EngineActor::spawn(session_id, engine, self.capacity)
+            })
+            .clone()
+    }
+
+    /// Actor of a session running `engine`, starting one if needed
+    ///
+    /// An actor running another engine for the session, e.g. one replaced
+    /// since, is replaced.
+    pub async fn attach(&self, session_id: &str, engine: &SharedEngine) -> EngineActor {
+        if let Some(actor) = self.actors.read().await.get(session_id) {
+            if actor.runs(engine) {
+                return actor.clone();
+            }
+        }
+        let mut actors = self.actors.write().await;
+        match actors.get(session_id) {
+            Some(actor) if actor.runs(engine) => actor.clone(),
+            _ => {
+                let actor = EngineActor::attach(session_id, engine.clone(), self.capacity);
+                actors.insert(session_id.to_string(), actor.clone());
+                actor
+            }
+        }
+    }
+
+    /// Start an actor for a session with the given engine
+    ///
+    /// Replaces any actor already running the session.
+    pub async fn insert(&self, session_id: &str, engine: ThinkingEngine) -> EngineActor {
+        let actor = EngineActor::spawn(session_id, engine, self.capacity);
+        self.actors
+            .write()
+            .await
+            .insert(session_id.to_string(), actor.clone());
+        actor
+    }
+
+    /// Process a thought in a session
+    pub async fn process_thought(
+        &self,
+        session_id: &str,
+        thought: ThoughtData,
+    ) -> SequentialThinkingResult<ThoughtData> {
+        self.actor(session_id).await.process_thought(thought).await
+    }
+
+    /// Stop a session's actor and return its engine
+    pub async fn remove(&self, session_id: &str) -> Option<SharedEngine> {
+        let actor = self.actors.write().await.remove(session_id)?;
+        actor.stop().await.ok()
+    }
+
+    /// IDs of the sessions with a running actor
+    pub async fn session_ids(&self) -> Vec<String> {
+        self.actors.read().await.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_session_actors() {
+        let actors = SessionActors::new(1);
+        let a = actors.actor("a").await;
+        let b = actors.actor("b").await;
+
+        // The mailbox of a is full until its actor gets to run, but b still
+        // takes thoughts
+        let first = a
+            .try_process_thought(ThoughtData::new("A1".to_string(), 1, 2))
+            .unwrap();
+        let full = a.try_process_thought(ThoughtData::new("A2".to_string(), 2, 2));
+        assert!(matches!(
+            full,
+            Err(SequentialThinkingError::RateLimitExceeded { .. })
+        ));
+        let other = b
+            .try_process_thought(ThoughtData::new("B1".to_string(), 1, 1))
+            .unwrap();
+        assert_eq!(first.wait().await.unwrap().thought, "A1");
+        assert_eq!(other.wait().await.unwrap().thought, "B1");
+
+        // Thoughts queued in order are processed in order
+        let c = EngineActor::spawn("c", ThinkingEngine::new(), 4);
+        let pending: Vec<_> = (1..=4)
+            .map(|number| {
+                c.try_process_thought(ThoughtData::new(format!("C{number}"), number, 4))
+                    .unwrap()
+            })
+            .collect();
+        for reply in pending {
+            reply.wait().await.unwrap();
+        }
+        let numbers = c
+            .inspect(|engine| {
+                engine
+                    .get_thoughts()
+                    .iter()
+                    .map(|t| t.thought_number)
+                    .collect::<Vec<_>>()
+            })
+            .await
+            .unwrap();
+        assert_eq!(numbers, [1, 2, 3, 4]);
+
+        let engine = actors.remove("a").await.unwrap();
+        assert_eq!(engine.read().await.session_id(), Some("a"));
+        assert_eq!(actors.session_ids().await, ["b"]);
+        assert!(a
+            .process_thought(ThoughtData::new("A5".to_string(), 5, 5))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! # Approval
+//!
+//! Confirmation of destructive tool calls made by a client.
+//!
+//! Some tools rewrite or replace reasoning history: merging sessions,
+//! cancelling processing, restoring a checkpoint over the current session and
+//! editing a thought in place. Once an [`Approver`] is installed with
+//! [`with_approver`](crate::thinking::client::SequentialThinkingClient::with_approver),
+//! the client asks it before sending a call of any tool listed in
+//! [`ApprovalConfig`], and fails with a
+//! [permission denied](crate::thinking::error::SequentialThinkingError::PermissionDenied)
+//! error when the call is refused. Clients without an approver send every
+//! call as before.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tools that need approval unless configured otherwise
+pub const DESTRUCTIVE_TOOLS: &[&str] = &[
+    "cancel_session",
+    "edit_thought",
+    "merge_sessions",
+    "resume_session",
+];
+
+/// Which tool calls need approval
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ApprovalConfig {
+    /// Whether calls need approval at all
+    pub enabled: bool,
+    /// Tools whose calls need approval
+    pub tools: Vec<String>,
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            tools: DESTRUCTIVE_TOOLS
+                .iter()
+                .map(|tool| tool.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl ApprovalConfig {
+    /// Check whether a call of `tool` with `arguments` needs approval
+    pub fn requires_approval(&self, tool: &str, arguments: &serde_json::Value) -> bool {
+        if !self.enabled || !self.tools.iter().any(|listed| listed == tool) {
+            return false;
+        }
+        // Resuming a paused session keeps its history; only restoring a
+        // checkpoint replaces it
+        tool != "resume_session" || arguments.get("checkpoint").is_some()
+    }
+}
+
+/// Decides whether a destructive tool call may go ahead
+pub trait Approver: Send + Sync {
+    /// Approve or refuse a call of `tool` with `arguments`
+    ///
+    /// Runs on a blocking thread, so it may wait for the user to answer. The
+    /// arguments do not include the API key.
+    fn approve(&self, tool: &str, arguments: &serde_json::Value) -> bool;
+}
+
+/// Approver accepting every call, e.g. for a `--yes` flag
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApproveAll;
+
+impl Approver for ApproveAll {
+    fn approve(&self, _tool: &str, _arguments: &serde_json::Value) -> bool {
+        true
+    }
+}
+
+/// Approver refusing every call, e.g. for runs nobody can answer prompts in
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenyAll;
+
+impl Approver for DenyAll {
+    fn approve(&self, _tool: &str, _arguments: &serde_json::Value) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::client::SequentialThinkingClient;
+    use crate::thinking::server::SequentialThinkingServer;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_destructive_calls_need_approval() {
+        let config = ApprovalConfig::default();
+        let checkpoint = serde_json::json!({ "checkpoint": {} });
+        assert!(config.requires_approval("merge_sessions", &serde_json::json!({})));
+        assert!(config.requires_approval("resume_session", &checkpoint));
+        assert!(!config.requires_approval("resume_session", &serde_json::json!({})));
+        assert!(!config.requires_approval("export_session", &serde_json::json!({})));
+        let disabled = ApprovalConfig {
+            enabled: false,
+            ..ApprovalConfig::default()
+        };
+        assert!(!disabled.requires_approval("merge_sessions", &serde_json::json!({})));
+
+        let client = SequentialThinkingClient::connect_in_process(SequentialThinkingServer::new())
+            .await
+            .unwrap();
+        let sessions = ["a".to_string(), "b".to_string()];
+        assert!(client.merge_sessions(&sessions, None).await.is_ok());
+
+        let refusing = client.clone().with_approver(Arc::new(DenyAll));
+        let error = refusing.merge_sessions(&sessions, None).await.unwrap_err();
+        assert_eq!(error.error_code(), "PERMISSION_DENIED");
+        assert_eq!(error.tool_call().unwrap().0, "merge_sessions");
+        // Tools outside the list are not held up
+        if let Err(e) = refusing.resume_paused_session("a", None).await {
+            assert_ne!(e.error_code(), "PERMISSION_DENIED");
+        }
+
+        let approving = client.with_approver(Arc::new(ApproveAll));
+        assert!(approving.merge_sessions(&sessions, None).await.is_ok());
+    }
+}
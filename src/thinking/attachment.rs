@@ -0,0 +1,224 @@
+//! # Attachments
+//!
+//! Typed content carried alongside a thought's text.
+//!
+//! A thought can attach code snippets, file references, images and links.
+//! Attachments travel through the `sequential_thinking` tool, are kept with
+//! the thought and are rendered specially in Markdown and HTML exports. Their
+//! number and size are limited by [`AttachmentLimits`].
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Content attached to a thought
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Attachment {
+    /// Source code snippet
+    Code {
+        /// Code text
+        content: String,
+        /// Language used for highlighting, such as `rust`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        language: Option<String>,
+        /// File the snippet comes from
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filename: Option<String>,
+    },
+    /// Reference to a file
+    File {
+        /// Path of the file
+        path: String,
+        /// What the file holds
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+    },
+    /// Base64-encoded image
+    Image {
+        /// Image media type, such as `image/png`
+        #[serde(alias = "mimeType")]
+        mime_type: String,
+        /// Base64-encoded image data
+        data: String,
+        /// Text describing the image
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        alt: Option<String>,
+    },
+    /// Link to a web page
+    Url {
+        /// HTTP or HTTPS address
+        url: String,
+        /// Link text
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+    },
+}
+
+impl Attachment {
+    /// Attach a code snippet
+    pub fn code(content: impl Into<String>, language: Option<&str>) -> Self {
+        Attachment::Code {
+            content: content.into(),
+            language: language.map(str::to_string),
+            filename: None,
+        }
+    }
+
+    /// Attach a link
+    pub fn url(url: impl Into<String>, title: Option<&str>) -> Self {
+        Attachment::Url {
+            url: url.into(),
+            title: title.map(str::to_string),
+        }
+    }
+
+    /// Size of the attachment's payload in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            Attachment::Code { content, .. } => content.len(),
+            Attachment::File { path, .. } => path.len(),
+            Attachment::Image { data, .. } => data.len(),
+            Attachment::Url { url, .. } => url.len(),
+        }
+    }
+
+    /// Check that the attachment is well formed and safe to render
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Attachment::Code { content, .. } if content.is_empty() => {
+                Err("code content cannot be empty".to_string())
+            }
+            Attachment::File { path, .. } if path.is_empty() => {
+                Err("file path cannot be empty".to_string())
+            }
+            Attachment::Image { mime_type, .. } if !mime_type.starts_with("image/") => {
+                Err(format!("unsupported image type: {mime_type}"))
+            }
+            Attachment::Image { data, .. }
+                if data.is_empty()
+                    || !data
+                        .bytes()
+                        .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'=')) =>
+            {
+                Err("image data must be non-empty base64".to_string())
+            }
+            Attachment::Url { url, .. }
+                if !(url.starts_with("https://") || url.starts_with("http://")) =>
+            {
+                Err(format!("unsupported URL: {url}"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Limits on the attachments of a single thought
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AttachmentLimits {
+    /// Maximum attachments per thought
+    pub max_attachments: usize,
+    /// Maximum payload size of one attachment in bytes
+    pub max_attachment_bytes: usize,
+}
+
+impl Default for AttachmentLimits {
+    fn default() -> Self {
+        Self {
+            max_attachments: 10,
+            max_attachment_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl AttachmentLimits {
+    /// Check a thought's attachments against the limits
+    pub fn check(&self, attachments: &[Attachment]) -> Result<(), String> {
+        if attachments.len() > self.max_attachments {
+            return Err(format!(
+                "A thought can have at most {} attachments, got {}",
+                self.max_attachments,
+                attachments.len()
+            ));
+        }
+        for (i, attachment) in attachments.iter().enumerate() {
+            attachment
+                .validate()
+                .map_err(|e| format!("Attachment {}: {e}", i + 1))?;
+            if attachment.size() > self.max_attachment_bytes {
+                return Err(format!(
+                    "Attachment {} is {} bytes, over the limit of {}",
+                    i + 1,
+                    attachment.size(),
+                    self.max_attachment_bytes
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::{ThinkingEngine, ThoughtData};
+
+    #[tokio::test]
+    async fn test_attachment_limits() {
+        let attachments: Vec<Attachment> = serde_json::from_value(serde_json::json!([
+            { "type": "code", "content": "fn main() {}", "language": "rust" },
+            { "type": "file", "path": "src/main.rs" },
+            { "type": "image", "mimeType": "image/png", "data": "iVBORw0KGgo=" },
+            { "type": "url", "url": "https://example.com", "title": "Example" }
+        ]))
+        .unwrap();
+        assert_eq!(
+            attachments[0],
+            Attachment::code("fn main() {}", Some("rust"))
+        );
+
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.set_attachment_limits(AttachmentLimits {
+            max_attachments: 4,
+            max_attachment_bytes: 20,
+        });
+        let thought = |n: u32| ThoughtData::new(format!("Step {n}"), n, 3);
+        let mut first = thought(1);
+        first.attachments = attachments;
+        let processed = engine.process_thought(first).await.unwrap();
+        assert_eq!(processed.attachments.len(), 4);
+
+        for (attachment, expected) in [
+            (
+                Attachment::code("x".repeat(21), None),
+                "21 bytes, over the limit of 20",
+            ),
+            (
+                Attachment::url("javascript:alert(1)", None),
+                "unsupported URL",
+            ),
+            (
+                Attachment::Image {
+                    mime_type: "text/html".to_string(),
+                    data: "PGI+".to_string(),
+                    alt: None,
+                },
+                "unsupported image type",
+            ),
+        ] {
+            let error = engine
+                .process_thought(thought(2).with_attachment(attachment))
+                .await
+                .unwrap_err();
+            assert!(error.contains(expected), "{expected} missing from {error}");
+        }
+        let crowded = (0..5).fold(thought(2), |t, _| {
+            t.with_attachment(Attachment::url("https://example.com", None))
+        });
+        assert!(engine
+            .process_thought(crowded)
+            .await
+            .unwrap_err()
+            .contains("at most 4 attachments"));
+        assert_eq!(engine.thought_count(), 1);
+    }
+}
@@ -0,0 +1,151 @@
+//! # Backpressure
+//!
+//! Limits on the tool calls a server handles at once.
+//!
+//! A [`Backpressure`] admits a tool call only while fewer than
+//! `max_in_flight_calls` calls are being handled, and fewer than
+//! `max_queued_per_session` of them belong to the call's session. Other
+//! calls are rejected at once with a
+//! [server busy](crate::thinking::error::SequentialThinkingError::ServerBusy)
+//! error telling the caller when to retry, instead of queueing behind the
+//! session lock and holding memory without bound.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::thinking::error::{SequentialThinkingError, SequentialThinkingResult};
+
+/// Limits on concurrent tool calls
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct BackpressureConfig {
+    /// Tool calls handled at once across all sessions
+    pub max_in_flight_calls: usize,
+    /// Tool calls of one session handled or waiting at once
+    pub max_queued_per_session: usize,
+    /// Wait suggested to rejected callers, in milliseconds
+    #[serde(default = "default_retry_after_ms")]
+    pub retry_after_ms: u64,
+}
+
+fn default_retry_after_ms() -> u64 {
+    1000
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            max_in_flight_calls: 256,
+            max_queued_per_session: 16,
+            retry_after_ms: default_retry_after_ms(),
+        }
+    }
+}
+
+/// Admission control for tool calls
+#[derive(Debug)]
+pub struct Backpressure {
+    config: BackpressureConfig,
+    in_flight: Arc<Semaphore>,
+    queued: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+/// A tool call admitted by [`Backpressure::admit`], released when dropped
+#[derive(Debug)]
+pub struct Admission {
+    _permit: OwnedSemaphorePermit,
+    session_id: String,
+    queued: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl Drop for Admission {
+    fn drop(&mut self) {
+        let mut queued = self.queued.lock().unwrap();
+        if let Some(count) = queued.get_mut(&self.session_id) {
+            *count -= 1;
+            if *count == 0 {
+                queued.remove(&self.session_id);
+            }
+        }
+    }
+}
+
+impl Backpressure {
+    /// Create admission control with the given limits
+    pub fn new(config: BackpressureConfig) -> Self {
+        Self {
+            in_flight: Arc::new(Semaphore::new(config.max_in_flight_calls)),
+            queued: Arc::new(Mutex::new(HashMap::new())),
+            config,
+        }
+    }
+
+    /// Admit a call of a session, or fail with a server busy error
+    pub fn admit(&self, session_id: &str) -> SequentialThinkingResult<Admission> {
+        let mut queued = self.queued.lock().unwrap();
+        let retry_after = Duration::from_millis(self.config.retry_after_ms);
+        if queued.get(session_id).copied().unwrap_or(0) >= self.config.max_queued_per_session {
+            return Err(SequentialThinkingError::server_busy(
+                format!(
+                    "session '{session_id}' already has {} calls in flight",
+                    self.config.max_queued_per_session
+                ),
+                retry_after,
+            ));
+        }
+        let permit = self.in_flight.clone().try_acquire_owned().map_err(|_| {
+            SequentialThinkingError::server_busy(
+                format!(
+                    "{} tool calls already in flight",
+                    self.config.max_in_flight_calls
+                ),
+                retry_after,
+            )
+        })?;
+        *queued.entry(session_id.to_string()).or_default() += 1;
+        Ok(Admission {
+            _permit: permit,
+            session_id: session_id.to_string(),
+            queued: self.queued.clone(),
+        })
+    }
+
+    /// Number of calls currently admitted
+    pub fn in_flight(&self) -> usize {
+        self.config.max_in_flight_calls - self.in_flight.available_permits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backpressure_limits() {
+        let backpressure = Backpressure::new(BackpressureConfig {
+            max_in_flight_calls: 3,
+            max_queued_per_session: 2,
+            retry_after_ms: 250,
+        });
+
+        let a1 = backpressure.admit("a").unwrap();
+        let _a2 = backpressure.admit("a").unwrap();
+        let busy = backpressure.admit("a").unwrap_err();
+        assert_eq!(busy.error_code(), "SERVER_BUSY");
+        assert_eq!(busy.retry_after(), Some(Duration::from_millis(250)));
+        assert!(busy.is_retryable());
+
+        // The server-wide limit applies across sessions
+        let _b1 = backpressure.admit("b").unwrap();
+        assert_eq!(backpressure.in_flight(), 3);
+        assert!(backpressure.admit("c").is_err());
+
+        // Finished calls make room again
+        drop(a1);
+        assert_eq!(backpressure.in_flight(), 2);
+        let _a3 = backpressure.admit("a").unwrap();
+    }
+}
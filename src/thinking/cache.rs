@@ -0,0 +1,257 @@
+//! # Response Cache
+//!
+//! Client-side cache of idempotent call results.
+//!
+//! Dashboards refresh the tool list, session analysis and exports far more
+//! often than sessions change. With a cache configured, the client answers
+//! repeated calls from memory. Entries are keyed by the version of their
+//! session, which the client bumps whenever it changes the session on the
+//! server, so a thought added through the client is never hidden by a cached
+//! result. Changes made by other clients show up once an entry expires.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+/// Response cache settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ResponseCacheConfig {
+    /// Seconds a cached result is served before the server is asked again
+    #[serde(default = "default_ttl_seconds")]
+    pub ttl_seconds: u64,
+    /// Results kept at most, evicting the oldest first
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+}
+
+fn default_ttl_seconds() -> u64 {
+    30
+}
+
+fn default_max_entries() -> usize {
+    256
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl_seconds: default_ttl_seconds(),
+            max_entries: default_max_entries(),
+        }
+    }
+}
+
+impl ResponseCacheConfig {
+    /// Check that the limits are positive
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.ttl_seconds == 0 {
+            errors.push("ttl_seconds: must be greater than 0".to_string());
+        }
+        if self.max_entries == 0 {
+            errors.push("max_entries: must be greater than 0".to_string());
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Identifies a cached result
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    /// Client method
+    operation: &'static str,
+    /// Session the result belongs to, if any
+    session_id: Option<String>,
+    /// Version of the session when the result was cached
+    version: u64,
+    /// Arguments that change the result, such as the export format
+    arguments: String,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    versions: HashMap<String, u64>,
+    entries: HashMap<CacheKey, (Instant, Value)>,
+    order: VecDeque<CacheKey>,
+}
+
+impl CacheState {
+    fn key(&self, operation: &'static str, session_id: Option<&str>, arguments: &str) -> CacheKey {
+        CacheKey {
+            operation,
+            session_id: session_id.map(str::to_string),
+            version: session_id
+                .and_then(|id| self.versions.get(id).copied())
+                .unwrap_or(0),
+            arguments: arguments.to_string(),
+        }
+    }
+}
+
+/// Results of idempotent calls, by operation, session version and arguments
+#[derive(Debug)]
+pub struct ResponseCache {
+    config: ResponseCacheConfig,
+    state: RwLock<CacheState>,
+}
+
+impl ResponseCache {
+    /// Create an empty cache
+    pub fn new(config: ResponseCacheConfig) -> Self {
+        Self {
+            config,
+            state: RwLock::new(CacheState::default()),
+        }
+    }
+
+    /// Get the cache settings
+    pub fn config(&self) -> &ResponseCacheConfig {
+        &self.config
+    }
+
+    /// Get a result cached for the current version of the session
+    pub async fn get(
+        &self,
+        operation: &'static str,
+        session_id: Option<&str>,
+        arguments: &str,
+    ) -> Option<Value> {
+        let state = self.state.read().await;
+        let (cached_at, value) = state
+            .entries
+            .get(&state.key(operation, session_id, arguments))?;
+        (cached_at.elapsed() < Duration::from_secs(self.config.ttl_seconds)).then(|| value.clone())
+    }
+
+    /// Cache a result for the current version of the session
+    pub async fn insert(
+        &self,
+        operation: &'static str,
+        session_id: Option<&str>,
+        arguments: &str,
+        value: Value,
+    ) {
+        let mut state = self.state.write().await;
+        let key = state.key(operation, session_id, arguments);
+        if state
+            .entries
+            .insert(key.clone(), (Instant::now(), value))
+            .is_none()
+        {
+            state.order.push_back(key);
+        }
+        while state.order.len() > self.config.max_entries {
+            if let Some(oldest) = state.order.pop_front() {
+                state.entries.remove(&oldest);
+            }
+        }
+    }
+
+    /// Start a new version of a session, dropping its cached results
+    pub async fn invalidate(&self, session_id: &str) {
+        let mut state = self.state.write().await;
+        *state.versions.entry(session_id.to_string()).or_default() += 1;
+        let CacheState { entries, order, .. } = &mut *state;
+        order.retain(|key| key.session_id.as_deref() != Some(session_id));
+        entries.retain(|key, _| key.session_id.as_deref() != Some(session_id));
+    }
+
+    /// Start a new version of every session, keeping results of no session
+    pub async fn invalidate_sessions(&self) {
+        let mut state = self.state.write().await;
+        let CacheState {
+            versions,
+            entries,
+            order,
+        } = &mut *state;
+        for key in order.iter().filter_map(|key| key.session_id.as_ref()) {
+            versions.entry(key.clone()).or_default();
+        }
+        versions.values_mut().for_each(|version| *version += 1);
+        order.retain(|key| key.session_id.is_none());
+        entries.retain(|key, _| key.session_id.is_none());
+    }
+
+    /// Get the version of a session, bumped on every invalidation
+    pub async fn version(&self, session_id: &str) -> u64 {
+        self.state
+            .read()
+            .await
+            .versions
+            .get(session_id)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_response_cache() {
+        let cache = ResponseCache::new(ResponseCacheConfig {
+            ttl_seconds: 60,
+            max_entries: 3,
+        });
+        let export = serde_json::json!("# Session");
+        cache
+            .insert("export_session", Some("a"), "markdown", export.clone())
+            .await;
+        cache
+            .insert("export_session", Some("b"), "markdown", export.clone())
+            .await;
+        cache
+            .insert("list_tools", None, "", serde_json::json!([]))
+            .await;
+
+        assert_eq!(
+            cache.get("export_session", Some("a"), "markdown").await,
+            Some(export.clone())
+        );
+        assert_eq!(cache.get("export_session", Some("a"), "json").await, None);
+
+        // A new version of one session leaves the others cached
+        cache.invalidate("a").await;
+        assert_eq!(cache.version("a").await, 1);
+        assert_eq!(
+            cache.get("export_session", Some("a"), "markdown").await,
+            None
+        );
+        assert!(cache
+            .get("export_session", Some("b"), "markdown")
+            .await
+            .is_some());
+
+        cache.invalidate_sessions().await;
+        assert_eq!(cache.version("b").await, 1);
+        assert_eq!(
+            cache.get("export_session", Some("b"), "markdown").await,
+            None
+        );
+        assert!(cache.get("list_tools", None, "").await.is_some());
+
+        // The oldest result is evicted when full
+        for session in ["c", "d", "e"] {
+            cache
+                .insert("analyze_session", Some(session), "", Value::Null)
+                .await;
+        }
+        assert_eq!(cache.get("list_tools", None, "").await, None);
+
+        assert!(ResponseCacheConfig {
+            ttl_seconds: 0,
+            max_entries: 1
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! # Engine Checkpoints
+//!
+//! Serializable snapshots of a thinking engine.
+//!
+//! A checkpoint captures everything needed to continue a session elsewhere:
+//! thoughts, branches, the dependency graph, progress and statistics. Resuming
+//! from a checkpoint restores that state exactly.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::annotations::AnnotationStore;
+use crate::thinking::graph::ThoughtGraph;
+use crate::thinking::{
+    SharedThought, ThinkingEngine, ThinkingProgress, ThinkingStats, ThoughtBranch, ThoughtEdit,
+};
+
+/// Current checkpoint format version
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+/// Serializable snapshot of a thinking engine's session state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineCheckpoint {
+    /// Checkpoint format version
+    pub version: u32,
+    /// Session the checkpoint was taken from
+    pub session_id: Option<String>,
+    /// Thoughts in the session
+    pub thoughts: Vec<SharedThought>,
+    /// Branches in the session
+    pub branches: HashMap<String, ThoughtBranch>,
+    /// Dependency graph between thoughts
+    pub graph: ThoughtGraph,
+    /// Thinking progress
+    pub progress: ThinkingProgress,
+    /// Processing statistics
+    pub stats: ThinkingStats,
+    /// Audit log of in-place thought edits
+    #[serde(default)]
+    pub edits: Vec<ThoughtEdit>,
+    /// Reviewer annotations on thoughts
+    #[serde(default)]
+    pub annotations: AnnotationStore,
+    /// When the checkpoint was taken
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl EngineCheckpoint {
+    /// Validate the checkpoint before restoring it
+    pub fn validate(&self) -> Result<(), String> {
+        if self.version != CHECKPOINT_VERSION {
+            return Err(format!(
+                "Unsupported checkpoint version {} (expected {CHECKPOINT_VERSION})",
+                self.version
+            ));
+        }
+
+        for thought in &self.thoughts {
+            thought.validate()?;
+            if !self.graph.contains(thought.thought_number) {
+                return Err(format!(
+                    "Checkpoint graph is missing thought {}",
+                    thought.thought_number
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ThinkingEngine {
+    /// Take a serializable snapshot of the current session
+    pub fn checkpoint(&self) -> EngineCheckpoint {
+        EngineCheckpoint {
+            version: CHECKPOINT_VERSION,
+            session_id: self.session_id.clone(),
+            thoughts: self.all_thoughts().into_owned(),
+            branches: self.branches.clone(),
+            graph: self.graph.clone(),
+            progress: self.progress.clone(),
+            stats: self.stats.clone(),
+            edits: self.edits.clone(),
+            annotations: self.annotations.clone(),
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Replace the current session with the state captured in a checkpoint
+    ///
+    /// The engine keeps its own identifier, logging, numbering and memory
+    /// settings; thoughts beyond the memory limit are spilled again.
+    pub fn resume_from(&mut self, checkpoint: EngineCheckpoint) -> Result<(), String> {
+        checkpoint.validate()?;
+
+        self.clear_spilled();
+        self.session_id = checkpoint.session_id;
+        self.clear_spilled();
+        self.thoughts = checkpoint.thoughts;
+        self.branches = checkpoint.branches;
+        self.graph = checkpoint.graph;
+        self.progress = checkpoint.progress;
+        self.stats = checkpoint.stats;
+        self.edits = checkpoint.edits;
+        self.annotations = checkpoint.annotations;
+        self.paused_at = None;
+        self.spill_excess();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::ThoughtData;
+
+    #[tokio::test]
+    async fn test_checkpoint_round_trip() {
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.start_session("long-run".to_string());
+        engine
+            .process_thought(ThoughtData::new("Frame the problem".to_string(), 1, 4))
+            .await
+            .unwrap();
+        engine
+            .process_thought(
+                ThoughtData::branch("Try another angle".to_string(), 2, 1, "alt".to_string())
+                    .with_dependencies(vec![1]),
+            )
+            .await
+            .unwrap();
+
+        let json = serde_json::to_string(&engine.checkpoint()).unwrap();
+        let checkpoint: EngineCheckpoint = serde_json::from_str(&json).unwrap();
+
+        let mut resumed = ThinkingEngine::with_logging(true);
+        resumed.resume_from(checkpoint).unwrap();
+
+        assert_eq!(resumed.session_id(), Some("long-run"));
+        assert_eq!(resumed.get_thoughts().len(), 2);
+        assert_eq!(resumed.get_branches().len(), 1);
+        assert_eq!(resumed.get_graph().dependencies(2), vec![1]);
+        assert_eq!(resumed.get_stats().total_branches, 1);
+
+        // Continuing after resume behaves like the original engine
+        resumed
+            .process_thought(
+                ThoughtData::new("Conclude".to_string(), 3, 4).with_dependencies(vec![2]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resumed.get_progress().current_thought, 3);
+
+        let mut stale = engine.checkpoint();
+        stale.version = 0;
+        assert!(ThinkingEngine::new().resume_from(stale).is_err());
+    }
+}
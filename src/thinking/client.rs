@@ -6,19 +6,38 @@
 //! sequential thinking servers and manages thinking sessions.
 
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
-use tracing::info;
-
-use ultrafast_mcp::{
-    ClientCapabilities, ClientInfo, ListToolsRequest, Tool, ToolCall, ToolContent, ToolResult,
-    UltraFastClient,
+use tokio::time::Instant;
+use tracing::{info, warn, Instrument};
+
+use ultrafast_mcp::{Tool, ToolCall, ToolContent, ToolResult};
+
+use crate::analytics::comparison::SessionComparison;
+use crate::analytics::cost::CostReport;
+use crate::annotations::Annotation;
+use crate::integrations::CreatedIssue;
+use crate::session::SessionTemplate;
+use crate::thinking::approval::{ApprovalConfig, Approver};
+use crate::thinking::cache::{ResponseCache, ResponseCacheConfig};
+use crate::thinking::checkpoint::EngineCheckpoint;
+use crate::thinking::code::CodeBlock;
+use crate::thinking::error::{ErrorContext, SequentialThinkingError, SequentialThinkingResult};
+use crate::thinking::replay::ReplayEvent;
+use crate::thinking::response::ThoughtResponse;
+use crate::thinking::sequence::NumberingPolicy;
+use crate::thinking::server::SequentialThinkingServer;
+use crate::thinking::transport::{transport_for_url, LoopbackTransport, Transport};
+use crate::thinking::{
+    SharedThought, ThinkingEngine, ThinkingProgress, ThinkingStats, ThoughtData,
 };
+use crate::usage::UsageReport;
+use crate::visualization::ThoughtRenderer;
 
-use crate::thinking::error::{SequentialThinkingError, SequentialThinkingResult};
-use crate::thinking::{ThinkingEngine, ThinkingProgress, ThinkingStats, ThoughtData};
-
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct ClientThinkingConfig {
     /// Whether to enable progress tracking
     pub enable_progress_tracking: bool,
@@ -28,8 +47,53 @@ pub struct ClientThinkingConfig {
     pub show_thought_visualization: bool,
     /// Maximum retry attempts for failed operations
     pub max_retry_attempts: u32,
-    /// Timeout for individual operations in seconds
+    /// Timeout for individual operations in seconds, retries included
     pub operation_timeout: u64,
+    /// Directory where sessions are auto-saved for crash recovery
+    #[serde(default = "default_auto_save_dir")]
+    pub auto_save_dir: String,
+    /// Author attributed to thoughts sent without one
+    #[serde(default)]
+    pub author: Option<String>,
+    /// What to do with a local thought the server did not accept
+    #[serde(default)]
+    pub sync_mode: SyncMode,
+    /// How thought numbers that do not follow the previous thought are handled
+    #[serde(default)]
+    pub numbering_policy: NumberingPolicy,
+    /// Cache of tool list, analysis and export results; disabled when unset
+    #[serde(default)]
+    pub response_cache: Option<ResponseCacheConfig>,
+    /// Tool calls that need approval once an approver is installed
+    #[serde(default)]
+    pub approval: ApprovalConfig,
+}
+
+/// How the client reconciles a thought the server did not accept
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncMode {
+    /// Keep the thought locally and queue it for
+    /// [`SequentialThinkingClient::resync_session`]
+    #[default]
+    PendingSync,
+    /// Undo the thought locally so client and server stay identical
+    Rollback,
+}
+
+/// Default directory for auto-saved sessions
+fn default_auto_save_dir() -> String {
+    "./.sequential-thinking/autosave".to_string()
 }
 
 impl Default for ClientThinkingConfig {
@@ -40,22 +104,111 @@ impl Default for ClientThinkingConfig {
             show_thought_visualization: true,
             max_retry_attempts: 3,
             operation_timeout: 30,
+            auto_save_dir: default_auto_save_dir(),
+            author: None,
+            sync_mode: SyncMode::PendingSync,
+            numbering_policy: NumberingPolicy::default(),
+            response_cache: None,
+            approval: ApprovalConfig::default(),
+        }
+    }
+}
+
+/// Auto-saved session state written to the auto-save directory
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AutoSaveRecord {
+    /// Session ID
+    pub session_id: String,
+    /// Session title
+    pub title: String,
+    /// Session metadata
+    pub metadata: HashMap<String, serde_json::Value>,
+    /// Session creation timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// When the record was written
+    pub saved_at: chrono::DateTime<chrono::Utc>,
+    /// Local engine state
+    pub checkpoint: EngineCheckpoint,
+    /// Thoughts not yet acknowledged by the server
+    #[serde(default)]
+    pub pending_sync: Vec<ThoughtData>,
+}
+
+impl AutoSaveRecord {
+    /// Capture the current state of a session
+    fn capture(session: &ThinkingSession) -> Self {
+        Self {
+            session_id: session.session_id.clone(),
+            title: session.title.clone(),
+            metadata: session.metadata.clone(),
+            created_at: session.created_at,
+            saved_at: chrono::Utc::now(),
+            checkpoint: session.engine.checkpoint(),
+            pending_sync: session.pending_sync.clone(),
         }
     }
+
+    /// Rebuild a session from the record
+    fn restore(self) -> Result<ThinkingSession, String> {
+        let mut session = ThinkingSession::new(self.session_id, self.title);
+        session.engine.resume_from(self.checkpoint)?;
+        session.metadata = self.metadata;
+        session.created_at = self.created_at;
+        session.pending_sync = self.pending_sync;
+        Ok(session)
+    }
 }
 
 /// Main sequential thinking client implementation
+#[derive(Clone)]
 pub struct SequentialThinkingClient {
-    /// Underlying MCP client
-    client: Arc<UltraFastClient>,
+    /// Connection to the server
+    transport: Arc<dyn Transport>,
     /// Client configuration
     config: ClientThinkingConfig,
     /// Active thinking sessions
     sessions: Arc<RwLock<HashMap<String, ThinkingSession>>>,
     /// Client statistics
-    stats: Arc<RwLock<ClientStats>>,
+    stats: Arc<ClientCounters>,
     /// Progress tracker
     progress_tracker: Arc<RwLock<ProgressTracker>>,
+    /// API key sent with every tool call
+    api_key: Option<String>,
+    /// Limit on each request to the server
+    request_timeout: Option<Duration>,
+    /// Results of idempotent calls, if caching is configured
+    cache: Option<Arc<ResponseCache>>,
+    /// How thoughts of local sessions are logged
+    renderer: ThoughtRenderer,
+    /// Asked before destructive tool calls, if installed
+    approver: Option<Arc<dyn Approver>>,
+}
+
+/// Point in time by which an operation against the server must finish
+#[derive(Debug, Clone, Copy)]
+struct Deadline {
+    at: Instant,
+    timeout: Duration,
+}
+
+impl Deadline {
+    /// Deadline of an operation starting now and lasting at most `timeout`
+    fn after(timeout: Duration) -> Self {
+        Self {
+            at: Instant::now() + timeout,
+            timeout,
+        }
+    }
+
+    /// Run `operation`, failing with a timeout error once the deadline passes
+    async fn run<T>(
+        self,
+        operation: impl Future<Output = SequentialThinkingResult<T>>,
+    ) -> SequentialThinkingResult<T> {
+        tokio::time::timeout_at(self.at, operation)
+            .await
+            .unwrap_or(Err(SequentialThinkingError::timeout(self.timeout)))
+    }
 }
 
 /// Client statistics
@@ -75,6 +228,56 @@ pub struct ClientStats {
     pub error_count: u64,
     /// Retry count
     pub retry_count: u64,
+    /// Calls answered from the response cache
+    pub cache_hits: u64,
+}
+
+/// Client statistics counters, bumped without locking on every request
+#[derive(Debug, Default)]
+struct ClientCounters {
+    total_requests: AtomicU64,
+    total_thoughts: AtomicU64,
+    total_sessions: AtomicU64,
+    total_response_time_ms: AtomicU64,
+    error_count: AtomicU64,
+    retry_count: AtomicU64,
+    cache_hits: AtomicU64,
+}
+
+impl ClientCounters {
+    /// Record one thought request
+    fn record_request(&self, response_time_ms: u64, retries: u64, ok: bool) {
+        self.total_requests.fetch_add(1, Ordering::Relaxed);
+        self.retry_count.fetch_add(retries, Ordering::Relaxed);
+        self.total_response_time_ms
+            .fetch_add(response_time_ms, Ordering::Relaxed);
+        let outcome = if ok {
+            &self.total_thoughts
+        } else {
+            &self.error_count
+        };
+        outcome.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Current values, with the average response time derived from the totals
+    fn snapshot(&self) -> ClientStats {
+        let total_requests = self.total_requests.load(Ordering::Relaxed);
+        let total_response_time_ms = self.total_response_time_ms.load(Ordering::Relaxed);
+        ClientStats {
+            total_requests,
+            total_thoughts: self.total_thoughts.load(Ordering::Relaxed),
+            total_sessions: self.total_sessions.load(Ordering::Relaxed),
+            avg_response_time_ms: if total_requests == 0 {
+                0.0
+            } else {
+                total_response_time_ms as f64 / total_requests as f64
+            },
+            total_response_time_ms,
+            error_count: self.error_count.load(Ordering::Relaxed),
+            retry_count: self.retry_count.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+        }
+    }
 }
 
 /// Progress tracking information
@@ -98,6 +301,17 @@ impl Default for ProgressTracker {
     }
 }
 
+/// Incremental update for a watched session
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionUpdate {
+    /// Thoughts recorded since the previous poll
+    pub new_thoughts: Vec<SharedThought>,
+    /// Number of thoughts currently held by the server
+    pub thought_count: usize,
+    /// Progress derived from the latest new thought
+    pub progress: Option<ThinkingProgress>,
+}
+
 /// A thinking session managed by the client
 pub struct ThinkingSession {
     /// Session ID
@@ -112,6 +326,11 @@ pub struct ThinkingSession {
     pub created_at: chrono::DateTime<chrono::Utc>,
     /// Last activity timestamp
     pub last_activity: chrono::DateTime<chrono::Utc>,
+    /// Thoughts accepted locally but not yet acknowledged by the server,
+    /// oldest first
+    pub pending_sync: Vec<ThoughtData>,
+    /// Whether the session has been created on the server
+    pub registered: bool,
 }
 
 impl ThinkingSession {
@@ -124,6 +343,8 @@ impl ThinkingSession {
             metadata: HashMap::new(),
             created_at: chrono::Utc::now(),
             last_activity: chrono::Utc::now(),
+            pending_sync: Vec::new(),
+            registered: false,
         }
     }
 
@@ -152,36 +373,7 @@ impl ThinkingSession {
 impl SequentialThinkingClient {
     /// Create a new sequential thinking client
     pub async fn new(server_url: &str) -> SequentialThinkingResult<Self> {
-        let client_info = ClientInfo {
-            name: "UltraFast MCP Sequential Thinking Client".to_string(),
-            version: "0.1.0".to_string(),
-            description: Some(
-                "High-performance Rust-based MCP client for sequential thinking".to_string(),
-            ),
-            homepage: Some(
-                "https://github.com/techgopal/ultrafast-mcp-sequential-thinking".to_string(),
-            ),
-            repository: Some(
-                "https://github.com/techgopal/ultrafast-mcp-sequential-thinking".to_string(),
-            ),
-            authors: Some(vec!["techgopal <techgopal2@gmail.com>".to_string()]),
-            license: Some("MIT".to_string()),
-        };
-        let client_capabilities = ClientCapabilities::default();
-        let client = UltraFastClient::new(client_info, client_capabilities);
-
-        let mut client_instance = Self {
-            client: Arc::new(client),
-            config: ClientThinkingConfig::default(),
-            sessions: Arc::new(RwLock::new(HashMap::new())),
-            stats: Arc::new(RwLock::new(ClientStats::default())),
-            progress_tracker: Arc::new(RwLock::new(ProgressTracker::default())),
-        };
-
-        // Connect to server
-        client_instance.connect(server_url).await?;
-
-        Ok(client_instance)
+        Self::with_config(server_url, ClientThinkingConfig::default()).await
     }
 
     /// Create a new client with custom configuration
@@ -189,101 +381,333 @@ impl SequentialThinkingClient {
         server_url: &str,
         config: ClientThinkingConfig,
     ) -> SequentialThinkingResult<Self> {
-        let client_info = ClientInfo {
-            name: "UltraFast MCP Sequential Thinking Client".to_string(),
-            version: "0.1.0".to_string(),
-            description: Some(
-                "High-performance Rust-based MCP client for sequential thinking".to_string(),
-            ),
-            homepage: Some(
-                "https://github.com/techgopal/ultrafast-mcp-sequential-thinking".to_string(),
-            ),
-            repository: Some(
-                "https://github.com/techgopal/ultrafast-mcp-sequential-thinking".to_string(),
-            ),
-            authors: Some(vec!["techgopal <techgopal2@gmail.com>".to_string()]),
-            license: Some("MIT".to_string()),
-        };
-        let client_capabilities = ClientCapabilities::default();
-        let client = UltraFastClient::new(client_info, client_capabilities);
+        Self::with_transport(transport_for_url(server_url)?, config).await
+    }
 
-        let mut client_instance = Self {
-            client: Arc::new(client),
+    /// Create a client connected to `server` running in this process
+    ///
+    /// Requests reach the server over channels as serialized MCP messages, so
+    /// integration tests cover tools, errors and retries without managing a
+    /// server process. Must be called within a Tokio runtime.
+    pub async fn connect_in_process(
+        server: SequentialThinkingServer,
+    ) -> SequentialThinkingResult<Self> {
+        Self::with_transport(
+            Arc::new(LoopbackTransport::spawn(server)),
+            ClientThinkingConfig::default(),
+        )
+        .await
+    }
+
+    /// Create a client connected through `transport`
+    ///
+    /// Use [`InMemoryTransport`](crate::thinking::transport::InMemoryTransport)
+    /// to talk to a server in the same process, or a custom [`Transport`].
+    pub async fn with_transport(
+        transport: Arc<dyn Transport>,
+        config: ClientThinkingConfig,
+    ) -> SequentialThinkingResult<Self> {
+        transport.connect().await?;
+
+        let cache = config
+            .response_cache
+            .clone()
+            .map(|cache| Arc::new(ResponseCache::new(cache)));
+        Ok(Self {
+            transport,
             config,
             sessions: Arc::new(RwLock::new(HashMap::new())),
-            stats: Arc::new(RwLock::new(ClientStats::default())),
+            stats: Arc::new(ClientCounters::default()),
             progress_tracker: Arc::new(RwLock::new(ProgressTracker::default())),
-        };
+            api_key: None,
+            request_timeout: None,
+            cache,
+            renderer: ThoughtRenderer::default(),
+            approver: None,
+        })
+    }
 
-        // Connect to server
-        client_instance.connect(server_url).await?;
+    /// Set how thoughts of local sessions are rendered in the thought log
+    pub fn with_renderer(mut self, renderer: ThoughtRenderer) -> Self {
+        self.renderer = renderer;
+        self
+    }
 
-        Ok(client_instance)
+    /// Ask `approver` before sending destructive tool calls
+    ///
+    /// Which tools count as destructive is set by the `approval` configuration.
+    pub fn with_approver(mut self, approver: Arc<dyn Approver>) -> Self {
+        self.approver = Some(approver);
+        self
     }
 
-    /// Connect to the server and initialize MCP connection
-    async fn connect(&mut self, server_url: &str) -> SequentialThinkingResult<()> {
-        info!("Connecting to server: {}", server_url);
+    /// Send an API key with every tool call
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
+    }
 
-        // Parse server URL to determine transport type
-        if server_url.starts_with("stdio://") || server_url == "stdio" {
-            // Connect via STDIO
-            self.client.connect_stdio().await.map_err(|e| {
-                SequentialThinkingError::transport_error(format!(
-                    "Failed to connect via STDIO: {e}"
-                ))
-            })?;
-        } else if server_url.starts_with("http://") || server_url.starts_with("https://") {
-            // Connect via HTTP
-            self.client
-                .connect_streamable_http(server_url)
+    /// Limit each request to the server, within the operation timeout
+    ///
+    /// Requests that time out are retried like failed ones while the
+    /// operation has time left.
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Deadline of an operation starting now, `timeout` overriding the
+    /// configured operation timeout
+    fn deadline(&self, timeout: Option<Duration>) -> Deadline {
+        Deadline::after(
+            timeout.unwrap_or_else(|| Duration::from_secs(self.config.operation_timeout)),
+        )
+    }
+
+    /// Send one request to the server, bounded by the request timeout
+    async fn request(&self, tool_call: ToolCall) -> SequentialThinkingResult<ToolResult> {
+        let call = self.transport.call_tool(tool_call);
+        let result = match self.request_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, call)
                 .await
-                .map_err(|e| {
-                    SequentialThinkingError::transport_error(format!(
-                        "Failed to connect via HTTP: {e}"
-                    ))
-                })?;
+                .map_err(|_| SequentialThinkingError::timeout(timeout))?,
+            None => call.await,
+        };
+        result
+    }
+
+    /// Call a tool within the operation timeout, or `timeout` when given
+    ///
+    /// Errors record the tool call and advice for recovering from them.
+    async fn call_tool(
+        &self,
+        tool_call: ToolCall,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<ToolResult> {
+        let (tool, arguments) = (tool_call.name.clone(), tool_call.arguments.clone());
+        let result = match self.check_approval(&tool_call).await {
+            Ok(()) => self.deadline(timeout).run(self.request(tool_call)).await,
+            Err(e) => Err(e),
+        };
+        result.map_err(|e| e.in_tool_call(tool, arguments))
+    }
+
+    /// Ask the approver, if any, whether a tool call may be sent
+    async fn check_approval(&self, tool_call: &ToolCall) -> SequentialThinkingResult<()> {
+        let Some(approver) = self.approver.clone() else {
+            return Ok(());
+        };
+        let mut arguments = tool_call
+            .arguments
+            .clone()
+            .unwrap_or_else(|| serde_json::json!({}));
+        if !self
+            .config
+            .approval
+            .requires_approval(&tool_call.name, &arguments)
+        {
+            return Ok(());
+        }
+        if let Some(fields) = arguments.as_object_mut() {
+            fields.remove("apiKey");
+        }
+
+        let tool = tool_call.name.clone();
+        let approved = tokio::task::spawn_blocking(move || approver.approve(&tool, &arguments))
+            .await
+            .unwrap_or(false);
+        if approved {
+            Ok(())
         } else {
-            return Err(SequentialThinkingError::transport_error(format!(
-                "Unsupported server URL format: {server_url}"
-            )));
+            Err(SequentialThinkingError::permission_denied(format!(
+                "{} was not approved",
+                tool_call.name
+            )))
         }
+    }
 
-        info!("Connected to server, initializing MCP connection...");
+    /// Get a result from the response cache, counting the hit
+    async fn cached<T: serde::de::DeserializeOwned>(
+        &self,
+        operation: &'static str,
+        session_id: Option<&str>,
+        arguments: &str,
+    ) -> Option<T> {
+        let value = self
+            .cache
+            .as_ref()?
+            .get(operation, session_id, arguments)
+            .await?;
+        let cached = serde_json::from_value(value).ok()?;
+        self.stats.cache_hits.fetch_add(1, Ordering::Relaxed);
+        Some(cached)
+    }
 
-        // Initialize the MCP connection
-        self.client.initialize().await.map_err(|e| {
-            SequentialThinkingError::transport_error(format!(
-                "Failed to initialize MCP connection: {e}"
-            ))
-        })?;
+    /// Store a result in the response cache, if configured
+    async fn cache_result<T: serde::Serialize>(
+        &self,
+        operation: &'static str,
+        session_id: Option<&str>,
+        arguments: &str,
+        result: &T,
+    ) {
+        if let (Some(cache), Ok(value)) = (&self.cache, serde_json::to_value(result)) {
+            cache.insert(operation, session_id, arguments, value).await;
+        }
+    }
 
-        info!("MCP connection initialized successfully");
-        Ok(())
+    /// Drop cached results of a session changed on the server, or of every
+    /// session when the change is not tied to one
+    async fn invalidate_cache(&self, session_id: Option<&str>) {
+        match (&self.cache, session_id) {
+            (Some(cache), Some(session_id)) => cache.invalidate(session_id).await,
+            (Some(cache), None) => cache.invalidate_sessions().await,
+            (None, _) => {}
+        }
+    }
+
+    /// Add the API key to tool call arguments
+    fn authenticated(&self, mut arguments: serde_json::Value) -> serde_json::Value {
+        if let (Some(key), Some(args)) = (&self.api_key, arguments.as_object_mut()) {
+            args.insert("apiKey".to_string(), serde_json::json!(key));
+        }
+        arguments
     }
 
     /// Start a new thinking session
-    pub async fn start_session(&self, title: String) -> SequentialThinkingResult<ThinkingSession> {
+    ///
+    /// The returned handle tracks its own progress and statistics, so several
+    /// sessions can be driven concurrently from one client.
+    pub async fn start_session(&self, title: String) -> SequentialThinkingResult<SessionHandle> {
         let session_id = uuid::Uuid::new_v4().to_string();
-        let mut session = ThinkingSession::new(session_id.clone(), title);
+        let mut session = ThinkingSession::new(session_id.clone(), title.clone());
 
         // Initialize the session
+        session.engine.set_renderer(self.renderer.clone());
+        session
+            .engine
+            .set_numbering_policy(self.config.numbering_policy);
         session.engine.start_session(session_id.clone());
 
         // Store the session
         {
             let mut sessions = self.sessions.write().await;
-            sessions.insert(session_id.clone(), session.clone());
+            sessions.insert(session_id.clone(), session);
+        }
+
+        self.stats.total_sessions.fetch_add(1, Ordering::Relaxed);
+
+        info!("Started new thinking session: {}", session_id);
+        Ok(self.open_handle(session_id, title))
+    }
+
+    /// Create a handle for a registered session, starting auto-save if enabled
+    fn open_handle(&self, session_id: String, title: String) -> SessionHandle {
+        let mut handle = SessionHandle::new(self.clone(), session_id, title);
+        if self.config.auto_save_interval > 0 {
+            handle.start_auto_save(self.auto_save_path(&handle.session_id));
+        }
+        handle
+    }
+
+    /// Path of the auto-save file for a session
+    pub fn auto_save_path(&self, session_id: &str) -> std::path::PathBuf {
+        std::path::Path::new(&self.config.auto_save_dir).join(format!("{session_id}.json"))
+    }
+
+    /// List auto-saved sessions that can be recovered, most recently saved first
+    pub fn list_recoverable_sessions(&self) -> Vec<AutoSaveRecord> {
+        let Ok(entries) = std::fs::read_dir(&self.config.auto_save_dir) else {
+            return Vec::new();
+        };
+
+        let mut records: Vec<AutoSaveRecord> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("json"))
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .filter_map(|content| serde_json::from_str(&content).ok())
+            .collect();
+        records.sort_by_key(|record| std::cmp::Reverse(record.saved_at));
+        records
+    }
+
+    /// Read the auto-save record of a session
+    pub fn load_auto_save(&self, session_id: &str) -> SequentialThinkingResult<AutoSaveRecord> {
+        let path = self.auto_save_path(session_id);
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            SequentialThinkingError::not_found(format!(
+                "No auto-saved session {session_id} at {}: {e}",
+                path.display()
+            ))
+        })?;
+        serde_json::from_str(&content)
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+    }
+
+    /// Restore an auto-saved session and return a handle to continue it
+    pub async fn recover_session(
+        &self,
+        session_id: &str,
+    ) -> SequentialThinkingResult<SessionHandle> {
+        let mut session = self
+            .load_auto_save(session_id)?
+            .restore()
+            .map_err(SequentialThinkingError::session_error)?;
+        session.engine.set_renderer(self.renderer.clone());
+        session
+            .engine
+            .set_numbering_policy(self.config.numbering_policy);
+        let title = session.title.clone();
+
+        {
+            let mut sessions = self.sessions.write().await;
+            sessions.insert(session_id.to_string(), session);
         }
 
-        // Update statistics
+        info!("Recovered auto-saved session: {}", session_id);
+        Ok(self.open_handle(session_id.to_string(), title))
+    }
+
+    /// Start a new thinking session from a template and submit its seeded thoughts
+    ///
+    /// `timeout` overrides the operation timeout of each seeded thought.
+    pub async fn start_session_from_template(
+        &self,
+        template: &SessionTemplate,
+        title: Option<String>,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<SessionHandle> {
+        template
+            .validate()
+            .map_err(SequentialThinkingError::validation_error)?;
+
+        let handle = self
+            .start_session(title.unwrap_or_else(|| template.name.clone()))
+            .await?;
+
         {
-            let mut stats = self.stats.write().await;
-            stats.total_sessions += 1;
+            let mut sessions = self.sessions.write().await;
+            if let Some(stored) = sessions.get_mut(&handle.session_id) {
+                stored.add_metadata(
+                    "template".to_string(),
+                    serde_json::Value::String(template.name.clone()),
+                );
+                stored.add_metadata(
+                    "tags".to_string(),
+                    serde_json::json!(template.required_tags),
+                );
+                for (key, value) in &template.metadata {
+                    stored.add_metadata(key.clone(), value.clone());
+                }
+            }
         }
 
-        info!("Started new thinking session: {}", session_id);
-        Ok(session)
+        for thought in template.seed_thought_data() {
+            handle.add_thought(thought, timeout).await?;
+        }
+
+        Ok(handle)
     }
 
     /// Get a thinking session by ID
@@ -293,128 +717,341 @@ impl SequentialThinkingClient {
     }
 
     /// Add a thought to a session
+    ///
+    /// The thought is processed locally first and then sent to the server,
+    /// after any earlier thoughts still waiting for the server. If the server
+    /// does not accept it, the error is returned and the local thought is
+    /// reconciled according to the configured [`SyncMode`]: queued for
+    /// [`resync_session`](Self::resync_session) or rolled back. A thought
+    /// the server does not answer within the operation timeout, or `timeout`
+    /// when given, is reconciled the same way.
     pub async fn add_thought(
         &self,
-        _session_id: &str,
+        session_id: &str,
         thought: ThoughtData,
-    ) -> SequentialThinkingResult<ThoughtData> {
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<ThoughtResponse> {
+        self.add_thought_tracked(session_id, thought, &self.progress_tracker, None, timeout)
+            .await
+    }
+
+    /// Add a thought, recording progress in `tracker` and optionally per-session statistics
+    async fn add_thought_tracked(
+        &self,
+        session_id: &str,
+        thought: ThoughtData,
+        tracker: &RwLock<ProgressTracker>,
+        session_stats: Option<&ClientCounters>,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<ThoughtResponse> {
         let start_time = std::time::Instant::now();
+        let deadline = self.deadline(timeout);
 
-        // Update request statistics
-        {
-            let mut stats = self.stats.write().await;
-            stats.total_requests += 1;
+        let mut thought = thought;
+        if thought.author.is_none() {
+            thought.author = self.config.author.clone();
         }
+        // Lets the server recognise retries (and resyncs) of this submission
+        thought
+            .idempotency_key
+            .get_or_insert_with(|| uuid::Uuid::new_v4().to_string());
+
+        let context = |thought_number| {
+            ErrorContext::new("add_thought")
+                .with_session(session_id)
+                .with_thought(thought_number)
+        };
 
-        // Process thought locally first
-        let mut sessions = self.sessions.write().await;
-        let session = sessions.get_mut(_session_id).ok_or_else(|| {
-            SequentialThinkingError::not_found(format!("Session not found: {_session_id}"))
-        })?;
+        // Thoughts must reach the server in order, so flush earlier ones first
+        let backlog = self.sync_pending(session_id, deadline).await;
 
-        let processed_thought = session
-            .engine
-            .process_thought(thought.clone())
-            .await
-            .map_err(SequentialThinkingError::processing_error)?;
+        // Process thought locally first, releasing the lock before the server round trip
+        let (processed_thought, checkpoint) = {
+            let mut sessions = self.sessions.write().await;
+            let session = sessions.get_mut(session_id).ok_or_else(|| {
+                SequentialThinkingError::not_found(format!("Session not found: {session_id}"))
+            })?;
 
-        // Send thought to server
-        let server_result = self.send_thought_to_server(thought).await;
+            // Renumber up front so the server sees the number recorded locally
+            let submitted = thought.thought_number;
+            thought.thought_number = session
+                .engine
+                .check_sequence(&thought)
+                .map_err(|e| SequentialThinkingError::from(e).with_context(context(submitted)))?;
+
+            let checkpoint =
+                (self.config.sync_mode == SyncMode::Rollback).then(|| session.engine.checkpoint());
+            let processed = session
+                .engine
+                .process_thought(thought.clone())
+                .await
+                .map_err(|e| {
+                    SequentialThinkingError::processing_error(e)
+                        .with_context(context(thought.thought_number))
+                })?;
+            (processed, checkpoint)
+        };
 
-        // Update response time statistics
-        {
-            let response_time = start_time.elapsed();
-            let mut stats = self.stats.write().await;
-            stats.total_response_time_ms += response_time.as_millis() as u64;
-            stats.avg_response_time_ms =
-                stats.total_response_time_ms as f64 / stats.total_requests as f64;
-
-            if server_result.is_ok() {
-                stats.total_thoughts += 1;
-            } else {
-                stats.error_count += 1;
+        // Send thought to server
+        let (server_result, retries) = match backlog {
+            Ok(_) => {
+                self.send_thought_to_server(session_id, thought.clone(), deadline)
+                    .await
             }
+            Err(e) => (Err(e), 0),
+        };
+        if server_result.is_err() {
+            self.reconcile_rejected(session_id, thought, checkpoint)
+                .await;
+        }
+        self.invalidate_cache(Some(session_id)).await;
+
+        // Update request and response time statistics
+        let response_time_ms = start_time.elapsed().as_millis() as u64;
+        let server_ok = server_result.is_ok();
+        self.stats
+            .record_request(response_time_ms, retries, server_ok);
+        if let Some(session_stats) = session_stats {
+            session_stats.record_request(response_time_ms, retries, server_ok);
         }
 
         // Update progress tracking
         if self.config.enable_progress_tracking {
-            self.update_progress_tracking(&processed_thought).await;
+            self.update_progress_tracking(tracker, &processed_thought)
+                .await;
         }
 
         // Update session activity
-        session.last_activity = chrono::Utc::now();
+        if let Some(session) = self.sessions.write().await.get_mut(session_id) {
+            session.last_activity = chrono::Utc::now();
+        }
 
-        Ok(processed_thought)
+        ThoughtResponse::from_tool_result(&server_result?).map_err(|e| {
+            SequentialThinkingError::serialization_error(e)
+                .with_context(context(processed_thought.thought_number))
+        })
     }
 
-    /// Send a thought to the server
-    async fn send_thought_to_server(
+    /// Undo or queue a local thought the server did not accept
+    async fn reconcile_rejected(
         &self,
+        session_id: &str,
         thought: ThoughtData,
-    ) -> SequentialThinkingResult<ToolResult> {
-        let args = serde_json::json!({
-            "thought": thought.thought,
-            "thoughtNumber": thought.thought_number,
-            "totalThoughts": thought.total_thoughts,
-            "nextThoughtNeeded": thought.next_thought_needed,
-            "isRevision": thought.is_revision,
-            "revisesThought": thought.revises_thought,
-            "branchFromThought": thought.branch_from_thought,
-            "branchId": thought.branch_id,
-            "needsMoreThoughts": thought.needs_more_thoughts
-        });
-
-        let tool_call = ToolCall {
-            name: "sequential_thinking".to_string(),
-            arguments: Some(args),
+        checkpoint: Option<EngineCheckpoint>,
+    ) {
+        let mut sessions = self.sessions.write().await;
+        let Some(session) = sessions.get_mut(session_id) else {
+            return;
         };
+        match checkpoint {
+            Some(checkpoint) => {
+                if let Err(e) = session.engine.resume_from(checkpoint) {
+                    warn!(
+                        "Failed to roll back thought {} in session {}: {}",
+                        thought.thought_number, session_id, e
+                    );
+                }
+            }
+            None => session.pending_sync.push(thought),
+        }
+    }
 
-        let mut attempts = 0;
-        loop {
-            match self.client.call_tool(tool_call.clone()).await {
-                Ok(result) => return Ok(result),
-                Err(e) => {
-                    attempts += 1;
-                    if attempts >= self.config.max_retry_attempts {
-                        return Err(SequentialThinkingError::transport_error(e.to_string()));
-                    }
+    /// Send thoughts the server has not acknowledged yet, oldest first
+    ///
+    /// Stops at the first thought the server does not accept, leaving it and
+    /// any later thoughts queued. Returns the number of thoughts acknowledged.
+    /// `timeout` overrides the operation timeout for the whole resync.
+    pub async fn resync_session(
+        &self,
+        session_id: &str,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<usize> {
+        self.sync_pending(session_id, self.deadline(timeout)).await
+    }
 
-                    // Update retry statistics
-                    {
-                        let mut stats = self.stats.write().await;
-                        stats.retry_count += 1;
-                    }
+    /// Send the thoughts of a session still waiting for the server by `deadline`
+    async fn sync_pending(
+        &self,
+        session_id: &str,
+        deadline: Deadline,
+    ) -> SequentialThinkingResult<usize> {
+        let pending = {
+            let sessions = self.sessions.read().await;
+            let session = sessions.get(session_id).ok_or_else(|| {
+                SequentialThinkingError::not_found(format!("Session not found: {session_id}"))
+            })?;
+            session.pending_sync.clone()
+        };
 
-                    // Wait before retrying
-                    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-                }
+        let mut synced = 0;
+        let mut result = Ok(());
+        for thought in pending {
+            if let Err(e) = self
+                .send_thought_to_server(session_id, thought, deadline)
+                .await
+                .0
+            {
+                result = Err(e);
+                break;
             }
+            synced += 1;
+        }
+
+        if synced > 0 {
+            if let Some(session) = self.sessions.write().await.get_mut(session_id) {
+                session.pending_sync.drain(..synced);
+            }
+            self.invalidate_cache(Some(session_id)).await;
+            info!("Resynced {} thought(s) in session {}", synced, session_id);
+        }
+        result.map(|()| synced)
+    }
+
+    /// Get the thoughts of a session still waiting for the server
+    pub async fn pending_sync(&self, session_id: &str) -> Vec<ThoughtData> {
+        self.sessions
+            .read()
+            .await
+            .get(session_id)
+            .map(|session| session.pending_sync.clone())
+            .unwrap_or_default()
+    }
+
+    /// Create a local session on the server before its first thought is sent
+    ///
+    /// A session the server already holds, such as one recovered from an
+    /// auto-save, counts as created.
+    async fn register_session(&self, session_id: &str) -> SequentialThinkingResult<()> {
+        let registered = self
+            .sessions
+            .read()
+            .await
+            .get(session_id)
+            .map(|session| session.registered)
+            .ok_or_else(|| {
+                SequentialThinkingError::not_found(format!("Session not found: {session_id}"))
+            })?;
+        if registered {
+            return Ok(());
         }
+
+        let tool_call = ToolCall {
+            name: "create_session".to_string(),
+            arguments: Some(self.authenticated(serde_json::json!({ "sessionId": session_id }))),
+        };
+        match self.request(tool_call).await {
+            Ok(_) => {}
+            Err(e)
+                if e.error_code() == "VALIDATION_ERROR"
+                    && e.to_string().contains("already exists") => {}
+            Err(e) => return Err(e),
+        }
+        if let Some(session) = self.sessions.write().await.get_mut(session_id) {
+            session.registered = true;
+        }
+        Ok(())
     }
 
-    /// Export a session
+    /// Send a thought to the server, retrying retryable failures until `deadline`
+    ///
+    /// The thought names its session, which is created on the server first
+    /// if needed.
+    /// Errors record the session and thought as well as the tool call. Every
+    /// attempt carries the same correlation ID, which the server logs and
+    /// echoes back in its response.
+    async fn send_thought_to_server(
+        &self,
+        session_id: &str,
+        thought: ThoughtData,
+        deadline: Deadline,
+    ) -> (SequentialThinkingResult<ToolResult>, u64) {
+        let correlation_id = uuid::Uuid::new_v4().to_string();
+        let mut arguments = self.authenticated(thought.to_tool_arguments());
+        if let Some(args) = arguments.as_object_mut() {
+            args.insert("sessionId".to_string(), serde_json::json!(session_id));
+            args.insert(
+                "correlationId".to_string(),
+                serde_json::json!(correlation_id),
+            );
+        }
+        let tool_call = ToolCall {
+            name: "sequential_thinking".to_string(),
+            arguments: Some(arguments),
+        };
+        let span = tracing::info_span!(
+            "send_thought",
+            session_id,
+            thought_number = thought.thought_number,
+            correlation_id = %correlation_id,
+        );
+
+        let mut retries = 0;
+        let result = deadline
+            .run(async {
+                let mut attempts = 0;
+                loop {
+                    let sent = match self.register_session(session_id).await {
+                        Ok(()) => self.request(tool_call.clone()).await,
+                        Err(e) => Err(e),
+                    };
+                    match sent {
+                        Ok(result) => return Ok(result),
+                        Err(e) => {
+                            attempts += 1;
+                            if !e.is_retryable() || attempts >= self.config.max_retry_attempts {
+                                return Err(e);
+                            }
+
+                            retries += 1;
+
+                            // Wait before retrying, as long as a busy server asks
+                            let wait = e.retry_after().unwrap_or(Duration::from_secs(1));
+                            tokio::time::sleep(wait).await;
+                        }
+                    }
+                }
+            })
+            .instrument(span)
+            .await
+            .map_err(|e| {
+                e.with_context(
+                    ErrorContext::new("send_thought")
+                        .with_session(session_id)
+                        .with_thought(thought.thought_number),
+                )
+                .in_tool_call(tool_call.name.clone(), tool_call.arguments.clone())
+            });
+        (result, retries)
+    }
+
+    /// Export a session of the server, from the response cache when it holds the export
     pub async fn export_session(
         &self,
-        _session_id: &str,
+        session_id: &str,
         format: &str,
+        timeout: Option<Duration>,
     ) -> SequentialThinkingResult<String> {
+        if let Some(export) = self
+            .cached("export_session", Some(session_id), format)
+            .await
+        {
+            return Ok(export);
+        }
+
         let args = serde_json::json!({
+            "sessionId": session_id,
             "format": format
         });
 
         let tool_call = ToolCall {
             name: "export_session".to_string(),
-            arguments: Some(args),
+            arguments: Some(self.authenticated(args)),
         };
 
-        let result = self
-            .client
-            .call_tool(tool_call)
-            .await
-            .map_err(|e| SequentialThinkingError::transport_error(e.to_string()))?;
+        let result = self.call_tool(tool_call, timeout).await?;
 
         // Extract content from result
-        if let Some(content) = result.content.first() {
+        let export = if let Some(content) = result.content.first() {
             match content {
                 ToolContent::Text { text } => Ok(text.clone()),
                 _ => Err(SequentialThinkingError::serialization_error(
@@ -425,27 +1062,32 @@ impl SequentialThinkingClient {
             Err(SequentialThinkingError::serialization_error(
                 "No content in export result".to_string(),
             ))
-        }
+        }?;
+
+        self.cache_result("export_session", Some(session_id), format, &export)
+            .await;
+        Ok(export)
     }
 
-    /// Analyze a session
+    /// Analyze a session, from the response cache when it holds the analysis
     pub async fn analyze_session(
         &self,
-        _session_id: &str,
+        session_id: &str,
+        timeout: Option<Duration>,
     ) -> SequentialThinkingResult<serde_json::Value> {
+        if let Some(analysis) = self.cached("analyze_session", Some(session_id), "").await {
+            return Ok(analysis);
+        }
+
         let tool_call = ToolCall {
             name: "analyze_session".to_string(),
-            arguments: Some(serde_json::json!({})),
+            arguments: Some(self.authenticated(serde_json::json!({ "sessionId": session_id }))),
         };
 
-        let result = self
-            .client
-            .call_tool(tool_call)
-            .await
-            .map_err(|e| SequentialThinkingError::transport_error(e.to_string()))?;
+        let result = self.call_tool(tool_call, timeout).await?;
 
         // Extract content from result
-        if let Some(content) = result.content.first() {
+        let analysis: serde_json::Value = if let Some(content) = result.content.first() {
             match content {
                 ToolContent::Text { text } => serde_json::from_str(text)
                     .map_err(|e| SequentialThinkingError::serialization_error(e.to_string())),
@@ -457,23 +1099,411 @@ impl SequentialThinkingClient {
             Err(SequentialThinkingError::serialization_error(
                 "No content in analysis result".to_string(),
             ))
+        }?;
+
+        self.cache_result("analyze_session", Some(session_id), "", &analysis)
+            .await;
+        Ok(analysis)
+    }
+
+    /// Take a checkpoint of the server's current session
+    pub async fn checkpoint_session(
+        &self,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<EngineCheckpoint> {
+        let response = self
+            .call_json_tool("checkpoint_session", serde_json::json!({}), timeout)
+            .await?;
+        serde_json::from_value(response["checkpoint"].clone())
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+    }
+
+    /// Resume the server's current session from a checkpoint
+    pub async fn resume_session(
+        &self,
+        checkpoint: &EngineCheckpoint,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<serde_json::Value> {
+        let response = self
+            .call_json_tool(
+                "resume_session",
+                serde_json::json!({ "checkpoint": checkpoint }),
+                timeout,
+            )
+            .await;
+        self.invalidate_cache(None).await;
+        response
+    }
+
+    /// Correct the text of a thought on the server without adding a revision
+    pub async fn edit_thought(
+        &self,
+        thought_number: u32,
+        new_text: &str,
+        editor: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<serde_json::Value> {
+        let mut arguments = serde_json::json!({
+            "thoughtNumber": thought_number,
+            "thought": new_text
+        });
+        if let Some(editor) = editor {
+            arguments["editor"] = serde_json::json!(editor);
         }
+        let response = self
+            .call_json_tool("edit_thought", arguments, timeout)
+            .await;
+        self.invalidate_cache(None).await;
+        response
     }
 
-    /// Get available tools from the server
-    pub async fn list_tools(&self) -> SequentialThinkingResult<Vec<Tool>> {
-        let tools = self
-            .client
-            .list_tools(ListToolsRequest { cursor: None })
+    /// Abort thought processing in progress on the server
+    pub async fn cancel_session(
+        &self,
+        session_id: &str,
+        reason: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<serde_json::Value> {
+        let mut arguments = serde_json::json!({ "sessionId": session_id });
+        if let Some(reason) = reason {
+            arguments["reason"] = serde_json::json!(reason);
+        }
+        let response = self
+            .call_json_tool("cancel_session", arguments, timeout)
+            .await;
+        self.invalidate_cache(Some(session_id)).await;
+        response
+    }
+
+    /// Pause a session on the server, rejecting its thoughts until resumed
+    pub async fn pause_session(
+        &self,
+        session_id: &str,
+        reason: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<serde_json::Value> {
+        let mut arguments = serde_json::json!({ "sessionId": session_id });
+        if let Some(reason) = reason {
+            arguments["reason"] = serde_json::json!(reason);
+        }
+        let response = self
+            .call_json_tool("pause_session", arguments, timeout)
+            .await;
+        self.invalidate_cache(Some(session_id)).await;
+        response
+    }
+
+    /// Resume a session paused with [`pause_session`](Self::pause_session)
+    pub async fn resume_paused_session(
+        &self,
+        session_id: &str,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<serde_json::Value> {
+        let response = self
+            .call_json_tool(
+                "resume_session",
+                serde_json::json!({ "sessionId": session_id }),
+                timeout,
+            )
+            .await;
+        self.invalidate_cache(Some(session_id)).await;
+        response
+    }
+
+    /// Replay how a session unfolded as timestamped events
+    ///
+    /// Use [`replay_delays`](crate::thinking::replay::replay_delays) to pace
+    /// the events for animation.
+    pub async fn replay_session(
+        &self,
+        session_id: &str,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<Vec<ReplayEvent>> {
+        let response = self
+            .call_json_tool(
+                "replay_session",
+                serde_json::json!({ "sessionId": session_id, "speed": 0 }),
+                timeout,
+            )
+            .await?;
+        serde_json::from_value(response["events"].clone())
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+    }
+
+    /// Attach a reviewer comment to a thought on the server
+    pub async fn annotate_thought(
+        &self,
+        annotation: &Annotation,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<Annotation> {
+        let mut arguments = serde_json::json!({
+            "thoughtNumber": annotation.thought_number,
+            "author": annotation.author,
+            "body": annotation.body
+        });
+        if let Some(ref branch_id) = annotation.branch_id {
+            arguments["branchId"] = serde_json::json!(branch_id);
+        }
+        let response = self
+            .call_json_tool("annotate_thought", arguments, timeout)
+            .await;
+        self.invalidate_cache(None).await;
+        let response = response?;
+        serde_json::from_value(response["annotation"].clone())
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+    }
+
+    /// Mark a reviewer comment on the server as resolved
+    pub async fn resolve_annotation(
+        &self,
+        id: &str,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<Annotation> {
+        let response = self
+            .call_json_tool(
+                "resolve_annotation",
+                serde_json::json!({ "annotationId": id }),
+                timeout,
+            )
+            .await;
+        self.invalidate_cache(None).await;
+        let response = response?;
+        serde_json::from_value(response["annotation"].clone())
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+    }
+
+    /// Call a tool and parse its text content as JSON
+    async fn call_json_tool(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<serde_json::Value> {
+        let tool_call = ToolCall {
+            name: name.to_string(),
+            arguments: Some(self.authenticated(arguments)),
+        };
+
+        let result = self.call_tool(tool_call, timeout).await?;
+
+        match result.content.first() {
+            Some(ToolContent::Text { text }) => serde_json::from_str(text)
+                .map_err(|e| SequentialThinkingError::serialization_error(e.to_string())),
+            _ => Err(SequentialThinkingError::serialization_error(format!(
+                "No text content in {name} result"
+            ))),
+        }
+    }
+
+    /// Merge the thoughts of several sessions on the server
+    pub async fn merge_sessions(
+        &self,
+        session_ids: &[String],
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<serde_json::Value> {
+        let response = self
+            .call_json_tool(
+                "merge_sessions",
+                serde_json::json!({ "sessionIds": session_ids }),
+                timeout,
+            )
+            .await;
+        self.invalidate_cache(None).await;
+        response
+    }
+
+    /// Compare the metrics of two sessions on the server
+    pub async fn compare_sessions(
+        &self,
+        session_a: &str,
+        session_b: &str,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<SessionComparison> {
+        let response = self
+            .call_json_tool(
+                "compare_sessions",
+                serde_json::json!({ "sessionA": session_a, "sessionB": session_b }),
+                timeout,
+            )
+            .await?;
+        serde_json::from_value(response)
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+    }
+
+    /// Export the decisions of the session as an ADR-style Markdown log
+    pub async fn export_decisions(
+        &self,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<String> {
+        let tool_call = ToolCall {
+            name: "export_decisions".to_string(),
+            arguments: Some(self.authenticated(serde_json::json!({ "format": "markdown" }))),
+        };
+
+        let result = self.call_tool(tool_call, timeout).await?;
+
+        match result.content.first() {
+            Some(ToolContent::Text { text }) => Ok(text.clone()),
+            _ => Err(SequentialThinkingError::serialization_error(
+                "No text content in export_decisions result".to_string(),
+            )),
+        }
+    }
+
+    /// Extract the code blocks of the session, optionally only those in `language`
+    pub async fn extract_code(
+        &self,
+        language: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<Vec<CodeBlock>> {
+        let tool_call = ToolCall {
+            name: "extract_code".to_string(),
+            arguments: Some(self.authenticated(serde_json::json!({ "language": language }))),
+        };
+
+        let result = self.call_tool(tool_call, timeout).await?;
+
+        result
+            .content
+            .iter()
+            .map(|content| match content {
+                ToolContent::Text { text } => serde_json::from_str(text)
+                    .map_err(|e| SequentialThinkingError::serialization_error(e.to_string())),
+                _ => Err(SequentialThinkingError::serialization_error(
+                    "No text content in extract_code result".to_string(),
+                )),
+            })
+            .collect()
+    }
+
+    /// Create tracker issues for the session's action items, or for the whole
+    /// session when `source` is `"session"`
+    ///
+    /// `tracker` and `dry_run` fall back to the server's integration settings.
+    pub async fn create_issues(
+        &self,
+        tracker: Option<&str>,
+        source: &str,
+        dry_run: Option<bool>,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<Vec<CreatedIssue>> {
+        let response = self
+            .call_json_tool(
+                "create_issues",
+                serde_json::json!({ "tracker": tracker, "source": source, "dryRun": dry_run }),
+                timeout,
+            )
+            .await?;
+        serde_json::from_value(response["issues"].clone())
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+    }
+
+    /// Estimate the token usage and cost of a session, attributed to `tags`
+    pub async fn cost_report(
+        &self,
+        session_id: &str,
+        tags: &[String],
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<CostReport> {
+        let response = self
+            .call_json_tool(
+                "cost_report",
+                serde_json::json!({ "sessionId": session_id, "tags": tags }),
+                timeout,
+            )
+            .await?;
+        serde_json::from_value(response)
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+    }
+
+    /// Get this month's usage and quota of every API key (admin only)
+    pub async fn usage_report(
+        &self,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<UsageReport> {
+        let response = self
+            .call_json_tool("usage_report", serde_json::json!({}), timeout)
+            .await?;
+        serde_json::from_value(response)
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+    }
+
+    /// Get server version, features, limits and supported export formats
+    pub async fn get_server_info(
+        &self,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<serde_json::Value> {
+        self.call_json_tool("get_server_info", serde_json::json!({}), timeout)
             .await
-            .map_err(|e| SequentialThinkingError::transport_error(e.to_string()))?;
+    }
 
-        Ok(tools.tools)
+    /// Check whether the server supports an export format
+    pub async fn supports_export_format(
+        &self,
+        format: &str,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<bool> {
+        let info = self.get_server_info(timeout).await?;
+        Ok(info["exportFormats"]
+            .as_array()
+            .is_some_and(|formats| formats.iter().any(|f| f.as_str() == Some(format))))
+    }
+
+    /// Poll a session for thoughts recorded after the first `seen` thoughts
+    ///
+    /// If the server holds fewer thoughts than `seen` (e.g. it was restarted),
+    /// the whole thought list is returned again. Only the new thoughts are
+    /// fetched, not a full export of the session.
+    pub async fn poll_session_updates(
+        &self,
+        session_id: &str,
+        seen: usize,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<SessionUpdate> {
+        let arguments = serde_json::json!({ "sessionId": session_id, "since": seen });
+        let response = self
+            .call_json_tool("poll_session", arguments, timeout)
+            .await?;
+
+        let thought_count = response["thoughtCount"].as_u64().unwrap_or(0) as usize;
+        let thoughts: Vec<SharedThought> = serde_json::from_value(response["thoughts"].clone())
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))?;
+
+        let progress = thoughts.last().map(|thought| {
+            let mut progress =
+                ThinkingProgress::new(thought.thought_number, thought.total_thoughts);
+            progress.update(thought);
+            progress
+        });
+
+        Ok(SessionUpdate {
+            thought_count,
+            new_thoughts: thoughts,
+            progress,
+        })
+    }
+
+    /// Get available tools from the server, or from the response cache
+    pub async fn list_tools(
+        &self,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<Vec<Tool>> {
+        if let Some(tools) = self.cached("list_tools", None, "").await {
+            return Ok(tools);
+        }
+
+        let tools = self
+            .deadline(timeout)
+            .run(self.transport.list_tools())
+            .await?;
+
+        self.cache_result("list_tools", None, "", &tools).await;
+        Ok(tools)
     }
 
     /// Get client statistics
     pub async fn get_stats(&self) -> ClientStats {
-        self.stats.read().await.clone()
+        self.stats.snapshot()
     }
 
     /// Get current progress
@@ -483,8 +1513,12 @@ impl SequentialThinkingClient {
     }
 
     /// Update progress tracking
-    async fn update_progress_tracking(&self, thought: &ThoughtData) {
-        let mut tracker = self.progress_tracker.write().await;
+    async fn update_progress_tracking(
+        &self,
+        tracker: &RwLock<ProgressTracker>,
+        thought: &ThoughtData,
+    ) {
+        let mut tracker = tracker.write().await;
         let progress = self.calculate_progress(thought);
 
         if let Some(ref current) = tracker.current_progress {
@@ -550,6 +1584,192 @@ impl SequentialThinkingClient {
     }
 }
 
+/// Handle to one thinking session started by a client
+///
+/// Each handle owns its own progress tracker, statistics and auto-save loop,
+/// so handles for different sessions never observe each other's progress.
+pub struct SessionHandle {
+    /// Session ID
+    pub session_id: String,
+    /// Session title
+    pub title: String,
+    /// Client the session belongs to
+    client: SequentialThinkingClient,
+    /// Progress tracker for this session only
+    progress_tracker: Arc<RwLock<ProgressTracker>>,
+    /// Statistics for this session only
+    stats: Arc<ClientCounters>,
+    /// Background auto-save task, if running
+    auto_save: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl SessionHandle {
+    /// Create a handle for a session already registered with the client
+    fn new(client: SequentialThinkingClient, session_id: String, title: String) -> Self {
+        Self {
+            session_id,
+            title,
+            client,
+            progress_tracker: Arc::new(RwLock::new(ProgressTracker::default())),
+            stats: Arc::new(ClientCounters::default()),
+            auto_save: None,
+        }
+    }
+
+    /// Get the session ID
+    pub fn id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Add a thought to this session, `timeout` overriding the operation timeout
+    pub async fn add_thought(
+        &self,
+        thought: ThoughtData,
+        timeout: Option<Duration>,
+    ) -> SequentialThinkingResult<ThoughtResponse> {
+        self.client
+            .add_thought_tracked(
+                &self.session_id,
+                thought,
+                &self.progress_tracker,
+                Some(&self.stats),
+                timeout,
+            )
+            .await
+    }
+
+    /// Send this session's thoughts the server has not acknowledged yet
+    pub async fn resync(&self, timeout: Option<Duration>) -> SequentialThinkingResult<usize> {
+        self.client.resync_session(&self.session_id, timeout).await
+    }
+
+    /// Get this session's thoughts still waiting for the server
+    pub async fn pending_sync(&self) -> Vec<ThoughtData> {
+        self.client.pending_sync(&self.session_id).await
+    }
+
+    /// Get this session's current progress
+    pub async fn get_progress(&self) -> Option<ThinkingProgress> {
+        self.progress_tracker.read().await.current_progress.clone()
+    }
+
+    /// Get this session's progress tracker
+    pub async fn get_progress_tracker(&self) -> ProgressTracker {
+        self.progress_tracker.read().await.clone()
+    }
+
+    /// Get this session's request statistics
+    pub async fn get_stats(&self) -> ClientStats {
+        self.stats.snapshot()
+    }
+
+    /// Get the thoughts recorded locally for this session
+    pub async fn get_thoughts(&self) -> Vec<SharedThought> {
+        self.snapshot()
+            .await
+            .map(|session| session.engine.all_thoughts().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Get a copy of the local session state
+    pub async fn snapshot(&self) -> Option<ThinkingSession> {
+        self.client.get_session(&self.session_id).await
+    }
+
+    /// Mark this session as complete
+    pub async fn complete(&self) -> SequentialThinkingResult<()> {
+        self.client.complete_session(&self.session_id).await
+    }
+
+    /// Periodically write the local session state to `path`
+    ///
+    /// Uses the client's `auto_save_interval`. Replaces any running auto-save
+    /// loop; the loop stops when the handle is dropped.
+    pub fn start_auto_save(&mut self, path: std::path::PathBuf) {
+        self.stop_auto_save();
+
+        let sessions = Arc::clone(&self.client.sessions);
+        let session_id = self.session_id.clone();
+        let period = std::time::Duration::from_secs(self.client.config.auto_save_interval.max(1));
+
+        self.auto_save = Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+
+                let record = match sessions.read().await.get(&session_id) {
+                    Some(session) => AutoSaveRecord::capture(session),
+                    None => break,
+                };
+                if let Err(e) = Self::write_record(&record, &path) {
+                    tracing::warn!("Auto-save of session {} failed: {}", session_id, e);
+                }
+            }
+        }));
+    }
+
+    /// Write the local session state to the auto-save directory now
+    pub async fn save_now(&self) -> SequentialThinkingResult<std::path::PathBuf> {
+        let record = self
+            .client
+            .sessions
+            .read()
+            .await
+            .get(&self.session_id)
+            .map(AutoSaveRecord::capture)
+            .ok_or_else(|| {
+                SequentialThinkingError::not_found(format!(
+                    "Session not found: {}",
+                    self.session_id
+                ))
+            })?;
+
+        let path = self.client.auto_save_path(&self.session_id);
+        Self::write_record(&record, &path).map_err(SequentialThinkingError::internal_error)?;
+        Ok(path)
+    }
+
+    /// Stop auto-saving and remove the auto-save file, e.g. after a clean exit
+    pub fn discard_auto_save(&mut self) {
+        self.stop_auto_save();
+        let _ = std::fs::remove_file(self.client.auto_save_path(&self.session_id));
+    }
+
+    /// Serialize a record to disk, writing via a temporary file so a crash
+    /// mid-write never leaves a truncated auto-save behind
+    fn write_record(record: &AutoSaveRecord, path: &std::path::Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(record).map_err(|e| e.to_string())?;
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, content).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp_path, path).map_err(|e| e.to_string())
+    }
+
+    /// Stop the auto-save loop if it is running
+    pub fn stop_auto_save(&mut self) {
+        if let Some(task) = self.auto_save.take() {
+            task.abort();
+        }
+    }
+
+    /// Check whether the auto-save loop is running
+    pub fn is_auto_saving(&self) -> bool {
+        self.auto_save
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+    }
+}
+
+impl Drop for SessionHandle {
+    fn drop(&mut self) {
+        self.stop_auto_save();
+    }
+}
+
 impl Clone for ThinkingSession {
     fn clone(&self) -> Self {
         Self {
@@ -559,21 +1779,17 @@ impl Clone for ThinkingSession {
             metadata: self.metadata.clone(),
             created_at: self.created_at,
             last_activity: self.last_activity,
+            pending_sync: self.pending_sync.clone(),
+            registered: self.registered,
         }
     }
 }
 
-impl Clone for ThinkingEngine {
-    fn clone(&self) -> Self {
-        // Note: This is a simplified clone implementation
-        // In a real implementation, you might want to implement proper cloning
-        Self::new()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::thinking::error::RecoveryAdvice;
+    use crate::thinking::transport::{InMemoryTransport, McpTransport};
 
     #[test]
     fn test_client_creation() {
@@ -594,25 +1810,17 @@ mod tests {
 
     #[test]
     fn test_progress_calculation() {
-        // Use dummy ClientInfo and ClientCapabilities for UltraFastClient
-        let client_info = ClientInfo {
-            name: "Test Client".to_string(),
-            version: "0.0.1".to_string(),
-            description: None,
-            homepage: None,
-            repository: None,
-            authors: None,
-            license: None,
-        };
-        let client_capabilities = ClientCapabilities::default();
-        let client = UltraFastClient::new(client_info, client_capabilities);
-
         let client = SequentialThinkingClient {
-            client: Arc::new(client),
+            transport: Arc::new(McpTransport::stdio()),
             config: ClientThinkingConfig::default(),
             sessions: Arc::new(RwLock::new(HashMap::new())),
-            stats: Arc::new(RwLock::new(ClientStats::default())),
+            stats: Arc::new(ClientCounters::default()),
             progress_tracker: Arc::new(RwLock::new(ProgressTracker::default())),
+            api_key: None,
+            request_timeout: None,
+            cache: None,
+            renderer: ThoughtRenderer::default(),
+            approver: None,
         };
 
         let thought = ThoughtData::new("Test thought".to_string(), 3, 5);
@@ -622,4 +1830,403 @@ mod tests {
         assert_eq!(progress.total_thoughts, 5);
         assert_eq!(progress.completed_thoughts, 2);
     }
+
+    #[tokio::test]
+    async fn test_export_names_its_session() {
+        let server = SequentialThinkingServer::new();
+        server.create_session("plan".to_string()).await.unwrap();
+        server
+            .process_session_thought(
+                "plan",
+                ThoughtData::new("Only in the plan".to_string(), 1, 1),
+            )
+            .await
+            .unwrap();
+        let client = SequentialThinkingClient::connect_in_process(server)
+            .await
+            .unwrap();
+
+        let export = client.export_session("plan", "json", None).await.unwrap();
+        assert!(export.contains("Only in the plan"));
+        let default = client
+            .export_session("default", "json", None)
+            .await
+            .unwrap();
+        assert!(!default.contains("Only in the plan"));
+        let missing = client
+            .export_session("unknown", "json", None)
+            .await
+            .unwrap_err();
+        assert_eq!(missing.error_code(), "NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn test_handles_keep_server_sessions_apart() {
+        let server = SequentialThinkingServer::new();
+        let client = SequentialThinkingClient::with_transport(
+            Arc::new(InMemoryTransport::new(server.clone())),
+            ClientThinkingConfig {
+                auto_save_interval: 0,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let first = client.start_session("First".to_string()).await.unwrap();
+        let second = client.start_session("Second".to_string()).await.unwrap();
+        for number in 1..=2 {
+            first
+                .add_thought(
+                    ThoughtData::new(format!("First plan {number}"), number, 2),
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+        second
+            .add_thought(ThoughtData::new("Second plan 1".to_string(), 1, 3), None)
+            .await
+            .unwrap();
+
+        let history = |id: &str| {
+            let server = server.clone();
+            let id = id.to_string();
+            async move {
+                server
+                    .get_session(&id)
+                    .await
+                    .unwrap()
+                    .all_thoughts()
+                    .iter()
+                    .map(|thought| thought.thought.clone())
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(history(first.id()).await, ["First plan 1", "First plan 2"]);
+        assert_eq!(history(second.id()).await, ["Second plan 1"]);
+        let export = client
+            .export_session(second.id(), "json", None)
+            .await
+            .unwrap();
+        assert!(!export.contains("First plan"));
+        let default = client
+            .export_session("default", "json", None)
+            .await
+            .unwrap();
+        assert!(!default.contains("plan"));
+
+        // A client restarted on a recovered session keeps using it
+        let mut recovered = client.get_session(first.id()).await.unwrap();
+        recovered.registered = false;
+        client
+            .sessions
+            .write()
+            .await
+            .insert(first.id().to_string(), recovered);
+        first
+            .add_thought(ThoughtData::new("First plan 3".to_string(), 3, 3), None)
+            .await
+            .unwrap();
+        assert_eq!(history(first.id()).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_poll_returns_each_new_thought_once() {
+        let client = SequentialThinkingClient::connect_in_process(SequentialThinkingServer::new())
+            .await
+            .unwrap();
+        let session = client.start_session("Watched".to_string()).await.unwrap();
+        session
+            .add_thought(
+                ThoughtData::new("First watched thought".to_string(), 1, 3),
+                None,
+            )
+            .await
+            .unwrap();
+        let update = client
+            .poll_session_updates(session.id(), 0, None)
+            .await
+            .unwrap();
+        assert_eq!(update.thought_count, 1);
+        assert_eq!(update.new_thoughts.len(), 1);
+
+        session
+            .add_thought(
+                ThoughtData::new("Second watched thought".to_string(), 2, 3),
+                None,
+            )
+            .await
+            .unwrap();
+        let update = client
+            .poll_session_updates(session.id(), update.thought_count, None)
+            .await
+            .unwrap();
+        assert_eq!(update.thought_count, 2);
+        assert_eq!(update.new_thoughts.len(), 1);
+        assert_eq!(update.new_thoughts[0].thought, "Second watched thought");
+        assert_eq!(update.progress.unwrap().current_thought, 2);
+
+        let update = client
+            .poll_session_updates(session.id(), update.thought_count, None)
+            .await
+            .unwrap();
+        assert!(update.new_thoughts.is_empty());
+        assert!(update.progress.is_none());
+        // A restarted server holding fewer thoughts sends them all again
+        let update = client
+            .poll_session_updates(session.id(), 5, None)
+            .await
+            .unwrap();
+        assert_eq!(update.new_thoughts.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_session_handles_are_isolated() {
+        let client = SequentialThinkingClient {
+            transport: Arc::new(McpTransport::stdio()),
+            config: ClientThinkingConfig {
+                max_retry_attempts: 1,
+                ..Default::default()
+            },
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            stats: Arc::new(ClientCounters::default()),
+            progress_tracker: Arc::new(RwLock::new(ProgressTracker::default())),
+            api_key: None,
+            request_timeout: None,
+            cache: None,
+            renderer: ThoughtRenderer::default(),
+            approver: None,
+        };
+
+        let first = client.start_session("First".to_string()).await.unwrap();
+        let second = client.start_session("Second".to_string()).await.unwrap();
+
+        // No server is connected, so the round trip fails but local state is kept
+        assert!(first
+            .add_thought(
+                ThoughtData::new("First session thought".to_string(), 1, 4),
+                None
+            )
+            .await
+            .is_err());
+        assert!(second
+            .add_thought(
+                ThoughtData::new("Second session thought".to_string(), 3, 9),
+                None
+            )
+            .await
+            .is_err());
+
+        assert_eq!(first.get_progress().await.unwrap().total_thoughts, 4);
+        assert_eq!(second.get_progress().await.unwrap().total_thoughts, 9);
+        assert_eq!(first.get_stats().await.total_requests, 1);
+        assert_eq!(client.get_stats().await.total_requests, 2);
+        assert_eq!(first.get_thoughts().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sync_modes() {
+        let new_client = |sync_mode| SequentialThinkingClient {
+            transport: Arc::new(McpTransport::stdio()),
+            config: ClientThinkingConfig {
+                max_retry_attempts: 1,
+                sync_mode,
+                ..Default::default()
+            },
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            stats: Arc::new(ClientCounters::default()),
+            progress_tracker: Arc::new(RwLock::new(ProgressTracker::default())),
+            api_key: None,
+            request_timeout: None,
+            cache: None,
+            renderer: ThoughtRenderer::default(),
+            approver: None,
+        };
+
+        // No server is connected, so every round trip fails
+        let client = new_client(SyncMode::PendingSync);
+        let handle = client.start_session("Offline".to_string()).await.unwrap();
+        for number in 1..=2 {
+            assert!(handle
+                .add_thought(
+                    ThoughtData::new(format!("Thought {number}"), number, 3),
+                    None
+                )
+                .await
+                .is_err());
+        }
+        assert_eq!(handle.get_thoughts().await.len(), 2);
+        let pending = handle.pending_sync().await;
+        assert_eq!(
+            pending.iter().map(|t| t.thought_number).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert!(handle.resync(None).await.is_err());
+        assert_eq!(handle.pending_sync().await.len(), 2);
+
+        let client = new_client(SyncMode::Rollback);
+        let handle = client.start_session("Offline".to_string()).await.unwrap();
+        assert!(handle
+            .add_thought(ThoughtData::new("Rolled back".to_string(), 1, 3), None)
+            .await
+            .is_err());
+        assert!(handle.get_thoughts().await.is_empty());
+        assert!(handle.pending_sync().await.is_empty());
+        assert_eq!(handle.resync(None).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_operation_timeout() {
+        let client = SequentialThinkingClient {
+            transport: Arc::new(McpTransport::stdio()),
+            config: ClientThinkingConfig {
+                max_retry_attempts: 5,
+                operation_timeout: 60,
+                auto_save_interval: 0,
+                ..Default::default()
+            },
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            stats: Arc::new(ClientCounters::default()),
+            progress_tracker: Arc::new(RwLock::new(ProgressTracker::default())),
+            api_key: None,
+            request_timeout: None,
+            cache: None,
+            renderer: ThoughtRenderer::default(),
+            approver: None,
+        };
+
+        // Failed requests are retried a second apart, so the override cuts
+        // the retries short long before the configured minute
+        let handle = client.start_session("Offline".to_string()).await.unwrap();
+        let started = std::time::Instant::now();
+        let result = handle
+            .add_thought(
+                ThoughtData::new("Too slow".to_string(), 1, 3),
+                Some(Duration::from_millis(50)),
+            )
+            .await;
+        assert!(started.elapsed() < Duration::from_secs(1));
+        let error = result.unwrap_err();
+        assert!(matches!(
+            error.root_cause(),
+            SequentialThinkingError::Timeout { duration } if *duration == Duration::from_millis(50)
+        ));
+        assert_eq!(error.recovery_advice(), RecoveryAdvice::Retry);
+        let (tool, arguments) = error.tool_call().unwrap();
+        assert_eq!(tool, "sequential_thinking");
+        assert_eq!(arguments.unwrap()["thought"], "Too slow");
+        assert_eq!(handle.pending_sync().await.len(), 1);
+        assert_eq!(handle.get_stats().await.error_count, 1);
+
+        let pending = Deadline::after(Duration::from_millis(10))
+            .run(std::future::pending::<SequentialThinkingResult<()>>())
+            .await;
+        assert!(matches!(
+            pending,
+            Err(SequentialThinkingError::Timeout { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_response_cache() {
+        let cache = Arc::new(ResponseCache::new(ResponseCacheConfig::default()));
+        let client = SequentialThinkingClient {
+            transport: Arc::new(McpTransport::stdio()),
+            config: ClientThinkingConfig {
+                max_retry_attempts: 1,
+                auto_save_interval: 0,
+                ..Default::default()
+            },
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            stats: Arc::new(ClientCounters::default()),
+            progress_tracker: Arc::new(RwLock::new(ProgressTracker::default())),
+            api_key: None,
+            request_timeout: None,
+            cache: Some(cache.clone()),
+            renderer: ThoughtRenderer::default(),
+            approver: None,
+        };
+
+        let handle = client.start_session("Cached".to_string()).await.unwrap();
+        let session_id = handle.id().to_string();
+        cache
+            .insert(
+                "export_session",
+                Some(&session_id),
+                "markdown",
+                serde_json::json!("# Cached"),
+            )
+            .await;
+
+        // Repeated exports are answered without reaching the server
+        for _ in 0..2 {
+            let export = client
+                .export_session(&session_id, "markdown", None)
+                .await
+                .unwrap();
+            assert_eq!(export, "# Cached");
+        }
+        assert_eq!(client.get_stats().await.cache_hits, 2);
+
+        // Adding a thought drops the session's results, even if the server
+        // could not be reached
+        let _ = handle
+            .add_thought(ThoughtData::new("Changed".to_string(), 1, 2), None)
+            .await;
+        assert_eq!(cache.version(&session_id).await, 1);
+        assert!(client
+            .export_session(&session_id, "markdown", None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_auto_save_and_recover() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ClientThinkingConfig {
+            max_retry_attempts: 1,
+            auto_save_interval: 0,
+            auto_save_dir: dir.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let new_client = || SequentialThinkingClient {
+            transport: Arc::new(McpTransport::stdio()),
+            config: config.clone(),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            stats: Arc::new(ClientCounters::default()),
+            progress_tracker: Arc::new(RwLock::new(ProgressTracker::default())),
+            api_key: None,
+            request_timeout: None,
+            cache: None,
+            renderer: ThoughtRenderer::default(),
+            approver: None,
+        };
+
+        let client = new_client();
+        let handle = client.start_session("Crashy".to_string()).await.unwrap();
+        assert!(!handle.is_auto_saving());
+        // No server is connected; the thought is still recorded locally
+        let _ = handle
+            .add_thought(
+                ThoughtData::new("Survive the crash".to_string(), 1, 3),
+                None,
+            )
+            .await;
+        handle.save_now().await.unwrap();
+
+        // A fresh client (e.g. after a crash) can see and recover the session
+        let restarted = new_client();
+        let recoverable = restarted.list_recoverable_sessions();
+        assert_eq!(recoverable.len(), 1);
+        assert_eq!(recoverable[0].title, "Crashy");
+        let record = restarted.load_auto_save(&handle.session_id).unwrap();
+        assert_eq!(record.checkpoint.thoughts.len(), 1);
+        assert!(restarted.load_auto_save("missing").is_err());
+
+        let mut recovered = restarted.recover_session(&handle.session_id).await.unwrap();
+        assert_eq!(recovered.get_thoughts().await.len(), 1);
+
+        recovered.discard_auto_save();
+        assert!(restarted.list_recoverable_sessions().is_empty());
+    }
 }
@@ -0,0 +1,126 @@
+//! # Clock
+//!
+//! Source of the current time for the engine, sessions and analytics.
+//!
+//! Thought timestamps, edit times, session expiry and processing times are
+//! all read from a [`Clock`]. Production code uses the [`SystemClock`];
+//! tests inject a [`TestClock`] and move time forward by hand, so expiry,
+//! durations and throughput can be asserted exactly.
+
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+
+/// Source of the current time
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    /// Get the current time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Clock shared between the components reading it
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Clock reading the system time
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// Create a system clock ready to share
+    pub fn shared() -> SharedClock {
+        Arc::new(Self)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+#[derive(Debug)]
+struct TestClockState {
+    now: DateTime<Utc>,
+    step: chrono::Duration,
+}
+
+/// Clock that only moves when told to
+///
+/// Clones share their time, so a test can keep one clone to advance while
+/// the code under test reads another.
+#[derive(Debug, Clone)]
+pub struct TestClock {
+    state: Arc<Mutex<TestClockState>>,
+}
+
+impl TestClock {
+    /// Create a clock stopped at `start`
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(TestClockState {
+                now: start,
+                step: chrono::Duration::zero(),
+            })),
+        }
+    }
+
+    /// Advance the clock by `step` after every reading
+    ///
+    /// Makes measured durations non-zero, e.g. one step of processing time
+    /// per thought.
+    pub fn with_step(self, step: chrono::Duration) -> Self {
+        self.state().step = step;
+        self
+    }
+
+    /// Move the clock forward by `duration`
+    pub fn advance(&self, duration: chrono::Duration) {
+        self.state().now += duration;
+    }
+
+    /// Set the clock to `at`
+    pub fn set(&self, at: DateTime<Utc>) {
+        self.state().now = at;
+    }
+
+    /// Share the clock as a [`SharedClock`]
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, TestClockState> {
+        self.state.lock().expect("test clock poisoned")
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> DateTime<Utc> {
+        let mut state = self.state();
+        let now = state.now;
+        state.now = now + state.step;
+        now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_clock() {
+        let start = Utc::now();
+        let clock = TestClock::new(start);
+        let shared = clock.shared();
+        assert_eq!(shared.now(), start);
+        assert_eq!(shared.now(), start);
+
+        clock.advance(chrono::Duration::minutes(5));
+        assert_eq!(shared.now(), start + chrono::Duration::minutes(5));
+
+        let stepping = TestClock::new(start).with_step(chrono::Duration::seconds(1));
+        assert_eq!(stepping.now(), start);
+        assert_eq!(stepping.now(), start + chrono::Duration::seconds(1));
+
+        clock.set(start);
+        assert_eq!(shared.now(), start);
+        assert!(SystemClock.now() >= start);
+    }
+}
@@ -0,0 +1,266 @@
+//! # Code Blocks
+//!
+//! Detection of fenced code blocks in thought text.
+//!
+//! Thoughts often carry code between ```` ``` ```` or `~~~` fences. This
+//! module splits thought text into prose and code, lets exports re-fence code
+//! so an unclosed or nested fence cannot swallow the rest of a document, and
+//! collects every code block of a session, including code attachments, as
+//! separate artifacts for the `extract_code` tool.
+
+use serde::{Deserialize, Serialize};
+
+use crate::thinking::attachment::Attachment;
+use crate::thinking::SharedThought;
+
+/// Part of a thought's text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    /// Prose between code blocks
+    Text(&'a str),
+    /// Fenced code block
+    Code {
+        /// Language from the fence's info string
+        language: Option<&'a str>,
+        /// Code without the fences
+        content: &'a str,
+    },
+}
+
+/// Opening fence of a code block: fence character, fence length and info string
+fn opening_fence(line: &str) -> Option<(char, usize, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let line = &line[indent..];
+    let marker = line.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let length = line.len() - line.trim_start_matches(marker).len();
+    let info = line[length..].trim();
+    // Backtick fences cannot have backticks in their info string
+    if length < 3 || (marker == '`' && info.contains('`')) {
+        return None;
+    }
+    Some((marker, length, info))
+}
+
+/// Check whether a line closes a code block opened with `marker` x `length`
+fn is_closing_fence(line: &str, marker: char, length: usize) -> bool {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return false;
+    }
+    let run = trimmed.len() - trimmed.trim_start_matches(marker).len();
+    run >= length && trimmed[run..].trim().is_empty()
+}
+
+/// Split text into prose and fenced code blocks
+///
+/// A fence that is never closed runs to the end of the text, as in CommonMark.
+pub fn split_code(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut offset = 0;
+    let mut open: Option<(char, usize, Option<&str>, usize)> = None;
+
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let bare = line.trim_end_matches(['\n', '\r']);
+        match open {
+            None => {
+                if let Some((marker, length, info)) = opening_fence(bare) {
+                    if text_start < line_start {
+                        segments.push(Segment::Text(&text[text_start..line_start]));
+                    }
+                    let language = info.split_whitespace().next();
+                    open = Some((marker, length, language, offset));
+                }
+            }
+            Some((marker, length, language, code_start)) => {
+                if is_closing_fence(bare, marker, length) {
+                    segments.push(Segment::Code {
+                        language,
+                        content: &text[code_start..line_start],
+                    });
+                    open = None;
+                    text_start = offset;
+                }
+            }
+        }
+    }
+    match open {
+        Some((_, _, language, code_start)) => segments.push(Segment::Code {
+            language,
+            content: &text[code_start..],
+        }),
+        None if text_start < text.len() => segments.push(Segment::Text(&text[text_start..])),
+        None => {}
+    }
+    segments
+}
+
+/// Backtick fence longer than any backtick run in `content`
+pub fn fence_for(content: &str) -> String {
+    let longest = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+/// Markdown fenced code block holding `content`
+pub fn fenced(content: &str, language: Option<&str>) -> String {
+    let fence = fence_for(content);
+    format!(
+        "{fence}{}\n{}\n{fence}\n",
+        language.unwrap_or(""),
+        content.trim_end_matches(['\n', '\r'])
+    )
+}
+
+/// Re-fence the code blocks of thought text so each one is closed
+pub fn normalize_fences(text: &str) -> String {
+    let mut markdown = String::with_capacity(text.len());
+    for segment in split_code(text) {
+        match segment {
+            Segment::Text(prose) => markdown.push_str(prose),
+            Segment::Code { language, content } => {
+                if !markdown.is_empty() && !markdown.ends_with('\n') {
+                    markdown.push('\n');
+                }
+                markdown.push_str(&fenced(content, language));
+            }
+        }
+    }
+    markdown.trim_end().to_string()
+}
+
+/// Where a code block was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeSource {
+    /// Fenced block in the thought text
+    Text,
+    /// Code attachment
+    Attachment,
+}
+
+/// Code block extracted from a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeBlock {
+    /// Number of the thought holding the code
+    pub thought_number: u32,
+    /// Branch of the thought, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_id: Option<String>,
+    /// Position of the block among the thought's code blocks, starting at 1
+    pub index: usize,
+    /// Where the block was found
+    pub source: CodeSource,
+    /// Language of the code, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// File the code comes from, for attachments that name one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// Code text
+    pub content: String,
+}
+
+/// Collect the code blocks of a session's thoughts, in thought order
+///
+/// Fenced blocks in a thought's text come before its code attachments.
+pub fn extract_code(thoughts: &[SharedThought]) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    for thought in thoughts {
+        let block = |index, source, language: Option<&str>, filename, content: &str| CodeBlock {
+            thought_number: thought.thought_number,
+            branch_id: thought.branch_id.clone(),
+            index,
+            source,
+            language: language.map(str::to_string),
+            filename,
+            content: content.trim_end_matches(['\n', '\r']).to_string(),
+        };
+        let fenced = split_code(&thought.thought)
+            .into_iter()
+            .filter_map(|segment| match segment {
+                Segment::Code { language, content } => Some((language, content)),
+                Segment::Text(_) => None,
+            });
+        let mut index = 0;
+        for (language, content) in fenced {
+            index += 1;
+            blocks.push(block(index, CodeSource::Text, language, None, content));
+        }
+        for attachment in &thought.attachments {
+            if let Attachment::Code {
+                content,
+                language,
+                filename,
+            } = attachment
+            {
+                index += 1;
+                blocks.push(block(
+                    index,
+                    CodeSource::Attachment,
+                    language.as_deref(),
+                    filename.clone(),
+                    content,
+                ));
+            }
+        }
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::ThoughtData;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_extract_code() {
+        let text = "Try this:\n```rust\nfn main() {}\n```\nor\n~~~~ python extra\nprint('~~~')\n~~~~\nThen ```sh\nls\n";
+        assert_eq!(
+            split_code(text),
+            vec![
+                Segment::Text("Try this:\n"),
+                Segment::Code {
+                    language: Some("rust"),
+                    content: "fn main() {}\n"
+                },
+                Segment::Text("or\n"),
+                Segment::Code {
+                    language: Some("python"),
+                    content: "print('~~~')\n"
+                },
+                Segment::Text("Then ```sh\nls\n"),
+            ]
+        );
+
+        // Unclosed fences are closed, and fences outgrow backticks in the code
+        assert_eq!(
+            normalize_fences("Before\n```js\nconst s = `a` + ```;"),
+            "Before\n````js\nconst s = `a` + ```;\n````"
+        );
+
+        let thoughts: Vec<SharedThought> = vec![
+            ThoughtData::new("No code".to_string(), 1, 2),
+            ThoughtData::branch(text.to_string(), 2, 1, "alt".to_string())
+                .with_attachment(Attachment::code("SELECT 1;", Some("sql"))),
+        ]
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+        let blocks = extract_code(&thoughts);
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].content, "fn main() {}");
+        assert_eq!(blocks[0].branch_id.as_deref(), Some("alt"));
+        assert_eq!(blocks[1].language.as_deref(), Some("python"));
+        assert_eq!(
+            (blocks[2].index, blocks[2].source),
+            (3, CodeSource::Attachment)
+        );
+    }
+}
@@ -0,0 +1,944 @@
+//! # CORS
+//!
+//! Cross-origin policy for the HTTP transport.
+//!
+//! The MCP framework's HTTP listener has no CORS settings of its own, so the
+//! server runs it on a loopback port behind a small gateway. The gateway
+//! answers preflight requests, rejects origins not listed in
+//! `security.allowed_origins` (which also guards against DNS rebinding) and
+//! proxies everything else, including SSE streams, to the framework. On the
+//! way back it adds `structuredContent` to tool results (see
+//! [`structured`](crate::thinking::structured)). The gateway also serves the
+//! admin usage report at `/usage`.
+
+use std::fmt;
+
+use crate::config::SecurityConfig;
+
+/// Methods browsers may use against the server
+pub const ALLOWED_METHODS: &[&str] = &["GET", "POST", "DELETE", "OPTIONS"];
+
+/// Request headers browsers may send
+pub const ALLOWED_HEADERS: &[&str] = &[
+    "accept",
+    "authorization",
+    "content-type",
+    "last-event-id",
+    "mcp-protocol-version",
+    "mcp-session-id",
+    "x-api-key",
+];
+
+/// Response headers scripts may read
+pub const EXPOSED_HEADERS: &[&str] = &["mcp-protocol-version", "mcp-session-id"];
+
+/// Default lifetime of a cached preflight response, in seconds
+pub const DEFAULT_MAX_AGE_SECONDS: u64 = 600;
+
+/// Why a cross-origin request was refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorsRejection {
+    /// The origin is not in the allowed list
+    OriginNotAllowed(String),
+    /// The preflight asked for a method the server does not accept
+    MethodNotAllowed(String),
+    /// The preflight asked for a header the server does not accept
+    HeaderNotAllowed(String),
+}
+
+impl fmt::Display for CorsRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorsRejection::OriginNotAllowed(origin) => write!(f, "Origin not allowed: {origin}"),
+            CorsRejection::MethodNotAllowed(method) => write!(f, "Method not allowed: {method}"),
+            CorsRejection::HeaderNotAllowed(header) => write!(f, "Header not allowed: {header}"),
+        }
+    }
+}
+
+impl std::error::Error for CorsRejection {}
+
+/// Cross-origin policy built from the allowed origins
+///
+/// Origins are matched case-insensitively and may be `*` (any origin), an
+/// exact origin (`https://app.example.com`), an origin with any port
+/// (`http://localhost:*`) or any subdomain (`https://*.example.com`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorsPolicy {
+    allowed_origins: Vec<String>,
+    max_age_seconds: u64,
+}
+
+impl CorsPolicy {
+    /// Create a policy allowing the given origins
+    pub fn new(allowed_origins: Vec<String>) -> Self {
+        Self {
+            allowed_origins: allowed_origins
+                .into_iter()
+                .map(|origin| origin.trim().trim_end_matches('/').to_lowercase())
+                .filter(|origin| !origin.is_empty())
+                .collect(),
+            max_age_seconds: DEFAULT_MAX_AGE_SECONDS,
+        }
+    }
+
+    /// Create a policy from the security configuration
+    pub fn from_config(security: &SecurityConfig) -> Self {
+        Self::new(security.allowed_origins.clone())
+    }
+
+    /// Set how long browsers may cache preflight responses
+    pub fn with_max_age(mut self, seconds: u64) -> Self {
+        self.max_age_seconds = seconds;
+        self
+    }
+
+    /// Check whether any origin is allowed
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|origin| origin == "*")
+    }
+
+    /// Check whether an origin is allowed
+    pub fn is_allowed(&self, origin: &str) -> bool {
+        let origin = origin.trim_end_matches('/').to_lowercase();
+        self.allowed_origins
+            .iter()
+            .any(|pattern| origin_matches(pattern, &origin))
+    }
+
+    /// CORS headers for a regular request
+    ///
+    /// Requests without an `Origin` header (non-browser clients) get no
+    /// headers and are not refused.
+    pub fn response_headers(
+        &self,
+        origin: Option<&str>,
+    ) -> Result<Vec<(&'static str, String)>, CorsRejection> {
+        let Some(origin) = origin else {
+            return Ok(Vec::new());
+        };
+        if !self.is_allowed(origin) {
+            return Err(CorsRejection::OriginNotAllowed(origin.to_string()));
+        }
+        Ok(vec![
+            ("access-control-allow-origin", origin.to_string()),
+            ("access-control-expose-headers", EXPOSED_HEADERS.join(", ")),
+            ("vary", "Origin".to_string()),
+        ])
+    }
+
+    /// CORS headers answering a preflight request
+    ///
+    /// `method` and `headers` are the values of the
+    /// `Access-Control-Request-Method` and `Access-Control-Request-Headers`
+    /// headers.
+    pub fn preflight_headers(
+        &self,
+        origin: &str,
+        method: &str,
+        headers: Option<&str>,
+    ) -> Result<Vec<(&'static str, String)>, CorsRejection> {
+        if !self.is_allowed(origin) {
+            return Err(CorsRejection::OriginNotAllowed(origin.to_string()));
+        }
+        let method = method.trim().to_uppercase();
+        if !ALLOWED_METHODS.contains(&method.as_str()) {
+            return Err(CorsRejection::MethodNotAllowed(method));
+        }
+        let requested: Vec<String> = headers
+            .unwrap_or_default()
+            .split(',')
+            .map(|header| header.trim().to_lowercase())
+            .filter(|header| !header.is_empty())
+            .collect();
+        if let Some(header) = requested
+            .iter()
+            .find(|header| !ALLOWED_HEADERS.contains(&header.as_str()))
+        {
+            return Err(CorsRejection::HeaderNotAllowed(header.clone()));
+        }
+
+        Ok(vec![
+            ("access-control-allow-origin", origin.to_string()),
+            ("access-control-allow-methods", ALLOWED_METHODS.join(", ")),
+            ("access-control-allow-headers", ALLOWED_HEADERS.join(", ")),
+            ("access-control-max-age", self.max_age_seconds.to_string()),
+            (
+                "vary",
+                "Origin, Access-Control-Request-Method, Access-Control-Request-Headers".to_string(),
+            ),
+        ])
+    }
+}
+
+/// Match a lowercase origin against a lowercase allowed-origin pattern
+fn origin_matches(pattern: &str, origin: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+    if let Some(base) = pattern.strip_suffix(":*") {
+        return origin == base
+            || origin
+                .strip_prefix(base)
+                .and_then(|rest| rest.strip_prefix(':'))
+                .is_some_and(|port| !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()));
+    }
+    if let Some((scheme, domain)) = pattern.split_once("://*.") {
+        return origin
+            .strip_prefix(scheme)
+            .and_then(|rest| rest.strip_prefix("://"))
+            .and_then(|host| host.strip_suffix(domain))
+            .and_then(|subdomain| subdomain.strip_suffix('.'))
+            .is_some_and(|subdomain| !subdomain.is_empty() && !subdomain.contains('/'));
+    }
+    pattern == origin
+}
+
+/// Gateway enforcing a [`CorsPolicy`] in front of the MCP HTTP listener
+#[cfg(feature = "http-transport")]
+pub mod gateway {
+    use std::future::Future;
+    use std::sync::atomic::{AtomicU16, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use axum::body::{Body, Bytes};
+    use axum::extract::{Request, State};
+    use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+    use axum::response::{IntoResponse, Response};
+    use axum::Router;
+    use futures_util::{stream, StreamExt};
+    use tracing::warn;
+
+    use super::CorsPolicy;
+    use crate::admin::ADMIN_PATH;
+    use crate::config::Role;
+    use crate::thinking::error::SequentialThinkingError;
+    use crate::thinking::server::{SequentialThinkingServer, DEFAULT_DASHBOARD_DAYS};
+    use crate::thinking::structured::{add_structured_content, SseRewriter};
+
+    /// Path of the admin usage report
+    pub const USAGE_PATH: &str = "/usage";
+
+    /// Largest request body forwarded to the MCP listener
+    const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+    /// Attempts at starting the MCP listener on a free loopback port
+    const UPSTREAM_ATTEMPTS: usize = 3;
+
+    /// Headers that describe a single connection and are not forwarded
+    const HOP_BY_HOP: &[&str] = &[
+        "connection",
+        "host",
+        "keep-alive",
+        "proxy-connection",
+        "te",
+        "trailer",
+        "transfer-encoding",
+        "upgrade",
+    ];
+
+    struct Gateway {
+        policy: CorsPolicy,
+        client: reqwest::Client,
+        upstream: UpstreamPort,
+        server: SequentialThinkingServer,
+    }
+
+    /// Loopback port the MCP listener behind the gateway runs on
+    #[derive(Debug, Clone, Default)]
+    pub struct UpstreamPort(Arc<AtomicU16>);
+
+    impl UpstreamPort {
+        /// Port of a listener that is already running
+        pub fn new(port: u16) -> Self {
+            Self(Arc::new(AtomicU16::new(port)))
+        }
+
+        /// Current port
+        pub fn get(&self) -> u16 {
+            self.0.load(Ordering::Relaxed)
+        }
+
+        fn set(&self, port: u16) {
+            self.0.store(port, Ordering::Relaxed);
+        }
+    }
+
+    /// Run the MCP listener on a free loopback port, recording it in `upstream`
+    ///
+    /// `start` runs the framework's HTTP listener on the port it is given.
+    /// The framework binds the port itself, so another process can take a
+    /// port found free before it does; the listener is then started again on
+    /// a new port.
+    pub async fn run_upstream<F, Fut, E>(
+        upstream: &UpstreamPort,
+        mut start: F,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+    where
+        F: FnMut(u16) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let mut attempt = 1;
+        loop {
+            let port = std::net::TcpListener::bind(("127.0.0.1", 0))?
+                .local_addr()?
+                .port();
+            upstream.set(port);
+            match start(port).await {
+                Err(e) => {
+                    let e = e.into();
+                    if attempt == UPSTREAM_ATTEMPTS || !e.to_string().contains("Failed to bind") {
+                        return Err(e);
+                    }
+                    warn!("MCP listener could not bind port {}: {}", port, e);
+                    attempt += 1;
+                }
+                Ok(()) => return Ok(()),
+            }
+        }
+    }
+
+    /// Serve the gateway on a bound listener, proxying to the MCP listener
+    ///
+    /// `server` shares its state with the one behind the MCP listener and
+    /// answers the admin endpoints.
+    pub async fn serve(
+        policy: CorsPolicy,
+        server: SequentialThinkingServer,
+        listener: tokio::net::TcpListener,
+        upstream: UpstreamPort,
+    ) -> std::io::Result<()> {
+        axum::serve(listener, router(policy, server, upstream)).await
+    }
+
+    /// Router answering preflights and proxying allowed requests
+    fn router(
+        policy: CorsPolicy,
+        server: SequentialThinkingServer,
+        upstream: UpstreamPort,
+    ) -> Router {
+        let gateway = Arc::new(Gateway {
+            policy,
+            client: reqwest::Client::new(),
+            upstream,
+            server,
+        });
+        Router::new().fallback(handle).with_state(gateway)
+    }
+
+    async fn handle(State(gateway): State<Arc<Gateway>>, request: Request) -> Response {
+        let [origin, preflight_method, requested_headers] = [
+            "origin",
+            "access-control-request-method",
+            "access-control-request-headers",
+        ]
+        .map(|name| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        });
+
+        if let (true, Some(origin), Some(method)) = (
+            request.method() == Method::OPTIONS,
+            &origin,
+            preflight_method,
+        ) {
+            return match gateway.policy.preflight_headers(
+                origin,
+                &method,
+                requested_headers.as_deref(),
+            ) {
+                Ok(headers) => with_headers(StatusCode::NO_CONTENT.into_response(), headers),
+                Err(rejection) => {
+                    warn!("Rejected CORS preflight: {}", rejection);
+                    (StatusCode::FORBIDDEN, rejection.to_string()).into_response()
+                }
+            };
+        }
+
+        let cors_headers = match gateway.policy.response_headers(origin.as_deref()) {
+            Ok(headers) => headers,
+            Err(rejection) => {
+                warn!("Rejected cross-origin request: {}", rejection);
+                return (StatusCode::FORBIDDEN, rejection.to_string()).into_response();
+            }
+        };
+
+        if request.uri().path() == USAGE_PATH {
+            let response = usage(&gateway.server, request.method(), request.headers()).await;
+            return with_headers(response, cors_headers);
+        }
+        if let Some(path) = request.uri().path().strip_prefix(ADMIN_PATH) {
+            if path.is_empty() || path.starts_with('/') {
+                let response = admin(
+                    &gateway.server,
+                    request.method(),
+                    path,
+                    request.uri().query(),
+                    request.headers(),
+                )
+                .await;
+                return with_headers(response, cors_headers);
+            }
+        }
+
+        match forward(&gateway, request).await {
+            Ok(response) => with_headers(response, cors_headers),
+            Err(e) => {
+                warn!("Failed to reach the MCP listener: {}", e);
+                (StatusCode::BAD_GATEWAY, "MCP listener unavailable").into_response()
+            }
+        }
+    }
+
+    /// Answer the usage report to admins
+    async fn usage(
+        server: &SequentialThinkingServer,
+        method: &Method,
+        headers: &HeaderMap,
+    ) -> Response {
+        if method != Method::GET {
+            return StatusCode::METHOD_NOT_ALLOWED.into_response();
+        }
+        match admin_rejection(server, headers, "read usage") {
+            Some(rejection) => rejection,
+            None => axum::Json(server.usage_report().await).into_response(),
+        }
+    }
+
+    /// Answer an admin request for `path`, relative to [`ADMIN_PATH`]
+    async fn admin(
+        server: &SequentialThinkingServer,
+        method: &Method,
+        path: &str,
+        query: Option<&str>,
+        headers: &HeaderMap,
+    ) -> Response {
+        if let Some(rejection) = admin_rejection(server, headers, "administer the server") {
+            return rejection;
+        }
+        let segments: Vec<String> = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                percent_encoding::percent_decode_str(segment)
+                    .decode_utf8_lossy()
+                    .into_owned()
+            })
+            .collect();
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+        match (method.as_str(), segments.as_slice()) {
+            ("GET", ["sessions"]) => axum::Json(server.live_sessions().await).into_response(),
+            ("GET", ["stats"]) => axum::Json(server.admin_stats().await).into_response(),
+            ("GET", ["analytics"]) => {
+                let days = query
+                    .into_iter()
+                    .flat_map(|query| query.split('&'))
+                    .find_map(|pair| pair.strip_prefix("days="))
+                    .map(|days| days.parse::<u32>())
+                    .unwrap_or(Ok(DEFAULT_DASHBOARD_DAYS));
+                let dashboard = match days {
+                    Ok(days) => server.analytics_dashboard(days).await,
+                    Err(e) => Err(SequentialThinkingError::validation_error(format!(
+                        "Invalid days: {e}"
+                    ))),
+                };
+                match dashboard {
+                    Ok(dashboard) => axum::Json(dashboard).into_response(),
+                    Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+                }
+            }
+            ("DELETE", ["sessions", session_id]) => {
+                if server.remove_session(session_id).await {
+                    StatusCode::NO_CONTENT.into_response()
+                } else {
+                    (StatusCode::NOT_FOUND, format!("session '{session_id}'")).into_response()
+                }
+            }
+            ("GET", ["sessions", session_id, "export"]) => {
+                let format = query
+                    .into_iter()
+                    .flat_map(|query| query.split('&'))
+                    .find_map(|pair| pair.strip_prefix("format="))
+                    .unwrap_or("json");
+                let content_type = match format {
+                    "markdown" => "text/markdown; charset=utf-8",
+                    _ => "application/json",
+                };
+                match server.export_session(session_id, format).await {
+                    Ok(content) => ([("content-type", content_type)], content).into_response(),
+                    Err(SequentialThinkingError::NotFound { resource }) => {
+                        (StatusCode::NOT_FOUND, resource).into_response()
+                    }
+                    Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+                }
+            }
+            (
+                _,
+                ["sessions"]
+                | ["stats"]
+                | ["analytics"]
+                | ["sessions", _]
+                | ["sessions", _, "export"],
+            ) => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+            _ => (StatusCode::NOT_FOUND, format!("admin path '{path}'")).into_response(),
+        }
+    }
+
+    /// Refusal of a caller without the admin role, or `None` for admins
+    ///
+    /// The API key is read from the `x-api-key` header or a bearer token.
+    fn admin_rejection(
+        server: &SequentialThinkingServer,
+        headers: &HeaderMap,
+        action: &str,
+    ) -> Option<Response> {
+        let api_key = headers
+            .get("x-api-key")
+            .and_then(|value| value.to_str().ok())
+            .or_else(|| {
+                headers
+                    .get("authorization")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.strip_prefix("Bearer "))
+            });
+        match server.security_config().role_for_key(api_key) {
+            Some(Role::Admin) => None,
+            Some(role) => Some(
+                (
+                    StatusCode::FORBIDDEN,
+                    format!("Role '{role}' is not allowed to {action} (requires 'admin')"),
+                )
+                    .into_response(),
+            ),
+            None => Some((StatusCode::UNAUTHORIZED, "Missing or unknown API key").into_response()),
+        }
+    }
+
+    /// Proxy a request to the MCP listener, streaming the response back
+    async fn forward(
+        gateway: &Gateway,
+        request: Request,
+    ) -> Result<Response, Box<dyn std::error::Error + Send + Sync>> {
+        let (parts, body) = request.into_parts();
+        let path = parts
+            .uri
+            .path_and_query()
+            .map(|path| path.as_str())
+            .unwrap_or("/");
+        let method = reqwest::Method::from_bytes(parts.method.as_str().as_bytes())?;
+
+        let mut upstream = gateway
+            .client
+            .request(
+                method,
+                format!("http://127.0.0.1:{}{}", gateway.upstream.get(), path),
+            )
+            .body(axum::body::to_bytes(body, MAX_BODY_BYTES).await?);
+        // The origin was checked against the policy above; the MCP listener
+        // only accepts its own default origin and would refuse the rest
+        for (name, value) in &parts.headers {
+            if !HOP_BY_HOP.contains(&name.as_str()) && name != "origin" {
+                upstream = upstream.header(name.as_str(), value.as_bytes());
+            }
+        }
+        let upstream = upstream.send().await?;
+
+        // Tool results gain structuredContent, which changes the body length
+        let content_type = upstream
+            .headers()
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let mut response = Response::builder().status(upstream.status().as_u16());
+        for (name, value) in upstream.headers() {
+            if !HOP_BY_HOP.contains(&name.as_str()) && name != "content-length" {
+                response = response.header(name.as_str(), value.as_bytes());
+            }
+        }
+
+        let body = if content_type.starts_with("application/json") {
+            let bytes = upstream.bytes().await?;
+            match serde_json::from_slice::<serde_json::Value>(&bytes) {
+                Ok(mut message) => match add_structured_content(&mut message) {
+                    true => Body::from(message.to_string()),
+                    false => Body::from(bytes),
+                },
+                Err(_) => Body::from(bytes),
+            }
+        } else if content_type.starts_with("text/event-stream") {
+            let rewriter = Arc::new(Mutex::new(SseRewriter::new()));
+            let finisher = rewriter.clone();
+            let events = upstream.bytes_stream().map(move |chunk| {
+                chunk.map(|chunk| Bytes::from(rewriter.lock().unwrap().push(&chunk)))
+            });
+            let rest =
+                stream::once(async move { Ok(Bytes::from(finisher.lock().unwrap().finish())) });
+            Body::from_stream(events.chain(rest))
+        } else {
+            Body::from_stream(upstream.bytes_stream())
+        };
+        Ok(response.body(body)?)
+    }
+
+    fn with_headers(mut response: Response, headers: Vec<(&'static str, String)>) -> Response {
+        for (name, value) in headers {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                response
+                    .headers_mut()
+                    .insert(HeaderName::from_static(name), value);
+            }
+        }
+        response
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::admin::AdminClient;
+        use crate::config::SecurityConfig;
+        use crate::usage::UsageMetric;
+        use std::collections::HashMap;
+
+        #[tokio::test]
+        async fn test_gateway_enforces_origins() {
+            let upstream = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+                .await
+                .unwrap();
+            let upstream_port = UpstreamPort::new(upstream.local_addr().unwrap().port());
+            tokio::spawn(async move {
+                let echo = Router::new().fallback(|request: Request| async move {
+                    let origin = request.headers().get("origin").is_some();
+                    format!("{} {} origin={origin}", request.method(), request.uri())
+                });
+                axum::serve(upstream, echo).await.unwrap();
+            });
+
+            let policy = CorsPolicy::new(vec!["http://localhost:*".to_string()]);
+            let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+                .await
+                .unwrap();
+            let gateway = format!("http://{}", listener.local_addr().unwrap());
+            tokio::spawn(async move {
+                axum::serve(
+                    listener,
+                    router(policy, SequentialThinkingServer::new(), upstream_port),
+                )
+                .await
+                .unwrap();
+            });
+
+            let client = reqwest::Client::new();
+            let response = client
+                .post(format!("{gateway}/mcp?x=1"))
+                .header("origin", "http://localhost:5173")
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(
+                response.headers()["access-control-allow-origin"],
+                "http://localhost:5173"
+            );
+            assert_eq!(response.text().await.unwrap(), "POST /mcp?x=1 origin=false");
+
+            let response = client
+                .post(format!("{gateway}/mcp"))
+                .header("origin", "http://evil.example")
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 403);
+
+            let response = client
+                .request(reqwest::Method::OPTIONS, format!("{gateway}/mcp"))
+                .header("origin", "http://localhost:5173")
+                .header("access-control-request-method", "POST")
+                .header(
+                    "access-control-request-headers",
+                    "content-type, mcp-session-id",
+                )
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 204);
+            assert!(response.headers()["access-control-allow-methods"]
+                .to_str()
+                .unwrap()
+                .contains("POST"));
+
+            // Non-browser clients send no origin and pass through
+            let response = client.get(format!("{gateway}/mcp")).send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert!(response
+                .headers()
+                .get("access-control-allow-origin")
+                .is_none());
+        }
+
+        #[tokio::test]
+        async fn test_gateway_reaches_mcp_listener() {
+            let mcp_server = SequentialThinkingServer::new().create_mcp_server();
+            let upstream = UpstreamPort::default();
+            tokio::spawn({
+                let upstream = upstream.clone();
+                async move {
+                    run_upstream(&upstream, |port| {
+                        mcp_server.run_streamable_http("127.0.0.1", port)
+                    })
+                    .await
+                }
+            });
+
+            let policy = CorsPolicy::new(vec!["https://app.example.com".to_string()]);
+            let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+                .await
+                .unwrap();
+            let gateway = format!("http://{}/mcp", listener.local_addr().unwrap());
+            tokio::spawn(serve(
+                policy,
+                SequentialThinkingServer::new(),
+                listener,
+                upstream,
+            ));
+
+            let client = reqwest::Client::new();
+            let initialize = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {},
+                    "clientInfo": { "name": "browser", "version": "1.0" }
+                }
+            });
+            // The listener may still be starting
+            let mut response = None;
+            for _ in 0..100 {
+                let sent = client
+                    .post(&gateway)
+                    .header("origin", "https://app.example.com")
+                    .header("accept", "application/json, text/event-stream")
+                    .json(&initialize)
+                    .send()
+                    .await
+                    .unwrap();
+                if sent.status() != 502 {
+                    response = Some(sent);
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+            let response = response.expect("MCP listener did not start");
+            assert_eq!(response.status(), 200);
+            assert_eq!(
+                response.headers()["access-control-allow-origin"],
+                "https://app.example.com"
+            );
+            let reply: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(
+                reply["result"]["serverInfo"]["name"],
+                "ultrafast-sequential-thinking"
+            );
+        }
+
+        #[tokio::test]
+        async fn test_upstream_retries_taken_ports() {
+            let upstream = UpstreamPort::default();
+            let mut ports = Vec::new();
+            run_upstream(&upstream, |port| {
+                ports.push(port);
+                let taken = ports.len() == 1;
+                async move {
+                    match taken {
+                        true => Err("Failed to bind to address: address in use"),
+                        false => Ok(()),
+                    }
+                }
+            })
+            .await
+            .unwrap();
+            assert_eq!(ports.len(), 2);
+            assert_eq!(upstream.get(), ports[1]);
+
+            let failed = run_upstream(&upstream, |_| async { Err("handler panicked") }).await;
+            assert_eq!(failed.unwrap_err().to_string(), "handler panicked");
+        }
+
+        #[tokio::test]
+        async fn test_usage_endpoint() {
+            let security = SecurityConfig {
+                api_key_validation: true,
+                api_keys: HashMap::from([
+                    ("agent".to_string(), Role::Contributor),
+                    ("ops".to_string(), Role::Admin),
+                ]),
+                ..Default::default()
+            };
+            let server = SequentialThinkingServer::new().with_security_config(security);
+            server
+                .usage()
+                .record("agent", UsageMetric::Thoughts, 3)
+                .await;
+
+            let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+                .await
+                .unwrap();
+            let gateway = format!("http://{}{USAGE_PATH}", listener.local_addr().unwrap());
+            let policy = CorsPolicy::new(vec!["*".to_string()]);
+            tokio::spawn(async move {
+                axum::serve(listener, router(policy, server, UpstreamPort::new(1)))
+                    .await
+                    .unwrap();
+            });
+
+            let client = reqwest::Client::new();
+            let status = |key: &'static str| {
+                let request = client.get(&gateway).header("x-api-key", key);
+                async move { request.send().await.unwrap().status() }
+            };
+            assert_eq!(status("unknown").await, 401);
+            assert_eq!(status("agent").await, 403);
+
+            let report: serde_json::Value = client
+                .get(&gateway)
+                .bearer_auth("ops")
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            assert_eq!(report["keys"][0]["key"], "agent");
+            assert_eq!(report["keys"][0]["usage"]["thoughts"], 3);
+        }
+
+        #[tokio::test]
+        async fn test_admin_endpoints() {
+            let security = SecurityConfig {
+                api_key_validation: true,
+                api_keys: HashMap::from([
+                    ("agent".to_string(), Role::Contributor),
+                    ("ops".to_string(), Role::Admin),
+                ]),
+                ..Default::default()
+            };
+            let server = SequentialThinkingServer::new().with_security_config(security);
+            for session_id in ["plan/a", "plan-b"] {
+                server.create_session(session_id.to_string()).await.unwrap();
+            }
+
+            let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+                .await
+                .unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let policy = CorsPolicy::new(vec!["*".to_string()]);
+            tokio::spawn(async move {
+                axum::serve(listener, router(policy, server, UpstreamPort::new(1)))
+                    .await
+                    .unwrap();
+            });
+
+            let denied = AdminClient::new(&url).with_api_key("agent");
+            assert!(matches!(
+                denied.stats().await,
+                Err(SequentialThinkingError::PermissionDenied { .. })
+            ));
+
+            let admin = AdminClient::new(&url).with_api_key("ops");
+            let sessions = admin.list_sessions().await.unwrap();
+            let ids: Vec<&str> = sessions.iter().map(|s| s.session_id.as_str()).collect();
+            assert_eq!(ids, ["plan-b", "plan/a"]);
+            assert_eq!(admin.stats().await.unwrap().active_sessions, 2);
+
+            let export = admin.export_session("plan/a", "json").await.unwrap();
+            let export: serde_json::Value = serde_json::from_str(&export).unwrap();
+            assert_eq!(export["format"], "json");
+            assert!(admin
+                .export_session("plan/a", "markdown")
+                .await
+                .unwrap()
+                .starts_with("# Sequential Thinking Session"));
+            assert!(matches!(
+                admin.export_session("plan/a", "pdf").await,
+                Err(SequentialThinkingError::ValidationError { .. })
+            ));
+
+            admin.kill_session("plan/a").await.unwrap();
+            assert!(matches!(
+                admin.kill_session("plan/a").await,
+                Err(SequentialThinkingError::NotFound { .. })
+            ));
+            let stats = admin.stats().await.unwrap();
+            assert_eq!(stats.active_sessions, 1);
+            assert_eq!(stats.stats.total_sessions, 2);
+
+            let dashboard = admin.analytics_dashboard(7).await.unwrap();
+            assert_eq!(dashboard.days.len(), 7);
+            assert!(matches!(
+                admin.analytics_dashboard(0).await,
+                Err(SequentialThinkingError::ValidationError { .. })
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_origin_matching() {
+        let policy = CorsPolicy::new(vec![
+            "https://app.example.com/".to_string(),
+            "http://localhost:*".to_string(),
+            "https://*.trusted.dev".to_string(),
+        ]);
+        assert!(policy.is_allowed("https://APP.example.com"));
+        assert!(policy.is_allowed("http://localhost"));
+        assert!(policy.is_allowed("http://localhost:5173"));
+        assert!(policy.is_allowed("https://a.b.trusted.dev"));
+        assert!(!policy.is_allowed("http://app.example.com"));
+        assert!(!policy.is_allowed("http://localhost:abc"));
+        assert!(!policy.is_allowed("http://localhost.evil.com"));
+        assert!(!policy.is_allowed("https://trusted.dev"));
+        assert!(!policy.is_allowed("https://eviltrusted.dev"));
+        assert!(!policy.allows_any_origin());
+        assert!(CorsPolicy::from_config(&SecurityConfig::default()).allows_any_origin());
+
+        assert_eq!(policy.response_headers(None), Ok(Vec::new()));
+        assert_eq!(
+            policy.response_headers(Some("https://evil.com")),
+            Err(CorsRejection::OriginNotAllowed(
+                "https://evil.com".to_string()
+            ))
+        );
+
+        let headers = policy
+            .preflight_headers(
+                "http://localhost:3000",
+                "post",
+                Some("Content-Type, Mcp-Session-Id"),
+            )
+            .unwrap();
+        assert!(headers.contains(&(
+            "access-control-allow-origin",
+            "http://localhost:3000".to_string()
+        )));
+        assert!(headers.contains(&("access-control-max-age", "600".to_string())));
+        assert_eq!(
+            policy.preflight_headers("http://localhost:3000", "PUT", None),
+            Err(CorsRejection::MethodNotAllowed("PUT".to_string()))
+        );
+        assert_eq!(
+            policy.preflight_headers("http://localhost:3000", "POST", Some("x-secret")),
+            Err(CorsRejection::HeaderNotAllowed("x-secret".to_string()))
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! # Elicitation
+//!
+//! Missing `sequential_thinking` arguments, asked of the user through MCP
+//! elicitation.
+//!
+//! With elicitation enabled, a call that omits required fields such as
+//! `totalThoughts` does not fail straight away. The server sends the host an
+//! `elicitation/create` request with a form for the missing fields, and
+//! processes the thought with the values the user enters. Declining or
+//! cancelling the form fails the call as before.
+//!
+//! Requests reach the host through a [`ThoughtElicitor`], supplied by whatever
+//! owns the MCP connection.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Arguments that can be elicited, as `(name, type, description)`
+pub const ELICITABLE_FIELDS: &[(&str, &str, &str)] = &[
+    ("thought", "string", "Your current thinking step"),
+    ("thoughtNumber", "integer", "Number of this thought"),
+    (
+        "totalThoughts",
+        "integer",
+        "Estimated number of thoughts needed",
+    ),
+];
+
+/// Elicitation settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ElicitationConfig {
+    /// Arguments to ask for when missing, out of `thought`, `thoughtNumber`
+    /// and `totalThoughts`
+    #[serde(default = "default_fields")]
+    pub fields: Vec<String>,
+    /// Message shown with the form
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+fn default_fields() -> Vec<String> {
+    vec!["thoughtNumber".to_string(), "totalThoughts".to_string()]
+}
+
+impl Default for ElicitationConfig {
+    fn default() -> Self {
+        Self {
+            fields: default_fields(),
+            message: None,
+        }
+    }
+}
+
+impl ElicitationConfig {
+    /// Configured fields missing from tool call arguments
+    pub fn missing(&self, args: &Value) -> Vec<String> {
+        self.fields
+            .iter()
+            .filter(|field| args.get(field.as_str()).is_none_or(Value::is_null))
+            .cloned()
+            .collect()
+    }
+
+    /// Build the request asking for the missing fields
+    pub fn request(&self, missing: Vec<String>) -> ElicitationRequest {
+        let message = self.message.clone().unwrap_or_else(|| {
+            format!(
+                "The thought is missing {}. Please provide the missing values.",
+                missing.join(", ")
+            )
+        });
+        ElicitationRequest {
+            message,
+            fields: missing,
+        }
+    }
+}
+
+/// Request for missing arguments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElicitationRequest {
+    /// Message shown to the user
+    pub message: String,
+    /// Names of the requested arguments
+    pub fields: Vec<String>,
+}
+
+impl ElicitationRequest {
+    /// Parameters of the equivalent MCP `elicitation/create` request
+    pub fn create_params(&self) -> Value {
+        let properties: Map<String, Value> = ELICITABLE_FIELDS
+            .iter()
+            .filter(|(name, _, _)| self.fields.iter().any(|field| field == name))
+            .map(|(name, kind, description)| {
+                let mut schema = serde_json::json!({ "type": kind, "description": description });
+                if *kind == "integer" {
+                    schema["minimum"] = serde_json::json!(1);
+                }
+                (name.to_string(), schema)
+            })
+            .collect();
+        serde_json::json!({
+            "message": self.message,
+            "requestedSchema": {
+                "type": "object",
+                "properties": properties,
+                "required": self.fields
+            }
+        })
+    }
+}
+
+/// Answer of the host, in the shape of an MCP `elicitation/create` result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum ElicitationResponse {
+    /// The user submitted the form
+    Accept {
+        /// Submitted values by argument name
+        #[serde(default)]
+        content: Map<String, Value>,
+    },
+    /// The user declined to provide the values
+    Decline,
+    /// The user dismissed the form
+    Cancel,
+}
+
+/// Sends elicitation requests to the connected host
+#[async_trait]
+pub trait ThoughtElicitor: Send + Sync + std::fmt::Debug {
+    /// Ask the user for values
+    async fn elicit(&self, request: ElicitationRequest) -> Result<ElicitationResponse, String>;
+}
+
+/// Fill in missing tool call arguments with values elicited from the user
+///
+/// Arguments that are present, or not configured for elicitation, are left
+/// alone, and no request is sent when nothing is missing.
+pub async fn complete_arguments(
+    elicitor: &dyn ThoughtElicitor,
+    config: &ElicitationConfig,
+    args: &mut Value,
+) -> Result<(), String> {
+    let missing = config.missing(args);
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let mut content = match elicitor.elicit(config.request(missing.clone())).await? {
+        ElicitationResponse::Accept { content } => content,
+        ElicitationResponse::Decline => {
+            return Err(format!(
+                "The user declined to provide {}",
+                missing.join(", ")
+            ))
+        }
+        ElicitationResponse::Cancel => {
+            return Err(format!(
+                "The user cancelled providing {}",
+                missing.join(", ")
+            ))
+        }
+    };
+    for field in missing {
+        let value = content
+            .remove(&field)
+            .ok_or_else(|| format!("Elicitation did not provide {field}"))?;
+        let valid = match ELICITABLE_FIELDS.iter().find(|(name, _, _)| *name == field) {
+            Some((_, "integer", _)) => value.as_u64().is_some_and(|n| n >= 1),
+            Some((_, "string", _)) => value.is_string(),
+            _ => false,
+        };
+        if !valid {
+            return Err(format!("Elicited {field} is not valid: {value}"));
+        }
+        args[field.as_str()] = value;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct FormElicitor(ElicitationResponse);
+
+    #[async_trait]
+    impl ThoughtElicitor for FormElicitor {
+        async fn elicit(&self, request: ElicitationRequest) -> Result<ElicitationResponse, String> {
+            assert_eq!(request.fields, vec!["totalThoughts".to_string()]);
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_complete_arguments() {
+        let config = ElicitationConfig::default();
+        let args = serde_json::json!({ "thought": "Outline the plan", "thoughtNumber": 1 });
+        assert_eq!(config.missing(&args), vec!["totalThoughts".to_string()]);
+
+        let params = config.request(config.missing(&args)).create_params();
+        assert_eq!(
+            params["requestedSchema"]["properties"]["totalThoughts"]["type"],
+            "integer"
+        );
+        assert_eq!(
+            params["requestedSchema"]["required"],
+            serde_json::json!(["totalThoughts"])
+        );
+
+        let accept: ElicitationResponse = serde_json::from_value(serde_json::json!({
+            "action": "accept",
+            "content": { "totalThoughts": 4 }
+        }))
+        .unwrap();
+        let mut completed = args.clone();
+        complete_arguments(&FormElicitor(accept), &config, &mut completed)
+            .await
+            .unwrap();
+        assert_eq!(completed["totalThoughts"], 4);
+        assert_eq!(completed["thought"], "Outline the plan");
+
+        for response in [
+            ElicitationResponse::Decline,
+            ElicitationResponse::Cancel,
+            ElicitationResponse::Accept {
+                content: Map::from_iter([("totalThoughts".to_string(), serde_json::json!(0))]),
+            },
+        ] {
+            let mut args = args.clone();
+            assert!(
+                complete_arguments(&FormElicitor(response), &config, &mut args)
+                    .await
+                    .is_err()
+            );
+        }
+    }
+}
@@ -8,6 +8,8 @@
 
 use thiserror::Error;
 
+use crate::thinking::sequence::SequenceError;
+
 /// Main error type for sequential thinking operations
 #[derive(Error, Debug)]
 pub enum SequentialThinkingError {
@@ -67,6 +69,51 @@ pub enum SequentialThinkingError {
     #[error("Operation was cancelled: {reason}")]
     Cancelled { reason: String },
 
+    /// Write rejected because the stored state changed since it was read
+    #[error("Storage conflict: {message}")]
+    StorageConflict { message: String },
+
+    /// Storage backend temporarily unreachable or failing
+    #[error("Storage unavailable: {message}")]
+    StorageUnavailable { message: String },
+
+    /// Stored data that cannot be read back
+    #[error("Storage corrupted: {message}")]
+    StorageCorrupted { message: String },
+
+    /// Too many calls in flight; try again after the given wait
+    #[error("Server busy: {message} (retry after {retry_after:?})")]
+    ServerBusy {
+        message: String,
+        retry_after: std::time::Duration,
+    },
+
+    /// Tool call made by the client that failed, with what it sent
+    #[error("Tool '{tool}' failed: {source}")]
+    ToolCallFailed {
+        /// Name of the tool called
+        tool: String,
+        /// Arguments of the call, without the API key
+        arguments: Option<serde_json::Value>,
+        /// What the caller can do about the failure
+        advice: RecoveryAdvice,
+        /// Error the call failed with
+        source: Box<SequentialThinkingError>,
+    },
+
+    /// Error with the operation, session and thought it happened in
+    #[error("{source} (during {context})")]
+    WithContext {
+        /// Where the error happened
+        context: Box<ErrorContext>,
+        /// Error the operation failed with
+        source: Box<SequentialThinkingError>,
+    },
+
+    /// Thought submitted out of sequence
+    #[error("Out of sequence: {0}")]
+    OutOfSequence(#[from] SequenceError),
+
     /// Wrapped error from underlying dependencies
     #[error("Wrapped error: {source}")]
     Wrapped {
@@ -75,6 +122,30 @@ pub enum SequentialThinkingError {
     },
 }
 
+/// What a caller can do about an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryAdvice {
+    /// Make the same call again, after the suggested wait if there is one
+    Retry,
+    /// Reconnect to the server, then make the call again
+    Reconnect,
+    /// Change the input before making the call again
+    FixInput,
+    /// Making the call again will not help
+    GiveUp,
+}
+
+impl std::fmt::Display for RecoveryAdvice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Retry => "retry",
+            Self::Reconnect => "reconnect",
+            Self::FixInput => "fix-input",
+            Self::GiveUp => "give-up",
+        })
+    }
+}
+
 impl SequentialThinkingError {
     /// Create an invalid thought data error
     pub fn invalid_thought_data(message: impl Into<String>) -> Self {
@@ -172,35 +243,198 @@ impl SequentialThinkingError {
         }
     }
 
+    /// Create a storage conflict error
+    pub fn storage_conflict(message: impl Into<String>) -> Self {
+        Self::StorageConflict {
+            message: message.into(),
+        }
+    }
+
+    /// Create a storage unavailable error
+    pub fn storage_unavailable(message: impl Into<String>) -> Self {
+        Self::StorageUnavailable {
+            message: message.into(),
+        }
+    }
+
+    /// Create a storage corrupted error
+    pub fn storage_corrupted(message: impl Into<String>) -> Self {
+        Self::StorageCorrupted {
+            message: message.into(),
+        }
+    }
+
+    /// Create a server busy error
+    pub fn server_busy(message: impl Into<String>, retry_after: std::time::Duration) -> Self {
+        Self::ServerBusy {
+            message: message.into(),
+            retry_after,
+        }
+    }
+
+    /// Wait suggested before retrying, if the error carries one
+    pub fn retry_after(&self) -> Option<std::time::Duration> {
+        match self.root_cause() {
+            Self::ServerBusy { retry_after, .. } => Some(*retry_after),
+            _ => None,
+        }
+    }
+
+    /// Record the tool call an error came from, with advice for recovering
+    ///
+    /// The `apiKey` argument is left out. An error already recording its
+    /// tool call is returned as is.
+    pub fn in_tool_call(
+        self,
+        tool: impl Into<String>,
+        arguments: Option<serde_json::Value>,
+    ) -> Self {
+        if self.tool_call().is_some() {
+            return self;
+        }
+        let arguments = arguments.map(|mut arguments| {
+            if let Some(fields) = arguments.as_object_mut() {
+                fields.remove("apiKey");
+            }
+            arguments
+        });
+        Self::ToolCallFailed {
+            tool: tool.into(),
+            arguments,
+            advice: self.recovery_advice(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Record the operation, session and thought an error happened in
+    ///
+    /// An error that already has a context keeps it, taking only the
+    /// details it is missing from `context`.
+    pub fn with_context(mut self, context: ErrorContext) -> Self {
+        match &mut self {
+            Self::WithContext { context: inner, .. } => {
+                inner.fill_from(context);
+                self
+            }
+            _ => Self::WithContext {
+                context: Box::new(context),
+                source: Box::new(self),
+            },
+        }
+    }
+
+    /// Context the error happened in, if recorded
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::WithContext { context, .. } => Some(context),
+            Self::ToolCallFailed { source, .. } => source.context(),
+            _ => None,
+        }
+    }
+
+    /// Error underneath any tool call or context record
+    pub fn root_cause(&self) -> &Self {
+        match self {
+            Self::ToolCallFailed { source, .. } | Self::WithContext { source, .. } => {
+                source.root_cause()
+            }
+            other => other,
+        }
+    }
+
+    /// Name and arguments of the tool call the error came from, if recorded
+    pub fn tool_call(&self) -> Option<(&str, Option<&serde_json::Value>)> {
+        match self {
+            Self::ToolCallFailed {
+                tool, arguments, ..
+            } => Some((tool, arguments.as_ref())),
+            Self::WithContext { source, .. } => source.tool_call(),
+            _ => None,
+        }
+    }
+
+    /// Log the error as a warning, with its context as structured fields
+    pub fn log(&self) {
+        let context = self.context();
+        tracing::warn!(
+            operation = context.map(|c| c.operation.as_str()),
+            session_id = context.and_then(|c| c.session_id.as_deref()),
+            thought_number = context.and_then(|c| c.thought_number),
+            code = self.error_code(),
+            "{}",
+            self
+        );
+    }
+
+    /// What the caller can do about this error
+    pub fn recovery_advice(&self) -> RecoveryAdvice {
+        match self {
+            Self::ToolCallFailed { advice, .. } => *advice,
+            Self::WithContext { source, .. } => source.recovery_advice(),
+            Self::TransportError { .. } => RecoveryAdvice::Reconnect,
+            Self::Timeout { .. }
+            | Self::RateLimitExceeded { .. }
+            | Self::StorageUnavailable { .. }
+            | Self::StorageConflict { .. }
+            | Self::ServerBusy { .. } => RecoveryAdvice::Retry,
+            Self::InvalidThoughtData { .. }
+            | Self::ValidationError { .. }
+            | Self::ProcessingError { .. }
+            | Self::BranchError { .. }
+            | Self::ConfigError { .. }
+            | Self::NotFound { .. }
+            | Self::OutOfSequence(_) => RecoveryAdvice::FixInput,
+            Self::SessionError { .. }
+            | Self::SerializationError { .. }
+            | Self::InternalError { .. }
+            | Self::PermissionDenied { .. }
+            | Self::Cancelled { .. }
+            | Self::StorageCorrupted { .. }
+            | Self::Wrapped { .. } => RecoveryAdvice::GiveUp,
+        }
+    }
+
     /// Check if this is a retryable error
+    ///
+    /// Storage conflicts are not: the same write fails again until the
+    /// caller reloads the state it changed.
     pub fn is_retryable(&self) -> bool {
         matches!(
-            self,
-            Self::TransportError { .. } | Self::Timeout { .. } | Self::RateLimitExceeded { .. }
+            self.root_cause(),
+            Self::TransportError { .. }
+                | Self::Timeout { .. }
+                | Self::RateLimitExceeded { .. }
+                | Self::StorageUnavailable { .. }
+                | Self::ServerBusy { .. }
         )
     }
 
     /// Check if this is a client error (not retryable)
     pub fn is_client_error(&self) -> bool {
         matches!(
-            self,
+            self.root_cause(),
             Self::InvalidThoughtData { .. }
+                | Self::OutOfSequence(_)
                 | Self::ValidationError { .. }
                 | Self::ConfigError { .. }
                 | Self::NotFound { .. }
                 | Self::PermissionDenied { .. }
+                | Self::StorageConflict { .. }
         )
     }
 
     /// Check if this is a server error (potentially retryable)
     pub fn is_server_error(&self) -> bool {
         matches!(
-            self,
+            self.root_cause(),
             Self::ProcessingError { .. }
                 | Self::SessionError { .. }
                 | Self::BranchError { .. }
                 | Self::InternalError { .. }
                 | Self::SerializationError { .. }
+                | Self::StorageUnavailable { .. }
+                | Self::StorageCorrupted { .. }
+                | Self::ServerBusy { .. }
         )
     }
 
@@ -249,6 +483,31 @@ impl SequentialThinkingError {
             Self::Cancelled { reason } => {
                 format!("Operation cancelled: {reason}")
             }
+            Self::StorageConflict { message } => {
+                format!("Conflicting update: {message}")
+            }
+            Self::StorageUnavailable { message } => {
+                format!("Storage unavailable: {message}")
+            }
+            Self::StorageCorrupted { message } => {
+                format!("Stored data is corrupted: {message}")
+            }
+            Self::ServerBusy {
+                message,
+                retry_after,
+            } => {
+                format!(
+                    "Server busy: {message}; try again in {} ms",
+                    retry_after.as_millis()
+                )
+            }
+            Self::ToolCallFailed { tool, source, .. } => {
+                format!("{} (calling {tool})", source.user_message())
+            }
+            Self::WithContext { source, .. } => source.user_message(),
+            Self::OutOfSequence(error) => {
+                format!("Thought out of sequence: {error}")
+            }
             Self::Wrapped { source } => {
                 format!("Error: {source}")
             }
@@ -272,6 +531,14 @@ impl SequentialThinkingError {
             Self::RateLimitExceeded { .. } => "RATE_LIMIT_EXCEEDED",
             Self::Timeout { .. } => "TIMEOUT",
             Self::Cancelled { .. } => "CANCELLED",
+            Self::StorageConflict { .. } => "STORAGE_CONFLICT",
+            Self::StorageUnavailable { .. } => "STORAGE_UNAVAILABLE",
+            Self::StorageCorrupted { .. } => "STORAGE_CORRUPTED",
+            Self::ServerBusy { .. } => "SERVER_BUSY",
+            Self::ToolCallFailed { source, .. } | Self::WithContext { source, .. } => {
+                source.error_code()
+            }
+            Self::OutOfSequence(_) => "OUT_OF_SEQUENCE",
             Self::Wrapped { .. } => "WRAPPED_ERROR",
         }
     }
@@ -285,6 +552,10 @@ pub type SequentialThinkingResult<T> = Result<T, SequentialThinkingError>;
 pub struct ErrorContext {
     /// Operation being performed
     pub operation: String,
+    /// Session the operation ran in
+    pub session_id: Option<String>,
+    /// Number of the thought the operation was handling
+    pub thought_number: Option<u32>,
     /// Additional context information
     pub context: std::collections::HashMap<String, String>,
     /// Timestamp when error occurred
@@ -296,11 +567,25 @@ impl ErrorContext {
     pub fn new(operation: impl Into<String>) -> Self {
         Self {
             operation: operation.into(),
+            session_id: None,
+            thought_number: None,
             context: std::collections::HashMap::new(),
             timestamp: chrono::Utc::now(),
         }
     }
 
+    /// Set the session the operation ran in
+    pub fn with_session(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Set the thought the operation was handling
+    pub fn with_thought(mut self, thought_number: u32) -> Self {
+        self.thought_number = Some(thought_number);
+        self
+    }
+
     /// Add context information
     pub fn with_context(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.context.insert(key.into(), value.into());
@@ -314,6 +599,37 @@ impl ErrorContext {
         }
         self
     }
+
+    /// Take the details missing here from a context recorded further out
+    fn fill_from(&mut self, outer: ErrorContext) {
+        if self.session_id.is_none() {
+            self.session_id = outer.session_id;
+        }
+        if self.thought_number.is_none() {
+            self.thought_number = outer.thought_number;
+        }
+        for (key, value) in outer.context {
+            self.context.entry(key).or_insert(value);
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.operation)?;
+        if let Some(session_id) = &self.session_id {
+            write!(f, " in session '{session_id}'")?;
+        }
+        if let Some(thought_number) = self.thought_number {
+            write!(f, ", thought {thought_number}")?;
+        }
+        let mut extra: Vec<_> = self.context.iter().collect();
+        extra.sort();
+        for (key, value) in extra {
+            write!(f, ", {key}={value}")?;
+        }
+        Ok(())
+    }
 }
 
 /// Error handling utilities
@@ -355,6 +671,29 @@ pub mod utils {
     pub fn invalid_format_error(field: &str, expected: &str) -> SequentialThinkingError {
         field_validation_error(field, &format!("Expected format: {expected}"))
     }
+
+    /// Classify an I/O error from a storage backend
+    ///
+    /// Unreadable data counts as corrupted and an existing entry as a
+    /// conflict; failures that may pass, such as timeouts, interruptions
+    /// and lost connections, count as the store being unavailable.
+    pub fn storage_io_error(context: &str, error: std::io::Error) -> SequentialThinkingError {
+        use std::io::ErrorKind;
+
+        let message = format!("{context}: {error}");
+        match error.kind() {
+            ErrorKind::InvalidData | ErrorKind::UnexpectedEof => {
+                SequentialThinkingError::storage_corrupted(message)
+            }
+            ErrorKind::AlreadyExists => SequentialThinkingError::storage_conflict(message),
+            ErrorKind::NotFound => SequentialThinkingError::not_found(message),
+            ErrorKind::PermissionDenied => SequentialThinkingError::permission_denied(message),
+            ErrorKind::InvalidInput | ErrorKind::Unsupported => {
+                SequentialThinkingError::internal_error(message)
+            }
+            _ => SequentialThinkingError::storage_unavailable(message),
+        }
+    }
 }
 
 // Implement From for common error types
@@ -393,6 +732,7 @@ impl From<chrono::ParseError> for SequentialThinkingError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
 
     #[test]
     fn test_error_creation() {
@@ -419,6 +759,74 @@ mod tests {
         assert!(message.contains("Invalid input"));
     }
 
+    #[test]
+    fn test_recovery_advice() {
+        for (error, advice) in [
+            (
+                SequentialThinkingError::transport_error("reset"),
+                RecoveryAdvice::Reconnect,
+            ),
+            (
+                SequentialThinkingError::server_busy("full", Duration::from_secs(1)),
+                RecoveryAdvice::Retry,
+            ),
+            (
+                SequentialThinkingError::validation_error("thought: too long"),
+                RecoveryAdvice::FixInput,
+            ),
+            (
+                SequentialThinkingError::permission_denied("read-only key"),
+                RecoveryAdvice::GiveUp,
+            ),
+        ] {
+            assert_eq!(error.recovery_advice(), advice);
+        }
+
+        let error = SequentialThinkingError::server_busy("full", Duration::from_secs(2))
+            .in_tool_call(
+                "sequential_thinking",
+                Some(serde_json::json!({ "thought": "Plan", "apiKey": "secret" })),
+            );
+        assert_eq!(error.recovery_advice(), RecoveryAdvice::Retry);
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(2)));
+        assert_eq!(error.error_code(), "SERVER_BUSY");
+        assert!(error.is_retryable());
+        let (tool, arguments) = error.tool_call().unwrap();
+        assert_eq!(tool, "sequential_thinking");
+        assert_eq!(arguments, Some(&serde_json::json!({ "thought": "Plan" })));
+        assert_eq!(RecoveryAdvice::FixInput.to_string(), "fix-input");
+
+        // Recording the call again keeps the original record
+        let again = error.in_tool_call("export_session", None);
+        assert_eq!(again.tool_call().unwrap().0, "sequential_thinking");
+    }
+
+    #[test]
+    fn test_storage_error_classification() {
+        use std::io::{Error, ErrorKind};
+
+        let unavailable = utils::storage_io_error(
+            "Failed to write sessions",
+            Error::new(ErrorKind::TimedOut, "disk busy"),
+        );
+        assert_eq!(unavailable.error_code(), "STORAGE_UNAVAILABLE");
+        assert!(unavailable.is_retryable());
+        assert!(unavailable.is_server_error());
+        assert_eq!(
+            unavailable.to_string(),
+            "Storage unavailable: Failed to write sessions: disk busy"
+        );
+
+        let corrupted =
+            utils::storage_io_error("Bad file", Error::new(ErrorKind::InvalidData, "not UTF-8"));
+        assert_eq!(corrupted.error_code(), "STORAGE_CORRUPTED");
+        assert!(!corrupted.is_retryable());
+
+        let conflict = SequentialThinkingError::storage_conflict("Session s1 changed");
+        assert!(conflict.is_client_error());
+        assert!(!conflict.is_retryable());
+    }
+
     #[test]
     fn test_error_context() {
         let context = ErrorContext::new("test_operation")
@@ -430,6 +838,45 @@ mod tests {
         assert_eq!(context.context.get("session_id"), Some(&"abc".to_string()));
     }
 
+    #[test]
+    fn test_errors_carry_context() {
+        let error = SequentialThinkingError::processing_error("graph cycle").with_context(
+            ErrorContext::new("process_thought")
+                .with_session("s1")
+                .with_thought(3),
+        );
+        assert_eq!(
+            error.to_string(),
+            "Thought processing error: graph cycle (during process_thought in session 's1', thought 3)"
+        );
+        assert_eq!(error.error_code(), "PROCESSING_ERROR");
+        assert_eq!(error.recovery_advice(), RecoveryAdvice::FixInput);
+        assert!(matches!(
+            error.root_cause(),
+            SequentialThinkingError::ProcessingError { .. }
+        ));
+
+        // Outer contexts only fill in what the inner one is missing
+        let error = SequentialThinkingError::timeout(Duration::from_secs(1))
+            .with_context(ErrorContext::new("process_thought").with_thought(2))
+            .with_context(
+                ErrorContext::new("add_thought")
+                    .with_session("s2")
+                    .with_thought(9)
+                    .with_context("format", "json"),
+            )
+            .in_tool_call("sequential_thinking", None);
+        let context = error.context().unwrap();
+        assert_eq!(context.operation, "process_thought");
+        assert_eq!(context.session_id.as_deref(), Some("s2"));
+        assert_eq!(context.thought_number, Some(2));
+        assert_eq!(
+            context.to_string(),
+            "process_thought in session 's2', thought 2, format=json"
+        );
+        assert!(error.is_retryable());
+    }
+
     #[test]
     fn test_from_implementations() {
         let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "File not found");
@@ -0,0 +1,247 @@
+//! # Thought Graph
+//!
+//! Dependency graph between thoughts in a session.
+//!
+//! This module models "thought 7 builds on thoughts 3 and 5" as a directed
+//! acyclic graph keyed by thought number, with cycle detection on insertion,
+//! topological ordering, and traversal queries.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::thinking::SharedThought;
+
+/// Directed acyclic graph of thought dependencies
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThoughtGraph {
+    /// Thought number -> thoughts it depends on
+    dependencies: BTreeMap<u32, BTreeSet<u32>>,
+    /// Thought number -> thoughts that depend on it
+    dependents: BTreeMap<u32, BTreeSet<u32>>,
+}
+
+impl ThoughtGraph {
+    /// Create an empty graph
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a graph from a list of thoughts, skipping edges that would create cycles
+    pub fn from_thoughts(thoughts: &[SharedThought]) -> Self {
+        let mut graph = Self::new();
+        for thought in thoughts {
+            let valid: Vec<u32> = thought
+                .depends_on
+                .iter()
+                .copied()
+                .filter(|dep| graph.contains(*dep))
+                .collect();
+            if graph.add_thought(thought.thought_number, &valid).is_err() {
+                let _ = graph.add_thought(thought.thought_number, &[]);
+            }
+        }
+        graph
+    }
+
+    /// Add a thought and its dependencies to the graph
+    ///
+    /// Dependencies must refer to thoughts already in the graph, and the new
+    /// edges must not introduce a cycle. On error the graph is left unchanged.
+    pub fn add_thought(&mut self, thought_number: u32, depends_on: &[u32]) -> Result<(), String> {
+        for &dep in depends_on {
+            if dep == thought_number {
+                return Err(format!("Thought {thought_number} cannot depend on itself"));
+            }
+            if !self.contains(dep) {
+                return Err(format!(
+                    "Thought {thought_number} depends on unknown thought {dep}"
+                ));
+            }
+            if self.ancestors(dep).contains(&thought_number) {
+                return Err(format!(
+                    "Dependency {thought_number} -> {dep} would create a cycle"
+                ));
+            }
+        }
+
+        self.dependencies.entry(thought_number).or_default();
+        self.dependents.entry(thought_number).or_default();
+        for &dep in depends_on {
+            self.dependencies
+                .entry(thought_number)
+                .or_default()
+                .insert(dep);
+            self.dependents
+                .entry(dep)
+                .or_default()
+                .insert(thought_number);
+        }
+        Ok(())
+    }
+
+    /// Check whether a thought is part of the graph
+    pub fn contains(&self, thought_number: u32) -> bool {
+        self.dependencies.contains_key(&thought_number)
+    }
+
+    /// Get the number of thoughts in the graph
+    pub fn node_count(&self) -> usize {
+        self.dependencies.len()
+    }
+
+    /// Get the number of dependency edges in the graph
+    pub fn edge_count(&self) -> usize {
+        self.dependencies.values().map(|deps| deps.len()).sum()
+    }
+
+    /// Get the direct dependencies of a thought
+    pub fn dependencies(&self, thought_number: u32) -> Vec<u32> {
+        self.dependencies
+            .get(&thought_number)
+            .map(|deps| deps.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the thoughts that directly depend on a thought
+    pub fn dependents(&self, thought_number: u32) -> Vec<u32> {
+        self.dependents
+            .get(&thought_number)
+            .map(|deps| deps.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get all thoughts a thought transitively depends on
+    pub fn ancestors(&self, thought_number: u32) -> BTreeSet<u32> {
+        Self::reachable(&self.dependencies, thought_number)
+    }
+
+    /// Get all thoughts that transitively depend on a thought
+    pub fn descendants(&self, thought_number: u32) -> BTreeSet<u32> {
+        Self::reachable(&self.dependents, thought_number)
+    }
+
+    /// Get thoughts without dependencies
+    pub fn roots(&self) -> Vec<u32> {
+        self.dependencies
+            .iter()
+            .filter(|(_, deps)| deps.is_empty())
+            .map(|(n, _)| *n)
+            .collect()
+    }
+
+    /// Get thoughts nothing depends on
+    pub fn leaves(&self) -> Vec<u32> {
+        self.dependents
+            .iter()
+            .filter(|(_, deps)| deps.is_empty())
+            .map(|(n, _)| *n)
+            .collect()
+    }
+
+    /// Get thoughts in dependency order (dependencies before dependents)
+    pub fn topological_order(&self) -> Vec<u32> {
+        let mut in_degree: BTreeMap<u32, usize> = self
+            .dependencies
+            .iter()
+            .map(|(n, deps)| (*n, deps.len()))
+            .collect();
+        let mut ready: BTreeSet<u32> = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(n, _)| *n)
+            .collect();
+        let mut order = Vec::with_capacity(in_degree.len());
+
+        while let Some(next) = ready.pop_first() {
+            order.push(next);
+            for dependent in self.dependents(next) {
+                if let Some(degree) = in_degree.get_mut(&dependent) {
+                    *degree -= 1;
+                    if *degree == 0 {
+                        ready.insert(dependent);
+                    }
+                }
+            }
+        }
+
+        order
+    }
+
+    /// Get the length of the longest dependency chain
+    pub fn depth(&self) -> usize {
+        let mut depths: BTreeMap<u32, usize> = BTreeMap::new();
+        for n in self.topological_order() {
+            let depth = self
+                .dependencies(n)
+                .iter()
+                .filter_map(|dep| depths.get(dep))
+                .max()
+                .map_or(1, |d| d + 1);
+            depths.insert(n, depth);
+        }
+        depths.values().copied().max().unwrap_or(0)
+    }
+
+    /// Remove all thoughts from the graph
+    pub fn clear(&mut self) {
+        self.dependencies.clear();
+        self.dependents.clear();
+    }
+
+    /// Breadth-first traversal over an adjacency map
+    fn reachable(edges: &BTreeMap<u32, BTreeSet<u32>>, start: u32) -> BTreeSet<u32> {
+        let mut visited = BTreeSet::new();
+        let mut queue: VecDeque<u32> = edges
+            .get(&start)
+            .map(|next| next.iter().copied().collect())
+            .unwrap_or_default();
+
+        while let Some(n) = queue.pop_front() {
+            if visited.insert(n) {
+                if let Some(next) = edges.get(&n) {
+                    queue.extend(next.iter().copied());
+                }
+            }
+        }
+
+        visited
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_queries() {
+        let mut graph = ThoughtGraph::new();
+        graph.add_thought(1, &[]).unwrap();
+        graph.add_thought(2, &[1]).unwrap();
+        graph.add_thought(3, &[1]).unwrap();
+        graph.add_thought(4, &[2, 3]).unwrap();
+
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(graph.dependencies(4), vec![2, 3]);
+        assert_eq!(graph.dependents(1), vec![2, 3]);
+        assert_eq!(graph.roots(), vec![1]);
+        assert_eq!(graph.leaves(), vec![4]);
+        assert_eq!(graph.topological_order(), vec![1, 2, 3, 4]);
+        assert_eq!(graph.depth(), 3);
+        assert!(graph.ancestors(4).contains(&1));
+        assert!(graph.descendants(1).contains(&4));
+    }
+
+    #[test]
+    fn test_graph_rejects_invalid_edges() {
+        let mut graph = ThoughtGraph::new();
+        graph.add_thought(1, &[]).unwrap();
+        graph.add_thought(2, &[1]).unwrap();
+
+        assert!(graph.add_thought(3, &[3]).is_err());
+        assert!(graph.add_thought(3, &[9]).is_err());
+        // Re-adding thought 1 as dependent on 2 would close the loop 1 -> 2 -> 1
+        assert!(graph.add_thought(1, &[2]).is_err());
+        assert_eq!(graph.dependencies(1), Vec::<u32>::new());
+    }
+}
@@ -0,0 +1,303 @@
+//! # Load Testing
+//!
+//! Drives a server with concurrent thinking sessions and measures how it copes.
+//!
+//! Each session adds its thoughts one after another, as an agent would, while
+//! up to `concurrency` sessions run at once. The report gives throughput,
+//! latency percentiles and error rates, which is what sizing a deployment
+//! needs. The server checks thought numbers against one shared sequence, so
+//! servers under a concurrent load test should use the `auto_renumber`
+//! numbering policy.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+use crate::thinking::client::SequentialThinkingClient;
+use crate::thinking::ThoughtData;
+
+/// Shape of a load test
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoadTestConfig {
+    /// Number of sessions to run
+    pub sessions: usize,
+    /// Thoughts added per session
+    pub thoughts: u32,
+    /// Sessions running at the same time
+    pub concurrency: usize,
+}
+
+impl Default for LoadTestConfig {
+    fn default() -> Self {
+        Self {
+            sessions: 10,
+            thoughts: 10,
+            concurrency: 4,
+        }
+    }
+}
+
+impl LoadTestConfig {
+    /// Validate the load test shape, returning every problem found
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.sessions == 0 {
+            errors.push("sessions: must be greater than 0".to_string());
+        }
+        if self.thoughts == 0 {
+            errors.push("thoughts: must be greater than 0".to_string());
+        }
+        if self.concurrency == 0 {
+            errors.push("concurrency: must be greater than 0".to_string());
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Latency distribution of successful requests, in milliseconds
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencySummary {
+    /// Fastest request
+    pub min: f64,
+    /// Mean latency
+    pub mean: f64,
+    /// Median latency
+    pub p50: f64,
+    /// 90th percentile
+    pub p90: f64,
+    /// 99th percentile
+    pub p99: f64,
+    /// Slowest request
+    pub max: f64,
+}
+
+impl LatencySummary {
+    /// Summarize latency samples using nearest-rank percentiles
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut millis: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        millis.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            let rank = (p / 100.0 * millis.len() as f64).ceil() as usize;
+            millis[rank.clamp(1, millis.len()) - 1]
+        };
+        Self {
+            min: millis[0],
+            mean: millis.iter().sum::<f64>() / millis.len() as f64,
+            p50: percentile(50.0),
+            p90: percentile(90.0),
+            p99: percentile(99.0),
+            max: millis[millis.len() - 1],
+        }
+    }
+}
+
+/// Outcome of one request
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestOutcome {
+    /// Time until the response arrived
+    pub latency: Duration,
+    /// Error code of a failed request
+    pub error: Option<String>,
+}
+
+/// Results of a load test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadTestReport {
+    /// Load test shape
+    pub config: LoadTestConfig,
+    /// Sessions that could not be started
+    pub failed_sessions: usize,
+    /// Thoughts sent
+    pub requests: usize,
+    /// Thoughts the server accepted
+    pub succeeded: usize,
+    /// Thoughts that failed
+    pub failed: usize,
+    /// Share of thoughts that failed (0.0 to 1.0)
+    pub error_rate: f64,
+    /// Failed thoughts by error code
+    pub errors: BTreeMap<String, usize>,
+    /// Wall-clock duration of the test in milliseconds
+    pub duration_ms: f64,
+    /// Accepted thoughts per second
+    pub throughput: f64,
+    /// Latency of accepted thoughts
+    pub latency: LatencySummary,
+}
+
+impl LoadTestReport {
+    /// Build a report from the outcome of every request
+    pub fn from_outcomes(
+        config: LoadTestConfig,
+        failed_sessions: usize,
+        outcomes: &[RequestOutcome],
+        elapsed: Duration,
+    ) -> Self {
+        let mut errors = BTreeMap::new();
+        let mut latencies = Vec::with_capacity(outcomes.len());
+        for outcome in outcomes {
+            match &outcome.error {
+                Some(code) => *errors.entry(code.clone()).or_insert(0) += 1,
+                None => latencies.push(outcome.latency),
+            }
+        }
+        let failed = outcomes.len() - latencies.len();
+        let seconds = elapsed.as_secs_f64();
+
+        Self {
+            config,
+            failed_sessions,
+            requests: outcomes.len(),
+            succeeded: latencies.len(),
+            failed,
+            error_rate: if outcomes.is_empty() {
+                0.0
+            } else {
+                failed as f64 / outcomes.len() as f64
+            },
+            errors,
+            duration_ms: seconds * 1000.0,
+            throughput: if seconds > 0.0 {
+                latencies.len() as f64 / seconds
+            } else {
+                0.0
+            },
+            latency: LatencySummary::from_samples(&latencies),
+        }
+    }
+}
+
+/// Run a load test through a client
+///
+/// The client should have auto-save disabled, and sessions are removed from
+/// it once they finish so long runs do not accumulate state.
+pub async fn run(client: &SequentialThinkingClient, config: LoadTestConfig) -> LoadTestReport {
+    run_with_progress(client, config, |_| {}).await
+}
+
+/// Run a load test, calling `on_request` as each request finishes
+///
+/// Requests of sessions that could not be started are not reported.
+pub async fn run_with_progress<F>(
+    client: &SequentialThinkingClient,
+    config: LoadTestConfig,
+    on_request: F,
+) -> LoadTestReport
+where
+    F: Fn(&RequestOutcome) + Send + Sync + 'static,
+{
+    let on_request = Arc::new(on_request);
+    let permits = Arc::new(Semaphore::new(config.concurrency.max(1)));
+    let started = Instant::now();
+
+    let tasks: Vec<_> = (0..config.sessions)
+        .map(|index| {
+            let client = client.clone();
+            let permits = permits.clone();
+            let on_request = on_request.clone();
+            let thoughts = config.thoughts;
+            tokio::spawn(async move {
+                let _permit = permits.acquire_owned().await.ok()?;
+                let session = client
+                    .start_session(format!("Load test session {}", index + 1))
+                    .await
+                    .ok()?;
+
+                let mut outcomes = Vec::with_capacity(thoughts as usize);
+                for n in 1..=thoughts {
+                    let mut thought = ThoughtData::new(
+                        format!("Load test thought {n} of session {}", index + 1),
+                        n,
+                        thoughts,
+                    );
+                    thought.next_thought_needed = n < thoughts;
+                    let sent = Instant::now();
+                    let error = session
+                        .add_thought(thought, None)
+                        .await
+                        .err()
+                        .map(|e| e.error_code().to_string());
+                    let outcome = RequestOutcome {
+                        latency: sent.elapsed(),
+                        error,
+                    };
+                    on_request(&outcome);
+                    outcomes.push(outcome);
+                }
+                client.remove_session(session.id()).await;
+                Some(outcomes)
+            })
+        })
+        .collect();
+
+    let mut outcomes = Vec::new();
+    let mut failed_sessions = 0;
+    for task in tasks {
+        match task.await {
+            Ok(Some(session)) => outcomes.extend(session),
+            _ => failed_sessions += 1,
+        }
+    }
+
+    LoadTestReport::from_outcomes(config, failed_sessions, &outcomes, started.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_test_report() {
+        let ms = Duration::from_millis;
+        let mut outcomes: Vec<RequestOutcome> = (1..=100)
+            .map(|n| RequestOutcome {
+                latency: ms(n),
+                error: None,
+            })
+            .collect();
+        outcomes.push(RequestOutcome {
+            latency: ms(500),
+            error: Some("TIMEOUT".to_string()),
+        });
+        outcomes.push(RequestOutcome {
+            latency: ms(1),
+            error: Some("OUT_OF_SEQUENCE".to_string()),
+        });
+
+        let report =
+            LoadTestReport::from_outcomes(LoadTestConfig::default(), 1, &outcomes, ms(2000));
+        assert_eq!(
+            (report.requests, report.succeeded, report.failed),
+            (102, 100, 2)
+        );
+        assert_eq!(report.failed_sessions, 1);
+        assert!((report.error_rate - 2.0 / 102.0).abs() < 1e-9);
+        assert_eq!(report.errors["TIMEOUT"], 1);
+        assert!((report.throughput - 50.0).abs() < 1e-9);
+
+        let latency = &report.latency;
+        assert_eq!((latency.min, latency.max), (1.0, 100.0));
+        assert_eq!((latency.p50, latency.p90, latency.p99), (50.0, 90.0, 99.0));
+        assert!((latency.mean - 50.5).abs() < 1e-9);
+
+        assert_eq!(LatencySummary::from_samples(&[]), LatencySummary::default());
+        assert_eq!(
+            LoadTestConfig {
+                concurrency: 0,
+                ..LoadTestConfig::default()
+            }
+            .validate()
+            .unwrap_err(),
+            vec!["concurrency: must be greater than 0"]
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! # Metadata Schemas
+//!
+//! Validation of thought metadata against named JSON Schemas.
+//!
+//! [`ThoughtData::metadata`](crate::thinking::ThoughtData) is a free-form map.
+//! A session can declare one of the schemas registered in the thinking
+//! configuration, and the engine then rejects thoughts whose metadata does not
+//! match it, listing every offending field.
+//!
+//! The common structural keywords are supported: `type`, `enum`, `const`,
+//! `properties`, `required`, `additionalProperties`, `items`, `minimum`,
+//! `maximum`, `minLength`, `maxLength`, `minItems` and `maxItems`. Other
+//! keywords are ignored. The server checks tool arguments against the input
+//! schemas of its tools with the same validator, through [`validate_json`].
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Types the `type` keyword accepts
+const TYPES: &[&str] = &[
+    "null", "boolean", "integer", "number", "string", "array", "object",
+];
+
+/// Named JSON Schema that thought metadata must match
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataSchema {
+    /// Name the schema is registered under
+    pub name: String,
+    /// JSON Schema of the metadata object
+    pub schema: Value,
+}
+
+impl MetadataSchema {
+    /// Create a schema, checking that it is usable
+    pub fn new(name: impl Into<String>, schema: Value) -> Result<Self, Vec<String>> {
+        let errors = check_schema(&schema, "");
+        if errors.is_empty() {
+            Ok(Self {
+                name: name.into(),
+                schema,
+            })
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Validate a thought's metadata, returning an error per offending field
+    ///
+    /// Missing metadata is validated as an empty object, so required keys
+    /// are still enforced.
+    pub fn validate(&self, metadata: Option<&HashMap<String, Value>>) -> Result<(), Vec<String>> {
+        let value = Value::Object(
+            metadata
+                .into_iter()
+                .flatten()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        );
+        validate_json(&value, &self.schema, "metadata")
+    }
+}
+
+/// Check that a schema only uses keywords the way the validator expects
+pub fn check_schema(schema: &Value, path: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+    let Some(object) = schema.as_object() else {
+        let path = if path.is_empty() { "schema" } else { path };
+        return vec![format!("{path}: must be an object")];
+    };
+    let at = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+
+    if let Some(types) = object.get("type") {
+        let names: Vec<&Value> = match types {
+            Value::Array(names) => names.iter().collect(),
+            name => vec![name],
+        };
+        for name in names {
+            if !name.as_str().is_some_and(|name| TYPES.contains(&name)) {
+                errors.push(format!("{}: unknown type {name}", at("type")));
+            }
+        }
+    }
+    if let Some(properties) = object.get("properties") {
+        match properties.as_object() {
+            Some(properties) => {
+                for (key, property) in properties {
+                    errors.extend(check_schema(property, &at(&format!("properties.{key}"))));
+                }
+            }
+            None => errors.push(format!("{}: must be an object", at("properties"))),
+        }
+    }
+    if let Some(required) = object.get("required") {
+        if !required
+            .as_array()
+            .is_some_and(|keys| keys.iter().all(Value::is_string))
+        {
+            errors.push(format!("{}: must be an array of strings", at("required")));
+        }
+    }
+    for key in ["items", "additionalProperties"] {
+        match object.get(key) {
+            Some(Value::Bool(_)) | None => {}
+            Some(nested) => errors.extend(check_schema(nested, &at(key))),
+        }
+    }
+    for key in ["minimum", "maximum"] {
+        if object.get(key).is_some_and(|bound| !bound.is_number()) {
+            errors.push(format!("{}: must be a number", at(key)));
+        }
+    }
+    for key in ["minLength", "maxLength", "minItems", "maxItems"] {
+        if object.get(key).is_some_and(|bound| !bound.is_u64()) {
+            errors.push(format!("{}: must be a non-negative integer", at(key)));
+        }
+    }
+    errors
+}
+
+/// Validate a value against a schema, returning an error per offending field
+///
+/// Each error starts with the path of the field, rooted at `path`.
+pub fn validate_json(value: &Value, schema: &Value, path: &str) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    validate_value(value, schema, path, &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// JSON Schema type name of a value
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Check whether a value has a JSON Schema type
+fn has_type(value: &Value, expected: &str) -> bool {
+    let actual = type_name(value);
+    actual == expected || (expected == "number" && actual == "integer")
+}
+
+/// Validate a value against a schema, collecting errors under `path`
+fn validate_value(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(types) = schema.get("type") {
+        let expected: Vec<&str> = match types {
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            name => name.as_str().into_iter().collect(),
+        };
+        if !expected.iter().any(|name| has_type(value, name)) {
+            errors.push(format!(
+                "{path}: expected {}, found {}",
+                expected.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            errors.push(format!(
+                "{path}: must be one of {}",
+                Value::Array(allowed.clone())
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            errors.push(format!("{path}: must be {expected}"));
+        }
+    }
+
+    match value {
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    errors.push(format!("{path}: must be at least {min}"));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    errors.push(format!("{path}: must be at most {max}"));
+                }
+            }
+        }
+        Value::String(s) => {
+            let length = s.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if length < min {
+                    errors.push(format!("{path}: must be at least {min} characters"));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if length > max {
+                    errors.push(format!("{path}: must be at most {max} characters"));
+                }
+            }
+        }
+        Value::Array(items) => {
+            let count = items.len() as u64;
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if count < min {
+                    errors.push(format!("{path}: must have at least {min} items"));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if count > max {
+                    errors.push(format!("{path}: must have at most {max} items"));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_value(item, item_schema, &format!("{path}[{i}]"), errors);
+                }
+            }
+        }
+        Value::Object(fields) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !fields.contains_key(key) {
+                        errors.push(format!("{path}.{key}: is required"));
+                    }
+                }
+            }
+            let mut keys: Vec<&String> = fields.keys().collect();
+            keys.sort();
+            for key in keys {
+                let field_path = format!("{path}.{key}");
+                match (
+                    properties.and_then(|p| p.get(key)),
+                    schema.get("additionalProperties"),
+                ) {
+                    (Some(property), _) => {
+                        validate_value(&fields[key], property, &field_path, errors)
+                    }
+                    (None, Some(Value::Bool(false))) => {
+                        errors.push(format!("{field_path}: is not allowed"))
+                    }
+                    (None, Some(additional)) => {
+                        validate_value(&fields[key], additional, &field_path, errors)
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::{ThinkingEngine, ThoughtData};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_metadata_schema() {
+        let schema = MetadataSchema::new(
+            "review",
+            json!({
+                "type": "object",
+                "properties": {
+                    "confidence": { "type": "number", "minimum": 0, "maximum": 1 },
+                    "stage": { "enum": ["draft", "final"] },
+                    "sources": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["confidence"],
+                "additionalProperties": false
+            }),
+        )
+        .unwrap();
+
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.set_metadata_schema(Some(schema));
+        let thought = |n: u32| ThoughtData::new(format!("Review step {n}"), n, 3);
+        engine
+            .process_thought(
+                thought(1)
+                    .with_metadata("confidence".to_string(), json!(0.8))
+                    .with_metadata("sources".to_string(), json!(["spec"])),
+            )
+            .await
+            .unwrap();
+
+        let error = engine
+            .process_thought(
+                thought(2)
+                    .with_metadata("confidence".to_string(), json!("high"))
+                    .with_metadata("stage".to_string(), json!("done"))
+                    .with_metadata("sources".to_string(), json!(["spec", 3]))
+                    .with_metadata("extra".to_string(), json!(true)),
+            )
+            .await
+            .unwrap_err();
+        for expected in [
+            "metadata.confidence: expected number, found string",
+            "metadata.extra: is not allowed",
+            "metadata.sources[1]: expected string, found integer",
+            "metadata.stage: must be one of",
+        ] {
+            assert!(error.contains(expected), "{expected} missing from {error}");
+        }
+        assert!(engine
+            .process_thought(thought(2))
+            .await
+            .unwrap_err()
+            .contains("metadata.confidence: is required"));
+        assert_eq!(engine.thought_count(), 1);
+
+        assert_eq!(
+            MetadataSchema::new("bad", json!({ "type": "text", "minLength": -1 })).unwrap_err(),
+            vec![
+                "type: unknown type \"text\"",
+                "minLength: must be a non-negative integer"
+            ]
+        );
+    }
+}
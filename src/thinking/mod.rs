@@ -6,14 +6,57 @@
 //! thinking processes, including thought data structures, processing logic,
 //! and the main thinking engine.
 
+pub mod actor;
+pub mod approval;
+pub mod attachment;
+pub mod backpressure;
+pub mod cache;
+pub mod checkpoint;
 pub mod client;
+pub mod clock;
+pub mod code;
+pub mod cors;
+pub mod elicitation;
 pub mod error;
+pub mod graph;
+pub mod loadtest;
+pub mod metadata;
+pub mod replay;
+pub mod response;
+pub mod sampling;
+pub mod sanitize;
+pub mod sequence;
 pub mod server;
+pub mod spill;
+pub mod strategy;
+pub mod structured;
+pub mod transcript;
+pub mod transport;
 
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
+use crate::annotations::{Annotation, AnnotationStore};
+use crate::thinking::attachment::{Attachment, AttachmentLimits};
+use crate::thinking::clock::{SharedClock, SystemClock};
+use crate::thinking::graph::ThoughtGraph;
+use crate::thinking::metadata::MetadataSchema;
+use crate::thinking::sanitize::SanitizeConfig;
+use crate::thinking::sequence::{NumberingPolicy, SequenceError};
+use crate::thinking::spill::SharedSpillStore;
+use crate::thinking::strategy::{StrategyContext, ThinkingStrategy};
+use crate::visualization::ThoughtRenderer;
+
+/// A recorded thought shared between the engine, its branches and readers
+///
+/// Sessions hand out these instead of copies, so exporting or snapshotting a
+/// large session does not duplicate every thought's text.
+pub type SharedThought = Arc<ThoughtData>;
+
 /// Core data structure for a single thought in the sequential thinking process
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ThoughtData {
@@ -40,12 +83,24 @@ pub struct ThoughtData {
     /// Whether more thoughts are needed (if reaching end but realizing more needed)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub needs_more_thoughts: Option<bool>,
+    /// Thought numbers this thought builds on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<u32>,
     /// Timestamp when this thought was created
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp: Option<chrono::DateTime<chrono::Utc>>,
     /// Metadata associated with this thought
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
+    /// Agent or human that contributed this thought
+    #[serde(default, alias = "agent_id", skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Client-chosen key identifying this submission, so retries are not recorded twice
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// Code snippets, files, images and links attached to this thought
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
 }
 
 impl Default for ThoughtData {
@@ -60,8 +115,12 @@ impl Default for ThoughtData {
             branch_from_thought: None,
             branch_id: None,
             needs_more_thoughts: None,
+            depends_on: Vec::new(),
             timestamp: Some(chrono::Utc::now()),
             metadata: None,
+            author: None,
+            idempotency_key: None,
+            attachments: Vec::new(),
         }
     }
 }
@@ -79,8 +138,12 @@ impl ThoughtData {
             branch_from_thought: None,
             branch_id: None,
             needs_more_thoughts: None,
+            depends_on: Vec::new(),
             timestamp: Some(chrono::Utc::now()),
             metadata: None,
+            author: None,
+            idempotency_key: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -96,8 +159,12 @@ impl ThoughtData {
             branch_from_thought: None,
             branch_id: None,
             needs_more_thoughts: None,
+            depends_on: Vec::new(),
             timestamp: Some(chrono::Utc::now()),
             metadata: None,
+            author: None,
+            idempotency_key: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -118,8 +185,12 @@ impl ThoughtData {
             branch_from_thought: Some(branch_from_thought),
             branch_id: Some(branch_id),
             needs_more_thoughts: None,
+            depends_on: Vec::new(),
             timestamp: Some(chrono::Utc::now()),
             metadata: None,
+            author: None,
+            idempotency_key: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -143,6 +214,30 @@ impl ThoughtData {
         self.revises_thought
     }
 
+    /// Declare the thoughts this thought builds on
+    pub fn with_dependencies(mut self, depends_on: Vec<u32>) -> Self {
+        self.depends_on = depends_on;
+        self
+    }
+
+    /// Attribute this thought to an agent or human
+    pub fn with_author(mut self, author: String) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Identify retries of this submission so they are recorded only once
+    pub fn with_idempotency_key(mut self, key: String) -> Self {
+        self.idempotency_key = Some(key);
+        self
+    }
+
+    /// Attach content to this thought
+    pub fn with_attachment(mut self, attachment: Attachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+
     /// Add metadata to this thought
     pub fn with_metadata(mut self, key: String, value: serde_json::Value) -> Self {
         if self.metadata.is_none() {
@@ -154,6 +249,127 @@ impl ThoughtData {
         self
     }
 
+    /// Read a thought from the arguments of a `sequential_thinking` tool call
+    ///
+    /// Arguments come from untrusted hosts, so any JSON value is accepted and
+    /// malformed input is reported as an error, never a panic. Numbers that
+    /// do not fit a thought number are rejected rather than truncated. The
+    /// thought is left without a timestamp for the engine's clock to set.
+    pub fn from_tool_arguments(args: &serde_json::Value) -> Result<Self, String> {
+        let thought = args
+            .get("thought")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "Missing 'thought' field".to_string())?
+            .to_string();
+
+        let thought_number = args
+            .get("thoughtNumber")
+            .filter(|v| v.is_u64())
+            .map(|v| thought_number_field(v, "thoughtNumber"))
+            .ok_or_else(|| "Missing 'thoughtNumber' field".to_string())??;
+
+        let total_thoughts = args
+            .get("totalThoughts")
+            .filter(|v| v.is_u64())
+            .map(|v| thought_number_field(v, "totalThoughts"))
+            .ok_or_else(|| "Missing 'totalThoughts' field".to_string())??;
+
+        let next_thought_needed = args
+            .get("nextThoughtNeeded")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let is_revision = args.get("isRevision").and_then(|v| v.as_bool());
+        let revises_thought = args
+            .get("revisesThought")
+            .filter(|v| v.is_u64())
+            .map(|v| thought_number_field(v, "revisesThought"))
+            .transpose()?;
+        let branch_from_thought = args
+            .get("branchFromThought")
+            .filter(|v| v.is_u64())
+            .map(|v| thought_number_field(v, "branchFromThought"))
+            .transpose()?;
+        let branch_id = args
+            .get("branchId")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let needs_more_thoughts = args.get("needsMoreThoughts").and_then(|v| v.as_bool());
+        let depends_on = args
+            .get("dependsOn")
+            .and_then(|v| v.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .map(|v| thought_number_field(v, "dependsOn"))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+        let author = args
+            .get("author")
+            .or_else(|| args.get("agentId"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let idempotency_key = args
+            .get("idempotencyKey")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let attachments = match args.get("attachments") {
+            None | Some(serde_json::Value::Null) => Vec::new(),
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|e| format!("Invalid 'attachments' field: {e}"))?,
+        };
+        let metadata = match args.get("metadata") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::Object(fields)) => Some(
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            ),
+            Some(_) => return Err("'metadata' must be an object".to_string()),
+        };
+
+        Ok(Self {
+            thought,
+            thought_number,
+            total_thoughts,
+            next_thought_needed,
+            is_revision,
+            revises_thought,
+            branch_from_thought,
+            branch_id,
+            needs_more_thoughts,
+            depends_on,
+            timestamp: None,
+            metadata,
+            author,
+            idempotency_key,
+            attachments,
+        })
+    }
+
+    /// Arguments of a `sequential_thinking` tool call submitting this thought
+    pub fn to_tool_arguments(&self) -> serde_json::Value {
+        serde_json::json!({
+            "thought": self.thought,
+            "thoughtNumber": self.thought_number,
+            "totalThoughts": self.total_thoughts,
+            "nextThoughtNeeded": self.next_thought_needed,
+            "isRevision": self.is_revision,
+            "revisesThought": self.revises_thought,
+            "branchFromThought": self.branch_from_thought,
+            "branchId": self.branch_id,
+            "needsMoreThoughts": self.needs_more_thoughts,
+            "dependsOn": self.depends_on,
+            "author": self.author,
+            "idempotencyKey": self.idempotency_key,
+            "metadata": self.metadata,
+            "attachments": self.attachments
+        })
+    }
+
     /// Validate the thought data
     pub fn validate(&self) -> Result<(), String> {
         if self.thought.is_empty() {
@@ -176,10 +392,21 @@ impl ThoughtData {
         if self.is_branch() && self.branch_id.is_none() {
             return Err("Branch thoughts must have a branch ID".to_string());
         }
+        if self.depends_on.contains(&self.thought_number) {
+            return Err("Thoughts cannot depend on themselves".to_string());
+        }
         Ok(())
     }
 }
 
+/// Read a thought number from a JSON integer, rejecting ones too large
+pub(crate) fn thought_number_field(value: &serde_json::Value, field: &str) -> Result<u32, String> {
+    let number = value
+        .as_u64()
+        .ok_or_else(|| format!("'{field}' must hold non-negative integers"))?;
+    u32::try_from(number).map_err(|_| format!("'{field}' must be at most {}", u32::MAX))
+}
+
 /// A collection of thoughts that form a branch
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThoughtBranch {
@@ -187,8 +414,8 @@ pub struct ThoughtBranch {
     pub branch_id: String,
     /// Parent thought number
     pub parent_thought: u32,
-    /// Thoughts in this branch
-    pub thoughts: Vec<ThoughtData>,
+    /// Thoughts in this branch, shared with the session's main thought list
+    pub thoughts: Vec<SharedThought>,
     /// Branch metadata
     pub metadata: HashMap<String, serde_json::Value>,
     /// When the branch was created
@@ -208,8 +435,8 @@ impl ThoughtBranch {
     }
 
     /// Add a thought to this branch
-    pub fn add_thought(&mut self, thought: ThoughtData) {
-        self.thoughts.push(thought);
+    pub fn add_thought(&mut self, thought: impl Into<SharedThought>) {
+        self.thoughts.push(thought.into());
     }
 
     /// Get the number of thoughts in this branch
@@ -219,10 +446,63 @@ impl ThoughtBranch {
 
     /// Get the latest thought in this branch
     pub fn latest_thought(&self) -> Option<&ThoughtData> {
-        self.thoughts.last()
+        self.thoughts.last().map(Arc::as_ref)
     }
 }
 
+/// Summary of one branch in a branch comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchSummary {
+    /// Branch identifier
+    pub branch_id: String,
+    /// Thought the branch forked from
+    pub parent_thought: u32,
+    /// Number of thoughts in the branch
+    pub thought_count: usize,
+    /// Total length of branch thoughts in characters
+    pub total_length: usize,
+    /// Average branch thought length in characters
+    pub avg_thought_length: f64,
+    /// Overall quality score of the branch's reasoning path
+    pub quality_score: f64,
+    /// Whether the branch reached a conclusion
+    pub concluded: bool,
+}
+
+/// Diff between two branches of the same session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchComparison {
+    /// First branch
+    pub branch_a: BranchSummary,
+    /// Second branch
+    pub branch_b: BranchSummary,
+    /// Main-line thought numbers shared by both reasoning paths
+    pub shared_prefix: Vec<u32>,
+    /// Thought numbers only on the first branch's path
+    pub divergent_a: Vec<u32>,
+    /// Thought numbers only on the second branch's path
+    pub divergent_b: Vec<u32>,
+    /// Branch with the higher quality score, if they differ
+    pub preferred_branch: Option<String>,
+}
+
+/// Audit record of an in-place thought correction
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ThoughtEdit {
+    /// Number of the edited thought
+    pub thought_number: u32,
+    /// Branch of the edited thought, if any
+    pub branch_id: Option<String>,
+    /// Who made the edit
+    pub editor: Option<String>,
+    /// When the edit was made
+    pub edited_at: chrono::DateTime<chrono::Utc>,
+    /// Text before the edit
+    pub old_text: String,
+    /// Text after the edit
+    pub new_text: String,
+}
+
 /// Progress information for a thinking session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThinkingProgress {
@@ -240,6 +520,16 @@ pub struct ThinkingProgress {
     pub progress_percentage: f64,
     /// Estimated time remaining (if available)
     pub estimated_time_remaining: Option<std::time::Duration>,
+    /// When the session's first thought was written
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the latest thought was written
+    pub last_thought_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Time from the first to the latest thought
+    pub elapsed_time: Option<std::time::Duration>,
+    /// Average time between consecutive thoughts
+    pub avg_thought_time: Option<std::time::Duration>,
+    /// Time the session sat idle before the latest thought
+    pub idle_time: Option<std::time::Duration>,
 }
 
 impl ThinkingProgress {
@@ -260,6 +550,11 @@ impl ThinkingProgress {
             needs_more_thoughts: true,
             progress_percentage,
             estimated_time_remaining: None,
+            started_at: None,
+            last_thought_at: None,
+            elapsed_time: None,
+            avg_thought_time: None,
+            idle_time: None,
         }
     }
 
@@ -277,6 +572,31 @@ impl ThinkingProgress {
         };
     }
 
+    /// Record when the latest of `thoughts_recorded` thoughts was written
+    ///
+    /// Times come from thought timestamps. The time remaining is estimated
+    /// from the average time between thoughts so far, once there are two.
+    pub fn record_timing(&mut self, at: chrono::DateTime<chrono::Utc>, thoughts_recorded: u64) {
+        let since =
+            |earlier: chrono::DateTime<chrono::Utc>| (at - earlier).to_std().unwrap_or_default();
+        let started_at = *self.started_at.get_or_insert(at);
+        self.idle_time = self.last_thought_at.map(since);
+        self.last_thought_at = Some(at);
+
+        let elapsed = since(started_at);
+        self.elapsed_time = Some(elapsed);
+        self.avg_thought_time = u32::try_from(thoughts_recorded.saturating_sub(1))
+            .ok()
+            .filter(|&intervals| intervals > 0)
+            .map(|intervals| elapsed / intervals);
+        self.estimated_time_remaining = if self.needs_more_thoughts {
+            self.avg_thought_time
+                .map(|avg| avg * self.total_thoughts.saturating_sub(self.current_thought))
+        } else {
+            Some(std::time::Duration::ZERO)
+        };
+    }
+
     /// Check if the thinking process is complete
     pub fn is_complete(&self) -> bool {
         !self.needs_more_thoughts && self.completed_thoughts >= self.total_thoughts
@@ -311,6 +631,9 @@ pub struct ThinkingStats {
     pub total_processing_time_ms: u64,
     /// Total length of all thoughts (for avg_thought_length)
     pub total_thought_length: u64,
+    /// Total time spent paused, not counting a pause still in progress
+    #[serde(default)]
+    pub total_paused_time_ms: u64,
 }
 
 impl Default for ThinkingStats {
@@ -322,27 +645,54 @@ impl Default for ThinkingStats {
             avg_processing_time_ms: 0.0,
             total_processing_time_ms: 0,
             total_thought_length: 0,
+            total_paused_time_ms: 0,
         }
     }
 }
 
 /// Main thinking engine that coordinates the thinking process
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ThinkingEngine {
     /// Unique identifier for this engine instance
     id: Uuid,
     /// Current thinking session
     session_id: Option<String>,
     /// Thoughts in the current session
-    thoughts: Vec<ThoughtData>,
+    thoughts: Vec<SharedThought>,
     /// Active branches
     branches: HashMap<String, ThoughtBranch>,
+    /// Dependency graph between thoughts
+    graph: ThoughtGraph,
     /// Thinking progress
     progress: ThinkingProgress,
     /// Processing statistics
     stats: ThinkingStats,
+    /// Audit log of in-place thought edits
+    edits: Vec<ThoughtEdit>,
+    /// Reviewer annotations on thoughts
+    annotations: AnnotationStore,
     /// Whether thought logging is disabled
     disable_logging: bool,
+    /// How logged thoughts are rendered
+    renderer: ThoughtRenderer,
+    /// How out-of-sequence thought numbers are handled
+    numbering: NumberingPolicy,
+    /// Structure the session's thinking follows
+    strategy: ThinkingStrategy,
+    /// Schema thought metadata must match, if the session declared one
+    metadata_schema: Option<MetadataSchema>,
+    /// Limits on the attachments of each thought
+    attachment_limits: AttachmentLimits,
+    /// How thought text is cleaned before it is stored and logged
+    sanitize: SanitizeConfig,
+    /// Thoughts kept in memory and where older ones are spilled, if bounded
+    spill: Option<(usize, SharedSpillStore)>,
+    /// Thoughts of the session spilled out of memory
+    spilled: usize,
+    /// Source of thought timestamps, edit times and processing times
+    clock: SharedClock,
+    /// When the session was paused, if it is
+    paused_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl ThinkingEngine {
@@ -353,9 +703,22 @@ impl ThinkingEngine {
             session_id: None,
             thoughts: Vec::new(),
             branches: HashMap::new(),
+            graph: ThoughtGraph::new(),
             progress: ThinkingProgress::new(1, 1),
             stats: ThinkingStats::default(),
+            edits: Vec::new(),
+            annotations: AnnotationStore::new(),
             disable_logging: false,
+            renderer: ThoughtRenderer::default(),
+            numbering: NumberingPolicy::default(),
+            strategy: ThinkingStrategy::default(),
+            metadata_schema: None,
+            attachment_limits: AttachmentLimits::default(),
+            sanitize: SanitizeConfig::default(),
+            spill: None,
+            spilled: 0,
+            clock: SystemClock::shared(),
+            paused_at: None,
         }
     }
 
@@ -367,30 +730,245 @@ impl ThinkingEngine {
         }
     }
 
+    /// Set the clock thoughts are timestamped and timed with
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+
+    /// Get the clock thoughts are timestamped and timed with
+    pub fn clock(&self) -> &SharedClock {
+        &self.clock
+    }
+
+    /// Set how logged thoughts are rendered
+    pub fn set_renderer(&mut self, renderer: ThoughtRenderer) {
+        self.renderer = renderer;
+    }
+
+    /// Set how out-of-sequence thought numbers are handled
+    pub fn set_numbering_policy(&mut self, numbering: NumberingPolicy) {
+        self.numbering = numbering;
+    }
+
+    /// Get how out-of-sequence thought numbers are handled
+    pub fn numbering_policy(&self) -> NumberingPolicy {
+        self.numbering
+    }
+
+    /// Set the strategy that validates thoughts and guides the next step
+    pub fn set_strategy(&mut self, strategy: ThinkingStrategy) {
+        self.strategy = strategy;
+    }
+
+    /// Get the strategy that validates thoughts and guides the next step
+    pub fn strategy(&self) -> ThinkingStrategy {
+        self.strategy
+    }
+
+    /// Set the schema thought metadata must match, or `None` to accept any
+    pub fn set_metadata_schema(&mut self, schema: Option<MetadataSchema>) {
+        self.metadata_schema = schema;
+    }
+
+    /// Get the schema thought metadata must match
+    pub fn metadata_schema(&self) -> Option<&MetadataSchema> {
+        self.metadata_schema.as_ref()
+    }
+
+    /// Set the limits on the attachments of each thought
+    pub fn set_attachment_limits(&mut self, limits: AttachmentLimits) {
+        self.attachment_limits = limits;
+    }
+
+    /// Set how thought text is cleaned before it is stored and logged
+    pub fn set_sanitize_config(&mut self, sanitize: SanitizeConfig) {
+        self.sanitize = sanitize;
+    }
+
+    /// Guidance for the step after a processed thought, if the strategy has any
+    pub fn guidance(&self, thought: &ThoughtData) -> Option<String> {
+        self.strategy.guidance(
+            thought,
+            StrategyContext {
+                position: self.thought_count(),
+                branches: self.branches.len(),
+                revisions: self.stats.total_revisions as usize,
+            },
+        )
+    }
+
+    /// Keep at most `max_in_memory` thoughts in memory, spilling older ones to `store`
+    ///
+    /// Spilled thoughts are loaded back by [`all_thoughts`](Self::all_thoughts)
+    /// but can no longer be edited in place. Branches keep their own copies.
+    pub fn set_bounded_memory(&mut self, max_in_memory: usize, store: SharedSpillStore) {
+        self.spill = Some((max_in_memory.max(1), store));
+        self.spill_excess();
+    }
+
+    /// Key the session's thoughts are spilled under
+    fn spill_key(&self) -> String {
+        self.session_id
+            .clone()
+            .unwrap_or_else(|| self.id.to_string())
+    }
+
+    /// Spill the oldest thoughts beyond the memory limit
+    ///
+    /// If the store fails the thoughts stay in memory, so nothing is lost.
+    fn spill_excess(&mut self) {
+        let Some((limit, store)) = &self.spill else {
+            return;
+        };
+        let excess = self.thoughts.len().saturating_sub(*limit);
+        if excess == 0 {
+            return;
+        }
+        let appended = match store.lock() {
+            Ok(mut store) => store.append(&self.spill_key(), &self.thoughts[..excess]),
+            Err(_) => Err("spill store lock poisoned".to_string()),
+        };
+        match appended {
+            Ok(()) => {
+                self.thoughts.drain(..excess);
+                self.spilled += excess;
+            }
+            Err(e) => tracing::warn!("Keeping thoughts in memory: {e}"),
+        }
+    }
+
+    /// Drop the session's spilled thoughts
+    fn clear_spilled(&mut self) {
+        if let Some((_, store)) = &self.spill {
+            if let Err(e) = store
+                .lock()
+                .map_err(|_| "spill store lock poisoned".to_string())
+                .and_then(|mut store| store.clear(&self.spill_key()))
+            {
+                tracing::warn!("Failed to clear spilled thoughts: {e}");
+            }
+        }
+        self.spilled = 0;
+    }
+
+    /// Check a thought's number against the previous thought
+    ///
+    /// Returns the number the thought would be recorded under.
+    pub fn check_sequence(&self, thought: &ThoughtData) -> Result<u32, SequenceError> {
+        let previous = self.thoughts.last().map(|t| t.thought_number);
+        self.numbering.check(previous, thought)
+    }
+
     /// Start a new thinking session
     pub fn start_session(&mut self, session_id: String) {
+        if self.spilled > 0 {
+            self.clear_spilled();
+        }
         self.session_id = Some(session_id);
+        // Also drops anything left over from an earlier run of this session
+        self.clear_spilled();
         self.thoughts.clear();
         self.branches.clear();
+        self.graph.clear();
         self.progress = ThinkingProgress::new(1, 1);
         self.stats = ThinkingStats::default();
+        self.edits.clear();
+        self.annotations.clear();
+        self.paused_at = None;
+    }
+
+    /// Pause the session, rejecting new thoughts until it is resumed
+    pub fn pause(&mut self) -> Result<(), String> {
+        if self.paused_at.is_some() {
+            return Err("Session is already paused".to_string());
+        }
+        self.paused_at = Some(self.clock.now());
+        Ok(())
+    }
+
+    /// Resume a paused session, returning how long it was paused
+    pub fn resume(&mut self) -> Result<std::time::Duration, String> {
+        let paused_at = self
+            .paused_at
+            .take()
+            .ok_or_else(|| "Session is not paused".to_string())?;
+        let paused_for = (self.clock.now() - paused_at).to_std().unwrap_or_default();
+        self.stats.total_paused_time_ms += paused_for.as_millis() as u64;
+        Ok(paused_for)
+    }
+
+    /// Check if the session is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Get the total time the session has spent paused, including a pause in progress
+    pub fn paused_time(&self) -> std::time::Duration {
+        let ongoing = self.paused_at.map_or(std::time::Duration::ZERO, |at| {
+            (self.clock.now() - at).to_std().unwrap_or_default()
+        });
+        std::time::Duration::from_millis(self.stats.total_paused_time_ms) + ongoing
     }
 
     /// Process a thought and add it to the session
-    pub async fn process_thought(&mut self, thought: ThoughtData) -> Result<ThoughtData, String> {
-        let start_time = std::time::Instant::now();
+    pub async fn process_thought(
+        &mut self,
+        mut thought: ThoughtData,
+    ) -> Result<ThoughtData, String> {
+        if self.paused_at.is_some() {
+            return Err("Session is paused; resume it before adding thoughts".to_string());
+        }
+        let start_time = self.clock.now();
+        self.sanitize.sanitize_thought(&mut thought);
 
-        // Validate the thought
+        // Validate the thought, its attachments and metadata, then check it against the strategy
         thought.validate()?;
+        self.attachment_limits.check(&thought.attachments)?;
+        if let Some(schema) = &self.metadata_schema {
+            schema
+                .validate(thought.metadata.as_ref())
+                .map_err(|errors| {
+                    format!(
+                        "Invalid metadata for schema '{}': {}",
+                        schema.name,
+                        errors.join("; ")
+                    )
+                })?;
+        }
+        let opens_branch = thought.branch_id.as_ref().is_some_and(|id| {
+            thought.branch_from_thought.is_some() && !self.branches.contains_key(id)
+        });
+        self.strategy.validate(
+            &thought,
+            StrategyContext {
+                position: self.thought_count() + 1,
+                branches: self.branches.len() + usize::from(opens_branch),
+                revisions: self.stats.total_revisions as usize + usize::from(thought.is_revision()),
+            },
+        )?;
+
+        // Enforce the numbering policy, renumbering if it asks for that
+        let mut processed_thought = thought.clone();
+        processed_thought.thought_number =
+            self.check_sequence(&thought).map_err(|e| e.to_string())?;
+        processed_thought.timestamp.get_or_insert(start_time);
 
         // Adjust total thoughts if needed
-        let mut processed_thought = thought.clone();
         if processed_thought.thought_number > processed_thought.total_thoughts {
             processed_thought.total_thoughts = processed_thought.thought_number;
         }
 
-        // Add to main thoughts
-        self.thoughts.push(processed_thought.clone());
+        // Record dependencies (rejects unknown thoughts and cycles)
+        self.graph.add_thought(
+            processed_thought.thought_number,
+            &processed_thought.depends_on,
+        )?;
+
+        // Add to main thoughts, sharing the record with its branch
+        let recorded_before = self.thought_count();
+        let shared = Arc::new(processed_thought.clone());
+        self.thoughts.push(shared.clone());
+        self.spill_excess();
 
         // Handle branching
         if let (Some(branch_from), Some(branch_id)) = (
@@ -401,16 +979,18 @@ impl ThinkingEngine {
                 .branches
                 .entry(branch_id.clone())
                 .or_insert_with(|| ThoughtBranch::new(branch_id.clone(), branch_from));
-            branch.add_thought(processed_thought.clone());
+            branch.add_thought(shared.clone());
         }
 
         // Update progress
         self.progress.update(&processed_thought);
 
         // Update statistics
-        let processing_time = start_time.elapsed();
+        let processing_time = self.clock.now() - start_time;
+        let counted_before = self.stats.total_thoughts;
         self.stats.total_thoughts += 1;
-        self.stats.total_processing_time_ms += processing_time.as_millis() as u64;
+        self.stats.total_thought_length += processed_thought.thought.len() as u64;
+        self.stats.total_processing_time_ms += processing_time.num_milliseconds().max(0) as u64;
         self.stats.avg_processing_time_ms =
             self.stats.total_processing_time_ms as f64 / self.stats.total_thoughts as f64;
 
@@ -421,6 +1001,25 @@ impl ThinkingEngine {
             self.stats.total_branches += 1;
         }
 
+        // Update timing from the thought's timestamp
+        self.progress.record_timing(
+            processed_thought.timestamp.unwrap_or(start_time),
+            self.stats.total_thoughts,
+        );
+
+        debug_assert_eq!(self.thought_count(), recorded_before + 1);
+        debug_assert_eq!(self.stats.total_thoughts, counted_before + 1);
+        debug_assert_eq!(
+            self.progress.current_thought,
+            processed_thought.thought_number
+        );
+        if let (true, Some(id)) = (processed_thought.is_branch(), &processed_thought.branch_id) {
+            debug_assert!(self.branches[id]
+                .thoughts
+                .last()
+                .is_some_and(|last| Arc::ptr_eq(last, &shared)));
+        }
+
         // Log the thought if logging is enabled
         if !self.disable_logging {
             self.log_thought(&processed_thought);
@@ -429,21 +1028,386 @@ impl ThinkingEngine {
         Ok(processed_thought)
     }
 
+    /// Process a thought unless the token is cancelled first
+    ///
+    /// Cancellation is observed before the thought is recorded, so an
+    /// aborted call never leaves a partially processed thought behind.
+    pub async fn process_thought_with_cancellation(
+        &mut self,
+        thought: ThoughtData,
+        token: &CancellationToken,
+    ) -> Result<ThoughtData, String> {
+        tokio::select! {
+            biased;
+            _ = token.cancelled() => Err("Thought processing was cancelled".to_string()),
+            result = self.process_thought(thought) => result,
+        }
+    }
+
     /// Get the current thinking progress
     pub fn get_progress(&self) -> &ThinkingProgress {
         &self.progress
     }
 
-    /// Get all thoughts in the current session
-    pub fn get_thoughts(&self) -> &[ThoughtData] {
+    /// Get the thoughts of the current session held in memory
+    ///
+    /// In bounded-memory mode older thoughts may have been spilled; use
+    /// [`all_thoughts`](Self::all_thoughts) for the whole session.
+    pub fn get_thoughts(&self) -> &[SharedThought] {
         &self.thoughts
     }
 
+    /// Get every thought in the current session, loading spilled ones back
+    ///
+    /// If the spill store cannot be read only the in-memory thoughts are returned.
+    pub fn all_thoughts(&self) -> Cow<'_, [SharedThought]> {
+        let Some((_, store)) = self.spill.as_ref().filter(|_| self.spilled > 0) else {
+            return Cow::Borrowed(&self.thoughts);
+        };
+        let loaded = match store.lock() {
+            Ok(store) => store.load(&self.spill_key()),
+            Err(_) => Err("spill store lock poisoned".to_string()),
+        };
+        match loaded {
+            Ok(mut thoughts) => {
+                thoughts.extend_from_slice(&self.thoughts);
+                Cow::Owned(thoughts)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to load spilled thoughts: {e}");
+                Cow::Borrowed(&self.thoughts)
+            }
+        }
+    }
+
+    /// Number of thoughts in the current session, including spilled ones
+    pub fn thought_count(&self) -> usize {
+        self.spilled + self.thoughts.len()
+    }
+
+    /// Number of thoughts spilled out of memory
+    pub fn spilled_count(&self) -> usize {
+        self.spilled
+    }
+
     /// Get all branches in the current session
     pub fn get_branches(&self) -> &HashMap<String, ThoughtBranch> {
         &self.branches
     }
 
+    /// Get the thought dependency graph
+    pub fn get_graph(&self) -> &ThoughtGraph {
+        &self.graph
+    }
+
+    /// Check that the engine's derived state agrees with its thoughts
+    ///
+    /// Statistics must count the session's thoughts, revisions, branch
+    /// thoughts and text length; progress must reflect the latest thought;
+    /// and each branch must hold exactly the thoughts carrying its ID.
+    /// Callers restoring or analyzing a session can use this before trusting
+    /// its state. Returns every violation found.
+    pub fn verify_invariants(&self) -> Result<(), Vec<String>> {
+        let thoughts = self.all_thoughts();
+        let mut errors = Vec::new();
+
+        let counts = [
+            ("total_thoughts", self.stats.total_thoughts, thoughts.len()),
+            (
+                "total_revisions",
+                self.stats.total_revisions,
+                thoughts.iter().filter(|t| t.is_revision()).count(),
+            ),
+            (
+                "total_branches",
+                self.stats.total_branches,
+                thoughts.iter().filter(|t| t.is_branch()).count(),
+            ),
+            (
+                "total_thought_length",
+                self.stats.total_thought_length,
+                thoughts.iter().map(|t| t.thought.len()).sum(),
+            ),
+        ];
+        for (field, recorded, actual) in counts {
+            if recorded != actual as u64 {
+                errors.push(format!(
+                    "stats.{field}: {recorded} recorded but the session has {actual}"
+                ));
+            }
+        }
+
+        if let Some(last) = thoughts.last() {
+            if self.progress.current_thought != last.thought_number
+                || self.progress.total_thoughts != last.total_thoughts
+            {
+                errors.push(format!(
+                    "progress: at thought {}/{} but the latest thought is {}/{}",
+                    self.progress.current_thought,
+                    self.progress.total_thoughts,
+                    last.thought_number,
+                    last.total_thoughts
+                ));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.progress.progress_percentage) {
+            errors.push(format!(
+                "progress.progress_percentage: {} is outside 0 to 1",
+                self.progress.progress_percentage
+            ));
+        }
+
+        for (id, branch) in &self.branches {
+            if *id != branch.branch_id {
+                errors.push(format!(
+                    "branches.{id}: holds branch '{}'",
+                    branch.branch_id
+                ));
+            }
+            let members: Vec<&SharedThought> = thoughts
+                .iter()
+                .filter(|t| t.is_branch() && t.branch_id.as_ref() == Some(id))
+                .collect();
+            let matches =
+                branch.thoughts.len() == members.len()
+                    && branch.thoughts.iter().zip(&members).all(|(a, b)| {
+                        a.thought_number == b.thought_number && a.thought == b.thought
+                    });
+            if !matches {
+                errors.push(format!(
+                    "branches.{id}: holds {} thoughts but the session has {} on the branch",
+                    branch.thoughts.len(),
+                    members.len()
+                ));
+            }
+            if let Some(first) = members.first() {
+                if first.branch_from_thought != Some(branch.parent_thought) {
+                    errors.push(format!(
+                        "branches.{id}: forks from thought {} but its first thought branches from {:?}",
+                        branch.parent_thought, first.branch_from_thought
+                    ));
+                }
+            }
+        }
+        for thought in thoughts.iter().filter(|t| t.is_branch()) {
+            if let Some(id) = thought
+                .branch_id
+                .as_ref()
+                .filter(|id| !self.branches.contains_key(*id))
+            {
+                errors.push(format!(
+                    "branches.{id}: missing for thought {}",
+                    thought.thought_number
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Compare two branches of the current session
+    ///
+    /// Each branch's reasoning path is the main-line thoughts up to its fork
+    /// point followed by the branch's own thoughts.
+    pub fn compare_branches(&self, a: &str, b: &str) -> Result<BranchComparison, String> {
+        let branch_a = self
+            .branches
+            .get(a)
+            .ok_or_else(|| format!("Branch not found: {a}"))?;
+        let branch_b = self
+            .branches
+            .get(b)
+            .ok_or_else(|| format!("Branch not found: {b}"))?;
+
+        let shared_fork = branch_a.parent_thought.min(branch_b.parent_thought);
+        let thoughts = self.all_thoughts();
+        let shared_prefix: Vec<u32> = thoughts
+            .iter()
+            .filter(|t| !t.is_branch() && t.thought_number <= shared_fork)
+            .map(|t| t.thought_number)
+            .collect();
+
+        let (summary_a, divergent_a) = Self::summarize_branch(&thoughts, branch_a, shared_fork);
+        let (summary_b, divergent_b) = Self::summarize_branch(&thoughts, branch_b, shared_fork);
+
+        let preferred_branch = if summary_a.quality_score > summary_b.quality_score {
+            Some(summary_a.branch_id.clone())
+        } else if summary_b.quality_score > summary_a.quality_score {
+            Some(summary_b.branch_id.clone())
+        } else {
+            None
+        };
+
+        Ok(BranchComparison {
+            branch_a: summary_a,
+            branch_b: summary_b,
+            shared_prefix,
+            divergent_a,
+            divergent_b,
+            preferred_branch,
+        })
+    }
+
+    /// Summarize a branch and list the thoughts on its path after the shared fork
+    fn summarize_branch(
+        thoughts: &[SharedThought],
+        branch: &ThoughtBranch,
+        shared_fork: u32,
+    ) -> (BranchSummary, Vec<u32>) {
+        let path: Vec<SharedThought> = thoughts
+            .iter()
+            .filter(|t| !t.is_branch() && t.thought_number <= branch.parent_thought)
+            .chain(branch.thoughts.iter())
+            .cloned()
+            .collect();
+        let divergent = path
+            .iter()
+            .filter(|t| t.is_branch() || t.thought_number > shared_fork)
+            .map(|t| t.thought_number)
+            .collect();
+
+        let total_length: usize = branch
+            .thoughts
+            .iter()
+            .map(|t| t.thought.chars().count())
+            .sum();
+        let quality = crate::analytics::AnalyticsEngine::new().calculate_quality_metrics(&path);
+
+        let summary = BranchSummary {
+            branch_id: branch.branch_id.clone(),
+            parent_thought: branch.parent_thought,
+            thought_count: branch.thought_count(),
+            total_length,
+            avg_thought_length: if branch.thoughts.is_empty() {
+                0.0
+            } else {
+                total_length as f64 / branch.thoughts.len() as f64
+            },
+            quality_score: quality.overall_quality_score,
+            concluded: branch
+                .latest_thought()
+                .map(|t| !t.next_thought_needed)
+                .unwrap_or(false),
+        };
+
+        (summary, divergent)
+    }
+
+    /// Correct the text of an existing thought in place
+    ///
+    /// Unlike a revision this does not add a thought; the previous text is
+    /// kept in the edit log. The latest thought with the given number is
+    /// edited.
+    pub fn edit_thought(
+        &mut self,
+        thought_number: u32,
+        new_text: String,
+    ) -> Result<ThoughtEdit, String> {
+        self.edit_thought_as(thought_number, new_text, None)
+    }
+
+    /// Correct the text of an existing thought, recording who made the edit
+    pub fn edit_thought_as(
+        &mut self,
+        thought_number: u32,
+        new_text: String,
+        editor: Option<String>,
+    ) -> Result<ThoughtEdit, String> {
+        let new_text = self.sanitize.sanitize(&new_text).into_owned();
+        if new_text.trim().is_empty() {
+            return Err("Thought content cannot be empty".to_string());
+        }
+
+        let thought = self
+            .thoughts
+            .iter_mut()
+            .rev()
+            .find(|t| t.thought_number == thought_number)
+            .ok_or_else(|| {
+                if self.spilled > 0 && self.graph.contains(thought_number) {
+                    format!("Thought {thought_number} has been spilled out of memory and cannot be edited")
+                } else {
+                    format!("Thought {thought_number} not found")
+                }
+            })?;
+
+        // Readers may still hold the old record, so replace it rather than
+        // mutating it in place
+        let mut edited = ThoughtData::clone(thought);
+        let old_text = std::mem::replace(&mut edited.thought, new_text.clone());
+        let branch_id = edited.branch_id.clone();
+        let edited = Arc::new(edited);
+        *thought = edited.clone();
+        self.stats.total_thought_length = (self.stats.total_thought_length + new_text.len() as u64)
+            .saturating_sub(old_text.len() as u64);
+
+        // Keep the branch sharing the edited thought
+        if let Some(branch) = branch_id.as_ref().and_then(|id| self.branches.get_mut(id)) {
+            if let Some(copy) = branch
+                .thoughts
+                .iter_mut()
+                .rev()
+                .find(|t| t.thought_number == thought_number)
+            {
+                *copy = edited;
+            }
+        }
+
+        let edit = ThoughtEdit {
+            thought_number,
+            branch_id,
+            editor,
+            edited_at: self.clock.now(),
+            old_text,
+            new_text,
+        };
+        self.edits.push(edit.clone());
+        Ok(edit)
+    }
+
+    /// Get the audit log of in-place thought edits
+    pub fn get_edit_log(&self) -> &[ThoughtEdit] {
+        &self.edits
+    }
+
+    /// Attach a reviewer annotation to an existing thought
+    pub fn annotate_thought(&mut self, annotation: Annotation) -> Result<Annotation, String> {
+        let exists = self.all_thoughts().iter().any(|t| {
+            t.thought_number == annotation.thought_number
+                && t.branch_id.as_deref() == annotation.branch_id.as_deref()
+        });
+        if !exists {
+            return Err(match annotation.branch_id {
+                Some(ref branch_id) => format!(
+                    "Thought {} not found on branch {branch_id}",
+                    annotation.thought_number
+                ),
+                None => format!("Thought {} not found", annotation.thought_number),
+            });
+        }
+
+        self.annotations.add(annotation).cloned()
+    }
+
+    /// Mark a reviewer annotation as resolved
+    pub fn resolve_annotation(&mut self, id: &str) -> Result<Annotation, String> {
+        self.annotations.resolve(id).cloned()
+    }
+
+    /// Get the reviewer annotations of the session
+    pub fn get_annotations(&self) -> &AnnotationStore {
+        &self.annotations
+    }
+
+    /// Replace the session's annotations, e.g. when loading a stored session
+    pub fn restore_annotations(&mut self, annotations: AnnotationStore) {
+        self.annotations = annotations;
+    }
+
     /// Get thinking statistics
     pub fn get_stats(&self) -> &ThinkingStats {
         &self.stats
@@ -466,40 +1430,9 @@ impl ThinkingEngine {
 
     /// Log a thought to stderr (for compatibility with official implementation)
     fn log_thought(&self, thought: &ThoughtData) {
-        let prefix = if thought.is_revision() {
-            "🔄 Revision"
-        } else if thought.is_branch() {
-            "🌿 Branch"
-        } else {
-            "💭 Thought"
-        };
-
-        let context = if thought.is_revision() {
-            format!(
-                " (revising thought {})",
-                thought.revises_thought.unwrap_or(0)
-            )
-        } else if thought.is_branch() {
-            format!(
-                " (from thought {}, ID: {})",
-                thought.branch_from_thought.unwrap_or(0),
-                thought.branch_id.as_deref().unwrap_or("unknown")
-            )
-        } else {
-            String::new()
-        };
-
-        let header = format!(
-            "{} {}/{}",
-            prefix, thought.thought_number, thought.total_thoughts
-        );
-        let border_length = std::cmp::max(header.len() + context.len(), thought.thought.len()) + 4;
-        let border = "─".repeat(border_length);
-
-        eprintln!(
-            "\n┌{}┐\n│ {}{} │\n├{}┤\n│ {} │\n└{}┘",
-            border, header, context, border, thought.thought, border
-        );
+        if let Some(rendered) = self.renderer.render(thought) {
+            eprintln!("{rendered}");
+        }
     }
 }
 
@@ -512,6 +1445,7 @@ impl Default for ThinkingEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_thought_data_creation() {
@@ -569,6 +1503,85 @@ mod tests {
         assert!(!engine.is_complete());
     }
 
+    #[tokio::test]
+    async fn test_thought_dependencies() {
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.start_session("test-session".to_string());
+
+        for n in 1..=3 {
+            let thought = ThoughtData::new(format!("Thought {n}"), n, 4);
+            engine.process_thought(thought).await.unwrap();
+        }
+        let combined =
+            ThoughtData::new("Combine 1 and 3".to_string(), 4, 4).with_dependencies(vec![1, 3]);
+        engine.process_thought(combined).await.unwrap();
+
+        assert_eq!(engine.get_graph().dependencies(4), vec![1, 3]);
+        assert_eq!(engine.get_graph().dependents(1), vec![4]);
+
+        let dangling =
+            ThoughtData::new("Builds on the future".to_string(), 5, 5).with_dependencies(vec![9]);
+        assert!(engine.process_thought(dangling).await.is_err());
+        assert_eq!(engine.get_thoughts().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_compare_branches() {
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.start_session("test-session".to_string());
+
+        for n in 1..=3 {
+            let thought = ThoughtData::new(format!("Main line thought number {n}"), n, 6);
+            engine.process_thought(thought).await.unwrap();
+        }
+        let a = ThoughtData::branch(
+            "Explore option A in depth".to_string(),
+            4,
+            1,
+            "a".to_string(),
+        );
+        let b = ThoughtData::branch("Explore option B".to_string(), 5, 2, "b".to_string());
+        engine.process_thought(a).await.unwrap();
+        engine.process_thought(b).await.unwrap();
+
+        // Branches hold the same records as the main list, not copies
+        assert!(Arc::ptr_eq(
+            &engine.get_thoughts()[3],
+            &engine.get_branches()["a"].thoughts[0]
+        ));
+
+        let comparison = engine.compare_branches("a", "b").unwrap();
+        assert_eq!(comparison.shared_prefix, vec![1]);
+        assert_eq!(comparison.divergent_a, vec![4]);
+        assert_eq!(comparison.divergent_b, vec![2, 5]);
+        assert_eq!(comparison.branch_a.thought_count, 1);
+        assert!(engine.compare_branches("a", "missing").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_thought_numbering() {
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.start_session("test-session".to_string());
+
+        for n in [1, 5] {
+            let thought = ThoughtData::new(format!("Thought {n}"), n, 5);
+            engine.process_thought(thought).await.unwrap();
+        }
+        let late = ThoughtData::new("Late thought".to_string(), 3, 5);
+        assert!(matches!(
+            engine.check_sequence(&late),
+            Err(SequenceError::OutOfOrder { previous: 5, .. })
+        ));
+        assert!(engine.process_thought(late.clone()).await.is_err());
+        assert_eq!(engine.get_thoughts().len(), 2);
+
+        engine.set_numbering_policy(NumberingPolicy::AutoRenumber);
+        let processed = engine.process_thought(late).await.unwrap();
+        assert_eq!(processed.thought_number, 6);
+        assert_eq!(processed.total_thoughts, 6);
+        assert_eq!(engine.get_progress().current_thought, 6);
+    }
+
     #[test]
     fn test_thinking_progress() {
         let mut progress = ThinkingProgress::new(1, 5);
@@ -582,4 +1595,207 @@ mod tests {
         assert_eq!(progress.completed_thoughts, 2);
         assert_eq!(progress.progress_percentage, 0.4);
     }
+
+    /// Any JSON value, a few levels deep
+    fn json_value() -> impl Strategy<Value = serde_json::Value> {
+        let leaf = prop_oneof![
+            Just(serde_json::Value::Null),
+            any::<bool>().prop_map(serde_json::Value::from),
+            any::<u64>().prop_map(serde_json::Value::from),
+            any::<i64>().prop_map(serde_json::Value::from),
+            any::<f64>().prop_map(serde_json::Value::from),
+            ".*".prop_map(serde_json::Value::from),
+        ];
+        leaf.prop_recursive(3, 32, 6, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..6).prop_map(serde_json::Value::from),
+                prop::collection::btree_map(".*", inner, 0..6)
+                    .prop_map(|fields| serde_json::Value::Object(fields.into_iter().collect())),
+            ]
+        })
+    }
+
+    /// Tool arguments mixing known fields of any type with unknown ones
+    fn tool_arguments() -> impl Strategy<Value = serde_json::Value> {
+        let field = prop_oneof![
+            Just("thought".to_string()),
+            Just("thoughtNumber".to_string()),
+            Just("totalThoughts".to_string()),
+            Just("nextThoughtNeeded".to_string()),
+            Just("isRevision".to_string()),
+            Just("revisesThought".to_string()),
+            Just("branchFromThought".to_string()),
+            Just("branchId".to_string()),
+            Just("dependsOn".to_string()),
+            Just("metadata".to_string()),
+            Just("attachments".to_string()),
+            ".*",
+        ];
+        let required = (".*", any::<u64>(), any::<u64>()).prop_map(|(thought, number, total)| {
+            serde_json::json!({
+                "thought": thought,
+                "thoughtNumber": number,
+                "totalThoughts": total,
+            })
+        });
+        (
+            prop_oneof![required, json_value()],
+            prop::collection::vec((field, json_value()), 0..6),
+        )
+            .prop_map(|(mut args, fields)| {
+                if let Some(args) = args.as_object_mut() {
+                    args.extend(fields);
+                }
+                args
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_tool_arguments_never_panic(args in tool_arguments()) {
+            if let Ok(thought) = ThoughtData::from_tool_arguments(&args) {
+                let _ = thought.validate();
+                let resent = ThoughtData::from_tool_arguments(&thought.to_tool_arguments()).unwrap();
+                prop_assert_eq!(resent.thought, thought.thought);
+                prop_assert_eq!(resent.thought_number, thought.thought_number);
+                prop_assert_eq!(resent.total_thoughts, thought.total_thoughts);
+                prop_assert_eq!(resent.revises_thought, thought.revises_thought);
+                prop_assert_eq!(resent.branch_from_thought, thought.branch_from_thought);
+                prop_assert_eq!(resent.branch_id, thought.branch_id);
+                prop_assert_eq!(resent.depends_on, thought.depends_on);
+            }
+        }
+
+        #[test]
+        fn test_thought_data_deserialization_never_panics(value in json_value()) {
+            if let Ok(thought) = serde_json::from_value::<ThoughtData>(value) {
+                let _ = thought.validate();
+            }
+        }
+    }
+
+    #[test]
+    fn test_tool_arguments_reject_oversized_numbers() {
+        let args = serde_json::json!({
+            "thought": "Wraps around",
+            "thoughtNumber": u64::from(u32::MAX) + 2,
+            "totalThoughts": 3,
+            "dependsOn": [1, u64::from(u32::MAX) + 1],
+        });
+        assert_eq!(
+            ThoughtData::from_tool_arguments(&args).unwrap_err(),
+            format!("'thoughtNumber' must be at most {}", u32::MAX)
+        );
+
+        let args = serde_json::json!({
+            "thought": "Depends on a real thought",
+            "thoughtNumber": 2,
+            "totalThoughts": 3,
+            "dependsOn": [1, u64::from(u32::MAX) + 1],
+        });
+        assert_eq!(
+            ThoughtData::from_tool_arguments(&args).unwrap_err(),
+            format!("'dependsOn' must be at most {}", u32::MAX)
+        );
+
+        let args = serde_json::json!({
+            "thought": "Depends on a negative thought",
+            "thoughtNumber": 2,
+            "totalThoughts": 3,
+            "dependsOn": [1, -1],
+        });
+        assert_eq!(
+            ThoughtData::from_tool_arguments(&args).unwrap_err(),
+            "'dependsOn' must hold non-negative integers"
+        );
+
+        let args = serde_json::json!({
+            "thought": "Depends on a real thought",
+            "thoughtNumber": 2,
+            "totalThoughts": 3,
+            "dependsOn": [1],
+        });
+        assert_eq!(
+            ThoughtData::from_tool_arguments(&args).unwrap().depends_on,
+            vec![1]
+        );
+    }
+
+    /// Thoughts a session might submit, numbered loosely so some are rejected
+    fn session_thought() -> impl Strategy<Value = ThoughtData> {
+        (".{1,40}", 1u32..20, 1u32..20, 0u8..4, "[ab]").prop_map(
+            |(text, number, total, kind, branch_id)| match kind {
+                0 => ThoughtData::revision(text, number, number.saturating_sub(1).max(1)),
+                1 => ThoughtData::branch(text, number, number.saturating_sub(1).max(1), branch_id),
+                _ => ThoughtData::new(text, number, total),
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn test_engine_invariants(
+            thoughts in prop::collection::vec(session_thought(), 1..30),
+            numbering in prop_oneof![
+                Just(NumberingPolicy::Strict),
+                Just(NumberingPolicy::AutoRenumber),
+                Just(NumberingPolicy::AllowGaps),
+            ],
+        ) {
+            let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+            let mut engine = ThinkingEngine::with_logging(true);
+            engine.set_numbering_policy(numbering);
+            engine.start_session("invariants".to_string());
+
+            let mut previous = 0;
+            for thought in thoughts {
+                let restart =
+                    thought.thought_number == 1 && !thought.is_revision() && !thought.is_branch();
+                if runtime.block_on(engine.process_thought(thought)).is_ok() {
+                    // Progress only moves forward, unless thought 1 starts over
+                    prop_assert!(restart || engine.get_progress().current_thought > previous);
+                    previous = engine.get_progress().current_thought;
+                }
+                prop_assert_eq!(engine.verify_invariants(), Ok(()));
+            }
+
+            if let Some(first) = engine.get_thoughts().first().map(|t| t.thought_number) {
+                engine.edit_thought(first, "Rewritten".to_string()).unwrap();
+                prop_assert_eq!(engine.verify_invariants(), Ok(()));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_invariants_reports_violations() {
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.start_session("tampered".to_string());
+        engine
+            .process_thought(ThoughtData::new("Start".to_string(), 1, 2))
+            .await
+            .unwrap();
+        engine
+            .process_thought(ThoughtData::branch(
+                "Fork".to_string(),
+                2,
+                1,
+                "alt".to_string(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(engine.verify_invariants(), Ok(()));
+
+        let mut checkpoint = engine.checkpoint();
+        checkpoint.stats.total_thoughts = 5;
+        checkpoint.branches.clear();
+        let mut restored = ThinkingEngine::with_logging(true);
+        restored.resume_from(checkpoint).unwrap();
+        assert_eq!(
+            restored.verify_invariants().unwrap_err(),
+            vec![
+                "stats.total_thoughts: 5 recorded but the session has 2".to_string(),
+                "branches.alt: missing for thought 2".to_string(),
+            ]
+        );
+    }
 }
@@ -0,0 +1,232 @@
+//! # Session Replay
+//!
+//! Timeline of how a thinking session unfolded.
+//!
+//! Replay turns the engine's thoughts and edit log into an ordered sequence of
+//! timestamped events (thought added, revised, branched, edited) that UIs can
+//! step through or animate at a chosen speed.
+
+use serde::{Deserialize, Serialize};
+
+use crate::thinking::{ThinkingEngine, ThoughtData, ThoughtEdit};
+
+/// What happened at a point in the session timeline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplayEventKind {
+    /// A thought was added to the main line or an existing branch
+    ThoughtAdded { thought: ThoughtData },
+    /// A thought revised an earlier one
+    ThoughtRevised {
+        thought: ThoughtData,
+        revises_thought: u32,
+    },
+    /// A thought started a new branch
+    Branched {
+        thought: ThoughtData,
+        branch_id: String,
+        branch_from_thought: u32,
+    },
+    /// A thought's text was corrected in place
+    ThoughtEdited { edit: ThoughtEdit },
+}
+
+/// Timestamped event in a session replay
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReplayEvent {
+    /// Position of the event in the replay, starting at 0
+    pub sequence: usize,
+    /// When the event happened, if known
+    pub timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    /// Milliseconds since the first timestamped event
+    pub elapsed_ms: u64,
+    /// The event itself
+    #[serde(flatten)]
+    pub kind: ReplayEventKind,
+}
+
+impl ReplayEvent {
+    /// Number of the thought the event concerns
+    pub fn thought_number(&self) -> u32 {
+        match &self.kind {
+            ReplayEventKind::ThoughtAdded { thought }
+            | ReplayEventKind::ThoughtRevised { thought, .. }
+            | ReplayEventKind::Branched { thought, .. } => thought.thought_number,
+            ReplayEventKind::ThoughtEdited { edit } => edit.thought_number,
+        }
+    }
+}
+
+/// Milliseconds to wait before each event when replaying at a given speed
+///
+/// A speed of 1.0 reproduces the original pacing, 2.0 plays twice as fast;
+/// a speed of zero or less (or non-finite) yields no delays.
+pub fn replay_delays(events: &[ReplayEvent], speed: f64) -> Vec<u64> {
+    let mut previous = 0;
+    events
+        .iter()
+        .map(|event| {
+            let gap = event.elapsed_ms.saturating_sub(previous);
+            previous = previous.max(event.elapsed_ms);
+            if speed.is_finite() && speed > 0.0 {
+                (gap as f64 / speed).round() as u64
+            } else {
+                0
+            }
+        })
+        .collect()
+}
+
+impl ThinkingEngine {
+    /// Replay the session as an ordered sequence of timestamped events
+    ///
+    /// Thoughts are emitted in processing order with the text they had when
+    /// added; in-place edits are interleaved at the time they were made.
+    pub fn replay(&self) -> impl Iterator<Item = ReplayEvent> {
+        let thoughts = self.all_thoughts();
+        let edits = self.get_edit_log();
+
+        // Edits change thoughts in place, so recover the text each thought
+        // had before its first edit
+        let mut originals: Vec<Option<String>> = vec![None; thoughts.len()];
+        for edit in edits {
+            let target = thoughts.iter().rposition(|t| {
+                t.thought_number == edit.thought_number
+                    && t.timestamp.is_none_or(|ts| ts <= edit.edited_at)
+            });
+            if let Some(index) = target {
+                originals[index].get_or_insert_with(|| edit.old_text.clone());
+            }
+        }
+
+        let mut pending_edits = edits.iter().peekable();
+        let mut timeline: Vec<(Option<chrono::DateTime<chrono::Utc>>, ReplayEventKind)> =
+            Vec::with_capacity(thoughts.len() + edits.len());
+        let mut seen_branches = std::collections::HashSet::new();
+
+        for (index, thought) in thoughts.iter().enumerate() {
+            if let Some(added_at) = thought.timestamp {
+                while let Some(edit) = pending_edits.next_if(|e| e.edited_at < added_at) {
+                    timeline.push((
+                        Some(edit.edited_at),
+                        ReplayEventKind::ThoughtEdited { edit: edit.clone() },
+                    ));
+                }
+            }
+
+            let mut snapshot = ThoughtData::clone(thought);
+            if let Some(original) = &originals[index] {
+                snapshot.thought = original.clone();
+            }
+
+            let kind = match (
+                thought.revises_thought,
+                thought.branch_from_thought,
+                &thought.branch_id,
+            ) {
+                (Some(revises_thought), _, _) if thought.is_revision() => {
+                    ReplayEventKind::ThoughtRevised {
+                        thought: snapshot,
+                        revises_thought,
+                    }
+                }
+                (_, Some(branch_from_thought), Some(branch_id))
+                    if seen_branches.insert(branch_id.clone()) =>
+                {
+                    ReplayEventKind::Branched {
+                        branch_id: branch_id.clone(),
+                        branch_from_thought,
+                        thought: snapshot,
+                    }
+                }
+                _ => ReplayEventKind::ThoughtAdded { thought: snapshot },
+            };
+            timeline.push((thought.timestamp, kind));
+        }
+        for edit in pending_edits {
+            timeline.push((
+                Some(edit.edited_at),
+                ReplayEventKind::ThoughtEdited { edit: edit.clone() },
+            ));
+        }
+
+        let start = timeline.iter().find_map(|(timestamp, _)| *timestamp);
+        timeline
+            .into_iter()
+            .enumerate()
+            .map(move |(sequence, (timestamp, kind))| ReplayEvent {
+                sequence,
+                timestamp,
+                elapsed_ms: match (start, timestamp) {
+                    (Some(start), Some(at)) => (at - start).num_milliseconds().max(0) as u64,
+                    _ => 0,
+                },
+                kind,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_replay_timeline() {
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.start_session("replay".to_string());
+
+        let start = chrono::Utc::now() - chrono::Duration::seconds(10);
+        let mut first = ThoughtData::new("Frist idea".to_string(), 1, 3);
+        first.timestamp = Some(start);
+        let mut second = ThoughtData::new("Second idea".to_string(), 2, 3);
+        second.timestamp = Some(start + chrono::Duration::seconds(2));
+        let mut revision = ThoughtData::revision("Better first idea".to_string(), 3, 1);
+        revision.timestamp = Some(start + chrono::Duration::seconds(4));
+        let mut branch = ThoughtData::branch("Alternative".to_string(), 4, 2, "alt".to_string());
+        branch.timestamp = Some(start + chrono::Duration::seconds(6));
+        let mut follow_up =
+            ThoughtData::branch("Alternative, cont.".to_string(), 5, 2, "alt".to_string());
+        follow_up.timestamp = Some(start + chrono::Duration::seconds(7));
+
+        for thought in [first, second, revision, branch, follow_up] {
+            engine.process_thought(thought).await.unwrap();
+        }
+        engine.edit_thought(1, "First idea".to_string()).unwrap();
+
+        let events: Vec<ReplayEvent> = engine.replay().collect();
+        assert_eq!(events.len(), 6);
+        assert!(matches!(
+            &events[0].kind,
+            ReplayEventKind::ThoughtAdded { thought } if thought.thought == "Frist idea"
+        ));
+        assert!(matches!(
+            events[2].kind,
+            ReplayEventKind::ThoughtRevised {
+                revises_thought: 1,
+                ..
+            }
+        ));
+        assert!(matches!(
+            &events[3].kind,
+            ReplayEventKind::Branched { branch_id, .. } if branch_id == "alt"
+        ));
+        assert!(matches!(
+            events[4].kind,
+            ReplayEventKind::ThoughtAdded { .. }
+        ));
+        assert!(matches!(
+            &events[5].kind,
+            ReplayEventKind::ThoughtEdited { edit } if edit.new_text == "First idea"
+        ));
+        assert_eq!(events[3].elapsed_ms, 6000);
+        assert_eq!(events[5].thought_number(), 1);
+
+        let delays = replay_delays(&events[..5], 2.0);
+        assert_eq!(delays, vec![0, 1000, 1000, 1000, 500]);
+        assert!(replay_delays(&events, 0.0).iter().all(|d| *d == 0));
+
+        let json = serde_json::to_value(&events[3]).unwrap();
+        assert_eq!(json["type"], "branched");
+        assert_eq!(json["branch_from_thought"], 2);
+    }
+}
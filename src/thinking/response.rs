@@ -0,0 +1,210 @@
+//! # Tool Responses
+//!
+//! Typed results of the sequential_thinking tool.
+//!
+//! The server serializes these structures into the tool's text content and
+//! the client parses them back, so both sides share one definition of the
+//! response layout.
+
+use serde::{Deserialize, Serialize};
+use ultrafast_mcp::{ToolContent, ToolResult};
+
+use crate::thinking::strategy::ThinkingStrategy;
+use crate::thinking::{ThinkingEngine, ThinkingProgress, ThoughtData};
+
+/// Result of processing a thought on the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThoughtResponse {
+    /// Number of the processed thought
+    pub thought_number: u32,
+    /// Total thoughts after processing (may have been raised)
+    pub total_thoughts: u32,
+    /// Whether another thought is needed
+    pub next_thought_needed: bool,
+    /// Branch identifiers in the session
+    pub branches: Vec<String>,
+    /// Number of thoughts in the session
+    pub thought_history_length: usize,
+    /// Session progress after processing
+    pub progress: ResponseProgress,
+    /// Session statistics after processing
+    pub stats: ResponseStats,
+    /// Server-side processing time in milliseconds
+    pub processing_time_ms: u64,
+    /// Strategy the session follows
+    #[serde(default)]
+    pub strategy: ThinkingStrategy,
+    /// What the strategy suggests for the next thought
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guidance: Option<String>,
+    /// Correlation ID of the tool call, echoed back by the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+}
+
+/// Progress section of a thought response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseProgress {
+    /// Current thought number
+    pub current_thought: u32,
+    /// Total estimated thoughts
+    pub total_thoughts: u32,
+    /// Number of completed thoughts
+    pub completed_thoughts: u32,
+    /// Progress percentage (0.0 to 1.0)
+    pub progress_percentage: f64,
+    /// Whether the session is complete
+    pub is_complete: bool,
+    /// Milliseconds from the first to the latest thought
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+    /// Average milliseconds between consecutive thoughts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_thought_ms: Option<u64>,
+    /// Estimated milliseconds until the last thought
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_remaining_ms: Option<u64>,
+    /// Milliseconds the session sat idle before the latest thought
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_ms: Option<u64>,
+}
+
+impl From<&ThinkingProgress> for ResponseProgress {
+    fn from(progress: &ThinkingProgress) -> Self {
+        let millis = |time: Option<std::time::Duration>| time.map(|t| t.as_millis() as u64);
+        Self {
+            current_thought: progress.current_thought,
+            total_thoughts: progress.total_thoughts,
+            completed_thoughts: progress.completed_thoughts,
+            progress_percentage: progress.progress_percentage,
+            is_complete: progress.is_complete(),
+            elapsed_ms: millis(progress.elapsed_time),
+            avg_thought_ms: millis(progress.avg_thought_time),
+            estimated_remaining_ms: millis(progress.estimated_time_remaining),
+            idle_ms: millis(progress.idle_time),
+        }
+    }
+}
+
+/// Statistics section of a thought response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseStats {
+    /// Total thoughts processed
+    pub total_thoughts: u64,
+    /// Total revisions made
+    pub total_revisions: u64,
+    /// Total branches created
+    pub total_branches: u64,
+    /// Average processing time per thought
+    pub avg_processing_time_ms: f64,
+}
+
+impl ThoughtResponse {
+    /// Build a response from a processed thought and the engine state after it
+    pub fn from_engine(
+        thought: &ThoughtData,
+        engine: &ThinkingEngine,
+        processing_time_ms: u64,
+    ) -> Self {
+        let stats = engine.get_stats();
+        let mut branches: Vec<String> = engine.get_branches().keys().cloned().collect();
+        branches.sort();
+
+        Self {
+            thought_number: thought.thought_number,
+            total_thoughts: thought.total_thoughts,
+            next_thought_needed: thought.next_thought_needed,
+            branches,
+            thought_history_length: engine.thought_count(),
+            progress: ResponseProgress::from(engine.get_progress()),
+            stats: ResponseStats {
+                total_thoughts: stats.total_thoughts,
+                total_revisions: stats.total_revisions,
+                total_branches: stats.total_branches,
+                avg_processing_time_ms: stats.avg_processing_time_ms,
+            },
+            processing_time_ms,
+            strategy: engine.strategy(),
+            guidance: engine.guidance(thought),
+            correlation_id: None,
+        }
+    }
+
+    /// Parse a response from the text content of a tool result
+    pub fn from_tool_result(result: &ToolResult) -> Result<Self, String> {
+        match result.content.first() {
+            Some(ToolContent::Text { text }) => serde_json::from_str(text)
+                .map_err(|e| format!("Invalid sequential_thinking response: {e}")),
+            _ => Err("No text content in sequential_thinking result".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_response_round_trip() {
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.start_session("response".to_string());
+        let thought = engine
+            .process_thought(ThoughtData::new("Only thought".to_string(), 1, 2))
+            .await
+            .unwrap();
+
+        let response = ThoughtResponse::from_engine(&thought, &engine, 3);
+        let result = ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response).unwrap(),
+            )],
+            is_error: Some(false),
+        };
+
+        let parsed = ThoughtResponse::from_tool_result(&result).unwrap();
+        assert_eq!(parsed.thought_number, 1);
+        assert_eq!(parsed.thought_history_length, 1);
+        assert_eq!(parsed.stats.total_thoughts, 1);
+        assert_eq!(parsed.processing_time_ms, 3);
+    }
+
+    #[tokio::test]
+    async fn test_progress_timing() {
+        let clock = crate::thinking::clock::TestClock::new(chrono::Utc::now());
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.set_clock(clock.shared());
+        engine.start_session("timed".to_string());
+
+        let thought_after = |gap_seconds: i64, number: u32| {
+            clock.advance(chrono::Duration::seconds(gap_seconds));
+            let mut thought = ThoughtData::new(format!("Step {number}"), number, 5);
+            thought.timestamp = None;
+            thought
+        };
+        let thought = engine.process_thought(thought_after(0, 1)).await.unwrap();
+        let progress = ThoughtResponse::from_engine(&thought, &engine, 0).progress;
+        assert_eq!(progress.elapsed_ms, Some(0));
+        assert_eq!(progress.avg_thought_ms, None);
+        assert_eq!(progress.estimated_remaining_ms, None);
+        assert_eq!(progress.idle_ms, None);
+
+        engine.process_thought(thought_after(30, 2)).await.unwrap();
+        let thought = engine.process_thought(thought_after(90, 3)).await.unwrap();
+        let progress = ThoughtResponse::from_engine(&thought, &engine, 0).progress;
+        assert_eq!(progress.elapsed_ms, Some(120_000));
+        assert_eq!(progress.avg_thought_ms, Some(60_000));
+        assert_eq!(progress.estimated_remaining_ms, Some(120_000));
+        assert_eq!(progress.idle_ms, Some(90_000));
+
+        let mut last = thought_after(10, 5);
+        last.next_thought_needed = false;
+        engine.process_thought(last).await.unwrap();
+        assert_eq!(
+            engine.get_progress().estimated_time_remaining,
+            Some(std::time::Duration::ZERO)
+        );
+    }
+}
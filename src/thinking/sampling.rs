@@ -0,0 +1,216 @@
+//! # Sampling
+//!
+//! Server-generated thoughts, produced by the host's model through MCP sampling.
+//!
+//! With sampling enabled, a `sequential_thinking` call whose thought is the
+//! continue marker asks the connected host for the next thought instead of
+//! recording the marker. The server sends the recent thoughts as a
+//! `sampling/createMessage` request and records the completion as the thought,
+//! so a chain can be driven from the server while the host keeps control of
+//! which model runs and whether the request is approved.
+//!
+//! Requests reach the host through a [`ThoughtSampler`], supplied by whatever
+//! owns the MCP connection.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::thinking::{SharedThought, ThoughtData};
+
+/// Metadata key marking thoughts generated through sampling
+pub const SAMPLED_KEY: &str = "sampled";
+
+/// Sampling settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SamplingConfig {
+    /// Thought text asking the server to generate the next thought
+    #[serde(default = "default_continue_marker")]
+    pub continue_marker: String,
+    /// Most recent thoughts sent to the host as context
+    #[serde(default = "default_context_thoughts")]
+    pub context_thoughts: usize,
+    /// Maximum tokens the host may generate per thought
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+    /// System prompt sent with each request
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+}
+
+fn default_continue_marker() -> String {
+    "[continue]".to_string()
+}
+
+fn default_context_thoughts() -> usize {
+    20
+}
+
+fn default_max_tokens() -> u32 {
+    1000
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            continue_marker: default_continue_marker(),
+            context_thoughts: default_context_thoughts(),
+            max_tokens: default_max_tokens(),
+            system_prompt: None,
+        }
+    }
+}
+
+impl SamplingConfig {
+    /// Check whether a thought asks for a generated thought
+    pub fn is_continue(&self, thought: &ThoughtData) -> bool {
+        thought.thought.trim() == self.continue_marker
+    }
+
+    /// Build the request generating `next` from the session's thoughts
+    pub fn request(&self, thoughts: &[SharedThought], next: &ThoughtData) -> SamplingRequest {
+        let recent = &thoughts[thoughts.len().saturating_sub(self.context_thoughts)..];
+        let mut prompt = String::new();
+        for thought in recent {
+            prompt.push_str(&format!(
+                "Thought {}/{}: {}\n",
+                thought.thought_number, thought.total_thoughts, thought.thought
+            ));
+        }
+        if recent.is_empty() {
+            prompt.push_str("No thoughts have been recorded yet.\n");
+        }
+        prompt.push_str(&format!(
+            "\nWrite thought {} of about {}. Reply with the thought only.",
+            next.thought_number, next.total_thoughts
+        ));
+
+        SamplingRequest {
+            system_prompt: self.system_prompt.clone().unwrap_or_else(|| {
+                "You are reasoning step by step. Continue the sequence of thoughts, \
+                 building on, questioning or revising the earlier ones."
+                    .to_string()
+            }),
+            prompt,
+            max_tokens: self.max_tokens,
+        }
+    }
+}
+
+/// Request for the next thought
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SamplingRequest {
+    /// System prompt
+    pub system_prompt: String,
+    /// User message holding the recent thoughts
+    pub prompt: String,
+    /// Maximum tokens to generate
+    pub max_tokens: u32,
+}
+
+impl SamplingRequest {
+    /// Parameters of the equivalent MCP `sampling/createMessage` request
+    pub fn create_message_params(&self) -> serde_json::Value {
+        serde_json::json!({
+            "messages": [{
+                "role": "user",
+                "content": {"type": "text", "text": self.prompt}
+            }],
+            "systemPrompt": self.system_prompt,
+            "includeContext": "none",
+            "maxTokens": self.max_tokens
+        })
+    }
+}
+
+/// Completion returned by the host
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SamplingResponse {
+    /// Generated thought text
+    pub text: String,
+    /// Model the host used
+    pub model: Option<String>,
+}
+
+/// Sends sampling requests to the connected host
+#[async_trait]
+pub trait ThoughtSampler: Send + Sync + std::fmt::Debug {
+    /// Ask the host for a completion
+    async fn sample(&self, request: SamplingRequest) -> Result<SamplingResponse, String>;
+}
+
+/// Replace a continue marker with a thought generated by the host
+///
+/// The generated thought keeps the numbering and flags of the marker, and
+/// carries the host's model in its metadata for cost tracking.
+pub async fn generate_thought(
+    sampler: &dyn ThoughtSampler,
+    config: &SamplingConfig,
+    thoughts: &[SharedThought],
+    marker: ThoughtData,
+) -> Result<ThoughtData, String> {
+    let response = sampler.sample(config.request(thoughts, &marker)).await?;
+    let text = response.text.trim();
+    if text.is_empty() {
+        return Err("Sampling returned an empty thought".to_string());
+    }
+
+    let mut thought = ThoughtData {
+        thought: text.to_string(),
+        ..marker
+    }
+    .with_metadata(SAMPLED_KEY.to_string(), serde_json::Value::Bool(true));
+    if let Some(model) = response.model {
+        thought = thought.with_metadata("model".to_string(), serde_json::Value::String(model));
+    }
+    Ok(thought)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[derive(Debug)]
+    struct EchoSampler;
+
+    #[async_trait]
+    impl ThoughtSampler for EchoSampler {
+        async fn sample(&self, request: SamplingRequest) -> Result<SamplingResponse, String> {
+            Ok(SamplingResponse {
+                text: format!("  Sampled after {} lines\n", request.prompt.lines().count()),
+                model: Some("host-model".to_string()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_thought() {
+        let config = SamplingConfig {
+            context_thoughts: 2,
+            ..SamplingConfig::default()
+        };
+        let thoughts: Vec<SharedThought> = (1..=3)
+            .map(|n| Arc::new(ThoughtData::new(format!("Step {n}"), n, 5)))
+            .collect();
+        let marker = ThoughtData::new("[continue]".to_string(), 4, 5);
+        assert!(config.is_continue(&marker));
+
+        let request = config.request(&thoughts, &marker);
+        assert!(!request.prompt.contains("Step 1"));
+        assert!(request.prompt.contains("Thought 3/5: Step 3"));
+        assert!(request
+            .prompt
+            .ends_with("Write thought 4 of about 5. Reply with the thought only."));
+        assert_eq!(request.create_message_params()["maxTokens"], 1000);
+
+        let thought = generate_thought(&EchoSampler, &config, &thoughts, marker)
+            .await
+            .unwrap();
+        assert_eq!(thought.thought, "Sampled after 4 lines");
+        assert_eq!(thought.thought_number, 4);
+        let metadata = thought.metadata.unwrap();
+        assert_eq!(metadata[SAMPLED_KEY], true);
+        assert_eq!(metadata["model"], "host-model");
+    }
+}
@@ -0,0 +1,181 @@
+//! # Sanitization
+//!
+//! Cleaning thought text before it is stored or logged.
+//!
+//! Thought text comes from untrusted hosts and is echoed to the stderr thought
+//! log and into exports. ANSI escape sequences and control characters in it
+//! could move the cursor, recolor or clear the operator's terminal, and text
+//! in decomposed Unicode forms compares and searches differently from the
+//! same text typed elsewhere. [`SanitizeConfig`] strips escape sequences and
+//! control characters other than newlines and tabs, and normalizes the rest
+//! to Unicode NFC.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::iter::Peekable;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+use crate::thinking::ThoughtData;
+
+/// How thought text is cleaned before it is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SanitizeConfig {
+    /// Whether thought text is sanitized at all
+    pub enabled: bool,
+    /// Normalize text to Unicode NFC
+    pub normalize_unicode: bool,
+    /// Remove ANSI escape sequences
+    pub strip_ansi: bool,
+    /// Remove control characters other than newlines and tabs
+    pub strip_control_characters: bool,
+}
+
+impl Default for SanitizeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            normalize_unicode: true,
+            strip_ansi: true,
+            strip_control_characters: true,
+        }
+    }
+}
+
+impl SanitizeConfig {
+    /// Configuration leaving text untouched
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::default()
+        }
+    }
+
+    /// Clean a piece of text, borrowing it when nothing needs to change
+    pub fn sanitize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if !self.enabled {
+            return Cow::Borrowed(text);
+        }
+        let strips =
+            (self.strip_ansi || self.strip_control_characters) && text.chars().any(is_stripped);
+        let normalizes = self.normalize_unicode && is_nfc_quick(text.chars()) != IsNormalized::Yes;
+        if !strips && !normalizes {
+            return Cow::Borrowed(text);
+        }
+
+        let mut text = Cow::Borrowed(text);
+        if strips && self.strip_ansi {
+            text = Cow::Owned(strip_ansi(&text));
+        }
+        if strips && self.strip_control_characters {
+            text = Cow::Owned(text.chars().filter(|&c| !is_stripped(c)).collect());
+        }
+        if self.normalize_unicode {
+            text = Cow::Owned(text.nfc().collect());
+        }
+        text
+    }
+
+    /// Clean the text, author and branch of a thought
+    pub fn sanitize_thought(&self, thought: &mut ThoughtData) {
+        if let Cow::Owned(text) = self.sanitize(&thought.thought) {
+            thought.thought = text;
+        }
+        for field in [&mut thought.author, &mut thought.branch_id]
+            .into_iter()
+            .flatten()
+        {
+            if let Cow::Owned(text) = self.sanitize(field) {
+                *field = text;
+            }
+        }
+    }
+}
+
+/// Check whether a character is a control character other than a newline or tab
+fn is_stripped(c: char) -> bool {
+    c.is_control() && c != '\n' && c != '\t'
+}
+
+/// Remove ANSI escape sequences, both the 7-bit and the C1 forms
+fn strip_ansi(text: &str) -> String {
+    let mut clean = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.next() {
+                Some('[') => skip_control_sequence(&mut chars),
+                Some(']' | 'P' | 'X' | '^' | '_') => skip_control_string(&mut chars),
+                // Intermediate bytes, then the final byte
+                Some(' '..='/') => {
+                    while chars.next_if(|c| matches!(c, ' '..='/')).is_some() {}
+                    chars.next();
+                }
+                _ => {}
+            },
+            '\u{9b}' => skip_control_sequence(&mut chars),
+            '\u{90}' | '\u{98}' | '\u{9d}' | '\u{9e}' | '\u{9f}' => skip_control_string(&mut chars),
+            c => clean.push(c),
+        }
+    }
+    clean
+}
+
+/// Skip the parameters and final byte of a CSI sequence such as `ESC[31m`
+fn skip_control_sequence(chars: &mut Peekable<impl Iterator<Item = char>>) {
+    while chars.next_if(|c| matches!(c, ' '..='?')).is_some() {}
+    chars.next_if(|c| matches!(c, '@'..='~'));
+}
+
+/// Skip an OSC, DCS or similar string up to its terminator
+fn skip_control_string(chars: &mut Peekable<impl Iterator<Item = char>>) {
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{07}' | '\u{9c}' => return,
+            '\u{1b}' if chars.next_if_eq(&'\\').is_some() => return,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::ThinkingEngine;
+
+    #[tokio::test]
+    async fn test_sanitize_thought_text() {
+        let config = SanitizeConfig::default();
+        assert!(matches!(
+            config.sanitize("Plain text\n\twith layout"),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(
+            config.sanitize("\u{1b}[2J\u{1b}[1;31mRed\u{1b}[0m alert\u{7}"),
+            "Red alert"
+        );
+        assert_eq!(
+            config.sanitize("\u{1b}]0;pwned\u{7}Title \u{1b}]8;;https://x\u{1b}\\link"),
+            "Title link"
+        );
+        assert_eq!(config.sanitize("Back\u{8}\u{8}space\r\n"), "Backspace\n");
+        assert_eq!(config.sanitize("Cafe\u{301}"), "Caf\u{e9}");
+        assert_eq!(
+            SanitizeConfig::disabled().sanitize("\u{1b}[31m"),
+            "\u{1b}[31m"
+        );
+
+        let mut engine = ThinkingEngine::with_logging(true);
+        let mut thought = ThoughtData::new("\u{1b}[31mCheck\u{1b}[0m the cache".to_string(), 1, 1);
+        thought.author = Some("agent\u{1b}[2K".to_string());
+        let processed = engine.process_thought(thought).await.unwrap();
+        assert_eq!(processed.thought, "Check the cache");
+        assert_eq!(processed.author.as_deref(), Some("agent"));
+        assert_eq!(engine.get_thoughts()[0].thought, "Check the cache");
+
+        // Text left empty after sanitizing is rejected
+        let blank = ThoughtData::new("\u{1b}[0m\u{0}".to_string(), 2, 2);
+        assert!(engine.process_thought(blank).await.is_err());
+    }
+}
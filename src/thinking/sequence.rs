@@ -0,0 +1,127 @@
+//! # Thought Numbering
+//!
+//! Sequence validation for submitted thought numbers.
+//!
+//! Thoughts are numbered across the main line, revisions and branches alike,
+//! so every new thought should follow the one processed before it. The
+//! [`NumberingPolicy`] decides how strictly that is enforced. A thought
+//! numbered 1 that is neither a revision nor a branch always starts a new
+//! sequence, so a long-lived engine can work through several problems in turn.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::thinking::ThoughtData;
+
+/// How the engine treats thought numbers that do not follow the previous one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberingPolicy {
+    /// Each thought must be numbered exactly one after the previous thought
+    Strict,
+    /// Out-of-sequence thoughts are renumbered to follow the previous thought
+    AutoRenumber,
+    /// Numbers must increase but may skip ahead
+    #[default]
+    AllowGaps,
+}
+
+/// Thought submitted out of sequence
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SequenceError {
+    /// The thought repeats the number of the previous thought
+    #[error("Thought {thought_number} was already submitted")]
+    Duplicate { thought_number: u32 },
+
+    /// The thought is numbered below the previous thought
+    #[error("Thought {thought_number} is out of order: the previous thought was {previous}")]
+    OutOfOrder { thought_number: u32, previous: u32 },
+
+    /// The thought skips numbers under the strict policy
+    #[error("Thought {thought_number} skips ahead: expected thought {expected}")]
+    Gap { thought_number: u32, expected: u32 },
+}
+
+impl NumberingPolicy {
+    /// Check a thought against the number of the previous thought
+    ///
+    /// Returns the number the thought should be recorded under, which only
+    /// differs from the submitted one under [`NumberingPolicy::AutoRenumber`].
+    pub fn check(self, previous: Option<u32>, thought: &ThoughtData) -> Result<u32, SequenceError> {
+        let thought_number = thought.thought_number;
+        let restart = thought_number == 1 && !thought.is_revision() && !thought.is_branch();
+        let expected = previous.map_or(1, |previous| previous + 1);
+        if restart || thought_number == expected {
+            return Ok(thought_number);
+        }
+
+        match (self, previous) {
+            (Self::AutoRenumber, _) => Ok(expected),
+            (_, Some(previous)) if thought_number == previous => {
+                Err(SequenceError::Duplicate { thought_number })
+            }
+            (_, Some(previous)) if thought_number < previous => Err(SequenceError::OutOfOrder {
+                thought_number,
+                previous,
+            }),
+            (Self::Strict, _) => Err(SequenceError::Gap {
+                thought_number,
+                expected,
+            }),
+            (Self::AllowGaps, _) => Ok(thought_number),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbering_policies() {
+        let thought = |n| ThoughtData::new(format!("Thought {n}"), n, 5);
+
+        for policy in [
+            NumberingPolicy::Strict,
+            NumberingPolicy::AutoRenumber,
+            NumberingPolicy::AllowGaps,
+        ] {
+            assert_eq!(policy.check(Some(2), &thought(3)), Ok(3));
+            // Thought 1 starts a new sequence
+            assert_eq!(policy.check(Some(4), &thought(1)), Ok(1));
+        }
+
+        let strict = NumberingPolicy::Strict;
+        assert_eq!(
+            strict.check(Some(3), &thought(3)),
+            Err(SequenceError::Duplicate { thought_number: 3 })
+        );
+        assert_eq!(
+            strict.check(Some(5), &thought(3)),
+            Err(SequenceError::OutOfOrder {
+                thought_number: 3,
+                previous: 5
+            })
+        );
+        assert_eq!(
+            strict.check(Some(1), &thought(5)),
+            Err(SequenceError::Gap {
+                thought_number: 5,
+                expected: 2
+            })
+        );
+        assert!(strict.check(None, &thought(2)).is_err());
+
+        let gaps = NumberingPolicy::AllowGaps;
+        assert_eq!(gaps.check(Some(1), &thought(5)), Ok(5));
+        assert!(gaps.check(Some(5), &thought(3)).is_err());
+        // A revision of thought 1 is not a restart
+        let revision = ThoughtData::revision("Rethink".to_string(), 1, 1);
+        assert!(gaps.check(Some(3), &revision).is_err());
+
+        let renumber = NumberingPolicy::AutoRenumber;
+        assert_eq!(renumber.check(Some(5), &thought(3)), Ok(6));
+        assert_eq!(renumber.check(None, &thought(4)), Ok(1));
+    }
+}
@@ -5,18 +5,60 @@
 //! This module provides the main server implementation that handles
 //! sequential thinking requests through the MCP protocol.
 
-use std::collections::HashMap;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 use tokio::sync::RwLock;
-use tracing::info;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, Instrument};
 
 use ultrafast_mcp::{
     ListToolsRequest, ListToolsResponse, MCPError, MCPResult, ServerCapabilities, ServerInfo, Tool,
     ToolCall, ToolContent, ToolHandler, ToolResult, ToolsCapability, UltraFastServer,
 };
 
-use crate::thinking::error::{SequentialThinkingError, SequentialThinkingResult};
-use crate::thinking::{ThinkingEngine, ThinkingStats, ThoughtData};
+use crate::actions::{self, ActionExtractor};
+use crate::admin::{AdminStats, LiveSession};
+use crate::analytics::cost::{CostReport, PricingConfig};
+use crate::analytics::dashboard::{AnalyticsDashboard, SharedMetricsStore, MAX_DASHBOARD_DAYS};
+use crate::analytics::exporter::AnalyticsPusher;
+use crate::analytics::readability::ReadabilityConfig;
+use crate::analytics::AnalyticsEngine;
+use crate::annotations::Annotation;
+use crate::config::{Role, SecurityConfig, ThinkingConfig, UIConfig};
+use crate::decisions::DecisionLog;
+use crate::export::dedup::INDEX_FILENAME;
+use crate::export::session_log::SessionLog;
+use crate::export::{ExportEngine, ExportFormat, ExportOptions, ExportRecord};
+use crate::integrations::{create_issues, IntegrationsConfig, IssueDraft};
+use crate::redaction::pii::{PiiKind, PiiScanner};
+use crate::session::SessionManager;
+use crate::thinking::actor::{SessionActors, SharedEngine};
+use crate::thinking::backpressure::Backpressure;
+use crate::thinking::checkpoint::EngineCheckpoint;
+use crate::thinking::code;
+use crate::thinking::elicitation::{self, ThoughtElicitor};
+use crate::thinking::error::{ErrorContext, SequentialThinkingError, SequentialThinkingResult};
+use crate::thinking::metadata::{self, MetadataSchema};
+use crate::thinking::replay::{replay_delays, ReplayEvent};
+use crate::thinking::response::{ResponseProgress, ThoughtResponse};
+use crate::thinking::sampling::{self, ThoughtSampler};
+use crate::thinking::spill::{FileSpillStore, SharedSpillStore};
+use crate::thinking::{
+    thought_number_field, SharedThought, ThinkingEngine, ThinkingStats, ThoughtData,
+};
+use crate::usage::{UsageConfig, UsageMetric, UsageReport, UsageTracker, ANONYMOUS_KEY};
+use crate::visualization::ThoughtRenderer;
+
+/// Export formats supported by the export_session tool
+pub const SUPPORTED_EXPORT_FORMATS: &[&str] = &["json", "markdown"];
+
+/// Number of idempotency keys the server remembers
+pub const IDEMPOTENCY_CACHE_SIZE: usize = 1024;
+
+/// Days reported by the analytics_dashboard tool when none are given
+pub const DEFAULT_DASHBOARD_DAYS: u32 = 30;
 
 #[derive(Debug, Clone)]
 pub struct SequentialThinkingServer {
@@ -24,16 +66,66 @@ pub struct SequentialThinkingServer {
     info: ServerInfo,
     /// Server capabilities
     capabilities: ServerCapabilities,
-    /// Thinking engine
-    engine: Arc<RwLock<ThinkingEngine>>,
-    /// Session management
-    sessions: Arc<RwLock<HashMap<String, ThinkingEngine>>>,
+    /// Configured thinking limits
+    thinking_config: ThinkingConfig,
+    /// Access control settings
+    security: SecurityConfig,
+    /// Model prices used for cost reports
+    pricing: PricingConfig,
+    /// Thresholds marking a thought as hard to read
+    readability: ReadabilityConfig,
+    /// Issue trackers the create_issues tool writes to
+    integrations: IntegrationsConfig,
+    /// Analytics of completed sessions, aggregated for the dashboard
+    analytics: Arc<RwLock<AnalyticsEngine>>,
+    /// Pushes the analytics of completed sessions to an external endpoint
+    pusher: Option<Arc<AnalyticsPusher>>,
+    /// Exports completed sessions in the configured formats
+    auto_export: Option<AutoExport>,
+    /// Raw log every processed thought is appended to
+    session_log: Option<SessionLog>,
+    /// Usage counters and monthly quotas by API key
+    usage: Arc<UsageTracker>,
+    /// Thinking engine of the default session
+    engine: SessionEngine,
+    /// Named sessions, each behind its own lock so they process thoughts in parallel
+    sessions: Arc<RwLock<HashMap<String, SessionEngine>>>,
     /// Server statistics
-    stats: Arc<RwLock<ServerStats>>,
+    stats: Arc<ServerCounters>,
+    /// Tokens cancelling in-flight thought processing, by session
+    cancellation: Arc<RwLock<HashMap<String, CancellationToken>>>,
+    /// Results of recent submissions that carried an idempotency key, by session
+    idempotency: Arc<RwLock<HashMap<String, IdempotencyCache>>>,
+    /// Where bounded-memory engines spill older thoughts
+    spill: Option<SharedSpillStore>,
+    /// Sends sampling requests to the connected host
+    sampler: Option<Arc<dyn ThoughtSampler>>,
+    /// Sends elicitation requests to the connected host
+    elicitor: Option<Arc<dyn ThoughtElicitor>>,
+    /// Limits on the tool calls handled at once
+    backpressure: Option<Arc<Backpressure>>,
+    /// Actors handling the tool calls of each session in order
+    actors: Option<Arc<SessionActors>>,
+    /// Session manager named sessions are stored in after every change
+    session_store: Option<Arc<SessionManager>>,
+}
+
+/// Engine of one session, locked independently of the other sessions
+type SessionEngine = SharedEngine;
+
+/// Exports of completed sessions
+#[derive(Debug, Clone)]
+struct AutoExport {
+    /// Engine writing the exports
+    exporter: Arc<tokio::sync::Mutex<ExportEngine>>,
+    /// Formats every completed session is exported in
+    formats: Vec<ExportFormat>,
+    /// Options of the exports
+    options: ExportOptions,
 }
 
 /// Server statistics
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ServerStats {
     /// Total requests processed
     pub total_requests: u64,
@@ -47,6 +139,82 @@ pub struct ServerStats {
     pub total_response_time_ms: u64,
     /// Error count
     pub error_count: u64,
+    /// Retried submissions answered from the idempotency cache
+    pub duplicate_requests: u64,
+}
+
+/// Server statistics counters, bumped without locking on every request
+#[derive(Debug, Default)]
+struct ServerCounters {
+    total_requests: AtomicU64,
+    total_thoughts: AtomicU64,
+    total_sessions: AtomicU64,
+    total_response_time_ms: AtomicU64,
+    error_count: AtomicU64,
+    duplicate_requests: AtomicU64,
+}
+
+impl ServerCounters {
+    /// Add one to a counter
+    fn bump(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Current values, with the average response time derived from the totals
+    fn snapshot(&self) -> ServerStats {
+        let total_requests = self.total_requests.load(Ordering::Relaxed);
+        let total_response_time_ms = self.total_response_time_ms.load(Ordering::Relaxed);
+        ServerStats {
+            total_requests,
+            total_thoughts: self.total_thoughts.load(Ordering::Relaxed),
+            total_sessions: self.total_sessions.load(Ordering::Relaxed),
+            avg_response_time_ms: if total_requests == 0 {
+                0.0
+            } else {
+                total_response_time_ms as f64 / total_requests as f64
+            },
+            total_response_time_ms,
+            error_count: self.error_count.load(Ordering::Relaxed),
+            duplicate_requests: self.duplicate_requests.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Processed thoughts by idempotency key, evicting the oldest key when full
+#[derive(Debug, Default)]
+struct IdempotencyCache {
+    results: HashMap<String, ThoughtData>,
+    order: VecDeque<String>,
+}
+
+impl IdempotencyCache {
+    /// Get the original result of a submission retried with `thought`
+    fn replay(
+        &self,
+        key: &str,
+        thought: &ThoughtData,
+    ) -> SequentialThinkingResult<Option<ThoughtData>> {
+        match self.results.get(key) {
+            Some(original) if original.thought != thought.thought => {
+                Err(SequentialThinkingError::validation_error(format!(
+                    "Idempotency key '{key}' was already used for a different thought"
+                )))
+            }
+            original => Ok(original.cloned()),
+        }
+    }
+
+    /// Remember the result of a keyed submission
+    fn insert(&mut self, key: String, processed: ThoughtData) {
+        if self.results.insert(key.clone(), processed).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > IDEMPOTENCY_CACHE_SIZE {
+            if let Some(oldest) = self.order.pop_front() {
+                self.results.remove(&oldest);
+            }
+        }
+    }
 }
 
 impl SequentialThinkingServer {
@@ -82,9 +250,27 @@ impl SequentialThinkingServer {
                 logging: None,
                 completion: None,
             },
+            thinking_config: ThinkingConfig::default(),
+            security: SecurityConfig::default(),
+            pricing: PricingConfig::default(),
+            readability: ReadabilityConfig::default(),
+            integrations: IntegrationsConfig::default(),
+            analytics: Arc::new(RwLock::new(AnalyticsEngine::new())),
+            pusher: None,
+            auto_export: None,
+            session_log: None,
+            usage: Arc::new(UsageTracker::default()),
             engine: Arc::new(RwLock::new(ThinkingEngine::with_logging(disable_logging))),
             sessions: Arc::new(RwLock::new(HashMap::new())),
-            stats: Arc::new(RwLock::new(ServerStats::default())),
+            stats: Arc::new(ServerCounters::default()),
+            cancellation: Arc::new(RwLock::new(HashMap::new())),
+            idempotency: Arc::new(RwLock::new(HashMap::new())),
+            spill: None,
+            sampler: None,
+            elicitor: None,
+            backpressure: None,
+            actors: None,
+            session_store: None,
         }
     }
 
@@ -97,10 +283,236 @@ impl SequentialThinkingServer {
         Self {
             info,
             capabilities,
+            thinking_config: ThinkingConfig::default(),
+            security: SecurityConfig::default(),
+            pricing: PricingConfig::default(),
+            readability: ReadabilityConfig::default(),
+            integrations: IntegrationsConfig::default(),
+            analytics: Arc::new(RwLock::new(AnalyticsEngine::new())),
+            pusher: None,
+            auto_export: None,
+            session_log: None,
+            usage: Arc::new(UsageTracker::default()),
             engine: Arc::new(RwLock::new(ThinkingEngine::with_logging(disable_logging))),
             sessions: Arc::new(RwLock::new(HashMap::new())),
-            stats: Arc::new(RwLock::new(ServerStats::default())),
+            stats: Arc::new(ServerCounters::default()),
+            cancellation: Arc::new(RwLock::new(HashMap::new())),
+            idempotency: Arc::new(RwLock::new(HashMap::new())),
+            spill: None,
+            sampler: None,
+            elicitor: None,
+            backpressure: None,
+            actors: None,
+            session_store: None,
+        }
+    }
+
+    /// Set the thinking limits reported to clients, the numbering policy and memory bounds
+    pub fn with_thinking_config(mut self, thinking_config: ThinkingConfig) -> Self {
+        self.spill = thinking_config
+            .bounded_memory
+            .as_ref()
+            .map(|bounded| FileSpillStore::shared(&bounded.spill_dir));
+        self.backpressure = thinking_config
+            .backpressure
+            .clone()
+            .map(|config| Arc::new(Backpressure::new(config)));
+        self.actors = thinking_config
+            .actors
+            .as_ref()
+            .map(|config| Arc::new(SessionActors::new(config.mailbox_capacity)));
+        if let Ok(mut engine) = self.engine.try_write() {
+            engine.set_numbering_policy(thinking_config.numbering_policy);
+            engine.set_strategy(thinking_config.strategy);
+            engine.set_attachment_limits(thinking_config.attachments);
+            engine.set_sanitize_config(thinking_config.sanitize);
+            self.configure_memory(&mut engine, &thinking_config);
+        }
+        self.thinking_config = thinking_config;
+        self
+    }
+
+    /// Apply the bounded-memory settings to an engine
+    fn configure_memory(&self, engine: &mut ThinkingEngine, thinking_config: &ThinkingConfig) {
+        if let (Some(bounded), Some(store)) = (&thinking_config.bounded_memory, &self.spill) {
+            engine.set_bounded_memory(bounded.max_thoughts_in_memory, store.clone());
+        }
+    }
+
+    /// Set how processed thoughts are rendered in the stderr thought log
+    pub fn with_ui_config(self, ui: &UIConfig) -> Self {
+        if let Ok(mut engine) = self.engine.try_write() {
+            engine.set_renderer(ThoughtRenderer::from_ui_config(ui));
+        }
+        self
+    }
+
+    /// Get the configured thinking limits
+    pub fn thinking_config(&self) -> &ThinkingConfig {
+        &self.thinking_config
+    }
+
+    /// Set the access control settings enforced on tool calls
+    pub fn with_security_config(mut self, security: SecurityConfig) -> Self {
+        self.security = security;
+        self
+    }
+
+    /// Get the access control settings
+    pub fn security_config(&self) -> &SecurityConfig {
+        &self.security
+    }
+
+    /// Set the model prices used by the cost_report tool
+    pub fn with_pricing_config(mut self, pricing: PricingConfig) -> Self {
+        if let Ok(mut analytics) = self.analytics.try_write() {
+            *analytics = std::mem::take(&mut *analytics).with_pricing(pricing.clone());
+        }
+        self.pricing = pricing;
+        self
+    }
+
+    /// Set the thresholds used to flag hard to read thoughts
+    pub fn with_readability_config(mut self, readability: ReadabilityConfig) -> Self {
+        if let Ok(mut analytics) = self.analytics.try_write() {
+            *analytics = std::mem::take(&mut *analytics).with_readability(readability.clone());
+        }
+        self.readability = readability;
+        self
+    }
+
+    /// Keep the aggregated analytics of completed sessions in `store`
+    ///
+    /// Aggregates saved by an earlier run are loaded right away.
+    pub fn with_metrics_store(self, store: SharedMetricsStore) -> Self {
+        if let Ok(mut analytics) = self.analytics.try_write() {
+            *analytics = std::mem::take(&mut *analytics).with_metrics_store(store);
+            if let Err(e) = analytics.load_aggregated_metrics() {
+                tracing::warn!("Failed to load aggregated metrics: {}", e);
+            }
+        }
+        self
+    }
+
+    /// Queue the analytics of completed sessions on `pusher`
+    pub fn with_analytics_pusher(mut self, pusher: Arc<AnalyticsPusher>) -> Self {
+        self.pusher = Some(pusher);
+        self
+    }
+
+    /// Get the pusher sending analytics to an external endpoint, if any
+    pub fn analytics_pusher(&self) -> Option<&Arc<AnalyticsPusher>> {
+        self.pusher.as_ref()
+    }
+
+    /// Export completed sessions in every configured format when `auto_export` is set
+    ///
+    /// Hashes of the exports are kept in the export directory, so a session
+    /// completing again unchanged is not exported twice. With a
+    /// `session_log_directory`, every processed thought is also appended to
+    /// its session's log as it happens, and the log is compacted into a
+    /// snapshot every `session_log_snapshot_interval` thoughts.
+    pub fn with_export_config(mut self, export: &crate::config::ExportConfig) -> Self {
+        self.session_log = export.session_log_directory.as_ref().map(|dir| {
+            let log = SessionLog::new(dir);
+            match export.session_log_snapshot_interval {
+                Some(interval) => log.with_snapshot_interval(interval),
+                None => log,
+            }
+        });
+        if !export.auto_export {
+            self.auto_export = None;
+            return self;
         }
+        let engine_config = crate::export::ExportConfig {
+            formats: export.formats.clone(),
+            auto_export: true,
+            export_directory: export.export_directory.clone(),
+            filename_template: export.filename_template.clone(),
+            include_metadata: export.include_metadata,
+            include_statistics: export.include_statistics,
+            destination: export.destination.clone(),
+            ..Default::default()
+        };
+        let index_path = std::path::Path::new(&export.export_directory).join(INDEX_FILENAME);
+        let exporter = ExportEngine::with_config(engine_config.clone())
+            .with_export_index(index_path)
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load export index: {}", e);
+                ExportEngine::with_config(engine_config)
+            });
+        self.auto_export = Some(AutoExport {
+            exporter: Arc::new(tokio::sync::Mutex::new(exporter)),
+            formats: export
+                .formats
+                .iter()
+                .filter_map(|format| format.parse().ok())
+                .collect(),
+            options: ExportOptions {
+                include_metadata: export.include_metadata,
+                include_statistics: export.include_statistics,
+                ..Default::default()
+            },
+        });
+        self
+    }
+
+    /// Set the issue trackers used by the create_issues tool
+    pub fn with_integrations_config(mut self, integrations: IntegrationsConfig) -> Self {
+        self.integrations = integrations;
+        self
+    }
+
+    /// Set how sampling requests reach the connected host
+    ///
+    /// Continue markers are only expanded when `thinking.sampling` is set.
+    pub fn with_sampler(mut self, sampler: Arc<dyn ThoughtSampler>) -> Self {
+        self.sampler = Some(sampler);
+        self
+    }
+
+    /// Set how elicitation requests reach the connected host
+    ///
+    /// Missing fields are only asked for when `thinking.elicitation` is set.
+    pub fn with_elicitor(mut self, elicitor: Arc<dyn ThoughtElicitor>) -> Self {
+        self.elicitor = Some(elicitor);
+        self
+    }
+
+    /// Store named sessions in a session manager after every change
+    ///
+    /// The manager may be shared with other writers, e.g. maintenance jobs
+    /// changing tags or status; their changes are kept.
+    pub fn with_session_manager(mut self, sessions: Arc<SessionManager>) -> Self {
+        self.session_store = Some(sessions);
+        self
+    }
+
+    /// Set the monthly quotas of API keys
+    pub fn with_usage_config(mut self, usage: UsageConfig) -> Self {
+        self.usage = Arc::new(UsageTracker::new(usage));
+        self
+    }
+
+    /// Get the usage counters and quotas of API keys
+    pub fn usage(&self) -> &UsageTracker {
+        &self.usage
+    }
+
+    /// Key a caller's usage is counted under
+    ///
+    /// Keys that are not configured, including those sent to a server
+    /// without API key validation, count as anonymous.
+    pub fn usage_key(&self, api_key: Option<&str>) -> String {
+        api_key
+            .filter(|key| self.security.api_keys.contains_key(*key))
+            .unwrap_or(ANONYMOUS_KEY)
+            .to_string()
+    }
+
+    /// Report this month's usage of every API key
+    pub async fn usage_report(&self) -> UsageReport {
+        self.usage.report().await
     }
 
     /// Get server information
@@ -115,80 +527,389 @@ impl SequentialThinkingServer {
 
     /// Get server statistics
     pub async fn get_stats(&self) -> ServerStats {
-        self.stats.read().await.clone()
+        self.stats.snapshot()
     }
 
     /// Create an UltraFast MCP server instance
     pub fn create_mcp_server(self) -> UltraFastServer {
         let info = self.info.clone();
         let capabilities = self.capabilities.clone();
-        let tool_handler = Arc::new(SequentialThinkingToolHandler {
-            server: Arc::new(self),
-        });
 
-        UltraFastServer::new(info, capabilities).with_tool_handler(tool_handler)
+        UltraFastServer::new(info, capabilities).with_tool_handler(self.tool_handler())
+    }
+
+    /// Create the handler answering tool calls for this server
+    pub fn tool_handler(self) -> Arc<dyn ToolHandler> {
+        Arc::new(SequentialThinkingToolHandler {
+            server: Arc::new(self),
+        })
     }
 
     /// Process a thought using the main engine
+    ///
+    /// A thought whose idempotency key was seen before is not processed again;
+    /// the original result is returned instead.
     pub async fn process_thought(
         &self,
         thought: ThoughtData,
     ) -> SequentialThinkingResult<ThoughtData> {
-        let start_time = std::time::Instant::now();
+        self.process_in("default", &self.engine, thought).await
+    }
 
-        // Update request statistics
-        {
-            let mut stats = self.stats.write().await;
-            stats.total_requests += 1;
+    /// Process a thought in a session created with [`create_session`](Self::create_session)
+    ///
+    /// Only the session's own engine is locked, so thoughts of different
+    /// sessions are processed in parallel.
+    pub async fn process_session_thought(
+        &self,
+        session_id: &str,
+        thought: ThoughtData,
+    ) -> SequentialThinkingResult<ThoughtData> {
+        let engine = self.session_engine(session_id).await?;
+        self.process_in(session_id, &engine, thought).await
+    }
+
+    /// Engine of a session, the default session being called `default`
+    async fn engine_for(&self, session_id: &str) -> SequentialThinkingResult<SessionEngine> {
+        if session_id == "default" {
+            return Ok(self.engine.clone());
         }
+        self.session_engine(session_id).await
+    }
 
-        // Process the thought
-        let result = {
-            let mut engine = self.engine.write().await;
-            engine.process_thought(thought).await
+    /// Token cancelling the thought processing of a session
+    async fn cancellation_token(&self, session_id: &str) -> CancellationToken {
+        if let Some(token) = self.cancellation.read().await.get(session_id) {
+            return token.clone();
+        }
+        self.cancellation
+            .write()
+            .await
+            .entry(session_id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Engine of a named session
+    async fn session_engine(&self, session_id: &str) -> SequentialThinkingResult<SessionEngine> {
+        self.sessions
+            .read()
+            .await
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| SequentialThinkingError::not_found(format!("session '{session_id}'")))
+    }
+
+    /// Process a thought with the engine of the given session
+    ///
+    /// Errors are logged and record the session and thought they happened in.
+    async fn process_in(
+        &self,
+        session_id: &str,
+        session: &RwLock<ThinkingEngine>,
+        thought: ThoughtData,
+    ) -> SequentialThinkingResult<ThoughtData> {
+        let start_time = std::time::Instant::now();
+        let thought_number = thought.thought_number;
+
+        ServerCounters::bump(&self.stats.total_requests);
+
+        // Process the thought, honouring cancellation and the per-thought timeout
+        let token = self.cancellation_token(session_id).await;
+        let span = tracing::info_span!("process_thought", session_id, thought_number);
+        let processing = async {
+            // Calls queued behind the engine lock can be cancelled too
+            let mut engine = tokio::select! {
+                biased;
+                _ = token.cancelled() => {
+                    return Err(SequentialThinkingError::cancelled("session was cancelled"));
+                }
+                engine = session.write() => engine,
+            };
+            let key = thought.idempotency_key.clone();
+            if let Some(key) = &key {
+                if let Some(cache) = self.idempotency.read().await.get(session_id) {
+                    if let Some(original) = cache.replay(key, &thought)? {
+                        return Ok((original, true));
+                    }
+                }
+            }
+
+            engine.check_sequence(&thought)?;
+            let processed = engine
+                .process_thought_with_cancellation(thought, &token)
+                .await
+                .map_err(|e| {
+                    if token.is_cancelled() {
+                        SequentialThinkingError::cancelled("session was cancelled")
+                    } else {
+                        SequentialThinkingError::processing_error(e)
+                    }
+                })?;
+            if let Some(log) = &self.session_log {
+                let session_id = engine.session_id().unwrap_or("default");
+                if let Err(e) = log.append(session_id, &processed) {
+                    tracing::warn!("Failed to append to session log: {}", e);
+                }
+            }
+            if let Some(key) = key {
+                self.idempotency
+                    .write()
+                    .await
+                    .entry(session_id.to_string())
+                    .or_default()
+                    .insert(key, processed.clone());
+            }
+            Ok((processed, false))
+        }
+        .instrument(span);
+        let result = match self.thinking_config.thought_timeout_seconds {
+            Some(seconds) => {
+                let timeout = std::time::Duration::from_secs(seconds);
+                tokio::time::timeout(timeout, processing)
+                    .await
+                    .unwrap_or_else(|_| Err(SequentialThinkingError::timeout(timeout)))
+            }
+            None => processing.await,
         };
 
         // Update response time statistics
-        {
-            let response_time = start_time.elapsed();
-            let mut stats = self.stats.write().await;
-            stats.total_response_time_ms += response_time.as_millis() as u64;
-            stats.avg_response_time_ms =
-                stats.total_response_time_ms as f64 / stats.total_requests as f64;
-
-            if result.is_ok() {
-                stats.total_thoughts += 1;
-            } else {
-                stats.error_count += 1;
+        self.stats
+            .total_response_time_ms
+            .fetch_add(start_time.elapsed().as_millis() as u64, Ordering::Relaxed);
+        ServerCounters::bump(match result {
+            Ok((_, false)) => &self.stats.total_thoughts,
+            Ok((_, true)) => &self.stats.duplicate_requests,
+            Err(_) => &self.stats.error_count,
+        });
+
+        if let Ok((processed, false)) = &result {
+            if !processed.next_thought_needed {
+                self.record_completed_session(session).await;
+                self.auto_export_session(session).await;
+            }
+        }
+
+        result.map(|(processed, _)| processed).map_err(|e| {
+            let e = e.with_context(
+                ErrorContext::new("process_thought")
+                    .with_session(session_id)
+                    .with_thought(thought_number),
+            );
+            e.log();
+            e
+        })
+    }
+
+    /// Fold a completed session into the aggregated analytics
+    ///
+    /// A session with an ID is only counted the first time it completes.
+    async fn record_completed_session(&self, session: &RwLock<ThinkingEngine>) {
+        let engine = session.read().await;
+        let mut analytics = self.analytics.write().await;
+        if let Some(session_id) = engine.session_id() {
+            if analytics.get_session_analytics(session_id).is_some() {
+                return;
+            }
+        }
+        let session = analytics.analyze_session(
+            engine.session_id().unwrap_or("default"),
+            "",
+            &engine.all_thoughts(),
+            engine.get_stats(),
+            engine.get_progress(),
+        );
+        if let Some(pusher) = &self.pusher {
+            pusher.enqueue(&session).await;
+        }
+    }
+
+    /// Export a completed session in every auto-export format
+    ///
+    /// Failed formats are logged and do not affect the others.
+    async fn auto_export_session(&self, session: &RwLock<ThinkingEngine>) -> Vec<ExportRecord> {
+        let Some(auto_export) = &self.auto_export else {
+            return Vec::new();
+        };
+        let mut exporter = auto_export.exporter.lock().await;
+        let prepared = {
+            let engine = session.read().await;
+            let branches = engine
+                .get_branches()
+                .iter()
+                .map(|(branch_id, branch)| (branch_id.clone(), branch.thoughts.clone()))
+                .collect();
+            exporter
+                .prepare_export_data(
+                    engine.session_id().unwrap_or("default"),
+                    None,
+                    &engine.all_thoughts(),
+                    Some(engine.get_stats()),
+                    Some(engine.get_progress()),
+                    Some(&branches),
+                    None,
+                    Some(engine.get_annotations()),
+                    &auto_export.options,
+                )
+                .map_err(|e| e.to_string())
+        };
+        let data = match prepared {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("Failed to prepare auto-export: {}", e);
+                return Vec::new();
+            }
+        };
+        exporter.export_all(&data, &auto_export.formats).await
+    }
+
+    /// Daily aggregates of the sessions completed in the last `days` days
+    pub async fn analytics_dashboard(
+        &self,
+        days: u32,
+    ) -> SequentialThinkingResult<AnalyticsDashboard> {
+        if !(1..=MAX_DASHBOARD_DAYS).contains(&days) {
+            return Err(SequentialThinkingError::validation_error(format!(
+                "days must be between 1 and {MAX_DASHBOARD_DAYS}"
+            )));
+        }
+        Ok(self.analytics.read().await.dashboard(days))
+    }
+
+    /// Replace a continue marker with a thought generated by the host
+    ///
+    /// The host sees the thoughts of the given session. A retried marker
+    /// whose idempotency key was seen before gets the original thought back,
+    /// so it is not generated twice.
+    pub async fn generate_thought(
+        &self,
+        session_id: &str,
+        marker: ThoughtData,
+    ) -> SequentialThinkingResult<ThoughtData> {
+        let (Some(config), Some(sampler)) = (&self.thinking_config.sampling, &self.sampler) else {
+            return Err(SequentialThinkingError::config_error(
+                "Sampling is not enabled on this server",
+            ));
+        };
+        if let Some(key) = &marker.idempotency_key {
+            let idempotency = self.idempotency.read().await;
+            if let Some(original) = idempotency
+                .get(session_id)
+                .and_then(|cache| cache.results.get(key))
+            {
+                return Ok(original.clone());
             }
         }
+        let engine = self.engine_for(session_id).await?;
+        let thoughts = engine.read().await.all_thoughts().into_owned();
+        sampling::generate_thought(sampler.as_ref(), config, &thoughts, marker)
+            .await
+            .map_err(SequentialThinkingError::processing_error)
+    }
+
+    /// Whether missing thought fields are asked of the user
+    pub fn elicitation_enabled(&self) -> bool {
+        self.thinking_config.elicitation.is_some() && self.elicitor.is_some()
+    }
+
+    /// Ask the user for the configured fields missing from tool call arguments
+    ///
+    /// Does nothing unless elicitation is enabled, leaving the missing fields
+    /// to be reported by argument validation.
+    pub async fn elicit_missing_fields(
+        &self,
+        args: &mut serde_json::Value,
+    ) -> SequentialThinkingResult<()> {
+        let (Some(config), Some(elicitor)) = (&self.thinking_config.elicitation, &self.elicitor)
+        else {
+            return Ok(());
+        };
+        elicitation::complete_arguments(elicitor.as_ref(), config, args)
+            .await
+            .map_err(SequentialThinkingError::invalid_thought_data)
+    }
 
-        result.map_err(SequentialThinkingError::processing_error)
+    /// Cancel the thought processing of a session that is in flight or waiting for its engine
+    ///
+    /// Affected calls fail with a `Cancelled` error; thoughts submitted
+    /// afterwards, and thoughts of other sessions, are processed normally.
+    pub async fn cancel_session(&self, session_id: &str) {
+        if let Some(token) = self.cancellation.write().await.remove(session_id) {
+            token.cancel();
+        }
     }
 
     /// Create a new thinking session
+    ///
+    /// Fails when a session with the ID exists, including the default
+    /// session called `default`.
     pub async fn create_session(&self, session_id: String) -> SequentialThinkingResult<()> {
+        let mut engine = ThinkingEngine::new();
+        engine.set_numbering_policy(self.thinking_config.numbering_policy);
+        engine.set_strategy(self.thinking_config.strategy);
+        engine.set_attachment_limits(self.thinking_config.attachments);
+        engine.set_sanitize_config(self.thinking_config.sanitize);
+        self.configure_memory(&mut engine, &self.thinking_config);
+        engine.start_session(session_id.clone());
         let mut sessions = self.sessions.write().await;
-        let engine = ThinkingEngine::new();
-        sessions.insert(session_id.clone(), engine);
-
-        {
-            let mut stats = self.stats.write().await;
-            stats.total_sessions += 1;
+        if session_id == "default" || sessions.contains_key(&session_id) {
+            return Err(SequentialThinkingError::validation_error(format!(
+                "Session already exists: {session_id}"
+            )));
         }
+        sessions.insert(session_id.clone(), Arc::new(RwLock::new(engine)));
+        drop(sessions);
+
+        ServerCounters::bump(&self.stats.total_sessions);
 
         info!("Created new thinking session: {}", session_id);
         Ok(())
     }
 
-    /// Get a thinking session
+    /// Store a named session's engine in the session manager, if one is set
+    ///
+    /// The update goes through
+    /// [`modify_session`](SessionManager::modify_session), so a conflicting
+    /// write by another writer is retried on the fresh stored session.
+    async fn persist_session(&self, session_id: &str) -> SequentialThinkingResult<()> {
+        let Some(store) = &self.session_store else {
+            return Ok(());
+        };
+        if session_id == "default" {
+            return Ok(());
+        }
+        let engine = self.session_engine(session_id).await?;
+        // Held until stored, so this copy cannot overwrite a later change
+        let engine = engine.read().await;
+        if store.get_session(session_id).await.is_none() {
+            match store
+                .create_named_session(session_id.to_string(), session_id.to_string())
+                .await
+            {
+                Ok(()) | Err(SequentialThinkingError::ValidationError { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        store
+            .modify_session(session_id, |stored| stored.set_engine(engine.clone()))
+            .await?;
+        Ok(())
+    }
+
+    /// Get a copy of a thinking session
     pub async fn get_session(&self, session_id: &str) -> Option<ThinkingEngine> {
-        let sessions = self.sessions.read().await;
-        sessions.get(session_id).cloned()
+        let engine = self.session_engine(session_id).await.ok()?;
+        let engine = engine.read().await;
+        Some(engine.clone())
     }
 
     /// Remove a thinking session
     pub async fn remove_session(&self, session_id: &str) -> bool {
+        self.cancel_session(session_id).await;
+        self.idempotency.write().await.remove(session_id);
+        if let Some(actors) = &self.actors {
+            actors.remove(session_id).await;
+        }
         let mut sessions = self.sessions.write().await;
         sessions.remove(session_id).is_some()
     }
@@ -198,6 +919,57 @@ impl SequentialThinkingServer {
         let sessions = self.sessions.read().await;
         sessions.keys().cloned().collect()
     }
+
+    /// Summarize the active sessions, ordered by ID
+    pub async fn live_sessions(&self) -> Vec<LiveSession> {
+        let sessions: Vec<(String, SessionEngine)> = self
+            .sessions
+            .read()
+            .await
+            .iter()
+            .map(|(session_id, engine)| (session_id.clone(), engine.clone()))
+            .collect();
+        let mut live = Vec::with_capacity(sessions.len());
+        for (session_id, engine) in sessions {
+            let engine = engine.read().await;
+            live.push(LiveSession {
+                session_id,
+                thought_count: engine.thought_count(),
+                branch_count: engine.get_branches().len(),
+                complete: engine.is_complete(),
+            });
+        }
+        live.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        live
+    }
+
+    /// Export an active session as `json` or `markdown`
+    pub async fn export_session(
+        &self,
+        session_id: &str,
+        format: &str,
+    ) -> SequentialThinkingResult<String> {
+        let extractor = ActionExtractor::new(&self.thinking_config.actions).map_err(|e| {
+            SequentialThinkingError::config_error(format!("Invalid action patterns: {e}"))
+        })?;
+        let engine = self.session_engine(session_id).await?;
+        let engine = engine.read().await;
+        render_session_export(&engine, &extractor, format).map_err(|e| {
+            SequentialThinkingError::validation_error(e).with_context(
+                ErrorContext::new("export_session")
+                    .with_session(session_id)
+                    .with_context("format", format),
+            )
+        })
+    }
+
+    /// Get the server statistics with the number of active sessions
+    pub async fn admin_stats(&self) -> AdminStats {
+        AdminStats {
+            stats: self.get_stats().await,
+            active_sessions: self.sessions.read().await.len(),
+        }
+    }
 }
 
 impl Default for SequentialThinkingServer {
@@ -214,11 +986,132 @@ struct SequentialThinkingToolHandler {
 #[async_trait::async_trait]
 impl ToolHandler for SequentialThinkingToolHandler {
     async fn handle_tool_call(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let correlation_id = correlation_id(&call);
+        let span = tracing::info_span!(
+            "tool_call",
+            tool = %call.name,
+            session_id = call_session_id(&call),
+            thought_number = tracing::field::Empty,
+            correlation_id = %correlation_id,
+        );
+        let document = DOCUMENT_TOOLS.contains(&call.name.as_str());
+        async {
+            match self.dispatch_tool_call(call).await {
+                Ok(mut result) => {
+                    if !document {
+                        echo_correlation_id(&mut result, &correlation_id);
+                    }
+                    Ok(result)
+                }
+                Err(e) => {
+                    tracing::warn!("Tool call failed: {}", e);
+                    Err(e)
+                }
+            }
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn list_tools(&self, _request: ListToolsRequest) -> MCPResult<ListToolsResponse> {
+        Ok(ListToolsResponse {
+            tools: tool_definitions(),
+            next_cursor: None,
+        })
+    }
+}
+
+impl SequentialThinkingToolHandler {
+    /// Authorize, admit and validate a tool call, then run its handler
+    async fn dispatch_tool_call(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        self.authorize(&call)?;
+        self.check_request_size(&call)?;
+        // Held until the call has been handled
+        let _admission = match &self.server.backpressure {
+            Some(backpressure) => Some(
+                backpressure
+                    .admit(call_session_id(&call))
+                    .map_err(|e| MCPError::invalid_request(e.user_message()))?,
+            ),
+            None => None,
+        };
+        self.server
+            .usage
+            .consume(&self.usage_key(&call), UsageMetric::Requests, 1)
+            .await
+            .map_err(|e| MCPError::invalid_request(e.to_string()))?;
+        // The arguments of sequential_thinking are checked once missing ones are elicited
+        if call.name != "sequential_thinking" {
+            validate_arguments(&call.name, call.arguments.as_ref())?;
+        }
+
+        // Cancellation skips the queue so it can stop the call being handled
+        if let Some(actors) = self
+            .server
+            .actors
+            .as_ref()
+            .filter(|_| call.name != "cancel_session")
+        {
+            let session_id = call_session_id(&call).to_string();
+            if let Ok(engine) = self.server.engine_for(&session_id).await {
+                let actor = actors.attach(&session_id, &engine).await;
+                let handler = Self {
+                    server: self.server.clone(),
+                };
+                let job = async move { handler.run_tool_call(call).await }
+                    .instrument(tracing::Span::current());
+                return actor
+                    .run(job)
+                    .await
+                    .map_err(|e| MCPError::internal_error(e.to_string()))?;
+            }
+        }
+        self.run_tool_call(call).await
+    }
+
+    /// Run the handler of a tool call, storing the session it changed
+    async fn run_tool_call(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let persisted = PERSISTED_TOOLS
+            .contains(&call.name.as_str())
+            .then(|| call_session_id(&call).to_string());
+        let result = self.handle_tool(call).await?;
+        if let Some(session_id) = persisted {
+            self.server
+                .persist_session(&session_id)
+                .await
+                .map_err(|e| MCPError::internal_error(e.to_string()))?;
+        }
+        Ok(result)
+    }
+
+    /// Run the handler matching a tool call's name
+    async fn handle_tool(&self, call: ToolCall) -> MCPResult<ToolResult> {
         match call.name.as_str() {
             "sequential_thinking" => self.handle_sequential_thinking(call).await,
+            "create_session" => self.handle_create_session(call).await,
             "export_session" => self.handle_export_session(call).await,
+            "poll_session" => self.handle_poll_session(call).await,
             "analyze_session" => self.handle_analyze_session(call).await,
             "merge_sessions" => self.handle_merge_sessions(call).await,
+            "compare_branches" => self.handle_compare_branches(call).await,
+            "compare_sessions" => self.handle_compare_sessions(call).await,
+            "edit_thought" => self.handle_edit_thought(call).await,
+            "annotate_thought" => self.handle_annotate_thought(call).await,
+            "resolve_annotation" => self.handle_resolve_annotation(call).await,
+            "replay_session" => self.handle_replay_session(call).await,
+            "cost_report" => self.handle_cost_report(call).await,
+            "extract_code" => self.handle_extract_code(call).await,
+            "export_decisions" => self.handle_export_decisions(call).await,
+            "extract_actions" => self.handle_extract_actions(call).await,
+            "create_issues" => self.handle_create_issues(call).await,
+            "cancel_session" => self.handle_cancel_session(call).await,
+            "pause_session" => self.handle_pause_session(call).await,
+            "get_server_info" => self.handle_get_server_info().await,
+            "checkpoint_session" => self.handle_checkpoint_session(call).await,
+            "resume_session" => self.handle_resume_session(call).await,
+            "usage_report" => self.handle_usage_report().await,
+            "analytics_dashboard" => self.handle_analytics_dashboard(call).await,
+            "redact_session" => self.handle_redact_session(call).await,
             _ => Err(MCPError::method_not_found(format!(
                 "Unknown tool: {}",
                 call.name
@@ -226,67 +1119,61 @@ impl ToolHandler for SequentialThinkingToolHandler {
         }
     }
 
-    async fn list_tools(&self, _request: ListToolsRequest) -> MCPResult<ListToolsResponse> {
-        let tools = vec![
-            create_sequential_thinking_tool(),
-            create_export_session_tool(),
-            create_analyze_session_tool(),
-            create_merge_sessions_tool(),
-        ];
-
-        Ok(ListToolsResponse {
-            tools,
-            next_cursor: None,
-        })
+    /// Reject tool calls whose arguments exceed the configured size
+    fn check_request_size(&self, call: &ToolCall) -> MCPResult<()> {
+        let limit = self.server.thinking_config.max_request_bytes;
+        let size = call.arguments.as_ref().map_or(0, json_size);
+        if size > limit {
+            return Err(MCPError::invalid_params(format!(
+                "arguments: {size} bytes exceeds the limit of {limit} bytes"
+            )));
+        }
+        Ok(())
     }
-}
 
-impl SequentialThinkingToolHandler {
-    /// Handle the main sequential thinking tool
-    async fn handle_sequential_thinking(&self, call: ToolCall) -> MCPResult<ToolResult> {
-        let start_time = std::time::Instant::now();
+    /// Check that the caller's API key grants the role a tool requires
+    ///
+    /// The key is read from the `apiKey` argument of the tool call.
+    fn authorize(&self, call: &ToolCall) -> MCPResult<()> {
+        let required = required_role_for_call(call);
 
-        // Extract and validate arguments
-        let args = call.arguments.ok_or_else(|| {
-            MCPError::invalid_params("Missing arguments for sequential_thinking".to_string())
-        })?;
+        let reason = match self.server.security.role_for_key(api_key(call)) {
+            Some(role) if role.allows(required) => return Ok(()),
+            Some(role) => format!(
+                "Role '{role}' is not allowed to call {} (requires '{required}')",
+                call.name
+            ),
+            None => "Missing or unknown API key".to_string(),
+        };
+        Err(MCPError::invalid_request(
+            SequentialThinkingError::permission_denied(reason).to_string(),
+        ))
+    }
 
-        let thought_data = self.extract_thought_data(&args)?;
+    /// Key the usage of a tool call is counted under
+    fn usage_key(&self, call: &ToolCall) -> String {
+        self.server.usage_key(api_key(call))
+    }
 
-        // Process the thought
-        let processed_thought = self
-            .server
-            .process_thought(thought_data)
+    /// Engine of the session named by a tool call's `sessionId`, or of the default session
+    async fn call_engine(&self, call: &ToolCall) -> MCPResult<SessionEngine> {
+        self.server
+            .engine_for(call_session_id(call))
             .await
-            .map_err(|e| MCPError::internal_error(e.to_string()))?;
+            .map_err(|e| MCPError::invalid_params(e.to_string()))
+    }
 
-        // Get current progress and statistics
-        let engine = self.server.engine.read().await;
-        let progress = engine.get_progress();
-        let stats = engine.get_stats();
-        let branches = engine.get_branches();
+    /// Handle starting a named session
+    async fn handle_create_session(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let session_id = call_session_id(&call).to_string();
+        self.server
+            .create_session(session_id.clone())
+            .await
+            .map_err(|e| MCPError::invalid_params(e.to_string()))?;
 
-        // Create response content
         let response_data = serde_json::json!({
-            "thoughtNumber": processed_thought.thought_number,
-            "totalThoughts": processed_thought.total_thoughts,
-            "nextThoughtNeeded": processed_thought.next_thought_needed,
-            "branches": branches.keys().collect::<Vec<_>>(),
-            "thoughtHistoryLength": engine.get_thoughts().len(),
-            "progress": {
-                "currentThought": progress.current_thought,
-                "totalThoughts": progress.total_thoughts,
-                "completedThoughts": progress.completed_thoughts,
-                "progressPercentage": progress.progress_percentage,
-                "isComplete": progress.is_complete()
-            },
-            "stats": {
-                "totalThoughts": stats.total_thoughts,
-                "totalRevisions": stats.total_revisions,
-                "totalBranches": stats.total_branches,
-                "avgProcessingTimeMs": stats.avg_processing_time_ms
-            },
-            "processingTimeMs": start_time.elapsed().as_millis()
+            "sessionId": session_id,
+            "activeSessions": self.server.sessions.read().await.len()
         });
 
         Ok(ToolResult {
@@ -297,42 +1184,153 @@ impl SequentialThinkingToolHandler {
         })
     }
 
-    /// Handle session export
-    async fn handle_export_session(&self, call: ToolCall) -> MCPResult<ToolResult> {
-        let args = call.arguments.ok_or_else(|| {
-            MCPError::invalid_params("Missing arguments for export_session".to_string())
-        })?;
+    /// Handle the main sequential thinking tool
+    async fn handle_sequential_thinking(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let start_time = std::time::Instant::now();
+        let usage_key = self.usage_key(&call);
 
-        let format = args
-            .get("format")
-            .and_then(|v| v.as_str())
-            .unwrap_or("json");
+        // Extract and validate arguments, asking the user for missing ones
+        let mut args = call
+            .arguments
+            .or_else(|| {
+                self.server
+                    .elicitation_enabled()
+                    .then(|| serde_json::json!({}))
+            })
+            .ok_or_else(|| {
+                MCPError::invalid_params("Missing arguments for sequential_thinking".to_string())
+            })?;
+        self.server
+            .elicit_missing_fields(&mut args)
+            .await
+            .map_err(|e| MCPError::invalid_params(e.to_string()))?;
 
-        let engine = self.server.engine.read().await;
-        let thoughts = engine.get_thoughts();
-        let branches = engine.get_branches();
-        let stats = engine.get_stats();
+        validate_arguments("sequential_thinking", Some(&args))?;
+        let mut thought_data =
+            ThoughtData::from_tool_arguments(&args).map_err(MCPError::invalid_params)?;
+        tracing::Span::current().record("thought_number", thought_data.thought_number);
+        let session_id = args
+            .get("sessionId")
+            .and_then(|v| v.as_str())
+            .unwrap_or("default");
+        let engine = self
+            .server
+            .engine_for(session_id)
+            .await
+            .map_err(|e| MCPError::invalid_params(e.to_string()))?;
+        if engine.read().await.is_paused() {
+            return Err(MCPError::invalid_request(
+                "Session is paused; call resume_session before adding thoughts".to_string(),
+            ));
+        }
+        if let Some(strategy) = args.get("strategy").and_then(|v| v.as_str()) {
+            let strategy = strategy.parse().map_err(MCPError::invalid_params)?;
+            engine.write().await.set_strategy(strategy);
+        }
+        if let Some(name) = args.get("metadataSchema").and_then(|v| v.as_str()) {
+            let schema = self
+                .server
+                .thinking_config
+                .metadata_schemas
+                .get(name)
+                .ok_or_else(|| {
+                    MCPError::invalid_params(format!("Unknown metadata schema: {name}"))
+                })?;
+            let schema = MetadataSchema::new(name, schema.clone())
+                .map_err(|errors| MCPError::invalid_params(errors.join("; ")))?;
+            engine.write().await.set_metadata_schema(Some(schema));
+        }
+        if let Some(sampling) = &self.server.thinking_config.sampling {
+            if sampling.is_continue(&thought_data) {
+                thought_data = self
+                    .server
+                    .generate_thought(session_id, thought_data)
+                    .await
+                    .map_err(|e| MCPError::internal_error(e.to_string()))?;
+            }
+        }
 
-        let export_data = serde_json::json!({
-            "session": {
-                "sessionId": engine.session_id(),
-                "thoughts": thoughts,
-                "branches": branches,
-                "stats": stats,
-                "exportedAt": chrono::Utc::now()
-            },
-            "format": format
-        });
+        let length = thought_data.thought.chars().count();
+        let limits = &self.server.thinking_config;
+        if length > limits.max_thought_length {
+            return Err(MCPError::invalid_params(format!(
+                "arguments.thought: must be at most {} characters",
+                limits.max_thought_length
+            )));
+        }
+        if length < limits.min_thought_length {
+            return Err(MCPError::invalid_params(format!(
+                "arguments.thought: must be at least {} characters",
+                limits.min_thought_length
+            )));
+        }
 
-        let content = match format {
-            "json" => serde_json::to_string_pretty(&export_data).unwrap(),
-            "markdown" => self.export_to_markdown(&export_data),
-            _ => {
-                return Err(MCPError::invalid_params(format!(
-                    "Unsupported format: {format}"
-                )))
-            }
-        };
+        // Thoughts and sessions count against the quota once processed
+        let starts_session = thought_data.thought_number == 1
+            && thought_data.branch_from_thought.is_none()
+            && !thought_data.is_revision();
+        let usage = &self.server.usage;
+        let quota_error = |e: SequentialThinkingError| MCPError::invalid_request(e.to_string());
+        usage
+            .check(&usage_key, UsageMetric::Thoughts, 1)
+            .await
+            .map_err(quota_error)?;
+        if starts_session {
+            usage
+                .check(&usage_key, UsageMetric::Sessions, 1)
+                .await
+                .map_err(quota_error)?;
+        }
+
+        // Process the thought
+        let processed_thought = self
+            .server
+            .process_in(session_id, &engine, thought_data)
+            .await
+            .map_err(|e| MCPError::internal_error(e.to_string()))?;
+        usage.record(&usage_key, UsageMetric::Thoughts, 1).await;
+        if starts_session {
+            usage.record(&usage_key, UsageMetric::Sessions, 1).await;
+        }
+
+        // Build the response from the engine state after processing
+        let engine = engine.read().await;
+        let response_data = ThoughtResponse::from_engine(
+            &processed_thought,
+            &engine,
+            start_time.elapsed().as_millis() as u64,
+        );
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response_data).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
+    /// Handle session export
+    async fn handle_export_session(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let usage_key = self.usage_key(&call);
+        let engine = self.call_engine(&call).await?;
+        let args = call.arguments.ok_or_else(|| {
+            MCPError::invalid_params("Missing arguments for export_session".to_string())
+        })?;
+
+        let format = args
+            .get("format")
+            .and_then(|v| v.as_str())
+            .unwrap_or("json");
+
+        let extractor = self.action_extractor()?;
+        let engine = engine.read().await;
+        let content =
+            render_session_export(&engine, &extractor, format).map_err(MCPError::invalid_params)?;
+        self.server
+            .usage
+            .consume(&usage_key, UsageMetric::ExportBytes, content.len() as u64)
+            .await
+            .map_err(|e| MCPError::invalid_request(e.to_string()))?;
 
         Ok(ToolResult {
             content: vec![ToolContent::text(content)],
@@ -340,15 +1338,61 @@ impl SequentialThinkingToolHandler {
         })
     }
 
+    /// Handle polling a session for thoughts recorded after the first `since`
+    ///
+    /// Only the new thoughts are sent. If the session holds fewer thoughts
+    /// than `since`, e.g. after a restart, every thought is sent again.
+    async fn handle_poll_session(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let since = call
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("since"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize;
+
+        let engine = engine.read().await;
+        let count = engine.thought_count();
+        let since = if since > count { 0 } else { since };
+        // Thoughts still in memory are read without loading spilled ones
+        let thoughts = match since.checked_sub(engine.spilled_count()) {
+            Some(in_memory) => engine.get_thoughts()[in_memory..].to_vec(),
+            None => engine
+                .all_thoughts()
+                .get(since..)
+                .map(<[SharedThought]>::to_vec)
+                .unwrap_or_default(),
+        };
+        let response_data = serde_json::json!({
+            "sessionId": call_session_id(&call),
+            "thoughtCount": count,
+            "thoughts": thoughts
+        });
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response_data).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
     /// Handle session analysis
-    async fn handle_analyze_session(&self, _call: ToolCall) -> MCPResult<ToolResult> {
-        let engine = self.server.engine.read().await;
-        let thoughts = engine.get_thoughts();
+    async fn handle_analyze_session(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let engine = engine.read().await;
+        let thoughts = engine.all_thoughts();
         let branches = engine.get_branches();
         let stats = engine.get_stats();
+        let paused = engine.paused_time();
+        let active = engine
+            .get_progress()
+            .elapsed_time
+            .unwrap_or_default()
+            .saturating_sub(paused);
 
         // Perform analysis
-        let analysis = self.analyze_thinking_session(thoughts, branches, stats);
+        let analysis = self.analyze_thinking_session(&thoughts, branches, stats, active, paused);
 
         Ok(ToolResult {
             content: vec![ToolContent::text(
@@ -375,7 +1419,7 @@ impl SequentialThinkingToolHandler {
         for session_id in session_ids {
             if let Some(session_id_str) = session_id.as_str() {
                 if let Some(session) = self.server.get_session(session_id_str).await {
-                    merged_thoughts.extend(session.get_thoughts().to_vec());
+                    merged_thoughts.extend(session.all_thoughts().into_owned());
                     let session_stats = session.get_stats();
                     merged_stats.total_thoughts += session_stats.total_thoughts;
                     merged_stats.total_revisions += session_stats.total_revisions;
@@ -398,162 +1442,858 @@ impl SequentialThinkingToolHandler {
         })
     }
 
-    /// Extract thought data from tool call arguments
-    fn extract_thought_data(&self, args: &serde_json::Value) -> MCPResult<ThoughtData> {
+    /// Handle comparing the metrics of two sessions
+    async fn handle_compare_sessions(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let args = call.arguments.ok_or_else(|| {
+            MCPError::invalid_params("Missing arguments for compare_sessions".to_string())
+        })?;
+
+        let mut analytics = AnalyticsEngine::new()
+            .with_pricing(self.server.pricing.clone())
+            .with_readability(self.server.readability.clone());
+        let session_arg = |field: &str| {
+            args.get(field)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .ok_or_else(|| MCPError::invalid_params(format!("Missing '{field}' field")))
+        };
+        let session_a = session_arg("sessionA")?;
+        let session_b = session_arg("sessionB")?;
+
+        for session_id in [&session_a, &session_b] {
+            let engine = self.server.get_session(session_id).await.ok_or_else(|| {
+                MCPError::invalid_params(format!("Session not found: {session_id}"))
+            })?;
+            analytics.analyze_session(
+                session_id,
+                "",
+                &engine.all_thoughts(),
+                engine.get_stats(),
+                engine.get_progress(),
+            );
+        }
+        let comparison = analytics
+            .compare_sessions(&session_a, &session_b)
+            .map_err(MCPError::internal_error)?;
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&comparison).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
+    /// Handle branch comparison
+    async fn handle_compare_branches(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let args = call.arguments.ok_or_else(|| {
+            MCPError::invalid_params("Missing arguments for compare_branches".to_string())
+        })?;
+
+        let branch_a = args
+            .get("branchA")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| MCPError::invalid_params("Missing 'branchA' field".to_string()))?;
+        let branch_b = args
+            .get("branchB")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| MCPError::invalid_params("Missing 'branchB' field".to_string()))?;
+
+        let engine = engine.read().await;
+        let comparison = engine
+            .compare_branches(branch_a, branch_b)
+            .map_err(MCPError::invalid_params)?;
+
+        let summarize = |summary: &crate::thinking::BranchSummary| {
+            serde_json::json!({
+                "branchId": summary.branch_id,
+                "parentThought": summary.parent_thought,
+                "thoughtCount": summary.thought_count,
+                "totalLength": summary.total_length,
+                "avgThoughtLength": summary.avg_thought_length,
+                "qualityScore": summary.quality_score,
+                "concluded": summary.concluded
+            })
+        };
+
+        let response_data = serde_json::json!({
+            "branchA": summarize(&comparison.branch_a),
+            "branchB": summarize(&comparison.branch_b),
+            "sharedPrefix": comparison.shared_prefix,
+            "divergentA": comparison.divergent_a,
+            "divergentB": comparison.divergent_b,
+            "preferredBranch": comparison.preferred_branch
+        });
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response_data).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
+    /// Handle an in-place thought correction
+    async fn handle_edit_thought(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let args = call.arguments.ok_or_else(|| {
+            MCPError::invalid_params("Missing arguments for edit_thought".to_string())
+        })?;
+
+        let thought_number = args
+            .get("thoughtNumber")
+            .filter(|v| v.is_u64())
+            .ok_or_else(|| MCPError::invalid_params("Missing 'thoughtNumber' field".to_string()))
+            .and_then(|v| {
+                thought_number_field(v, "thoughtNumber").map_err(MCPError::invalid_params)
+            })?;
         let thought = args
             .get("thought")
             .and_then(|v| v.as_str())
             .ok_or_else(|| MCPError::invalid_params("Missing 'thought' field".to_string()))?
             .to_string();
+        let editor = args
+            .get("editor")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
 
-        let thought_number = args
-            .get("thoughtNumber")
-            .and_then(|v| v.as_u64())
-            .ok_or_else(|| MCPError::invalid_params("Missing 'thoughtNumber' field".to_string()))?
-            as u32;
+        let mut engine = engine.write().await;
+        let edit = engine
+            .edit_thought_as(thought_number, thought, editor)
+            .map_err(MCPError::invalid_params)?;
 
-        let total_thoughts = args
-            .get("totalThoughts")
-            .and_then(|v| v.as_u64())
-            .ok_or_else(|| MCPError::invalid_params("Missing 'totalThoughts' field".to_string()))?
-            as u32;
+        let response_data = serde_json::json!({
+            "thoughtNumber": edit.thought_number,
+            "branchId": edit.branch_id,
+            "editor": edit.editor,
+            "editedAt": edit.edited_at,
+            "oldText": edit.old_text,
+            "newText": edit.new_text,
+            "editCount": engine.get_edit_log().len()
+        });
 
-        let next_thought_needed = args
-            .get("nextThoughtNeeded")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(true);
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response_data).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
 
-        let is_revision = args.get("isRevision").and_then(|v| v.as_bool());
-        let revises_thought = args
-            .get("revisesThought")
-            .and_then(|v| v.as_u64())
-            .map(|v| v as u32);
-        let branch_from_thought = args
-            .get("branchFromThought")
-            .and_then(|v| v.as_u64())
-            .map(|v| v as u32);
-        let branch_id = args
-            .get("branchId")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
-        let needs_more_thoughts = args.get("needsMoreThoughts").and_then(|v| v.as_bool());
-
-        Ok(ThoughtData {
-            thought,
-            thought_number,
-            total_thoughts,
-            next_thought_needed,
-            is_revision,
-            revises_thought,
-            branch_from_thought,
-            branch_id,
-            needs_more_thoughts,
-            timestamp: Some(chrono::Utc::now()),
-            metadata: None,
+    /// Handle estimating the token usage and cost of the session
+    async fn handle_cost_report(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let tags: Vec<String> = call
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("tags"))
+            .and_then(|v| v.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|tag| tag.as_str())
+                    .map(|tag| tag.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let engine = engine.read().await;
+        let report =
+            CostReport::build(&self.server.pricing, [(&tags[..], &*engine.all_thoughts())]);
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&report).unwrap(),
+            )],
+            is_error: Some(false),
         })
     }
 
-    /// Export session data to Markdown format
-    fn export_to_markdown(&self, data: &serde_json::Value) -> String {
-        let session = &data["session"];
-        let thoughts = &session["thoughts"];
+    /// Handle extracting the session's code blocks, one content item per block
+    async fn handle_extract_code(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let language = call
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("language"))
+            .and_then(|v| v.as_str());
 
-        let mut markdown = String::new();
-        markdown.push_str("# Sequential Thinking Session\n\n");
+        let engine = engine.read().await;
+        let content = code::extract_code(&engine.all_thoughts())
+            .into_iter()
+            .filter(|block| {
+                language.is_none_or(|language| {
+                    block
+                        .language
+                        .as_deref()
+                        .is_some_and(|l| l.eq_ignore_ascii_case(language))
+                })
+            })
+            .map(|block| ToolContent::text(serde_json::to_string_pretty(&block).unwrap()))
+            .collect();
 
-        if let Some(session_id) = session["sessionId"].as_str() {
-            markdown.push_str(&format!("**Session ID:** {session_id}\n\n"));
-        }
+        Ok(ToolResult {
+            content,
+            is_error: Some(false),
+        })
+    }
+
+    /// Handle extracting the session's action items
+    async fn handle_extract_actions(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let extractor = self.action_extractor()?;
+        let engine = engine.read().await;
+        let items = extractor.extract(&engine.all_thoughts());
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&serde_json::json!({ "actionItems": items })).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
 
-        markdown.push_str("## Thoughts\n\n");
+    /// Handle producing a copy of the session with personal data redacted
+    async fn handle_redact_session(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let kinds = match call.arguments.as_ref().and_then(|args| args.get("kinds")) {
+            Some(kinds) => kinds
+                .as_array()
+                .ok_or_else(|| MCPError::invalid_params("'kinds' must be an array".to_string()))?
+                .iter()
+                .map(|kind| {
+                    kind.as_str()
+                        .unwrap_or_default()
+                        .parse::<PiiKind>()
+                        .map_err(MCPError::invalid_params)
+                })
+                .collect::<MCPResult<Vec<_>>>()?,
+            None => PiiKind::ALL.to_vec(),
+        };
 
-        if let Some(thoughts_array) = thoughts.as_array() {
-            for thought in thoughts_array.iter() {
-                let thought_number = thought["thoughtNumber"].as_u64().unwrap_or(0);
-                let total_thoughts = thought["totalThoughts"].as_u64().unwrap_or(0);
-                let thought_content = thought["thought"].as_str().unwrap_or("");
+        let engine = engine.read().await;
+        let session = PiiScanner::new(&kinds).redact_session(&engine.all_thoughts());
+        let result = serde_json::json!({
+            "thoughts": session.thoughts,
+            "findings": session.findings,
+            "counts": session.counts(),
+        });
 
-                markdown.push_str(&format!(
-                    "### Thought {thought_number}/{total_thoughts}\n\n"
-                ));
-                markdown.push_str(&format!("{thought_content}\n\n"));
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&result).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
 
-                if thought["isRevision"].as_bool().unwrap_or(false) {
-                    markdown.push_str("*This thought revises a previous thought*\n\n");
-                }
+    /// Handle creating tracker issues from action items or the concluded session
+    async fn handle_create_issues(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let args = call.arguments.unwrap_or_default();
+        let integrations = &self.server.integrations;
+        let tracker = integrations
+            .tracker(args.get("tracker").and_then(|v| v.as_str()))
+            .map_err(MCPError::invalid_params)?;
+        let dry_run = args
+            .get("dryRun")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(integrations.dry_run);
 
-                if thought["branchFromThought"].is_number() {
-                    markdown.push_str("*This thought is a branch*\n\n");
+        let extractor = self.action_extractor()?;
+        let drafts = {
+            let engine = engine.read().await;
+            let thoughts = engine.all_thoughts();
+            let items = extractor.extract(&thoughts);
+            match args
+                .get("source")
+                .and_then(|v| v.as_str())
+                .unwrap_or("actions")
+            {
+                "actions" => items
+                    .iter()
+                    .map(|item| IssueDraft::from_action(item, &thoughts, engine.session_id()))
+                    .collect(),
+                "session" => vec![
+                    IssueDraft::from_session(&thoughts, &items, engine.session_id())
+                        .map_err(MCPError::invalid_params)?,
+                ],
+                source => {
+                    return Err(MCPError::invalid_params(format!(
+                        "Unsupported source: {source}"
+                    )))
                 }
             }
-        }
+        };
 
-        markdown.push_str("## Statistics\n\n");
-        if let Some(stats) = session.get("stats") {
-            markdown.push_str(&format!("- Total Thoughts: {}\n", stats["totalThoughts"]));
-            markdown.push_str(&format!("- Total Revisions: {}\n", stats["totalRevisions"]));
-            markdown.push_str(&format!("- Total Branches: {}\n", stats["totalBranches"]));
-            markdown.push_str(&format!(
-                "- Average Processing Time: {:.2}ms\n",
-                stats["avgProcessingTimeMs"]
+        let issues = create_issues(tracker.as_ref(), &drafts, dry_run)
+            .await
+            .map_err(|e| MCPError::internal_error(format!("Failed to create issues: {e}")))?;
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "dryRun": dry_run,
+                    "issues": issues
+                }))
+                .unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
+    /// Action extractor for the configured patterns
+    fn action_extractor(&self) -> MCPResult<ActionExtractor> {
+        ActionExtractor::new(&self.server.thinking_config.actions)
+            .map_err(|e| MCPError::internal_error(format!("Invalid action patterns: {e}")))
+    }
+
+    /// Handle exporting the session's decisions as an ADR-style log
+    async fn handle_export_decisions(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let format = call
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("format"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("markdown");
+
+        let engine = engine.read().await;
+        let log = DecisionLog::from_thoughts(&engine.all_thoughts());
+        let content = match format {
+            "markdown" => log.to_markdown(engine.session_id()),
+            "json" => serde_json::to_string_pretty(&log).unwrap(),
+            _ => {
+                return Err(MCPError::invalid_params(format!(
+                    "Unsupported format: {format}"
+                )))
+            }
+        };
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(content)],
+            is_error: Some(false),
+        })
+    }
+
+    /// Handle replaying the session timeline step by step
+    async fn handle_replay_session(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let args = call.arguments.unwrap_or_default();
+
+        let speed = args.get("speed").and_then(|v| v.as_f64()).unwrap_or(1.0);
+        if speed.is_nan() || speed < 0.0 {
+            return Err(MCPError::invalid_params(
+                "'speed' must be zero or positive".to_string(),
             ));
         }
+        let from_sequence = args
+            .get("fromSequence")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize;
+        let limit = args
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|l| l as usize)
+            .unwrap_or(usize::MAX);
+
+        let engine = engine.read().await;
+        let events: Vec<ReplayEvent> = engine.replay().collect();
+        let total_duration_ms = events.last().map(|e| e.elapsed_ms).unwrap_or(0);
+        let delays = replay_delays(&events, speed);
+
+        let steps: Vec<serde_json::Value> = events
+            .iter()
+            .zip(delays)
+            .skip(from_sequence)
+            .take(limit)
+            .map(|(event, delay_ms)| {
+                let mut step = serde_json::to_value(event).unwrap();
+                step["delayMs"] = serde_json::json!(delay_ms);
+                step
+            })
+            .collect();
+        let next_sequence = from_sequence.saturating_add(steps.len());
 
-        markdown
+        let response_data = serde_json::json!({
+            "sessionId": engine.session_id(),
+            "speed": speed,
+            "eventCount": events.len(),
+            "totalDurationMs": total_duration_ms,
+            "events": steps,
+            "nextSequence": (next_sequence < events.len()).then_some(next_sequence)
+        });
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response_data).unwrap(),
+            )],
+            is_error: Some(false),
+        })
     }
 
-    /// Analyze thinking session
-    fn analyze_thinking_session(
-        &self,
-        thoughts: &[ThoughtData],
-        branches: &std::collections::HashMap<String, crate::thinking::ThoughtBranch>,
-        stats: &ThinkingStats,
-    ) -> serde_json::Value {
-        let total_thoughts = thoughts.len();
-        let revisions = thoughts.iter().filter(|t| t.is_revision()).count();
-        let branch_thoughts = thoughts.iter().filter(|t| t.is_branch()).count();
+    /// Handle cancelling in-flight thought processing
+    async fn handle_cancel_session(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let reason = call
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("reason"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
 
-        let avg_thought_length = if total_thoughts > 0 {
-            thoughts.iter().map(|t| t.thought.len()).sum::<usize>() as f64 / total_thoughts as f64
-        } else {
-            0.0
-        };
+        self.server.cancel_session(call_session_id(&call)).await;
+        info!(
+            "Cancelled in-flight thought processing of session {}{}",
+            call_session_id(&call),
+            reason
+                .as_deref()
+                .map(|r| format!(": {r}"))
+                .unwrap_or_default()
+        );
 
-        serde_json::json!({
-            "analysis": {
-                "totalThoughts": total_thoughts,
-                "revisions": revisions,
-                "branchThoughts": branch_thoughts,
-                "activeBranches": branches.len(),
-                "avgThoughtLength": avg_thought_length,
-                "revisionRate": if total_thoughts > 0 { revisions as f64 / total_thoughts as f64 } else { 0.0 },
-                "branchRate": if total_thoughts > 0 { branch_thoughts as f64 / total_thoughts as f64 } else { 0.0 },
-                "processingStats": stats
-            }
+        let engine = engine.read().await;
+        let response_data = serde_json::json!({
+            "sessionId": engine.session_id(),
+            "cancelled": true,
+            "reason": reason,
+            "thoughtCount": engine.thought_count()
+        });
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response_data).unwrap(),
+            )],
+            is_error: Some(false),
         })
     }
-}
 
-/// Create the main sequential thinking tool definition
-fn create_sequential_thinking_tool() -> Tool {
-    Tool {
-        name: "sequential_thinking".to_string(),
-        description: "A detailed tool for dynamic and reflective problem-solving through thoughts.
-This tool helps analyze problems through a flexible thinking process that can adapt and evolve.
-Each thought can build on, question, or revise previous insights as understanding deepens.
+    /// Handle pausing the current session
+    async fn handle_pause_session(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let reason = call
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("reason"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
 
-When to use this tool:
-- Breaking down complex problems into steps
-- Planning and design with room for revision
-- Analysis that might need course correction
-- Problems where the full scope might not be clear initially
-- Problems that require a multi-step solution
-- Tasks that need to maintain context over multiple steps
-- Situations where irrelevant information needs to be filtered out
+        let mut engine = engine.write().await;
+        engine.pause().map_err(MCPError::invalid_request)?;
+        info!(
+            "Paused thinking session{}",
+            reason
+                .as_deref()
+                .map(|r| format!(": {r}"))
+                .unwrap_or_default()
+        );
 
-Key features:
-- You can adjust total_thoughts up or down as you progress
-- You can question or revise previous thoughts
+        let response_data = serde_json::json!({
+            "sessionId": engine.session_id(),
+            "paused": true,
+            "reason": reason,
+            "thoughtCount": engine.thought_count(),
+            "totalPausedMs": engine.get_stats().total_paused_time_ms
+        });
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response_data).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
+    /// Handle attaching a reviewer comment to a thought
+    async fn handle_annotate_thought(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let args = call.arguments.ok_or_else(|| {
+            MCPError::invalid_params("Missing arguments for annotate_thought".to_string())
+        })?;
+
+        let thought_number = args
+            .get("thoughtNumber")
+            .filter(|v| v.is_u64())
+            .ok_or_else(|| MCPError::invalid_params("Missing 'thoughtNumber' field".to_string()))
+            .and_then(|v| {
+                thought_number_field(v, "thoughtNumber").map_err(MCPError::invalid_params)
+            })?;
+        let author = args
+            .get("author")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| MCPError::invalid_params("Missing 'author' field".to_string()))?
+            .to_string();
+        let body = args
+            .get("body")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| MCPError::invalid_params("Missing 'body' field".to_string()))?
+            .to_string();
+
+        let mut annotation = Annotation::new(thought_number, author, body);
+        if let Some(branch_id) = args.get("branchId").and_then(|v| v.as_str()) {
+            annotation = annotation.on_branch(branch_id.to_string());
+        }
+
+        let mut engine = engine.write().await;
+        let annotation = engine
+            .annotate_thought(annotation)
+            .map_err(MCPError::invalid_params)?;
+
+        let response_data = serde_json::json!({
+            "annotation": annotation,
+            "unresolvedCount": engine.get_annotations().unresolved().len()
+        });
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response_data).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
+    /// Handle resolving a reviewer comment
+    async fn handle_resolve_annotation(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let args = call.arguments.ok_or_else(|| {
+            MCPError::invalid_params("Missing arguments for resolve_annotation".to_string())
+        })?;
+
+        let annotation_id = args
+            .get("annotationId")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| MCPError::invalid_params("Missing 'annotationId' field".to_string()))?;
+
+        let mut engine = engine.write().await;
+        let annotation = engine
+            .resolve_annotation(annotation_id)
+            .map_err(MCPError::invalid_params)?;
+
+        let response_data = serde_json::json!({
+            "annotation": annotation,
+            "unresolvedCount": engine.get_annotations().unresolved().len()
+        });
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response_data).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
+    /// Handle taking a checkpoint of the current session
+    async fn handle_checkpoint_session(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let engine = engine.read().await;
+        let checkpoint = engine.checkpoint();
+
+        let response_data = serde_json::json!({
+            "sessionId": checkpoint.session_id,
+            "thoughtCount": checkpoint.thoughts.len(),
+            "checkpoint": checkpoint
+        });
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response_data).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
+    /// Handle resuming the current session from a checkpoint, or after a pause
+    async fn handle_resume_session(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let engine = self.call_engine(&call).await?;
+        let checkpoint_value = call
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("checkpoint"))
+            .cloned();
+
+        let mut engine = engine.write().await;
+        let paused_for = match checkpoint_value {
+            Some(checkpoint_value) => {
+                let checkpoint: EngineCheckpoint = serde_json::from_value(checkpoint_value)
+                    .map_err(|e| MCPError::invalid_params(format!("Invalid checkpoint: {e}")))?;
+                engine
+                    .resume_from(checkpoint)
+                    .map_err(MCPError::invalid_params)?;
+                // Cached results refer to thoughts the resumed session may not have
+                self.server
+                    .idempotency
+                    .write()
+                    .await
+                    .remove(call_session_id(&call));
+                None
+            }
+            None => Some(engine.resume().map_err(MCPError::invalid_request)?),
+        };
+
+        let response_data = serde_json::json!({
+            "sessionId": engine.session_id(),
+            "thoughtHistoryLength": engine.thought_count(),
+            "branches": engine.get_branches().keys().collect::<Vec<_>>(),
+            "progress": ResponseProgress::from(engine.get_progress()),
+            "pausedForMs": paused_for.map(|paused_for| paused_for.as_millis() as u64),
+            "totalPausedMs": engine.get_stats().total_paused_time_ms
+        });
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response_data).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
+    /// Handle reporting this month's usage of every API key
+    async fn handle_usage_report(&self) -> MCPResult<ToolResult> {
+        let report = self.server.usage_report().await;
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&report).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
+    /// Handle reporting daily aggregates of completed sessions
+    async fn handle_analytics_dashboard(&self, call: ToolCall) -> MCPResult<ToolResult> {
+        let days = match call.arguments.as_ref().and_then(|args| args.get("days")) {
+            None | Some(serde_json::Value::Null) => DEFAULT_DASHBOARD_DAYS,
+            Some(days) => days
+                .as_u64()
+                .and_then(|days| u32::try_from(days).ok())
+                .ok_or_else(|| {
+                    MCPError::invalid_params("'days' must be a positive integer".to_string())
+                })?,
+        };
+        let dashboard = self
+            .server
+            .analytics_dashboard(days)
+            .await
+            .map_err(|e| MCPError::invalid_params(e.to_string()))?;
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&dashboard).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
+    /// Handle the version and compatibility handshake
+    async fn handle_get_server_info(&self) -> MCPResult<ToolResult> {
+        let info = &self.server.info;
+        let capabilities = &self.server.capabilities;
+        let limits = &self.server.thinking_config;
+
+        let response_data = serde_json::json!({
+            "name": info.name,
+            "version": info.version,
+            "capabilities": {
+                "tools": capabilities.tools.is_some(),
+                "resources": capabilities.resources.is_some(),
+                "prompts": capabilities.prompts.is_some(),
+                "logging": capabilities.logging.is_some(),
+                "completion": capabilities.completion.is_some()
+            },
+            "features": {
+                "analytics": cfg!(feature = "analytics"),
+                "export": cfg!(feature = "export"),
+                "httpTransport": cfg!(feature = "http-transport"),
+                "stdioTransport": cfg!(feature = "stdio-transport")
+            },
+            "tools": tool_definitions()
+                .into_iter()
+                .map(|tool| tool.name)
+                .collect::<Vec<_>>(),
+            "limits": {
+                "maxThoughtsPerSession": limits.max_thoughts_per_session,
+                "maxBranchesPerSession": limits.max_branches_per_session,
+                "maxThoughtLength": limits.max_thought_length,
+                "minThoughtLength": limits.min_thought_length,
+                "maxRequestBytes": limits.max_request_bytes,
+                "sessionTimeoutSeconds": limits.session_timeout_seconds,
+                "thoughtTimeoutSeconds": limits.thought_timeout_seconds
+            },
+            "exportFormats": SUPPORTED_EXPORT_FORMATS,
+            "apiKeyRequired": self.server.security.api_key_validation,
+            "continueMarker": limits
+                .sampling
+                .as_ref()
+                .filter(|_| self.server.sampler.is_some())
+                .map(|sampling| &sampling.continue_marker)
+        });
+
+        Ok(ToolResult {
+            content: vec![ToolContent::text(
+                serde_json::to_string_pretty(&response_data).unwrap(),
+            )],
+            is_error: Some(false),
+        })
+    }
+
+    /// Analyze thinking session
+    fn analyze_thinking_session(
+        &self,
+        thoughts: &[SharedThought],
+        branches: &std::collections::HashMap<String, crate::thinking::ThoughtBranch>,
+        stats: &ThinkingStats,
+        active: std::time::Duration,
+        paused: std::time::Duration,
+    ) -> serde_json::Value {
+        let total_thoughts = thoughts.len();
+        let revisions = thoughts.iter().filter(|t| t.is_revision()).count();
+        let branch_thoughts = thoughts.iter().filter(|t| t.is_branch()).count();
+
+        let avg_thought_length = if total_thoughts > 0 {
+            thoughts.iter().map(|t| t.thought.len()).sum::<usize>() as f64 / total_thoughts as f64
+        } else {
+            0.0
+        };
+        let branch_report = AnalyticsEngine::new()
+            .with_readability(self.server.readability.clone())
+            .branch_breakdown(thoughts);
+
+        serde_json::json!({
+            "analysis": {
+                "totalThoughts": total_thoughts,
+                "revisions": revisions,
+                "branchThoughts": branch_thoughts,
+                "activeBranches": branches.len(),
+                "avgThoughtLength": avg_thought_length,
+                "revisionRate": if total_thoughts > 0 { revisions as f64 / total_thoughts as f64 } else { 0.0 },
+                "branchRate": if total_thoughts > 0 { branch_thoughts as f64 / total_thoughts as f64 } else { 0.0 },
+                "activeTimeMs": active.as_millis() as u64,
+                "pausedTimeMs": paused.as_millis() as u64,
+                "processingStats": stats,
+                "branchReport": branch_report
+            }
+        })
+    }
+}
+
+/// Render a session as a `json` or `markdown` export
+fn render_session_export(
+    engine: &ThinkingEngine,
+    extractor: &ActionExtractor,
+    format: &str,
+) -> Result<String, String> {
+    let thoughts = engine.all_thoughts();
+    let export_data = serde_json::json!({
+        "session": {
+            "sessionId": engine.session_id(),
+            "thoughts": thoughts,
+            "branches": engine.get_branches(),
+            "stats": engine.get_stats(),
+            "annotations": engine.get_annotations(),
+            "actionItems": extractor.extract(&thoughts),
+            "branchReport": AnalyticsEngine::new().branch_breakdown(&thoughts),
+            "lastThoughtAt": thoughts.iter().filter_map(|thought| thought.timestamp).max()
+        },
+        "format": format
+    });
+
+    match format {
+        "json" => Ok(serde_json::to_string_pretty(&export_data).unwrap()),
+        "markdown" => Ok(export_to_markdown(&export_data)),
+        _ => Err(format!("Unsupported format: {format}")),
+    }
+}
+
+/// Export session data to Markdown format
+fn export_to_markdown(data: &serde_json::Value) -> String {
+    let session = &data["session"];
+    let thoughts = &session["thoughts"];
+
+    let mut markdown = String::new();
+    markdown.push_str("# Sequential Thinking Session\n\n");
+
+    if let Some(session_id) = session["sessionId"].as_str() {
+        markdown.push_str(&format!("**Session ID:** {session_id}\n\n"));
+    }
+
+    markdown.push_str("## Thoughts\n\n");
+
+    if let Some(thoughts_array) = thoughts.as_array() {
+        for thought in thoughts_array.iter() {
+            let thought_number = thought["thoughtNumber"].as_u64().unwrap_or(0);
+            let total_thoughts = thought["totalThoughts"].as_u64().unwrap_or(0);
+            let thought_content = thought["thought"].as_str().unwrap_or("");
+
+            markdown.push_str(&format!(
+                "### Thought {thought_number}/{total_thoughts}\n\n"
+            ));
+            markdown.push_str(&format!("{}\n\n", code::normalize_fences(thought_content)));
+
+            if thought["isRevision"].as_bool().unwrap_or(false) {
+                markdown.push_str("*This thought revises a previous thought*\n\n");
+            }
+
+            if thought["branchFromThought"].is_number() {
+                markdown.push_str("*This thought is a branch*\n\n");
+            }
+
+            if let Some(annotations) = session["annotations"].as_array() {
+                for annotation in annotations.iter().filter(|a| {
+                    a["thought_number"] == thought["thought_number"]
+                        && a["branch_id"] == thought["branch_id"]
+                }) {
+                    markdown.push_str(&format!(
+                        "> 💬 **{}**{}: {}\n\n",
+                        annotation["author"].as_str().unwrap_or(""),
+                        if annotation["resolved"].as_bool().unwrap_or(false) {
+                            " (resolved)"
+                        } else {
+                            ""
+                        },
+                        annotation["body"].as_str().unwrap_or("")
+                    ));
+                }
+            }
+        }
+    }
+
+    let action_items: Vec<actions::ActionItem> =
+        serde_json::from_value(session["actionItems"].clone()).unwrap_or_default();
+    markdown.push_str(&actions::markdown_checklist(&action_items));
+
+    markdown.push_str("## Statistics\n\n");
+    if let Some(stats) = session.get("stats") {
+        markdown.push_str(&format!("- Total Thoughts: {}\n", stats["totalThoughts"]));
+        markdown.push_str(&format!("- Total Revisions: {}\n", stats["totalRevisions"]));
+        markdown.push_str(&format!("- Total Branches: {}\n", stats["totalBranches"]));
+        markdown.push_str(&format!(
+            "- Average Processing Time: {:.2}ms\n",
+            stats["avgProcessingTimeMs"]
+        ));
+    }
+
+    markdown
+}
+
+/// Create the main sequential thinking tool definition
+fn create_sequential_thinking_tool() -> Tool {
+    Tool {
+        name: "sequential_thinking".to_string(),
+        description: "A detailed tool for dynamic and reflective problem-solving through thoughts.
+This tool helps analyze problems through a flexible thinking process that can adapt and evolve.
+Each thought can build on, question, or revise previous insights as understanding deepens.
+
+When to use this tool:
+- Breaking down complex problems into steps
+- Planning and design with room for revision
+- Analysis that might need course correction
+- Problems where the full scope might not be clear initially
+- Problems that require a multi-step solution
+- Tasks that need to maintain context over multiple steps
+- Situations where irrelevant information needs to be filtered out
+
+Key features:
+- You can adjust total_thoughts up or down as you progress
+- You can question or revise previous thoughts
 - You can add more thoughts even after reaching what seemed like the end
 - You can express uncertainty and explore alternative approaches
 - Not every thought needs to build linearly - you can branch or backtrack
@@ -604,12 +2344,70 @@ Key features:
                 "needsMoreThoughts": {
                     "type": "boolean",
                     "description": "If more thoughts are needed"
+                },
+                "dependsOn": {
+                    "type": "array",
+                    "items": {
+                        "type": "integer",
+                        "minimum": 1
+                    },
+                    "description": "Thought numbers this thought builds on"
+                },
+                "author": {
+                    "type": "string",
+                    "description": "Agent or human contributing this thought (alias: agentId)"
+                },
+                "agentId": {
+                    "type": "string",
+                    "description": "Alias of author"
+                },
+                "idempotencyKey": {
+                    "type": "string",
+                    "description": "Unique key for this submission; a retry with the same key returns the original result instead of recording the thought again"
+                },
+                "strategy": {
+                    "type": "string",
+                    "enum": ["linear", "tree_of_thought", "dialectic", "five_whys", "premortem_analysis"],
+                    "description": "Thinking strategy for the session, usually sent with the first thought; responses then carry guidance for the next step"
+                },
+                "metadata": {
+                    "type": "object",
+                    "description": "Structured data attached to this thought, such as confidence or token counts"
+                },
+                "attachments": {
+                    "type": "array",
+                    "description": "Content attached to this thought",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "type": { "type": "string", "enum": ["code", "file", "image", "url"] },
+                            "content": { "type": "string", "description": "Code text (code)" },
+                            "language": { "type": "string", "description": "Code language (code)" },
+                            "filename": { "type": "string", "description": "Source file of the code (code)" },
+                            "path": { "type": "string", "description": "File path (file)" },
+                            "description": { "type": "string", "description": "What the file holds (file)" },
+                            "mime_type": { "type": "string", "description": "Image media type such as image/png (image)" },
+                            "data": { "type": "string", "description": "Base64-encoded image data (image)" },
+                            "alt": { "type": "string", "description": "Image description (image)" },
+                            "url": { "type": "string", "description": "HTTP or HTTPS address (url)" },
+                            "title": { "type": "string", "description": "Link text (url)" }
+                        },
+                        "required": ["type"]
+                    }
+                },
+                "metadataSchema": {
+                    "type": "string",
+                    "description": "Name of a configured metadata schema that this and later thoughts' metadata must match"
+                },
+                "sessionId": {
+                    "type": "string",
+                    "description": "Session created by the host to record this thought in, instead of the default session; sessions are processed independently of each other"
                 }
             },
             "required": ["thought", "nextThoughtNeeded", "thoughtNumber", "totalThoughts"]
         }),
         annotations: None,
-        output_schema: None,
+        output_schema: Some(sequential_thinking_output_schema()),
     }
 }
 
@@ -623,14 +2421,14 @@ fn create_export_session_tool() -> Tool {
             "properties": {
                 "format": {
                     "type": "string",
-                    "enum": ["json", "markdown"],
+                    "enum": SUPPORTED_EXPORT_FORMATS,
                     "description": "Export format",
                     "default": "json"
                 }
             }
         }),
         annotations: None,
-        output_schema: None,
+        output_schema: Some(export_session_output_schema()),
     }
 }
 
@@ -644,7 +2442,7 @@ fn create_analyze_session_tool() -> Tool {
             "properties": {}
         }),
         annotations: None,
-        output_schema: None,
+        output_schema: Some(analyze_session_output_schema()),
     }
 }
 
@@ -667,27 +2465,3062 @@ fn create_merge_sessions_tool() -> Tool {
             "required": ["sessionIds"]
         }),
         annotations: None,
-        output_schema: None,
+        output_schema: Some(merge_sessions_output_schema()),
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// JSON schema of the sequential_thinking response (see `ThoughtResponse`)
+fn sequential_thinking_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "thoughtNumber": { "type": "integer", "minimum": 1 },
+            "totalThoughts": { "type": "integer", "minimum": 1 },
+            "nextThoughtNeeded": { "type": "boolean" },
+            "branches": { "type": "array", "items": { "type": "string" } },
+            "thoughtHistoryLength": { "type": "integer", "minimum": 0 },
+            "progress": thinking_progress_schema(),
+            "stats": {
+                "type": "object",
+                "properties": {
+                    "totalThoughts": { "type": "integer" },
+                    "totalRevisions": { "type": "integer" },
+                    "totalBranches": { "type": "integer" },
+                    "avgProcessingTimeMs": { "type": "number" }
+                },
+                "required": ["totalThoughts", "totalRevisions", "totalBranches", "avgProcessingTimeMs"]
+            },
+            "processingTimeMs": { "type": "integer", "minimum": 0 },
+            "strategy": {
+                "type": "string",
+                "enum": ["linear", "tree_of_thought", "dialectic", "five_whys", "premortem_analysis"]
+            },
+            "guidance": { "type": "string" }
+        },
+        "required": [
+            "thoughtNumber",
+            "totalThoughts",
+            "nextThoughtNeeded",
+            "branches",
+            "thoughtHistoryLength",
+            "progress",
+            "stats",
+            "processingTimeMs"
+        ]
+    })
+}
 
-    #[test]
-    fn test_server_creation() {
-        let server = SequentialThinkingServer::new();
-        assert_eq!(server.info().name, "ultrafast-sequential-thinking");
-        assert!(server.capabilities().tools.is_some());
-    }
+/// JSON schema of the export_session response for the json format
+///
+/// The markdown format returns a markdown document instead.
+fn export_session_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "session": {
+                "type": "object",
+                "properties": {
+                    "sessionId": { "type": ["string", "null"] },
+                    "thoughts": { "type": "array", "items": { "type": "object" } },
+                    "branches": { "type": "object" },
+                    "stats": thinking_stats_schema(),
+                    "annotations": { "type": "array", "items": { "type": "object" } },
+                    "actionItems": { "type": "array", "items": { "type": "object" } },
+                    "branchReport": branch_report_schema(),
+                    "lastThoughtAt": { "type": ["string", "null"], "format": "date-time" }
+                },
+                "required": ["sessionId", "thoughts", "branches", "stats", "lastThoughtAt"]
+            },
+            "format": { "type": "string", "enum": SUPPORTED_EXPORT_FORMATS }
+        },
+        "required": ["session", "format"]
+    })
+}
 
-    #[test]
-    fn test_tool_definitions() {
-        let sequential_tool = create_sequential_thinking_tool();
-        assert_eq!(sequential_tool.name, "sequential_thinking");
-        let export_tool = create_export_session_tool();
-        assert_eq!(export_tool.name, "export_session");
+/// JSON schema of the analyze_session response
+fn analyze_session_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "analysis": {
+                "type": "object",
+                "properties": {
+                    "totalThoughts": { "type": "integer" },
+                    "revisions": { "type": "integer" },
+                    "branchThoughts": { "type": "integer" },
+                    "activeBranches": { "type": "integer" },
+                    "avgThoughtLength": { "type": "number" },
+                    "revisionRate": { "type": "number" },
+                    "branchRate": { "type": "number" },
+                    "activeTimeMs": { "type": "integer", "minimum": 0 },
+                    "pausedTimeMs": { "type": "integer", "minimum": 0 },
+                    "processingStats": thinking_stats_schema(),
+                    "branchReport": branch_report_schema()
+                },
+                "required": [
+                    "totalThoughts",
+                    "revisions",
+                    "branchThoughts",
+                    "activeBranches",
+                    "avgThoughtLength",
+                    "revisionRate",
+                    "branchRate",
+                    "activeTimeMs",
+                    "pausedTimeMs",
+                    "processingStats"
+                ]
+            }
+        },
+        "required": ["analysis"]
+    })
+}
+
+/// JSON schema of the merge_sessions response
+fn merge_sessions_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "mergedThoughts": { "type": "integer", "minimum": 0 },
+            "mergedStats": thinking_stats_schema(),
+            "sessionIds": { "type": "array", "items": { "type": "string" } }
+        },
+        "required": ["mergedThoughts", "mergedStats", "sessionIds"]
+    })
+}
+
+/// JSON schema of the compare_sessions response (see `SessionComparison`)
+fn compare_sessions_output_schema() -> serde_json::Value {
+    let style = serde_json::json!({
+        "type": "string",
+        "enum": ["Linear", "Iterative", "Exploratory", "Analytical", "Creative", "Mixed"]
+    });
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "session_a": { "type": "string" },
+            "session_b": { "type": "string" },
+            "metrics": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "metric": { "type": "string" },
+                        "a": { "type": "number" },
+                        "b": { "type": "number" },
+                        "difference": { "type": "number" },
+                        "percent_change": { "type": ["number", "null"] },
+                        "preference": { "type": "string", "enum": ["higher", "lower", "neutral"] },
+                        "better": { "type": ["string", "null"] }
+                    },
+                    "required": ["metric", "a", "b", "difference", "percent_change", "preference", "better"]
+                }
+            },
+            "style_a": style,
+            "style_b": style,
+            "wins": {
+                "type": "array",
+                "items": { "type": "integer", "minimum": 0 },
+                "minItems": 2,
+                "maxItems": 2
+            },
+            "summary": { "type": "string" }
+        },
+        "required": ["session_a", "session_b", "metrics", "style_a", "style_b", "wins", "summary"]
+    })
+}
+
+/// JSON schema of the compare_branches response
+fn compare_branches_output_schema() -> serde_json::Value {
+    let summary = serde_json::json!({
+        "type": "object",
+        "properties": {
+            "branchId": { "type": "string" },
+            "parentThought": { "type": "integer" },
+            "thoughtCount": { "type": "integer" },
+            "totalLength": { "type": "integer" },
+            "avgThoughtLength": { "type": "number" },
+            "qualityScore": { "type": "number" },
+            "concluded": { "type": "boolean" }
+        },
+        "required": [
+            "branchId",
+            "parentThought",
+            "thoughtCount",
+            "totalLength",
+            "avgThoughtLength",
+            "qualityScore",
+            "concluded"
+        ]
+    });
+    let thought_numbers = serde_json::json!({ "type": "array", "items": { "type": "integer" } });
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "branchA": summary,
+            "branchB": summary,
+            "sharedPrefix": thought_numbers,
+            "divergentA": thought_numbers,
+            "divergentB": thought_numbers,
+            "preferredBranch": { "type": ["string", "null"] }
+        },
+        "required": ["branchA", "branchB", "sharedPrefix", "divergentA", "divergentB", "preferredBranch"]
+    })
+}
+
+/// JSON schema of the edit_thought response
+fn edit_thought_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "thoughtNumber": { "type": "integer", "minimum": 1 },
+            "branchId": { "type": ["string", "null"] },
+            "editor": { "type": ["string", "null"] },
+            "editedAt": { "type": "string", "format": "date-time" },
+            "oldText": { "type": "string" },
+            "newText": { "type": "string" },
+            "editCount": { "type": "integer", "minimum": 1 }
+        },
+        "required": ["thoughtNumber", "branchId", "editor", "editedAt", "oldText", "newText", "editCount"]
+    })
+}
+
+/// JSON schema of the cost_report response (see `CostReport`)
+fn cost_report_output_schema() -> serde_json::Value {
+    let bucket = serde_json::json!({
+        "type": "object",
+        "properties": {
+            "key": { "type": "string" },
+            "thought_count": { "type": "integer" },
+            "estimated_thoughts": { "type": "integer" },
+            "input_tokens": { "type": "integer" },
+            "output_tokens": { "type": "integer" },
+            "cost": { "type": "number" }
+        },
+        "required": ["key", "thought_count", "estimated_thoughts", "input_tokens", "output_tokens", "cost"]
+    });
+    let buckets = serde_json::json!({ "type": "array", "items": bucket });
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "currency": { "type": "string" },
+            "total": bucket,
+            "by_model": buckets,
+            "by_tag": buckets,
+            "by_author": buckets,
+            "by_day": buckets,
+            "unpriced_models": { "type": "array", "items": { "type": "string" } },
+            "generated_at": { "type": "string", "format": "date-time" }
+        },
+        "required": [
+            "currency",
+            "total",
+            "by_model",
+            "by_tag",
+            "by_author",
+            "by_day",
+            "unpriced_models",
+            "generated_at"
+        ]
+    })
+}
+
+/// JSON schema of the extract_actions response (see `ActionItem`)
+fn extract_actions_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "actionItems": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "text": { "type": "string" },
+                        "thoughtNumbers": { "type": "array", "items": { "type": "integer" } },
+                        "source": { "type": "string", "enum": ["marker", "heuristic"] }
+                    },
+                    "required": ["text", "thoughtNumbers", "source"]
+                }
+            }
+        },
+        "required": ["actionItems"]
+    })
+}
+
+/// JSON schema of the redact_session response (see `RedactedSession`)
+fn redact_session_output_schema() -> serde_json::Value {
+    let kinds = PiiKind::ALL.map(|kind| kind.to_string());
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "thoughts": { "type": "array", "items": { "type": "object" } },
+            "findings": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "kind": { "type": "string", "enum": kinds },
+                        "thought_number": { "type": "integer" },
+                        "branch_id": { "type": "string" },
+                        "field": { "type": "string" },
+                        "start": { "type": "integer" },
+                        "end": { "type": "integer" }
+                    },
+                    "required": ["kind", "thought_number", "field", "start", "end"]
+                }
+            },
+            "counts": { "type": "object" }
+        },
+        "required": ["thoughts", "findings", "counts"]
+    })
+}
+
+/// JSON schema of the create_issues response (see `CreatedIssue`)
+fn create_issues_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "dryRun": { "type": "boolean" },
+            "issues": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "tracker": { "type": "string" },
+                        "title": { "type": "string" },
+                        "key": { "type": "string" },
+                        "url": { "type": "string" },
+                        "request": {
+                            "type": "object",
+                            "properties": {
+                                "url": { "type": "string" },
+                                "body": { "type": "object" }
+                            },
+                            "required": ["url", "body"]
+                        }
+                    },
+                    "required": ["tracker", "title"]
+                }
+            }
+        },
+        "required": ["dryRun", "issues"]
+    })
+}
+
+/// JSON schema of the export_decisions response for the json format
+///
+/// The markdown format returns a markdown document instead.
+fn export_decisions_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "decisions": { "type": "array", "items": { "type": "object" } }
+        },
+        "required": ["decisions"]
+    })
+}
+
+/// JSON schema of the replay_session response
+fn replay_session_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "sessionId": { "type": ["string", "null"] },
+            "speed": { "type": "number", "minimum": 0 },
+            "eventCount": { "type": "integer", "minimum": 0 },
+            "totalDurationMs": { "type": "integer", "minimum": 0 },
+            "events": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "sequence": { "type": "integer" },
+                        "timestamp": { "type": ["string", "null"], "format": "date-time" },
+                        "elapsed_ms": { "type": "integer" },
+                        "type": {
+                            "type": "string",
+                            "enum": ["thought_added", "thought_revised", "branched", "thought_edited"]
+                        },
+                        "thought": { "type": "object" },
+                        "revises_thought": { "type": "integer" },
+                        "branch_id": { "type": "string" },
+                        "branch_from_thought": { "type": "integer" },
+                        "edit": { "type": "object" },
+                        "delayMs": { "type": "integer", "minimum": 0 }
+                    },
+                    "required": ["sequence", "timestamp", "elapsed_ms", "type", "delayMs"]
+                }
+            },
+            "nextSequence": { "type": ["integer", "null"] }
+        },
+        "required": ["sessionId", "speed", "eventCount", "totalDurationMs", "events", "nextSequence"]
+    })
+}
+
+/// JSON schema of the cancel_session response
+fn cancel_session_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "sessionId": { "type": ["string", "null"] },
+            "cancelled": { "type": "boolean" },
+            "reason": { "type": ["string", "null"] },
+            "thoughtCount": { "type": "integer", "minimum": 0 }
+        },
+        "required": ["sessionId", "cancelled", "reason", "thoughtCount"]
+    })
+}
+
+/// JSON schema of the create_session response
+fn create_session_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "sessionId": { "type": "string" },
+            "activeSessions": { "type": "integer", "minimum": 0 }
+        },
+        "required": ["sessionId", "activeSessions"]
+    })
+}
+
+/// JSON schema of the poll_session response
+fn poll_session_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "sessionId": { "type": "string" },
+            "thoughtCount": { "type": "integer", "minimum": 0 },
+            "thoughts": { "type": "array", "items": { "type": "object" } }
+        },
+        "required": ["sessionId", "thoughtCount", "thoughts"]
+    })
+}
+
+/// JSON schema of the pause_session response
+fn pause_session_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "sessionId": { "type": ["string", "null"] },
+            "paused": { "type": "boolean" },
+            "reason": { "type": ["string", "null"] },
+            "thoughtCount": { "type": "integer", "minimum": 0 },
+            "totalPausedMs": { "type": "integer", "minimum": 0 }
+        },
+        "required": ["sessionId", "paused", "reason", "thoughtCount", "totalPausedMs"]
+    })
+}
+
+/// JSON schema of the annotate_thought and resolve_annotation responses
+fn annotation_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "annotation": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string" },
+                    "thought_number": { "type": "integer" },
+                    "branch_id": { "type": ["string", "null"] },
+                    "author": { "type": "string" },
+                    "body": { "type": "string" },
+                    "created_at": { "type": "string", "format": "date-time" },
+                    "resolved": { "type": "boolean" },
+                    "resolved_at": { "type": ["string", "null"], "format": "date-time" }
+                },
+                "required": ["id", "thought_number", "author", "body", "created_at", "resolved"]
+            },
+            "unresolvedCount": { "type": "integer", "minimum": 0 }
+        },
+        "required": ["annotation", "unresolvedCount"]
+    })
+}
+
+/// JSON schema of the checkpoint_session response
+///
+/// The checkpoint itself is opaque; pass it back to resume_session unchanged.
+fn checkpoint_session_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "sessionId": { "type": ["string", "null"] },
+            "thoughtCount": { "type": "integer", "minimum": 0 },
+            "checkpoint": { "type": "object" }
+        },
+        "required": ["sessionId", "thoughtCount", "checkpoint"]
+    })
+}
+
+/// JSON schema of the resume_session response
+fn resume_session_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "sessionId": { "type": ["string", "null"] },
+            "thoughtHistoryLength": { "type": "integer", "minimum": 0 },
+            "branches": { "type": "array", "items": { "type": "string" } },
+            "progress": thinking_progress_schema(),
+            "pausedForMs": { "type": ["integer", "null"], "minimum": 0 },
+            "totalPausedMs": { "type": "integer", "minimum": 0 }
+        },
+        "required": ["sessionId", "thoughtHistoryLength", "branches", "progress", "pausedForMs", "totalPausedMs"]
+    })
+}
+
+/// JSON schema of the get_server_info response
+fn get_server_info_output_schema() -> serde_json::Value {
+    let flags = |names: &[&str]| {
+        let properties: serde_json::Map<String, serde_json::Value> = names
+            .iter()
+            .map(|name| (name.to_string(), serde_json::json!({ "type": "boolean" })))
+            .collect();
+        serde_json::json!({ "type": "object", "properties": properties, "required": names })
+    };
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": "string" },
+            "version": { "type": "string" },
+            "capabilities": flags(&["tools", "resources", "prompts", "logging", "completion"]),
+            "features": flags(&["analytics", "export", "httpTransport", "stdioTransport"]),
+            "tools": { "type": "array", "items": { "type": "string" } },
+            "limits": {
+                "type": "object",
+                "properties": {
+                    "maxThoughtsPerSession": { "type": "integer" },
+                    "maxBranchesPerSession": { "type": "integer" },
+                    "maxThoughtLength": { "type": "integer" },
+                    "minThoughtLength": { "type": "integer" },
+                    "maxRequestBytes": { "type": "integer" },
+                    "sessionTimeoutSeconds": { "type": "integer" },
+                    "thoughtTimeoutSeconds": { "type": ["integer", "null"] }
+                },
+                "required": [
+                    "maxThoughtsPerSession",
+                    "maxBranchesPerSession",
+                    "maxThoughtLength",
+                    "minThoughtLength",
+                    "maxRequestBytes",
+                    "sessionTimeoutSeconds",
+                    "thoughtTimeoutSeconds"
+                ]
+            },
+            "exportFormats": { "type": "array", "items": { "type": "string" } },
+            "apiKeyRequired": { "type": "boolean" },
+            "continueMarker": { "type": ["string", "null"] }
+        },
+        "required": [
+            "name",
+            "version",
+            "capabilities",
+            "features",
+            "tools",
+            "limits",
+            "exportFormats",
+            "apiKeyRequired",
+            "continueMarker"
+        ]
+    })
+}
+
+/// JSON schema of the usage_report response (see `UsageReport`)
+fn usage_report_output_schema() -> serde_json::Value {
+    let metrics = ["requests", "thoughts", "sessions", "export_bytes"];
+    let counters = |kind: &str| -> serde_json::Map<String, serde_json::Value> {
+        metrics
+            .iter()
+            .map(|metric| (metric.to_string(), serde_json::json!({ "type": kind })))
+            .collect()
+    };
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "period": { "type": "string" },
+            "resets_on": { "type": "string", "format": "date" },
+            "keys": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "key": { "type": "string" },
+                        "usage": {
+                            "type": "object",
+                            "properties": counters("integer"),
+                            "required": metrics
+                        },
+                        "quota": { "type": ["object", "null"], "properties": counters("integer") }
+                    },
+                    "required": ["key", "usage", "quota"]
+                }
+            }
+        },
+        "required": ["period", "resets_on", "keys"]
+    })
+}
+
+/// JSON schema of the analytics_dashboard response (see `AnalyticsDashboard`)
+fn analytics_dashboard_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "generated_at": { "type": "string", "format": "date-time" },
+            "total_sessions": { "type": "integer" },
+            "days": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "date": { "type": "string", "format": "date" },
+                        "sessions": { "type": "integer" },
+                        "thoughts": { "type": "integer" },
+                        "avg_quality": { "type": "number" },
+                        "avg_processing_time_ms": { "type": "number" },
+                        "p95_processing_time_ms": { "type": "number" }
+                    },
+                    "required": [
+                        "date",
+                        "sessions",
+                        "thoughts",
+                        "avg_quality",
+                        "avg_processing_time_ms",
+                        "p95_processing_time_ms"
+                    ]
+                }
+            }
+        },
+        "required": ["generated_at", "total_sessions", "days"]
+    })
+}
+
+/// JSON schema of the progress reported with a thought
+fn thinking_progress_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "currentThought": { "type": "integer" },
+            "totalThoughts": { "type": "integer" },
+            "completedThoughts": { "type": "integer" },
+            "progressPercentage": { "type": "number" },
+            "isComplete": { "type": "boolean" },
+            "elapsedMs": { "type": "integer" },
+            "avgThoughtMs": { "type": "integer" },
+            "estimatedRemainingMs": { "type": "integer" },
+            "idleMs": { "type": "integer" }
+        },
+        "required": ["currentThought", "totalThoughts", "completedThoughts", "progressPercentage", "isComplete"]
+    })
+}
+
+/// JSON schema of a serialized `ThinkingStats`
+fn thinking_stats_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "total_thoughts": { "type": "integer" },
+            "total_revisions": { "type": "integer" },
+            "total_branches": { "type": "integer" },
+            "avg_processing_time_ms": { "type": "number" },
+            "total_processing_time_ms": { "type": "integer" },
+            "total_thought_length": { "type": "integer" },
+            "total_paused_time_ms": { "type": "integer" }
+        },
+        "required": [
+            "total_thoughts",
+            "total_revisions",
+            "total_branches",
+            "avg_processing_time_ms",
+            "total_processing_time_ms",
+            "total_thought_length"
+        ]
+    })
+}
+
+/// JSON schema of the per-branch metrics in analyses and exports
+fn branch_report_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "array",
+        "description": "Metrics and outcome of each branch, in the order branches were started",
+        "items": {
+            "type": "object",
+            "properties": {
+                "branch_id": { "type": "string" },
+                "parent_thought": { "type": "integer", "minimum": 0 },
+                "thought_count": { "type": "integer", "minimum": 0 },
+                "total_length": { "type": "integer", "minimum": 0 },
+                "avg_thought_length": { "type": "number" },
+                "quality_score": { "type": "number" },
+                "duration": { "type": "integer", "minimum": 0 },
+                "status": {
+                    "type": "string",
+                    "enum": ["open", "concluded", "merged", "abandoned"]
+                }
+            },
+            "required": ["branch_id", "parent_thought", "thought_count", "quality_score", "status"]
+        }
+    })
+}
+
+/// Tools that do not work on a single session, so take no `sessionId`
+const SESSIONLESS_TOOLS: &[&str] = &[
+    "merge_sessions",
+    "compare_sessions",
+    "get_server_info",
+    "usage_report",
+    "analytics_dashboard",
+];
+
+/// Tools whose results are documents, returned as rendered without a correlation ID
+///
+/// Exporting an unchanged session twice then gives the same bytes.
+const DOCUMENT_TOOLS: &[&str] = &["export_session", "export_decisions", "extract_code"];
+
+/// Tools changing the session they name, which is stored after they succeed
+const PERSISTED_TOOLS: &[&str] = &[
+    "sequential_thinking",
+    "create_session",
+    "edit_thought",
+    "annotate_thought",
+    "resolve_annotation",
+    "pause_session",
+    "resume_session",
+];
+
+/// Get the definitions of every tool the server exposes
+///
+/// Tools working on a session take its `sessionId`, and object results also
+/// carry the correlation ID of the call.
+fn tool_definitions() -> Vec<Tool> {
+    let mut tools = vec![
+        create_sequential_thinking_tool(),
+        create_create_session_tool(),
+        create_export_session_tool(),
+        create_poll_session_tool(),
+        create_analyze_session_tool(),
+        create_merge_sessions_tool(),
+        create_compare_branches_tool(),
+        create_compare_sessions_tool(),
+        create_edit_thought_tool(),
+        create_annotate_thought_tool(),
+        create_resolve_annotation_tool(),
+        create_replay_session_tool(),
+        create_cost_report_tool(),
+        create_extract_code_tool(),
+        create_export_decisions_tool(),
+        create_extract_actions_tool(),
+        create_create_issues_tool(),
+        create_cancel_session_tool(),
+        create_pause_session_tool(),
+        create_get_server_info_tool(),
+        create_checkpoint_session_tool(),
+        create_resume_session_tool(),
+        create_usage_report_tool(),
+        create_analytics_dashboard_tool(),
+        create_redact_session_tool(),
+    ];
+    for tool in &mut tools {
+        if !SESSIONLESS_TOOLS.contains(&tool.name.as_str()) {
+            if let Some(properties) = tool
+                .input_schema
+                .get_mut("properties")
+                .and_then(|properties| properties.as_object_mut())
+            {
+                properties
+                    .entry("sessionId")
+                    .or_insert_with(|| serde_json::json!({
+                        "type": "string",
+                        "description": "Session created with create_session; omit for the default session"
+                    }));
+            }
+        }
+        if let Some(properties) = tool
+            .output_schema
+            .as_mut()
+            .filter(|_| !DOCUMENT_TOOLS.contains(&tool.name.as_str()))
+            .and_then(|schema| schema.get_mut("properties"))
+            .and_then(|properties| properties.as_object_mut())
+        {
+            properties.insert(
+                "correlationId".to_string(),
+                serde_json::json!({
+                    "type": "string",
+                    "description": "Correlation ID of the call, from its correlationId argument or generated"
+                }),
+            );
+        }
+    }
+    tools
+}
+
+/// Create the checkpoint session tool definition
+fn create_checkpoint_session_tool() -> Tool {
+    Tool {
+        name: "checkpoint_session".to_string(),
+        description: "Take a serializable snapshot of the current thinking session so it can be resumed later or elsewhere".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {}
+        }),
+        annotations: None,
+        output_schema: Some(checkpoint_session_output_schema()),
+    }
+}
+
+/// Create the resume session tool definition
+fn create_resume_session_tool() -> Tool {
+    Tool {
+        name: "resume_session".to_string(),
+        description: "Resume the current thinking session after pause_session, or replace it with the state captured by checkpoint_session".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "checkpoint": {
+                    "type": "object",
+                    "description": "Checkpoint object returned by checkpoint_session; omit to resume a paused session"
+                }
+            }
+        }),
+        annotations: None,
+        output_schema: Some(resume_session_output_schema()),
+    }
+}
+
+/// Create the get server info tool definition
+fn create_get_server_info_tool() -> Tool {
+    Tool {
+        name: "get_server_info".to_string(),
+        description: "Get the server version, enabled features, configured limits, and supported export formats".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {}
+        }),
+        annotations: None,
+        output_schema: Some(get_server_info_output_schema()),
+    }
+}
+
+/// Create the usage report tool definition
+fn create_usage_report_tool() -> Tool {
+    Tool {
+        name: "usage_report".to_string(),
+        description: "Report this month's requests, thoughts, sessions and export bytes for every API key, with their monthly quotas".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {}
+        }),
+        annotations: None,
+        output_schema: Some(usage_report_output_schema()),
+    }
+}
+
+/// Create the analytics dashboard tool definition
+fn create_analytics_dashboard_tool() -> Tool {
+    Tool {
+        name: "analytics_dashboard".to_string(),
+        description: "Report daily aggregates of completed sessions for charting: sessions, thoughts, average quality and average and p95 processing time per day, oldest day first".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "days": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_DASHBOARD_DAYS,
+                    "description": "Number of days to report, today included (default 30)"
+                }
+            }
+        }),
+        annotations: None,
+        output_schema: Some(analytics_dashboard_output_schema()),
+    }
+}
+
+/// Create the redact session tool definition
+fn create_redact_session_tool() -> Tool {
+    Tool {
+        name: "redact_session".to_string(),
+        description: "Produce a copy of the current session safe to share: emails, phone numbers, API keys and credit card numbers in thoughts and code attachments are replaced with placeholders such as [email], and each redaction is listed with its thought, field and byte range".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "kinds": {
+                    "type": "array",
+                    "items": {
+                        "type": "string",
+                        "enum": PiiKind::ALL.map(|kind| kind.to_string())
+                    },
+                    "description": "Kinds of data to redact (default: all)"
+                }
+            }
+        }),
+        annotations: None,
+        output_schema: Some(redact_session_output_schema()),
+    }
+}
+
+/// Create the compare sessions tool definition
+fn create_compare_sessions_tool() -> Tool {
+    Tool {
+        name: "compare_sessions".to_string(),
+        description: "Compare the metrics of two sessions (length, efficiency, quality, style, time and cost) and return a per-metric diff with a short summary, e.g. to A/B test prompting strategies".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "sessionA": {
+                    "type": "string",
+                    "description": "First session ID"
+                },
+                "sessionB": {
+                    "type": "string",
+                    "description": "Second session ID"
+                }
+            },
+            "required": ["sessionA", "sessionB"]
+        }),
+        annotations: None,
+        output_schema: Some(compare_sessions_output_schema()),
+    }
+}
+
+/// Create the compare branches tool definition
+fn create_compare_branches_tool() -> Tool {
+    Tool {
+        name: "compare_branches".to_string(),
+        description: "Compare two branches of the current thinking session to decide which reasoning path to keep".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "branchA": {
+                    "type": "string",
+                    "description": "First branch identifier"
+                },
+                "branchB": {
+                    "type": "string",
+                    "description": "Second branch identifier"
+                }
+            },
+            "required": ["branchA", "branchB"]
+        }),
+        annotations: None,
+        output_schema: Some(compare_branches_output_schema()),
+    }
+}
+
+/// Minimum role needed to call a tool
+fn required_role(tool: &str) -> Role {
+    match tool {
+        "export_session"
+        | "poll_session"
+        | "analyze_session"
+        | "compare_branches"
+        | "compare_sessions"
+        | "replay_session"
+        | "cost_report"
+        | "extract_code"
+        | "export_decisions"
+        | "extract_actions"
+        | "get_server_info"
+        | "checkpoint_session"
+        | "analytics_dashboard"
+        | "redact_session" => Role::Reader,
+        "merge_sessions" | "resume_session" | "usage_report" => Role::Admin,
+        _ => Role::Contributor,
+    }
+}
+
+/// Minimum role needed for a tool call
+///
+/// Resuming a paused session needs no more than pausing it; only resuming
+/// from a checkpoint replaces the session.
+fn required_role_for_call(call: &ToolCall) -> Role {
+    let resumes_pause = call.name == "resume_session"
+        && call
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("checkpoint"))
+            .is_none();
+    if resumes_pause {
+        Role::Contributor
+    } else {
+        required_role(&call.name)
+    }
+}
+
+/// API key sent as the `apiKey` argument of a tool call
+fn api_key(call: &ToolCall) -> Option<&str> {
+    call.arguments
+        .as_ref()
+        .and_then(|args| args.get("apiKey"))
+        .and_then(|v| v.as_str())
+}
+
+/// Correlation ID of a tool call, from its `correlationId` argument or generated
+fn correlation_id(call: &ToolCall) -> String {
+    call.arguments
+        .as_ref()
+        .and_then(|args| args.get("correlationId"))
+        .and_then(|v| v.as_str())
+        .map_or_else(|| uuid::Uuid::new_v4().to_string(), str::to_string)
+}
+
+/// Add the correlation ID to a tool result whose text is a JSON object
+///
+/// Other results, such as Markdown exports, are left as they are.
+fn echo_correlation_id(result: &mut ToolResult, correlation_id: &str) {
+    let Some(ToolContent::Text { text }) = result.content.first_mut() else {
+        return;
+    };
+    if !text.trim_start().starts_with('{') {
+        return;
+    }
+    if let Ok(serde_json::Value::Object(mut fields)) = serde_json::from_str(text) {
+        fields.insert("correlationId".to_string(), correlation_id.into());
+        if let Ok(echoed) = serde_json::to_string_pretty(&fields) {
+            *text = echoed;
+        }
+    }
+}
+
+/// Input schemas of the tools, strict about unknown arguments
+///
+/// Every tool also accepts the `apiKey` argument used for authorization and
+/// the `correlationId` argument used for tracing.
+fn input_schemas() -> &'static HashMap<String, serde_json::Value> {
+    static SCHEMAS: OnceLock<HashMap<String, serde_json::Value>> = OnceLock::new();
+    SCHEMAS.get_or_init(|| {
+        tool_definitions()
+            .into_iter()
+            .map(|tool| {
+                let mut schema = tool.input_schema;
+                if let Some(object) = schema.as_object_mut() {
+                    object
+                        .entry("additionalProperties")
+                        .or_insert(serde_json::Value::Bool(false));
+                    if let Some(properties) = object
+                        .entry("properties")
+                        .or_insert_with(|| serde_json::json!({}))
+                        .as_object_mut()
+                    {
+                        properties.insert(
+                            "apiKey".to_string(),
+                            serde_json::json!({ "type": "string" }),
+                        );
+                        properties.insert(
+                            "correlationId".to_string(),
+                            serde_json::json!({ "type": "string", "maxLength": 128 }),
+                        );
+                    }
+                }
+                (tool.name, schema)
+            })
+            .collect()
+    })
+}
+
+/// Check a tool call's arguments against the tool's input schema
+///
+/// Every offending field is listed in the error. Missing arguments are
+/// checked as an empty object.
+fn validate_arguments(tool: &str, args: Option<&serde_json::Value>) -> MCPResult<()> {
+    let Some(schema) = input_schemas().get(tool) else {
+        return Ok(());
+    };
+    // Null stands for an omitted argument, as clients send unset options
+    let args: serde_json::Map<String, serde_json::Value> = match args {
+        Some(serde_json::Value::Object(fields)) => fields
+            .iter()
+            .filter(|(_, value)| !value.is_null())
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+        Some(other) => {
+            return Err(MCPError::invalid_params(format!(
+                "arguments: expected object, found {other}"
+            )))
+        }
+        None => serde_json::Map::new(),
+    };
+    metadata::validate_json(&serde_json::Value::Object(args), schema, "arguments")
+        .map_err(|errors| MCPError::invalid_params(errors.join("; ")))
+}
+
+/// Length of a JSON value once serialized, without building the text
+fn json_size(value: &serde_json::Value) -> usize {
+    struct Counter(usize);
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut counter = Counter(0);
+    // Writing to the counter cannot fail
+    let _ = serde_json::to_writer(&mut counter, value);
+    counter.0
+}
+
+/// Session a tool call addresses, for limiting calls per session
+fn call_session_id(call: &ToolCall) -> &str {
+    call.arguments
+        .as_ref()
+        .and_then(|args| args.get("sessionId"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("default")
+}
+
+/// Create the cost report tool definition
+fn create_cost_report_tool() -> Tool {
+    Tool {
+        name: "cost_report".to_string(),
+        description: "Estimate the token usage and cost of the current session from configured model prices, broken down by model, tag, author and day. Token counts come from each thought's input_tokens/output_tokens/model metadata, or are estimated from the thought text".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Tags to attribute the session's cost to"
+                }
+            }
+        }),
+        annotations: None,
+        output_schema: Some(cost_report_output_schema()),
+    }
+}
+
+/// Create the extract code tool definition
+fn create_extract_code_tool() -> Tool {
+    Tool {
+        name: "extract_code".to_string(),
+        description: "Extract the code blocks of the current session as separate artifacts: fenced blocks in thought text and code attachments, one content item per block with its thought number, branch and language".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "language": {
+                    "type": "string",
+                    "description": "Only return blocks in this language"
+                }
+            }
+        }),
+        annotations: None,
+        output_schema: None,
+    }
+}
+
+/// Create the export decisions tool definition
+fn create_export_decisions_tool() -> Tool {
+    Tool {
+        name: "export_decisions".to_string(),
+        description: "Export the decisions made in the current session as an ADR-style log. Thoughts are decisions when their metadata sets decision to true or their text starts with 'decision:'; each is proposed, accepted once the session concludes, or reversed by a later revision".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "format": {
+                    "type": "string",
+                    "enum": ["markdown", "json"],
+                    "description": "Log format",
+                    "default": "markdown"
+                }
+            }
+        }),
+        annotations: None,
+        output_schema: Some(export_decisions_output_schema()),
+    }
+}
+
+/// Create the extract actions tool definition
+fn create_extract_actions_tool() -> Tool {
+    Tool {
+        name: "extract_actions".to_string(),
+        description: "List the action items mentioned in the current session: TODO/FIXME markers, unchecked checkboxes and sentences such as 'we need to ...', deduplicated, each with the thoughts that mention it".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {}
+        }),
+        annotations: None,
+        output_schema: Some(extract_actions_output_schema()),
+    }
+}
+
+/// Create the create issues tool definition
+fn create_create_issues_tool() -> Tool {
+    Tool {
+        name: "create_issues".to_string(),
+        description: "Create GitHub or Jira issues from the current session: one per action item, or one summarizing the concluded session. With dryRun the issues are returned without contacting the tracker".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "tracker": {
+                    "type": "string",
+                    "enum": ["github", "jira"],
+                    "description": "Tracker to create issues in; defaults to the only configured one"
+                },
+                "source": {
+                    "type": "string",
+                    "enum": ["actions", "session"],
+                    "description": "Create an issue per action item, or one for the concluded session",
+                    "default": "actions"
+                },
+                "dryRun": {
+                    "type": "boolean",
+                    "description": "Return the requests that would be sent instead of creating issues; defaults to integrations.dry_run"
+                }
+            }
+        }),
+        annotations: None,
+        output_schema: Some(create_issues_output_schema()),
+    }
+}
+
+/// Create the create session tool definition
+fn create_create_session_tool() -> Tool {
+    Tool {
+        name: "create_session".to_string(),
+        description: "Start a named thinking session. Pass its ID as sessionId to the other tools to work in it; calls without a sessionId use the default session".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "sessionId": {
+                    "type": "string",
+                    "minLength": 1,
+                    "description": "ID of the new session"
+                }
+            },
+            "required": ["sessionId"]
+        }),
+        annotations: None,
+        output_schema: Some(create_session_output_schema()),
+    }
+}
+
+/// Create the poll session tool definition
+fn create_poll_session_tool() -> Tool {
+    Tool {
+        name: "poll_session".to_string(),
+        description: "Get the thoughts recorded in a session after the first `since`, with the session's thought count, for watching a session without exporting it".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "since": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Number of thoughts already seen"
+                }
+            }
+        }),
+        annotations: None,
+        output_schema: Some(poll_session_output_schema()),
+    }
+}
+
+/// Create the cancel session tool definition
+fn create_cancel_session_tool() -> Tool {
+    Tool {
+        name: "cancel_session".to_string(),
+        description: "Abort thought processing that is in progress or queued for the current session. Aborted sequential_thinking calls fail with a cancellation error; later thoughts are processed normally".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "reason": {
+                    "type": "string",
+                    "description": "Why the processing is being cancelled"
+                }
+            }
+        }),
+        annotations: None,
+        output_schema: Some(cancel_session_output_schema()),
+    }
+}
+
+/// Create the pause session tool definition
+fn create_pause_session_tool() -> Tool {
+    Tool {
+        name: "pause_session".to_string(),
+        description: "Pause the current thinking session. New thoughts are rejected until resume_session is called; time spent paused is reported separately from thinking time".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "reason": {
+                    "type": "string",
+                    "description": "Why the session is being paused"
+                }
+            }
+        }),
+        annotations: None,
+        output_schema: Some(pause_session_output_schema()),
+    }
+}
+
+/// Create the replay session tool definition
+fn create_replay_session_tool() -> Tool {
+    Tool {
+        name: "replay_session".to_string(),
+        description: "Replay how the current session unfolded as timestamped events (thought added, revised, branched, edited). Each event carries the delay to wait before showing it at the requested speed, so UIs can animate the reasoning".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "speed": {
+                    "type": "number",
+                    "description": "Playback speed: 1 keeps the original pacing, 2 is twice as fast, 0 removes delays",
+                    "minimum": 0,
+                    "default": 1
+                },
+                "fromSequence": {
+                    "type": "integer",
+                    "description": "Sequence number of the first event to return",
+                    "minimum": 0,
+                    "default": 0
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of events to return, for stepping through the replay",
+                    "minimum": 1
+                }
+            }
+        }),
+        annotations: None,
+        output_schema: Some(replay_session_output_schema()),
+    }
+}
+
+/// Create the edit thought tool definition
+fn create_edit_thought_tool() -> Tool {
+    Tool {
+        name: "edit_thought".to_string(),
+        description: "Correct the text of an existing thought in place (e.g. typos). Unlike a revision, no new thought is added; the previous text is kept in an audit log".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "thoughtNumber": {
+                    "type": "integer",
+                    "description": "Number of the thought to edit",
+                    "minimum": 1
+                },
+                "thought": {
+                    "type": "string",
+                    "description": "Corrected thought text"
+                },
+                "editor": {
+                    "type": "string",
+                    "description": "Who is making the edit"
+                }
+            },
+            "required": ["thoughtNumber", "thought"]
+        }),
+        annotations: None,
+        output_schema: Some(edit_thought_output_schema()),
+    }
+}
+
+/// Create the annotate thought tool definition
+fn create_annotate_thought_tool() -> Tool {
+    Tool {
+        name: "annotate_thought".to_string(),
+        description: "Attach a reviewer comment to a thought in the current session. Comments are stored with the session and included in exports".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "thoughtNumber": {
+                    "type": "integer",
+                    "description": "Number of the thought to comment on",
+                    "minimum": 1
+                },
+                "branchId": {
+                    "type": "string",
+                    "description": "Branch of the thought, if it is on a branch"
+                },
+                "author": {
+                    "type": "string",
+                    "description": "Comment author"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Comment text"
+                }
+            },
+            "required": ["thoughtNumber", "author", "body"]
+        }),
+        annotations: None,
+        output_schema: Some(annotation_output_schema()),
+    }
+}
+
+/// Create the resolve annotation tool definition
+fn create_resolve_annotation_tool() -> Tool {
+    Tool {
+        name: "resolve_annotation".to_string(),
+        description: "Mark a reviewer comment as resolved".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "annotationId": {
+                    "type": "string",
+                    "description": "Identifier returned by annotate_thought"
+                }
+            },
+            "required": ["annotationId"]
+        }),
+        annotations: None,
+        output_schema: Some(annotation_output_schema()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionStatus;
+    use crate::thinking::actor::ActorConfig;
+
+    #[test]
+    fn test_server_creation() {
+        let server = SequentialThinkingServer::new();
+        assert_eq!(server.info().name, "ultrafast-sequential-thinking");
+        assert!(server.capabilities().tools.is_some());
+    }
+
+    #[test]
+    fn test_tool_definitions() {
+        let sequential_tool = create_sequential_thinking_tool();
+        assert_eq!(sequential_tool.name, "sequential_thinking");
+        let export_tool = create_export_session_tool();
+        assert_eq!(export_tool.name, "export_session");
+        let names: Vec<String> = tool_definitions().into_iter().map(|t| t.name).collect();
+        assert!(names.contains(&"get_server_info".to_string()));
+    }
+
+    /// Check a value against the subset of JSON schema used by the tool definitions
+    fn assert_matches_schema(value: &serde_json::Value, schema: &serde_json::Value, path: &str) {
+        let type_matches = |expected: &str| match expected {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "integer" => value.is_u64() || value.is_i64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "null" => value.is_null(),
+            _ => false,
+        };
+        match &schema["type"] {
+            serde_json::Value::String(t) => assert!(type_matches(t), "{path}: expected {t}"),
+            serde_json::Value::Array(types) => assert!(
+                types.iter().any(|t| type_matches(t.as_str().unwrap())),
+                "{path}: expected one of {types:?}"
+            ),
+            _ => {}
+        }
+
+        if let Some(required) = schema["required"].as_array() {
+            for key in required {
+                let key = key.as_str().unwrap();
+                assert!(value.get(key).is_some(), "{path}: missing {key}");
+            }
+        }
+        if let (Some(properties), Some(object)) =
+            (schema["properties"].as_object(), value.as_object())
+        {
+            for (key, field) in object {
+                let field_schema = properties
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{path}: {key} not in schema"));
+                assert_matches_schema(field, field_schema, &format!("{path}.{key}"));
+            }
+        }
+        if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+            for item in array {
+                assert_matches_schema(item, items, &format!("{path}[]"));
+            }
+        }
+    }
+
+    /// Parse the JSON text content of a tool result
+    ///
+    /// The echoed correlation ID is left out; `test_responses_echo_correlation_id`
+    /// covers it.
+    fn result_json(result: &ToolResult) -> serde_json::Value {
+        let ToolContent::Text { text } = &result.content[0] else {
+            panic!("expected text content");
+        };
+        let mut value: serde_json::Value = serde_json::from_str(text).unwrap();
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("correlationId");
+        }
+        value
+    }
+
+    #[tokio::test]
+    async fn test_annotate_thought() {
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new()),
+        };
+        let call = |name: &str, arguments: serde_json::Value| ToolCall {
+            name: name.to_string(),
+            arguments: Some(arguments),
+        };
+
+        handler
+            .server
+            .process_thought(ThoughtData::new("Assume linear growth".to_string(), 1, 2))
+            .await
+            .unwrap();
+
+        let missing = handler
+            .handle_tool_call(call(
+                "annotate_thought",
+                serde_json::json!({ "thoughtNumber": 7, "author": "rev", "body": "?" }),
+            ))
+            .await;
+        assert!(missing.is_err());
+
+        let annotated = result_json(
+            &handler
+                .handle_tool_call(call(
+                    "annotate_thought",
+                    serde_json::json!({
+                        "thoughtNumber": 1,
+                        "author": "reviewer",
+                        "body": "Why linear?"
+                    }),
+                ))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(annotated["unresolvedCount"], 1);
+        let id = annotated["annotation"]["id"].as_str().unwrap().to_string();
+
+        let markdown = handler
+            .handle_tool_call(call(
+                "export_session",
+                serde_json::json!({ "format": "markdown" }),
+            ))
+            .await
+            .unwrap();
+        let ToolContent::Text { text } = &markdown.content[0] else {
+            panic!("expected text content");
+        };
+        assert!(text.contains("**reviewer**: Why linear?"));
+
+        let resolved = result_json(
+            &handler
+                .handle_tool_call(call(
+                    "resolve_annotation",
+                    serde_json::json!({ "annotationId": id }),
+                ))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(resolved["annotation"]["resolved"], true);
+        assert_eq!(resolved["unresolvedCount"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_responses_match_output_schemas() {
+        let integrations = serde_json::from_value(
+            serde_json::json!({ "github": { "repository": "acme/parser" } }),
+        )
+        .unwrap();
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(
+                SequentialThinkingServer::new().with_integrations_config(integrations),
+            ),
+        };
+        let call = |name: &str, arguments: serde_json::Value| ToolCall {
+            name: name.to_string(),
+            arguments: Some(arguments),
+        };
+
+        let thought = handler
+            .handle_tool_call(call(
+                "sequential_thinking",
+                serde_json::json!({
+                    "thought": "Check the schema",
+                    "thoughtNumber": 1,
+                    "totalThoughts": 2,
+                    "nextThoughtNeeded": true,
+                    "strategy": "dialectic"
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_matches_schema(
+            &result_json(&thought),
+            &sequential_thinking_output_schema(),
+            "sequential_thinking",
+        );
+        assert_eq!(
+            result_json(&thought)["guidance"],
+            "State the strongest counterargument to this thesis"
+        );
+
+        let export = handler
+            .handle_tool_call(call(
+                "export_session",
+                serde_json::json!({ "format": "json" }),
+            ))
+            .await
+            .unwrap();
+        assert_matches_schema(
+            &result_json(&export),
+            &export_session_output_schema(),
+            "export_session",
+        );
+
+        let analysis = handler
+            .handle_tool_call(call("analyze_session", serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_matches_schema(
+            &result_json(&analysis),
+            &analyze_session_output_schema(),
+            "analyze_session",
+        );
+
+        let merge = handler
+            .handle_tool_call(call(
+                "merge_sessions",
+                serde_json::json!({ "sessionIds": ["missing"] }),
+            ))
+            .await
+            .unwrap();
+        assert_matches_schema(
+            &result_json(&merge),
+            &merge_sessions_output_schema(),
+            "merge_sessions",
+        );
+
+        for (number, branch_id, text) in [
+            (2, "cache", "Decision: add a cache\nTODO: measure hit rate"),
+            (3, "index", "Add an index instead"),
+        ] {
+            handler
+                .handle_tool_call(call(
+                    "sequential_thinking",
+                    serde_json::json!({
+                        "thought": text,
+                        "thoughtNumber": number,
+                        "totalThoughts": 4,
+                        "nextThoughtNeeded": true,
+                        "branchFromThought": 1,
+                        "branchId": branch_id
+                    }),
+                ))
+                .await
+                .unwrap();
+        }
+        for session_id in ["a", "b"] {
+            handler
+                .server
+                .create_session(session_id.to_string())
+                .await
+                .unwrap();
+        }
+
+        let annotation = handler
+            .handle_tool_call(call(
+                "annotate_thought",
+                serde_json::json!({ "thoughtNumber": 1, "author": "reviewer", "body": "Why?" }),
+            ))
+            .await
+            .unwrap();
+        assert_matches_schema(
+            &result_json(&annotation),
+            &annotation_output_schema(),
+            "annotate_thought",
+        );
+        let annotation_id = result_json(&annotation)["annotation"]["id"].clone();
+        let checkpoint = handler
+            .handle_tool_call(call("checkpoint_session", serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_matches_schema(
+            &result_json(&checkpoint),
+            &checkpoint_session_output_schema(),
+            "checkpoint_session",
+        );
+
+        let calls = [
+            (
+                "compare_branches",
+                serde_json::json!({ "branchA": "cache", "branchB": "index" }),
+                compare_branches_output_schema(),
+            ),
+            (
+                "compare_sessions",
+                serde_json::json!({ "sessionA": "a", "sessionB": "b" }),
+                compare_sessions_output_schema(),
+            ),
+            (
+                "resolve_annotation",
+                serde_json::json!({ "annotationId": annotation_id }),
+                annotation_output_schema(),
+            ),
+            (
+                "edit_thought",
+                serde_json::json!({ "thoughtNumber": 1, "thought": "Check the schemas" }),
+                edit_thought_output_schema(),
+            ),
+            (
+                "cost_report",
+                serde_json::json!({}),
+                cost_report_output_schema(),
+            ),
+            (
+                "extract_actions",
+                serde_json::json!({}),
+                extract_actions_output_schema(),
+            ),
+            (
+                "create_issues",
+                serde_json::json!({ "dryRun": true }),
+                create_issues_output_schema(),
+            ),
+            (
+                "export_decisions",
+                serde_json::json!({ "format": "json" }),
+                export_decisions_output_schema(),
+            ),
+            (
+                "replay_session",
+                serde_json::json!({}),
+                replay_session_output_schema(),
+            ),
+            (
+                "get_server_info",
+                serde_json::json!({}),
+                get_server_info_output_schema(),
+            ),
+            (
+                "resume_session",
+                serde_json::json!({ "checkpoint": result_json(&checkpoint)["checkpoint"] }),
+                resume_session_output_schema(),
+            ),
+            (
+                "usage_report",
+                serde_json::json!({}),
+                usage_report_output_schema(),
+            ),
+            (
+                "analytics_dashboard",
+                serde_json::json!({ "days": 7 }),
+                analytics_dashboard_output_schema(),
+            ),
+            (
+                "redact_session",
+                serde_json::json!({ "kinds": ["email"] }),
+                redact_session_output_schema(),
+            ),
+            (
+                "cancel_session",
+                serde_json::json!({}),
+                cancel_session_output_schema(),
+            ),
+            (
+                "create_session",
+                serde_json::json!({ "sessionId": "c" }),
+                create_session_output_schema(),
+            ),
+            (
+                "poll_session",
+                serde_json::json!({ "since": 0 }),
+                poll_session_output_schema(),
+            ),
+            (
+                "pause_session",
+                serde_json::json!({ "reason": "lunch" }),
+                pause_session_output_schema(),
+            ),
+            (
+                "resume_session",
+                serde_json::json!({}),
+                resume_session_output_schema(),
+            ),
+        ];
+        for (name, arguments, schema) in calls {
+            let result = handler
+                .handle_tool_call(call(name, arguments))
+                .await
+                .unwrap();
+            assert_matches_schema(&result_json(&result), &schema, name);
+        }
+
+        // Every tool answering with a single JSON object declares its shape
+        for tool in tool_definitions() {
+            assert_eq!(
+                tool.output_schema.is_some(),
+                tool.name != "extract_code",
+                "{}",
+                tool.name
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_role_based_access() {
+        let security = SecurityConfig {
+            api_key_validation: true,
+            api_keys: HashMap::from([
+                ("dashboard".to_string(), Role::Reader),
+                ("agent".to_string(), Role::Contributor),
+                ("ops".to_string(), Role::Admin),
+            ]),
+            ..Default::default()
+        };
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new().with_security_config(security)),
+        };
+        let thought = |key: &str| ToolCall {
+            name: "sequential_thinking".to_string(),
+            arguments: Some(serde_json::json!({
+                "thought": "Take the only step",
+                "thoughtNumber": 1,
+                "totalThoughts": 1,
+                "nextThoughtNeeded": false,
+                "apiKey": key
+            })),
+        };
+        let export = |key: Option<&str>| ToolCall {
+            name: "export_session".to_string(),
+            arguments: Some(match key {
+                Some(key) => serde_json::json!({ "format": "json", "apiKey": key }),
+                None => serde_json::json!({ "format": "json" }),
+            }),
+        };
+
+        assert!(handler
+            .handle_tool_call(thought("dashboard"))
+            .await
+            .is_err());
+        assert!(handler.handle_tool_call(thought("agent")).await.is_ok());
+        assert!(handler
+            .handle_tool_call(export(Some("dashboard")))
+            .await
+            .is_ok());
+        assert!(handler
+            .handle_tool_call(export(Some("stolen")))
+            .await
+            .is_err());
+        assert!(handler.handle_tool_call(export(None)).await.is_err());
+
+        let merge = |key: &str| ToolCall {
+            name: "merge_sessions".to_string(),
+            arguments: Some(serde_json::json!({ "apiKey": key })),
+        };
+        assert!(handler.handle_tool_call(merge("agent")).await.is_err());
+        assert!(Role::Admin.allows(required_role("merge_sessions")));
+
+        // Contributors resume their own pauses, but not checkpoints
+        let session_call = |name: &str, arguments: serde_json::Value| ToolCall {
+            name: name.to_string(),
+            arguments: Some(arguments),
+        };
+        let agent = serde_json::json!({ "apiKey": "agent" });
+        assert!(handler
+            .handle_tool_call(session_call("pause_session", agent.clone()))
+            .await
+            .is_ok());
+        assert!(handler
+            .handle_tool_call(session_call("resume_session", agent))
+            .await
+            .is_ok());
+        assert!(handler
+            .handle_tool_call(session_call(
+                "resume_session",
+                serde_json::json!({ "apiKey": "agent", "checkpoint": {} })
+            ))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pause_session() {
+        let clock = crate::thinking::clock::TestClock::new(chrono::Utc::now());
+        let server = SequentialThinkingServer::new();
+        server.engine.write().await.set_clock(clock.shared());
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(server),
+        };
+        let call = |name: &str, arguments: serde_json::Value| ToolCall {
+            name: name.to_string(),
+            arguments: Some(arguments),
+        };
+        let thought = |number: u32| {
+            call(
+                "sequential_thinking",
+                serde_json::json!({
+                    "thought": format!("Working through step {number}"),
+                    "thoughtNumber": number,
+                    "totalThoughts": 2,
+                    "nextThoughtNeeded": number < 2
+                }),
+            )
+        };
+
+        handler.handle_tool_call(thought(1)).await.unwrap();
+        handler
+            .handle_tool_call(call("pause_session", serde_json::json!({})))
+            .await
+            .unwrap();
+        let rejected = handler.handle_tool_call(thought(2)).await.unwrap_err();
+        assert!(rejected.to_string().contains("Session is paused"));
+        assert!(handler
+            .handle_tool_call(call("pause_session", serde_json::json!({})))
+            .await
+            .is_err());
+
+        clock.advance(chrono::Duration::minutes(5));
+        let resumed = handler
+            .handle_tool_call(call("resume_session", serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(result_json(&resumed)["pausedForMs"], 300_000);
+        assert!(handler
+            .handle_tool_call(call("resume_session", serde_json::json!({})))
+            .await
+            .is_err());
+
+        clock.advance(chrono::Duration::seconds(30));
+        handler.handle_tool_call(thought(2)).await.unwrap();
+        let analysis = handler
+            .handle_tool_call(call("analyze_session", serde_json::json!({})))
+            .await
+            .unwrap();
+        let analysis = &result_json(&analysis)["analysis"];
+        assert_eq!(analysis["pausedTimeMs"], 300_000);
+        assert_eq!(analysis["activeTimeMs"], 30_000);
+        assert_eq!(analysis["processingStats"]["total_paused_time_ms"], 300_000);
+    }
+
+    #[tokio::test]
+    async fn test_usage_quotas() {
+        let security = SecurityConfig {
+            api_key_validation: true,
+            api_keys: HashMap::from([
+                ("agent".to_string(), Role::Contributor),
+                ("ops".to_string(), Role::Admin),
+            ]),
+            ..Default::default()
+        };
+        let usage = UsageConfig {
+            keys: HashMap::from([(
+                "agent".to_string(),
+                crate::usage::UsageQuota {
+                    thoughts: Some(1),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(
+                SequentialThinkingServer::new()
+                    .with_security_config(security)
+                    .with_usage_config(usage),
+            ),
+        };
+        let thought = |number: u32| {
+            let mut arguments =
+                ThoughtData::new(format!("Working through step {number}"), number, 2)
+                    .to_tool_arguments();
+            arguments["apiKey"] = serde_json::json!("agent");
+            ToolCall {
+                name: "sequential_thinking".to_string(),
+                arguments: Some(arguments),
+            }
+        };
+        let report = |key: &str| ToolCall {
+            name: "usage_report".to_string(),
+            arguments: Some(serde_json::json!({ "apiKey": key })),
+        };
+
+        handler.handle_tool_call(thought(1)).await.unwrap();
+        let refused = handler.handle_tool_call(thought(2)).await.unwrap_err();
+        assert!(refused
+            .to_string()
+            .contains("monthly quota of 1 thoughts reached for this API key"));
+        assert!(handler.handle_tool_call(report("agent")).await.is_err());
+
+        let report = result_json(&handler.handle_tool_call(report("ops")).await.unwrap());
+        let agent = &report["keys"][0];
+        assert_eq!(agent["key"], "agent");
+        // Calls refused for their role are not counted
+        assert_eq!(agent["usage"]["requests"], 2);
+        assert_eq!(agent["usage"]["thoughts"], 1);
+        assert_eq!(agent["usage"]["sessions"], 1);
+        assert_eq!(agent["quota"]["thoughts"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_retries() {
+        let server = SequentialThinkingServer::new();
+        let thought = ThoughtData::new("Only count me once".to_string(), 1, 2)
+            .with_idempotency_key("retry-1".to_string());
+
+        let first = server.process_thought(thought.clone()).await.unwrap();
+        let retried = server.process_thought(thought).await.unwrap();
+        assert_eq!(first, retried);
+        assert_eq!(server.engine.read().await.get_thoughts().len(), 1);
+
+        let stats = server.get_stats().await;
+        assert_eq!(stats.total_thoughts, 1);
+        assert_eq!(stats.duplicate_requests, 1);
+
+        // Reusing a key for different content is rejected
+        let reused = ThoughtData::new("Something else".to_string(), 2, 2)
+            .with_idempotency_key("retry-1".to_string());
+        let error = server.process_thought(reused).await.unwrap_err();
+        assert!(matches!(
+            error.root_cause(),
+            SequentialThinkingError::ValidationError { .. }
+        ));
+        assert_eq!(error.context().unwrap().thought_number, Some(2));
+    }
+
+    #[derive(Debug, Default)]
+    struct CountingSampler(std::sync::atomic::AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl ThoughtSampler for CountingSampler {
+        async fn sample(
+            &self,
+            request: sampling::SamplingRequest,
+        ) -> Result<sampling::SamplingResponse, String> {
+            let n = self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            assert!(request.prompt.contains("Frame the problem"));
+            Ok(sampling::SamplingResponse {
+                text: format!("Generated thought number {n}"),
+                model: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sampled_thoughts() {
+        let sampler = Arc::new(CountingSampler::default());
+        let server = SequentialThinkingServer::new();
+        let marker = ThoughtData::new("[continue]".to_string(), 2, 3)
+            .with_idempotency_key("next".to_string());
+        assert!(server
+            .generate_thought("default", marker.clone())
+            .await
+            .is_err());
+
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(
+                server
+                    .with_thinking_config(ThinkingConfig {
+                        sampling: Some(Default::default()),
+                        ..Default::default()
+                    })
+                    .with_sampler(sampler.clone()),
+            ),
+        };
+        let call = |thought: &ThoughtData| ToolCall {
+            name: "sequential_thinking".to_string(),
+            arguments: Some(thought.to_tool_arguments()),
+        };
+        handler
+            .handle_sequential_thinking(call(&ThoughtData::new(
+                "Frame the problem".to_string(),
+                1,
+                3,
+            )))
+            .await
+            .unwrap();
+
+        // A retried marker returns the first generated thought
+        for _ in 0..2 {
+            let result = handler
+                .handle_sequential_thinking(call(&marker))
+                .await
+                .unwrap();
+            assert_eq!(result_json(&result)["thoughtNumber"], 2);
+        }
+        assert_eq!(sampler.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let engine = handler.server.engine.read().await;
+        assert_eq!(engine.get_thoughts().len(), 2);
+        assert_eq!(
+            engine.get_thoughts()[1].thought,
+            "Generated thought number 1"
+        );
+    }
+
+    #[derive(Debug)]
+    struct EstimateElicitor;
+
+    #[async_trait::async_trait]
+    impl ThoughtElicitor for EstimateElicitor {
+        async fn elicit(
+            &self,
+            request: elicitation::ElicitationRequest,
+        ) -> Result<elicitation::ElicitationResponse, String> {
+            let content = request
+                .fields
+                .into_iter()
+                .map(|field| (field, serde_json::json!(3)))
+                .collect();
+            Ok(elicitation::ElicitationResponse::Accept { content })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_elicited_fields() {
+        let call = || ToolCall {
+            name: "sequential_thinking".to_string(),
+            arguments: Some(serde_json::json!({
+                "thought": "How many steps will this take?",
+                "nextThoughtNeeded": true
+            })),
+        };
+        let server = SequentialThinkingServer::new().with_elicitor(Arc::new(EstimateElicitor));
+        assert!(!server.elicitation_enabled());
+
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(server.with_thinking_config(ThinkingConfig {
+                elicitation: Some(elicitation::ElicitationConfig {
+                    fields: vec!["totalThoughts".to_string()],
+                    message: None,
+                }),
+                ..Default::default()
+            })),
+        };
+        // thoughtNumber is not elicited, so the call still fails without it
+        assert!(handler.handle_sequential_thinking(call()).await.is_err());
+
+        let mut numbered = call();
+        numbered.arguments.as_mut().unwrap()["thoughtNumber"] = serde_json::json!(1);
+        let result = handler.handle_sequential_thinking(numbered).await.unwrap();
+        assert_eq!(result_json(&result)["totalThoughts"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_actors_handle_concurrent_calls_in_order() {
+        let handler = Arc::new(SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new().with_thinking_config(
+                ThinkingConfig {
+                    actors: Some(ActorConfig {
+                        mailbox_capacity: 2,
+                    }),
+                    ..Default::default()
+                },
+            )),
+        });
+        let sessions = ["a", "b", "c", "d"];
+        for session_id in sessions {
+            handler
+                .server
+                .create_session(session_id.to_string())
+                .await
+                .unwrap();
+        }
+        let thought = |session_id: &str, number: u32| ToolCall {
+            name: "sequential_thinking".to_string(),
+            arguments: Some(serde_json::json!({
+                "sessionId": session_id,
+                "thought": format!("Step {number} of session {session_id}"),
+                "thoughtNumber": number,
+                "totalThoughts": 5,
+                "nextThoughtNeeded": number < 5
+            })),
+        };
+
+        // Every session takes thoughts while exports of it run alongside
+        let tasks: Vec<_> = sessions
+            .iter()
+            .flat_map(|session_id| {
+                let writer = tokio::spawn({
+                    let handler = handler.clone();
+                    let calls: Vec<_> = (1..=5).map(|n| thought(session_id, n)).collect();
+                    async move {
+                        for call in calls {
+                            handler.handle_tool_call(call).await.unwrap();
+                        }
+                    }
+                });
+                let reader = tokio::spawn({
+                    let handler = handler.clone();
+                    let call = ToolCall {
+                        name: "export_session".to_string(),
+                        arguments: Some(serde_json::json!({ "sessionId": session_id })),
+                    };
+                    async move {
+                        for _ in 0..5 {
+                            handler.handle_tool_call(call.clone()).await.unwrap();
+                        }
+                    }
+                });
+                [writer, reader]
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        for session_id in sessions {
+            let engine = handler.server.session_engine(session_id).await.unwrap();
+            let numbers: Vec<_> = engine
+                .read()
+                .await
+                .get_thoughts()
+                .iter()
+                .map(|t| t.thought_number)
+                .collect();
+            assert_eq!(numbers, [1, 2, 3, 4, 5]);
+        }
+        let actors = handler.server.actors.as_ref().unwrap();
+        let mut running = actors.session_ids().await;
+        running.sort();
+        assert_eq!(running, sessions);
+
+        // Removing a session stops its actor
+        assert!(handler.server.remove_session("d").await);
+        let mut running = actors.session_ids().await;
+        running.sort();
+        assert_eq!(running, ["a", "b", "c"]);
+
+        // Cancelling does not wait for the call an actor is busy with
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+        let busy = actors.actor("a").await;
+        let blocking = tokio::spawn(async move {
+            busy.run(async move {
+                let _ = released.await;
+            })
+            .await
+        });
+        tokio::task::yield_now().await;
+        let cancel = ToolCall {
+            name: "cancel_session".to_string(),
+            arguments: Some(serde_json::json!({ "sessionId": "a" })),
+        };
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            handler.handle_tool_call(cancel),
+        )
+        .await
+        .expect("cancel_session queued behind the busy actor")
+        .unwrap();
+        release.send(()).unwrap();
+        blocking.await.unwrap().unwrap();
+    }
+
+    /// Clock letting a maintenance writer tag the stored session the first
+    /// time it is read once armed
+    #[derive(Debug)]
+    struct MeddlingClock {
+        store: Arc<SessionManager>,
+        armed: std::sync::atomic::AtomicBool,
+    }
+
+    impl crate::thinking::clock::Clock for MeddlingClock {
+        fn now(&self) -> chrono::DateTime<chrono::Utc> {
+            if self.armed.swap(false, std::sync::atomic::Ordering::SeqCst) {
+                let store = self.store.clone();
+                tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(async move {
+                        store
+                            .modify_session("s", |session| session.add_tag("reviewed".to_string()))
+                            .await
+                            .unwrap();
+                    })
+                });
+            }
+            chrono::Utc::now()
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_conflicting_writers_keep_every_change() {
+        let store = Arc::new(SessionManager::new());
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new().with_session_manager(store.clone())),
+        };
+        let call = |name: &str, arguments: serde_json::Value| ToolCall {
+            name: name.to_string(),
+            arguments: Some(arguments),
+        };
+        handler
+            .handle_tool_call(call(
+                "create_session",
+                serde_json::json!({ "sessionId": "s" }),
+            ))
+            .await
+            .unwrap();
+        handler
+            .handle_tool_call(call(
+                "sequential_thinking",
+                serde_json::json!({
+                    "sessionId": "s",
+                    "thought": "Frame the shared problem",
+                    "thoughtNumber": 1,
+                    "totalThoughts": 2,
+                    "nextThoughtNeeded": true
+                }),
+            ))
+            .await
+            .unwrap();
+        let stored = store.get_session("s").await.unwrap();
+        assert_eq!(stored.engine.thought_count(), 1);
+        assert_eq!(stored.version(), 3);
+
+        // Another writer tags the session while the server is storing an
+        // annotation, so the server's first update is stale
+        let clock = Arc::new(MeddlingClock {
+            store: store.clone(),
+            armed: std::sync::atomic::AtomicBool::new(true),
+        });
+        let engine = handler.server.session_engine("s").await.unwrap();
+        engine.write().await.set_clock(clock);
+        handler
+            .handle_tool_call(call(
+                "annotate_thought",
+                serde_json::json!({
+                    "sessionId": "s",
+                    "thoughtNumber": 1,
+                    "author": "reviewer",
+                    "body": "Check the assumptions"
+                }),
+            ))
+            .await
+            .unwrap();
+
+        let stored = store.get_session("s").await.unwrap();
+        assert_eq!(stored.metadata.tags, ["reviewed"]);
+        assert_eq!(stored.engine.get_annotations().len(), 1);
+        assert_eq!(stored.version(), 5);
+
+        handler
+            .handle_tool_call(call(
+                "sequential_thinking",
+                serde_json::json!({
+                    "sessionId": "s",
+                    "thought": "Settle on the answer",
+                    "thoughtNumber": 2,
+                    "totalThoughts": 2,
+                    "nextThoughtNeeded": false
+                }),
+            ))
+            .await
+            .unwrap();
+        let stored = store.get_session("s").await.unwrap();
+        assert_eq!(stored.engine.thought_count(), 2);
+        assert_eq!(stored.metadata.tags, ["reviewed"]);
+        assert_eq!(stored.status(), &SessionStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_timeout() {
+        let server = Arc::new(SequentialThinkingServer::new());
+
+        // Queue a thought behind a held engine lock, then cancel it
+        let guard = server.engine.write().await;
+        let pending = tokio::spawn({
+            let server = server.clone();
+            async move {
+                server
+                    .process_thought(ThoughtData::new("Slow validation".to_string(), 1, 2))
+                    .await
+            }
+        });
+        tokio::task::yield_now().await;
+        server.cancel_session("default").await;
+        let cancelled = pending.await.unwrap().unwrap_err();
+        assert!(matches!(
+            cancelled.root_cause(),
+            SequentialThinkingError::Cancelled { .. }
+        ));
+        drop(guard);
+
+        // The session keeps working after a cancellation
+        server
+            .process_thought(ThoughtData::new("Fresh start".to_string(), 1, 2))
+            .await
+            .unwrap();
+        assert_eq!(server.engine.read().await.get_thoughts().len(), 1);
+
+        let limited = Arc::new(SequentialThinkingServer::new().with_thinking_config(
+            ThinkingConfig {
+                thought_timeout_seconds: Some(0),
+                ..Default::default()
+            },
+        ));
+        let _guard = limited.engine.write().await;
+        let timed_out = limited
+            .process_thought(ThoughtData::new("Never runs".to_string(), 1, 1))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            timed_out.root_cause(),
+            SequentialThinkingError::Timeout { .. }
+        ));
+        assert_eq!(
+            timed_out.to_string(),
+            "Operation timed out after 0ns (during process_thought in session 'default', thought 1)"
+        );
+        assert_eq!(limited.get_stats().await.error_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_server_info() {
+        let limits = ThinkingConfig {
+            max_thought_length: 500,
+            ..Default::default()
+        };
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new().with_thinking_config(limits)),
+        };
+
+        let result = handler.handle_get_server_info().await.unwrap();
+        let info = result_json(&result);
+        assert_eq!(info["limits"]["maxThoughtLength"], 500);
+        assert_eq!(
+            info["exportFormats"],
+            serde_json::json!(["json", "markdown"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stats_count_concurrent_thoughts() {
+        let server = Arc::new(SequentialThinkingServer::new());
+        for session_id in ["a", "b", "c", "d"] {
+            server.create_session(session_id.to_string()).await.unwrap();
+        }
+        let tasks: Vec<_> = ["a", "b", "c", "d"]
+            .into_iter()
+            .map(|session_id| {
+                let server = server.clone();
+                tokio::spawn(async move {
+                    for number in 1..=5 {
+                        let thought = ThoughtData::new(format!("{session_id}{number}"), number, 5);
+                        server
+                            .process_session_thought(session_id, thought)
+                            .await
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let stats = server.get_stats().await;
+        assert_eq!(stats.total_sessions, 4);
+        assert_eq!(stats.total_requests, 20);
+        assert_eq!(stats.total_thoughts, 20);
+        assert_eq!(stats.error_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_tool_arguments_are_validated() {
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new().with_thinking_config(
+                ThinkingConfig {
+                    max_thought_length: 40,
+                    max_request_bytes: 512,
+                    ..Default::default()
+                },
+            )),
+        };
+        let call = |name: &str, arguments: serde_json::Value| ToolCall {
+            name: name.to_string(),
+            arguments: Some(arguments),
+        };
+        let error = |result: MCPResult<ToolResult>| result.unwrap_err().to_string();
+
+        let loose = error(
+            handler
+                .handle_tool_call(call(
+                    "sequential_thinking",
+                    serde_json::json!({
+                        "thought": "Check the inputs first",
+                        "thoughtNumber": "1",
+                        "totalThoughts": 2,
+                        "nextThoughtNeeded": true,
+                        "dependsOn": [1, "x"],
+                        "priority": "high"
+                    }),
+                ))
+                .await,
+        );
+        for expected in [
+            "arguments.dependsOn[1]: expected integer, found string",
+            "arguments.priority: is not allowed",
+            "arguments.thoughtNumber: expected integer, found string",
+        ] {
+            assert!(loose.contains(expected), "{expected} missing from {loose}");
+        }
+        assert!(error(
+            handler
+                .handle_tool_call(call("export_session", serde_json::json!({ "format": 1 })))
+                .await
+        )
+        .contains("arguments.format: expected string, found integer"));
+
+        let thought = |text: &str| {
+            call(
+                "sequential_thinking",
+                ThoughtData::new(text.to_string(), 1, 1).to_tool_arguments(),
+            )
+        };
+        assert!(error(handler.handle_tool_call(thought("Short")).await)
+            .contains("arguments.thought: must be at least 10 characters"));
+        assert!(
+            error(handler.handle_tool_call(thought(&"x".repeat(41))).await)
+                .contains("arguments.thought: must be at most 40 characters")
+        );
+        assert!(
+            error(handler.handle_tool_call(thought(&"x".repeat(600))).await)
+                .contains("exceeds the limit of 512 bytes")
+        );
+        handler
+            .handle_tool_call(thought("Within every limit"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_responses_echo_correlation_id() {
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new()),
+        };
+        let mut arguments =
+            ThoughtData::new("Trace this thought".to_string(), 1, 2).to_tool_arguments();
+        arguments["correlationId"] = serde_json::json!("host-req-42");
+        let result = handler
+            .handle_tool_call(ToolCall {
+                name: "sequential_thinking".to_string(),
+                arguments: Some(arguments),
+            })
+            .await
+            .unwrap();
+        let response = ThoughtResponse::from_tool_result(&result).unwrap();
+        assert_eq!(response.correlation_id.as_deref(), Some("host-req-42"));
+
+        // Calls without one get a generated ID
+        let result = handler
+            .handle_tool_call(ToolCall {
+                name: "get_server_info".to_string(),
+                arguments: None,
+            })
+            .await
+            .unwrap();
+        let ToolContent::Text { text } = &result.content[0] else {
+            panic!("expected text content");
+        };
+        let info: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert!(info["correlationId"]
+            .as_str()
+            .is_some_and(|id| uuid::Uuid::parse_str(id).is_ok()));
+
+        // The published output schemas list the field, except those of documents
+        for tool in tool_definitions() {
+            if DOCUMENT_TOOLS.contains(&tool.name.as_str()) {
+                continue;
+            }
+            if let Some(schema) = tool.output_schema {
+                assert!(
+                    schema["properties"].get("correlationId").is_some(),
+                    "{} output schema lacks correlationId",
+                    tool.name
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exports_of_unchanged_sessions_are_identical() {
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new()),
+        };
+        handler
+            .handle_tool_call(ToolCall {
+                name: "sequential_thinking".to_string(),
+                arguments: Some(
+                    ThoughtData::new("Export this thought".to_string(), 1, 2).to_tool_arguments(),
+                ),
+            })
+            .await
+            .unwrap();
+        for format in ["json", "markdown"] {
+            let mut exports = Vec::new();
+            for correlation_id in ["first-export", "second-export"] {
+                let result = handler
+                    .handle_tool_call(ToolCall {
+                        name: "export_session".to_string(),
+                        arguments: Some(serde_json::json!({
+                            "format": format,
+                            "correlationId": correlation_id
+                        })),
+                    })
+                    .await
+                    .unwrap();
+                let ToolContent::Text { text } = &result.content[0] else {
+                    panic!("expected text content");
+                };
+                assert!(!text.contains(correlation_id));
+                exports.push(text.clone());
+            }
+            assert_eq!(exports[0], exports[1], "{format} exports differ");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_busy_sessions_reject_calls() {
+        let server = SequentialThinkingServer::new().with_thinking_config(ThinkingConfig {
+            backpressure: Some(crate::thinking::backpressure::BackpressureConfig {
+                max_in_flight_calls: 8,
+                max_queued_per_session: 1,
+                retry_after_ms: 500,
+            }),
+            ..Default::default()
+        });
+        let handler = Arc::new(SequentialThinkingToolHandler {
+            server: Arc::new(server),
+        });
+        handler
+            .server
+            .create_session("a".to_string())
+            .await
+            .unwrap();
+        let call = |number: u32| ToolCall {
+            name: "sequential_thinking".to_string(),
+            arguments: Some(serde_json::json!({
+                "thought": format!("Thought number {number}"),
+                "thoughtNumber": number,
+                "totalThoughts": 2,
+                "nextThoughtNeeded": true,
+                "sessionId": "a"
+            })),
+        };
+
+        // A call waiting on the session's engine fills its queue
+        let engine = handler.server.session_engine("a").await.unwrap();
+        let guard = engine.write().await;
+        let waiting = tokio::spawn({
+            let handler = handler.clone();
+            let call = call(1);
+            async move { handler.handle_tool_call(call).await }
+        });
+        let backpressure = handler.server.backpressure.clone().unwrap();
+        while backpressure.in_flight() == 0 {
+            tokio::task::yield_now().await;
+        }
+        let busy = handler.handle_tool_call(call(2)).await.unwrap_err();
+        assert!(busy.to_string().contains("try again in 500 ms"));
+
+        drop(guard);
+        waiting.await.unwrap().unwrap();
+        assert_eq!(backpressure.in_flight(), 0);
+        handler.handle_tool_call(call(2)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sessions_process_thoughts_independently() {
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new()),
+        };
+        let server = &handler.server;
+        for session_id in ["a", "b"] {
+            server.create_session(session_id.to_string()).await.unwrap();
+        }
+
+        // A session busy with a slow thought does not hold up the others
+        let busy = server.session_engine("a").await.unwrap();
+        let _guard = busy.write().await;
+        let quick = std::time::Duration::from_secs(1);
+        tokio::time::timeout(
+            quick,
+            server.process_session_thought("b", ThoughtData::new("B1".to_string(), 1, 2)),
+        )
+        .await
+        .expect("session b waited for session a")
+        .unwrap();
+        tokio::time::timeout(
+            quick,
+            server.process_thought(ThoughtData::new("Default".to_string(), 1, 1)),
+        )
+        .await
+        .expect("the default session waited for session a")
+        .unwrap();
+
+        let result = handler
+            .handle_sequential_thinking(ToolCall {
+                name: "sequential_thinking".to_string(),
+                arguments: Some(serde_json::json!({
+                    "thought": "Second thought of b",
+                    "thoughtNumber": 2,
+                    "totalThoughts": 2,
+                    "nextThoughtNeeded": false,
+                    "sessionId": "b"
+                })),
+            })
+            .await
+            .unwrap();
+        assert_eq!(result_json(&result)["thoughtHistoryLength"], 2);
+        assert_eq!(server.get_session("b").await.unwrap().thought_count(), 2);
+        assert_eq!(server.engine.read().await.thought_count(), 1);
+        assert!(server
+            .process_session_thought("missing", ThoughtData::new("X".to_string(), 1, 1))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_compare_sessions_tool() {
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new()),
+        };
+        for (session_id, count) in [("prompt-a", 1), ("prompt-b", 3)] {
+            handler
+                .server
+                .create_session(session_id.to_string())
+                .await
+                .unwrap();
+            for n in 1..=count {
+                handler
+                    .server
+                    .process_session_thought(
+                        session_id,
+                        ThoughtData::new(format!("Thought {n}"), n, 3),
+                    )
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let compare = |b: &str| ToolCall {
+            name: "compare_sessions".to_string(),
+            arguments: Some(serde_json::json!({ "sessionA": "prompt-a", "sessionB": b })),
+        };
+        let comparison = result_json(
+            &handler
+                .handle_compare_sessions(compare("prompt-b"))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(comparison["session_b"], "prompt-b");
+        assert_eq!(comparison["metrics"][0]["metric"], "total_thoughts");
+        assert_eq!(comparison["metrics"][0]["difference"], 2.0);
+        assert!(comparison["summary"].as_str().unwrap().contains("prompt-b"));
+        assert!(handler
+            .handle_compare_sessions(compare("missing"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cost_report_tool() {
+        let pricing = PricingConfig {
+            default_model: Some("agent-model".to_string()),
+            models: HashMap::from([(
+                "agent-model".to_string(),
+                crate::analytics::cost::ModelPricing {
+                    input_per_million: 0.0,
+                    output_per_million: 1_000_000.0,
+                },
+            )]),
+            ..Default::default()
+        };
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new().with_pricing_config(pricing)),
+        };
+        handler
+            .server
+            .process_thought(ThoughtData::new("Twelve chars".to_string(), 1, 1))
+            .await
+            .unwrap();
+
+        let call = ToolCall {
+            name: "cost_report".to_string(),
+            arguments: Some(serde_json::json!({ "tags": ["team-a"] })),
+        };
+        let report = result_json(&handler.handle_cost_report(call).await.unwrap());
+        assert_eq!(report["total"]["output_tokens"], 3);
+        assert_eq!(report["total"]["cost"], 3.0);
+        assert_eq!(report["by_tag"][0]["key"], "team-a");
+        assert!(tool_definitions()
+            .iter()
+            .any(|tool| tool.name == "cost_report"));
+    }
+
+    #[tokio::test]
+    async fn test_analytics_dashboard_tool() {
+        let store = crate::analytics::dashboard::MemoryMetricsStore::shared();
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new().with_metrics_store(store.clone())),
+        };
+        for number in 1..=2 {
+            let mut thought = ThoughtData::new(format!("Step {number}"), number, 2);
+            thought.next_thought_needed = number < 2;
+            handler.server.process_thought(thought).await.unwrap();
+        }
+
+        let dashboard = |days: serde_json::Value| ToolCall {
+            name: "analytics_dashboard".to_string(),
+            arguments: Some(serde_json::json!({ "days": days })),
+        };
+        let report = result_json(
+            &handler
+                .handle_tool_call(dashboard(serde_json::json!(3)))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(report["total_sessions"], 1);
+        assert_eq!(report["days"].as_array().unwrap().len(), 3);
+        assert_eq!(report["days"][2]["sessions"], 1);
+        assert_eq!(report["days"][2]["thoughts"], 2);
+        assert!(handler
+            .handle_tool_call(dashboard(serde_json::json!(0)))
+            .await
+            .is_err());
+
+        // A restarted server picks the aggregates up from the store
+        let restarted = SequentialThinkingServer::new().with_metrics_store(store);
+        let dashboard = restarted.analytics_dashboard(1).await.unwrap();
+        assert_eq!(dashboard.total_sessions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_auto_export_on_completion() {
+        let dir = tempfile::tempdir().unwrap();
+        let server =
+            SequentialThinkingServer::new().with_export_config(&crate::config::ExportConfig {
+                formats: vec![
+                    "json".to_string(),
+                    "markdown".to_string(),
+                    "html".to_string(),
+                ],
+                auto_export: true,
+                export_directory: dir.path().to_string_lossy().to_string(),
+                ..Default::default()
+            });
+        for number in 1..=2 {
+            let mut thought = ThoughtData::new(format!("Step {number}"), number, 2);
+            thought.next_thought_needed = number < 2;
+            server.process_thought(thought).await.unwrap();
+        }
+
+        let mut extensions: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|entry| {
+                let path = entry.unwrap().path();
+                path.extension().map(|e| e.to_string_lossy().into_owned())
+            })
+            .collect();
+        extensions.sort();
+        assert_eq!(extensions, ["html", "json", "json", "md"]);
+        assert!(dir.path().join(INDEX_FILENAME).exists());
+
+        // Completing again unchanged skips every format
+        let records = server.auto_export_session(&server.engine).await;
+        let formats: Vec<ExportFormat> = records.iter().map(|r| r.format.clone()).collect();
+        assert_eq!(
+            formats,
+            [
+                ExportFormat::Json,
+                ExportFormat::Markdown,
+                ExportFormat::Html
+            ]
+        );
+        assert!(records.iter().all(|r| r.success && r.skipped_duplicate));
+    }
+
+    #[tokio::test]
+    async fn test_session_log_appends_each_thought() {
+        let dir = tempfile::tempdir().unwrap();
+        let server =
+            SequentialThinkingServer::new().with_export_config(&crate::config::ExportConfig {
+                session_log_directory: Some(dir.path().to_string_lossy().to_string()),
+                ..Default::default()
+            });
+        let log = SessionLog::new(dir.path());
+        for number in 1..=2 {
+            let mut thought = ThoughtData::new(format!("Step {number}"), number, 3);
+            thought.idempotency_key = Some(format!("step-{number}"));
+            server.process_thought(thought.clone()).await.unwrap();
+            // Retries answered from the idempotency cache are not logged again
+            server.process_thought(thought).await.unwrap();
+            assert_eq!(log.read("default").unwrap().len(), number as usize);
+        }
+        let logged = log.read("default").unwrap();
+        assert_eq!(logged[1].thought, "Step 2");
+        assert!(logged[1].timestamp.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_sessions_stay_apart() {
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new()),
+        };
+        let call = |name: &str, arguments: serde_json::Value| ToolCall {
+            name: name.to_string(),
+            arguments: Some(arguments),
+        };
+        let thought = |session_id: &str, text: &str, number: u32| {
+            call(
+                "sequential_thinking",
+                serde_json::json!({
+                    "sessionId": session_id,
+                    "thought": text,
+                    "thoughtNumber": number,
+                    "totalThoughts": 3,
+                    "nextThoughtNeeded": true,
+                    "idempotencyKey": format!("step-{number}")
+                }),
+            )
+        };
+        for session_id in ["a", "b"] {
+            handler
+                .handle_tool_call(call(
+                    "create_session",
+                    serde_json::json!({ "sessionId": session_id }),
+                ))
+                .await
+                .unwrap();
+        }
+        for session_id in ["a", "default"] {
+            let duplicate = call(
+                "create_session",
+                serde_json::json!({ "sessionId": session_id }),
+            );
+            assert!(handler.handle_tool_call(duplicate).await.is_err());
+        }
+        // The same idempotency key is a separate submission in each session
+        handler
+            .handle_tool_call(thought("a", "Alpha plan first", 1))
+            .await
+            .unwrap();
+        handler
+            .handle_tool_call(thought("b", "Beta plan first", 1))
+            .await
+            .unwrap();
+        assert_eq!(handler.server.get_stats().await.duplicate_requests, 0);
+
+        let export = |session_id: &str| {
+            call(
+                "export_session",
+                serde_json::json!({ "sessionId": session_id, "format": "markdown" }),
+            )
+        };
+        let exported = handler.handle_tool_call(export("a")).await.unwrap();
+        let ToolContent::Text { text } = &exported.content[0] else {
+            panic!("expected text content");
+        };
+        assert!(text.contains("Alpha plan") && !text.contains("Beta plan"));
+        let analysis = |session_id: &str| {
+            call(
+                "analyze_session",
+                serde_json::json!({ "sessionId": session_id }),
+            )
+        };
+        let analyzed = handler.handle_tool_call(analysis("b")).await.unwrap();
+        assert_eq!(result_json(&analyzed)["analysis"]["totalThoughts"], 1);
+        let analyzed = handler.handle_tool_call(analysis("default")).await.unwrap();
+        assert_eq!(result_json(&analyzed)["analysis"]["totalThoughts"], 0);
+        assert!(handler.handle_tool_call(export("missing")).await.is_err());
+
+        // Resuming a from a checkpoint leaves b and its idempotency keys alone
+        let checkpoint = handler
+            .handle_tool_call(call(
+                "checkpoint_session",
+                serde_json::json!({ "sessionId": "a" }),
+            ))
+            .await
+            .unwrap();
+        handler
+            .handle_tool_call(thought("a", "Alpha detail", 2))
+            .await
+            .unwrap();
+        let resumed = handler
+            .handle_tool_call(call(
+                "resume_session",
+                serde_json::json!({
+                    "sessionId": "a",
+                    "checkpoint": result_json(&checkpoint)["checkpoint"]
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(result_json(&resumed)["thoughtHistoryLength"], 1);
+        handler
+            .handle_tool_call(thought("b", "Beta plan first", 1))
+            .await
+            .unwrap();
+        assert_eq!(handler.server.get_stats().await.duplicate_requests, 1);
+        handler
+            .handle_tool_call(thought("a", "Alpha detail again", 2))
+            .await
+            .unwrap();
+        let b = handler.server.get_session("b").await.unwrap();
+        assert_eq!(b.thought_count(), 1);
+
+        // Cancelling a leaves thoughts queued in b waiting
+        let b = handler.server.session_engine("b").await.unwrap();
+        let guard = b.write().await;
+        let pending = tokio::spawn({
+            let server = handler.server.clone();
+            async move {
+                server
+                    .process_session_thought("b", ThoughtData::new("Beta detail".to_string(), 2, 3))
+                    .await
+            }
+        });
+        tokio::task::yield_now().await;
+        let cancel = |session_id: &str| {
+            call(
+                "cancel_session",
+                serde_json::json!({ "sessionId": session_id }),
+            )
+        };
+        handler.handle_tool_call(cancel("a")).await.unwrap();
+        tokio::task::yield_now().await;
+        assert!(!pending.is_finished());
+        // The reply reads b once the held lock is released
+        let cancelling = tokio::spawn({
+            let handler = SequentialThinkingToolHandler {
+                server: handler.server.clone(),
+            };
+            let call = cancel("b");
+            async move { handler.handle_tool_call(call).await }
+        });
+        let cancelled = pending.await.unwrap().unwrap_err();
+        assert!(matches!(
+            cancelled.root_cause(),
+            SequentialThinkingError::Cancelled { .. }
+        ));
+        drop(guard);
+        cancelling.await.unwrap().unwrap();
+        handler
+            .handle_tool_call(thought("a", "Alpha conclusion", 3))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_extract_code_tool() {
+        let handler = SequentialThinkingToolHandler {
+            server: Arc::new(SequentialThinkingServer::new()),
+        };
+        handler
+            .server
+            .process_thought(ThoughtData::new(
+                "Compare:\n```rust\nfn a() {}\n```\n```python\ndef b(): pass\n```".to_string(),
+                1,
+                1,
+            ))
+            .await
+            .unwrap();
+
+        let call = |arguments| ToolCall {
+            name: "extract_code".to_string(),
+            arguments,
+        };
+        let result = handler.handle_extract_code(call(None)).await.unwrap();
+        assert_eq!(result.content.len(), 2);
+        let result = handler
+            .handle_extract_code(call(Some(serde_json::json!({ "language": "Python" }))))
+            .await
+            .unwrap();
+        let block = result_json(&result);
+        assert_eq!(block["content"], "def b(): pass");
+        assert_eq!(block["index"], 2);
+        assert_eq!(required_role("extract_code"), Role::Reader);
     }
 
     #[tokio::test]
@@ -0,0 +1,187 @@
+//! # Thought Spilling
+//!
+//! Storage for thoughts evicted from memory by bounded-memory engines.
+//!
+//! With a memory limit set, a [`ThinkingEngine`](crate::thinking::ThinkingEngine)
+//! keeps only its most recent thoughts in memory and appends older ones to a
+//! [`SpillStore`]. Spilled thoughts are loaded back whenever the whole session
+//! is needed, for exports, analysis and checkpoints.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::thinking::SharedThought;
+
+/// Bounded-memory settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct BoundedMemoryConfig {
+    /// Thoughts kept in memory per session; older ones are spilled
+    pub max_thoughts_in_memory: usize,
+    /// Directory spilled thoughts are written to
+    #[serde(default = "default_spill_dir")]
+    pub spill_dir: String,
+}
+
+fn default_spill_dir() -> String {
+    "./spill".to_string()
+}
+
+impl Default for BoundedMemoryConfig {
+    fn default() -> Self {
+        Self {
+            max_thoughts_in_memory: 1000,
+            spill_dir: default_spill_dir(),
+        }
+    }
+}
+
+/// Storage for spilled thoughts, keyed by session
+pub trait SpillStore: Send + std::fmt::Debug {
+    /// Append thoughts to a session's spilled thoughts
+    fn append(&mut self, session_id: &str, thoughts: &[SharedThought]) -> Result<(), String>;
+
+    /// Load a session's spilled thoughts, oldest first
+    fn load(&self, session_id: &str) -> Result<Vec<SharedThought>, String>;
+
+    /// Remove a session's spilled thoughts
+    fn clear(&mut self, session_id: &str) -> Result<(), String>;
+}
+
+/// Spill store shared between engines
+pub type SharedSpillStore = Arc<Mutex<dyn SpillStore>>;
+
+/// Spill store writing one JSON Lines file per session
+#[derive(Debug, Clone)]
+pub struct FileSpillStore {
+    dir: PathBuf,
+}
+
+impl FileSpillStore {
+    /// Create a store writing to the given directory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Create a shareable store writing to the given directory
+    pub fn shared(dir: impl Into<PathBuf>) -> SharedSpillStore {
+        Arc::new(Mutex::new(Self::new(dir)))
+    }
+
+    /// Path of a session's spill file
+    pub fn path(&self, session_id: &str) -> PathBuf {
+        let name: String = session_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{name}.jsonl"))
+    }
+}
+
+impl SpillStore for FileSpillStore {
+    fn append(&mut self, session_id: &str, thoughts: &[SharedThought]) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create spill directory: {e}"))?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(session_id))
+            .map_err(|e| format!("Failed to open spill file: {e}"))?;
+
+        let mut lines = String::new();
+        for thought in thoughts {
+            lines.push_str(&serde_json::to_string(thought).map_err(|e| e.to_string())?);
+            lines.push('\n');
+        }
+        file.write_all(lines.as_bytes())
+            .map_err(|e| format!("Failed to write spill file: {e}"))
+    }
+
+    fn load(&self, session_id: &str) -> Result<Vec<SharedThought>, String> {
+        let file = match std::fs::File::open(self.path(session_id)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to open spill file: {e}")),
+        };
+        std::io::BufReader::new(file)
+            .lines()
+            .map(|line| {
+                let line = line.map_err(|e| format!("Failed to read spill file: {e}"))?;
+                serde_json::from_str(&line)
+                    .map(Arc::new)
+                    .map_err(|e| format!("Corrupt spill file: {e}"))
+            })
+            .collect()
+    }
+
+    fn clear(&mut self, session_id: &str) -> Result<(), String> {
+        match std::fs::remove_file(self.path(session_id)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to remove spill file: {e}"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::{ThinkingEngine, ThoughtData};
+
+    #[tokio::test]
+    async fn test_bounded_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileSpillStore::shared(dir.path());
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.set_bounded_memory(3, store.clone());
+        engine.start_session("long session".to_string());
+        for n in 1..=10 {
+            engine
+                .process_thought(ThoughtData::new(format!("Thought {n}"), n, 10))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(engine.get_thoughts().len(), 3);
+        assert_eq!(engine.spilled_count(), 7);
+        assert_eq!(engine.thought_count(), 10);
+        let numbers: Vec<u32> = engine
+            .all_thoughts()
+            .iter()
+            .map(|t| t.thought_number)
+            .collect();
+        assert_eq!(numbers, (1..=10).collect::<Vec<_>>());
+        assert!(dir.path().join("long_session.jsonl").exists());
+        assert!(engine
+            .edit_thought(2, "Changed".to_string())
+            .unwrap_err()
+            .contains("spilled"));
+
+        // Checkpoints hold the whole session and re-spill on resume
+        let checkpoint = engine.checkpoint();
+        assert_eq!(checkpoint.thoughts.len(), 10);
+        let mut resumed = ThinkingEngine::with_logging(true);
+        resumed.set_bounded_memory(4, FileSpillStore::shared(dir.path().join("resumed")));
+        resumed.resume_from(checkpoint).unwrap();
+        assert_eq!(resumed.get_thoughts().len(), 4);
+        assert_eq!(resumed.all_thoughts().len(), 10);
+
+        engine.start_session("next".to_string());
+        assert_eq!(engine.thought_count(), 0);
+        assert!(store
+            .lock()
+            .unwrap()
+            .load("long session")
+            .unwrap()
+            .is_empty());
+    }
+}
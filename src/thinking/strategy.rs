@@ -0,0 +1,216 @@
+//! # Thinking Strategies
+//!
+//! Guided modes that shape how a session's thoughts are structured.
+//!
+//! A [`ThinkingStrategy`] adds validation rules to the engine and guidance to
+//! tool responses, prompting for the next step the strategy calls for. The
+//! rules only constrain when a session may conclude and which kinds of
+//! thoughts are allowed; the numbering policy still applies on top.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::thinking::ThoughtData;
+
+/// Structure a session's thinking follows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ThinkingStrategy {
+    /// Free-form sequence of thoughts with no additional rules
+    #[default]
+    Linear,
+    /// Explore at least two branches before concluding
+    TreeOfThought,
+    /// Thesis, antithesis and synthesis before concluding
+    Dialectic,
+    /// Ask "why" five times to reach a root cause, without branching
+    FiveWhys,
+    /// Imagine the plan failed, find the causes, then revise the plan
+    PremortemAnalysis,
+}
+
+impl std::str::FromStr for ThinkingStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "linear" => Ok(Self::Linear),
+            "tree_of_thought" => Ok(Self::TreeOfThought),
+            "dialectic" => Ok(Self::Dialectic),
+            "five_whys" => Ok(Self::FiveWhys),
+            "premortem_analysis" | "premortem" => Ok(Self::PremortemAnalysis),
+            _ => Err(format!("Unknown thinking strategy: {s}")),
+        }
+    }
+}
+
+/// Where a session stands when a thought is added
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrategyContext {
+    /// Position of the thought in the session, starting at 1
+    pub position: usize,
+    /// Branches explored so far, including one the thought opens
+    pub branches: usize,
+    /// Revisions made so far, including the thought itself
+    pub revisions: usize,
+}
+
+impl ThinkingStrategy {
+    /// Thoughts a session needs before it may conclude
+    pub fn min_thoughts(self) -> usize {
+        match self {
+            Self::Linear => 1,
+            Self::TreeOfThought | Self::Dialectic | Self::PremortemAnalysis => 3,
+            Self::FiveWhys => 5,
+        }
+    }
+
+    /// Check a thought against the strategy's rules
+    pub fn validate(self, thought: &ThoughtData, context: StrategyContext) -> Result<(), String> {
+        if self == Self::FiveWhys && thought.is_branch() {
+            return Err(
+                "The five whys strategy follows a single chain; branches are not allowed"
+                    .to_string(),
+            );
+        }
+        if thought.next_thought_needed {
+            return Ok(());
+        }
+        if context.position < self.min_thoughts() {
+            return Err(format!(
+                "The {self} strategy needs at least {} thoughts before concluding",
+                self.min_thoughts()
+            ));
+        }
+        match self {
+            Self::TreeOfThought if context.branches < 2 => Err(
+                "The tree of thought strategy needs at least two branches before concluding"
+                    .to_string(),
+            ),
+            Self::PremortemAnalysis if context.revisions == 0 => Err(
+                "The premortem strategy needs a revision of the plan before concluding".to_string(),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Guidance for the step after a thought, if the strategy has any
+    pub fn guidance(self, thought: &ThoughtData, context: StrategyContext) -> Option<String> {
+        if !thought.next_thought_needed {
+            return None;
+        }
+        let n = thought.thought_number;
+        let text = match self {
+            Self::Linear => return None,
+            Self::TreeOfThought if context.branches < 2 => format!(
+                "Branch from thought {n} to explore an alternative approach ({} of at least 2 branches explored)",
+                context.branches
+            ),
+            Self::TreeOfThought => {
+                "Compare the branches and continue the most promising one".to_string()
+            }
+            Self::Dialectic => match context.position % 3 {
+                1 => "State the strongest counterargument to this thesis".to_string(),
+                2 => "Reconcile the thesis and the counterargument into a synthesis".to_string(),
+                _ => "Challenge the synthesis as a new thesis, or conclude".to_string(),
+            },
+            Self::FiveWhys if context.position < 5 => format!(
+                "Ask why thought {n} happened (why {} of 5)",
+                context.position + 1
+            ),
+            Self::FiveWhys => "State the root cause and how to address it".to_string(),
+            Self::PremortemAnalysis => match context.position {
+                1 => "Assume the plan has failed and describe how it failed".to_string(),
+                2 => "List the causes that led to the failure".to_string(),
+                _ if context.revisions == 0 => {
+                    "Revise the plan to address each cause".to_string()
+                }
+                _ => "Check the revised plan for remaining risks, or conclude".to_string(),
+            },
+        };
+        Some(text)
+    }
+}
+
+impl std::fmt::Display for ThinkingStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Linear => "linear",
+            Self::TreeOfThought => "tree of thought",
+            Self::Dialectic => "dialectic",
+            Self::FiveWhys => "five whys",
+            Self::PremortemAnalysis => "premortem",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::ThinkingEngine;
+
+    #[tokio::test]
+    async fn test_thinking_strategies() {
+        let mut engine = ThinkingEngine::with_logging(true);
+        engine.set_strategy("tree-of-thought".parse().unwrap());
+        engine.start_session("tree".to_string());
+        engine
+            .process_thought(ThoughtData::new("Frame the problem".to_string(), 1, 3))
+            .await
+            .unwrap();
+        let branch = engine
+            .process_thought(ThoughtData::branch(
+                "First approach".to_string(),
+                2,
+                1,
+                "a".to_string(),
+            ))
+            .await
+            .unwrap();
+        assert!(engine
+            .guidance(&branch)
+            .unwrap()
+            .contains("1 of at least 2 branches"));
+
+        let mut early = ThoughtData::new("Settle on the first approach".to_string(), 3, 3);
+        early.next_thought_needed = false;
+        assert!(engine
+            .process_thought(early.clone())
+            .await
+            .unwrap_err()
+            .contains("two branches"));
+        engine
+            .process_thought(ThoughtData::branch(
+                "Second approach".to_string(),
+                3,
+                1,
+                "b".to_string(),
+            ))
+            .await
+            .unwrap();
+        early.thought_number = 4;
+        engine.process_thought(early).await.unwrap();
+
+        let mut whys = ThinkingEngine::with_logging(true);
+        whys.set_strategy(ThinkingStrategy::FiveWhys);
+        let first = whys
+            .process_thought(ThoughtData::new("The build failed".to_string(), 1, 5))
+            .await
+            .unwrap();
+        assert_eq!(
+            whys.guidance(&first).unwrap(),
+            "Ask why thought 1 happened (why 2 of 5)"
+        );
+        assert!(whys
+            .process_thought(ThoughtData::branch(
+                "Maybe not".to_string(),
+                2,
+                1,
+                "x".to_string()
+            ))
+            .await
+            .is_err());
+        assert_eq!(ThinkingEngine::new().guidance(&first), None);
+    }
+}
@@ -0,0 +1,154 @@
+//! # Structured Content
+//!
+//! `structuredContent` for tool results, as defined by MCP 2025-06-18.
+//!
+//! A tool result can carry its JSON value in a `structuredContent` field,
+//! typed by the tool's output schema, next to the same value serialized as
+//! text. The MCP framework's tool results have no such field, so tools return
+//! their JSON as text and the HTTP gateway copies it into `structuredContent`
+//! on the way to the host. Hosts on the stdio transport read the text.
+
+use serde_json::Value;
+
+/// Add `structuredContent` to the tool results in a JSON-RPC message or batch
+///
+/// A result gets structured content when it is not an error and its only
+/// content item is text holding a JSON object. Returns whether anything was
+/// added.
+pub fn add_structured_content(message: &mut Value) -> bool {
+    if let Value::Array(batch) = message {
+        return batch
+            .iter_mut()
+            .fold(false, |added, item| add_structured_content(item) | added);
+    }
+    let Some(result) = message.get_mut("result").and_then(Value::as_object_mut) else {
+        return false;
+    };
+    if result.contains_key("structuredContent")
+        || result.get("isError").and_then(Value::as_bool) == Some(true)
+    {
+        return false;
+    }
+    let structured = match result.get("content").and_then(Value::as_array) {
+        Some(content) if content.len() == 1 && content[0]["type"] == "text" => content[0]["text"]
+            .as_str()
+            .and_then(|text| serde_json::from_str::<Value>(text).ok())
+            .filter(Value::is_object),
+        _ => None,
+    };
+    match structured {
+        Some(structured) => {
+            result.insert("structuredContent".to_string(), structured);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Adds structured content to the messages of a server-sent event stream
+///
+/// Chunks are split into lines as they arrive; `data:` lines holding a
+/// JSON-RPC message are rewritten and every other line passes through.
+#[derive(Debug, Default)]
+pub struct SseRewriter {
+    pending: Vec<u8>,
+}
+
+impl SseRewriter {
+    /// Create a rewriter for a new stream
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a chunk, returning the complete lines it finished
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<u8> {
+        self.pending.extend_from_slice(chunk);
+        let Some(end) = self.pending.iter().rposition(|&b| b == b'\n') else {
+            return Vec::new();
+        };
+        let lines: Vec<u8> = self.pending.drain(..=end).collect();
+        let mut output = Vec::with_capacity(lines.len());
+        for line in lines.split_inclusive(|&b| b == b'\n') {
+            output.extend_from_slice(&rewrite_line(line));
+        }
+        output
+    }
+
+    /// Return the unterminated last line of the stream, if any
+    pub fn finish(&mut self) -> Vec<u8> {
+        let rest = std::mem::take(&mut self.pending);
+        rewrite_line(&rest)
+    }
+}
+
+/// Rewrite one event stream line, keeping its line ending
+fn rewrite_line(line: &[u8]) -> Vec<u8> {
+    let Some(data) = line.strip_prefix(b"data:") else {
+        return line.to_vec();
+    };
+    let body_len = data
+        .iter()
+        .rposition(|&b| b != b'\n' && b != b'\r')
+        .map_or(0, |i| i + 1);
+    let (body, ending) = data.split_at(body_len);
+    let Ok(mut message) = serde_json::from_slice::<Value>(body) else {
+        return line.to_vec();
+    };
+    if !add_structured_content(&mut message) {
+        return line.to_vec();
+    }
+    let mut rewritten = b"data: ".to_vec();
+    rewritten.extend_from_slice(message.to_string().as_bytes());
+    rewritten.extend_from_slice(ending);
+    rewritten
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_structured_content() {
+        let result = |content: Value| serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": { "content": content } });
+        let text = |text: &str| serde_json::json!([{ "type": "text", "text": text }]);
+
+        let mut message = result(text("{\n  \"thoughtNumber\": 1\n}"));
+        assert!(add_structured_content(&mut message));
+        assert_eq!(message["result"]["structuredContent"]["thoughtNumber"], 1);
+
+        // Markdown, arrays, several items and errors stay text only
+        for mut message in [
+            result(text("# Session")),
+            result(text("[1, 2]")),
+            result(serde_json::json!([
+                { "type": "text", "text": "{}" },
+                { "type": "text", "text": "{}" }
+            ])),
+            serde_json::json!({ "id": 2, "result": { "content": text("{}"), "isError": true } }),
+            serde_json::json!({ "id": 3, "result": { "contents": [] } }),
+        ] {
+            assert!(!add_structured_content(&mut message), "{message}");
+        }
+
+        let mut rewriter = SseRewriter::new();
+        let event = format!(
+            "event: message\ndata: {}\n\n",
+            result(text("{\"ok\":true}"))
+        );
+        let (head, tail) = event.split_at(30);
+        let mut output = rewriter.push(head.as_bytes());
+        output.extend(rewriter.push(tail.as_bytes()));
+        output.extend(rewriter.finish());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("event: message\ndata: {"));
+        assert!(output.ends_with("}\n\n"));
+        let data: Value = serde_json::from_str(
+            output
+                .lines()
+                .find_map(|l| l.strip_prefix("data: "))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(data["result"]["structuredContent"]["ok"], true);
+    }
+}
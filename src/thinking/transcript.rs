@@ -0,0 +1,149 @@
+//! # Transcripts
+//!
+//! Recordings of interactive client sessions.
+//!
+//! A [`Transcript`] holds every command typed in an interactive session with
+//! the response the client printed for it, and when it was typed. Replaying
+//! a transcript runs the same commands against a server again, paced like
+//! the original for demos, and comparing the responses catches regressions.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::thinking::error::{SequentialThinkingError, SequentialThinkingResult};
+
+/// Version of the transcript file layout
+pub const TRANSCRIPT_VERSION: u32 = 1;
+
+/// Commands and responses of a recorded interactive session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transcript {
+    /// Version of the file layout
+    pub version: u32,
+    /// Title of the recorded session
+    pub title: String,
+    /// Template the session started from, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// ID of the recorded session
+    pub session_id: String,
+    /// When recording started
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+    /// Commands in the order they were typed
+    pub entries: Vec<TranscriptEntry>,
+}
+
+/// One command of a transcript and its response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    /// Milliseconds since recording started
+    pub elapsed_ms: u64,
+    /// Command line as typed
+    pub command: String,
+    /// Text the client printed in response
+    pub response: String,
+}
+
+impl Transcript {
+    /// Start an empty transcript of a session
+    pub fn new(title: impl Into<String>, session_id: impl Into<String>) -> Self {
+        Self {
+            version: TRANSCRIPT_VERSION,
+            title: title.into(),
+            template: None,
+            session_id: session_id.into(),
+            recorded_at: chrono::Utc::now(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Record a command and its response
+    pub fn record(&mut self, command: impl Into<String>, response: impl Into<String>) {
+        let elapsed = chrono::Utc::now() - self.recorded_at;
+        self.entries.push(TranscriptEntry {
+            elapsed_ms: elapsed.num_milliseconds().max(0) as u64,
+            command: command.into(),
+            response: response.into(),
+        });
+    }
+
+    /// Milliseconds to wait before each command when replaying at a given speed
+    ///
+    /// A speed of 0 or less replays without delays.
+    pub fn delays(&self, speed: f64) -> Vec<u64> {
+        let mut previous = 0;
+        self.entries
+            .iter()
+            .map(|entry| {
+                let gap = entry.elapsed_ms.saturating_sub(previous);
+                previous = entry.elapsed_ms;
+                if speed > 0.0 {
+                    (gap as f64 / speed) as u64
+                } else {
+                    0
+                }
+            })
+            .collect()
+    }
+
+    /// Read a transcript from a JSON file
+    pub fn load(path: &Path) -> SequentialThinkingResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            SequentialThinkingError::not_found(format!("transcript {}: {e}", path.display()))
+        })?;
+        let transcript: Self = serde_json::from_str(&content)?;
+        if transcript.version > TRANSCRIPT_VERSION {
+            return Err(SequentialThinkingError::validation_error(format!(
+                "transcript version {} is newer than the supported version {TRANSCRIPT_VERSION}",
+                transcript.version
+            )));
+        }
+        Ok(transcript)
+    }
+
+    /// Write the transcript to a JSON file
+    ///
+    /// The file is replaced through a temporary file, so a crash mid-write
+    /// keeps the previous recording.
+    pub fn save(&self, path: &Path) -> SequentialThinkingResult<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, content)
+            .and_then(|()| std::fs::rename(&tmp_path, path))
+            .map_err(|e| {
+                SequentialThinkingError::internal_error(format!(
+                    "Failed to write transcript {}: {e}",
+                    path.display()
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("demo.json");
+
+        let mut transcript = Transcript::new("Demo", "s1");
+        transcript.record(
+            "think Start with the failing test",
+            "✅ Thought 1 processed",
+        );
+        transcript.record("progress", "📊 Progress: 1/5 (20.0%)");
+        transcript.entries[1].elapsed_ms = transcript.entries[0].elapsed_ms + 400;
+        transcript.save(&path).unwrap();
+
+        let loaded = Transcript::load(&path).unwrap();
+        assert_eq!(loaded, transcript);
+        assert_eq!(loaded.delays(2.0)[1], 200);
+        assert_eq!(loaded.delays(0.0), [0, 0]);
+
+        std::fs::write(&path, r#"{"version": 2, "title": "", "session_id": "", "recorded_at": "2026-01-01T00:00:00Z", "entries": []}"#).unwrap();
+        assert!(Transcript::load(&path).is_err());
+        assert!(Transcript::load(&dir.path().join("missing.json")).is_err());
+    }
+}
@@ -0,0 +1,523 @@
+//! # Client Transports
+//!
+//! How the client reaches a server.
+//!
+//! [`SequentialThinkingClient`](super::client::SequentialThinkingClient)
+//! sends every request through a [`Transport`]. [`McpTransport`] speaks MCP
+//! over stdio or streamable HTTP. [`InMemoryTransport`] hands requests
+//! straight to a [`SequentialThinkingServer`] in the same process, so tests
+//! can run a client against a real server without spawning one.
+//! [`LoopbackTransport`] serves such a server on its own task and passes
+//! serialized messages over channels, as a connection would. Other
+//! transports, such as WebSocket, implement the trait and are passed to
+//! `SequentialThinkingClient::with_transport`.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tracing::info;
+
+use ultrafast_mcp::McpCoreError::ProtocolError;
+use ultrafast_mcp::{
+    ClientCapabilities, ClientInfo, ListToolsRequest, MCPError, Tool, ToolCall, ToolHandler,
+    ToolResult, UltraFastClient,
+};
+
+use crate::thinking::error::{SequentialThinkingError, SequentialThinkingResult};
+use crate::thinking::server::SequentialThinkingServer;
+
+/// Connection from the client to a server
+#[async_trait::async_trait]
+pub trait Transport: Send + Sync {
+    /// Open the connection, ready for tool calls
+    async fn connect(&self) -> SequentialThinkingResult<()>;
+
+    /// Call a tool on the server
+    async fn call_tool(&self, call: ToolCall) -> SequentialThinkingResult<ToolResult>;
+
+    /// List the tools the server offers
+    async fn list_tools(&self) -> SequentialThinkingResult<Vec<Tool>>;
+}
+
+/// Pick the transport for a server URL: `stdio` or `http(s)://...`
+pub fn transport_for_url(server_url: &str) -> SequentialThinkingResult<Arc<dyn Transport>> {
+    if server_url.starts_with("stdio://") || server_url == "stdio" {
+        Ok(Arc::new(McpTransport::stdio()))
+    } else if server_url.starts_with("http://") || server_url.starts_with("https://") {
+        Ok(Arc::new(McpTransport::http(server_url)))
+    } else {
+        Err(SequentialThinkingError::transport_error(format!(
+            "Unsupported server URL format: {server_url}"
+        )))
+    }
+}
+
+/// Wait suggested to a busy caller whose server named none
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Internal error the MCP client raises while it is not connected and initialized
+const NOT_OPERATING: &str = "not in operating state";
+
+/// Error of a failed request, by what the server or the connection reported
+///
+/// Only the JSON-RPC code and message of a server error reach the client,
+/// and access, busy and quota refusals all arrive as internal errors, so
+/// those are told apart by the error the server wrote into the message.
+/// Invalid parameters are validation errors; only failures of the
+/// connection itself, including a client that is not connected yet, become
+/// transport errors.
+pub fn request_error(error: MCPError) -> SequentialThinkingError {
+    match error {
+        MCPError::Transport(e) => SequentialThinkingError::transport_error(e.to_string()),
+        MCPError::Protocol(ProtocolError::InternalError(ref message))
+            if message.contains(NOT_OPERATING) =>
+        {
+            SequentialThinkingError::transport_error(error.to_string())
+        }
+        MCPError::Io(e) => SequentialThinkingError::transport_error(e.to_string()),
+        MCPError::Protocol(
+            e @ (ProtocolError::RequestTimeout
+            | ProtocolError::ConnectionClosed
+            | ProtocolError::TransportError(_)),
+        ) => SequentialThinkingError::transport_error(e.to_string()),
+        MCPError::Serialization(e) => SequentialThinkingError::serialization_error(e.to_string()),
+        MCPError::Protocol(ProtocolError::InvalidParams(message)) => server_error(&message)
+            .unwrap_or_else(|| SequentialThinkingError::validation_error(message)),
+        MCPError::Protocol(
+            ProtocolError::NotFound(message) | ProtocolError::MethodNotFound(message),
+        ) => SequentialThinkingError::not_found(message),
+        other => {
+            let message = other.to_string();
+            server_error(&message)
+                .unwrap_or_else(|| SequentialThinkingError::internal_error(message))
+        }
+    }
+}
+
+/// Refusal the server wrote into an error message, if any
+fn server_error(message: &str) -> Option<SequentialThinkingError> {
+    let after = |marker: &str| {
+        message
+            .find(marker)
+            .map(|start| message[start + marker.len()..].to_string())
+    };
+    if let Some(reason) = after("Permission denied: ") {
+        return Some(SequentialThinkingError::permission_denied(reason));
+    }
+    if let Some(busy) = after("Server busy: ") {
+        // Written by `user_message`: "<reason>; try again in <n> ms"
+        let (reason, retry_after) = match busy.rsplit_once("; try again in ") {
+            Some((reason, wait)) => (
+                reason.to_string(),
+                wait.trim_end_matches(" ms")
+                    .parse()
+                    .map_or(DEFAULT_RETRY_AFTER, Duration::from_millis),
+            ),
+            None => (busy, DEFAULT_RETRY_AFTER),
+        };
+        return Some(SequentialThinkingError::server_busy(reason, retry_after));
+    }
+    if let Some(limit) = after("Rate limit exceeded: ") {
+        return Some(SequentialThinkingError::rate_limit_exceeded(limit));
+    }
+    after("Resource not found: ").map(SequentialThinkingError::not_found)
+}
+
+/// Where an [`McpTransport`] connects
+#[derive(Debug, Clone, PartialEq)]
+enum McpEndpoint {
+    Stdio,
+    Http(String),
+}
+
+/// MCP over stdio or streamable HTTP
+pub struct McpTransport {
+    client: UltraFastClient,
+    endpoint: McpEndpoint,
+}
+
+impl McpTransport {
+    /// Connect through the standard input and output of this process
+    pub fn stdio() -> Self {
+        Self::new(McpEndpoint::Stdio)
+    }
+
+    /// Connect to a server listening on `url`
+    pub fn http(url: impl Into<String>) -> Self {
+        Self::new(McpEndpoint::Http(url.into()))
+    }
+
+    fn new(endpoint: McpEndpoint) -> Self {
+        let client_info = ClientInfo {
+            name: "UltraFast MCP Sequential Thinking Client".to_string(),
+            version: "0.1.0".to_string(),
+            description: Some(
+                "High-performance Rust-based MCP client for sequential thinking".to_string(),
+            ),
+            homepage: Some(
+                "https://github.com/techgopal/ultrafast-mcp-sequential-thinking".to_string(),
+            ),
+            repository: Some(
+                "https://github.com/techgopal/ultrafast-mcp-sequential-thinking".to_string(),
+            ),
+            authors: Some(vec!["techgopal <techgopal2@gmail.com>".to_string()]),
+            license: Some("MIT".to_string()),
+        };
+        Self {
+            client: UltraFastClient::new(client_info, ClientCapabilities::default()),
+            endpoint,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for McpTransport {
+    async fn connect(&self) -> SequentialThinkingResult<()> {
+        match &self.endpoint {
+            McpEndpoint::Stdio => {
+                info!("Connecting to server: stdio");
+                self.client.connect_stdio().await.map_err(|e| {
+                    SequentialThinkingError::transport_error(format!(
+                        "Failed to connect via STDIO: {e}"
+                    ))
+                })?;
+            }
+            McpEndpoint::Http(url) => {
+                info!("Connecting to server: {}", url);
+                self.client
+                    .connect_streamable_http(url)
+                    .await
+                    .map_err(|e| {
+                        SequentialThinkingError::transport_error(format!(
+                            "Failed to connect via HTTP: {e}"
+                        ))
+                    })?;
+            }
+        }
+
+        info!("Connected to server, initializing MCP connection...");
+
+        // Initialize the MCP connection
+        self.client.initialize().await.map_err(|e| {
+            SequentialThinkingError::transport_error(format!(
+                "Failed to initialize MCP connection: {e}"
+            ))
+        })?;
+
+        info!("MCP connection initialized successfully");
+        Ok(())
+    }
+
+    async fn call_tool(&self, call: ToolCall) -> SequentialThinkingResult<ToolResult> {
+        self.client.call_tool(call).await.map_err(request_error)
+    }
+
+    async fn list_tools(&self) -> SequentialThinkingResult<Vec<Tool>> {
+        self.client
+            .list_tools(ListToolsRequest { cursor: None })
+            .await
+            .map(|response| response.tools)
+            .map_err(request_error)
+    }
+}
+
+/// Server running in the same process
+///
+/// Requests go straight to the server's tool handler, with the same access
+/// control and quotas as over MCP. The server is cloned, so a copy kept by
+/// the caller sees every session the client changes.
+pub struct InMemoryTransport {
+    handler: Arc<dyn ToolHandler>,
+}
+
+impl InMemoryTransport {
+    /// Connect to `server`
+    pub fn new(server: SequentialThinkingServer) -> Self {
+        Self {
+            handler: server.tool_handler(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for InMemoryTransport {
+    async fn connect(&self) -> SequentialThinkingResult<()> {
+        Ok(())
+    }
+
+    async fn call_tool(&self, call: ToolCall) -> SequentialThinkingResult<ToolResult> {
+        self.handler
+            .handle_tool_call(call)
+            .await
+            .map_err(request_error)
+    }
+
+    async fn list_tools(&self) -> SequentialThinkingResult<Vec<Tool>> {
+        self.handler
+            .list_tools(ListToolsRequest { cursor: None })
+            .await
+            .map(|response| response.tools)
+            .map_err(request_error)
+    }
+}
+
+/// Requests a loopback server task holds before senders wait
+const LOOPBACK_CAPACITY: usize = 64;
+
+/// Message sent to a loopback server task, as JSON where it has a body
+enum LoopbackMessage {
+    CallTool(String),
+    ListTools,
+}
+
+/// Message to a loopback server task with the channel for its reply
+struct LoopbackRequest {
+    message: LoopbackMessage,
+    reply: oneshot::Sender<Result<String, MCPError>>,
+}
+
+/// Server running on a task of this process, reached over channels
+///
+/// Tool calls and results are serialized on the way, so a loopback client
+/// exercises the same encoding, errors and retries as one talking to a
+/// remote server. The task answers requests concurrently and stops when the
+/// transport is dropped.
+pub struct LoopbackTransport {
+    requests: mpsc::Sender<LoopbackRequest>,
+}
+
+impl LoopbackTransport {
+    /// Serve `server` on a new task; must be called within a Tokio runtime
+    pub fn spawn(server: SequentialThinkingServer) -> Self {
+        let handler = server.tool_handler();
+        let (requests, mut incoming) = mpsc::channel::<LoopbackRequest>(LOOPBACK_CAPACITY);
+        tokio::spawn(async move {
+            while let Some(request) = incoming.recv().await {
+                let handler = Arc::clone(&handler);
+                tokio::spawn(async move {
+                    let response = serve_loopback(handler.as_ref(), request.message).await;
+                    let _ = request.reply.send(response);
+                });
+            }
+        });
+        Self { requests }
+    }
+
+    /// Send a message to the server task and wait for its reply
+    async fn send(&self, message: LoopbackMessage) -> SequentialThinkingResult<String> {
+        let (reply, response) = oneshot::channel();
+        self.requests
+            .send(LoopbackRequest { message, reply })
+            .await
+            .map_err(|_| {
+                SequentialThinkingError::transport_error("In-process server has stopped")
+            })?;
+        response
+            .await
+            .map_err(|_| {
+                SequentialThinkingError::transport_error("In-process server dropped the request")
+            })?
+            .map_err(request_error)
+    }
+}
+
+/// Answer one loopback message
+async fn serve_loopback(
+    handler: &dyn ToolHandler,
+    message: LoopbackMessage,
+) -> Result<String, MCPError> {
+    match message {
+        LoopbackMessage::CallTool(call) => {
+            let call: ToolCall = serde_json::from_str(&call).map_err(MCPError::Serialization)?;
+            let result = handler.handle_tool_call(call).await?;
+            serde_json::to_string(&result).map_err(MCPError::Serialization)
+        }
+        LoopbackMessage::ListTools => {
+            let response = handler
+                .list_tools(ListToolsRequest { cursor: None })
+                .await?;
+            serde_json::to_string(&response.tools).map_err(MCPError::Serialization)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for LoopbackTransport {
+    async fn connect(&self) -> SequentialThinkingResult<()> {
+        if self.requests.is_closed() {
+            return Err(SequentialThinkingError::transport_error(
+                "In-process server has stopped",
+            ));
+        }
+        Ok(())
+    }
+
+    async fn call_tool(&self, call: ToolCall) -> SequentialThinkingResult<ToolResult> {
+        let call = serde_json::to_string(&call)
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))?;
+        let result = self.send(LoopbackMessage::CallTool(call)).await?;
+        serde_json::from_str(&result)
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+    }
+
+    async fn list_tools(&self) -> SequentialThinkingResult<Vec<Tool>> {
+        let tools = self.send(LoopbackMessage::ListTools).await?;
+        serde_json::from_str(&tools)
+            .map_err(|e| SequentialThinkingError::serialization_error(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thinking::client::{ClientThinkingConfig, SequentialThinkingClient};
+    use crate::thinking::ThoughtData;
+    use crate::usage::{UsageConfig, UsageQuota};
+
+    #[tokio::test]
+    async fn test_in_memory_transport() {
+        let server = SequentialThinkingServer::new();
+        let client = SequentialThinkingClient::with_transport(
+            Arc::new(InMemoryTransport::new(server.clone())),
+            ClientThinkingConfig {
+                auto_save_interval: 0,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let tools = client.list_tools(None).await.unwrap();
+        assert!(tools.iter().any(|tool| tool.name == "sequential_thinking"));
+
+        let session = client
+            .start_session("In process".to_string())
+            .await
+            .unwrap();
+        session
+            .add_thought(ThoughtData::new("First step".to_string(), 1, 2), None)
+            .await
+            .unwrap();
+        assert!(session.pending_sync().await.is_empty());
+        assert_eq!(server.get_stats().await.total_thoughts, 1);
+
+        assert!(matches!(
+            transport_for_url("ftp://localhost"),
+            Err(SequentialThinkingError::TransportError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_loopback_transport() {
+        let client = SequentialThinkingClient::connect_in_process(SequentialThinkingServer::new())
+            .await
+            .unwrap();
+        assert!(!client.list_tools(None).await.unwrap().is_empty());
+
+        // Tool errors reach the client like those of a remote server
+        let error = client
+            .export_session("unknown", "pdf", None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.root_cause(),
+            SequentialThinkingError::ValidationError { .. }
+        ));
+        assert_eq!(error.tool_call().unwrap().0, "export_session");
+
+        // A thought the server refuses is retried, then kept for a resync
+        let server = SequentialThinkingServer::new().with_usage_config(UsageConfig {
+            quota: Some(UsageQuota {
+                thoughts: Some(1),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let client = SequentialThinkingClient::with_transport(
+            Arc::new(LoopbackTransport::spawn(server.clone())),
+            ClientThinkingConfig {
+                max_retry_attempts: 2,
+                auto_save_interval: 0,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let session = client.start_session("Quota".to_string()).await.unwrap();
+        session
+            .add_thought(
+                ThoughtData::new("Allowed by the quota".to_string(), 1, 2),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(session
+            .add_thought(ThoughtData::new("Over quota".to_string(), 2, 2), None)
+            .await
+            .is_err());
+        assert_eq!(session.get_stats().await.retry_count, 1);
+        assert_eq!(session.pending_sync().await.len(), 1);
+        assert_eq!(server.get_stats().await.total_thoughts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_keep_their_kind() {
+        let client = SequentialThinkingClient::with_transport(
+            Arc::new(LoopbackTransport::spawn(SequentialThinkingServer::new())),
+            ClientThinkingConfig {
+                max_retry_attempts: 3,
+                auto_save_interval: 0,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let session = client.start_session("Invalid".to_string()).await.unwrap();
+        let error = session
+            .add_thought(ThoughtData::new("Too short".to_string(), 1, 2), None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.error_code(), "VALIDATION_ERROR");
+        assert!(!error.is_retryable());
+        assert_eq!(session.get_stats().await.retry_count, 0);
+
+        // Refusals arriving as internal errors are told apart by their message
+        let wire = |message: String| {
+            request_error(MCPError::internal_error(format!(
+                "Tool call failed: Protocol error: Invalid request: {message}"
+            )))
+        };
+        let denied = wire(SequentialThinkingError::permission_denied("Missing key").to_string());
+        assert_eq!(denied.error_code(), "PERMISSION_DENIED");
+        let busy = wire(
+            SequentialThinkingError::server_busy("Queue full", Duration::from_millis(250))
+                .user_message(),
+        );
+        assert_eq!(busy.retry_after(), Some(Duration::from_millis(250)));
+        let quota = wire(SequentialThinkingError::rate_limit_exceeded("1 thought").to_string());
+        assert_eq!(quota.error_code(), "RATE_LIMIT_EXCEEDED");
+        assert_eq!(
+            wire("Something broke".to_string()).error_code(),
+            "INTERNAL_ERROR"
+        );
+        let missing = request_error(MCPError::invalid_params(
+            SequentialThinkingError::not_found("session 'x'").to_string(),
+        ));
+        assert_eq!(missing.error_code(), "NOT_FOUND");
+    }
+
+    #[test]
+    fn test_client_state_errors_are_retryable() {
+        let error = request_error(MCPError::internal_error(
+            "Client is not in operating state".to_string(),
+        ));
+        assert!(matches!(
+            error,
+            SequentialThinkingError::TransportError { .. }
+        ));
+        assert!(error.is_retryable());
+
+        let closed = request_error(MCPError::Protocol(ProtocolError::ConnectionClosed));
+        assert!(closed.is_retryable());
+    }
+}
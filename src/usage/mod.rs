@@ -0,0 +1,417 @@
+//! # Usage Module
+//!
+//! Per-API-key usage counters and monthly quotas.
+//!
+//! The server counts the requests, thoughts, sessions and export bytes of
+//! each API key over the current calendar month (UTC). Quotas cap any of these
+//! per month; a call that would go over a quota is refused with an error
+//! naming the quota and when it resets. Callers without a configured key are
+//! counted together as [`ANONYMOUS_KEY`].
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use tokio::sync::RwLock;
+
+use crate::thinking::error::{SequentialThinkingError, SequentialThinkingResult};
+
+/// Key under which callers without a configured API key are counted
+pub const ANONYMOUS_KEY: &str = "anonymous";
+
+/// Usage tracking settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct UsageConfig {
+    /// Monthly quota of every API key without its own
+    #[serde(default)]
+    pub quota: Option<UsageQuota>,
+    /// Monthly quotas by API key, replacing the default quota
+    #[serde(default)]
+    pub keys: HashMap<String, UsageQuota>,
+}
+
+impl UsageConfig {
+    /// Check that every quota limit is positive
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let mut quotas: Vec<(String, &UsageQuota)> = self
+            .keys
+            .iter()
+            .map(|(key, quota)| (format!("keys.{key}"), quota))
+            .collect();
+        quotas.sort_by(|a, b| a.0.cmp(&b.0));
+        quotas.extend(self.quota.as_ref().map(|q| ("quota".to_string(), q)));
+        for (path, quota) in quotas {
+            for metric in UsageMetric::ALL {
+                if quota.limit(metric) == Some(0) {
+                    errors.push(format!("{path}.{}: must be greater than 0", metric.key()));
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Quota applying to an API key
+    pub fn quota_for(&self, key: &str) -> Option<&UsageQuota> {
+        self.keys.get(key).or(self.quota.as_ref())
+    }
+}
+
+/// Monthly limits; unset limits are unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct UsageQuota {
+    /// Tool calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests: Option<u64>,
+    /// Recorded thoughts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thoughts: Option<u64>,
+    /// Started sessions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sessions: Option<u64>,
+    /// Bytes of exported sessions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_bytes: Option<u64>,
+}
+
+impl UsageQuota {
+    /// Limit of a metric
+    pub fn limit(&self, metric: UsageMetric) -> Option<u64> {
+        match metric {
+            UsageMetric::Requests => self.requests,
+            UsageMetric::Thoughts => self.thoughts,
+            UsageMetric::Sessions => self.sessions,
+            UsageMetric::ExportBytes => self.export_bytes,
+        }
+    }
+}
+
+/// What usage is counted in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UsageMetric {
+    Requests,
+    Thoughts,
+    Sessions,
+    ExportBytes,
+}
+
+impl UsageMetric {
+    /// Every metric, in report order
+    pub const ALL: [UsageMetric; 4] = [
+        UsageMetric::Requests,
+        UsageMetric::Thoughts,
+        UsageMetric::Sessions,
+        UsageMetric::ExportBytes,
+    ];
+
+    /// Name of the metric in configuration
+    pub fn key(&self) -> &'static str {
+        match self {
+            UsageMetric::Requests => "requests",
+            UsageMetric::Thoughts => "thoughts",
+            UsageMetric::Sessions => "sessions",
+            UsageMetric::ExportBytes => "export_bytes",
+        }
+    }
+}
+
+impl fmt::Display for UsageMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UsageMetric::ExportBytes => f.write_str("export bytes"),
+            metric => f.write_str(metric.key()),
+        }
+    }
+}
+
+/// Usage of one API key in one month
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageCounters {
+    /// Tool calls
+    pub requests: u64,
+    /// Recorded thoughts
+    pub thoughts: u64,
+    /// Started sessions
+    pub sessions: u64,
+    /// Bytes of exported sessions
+    pub export_bytes: u64,
+}
+
+impl UsageCounters {
+    fn get(&self, metric: UsageMetric) -> u64 {
+        match metric {
+            UsageMetric::Requests => self.requests,
+            UsageMetric::Thoughts => self.thoughts,
+            UsageMetric::Sessions => self.sessions,
+            UsageMetric::ExportBytes => self.export_bytes,
+        }
+    }
+
+    fn add(&mut self, metric: UsageMetric, amount: u64) {
+        let counter = match metric {
+            UsageMetric::Requests => &mut self.requests,
+            UsageMetric::Thoughts => &mut self.thoughts,
+            UsageMetric::Sessions => &mut self.sessions,
+            UsageMetric::ExportBytes => &mut self.export_bytes,
+        };
+        *counter = counter.saturating_add(amount);
+    }
+}
+
+/// Usage of every API key in the current month
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageReport {
+    /// Month the counters cover, as `YYYY-MM`
+    pub period: String,
+    /// First day of the next period
+    pub resets_on: NaiveDate,
+    /// Usage by API key, sorted by key
+    pub keys: Vec<KeyUsage>,
+}
+
+/// Usage and quota of one API key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyUsage {
+    /// API key, or `anonymous`
+    pub key: String,
+    /// Usage this month
+    pub usage: UsageCounters,
+    /// Monthly quota, if any
+    pub quota: Option<UsageQuota>,
+}
+
+/// Counts usage by API key and enforces quotas
+#[derive(Debug, Default)]
+pub struct UsageTracker {
+    config: UsageConfig,
+    /// Current period and the counters of each key in it
+    counters: RwLock<(String, HashMap<String, UsageCounters>)>,
+}
+
+impl UsageTracker {
+    /// Create a tracker enforcing the configured quotas
+    pub fn new(config: UsageConfig) -> Self {
+        Self {
+            config,
+            counters: RwLock::default(),
+        }
+    }
+
+    /// Get the usage settings
+    pub fn config(&self) -> &UsageConfig {
+        &self.config
+    }
+
+    /// Check that `amount` more of a metric stays within the key's quota
+    pub async fn check(
+        &self,
+        key: &str,
+        metric: UsageMetric,
+        amount: u64,
+    ) -> SequentialThinkingResult<()> {
+        let now = Utc::now();
+        let mut counters = self.counters.write().await;
+        let used = current(&mut counters, now)
+            .get(key)
+            .map_or(0, |c| c.get(metric));
+        self.within_quota(key, metric, used, amount, now)
+    }
+
+    /// Count usage of a metric without checking the quota
+    pub async fn record(&self, key: &str, metric: UsageMetric, amount: u64) {
+        let mut counters = self.counters.write().await;
+        current(&mut counters, Utc::now())
+            .entry(key.to_string())
+            .or_default()
+            .add(metric, amount);
+    }
+
+    /// Count usage of a metric, unless it would go over the key's quota
+    pub async fn consume(
+        &self,
+        key: &str,
+        metric: UsageMetric,
+        amount: u64,
+    ) -> SequentialThinkingResult<()> {
+        self.consume_at(key, metric, amount, Utc::now()).await
+    }
+
+    async fn consume_at(
+        &self,
+        key: &str,
+        metric: UsageMetric,
+        amount: u64,
+        now: DateTime<Utc>,
+    ) -> SequentialThinkingResult<()> {
+        let mut counters = self.counters.write().await;
+        let usage = current(&mut counters, now)
+            .entry(key.to_string())
+            .or_default();
+        self.within_quota(key, metric, usage.get(metric), amount, now)?;
+        usage.add(metric, amount);
+        Ok(())
+    }
+
+    fn within_quota(
+        &self,
+        key: &str,
+        metric: UsageMetric,
+        used: u64,
+        amount: u64,
+        now: DateTime<Utc>,
+    ) -> SequentialThinkingResult<()> {
+        match self.config.quota_for(key).and_then(|q| q.limit(metric)) {
+            Some(limit) if used.saturating_add(amount) > limit => {
+                Err(SequentialThinkingError::rate_limit_exceeded(format!(
+                    "monthly quota of {limit} {metric} reached for this API key ({used} used); \
+                     it resets on {}",
+                    next_period(now)
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the usage of one API key this month
+    pub async fn usage(&self, key: &str) -> UsageCounters {
+        let mut counters = self.counters.write().await;
+        current(&mut counters, Utc::now())
+            .get(key)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Report the usage of every key this month
+    ///
+    /// Keys with their own quota are listed even before their first call.
+    pub async fn report(&self) -> UsageReport {
+        self.report_at(Utc::now()).await
+    }
+
+    async fn report_at(&self, now: DateTime<Utc>) -> UsageReport {
+        let mut counters = self.counters.write().await;
+        let usage = current(&mut counters, now);
+        let keys = usage
+            .keys()
+            .chain(self.config.keys.keys())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .map(|key| KeyUsage {
+                key: key.clone(),
+                usage: usage.get(key).copied().unwrap_or_default(),
+                quota: self.config.quota_for(key).copied(),
+            })
+            .collect();
+        UsageReport {
+            period: period(now),
+            resets_on: next_period(now),
+            keys,
+        }
+    }
+}
+
+/// Counters of the current period, starting afresh in a new month
+fn current(
+    counters: &mut (String, HashMap<String, UsageCounters>),
+    now: DateTime<Utc>,
+) -> &mut HashMap<String, UsageCounters> {
+    let period = period(now);
+    if counters.0 != period {
+        *counters = (period, HashMap::new());
+    }
+    &mut counters.1
+}
+
+fn period(now: DateTime<Utc>) -> String {
+    now.format("%Y-%m").to_string()
+}
+
+fn next_period(now: DateTime<Utc>) -> NaiveDate {
+    let (year, month) = match now.month() {
+        12 => (now.year() + 1, 1),
+        month => (now.year(), month + 1),
+    };
+    NaiveDate::from_ymd_opt(year, month, 1).expect("first day of a month is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn test_monthly_quotas() {
+        let tracker = UsageTracker::new(UsageConfig {
+            quota: Some(UsageQuota {
+                thoughts: Some(2),
+                ..Default::default()
+            }),
+            keys: HashMap::from([(
+                "ops".to_string(),
+                UsageQuota {
+                    export_bytes: Some(100),
+                    ..Default::default()
+                },
+            )]),
+        });
+        let october = Utc.with_ymd_and_hms(2026, 10, 31, 23, 0, 0).unwrap();
+        let november = Utc.with_ymd_and_hms(2026, 11, 1, 0, 0, 0).unwrap();
+
+        for _ in 0..2 {
+            tracker
+                .consume_at("agent", UsageMetric::Thoughts, 1, october)
+                .await
+                .unwrap();
+        }
+        let error = tracker
+            .consume_at("agent", UsageMetric::Thoughts, 1, october)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            SequentialThinkingError::RateLimitExceeded { .. }
+        ));
+        assert!(error.to_string().contains(
+            "monthly quota of 2 thoughts reached for this API key (2 used); it resets on 2026-11-01"
+        ));
+
+        // Keys with their own quota are not bound by the default one
+        tracker
+            .consume_at("ops", UsageMetric::Thoughts, 5, october)
+            .await
+            .unwrap();
+        assert!(tracker
+            .consume_at("ops", UsageMetric::ExportBytes, 101, october)
+            .await
+            .is_err());
+
+        // A new month starts from zero
+        tracker
+            .consume_at("agent", UsageMetric::Thoughts, 1, november)
+            .await
+            .unwrap();
+        let report = tracker.report_at(november).await;
+        assert_eq!(report.period, "2026-11");
+        assert_eq!(report.keys[0].key, "agent");
+        assert_eq!(report.keys[0].usage.thoughts, 1);
+        assert_eq!(report.keys[1].key, "ops");
+        assert_eq!(report.keys[1].quota.unwrap().export_bytes, Some(100));
+
+        let invalid = UsageConfig {
+            quota: Some(UsageQuota {
+                sessions: Some(0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            invalid.validate().unwrap_err(),
+            vec!["quota.sessions: must be greater than 0".to_string()]
+        );
+    }
+}
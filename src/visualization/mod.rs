@@ -0,0 +1,473 @@
+//! # Visualization Module
+//!
+//! Rendering of thoughts for the stderr thought log.
+//!
+//! The renderer supports several styles (a box per thought, a compact single
+//! line, JSON lines, or nothing), optional ANSI colors, and an ASCII fallback
+//! for terminals whose locale is not UTF-8. Widths are measured in terminal
+//! columns, so wide characters and multi-line thoughts keep boxes aligned,
+//! and boxes are wrapped to fit the terminal.
+
+pub mod progress;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::config::UIConfig;
+use crate::thinking::ThoughtData;
+
+/// How thoughts are rendered to the thought log
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum VisualizationStyle {
+    /// A bordered box with a header and the thought text
+    #[default]
+    Boxed,
+    /// One line per thought
+    Compact,
+    /// One JSON object per thought
+    Json,
+    /// No output
+    None,
+}
+
+impl fmt::Display for VisualizationStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            VisualizationStyle::Boxed => "boxed",
+            VisualizationStyle::Compact => "compact",
+            VisualizationStyle::Json => "json",
+            VisualizationStyle::None => "none",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for VisualizationStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "boxed" | "box" => Ok(VisualizationStyle::Boxed),
+            "compact" | "line" => Ok(VisualizationStyle::Compact),
+            "json" => Ok(VisualizationStyle::Json),
+            "none" | "off" => Ok(VisualizationStyle::None),
+            _ => Err(format!("Unknown visualization style: {s}")),
+        }
+    }
+}
+
+/// Border characters for boxed output
+struct BoxChars {
+    horizontal: &'static str,
+    vertical: &'static str,
+    top_left: &'static str,
+    top_right: &'static str,
+    middle_left: &'static str,
+    middle_right: &'static str,
+    bottom_left: &'static str,
+    bottom_right: &'static str,
+}
+
+const UNICODE_BOX: BoxChars = BoxChars {
+    horizontal: "─",
+    vertical: "│",
+    top_left: "┌",
+    top_right: "┐",
+    middle_left: "├",
+    middle_right: "┤",
+    bottom_left: "└",
+    bottom_right: "┘",
+};
+
+const ASCII_BOX: BoxChars = BoxChars {
+    horizontal: "-",
+    vertical: "|",
+    top_left: "+",
+    top_right: "+",
+    middle_left: "+",
+    middle_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+};
+
+/// Narrowest box content width wrapping will produce
+const MIN_CONTENT_WIDTH: usize = 20;
+
+/// Renders thoughts for the thought log
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThoughtRenderer {
+    style: VisualizationStyle,
+    color: bool,
+    unicode: bool,
+    max_width: Option<usize>,
+}
+
+impl Default for ThoughtRenderer {
+    fn default() -> Self {
+        Self::new(VisualizationStyle::default())
+    }
+}
+
+impl ThoughtRenderer {
+    /// Create a renderer without colors, using Unicode if the locale allows it
+    pub fn new(style: VisualizationStyle) -> Self {
+        Self {
+            style,
+            color: false,
+            unicode: locale_supports_unicode(),
+            max_width: terminal_width(),
+        }
+    }
+
+    /// Create a renderer from UI settings
+    ///
+    /// Colors are only used when enabled, stderr is a terminal and
+    /// `NO_COLOR` is not set.
+    pub fn from_ui_config(ui: &UIConfig) -> Self {
+        Self::new(ui.thought_style).with_color(ui.color_output && stderr_supports_color())
+    }
+
+    /// Enable or disable ANSI colors
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Enable or disable Unicode box drawing and symbols
+    pub fn with_unicode(mut self, unicode: bool) -> Self {
+        self.unicode = unicode;
+        self
+    }
+
+    /// Wrap boxes to fit within the given number of columns (`None` disables wrapping)
+    pub fn with_max_width(mut self, max_width: Option<usize>) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    /// Get the rendering style
+    pub fn style(&self) -> VisualizationStyle {
+        self.style
+    }
+
+    /// Render a thought, or `None` if the style produces no output
+    pub fn render(&self, thought: &ThoughtData) -> Option<String> {
+        match self.style {
+            VisualizationStyle::Boxed => Some(self.render_boxed(thought)),
+            VisualizationStyle::Compact => Some(self.render_compact(thought)),
+            VisualizationStyle::Json => Some(
+                serde_json::json!({ "event": event_name(thought), "thought": thought }).to_string(),
+            ),
+            VisualizationStyle::None => None,
+        }
+    }
+
+    /// Header parts: the colored label with thought position, its plain
+    /// display width, and the revision/branch context (if any)
+    fn header(&self, thought: &ThoughtData) -> (String, usize, String) {
+        let (symbol, label, color) = if thought.is_revision() {
+            ("🔄", "Revision", "33")
+        } else if thought.is_branch() {
+            ("🌿", "Branch", "32")
+        } else {
+            ("💭", "Thought", "34")
+        };
+
+        let prefix = if self.unicode {
+            format!("{symbol} {label}")
+        } else {
+            label.to_string()
+        };
+        let position = format!("{}/{}", thought.thought_number, thought.total_thoughts);
+        let width = prefix.width() + 1 + position.width();
+        let main = if self.color {
+            format!("\x1b[{color}m{prefix}\x1b[0m {position}")
+        } else {
+            format!("{prefix} {position}")
+        };
+
+        let context = if thought.is_revision() {
+            format!(
+                "(revising thought {})",
+                thought.revises_thought.unwrap_or(0)
+            )
+        } else if thought.is_branch() {
+            format!(
+                "(from thought {}, ID: {})",
+                thought.branch_from_thought.unwrap_or(0),
+                thought.branch_id.as_deref().unwrap_or("unknown")
+            )
+        } else {
+            String::new()
+        };
+
+        (main, width, context)
+    }
+
+    fn render_boxed(&self, thought: &ThoughtData) -> String {
+        let chars = if self.unicode {
+            &UNICODE_BOX
+        } else {
+            &ASCII_BOX
+        };
+        let (main, main_width, context) = self.header(thought);
+        let full_header_width = if context.is_empty() {
+            main_width
+        } else {
+            main_width + 1 + context.width()
+        };
+
+        // Content may use the terminal width minus the borders and padding
+        let natural_width = thought
+            .thought
+            .lines()
+            .map(|line| line.width())
+            .chain(std::iter::once(full_header_width))
+            .max()
+            .unwrap_or(0);
+        let inner_width = match self.max_width {
+            Some(max) => natural_width.min(max.saturating_sub(4).max(MIN_CONTENT_WIDTH)),
+            None => natural_width,
+        };
+
+        // Keep the context on the header line when it fits; otherwise wrap it
+        // onto indented lines aligned with the label
+        let mut header_rows: Vec<(String, usize)> = Vec::new();
+        if full_header_width <= inner_width {
+            let text = if context.is_empty() {
+                main
+            } else {
+                format!("{main} {context}")
+            };
+            header_rows.push((text, full_header_width));
+        } else {
+            header_rows.push((main, main_width));
+            let indent = if self.unicode { 3 } else { 2 };
+            for line in wrap(&context, inner_width.saturating_sub(indent).max(1)) {
+                let width = indent + line.width();
+                header_rows.push((format!("{}{line}", " ".repeat(indent)), width));
+            }
+        }
+
+        let body_rows: Vec<String> = thought
+            .thought
+            .lines()
+            .flat_map(|line| wrap(line, inner_width))
+            .collect();
+
+        let border = chars.horizontal.repeat(inner_width + 2);
+        let row = |text: &str, width: usize| {
+            format!(
+                "{v} {text}{} {v}",
+                " ".repeat(inner_width.saturating_sub(width)),
+                v = chars.vertical
+            )
+        };
+
+        let mut out = format!("\n{}{border}{}\n", chars.top_left, chars.top_right);
+        for (text, width) in &header_rows {
+            out.push_str(&row(text, *width));
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            "{}{border}{}\n",
+            chars.middle_left, chars.middle_right
+        ));
+        for line in &body_rows {
+            out.push_str(&row(line, line.width()));
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            "{}{border}{}",
+            chars.bottom_left, chars.bottom_right
+        ));
+        out
+    }
+
+    fn render_compact(&self, thought: &ThoughtData) -> String {
+        let (main, _, context) = self.header(thought);
+        let header = if context.is_empty() {
+            main
+        } else {
+            format!("{main} {context}")
+        };
+        let text = thought
+            .thought
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("{header}: {text}")
+    }
+}
+
+/// Word-wrap a line to a display width, splitting words that do not fit
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    if line.width() <= width {
+        return vec![line.to_string()];
+    }
+
+    let mut rows = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0;
+    for word in line.split_whitespace() {
+        let word_width = word.width();
+        if current_width > 0 && current_width + 1 + word_width <= width {
+            current.push(' ');
+            current.push_str(word);
+            current_width += 1 + word_width;
+            continue;
+        }
+        if current_width > 0 {
+            rows.push(std::mem::take(&mut current));
+            current_width = 0;
+        }
+        if word_width <= width {
+            current.push_str(word);
+            current_width = word_width;
+            continue;
+        }
+
+        // Break words wider than a row at character boundaries
+        for c in word.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if current_width + char_width > width && current_width > 0 {
+                rows.push(std::mem::take(&mut current));
+                current_width = 0;
+            }
+            current.push(c);
+            current_width += char_width;
+        }
+    }
+    if current_width > 0 || rows.is_empty() {
+        rows.push(current);
+    }
+    rows
+}
+
+/// Terminal width in columns, from `COLUMNS` or the size of stderr
+///
+/// Returns `None` when stderr is not a terminal, so redirected logs are not
+/// wrapped.
+fn terminal_width() -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .filter(|c| *c > 0)
+    {
+        return Some(columns);
+    }
+    terminal_size::terminal_size_of(std::io::stderr())
+        .map(|(terminal_size::Width(width), _)| width as usize)
+}
+
+/// Event name used in JSON output
+fn event_name(thought: &ThoughtData) -> &'static str {
+    if thought.is_revision() {
+        "revision"
+    } else if thought.is_branch() {
+        "branch"
+    } else {
+        "thought"
+    }
+}
+
+/// Whether the locale can display Unicode box drawing and symbols
+///
+/// An explicit non-UTF-8 locale (e.g. `C` or `en_US.ISO-8859-1`) selects the
+/// ASCII fallback; an unset locale keeps Unicode output.
+fn locale_supports_unicode() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+        .map(|locale| {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+        .unwrap_or(true)
+}
+
+/// Whether ANSI colors should be written to stderr
+fn stderr_supports_color() -> bool {
+    std::env::var_os("NO_COLOR").is_none() && atty::is(atty::Stream::Stderr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_styles() {
+        let thought = ThoughtData::new("短い考え\nsecond line".to_string(), 1, 2);
+
+        let boxed = ThoughtRenderer::new(VisualizationStyle::Boxed)
+            .with_unicode(true)
+            .with_max_width(None)
+            .render(&thought)
+            .unwrap();
+        let widths: Vec<usize> = boxed.trim().lines().map(|l| l.width()).collect();
+        assert_eq!(widths.len(), 6);
+        assert!(widths.iter().all(|w| *w == widths[0]));
+        assert!(boxed.contains("💭 Thought 1/2"));
+
+        let ascii = ThoughtRenderer::new(VisualizationStyle::Boxed)
+            .with_unicode(false)
+            .with_max_width(None)
+            .render(&ThoughtData::revision("Rethink".to_string(), 2, 1))
+            .unwrap();
+        assert!(ascii.is_ascii());
+        assert!(ascii.contains("| Revision 2/2 (revising thought 1) |"));
+
+        let wrapped = ThoughtRenderer::new(VisualizationStyle::Boxed)
+            .with_unicode(true)
+            .with_max_width(Some(30))
+            .render(
+                &ThoughtData::branch(
+                    "全角の文字を含む長い考えが折り返される supercalifragilisticexpialidocious-and-more"
+                        .to_string(),
+                    4,
+                    2,
+                    "alternative-approach".to_string(),
+                ),
+            )
+            .unwrap();
+        let rows: Vec<&str> = wrapped.trim().lines().collect();
+        assert!(rows.iter().all(|row| row.width() == 30));
+        assert!(rows[1].contains("🌿 Branch 4/4"));
+        assert!(rows[2].starts_with("│    (from thought 2,"));
+        assert!(rows.len() > 7);
+
+        assert_eq!(wrap("a bb ccc", 4), vec!["a bb", "ccc"]);
+        assert_eq!(wrap("abcdefgh", 3), vec!["abc", "def", "gh"]);
+        assert_eq!(wrap("", 3), vec![""]);
+
+        let compact = ThoughtRenderer::new(VisualizationStyle::Compact)
+            .with_unicode(false)
+            .render(&thought)
+            .unwrap();
+        assert_eq!(compact, "Thought 1/2: 短い考え second line");
+
+        let colored = ThoughtRenderer::new(VisualizationStyle::Compact)
+            .with_color(true)
+            .render(&thought)
+            .unwrap();
+        assert!(colored.contains("\x1b[34m"));
+
+        let json = ThoughtRenderer::new(VisualizationStyle::Json)
+            .render(&thought)
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["event"], "thought");
+
+        assert!(ThoughtRenderer::new(VisualizationStyle::None)
+            .render(&thought)
+            .is_none());
+        assert_eq!(
+            "off".parse::<VisualizationStyle>(),
+            Ok(VisualizationStyle::None)
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! # Progress
+//!
+//! Progress bars and spinners for long-running client commands.
+//!
+//! [`Progress`] draws to stderr the way [`UIConfig`] asks: nothing when
+//! `show_progress_bars` is off, colors only with `color_output` on a color
+//! terminal, and the bar characters of the configured `theme`. Bars are
+//! hidden when stderr is not a terminal or `TERM` is `dumb`, so piped and
+//! logged output stays clean, and fall back to ASCII in non-UTF-8 locales.
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::borrow::Cow;
+use std::time::Duration;
+
+use super::{locale_supports_unicode, stderr_supports_color};
+use crate::config::UIConfig;
+
+/// How often spinners redraw
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Look of bars and spinners, chosen by [`UIConfig::theme`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProgressTheme {
+    /// Block bar with a spinner, ETA and colors
+    #[default]
+    Default,
+    /// Thin bar with counts only
+    Minimal,
+    /// Default layout drawn with ASCII characters only
+    Ascii,
+}
+
+impl ProgressTheme {
+    /// Theme of a name, falling back to the default for unknown names
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "minimal" | "plain" => ProgressTheme::Minimal,
+            "ascii" => ProgressTheme::Ascii,
+            _ => ProgressTheme::Default,
+        }
+    }
+}
+
+/// Factory for progress bars and spinners following the UI settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    enabled: bool,
+    color: bool,
+    unicode: bool,
+    theme: ProgressTheme,
+}
+
+impl Progress {
+    /// Progress display following the UI settings and the terminal
+    pub fn from_ui_config(ui: &UIConfig) -> Self {
+        let theme = ProgressTheme::from_name(&ui.theme);
+        Self {
+            enabled: ui.show_progress_bars && stderr_is_interactive(),
+            color: ui.color_output && stderr_supports_color(),
+            unicode: theme != ProgressTheme::Ascii && locale_supports_unicode(),
+            theme,
+        }
+    }
+
+    /// Progress display drawing nothing
+    pub fn hidden() -> Self {
+        Self {
+            enabled: false,
+            color: false,
+            unicode: false,
+            theme: ProgressTheme::Default,
+        }
+    }
+
+    /// Whether bars are drawn at all
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Bar counting up to `len` steps
+    pub fn bar(&self, len: u64, message: impl Into<Cow<'static, str>>) -> ProgressBar {
+        if !self.enabled {
+            return ProgressBar::hidden();
+        }
+        ProgressBar::with_draw_target(Some(len), ProgressDrawTarget::stderr())
+            .with_style(self.bar_style())
+            .with_message(message)
+    }
+
+    /// Spinner for work of unknown length
+    ///
+    /// Spinners redraw on their own until finished.
+    pub fn spinner(&self, message: impl Into<Cow<'static, str>>) -> ProgressBar {
+        if !self.enabled {
+            return ProgressBar::hidden();
+        }
+        let spinner = ProgressBar::with_draw_target(None, ProgressDrawTarget::stderr())
+            .with_style(self.spinner_style())
+            .with_message(message);
+        spinner.enable_steady_tick(TICK_INTERVAL);
+        spinner
+    }
+
+    fn bar_style(&self) -> ProgressStyle {
+        let bar = match (self.theme, self.color) {
+            (ProgressTheme::Minimal, _) | (_, false) => "{bar:40}",
+            (_, true) => "{bar:40.cyan/blue}",
+        };
+        let template = match self.theme {
+            ProgressTheme::Minimal => format!("{{msg}} {bar} {{pos}}/{{len}}"),
+            _ => format!("{{spinner}} {{msg}} [{bar}] {{pos}}/{{len}} ({{eta}})"),
+        };
+        let chars = match (self.theme, self.unicode) {
+            (_, false) => "#>-",
+            (ProgressTheme::Minimal, true) => "━╸ ",
+            (_, true) => "█▓░",
+        };
+        ProgressStyle::with_template(&template)
+            .expect("progress bar template is valid")
+            .progress_chars(chars)
+            .tick_chars(self.tick_chars())
+    }
+
+    fn spinner_style(&self) -> ProgressStyle {
+        let template = match (self.theme, self.color) {
+            (ProgressTheme::Minimal, _) => "{spinner} {msg} ({elapsed})",
+            (_, true) => "{spinner:.cyan} {msg} ({elapsed})",
+            (_, false) => "{spinner} {msg} ({elapsed})",
+        };
+        ProgressStyle::with_template(template)
+            .expect("spinner template is valid")
+            .tick_chars(self.tick_chars())
+    }
+
+    /// Spinner frames, the last one shown once finished
+    fn tick_chars(&self) -> &'static str {
+        if self.unicode {
+            "⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏✓"
+        } else {
+            "|/-\\+"
+        }
+    }
+}
+
+/// Whether stderr is a terminal able to redraw progress lines
+fn stderr_is_interactive() -> bool {
+    std::env::var("TERM").map_or(true, |term| term != "dumb") && atty::is(atty::Stream::Stderr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_follows_ui_config() {
+        let ui = UIConfig {
+            show_progress_bars: false,
+            ..UIConfig::default()
+        };
+        let progress = Progress::from_ui_config(&ui);
+        assert!(!progress.is_enabled());
+        assert!(progress.bar(10, "Sending").is_hidden());
+        assert!(progress.spinner("Exporting").is_hidden());
+
+        assert_eq!(ProgressTheme::from_name("Minimal"), ProgressTheme::Minimal);
+        assert_eq!(ProgressTheme::from_name("ascii"), ProgressTheme::Ascii);
+        assert_eq!(ProgressTheme::from_name("neon"), ProgressTheme::Default);
+
+        // Every theme builds valid templates, with and without colors
+        for theme in [
+            ProgressTheme::Default,
+            ProgressTheme::Minimal,
+            ProgressTheme::Ascii,
+        ] {
+            for (color, unicode) in [(true, true), (false, false)] {
+                let progress = Progress {
+                    enabled: true,
+                    color,
+                    unicode,
+                    theme,
+                };
+                progress.bar_style();
+                progress.spinner_style();
+            }
+        }
+    }
+}